@@ -5,11 +5,14 @@ import com.svenschmidt.kitana.core.NativeAccess
 import com.svenschmidt.kitana.viewmodel.DateTimeViewModel
 import org.hamcrest.MatcherAssert
 import org.hamcrest.number.IsCloseTo.closeTo
+import org.json.JSONObject
 import org.junit.Assert
 import org.junit.Before
 import org.junit.Test
 import org.junit.runner.RunWith
 import java.time.LocalDateTime
+import java.time.ZoneId
+import java.time.ZonedDateTime
 
 @RunWith(AndroidJUnit4::class)
 class NativeAccessTest {
@@ -40,6 +43,36 @@ class NativeAccessTest {
         MatcherAssert.assertThat(jd, closeTo(2_459_596.101598, 0.000_001))
     }
 
+    @Test
+    fun rust_julian_day_from_unix_millis_test() {
+        // Arrange
+
+        // SS: Jan. 16th 2022, 2:26:18pm UTC
+        val millis = 1_642_343_178_000L
+
+        // Act
+        val jd = NativeAccess.rust_julian_day_from_unix_millis(millis)
+
+        // Assert
+        MatcherAssert.assertThat(jd, closeTo(2_459_596.101598, 0.000_001))
+        Assert.assertEquals(millis, NativeAccess.rust_unix_millis_from_julian_day(jd))
+    }
+
+    @Test
+    fun rust_julian_day_from_zoned_date_time_test() {
+        // Arrange
+
+        // SS: Jan. 16th 2022, 2:26:18pm UTC in Denver, as the view models
+        // pass it to the library
+        val dateTime = ZonedDateTime.of(2022, 1, 16, 7, 26, 18, 0, ZoneId.of("America/Denver"))
+
+        // Act
+        val jd = NativeAccess.rust_julian_day_from_unix_millis(dateTime.toInstant().toEpochMilli())
+
+        // Assert
+        MatcherAssert.assertThat(jd, closeTo(2_459_596.101598, 0.000_001))
+    }
+
     @Test
     fun rust_local_siderial_time_test() {
         // Arrange
@@ -89,6 +122,41 @@ class NativeAccessTest {
         Assert.assertEquals(hms_str, "16h 6m 46.994s")
     }
 
+    @Test
+    fun rust_to_dms_batch() {
+        // Arrange
+        val degrees = doubleArrayOf(13.769657226951539, -5.5)
+
+        // Act
+        val dms = NativeAccess.rust_to_dms_batch(degrees, 3)
+
+        // Assert
+        Assert.assertArrayEquals(arrayOf("13° 46' 10.766\"", "-5° 30' 0.000\""), dms)
+    }
+
+    @Test
+    fun rust_to_hms_batch() {
+        // Arrange
+        val degrees = doubleArrayOf(241.6958092513155, 15.0)
+
+        // Act
+        val hms = NativeAccess.rust_to_hms_batch(degrees, 3)
+
+        // Assert
+        Assert.assertArrayEquals(arrayOf("16h 6m 46.994s", "1h 0m 0.000s"), hms)
+    }
+
+    @Test
+    fun rust_data_info() {
+        // Act
+        val info = JSONObject(NativeAccess.rust_data_info())
+
+        // Assert
+        Assert.assertEquals("2017-01-01", info.getJSONObject("leap_seconds").getString("last"))
+        Assert.assertEquals(37, info.getJSONObject("leap_seconds").getInt("tai_minus_utc"))
+        Assert.assertTrue(info.getJSONObject("delta_t").getInt("entries") > 0)
+    }
+
     @Test
     fun rust_moon_data_test() {
         // Arrange
@@ -122,6 +190,10 @@ class NativeAccessTest {
             moonOutputData.distanceFromEarth,
             closeTo(403836.9196467576, 0.001)
         )
+        MatcherAssert.assertThat(
+            moonOutputData.distancePercentOfMean,
+            closeTo(100.0 * 403836.9196467576 / 385000.56, 0.001)
+        )
         MatcherAssert.assertThat(moonOutputData.hourAngle, closeTo(114.1696980754786, 0.001))
         MatcherAssert.assertThat(
             moonOutputData.geocentricLatitude,
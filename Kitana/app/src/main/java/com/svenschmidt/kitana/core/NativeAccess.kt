@@ -13,24 +13,55 @@ class NativeAccess {
     )
 
     data class MoonOutputData(
+        // SS: false if the library could not calculate the data for the input,
+        // all other values are then 0
+        var isValid: Boolean = false,
         var phaseAngle: Double = 0.0,
         var phaseAge: Double = 0.0,
         var illuminatedFraction: Double = 0.0,
+        var elongation: Double = 0.0,
+        var isWaxing: Boolean = false,
         var phaseDesc: String = "",
         var geocentricLongitude: Double = 0.0,
         var geocentricLatitude: Double = 0.0,
         var distanceFromEarth: Double = 0.0,
+        var distanceEarthRadii: Double = 0.0,
+        // SS: in percent of the mean distance of 385,000.56 km
+        var distancePercentOfMean: Double = 0.0,
+        var nextApsisIsPerigee: Boolean = false,
+        var nextApsisTime: DateTime = DateTime(),
+        var nextApsisDistance: Double = 0.0,
         var hourAngle: Double = 0.0,
         var rightAscension: Double = 0.0,
         var declination: Double = 0.0,
         var azimuth: Double = 0.0,
         var altitude: Double = 0.0,
+        var uncertainty: Double = 0.0,
+        // SS: direction of the bright limb, counterclockwise from the zenith, in degrees
+        var crescentTilt: Double = 0.0,
+        // SS: geocentric longitude rate in degrees per day, to tell a fast from a slow moon
+        var longitudinalVelocity: Double = 0.0,
         var riseTime: DateTime = DateTime(),
         var setTime: DateTime = DateTime(),
         var transitTime: DateTime = DateTime(),
     )
 
+    data class SolunarOutputData(
+        // SS: false if the library could not calculate the data for the input
+        var isValid: Boolean = false,
+        var majorPeriod1Start: DateTime = DateTime(),
+        var majorPeriod1End: DateTime = DateTime(),
+        var majorPeriod2Start: DateTime = DateTime(),
+        var majorPeriod2End: DateTime = DateTime(),
+        var minorPeriod1Start: DateTime = DateTime(),
+        var minorPeriod1End: DateTime = DateTime(),
+        var minorPeriod2Start: DateTime = DateTime(),
+        var minorPeriod2End: DateTime = DateTime(),
+        var dayRating: Short = 0,
+    )
+
     data class MoonInputData(
+        // SS: Julian day in UTC, e.g. from rust_julian_day_from_unix_millis
         var jd: Double,
         var timezoneOffset: Short,
         var longitudeObserver: Double,
@@ -38,20 +69,42 @@ class NativeAccess {
         var heightAboveSeaObserver: Double,
         var pressure: Double,
         var temperature: Double,
+        // SS: IANA time zone name, e.g. "America/Denver", takes precedence over
+        // timezoneOffset if the library is built with feature tz
+        var timezone: String = "",
+        // SS: whether the observer sees a sea horizon, e.g. from a ship's deck,
+        // which lowers the horizon by the dip for heightAboveSeaObserver
+        var seaHorizon: Boolean = false,
     )
 
     companion object {
         // SS: format
         external fun rust_to_dms(degrees: Double, width: Byte): String
         external fun rust_to_hms(degrees: Double, width: Byte): String
+        // SS: one call per table refresh instead of one per row
+        external fun rust_to_dms_batch(degrees: DoubleArray, width: Byte): Array<String>
+        external fun rust_to_hms_batch(degrees: DoubleArray, width: Byte): Array<String>
 
         // SS: Julian Day
         external fun rust_julian_day(year: Int, month: Int, day: Double): Double
+        external fun rust_julian_day_from_unix_millis(millis: Long): Double
+        external fun rust_unix_millis_from_julian_day(jd: Double): Long
 
         // SS: Siderial Time
         external fun rust_local_siderial_time(jd: Double, longitudeObserver: Double): Double
 
         // SS: moon
         external fun rust_moon_data(moonInputData: MoonInputData, moonOutputData: MoonOutputData)
+
+        // SS: widget, packed little-endian layout documented in moonlib/src/widget.rs
+        const val WIDGET_DATA_SIZE = 60
+        external fun rust_moon_widget_data(moonInputData: MoonInputData, buffer: ByteArray)
+
+        // SS: solunar periods
+        external fun rust_solunar_data(moonInputData: MoonInputData, solunarOutputData: SolunarOutputData)
+
+        // SS: diagnostics, JSON with the library's version and the dates its
+        // delta t and leap second tables end
+        external fun rust_data_info(): String
     }
 }
\ No newline at end of file
@@ -82,8 +82,7 @@ class DateTimeViewModel(application: Application) : AndroidViewModel(application
         utcTime.postValue(formatted)
 
         // SS: set Julian day
-        val (year, month, day) = fromLocalDateTime(utcDateTime)
-        val julianDay = NativeAccess.rust_julian_day(year, month, day)
+        val julianDay = NativeAccess.rust_julian_day_from_unix_millis(localDateTime.toInstant().toEpochMilli())
         this.julianDay.postValue(julianDay.toString())
 
         // SS: Boulder longitude
@@ -7,7 +7,6 @@ import com.svenschmidt.kitana.InitApp
 import com.svenschmidt.kitana.core.DateTimeProvider
 import com.svenschmidt.kitana.core.NativeAccess
 import java.time.LocalDateTime
-import java.time.ZoneId
 import java.time.ZoneOffset
 import java.time.ZonedDateTime
 import javax.inject.Inject
@@ -24,6 +23,7 @@ class MoonActivityViewModel(application: Application) : AndroidViewModel(applica
     val geocentricLongitude = MutableLiveData<String>()
     val geocentricLatitude = MutableLiveData<String>()
     val distance = MutableLiveData<String>()
+    val nextApsis = MutableLiveData<String>()
     val hourAngle = MutableLiveData<String>()
     val rightAscension = MutableLiveData<String>()
     val declination = MutableLiveData<String>()
@@ -46,44 +46,55 @@ class MoonActivityViewModel(application: Application) : AndroidViewModel(applica
     }
 
     private fun onUpdateDateTime(localDateTime: ZonedDateTime) {
-        // SS: get UTC time
-        val utcDateTime: LocalDateTime = localDateTime.toLocalDateTime().atZone(ZoneId.systemDefault()).withZoneSameInstant(ZoneOffset.UTC).toLocalDateTime()
-        val (year, month, day) = DateTimeViewModel.fromLocalDateTime(utcDateTime)
-        val julianDay = NativeAccess.rust_julian_day(year, month, day)
+        // SS: the library converts the instant to a Julian day in UTC
+        val julianDay = NativeAccess.rust_julian_day_from_unix_millis(localDateTime.toInstant().toEpochMilli())
 
         // TODO: pass in correct lat, long, height -> location: Boulder, CO
         val timezoneOffset = localDateTime.offset.totalSeconds.toHours().toInt().toShort()
-        val moonInputData = NativeAccess.MoonInputData(julianDay, timezoneOffset, 105.2151, 40.05972, 1624.0, 1013.0, 10.0)
+        val moonInputData = NativeAccess.MoonInputData(julianDay, timezoneOffset, 105.2151, 40.05972, 1624.0, 1013.0, 10.0, localDateTime.zone.id)
         val moonOutputData = NativeAccess.MoonOutputData()
         NativeAccess.rust_moon_data(moonInputData, moonOutputData)
 
+        // SS: the library logs the input it could not calculate the data for
+        if (!moonOutputData.isValid) {
+            listOf(phaseAngle, phaseAge, fractionIlluminated, phaseName, geocentricLongitude, geocentricLatitude,
+                distance, nextApsis, hourAngle, rightAscension, declination, altitude, azimuth, rises, transits, sets)
+                .forEach { it.postValue("-") }
+            return
+        }
+
         phaseAngle.postValue("${moonOutputData.phaseAngle.format(2)}°")
         phaseAge.postValue("${moonOutputData.phaseAge.format(2)} days")
         fractionIlluminated.postValue("${(moonOutputData.illuminatedFraction * 100).format(2)}%")
         phaseName.postValue(moonOutputData.phaseDesc)
 
-        val geocentricLongitudeDMS = NativeAccess.rust_to_dms(moonOutputData.geocentricLongitude, 2)
-        geocentricLongitude.postValue(geocentricLongitudeDMS)
-
-        val geocentricLatitudeDMS = NativeAccess.rust_to_dms(moonOutputData.geocentricLatitude, 2)
-        geocentricLatitude.postValue(geocentricLatitudeDMS)
-
-        distance.postValue("${moonOutputData.distanceFromEarth.format(0)}km")
-
-        val hourAngleStr = NativeAccess.rust_to_hms(moonOutputData.hourAngle, 2)
-        hourAngle.postValue(hourAngleStr)
-
-        val raStr = NativeAccess.rust_to_hms(moonOutputData.rightAscension, 2)
-        rightAscension.postValue(raStr)
-
-        val declStr = NativeAccess.rust_to_dms(moonOutputData.declination, 2)
-        declination.postValue(declStr)
-
-        val azimuthStr = NativeAccess.rust_to_dms(moonOutputData.azimuth, 2)
-        azimuth.postValue(azimuthStr)
-
-        val altitudeStr = NativeAccess.rust_to_dms(moonOutputData.altitude, 2)
-        altitude.postValue(altitudeStr)
+        // SS: format all angles with one call each
+        val dms = NativeAccess.rust_to_dms_batch(doubleArrayOf(moonOutputData.geocentricLongitude,
+            moonOutputData.geocentricLatitude, moonOutputData.declination, moonOutputData.azimuth,
+            moonOutputData.altitude), 2)
+        val hms = NativeAccess.rust_to_hms_batch(doubleArrayOf(moonOutputData.hourAngle,
+            moonOutputData.rightAscension), 2)
+
+        geocentricLongitude.postValue(dms[0])
+        geocentricLatitude.postValue(dms[1])
+
+        distance.postValue("${moonOutputData.distanceFromEarth.format(0)}km, " +
+                "${moonOutputData.distanceEarthRadii.format(2)} Earth radii, " +
+                "${moonOutputData.distancePercentOfMean.format(1)}% of mean")
+
+        // SS: next perigee or apogee, in local time
+        val apsisTime = moonOutputData.nextApsisTime
+        val utcApsisTime = LocalDateTime.of(apsisTime.year.toInt(), apsisTime.month.toInt(), apsisTime.day.toInt(), apsisTime.hours.toInt(), apsisTime.minutes.toInt(), apsisTime.seconds.toInt())
+        val localApsisTime = utcApsisTime.atZone(ZoneOffset.UTC).withZoneSameInstant(localDateTime.zone).toLocalDateTime()
+        val apsisKind = if (moonOutputData.nextApsisIsPerigee) "perigee" else "apogee"
+        nextApsis.postValue("$apsisKind ${localApsisTime.toLocalDate()} ${String.format("%02d", localApsisTime.hour)}:${String.format("%02d", localApsisTime.minute)}, " +
+                "${moonOutputData.nextApsisDistance.format(0)}km")
+
+        hourAngle.postValue(hms[0])
+        rightAscension.postValue(hms[1])
+        declination.postValue(dms[2])
+        azimuth.postValue(dms[3])
+        altitude.postValue(dms[4])
 
         // SS: set rise time
         if (moonOutputData.riseTime.isValid) {
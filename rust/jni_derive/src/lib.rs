@@ -0,0 +1,356 @@
+//! Derive JNI field marshalling for the structs passed between the Android
+//! app and moonlib.
+//!
+//! #[derive(JniStruct)] maps each field of a struct to the field of the
+//! Kotlin data class with the camel case name, e.g. is_valid to isValid, and
+//! generates
+//! - JniSignature, the class's type signature, e.g. for nested objects
+//! - JniFields, the Kotlin field names and type signatures, to check them
+//!   against the Kotlin sources on the host
+//! - JniRead::read_fields and JniWrite::write_fields, for Android only
+//!
+//! Reading tolerates a Kotlin class without some of the fields, e.g. from an
+//! older app: they are set to the field's value in the struct's Default and
+//! reported as missing. Structs that are read need to implement Default.
+//! Writing skips the fields the Kotlin class lacks and reports them.
+//!
+//! Attributes:
+//! - #[jni(class = "com/.../NativeAccess$DateTime")]: Kotlin class, required
+//! - #[jni(class = "...", read)] or #[jni(class = "...", write)]: generate
+//!   only one direction, e.g. for output fields that cannot be read back.
+//!   Both if neither is given
+//! - #[jni(name = "...")] on a field: Kotlin field name
+//! - #[jni(skip)] on a field: not marshalled, set to its default when read
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+#[proc_macro_derive(JniStruct, attributes(jni))]
+pub fn derive_jni_struct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Options of the #[jni(...)] attribute on the struct
+struct StructOptions {
+    class: String,
+    read: bool,
+    write: bool,
+}
+
+/// A marshalled field
+struct Field {
+    ident: syn::Ident,
+    ty: syn::Type,
+    kotlin_name: String,
+}
+
+fn struct_options(input: &DeriveInput) -> syn::Result<StructOptions> {
+    let mut class = None;
+    let mut read = false;
+    let mut write = false;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("jni"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("class") {
+                class = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("read") {
+                read = true;
+            } else if meta.path.is_ident("write") {
+                write = true;
+            } else {
+                return Err(meta.error("expected class, read or write"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let class = class
+        .ok_or_else(|| syn::Error::new_spanned(&input.ident, "missing #[jni(class = \"...\")]"))?;
+
+    // SS: both directions unless restricted to one
+    if !read && !write {
+        read = true;
+        write = true;
+    }
+
+    Ok(StructOptions { class, read, write })
+}
+
+/// Fields to marshal, and the skipped ones
+fn fields(input: &DeriveInput) -> syn::Result<(Vec<Field>, Vec<syn::Ident>)> {
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "JniStruct needs named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "JniStruct can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut fields = vec![];
+    let mut skipped = vec![];
+
+    for field in named {
+        let ident = field.ident.clone().unwrap();
+        let mut kotlin_name = camel_case(&ident.to_string());
+        let mut skip = false;
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("jni"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    kotlin_name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else {
+                    return Err(meta.error("expected name or skip"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if skip {
+            skipped.push(ident);
+        } else {
+            fields.push(Field {
+                ident,
+                ty: field.ty.clone(),
+                kotlin_name,
+            });
+        }
+    }
+
+    Ok((fields, skipped))
+}
+
+/// Convert a Rust field name to the Kotlin one, e.g. is_valid to isValid
+fn camel_case(name: &str) -> String {
+    let mut parts = name.split('_');
+    let first = parts.next().unwrap_or_default().to_string();
+    parts.fold(first, |mut accum, part| {
+        let mut chars = part.chars();
+        if let Some(c) = chars.next() {
+            accum.extend(c.to_uppercase());
+            accum.push_str(chars.as_str());
+        }
+        accum
+    })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let options = struct_options(&input)?;
+    let (fields, skipped) = fields(&input)?;
+
+    let name = &input.ident;
+    let signature = format!("L{};", options.class);
+    let module = quote!(crate::moon::jni_bridge::jni_struct);
+
+    let idents = fields.iter().map(|field| &field.ident).collect::<Vec<_>>();
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let kotlin_names = fields
+        .iter()
+        .map(|field| &field.kotlin_name)
+        .collect::<Vec<_>>();
+
+    let mut output = quote! {
+        impl #module::JniSignature for #name {
+            const SIGNATURE: &'static str = #signature;
+        }
+
+        impl #module::JniFields for #name {
+            const FIELDS: &'static [(&'static str, &'static str)] = &[
+                #((#kotlin_names, <#types as #module::JniSignature>::SIGNATURE),)*
+            ];
+        }
+    };
+
+    if options.read {
+        output.extend(quote! {
+            #[cfg(target_os = "android")]
+            impl #module::android::JniRead for #name {
+                fn read_fields(
+                    env: ::jni::JNIEnv,
+                    object: ::jni::objects::JObject,
+                    missing: &mut ::std::vec::Vec<::std::string::String>,
+                ) -> Self {
+                    let default = <Self as ::core::default::Default>::default();
+                    Self {
+                        #(#idents: match #module::android::JniGet::get(env, object, #kotlin_names) {
+                            ::core::option::Option::Some(value) => value,
+                            ::core::option::Option::None => {
+                                missing.push(::std::string::String::from(#kotlin_names));
+                                default.#idents
+                            }
+                        },)*
+                        #(#skipped: default.#skipped,)*
+                    }
+                }
+            }
+
+            #[cfg(target_os = "android")]
+            impl #module::android::JniGet for #name {
+                fn get(
+                    env: ::jni::JNIEnv,
+                    object: ::jni::objects::JObject,
+                    name: &str,
+                ) -> ::core::option::Option<Self> {
+                    let field = #module::android::get_field(env, object, name, #signature)?
+                        .l()
+                        .ok()?;
+                    ::core::option::Option::Some(
+                        <Self as #module::android::JniRead>::read_from(env, field),
+                    )
+                }
+            }
+        });
+    }
+
+    if options.write {
+        output.extend(quote! {
+            #[cfg(target_os = "android")]
+            impl #module::android::JniWrite for #name {
+                fn write_fields(
+                    &self,
+                    env: ::jni::JNIEnv,
+                    object: ::jni::objects::JObject,
+                    missing: &mut ::std::vec::Vec<::std::string::String>,
+                ) {
+                    #(
+                        if !#module::android::JniSet::set(&self.#idents, env, object, #kotlin_names) {
+                            missing.push(::std::string::String::from(#kotlin_names));
+                        }
+                    )*
+                }
+            }
+
+            #[cfg(target_os = "android")]
+            impl #module::android::JniSet for #name {
+                fn set(
+                    &self,
+                    env: ::jni::JNIEnv,
+                    object: ::jni::objects::JObject,
+                    name: &str,
+                ) -> bool {
+                    let field = #module::android::get_field(env, object, name, #signature)
+                        .and_then(|field| field.l().ok());
+                    match field {
+                        ::core::option::Option::Some(field) => {
+                            #module::android::JniWrite::write_to(self, env, field);
+                            true
+                        }
+                        ::core::option::Option::None => false,
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn camel_case_test() {
+        // Arrange
+        let names = ["jd", "is_valid", "height_above_sea_observer"];
+
+        // Act
+        let camel_case = names.map(camel_case);
+
+        // Assert
+        assert_eq!(["jd", "isValid", "heightAboveSeaObserver"], camel_case);
+    }
+
+    #[test]
+    fn expand_test() {
+        // Arrange
+        let input: DeriveInput = parse_quote! {
+            #[jni(class = "com/example/Data", write)]
+            struct Data {
+                is_valid: bool,
+                #[jni(name = "degrees")]
+                angle: f64,
+                #[jni(skip)]
+                cache: Vec<f64>,
+            }
+        };
+
+        // Act
+        let output = expand(input).unwrap().to_string();
+
+        // Assert
+        assert!(output.contains("\"Lcom/example/Data;\""));
+        assert!(output.contains("\"isValid\""));
+        assert!(output.contains("\"degrees\""));
+        assert!(!output.contains("cache"));
+        assert!(output.contains("JniWrite"));
+        assert!(!output.contains("JniRead"));
+    }
+
+    #[test]
+    fn read_defaults_test() {
+        // Arrange
+        let input: DeriveInput = parse_quote! {
+            #[jni(class = "com/example/Data", read)]
+            struct Data {
+                angle: f64,
+                #[jni(skip)]
+                cache: Vec<f64>,
+            }
+        };
+
+        // Act
+        let output = expand(input).unwrap().to_string();
+
+        // Assert
+
+        // SS: missing fields fall back to Default and are reported, no unwrap
+        assert!(output.contains("read_fields"));
+        assert!(output.contains("Default > :: default ()"));
+        assert!(output.contains("default . angle"));
+        assert!(output.contains("default . cache"));
+        assert!(output.contains("missing . push"));
+        assert!(!output.contains("unwrap"));
+    }
+
+    #[test]
+    fn missing_class_test() {
+        // Arrange
+        let input: DeriveInput = parse_quote! {
+            struct Data {
+                angle: f64,
+            }
+        };
+
+        // Act
+        let output = expand(input);
+
+        // Assert
+        assert!(output.is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! The Moon right now for an observer: phase, position, and the times of
+//! moonrise, moonset and transit, as shown by the app.
+//!
+//!   cargo run --package moonlib --example moon_tonight -- [latitude] [longitude] [time zone offset]
+//!
+//! The longitude is positive west of Greenwich, as everywhere in moonlib,
+//! the time zone offset in hours. Without arguments, for Munich in UTC.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use moonlib::bridge_core::{self, MoonInput};
+use moonlib::date::jd::JD;
+
+fn main() {
+    let args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse::<f64>().expect("arguments must be numbers"))
+        .collect::<Vec<_>>();
+    let latitude = args.first().copied().unwrap_or(48.1);
+    let longitude = args.get(1).copied().unwrap_or(-11.6);
+    let timezone_offset = args.get(2).copied().unwrap_or(0.0) as i8;
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64;
+    let now = JD::from_unix_millis(millis);
+
+    print!("{}", report(now, latitude, longitude, timezone_offset));
+}
+
+/// The Moon's data at a time, one value per line
+/// In:
+/// jd: Julian Day, in UTC
+/// latitude, longitude: observer's location, in degrees, longitude positive
+/// west of Greenwich
+/// timezone_offset: in hours
+/// Out: report
+fn report(jd: JD, latitude: f64, longitude: f64, timezone_offset: i8) -> String {
+    let input = MoonInput {
+        jd: jd.jd,
+        timezone_offset,
+        timezone: None,
+        longitude_observer: longitude,
+        latitude_observer: latitude,
+        height_above_sea_observer: 0.0,
+        pressure: 1013.0,
+        temperature: 10.0,
+        sea_horizon: false,
+    };
+    let output = bridge_core::moon_data(&input);
+
+    format!("{jd} UTC, latitude {latitude}, longitude {longitude}\n{output}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_test() {
+        // Arrange
+
+        // SS: 2000 January 1, 12h UTC
+        let jd = JD::new(2_451_545.0);
+
+        // Act
+        let report = report(jd, 48.1, -11.6, 1);
+
+        // Assert
+        assert!(report.starts_with("2000-01-01T12:00:00 UTC"));
+        assert!(report.contains("Waning Crescent"));
+        assert!(!report.contains("invalid"));
+    }
+}
@@ -0,0 +1,113 @@
+//! The Moon's phase for each day of a month, with the days of new moon,
+//! first quarter, full moon and last quarter marked.
+//!
+//!   cargo run --package moonlib --example phase_calendar -- [year] [month]
+//!
+//! Without arguments, for January 2024. The phase is given at 0h UTC.
+
+use moonlib::bridge_core::{self, MoonInput, MoonOutput};
+use moonlib::date::{date::Date, jd::JD};
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let year = args.next().map_or(2024, |arg| arg.parse().expect("year"));
+    let month = args.next().map_or(1, |arg| arg.parse().expect("month"));
+
+    print!("{}", calendar(year, month));
+}
+
+/// Principal phase reached between two days
+/// In: elongation of the Moon from the Sun on the two days, in degrees
+/// [-180, 180)
+/// Out: name of the phase, if the elongation passes it
+fn principal_phase(before: f64, after: f64) -> Option<&'static str> {
+    if before < 0.0 && after >= 0.0 {
+        Some("new moon")
+    } else if before < 90.0 && after >= 90.0 {
+        Some("first quarter")
+    } else if before > 0.0 && after < 0.0 {
+        // SS: the elongation wraps around from 180 to -180
+        Some("full moon")
+    } else if before < -90.0 && after >= -90.0 {
+        Some("last quarter")
+    } else {
+        None
+    }
+}
+
+/// The Moon's data for an observer at the center of the Earth
+/// In: Julian Day, in UTC
+/// Out: the Moon's data
+fn moon_at(jd: JD) -> MoonOutput {
+    let input = MoonInput {
+        jd: jd.jd,
+        timezone_offset: 0,
+        timezone: None,
+        longitude_observer: 0.0,
+        latitude_observer: 0.0,
+        height_above_sea_observer: 0.0,
+        pressure: 1013.0,
+        temperature: 10.0,
+        sea_horizon: false,
+    };
+    bridge_core::moon_data(&input)
+}
+
+/// Phase calendar, one line per day
+/// In: year and month
+/// Out: calendar
+fn calendar(year: i16, month: u8) -> String {
+    let first = JD::from(Date {
+        year,
+        month,
+        day: 1.0,
+    });
+
+    // SS: one day more than the month has, for the phase reached on its
+    // last day
+    let days = (0..32)
+        .map(|day| JD::new(first.jd + day as f64))
+        .take_while(|jd| jd.to_calendar_date().month == month)
+        .collect::<Vec<_>>();
+    let moon = (0..=days.len())
+        .map(|day| moon_at(JD::new(first.jd + day as f64)))
+        .collect::<Vec<_>>();
+
+    let mut calendar = String::new();
+    for (idx, jd) in days.iter().enumerate() {
+        let phase = principal_phase(moon[idx].elongation, moon[idx + 1].elongation)
+            .map(|phase| format!("  <- {phase} before the next day"))
+            .unwrap_or_default();
+
+        calendar += &format!(
+            "{}  {:<16} {:5.1} %{phase}\n",
+            &jd.to_string()[..10],
+            moon[idx].phase_desc,
+            100.0 * moon[idx].illuminated_fraction
+        );
+    }
+    calendar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_test() {
+        // Act
+        let calendar = calendar(2024, 2);
+
+        // Assert
+
+        // SS: full moon on 2024 February 24, 12:30 UTC, new moon on
+        // February 9, 22:59 UTC
+        assert_eq!(29, calendar.lines().count());
+        assert!(calendar
+            .lines()
+            .any(|line| line.starts_with("2024-02-24") && line.contains("full moon")));
+        assert!(calendar
+            .lines()
+            .any(|line| line.starts_with("2024-02-09") && line.contains("new moon")));
+    }
+}
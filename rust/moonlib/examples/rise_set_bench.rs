@@ -0,0 +1,71 @@
+//! Time the rise/set searches for a year of days, and compare them to the
+//! cost of converting angles between degrees and radians, which the loops of
+//! the searches do a few times per step.
+//! To run on an ARM device, e.g. Android:
+//!
+//!   cargo build --release -p moonlib --example rise_set_bench --target aarch64-linux-android
+//!   adb push target/aarch64-linux-android/release/examples/rise_set_bench /data/local/tmp
+//!   adb shell /data/local/tmp/rise_set_bench
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use moonlib::config::AngleUnit;
+use moonlib::date::jd::JD;
+use moonlib::moon::position::topocentric_coordinates;
+use moonlib::session::{Observer, Session};
+
+const DAYS: f64 = 365.0;
+const CONVERSIONS: usize = 10_000_000;
+
+/// Best of 5 runs, to reduce the noise of other processes
+fn best_of_5(f: impl Fn()) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn session() -> Session {
+    // SS: Munich, 2024
+    let observer = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+    let start = JD::new(2_460_310.5);
+    Session::new(observer, start, JD::new(start.jd + DAYS - 1.0))
+}
+
+fn main() {
+    let moon = best_of_5(|| {
+        let session = session();
+        black_box(session.moon().rise());
+        black_box(session.moon().set());
+        black_box(session.moon().transit());
+    });
+    println!(
+        "moon rise, set and transit: {:.1} us per day",
+        moon.as_secs_f64() * 1e6 / DAYS
+    );
+
+    let sun = best_of_5(|| {
+        black_box(session().sun().day());
+    });
+    println!(
+        "sun rise, set and noon:     {:.1} us per day",
+        sun.as_secs_f64() * 1e6 / DAYS
+    );
+
+    let session = session();
+    let azimuth = topocentric_coordinates(session.days()[0], &session.observer, 0.0).azimuth;
+    let conversion = best_of_5(|| {
+        for _ in 0..CONVERSIONS {
+            black_box(black_box(azimuth).value(AngleUnit::Radians));
+        }
+    });
+    println!(
+        "degrees to radians:         {:.2} ns per conversion",
+        conversion.as_secs_f64() * 1e9 / CONVERSIONS as f64
+    );
+}
@@ -0,0 +1,146 @@
+//! Table of moonrise, moonset, the Moon's transit, sunrise and sunset for
+//! consecutive days, calculated with a Session.
+//!
+//!   cargo run --package moonlib --example rise_set_table -- [year] [month] [day] [days] [latitude] [longitude] [time zone offset]
+//!
+//! The longitude is positive west of Greenwich, the time zone offset in
+//! hours. Without arguments, for 7 days from 2024 March 1 in Munich, UTC+1.
+
+use moonlib::date::{date::Date, jd::JD};
+use moonlib::moon::rise_set_transit::OutputKind;
+use moonlib::session::{Observer, Session};
+use moonlib::sun::rise_set::DayVisibility;
+
+fn main() {
+    let args = std::env::args()
+        .skip(1)
+        .map(|arg| arg.parse::<f64>().expect("arguments must be numbers"))
+        .collect::<Vec<_>>();
+    let arg = |idx: usize, default: f64| args.get(idx).copied().unwrap_or(default);
+
+    let start = JD::from(Date {
+        year: arg(0, 2024.0) as i16,
+        month: arg(1, 3.0) as u8,
+        day: arg(2, 1.0),
+    });
+    let days = arg(3, 7.0) as u32;
+    let observer = Observer::new(arg(5, -11.6), arg(4, 48.1), arg(6, 1.0) as i8, 1013.0, 10.0);
+
+    print!("{}", table(observer, start, days));
+}
+
+/// Local time of day, hours and minutes
+/// In:
+/// jd: Julian Day, in UT
+/// timezone_offset: in hours
+/// Out: time of day, e.g. 21:05
+fn local_time(jd: JD, timezone_offset: i8) -> String {
+    JD::new(jd.jd + timezone_offset as f64 / 24.0).to_string()[11..16].to_string()
+}
+
+/// Local time of day of an event, or why there is none
+fn time_of_day(event: OutputKind, timezone_offset: i8) -> String {
+    match event {
+        OutputKind::Time(jd) => local_time(jd, timezone_offset),
+        OutputKind::NeverRises => "no rise".to_string(),
+        OutputKind::NeverSets => "no set".to_string(),
+        OutputKind::NeverTransits => "no transit".to_string(),
+    }
+}
+
+/// Sunrise and sunset, or the Sun's visibility all day
+fn sunrise_sunset(visibility: DayVisibility, timezone_offset: i8) -> (String, String) {
+    let time = |jd: JD| local_time(jd, timezone_offset);
+    match visibility {
+        DayVisibility::RisesAndSets { rise, set } => (time(rise), time(set)),
+        DayVisibility::AlwaysAbove => ("polar day".to_string(), "-".to_string()),
+        DayVisibility::AlwaysBelow => ("polar night".to_string(), "-".to_string()),
+        DayVisibility::RisesOnly(rise) => (time(rise), "-".to_string()),
+        DayVisibility::SetsOnly(set) => ("-".to_string(), time(set)),
+    }
+}
+
+/// Rise/set table, one line per day
+/// In:
+/// observer: observer's location and time zone
+/// start: Julian Day of the first day, 0h
+/// days: number of days
+/// Out: table, times in the observer's time zone
+fn table(observer: Observer, start: JD, days: u32) -> String {
+    // SS: the session assigns the events to the observer's local days, but
+    // returns their times in UT
+    let timezone_offset = observer.timezone_offset;
+    let end = JD::new(start.jd + (days as f64 - 1.0));
+    let session = Session::new(observer, start, end);
+
+    let moon = session.moon();
+    let rises = moon.rise();
+    let sets = moon.set();
+    let transits = moon.transit();
+    let solar_days = session.sun().day();
+
+    let mut table = format!(
+        "{:<10}  {:>10}  {:>10}  {:>10}  {:>11}  {:>6}\n",
+        "date", "moonrise", "transit", "moonset", "sunrise", "sunset"
+    );
+    for (idx, day) in session.days().iter().enumerate() {
+        let (sunrise, sunset) = sunrise_sunset(solar_days[idx].visibility, timezone_offset);
+        table += &format!(
+            "{:<10}  {:>10}  {:>10}  {:>10}  {:>11}  {:>6}\n",
+            &day.to_string()[..10],
+            time_of_day(rises[idx], timezone_offset),
+            time_of_day(transits[idx], timezone_offset),
+            time_of_day(sets[idx], timezone_offset),
+            sunrise,
+            sunset
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+        let start = JD::from(Date {
+            year: 2024,
+            month: 3,
+            day: 1.0,
+        });
+
+        // Act
+        let table = table(observer, start, 7);
+
+        // Assert
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(8, lines.len());
+        assert!(lines[1].starts_with("2024-03-01"));
+
+        // SS: sunrise in Munich on 2024 March 1 is at 06:55 CET
+        assert!(lines[1].contains("06:54"));
+        assert!(lines[7].starts_with("2024-03-07"));
+    }
+
+    #[test]
+    fn polar_night_test() {
+        // Arrange
+
+        // SS: Longyearbyen, the Sun stays below the horizon in December
+        let observer = Observer::new(-15.6, 78.2, 1, 1013.0, -10.0);
+        let start = JD::from(Date {
+            year: 2023,
+            month: 12,
+            day: 21.0,
+        });
+
+        // Act
+        let table = table(observer, start, 1);
+
+        // Assert
+        assert!(table.contains("polar night"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Time a scan of the Sun's position over a day at 1 minute steps, from
+//! the VSOP87 series for each minute and from a SunDayCache, and count the
+//! evaluations of the series.
+//! To run on an ARM device, e.g. Android, see rise_set_bench.
+
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use moonlib::coordinates::{self, Declination, RightAscension};
+use moonlib::date::jd::JD;
+use moonlib::earth;
+use moonlib::sun::{self, SunDayCache};
+
+const DAYS: usize = 30;
+const STEPS: usize = 1440;
+
+/// Best of 5 runs, to reduce the noise of other processes
+fn best_of_5(f: impl Fn()) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Sun's apparent right ascension and declination from the full VSOP87 series
+fn position(jd: JD) -> (RightAscension, Declination) {
+    let sun = sun::apparent_position(jd);
+    coordinates::ecliptical_2_equatorial(sun.longitude, sun.latitude, earth::true_obliquity(jd))
+}
+
+fn minutes(day: usize) -> impl Iterator<Item = JD> {
+    // SS: 2024 March 1st, 0h TD
+    let start = 2_460_370.5 + day as f64;
+    (0..=STEPS).map(move |minute| JD::new(start + minute as f64 / STEPS as f64))
+}
+
+fn main() {
+    let evaluations = Cell::new(0);
+    let counted = |jd: JD| {
+        evaluations.set(evaluations.get() + 1);
+        position(jd)
+    };
+
+    let direct = best_of_5(|| {
+        for day in 0..DAYS {
+            for jd in minutes(day) {
+                black_box(counted(jd));
+            }
+        }
+    });
+    let direct_evaluations = evaluations.replace(0) / 5;
+
+    let cached = best_of_5(|| {
+        for day in 0..DAYS {
+            let cache = SunDayCache::with_position(minutes(day).next().unwrap(), counted);
+            for jd in minutes(day) {
+                black_box(cache.ra_dec(jd));
+            }
+        }
+    });
+    let cached_evaluations = evaluations.get() / 5;
+
+    println!(
+        "VSOP87 for each minute: {:.1} us per day, {} evaluations per day",
+        direct.as_secs_f64() * 1e6 / DAYS as f64,
+        direct_evaluations / DAYS
+    );
+    println!(
+        "SunDayCache:            {:.1} us per day, {} evaluations per day",
+        cached.as_secs_f64() * 1e6 / DAYS as f64,
+        cached_evaluations / DAYS
+    );
+    println!(
+        "{:.0}x fewer evaluations, {:.0}x faster",
+        direct_evaluations as f64 / cached_evaluations as f64,
+        direct.as_secs_f64() / cached.as_secs_f64()
+    );
+}
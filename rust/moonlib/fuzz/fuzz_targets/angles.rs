@@ -0,0 +1,39 @@
+//! Conversions of angles from and to degrees, arc minutes and arc seconds,
+//! or hours, minutes and seconds, and their formatting
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use moonlib::config::AngleUnit;
+use moonlib::coordinates::{Declination, Degrees, RightAscension};
+
+fuzz_target!(|input: (i16, u8, u8, f64, f64, u8)| {
+    let (d, m, h, s, angle, width) = input;
+
+    let dms = Degrees::from_dms(d, m, s);
+    let hms = Degrees::from_hms(h, m, s);
+    let _ = dms.to_string();
+    let _ = hms.to_hms_str(width);
+    let _ = Declination::from_dms(d, m, s).to_string();
+    let _ = RightAscension::from_hms(h, m, s).to_string();
+
+    let angle = Degrees::new(angle);
+    let _ = angle.to_string();
+    let _ = angle.to_dms_str(width);
+    let _ = angle.to_hms_str(width);
+
+    let degrees = |angle: Degrees| angle.value(AngleUnit::Degrees);
+    if degrees(angle).is_finite() {
+        let mapped = degrees(angle.map_to_0_to_360());
+        assert!((0.0..=360.0).contains(&mapped), "{}", degrees(angle));
+
+        if degrees(dms).is_finite() {
+            let difference = degrees(angle.signed_difference(dms));
+            assert!(
+                (-180.0..180.0).contains(&difference),
+                "{} {}",
+                degrees(angle),
+                degrees(dms)
+            );
+        }
+    }
+});
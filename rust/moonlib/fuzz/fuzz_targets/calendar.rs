@@ -0,0 +1,29 @@
+//! Conversion of Julian Days to calendar dates, Meeus, chapter 7, also for
+//! Julian Days far outside of the years the crate is meant for
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use moonlib::date::jd::JD;
+use moonlib::time::{LeapSeconds, ScaledJD, TimeScale};
+
+fuzz_target!(|input: (f64, i8)| {
+    let (jd, timezone_offset) = input;
+    let jd = JD::new(jd);
+    let table = LeapSeconds::iers();
+
+    let date = jd.to_calendar_date();
+    let _ = jd.to_string();
+    let _ = jd.to_calendar_date_utc(&table);
+    let _ = ScaledJD::new(jd, TimeScale::UTC).to_calendar_date_utc(&table);
+    let _ = jd.start_of_local_day(timezone_offset);
+    let _ = jd.round_to_minute();
+    let _ = jd.floor_to_hour();
+    let _ = jd.to_unix_millis();
+
+    // SS: Meeus' method is valid for positive Julian Days, the year fits
+    // into an i16 up to about 32767 CE
+    if (0.0..13_000_000.0).contains(&jd.jd) {
+        assert!((1..=12).contains(&date.month), "{}", jd.jd);
+        assert!((1.0..32.0).contains(&date.day), "{}", jd.jd);
+    }
+});
@@ -0,0 +1,17 @@
+//! Parsing of the configuration string passed in from the app
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use moonlib::config::MoonlibConfig;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    // SS: a configuration that parses is written back to an equal one
+    if let Ok(config) = s.parse::<MoonlibConfig>() {
+        let written = config.to_string();
+        assert_eq!(Ok(config), written.parse::<MoonlibConfig>(), "{written}");
+    }
+});
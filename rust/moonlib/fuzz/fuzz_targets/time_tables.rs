@@ -0,0 +1,59 @@
+//! Lookups in the leap second and delta t tables, in particular close to
+//! their entries and ends, where the index arithmetic of the binary searches
+//! is most likely to go wrong
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use moonlib::date::jd::JD;
+use moonlib::time::{self, LeapSeconds, ScaledJD, TimeScale};
+
+fuzz_target!(|input: (u16, f64, bool)| {
+    let (idx, offset, near_entry) = input;
+    let table = LeapSeconds::iers();
+
+    // SS: either close to a table entry, or anywhere
+    let jd = if near_entry && offset.is_finite() {
+        let entries: Vec<JD> = table
+            .entries()
+            .map(|entry| entry.jd)
+            .chain(time::delta_t_entries().map(|entry| entry.jd))
+            .collect();
+        JD::new(entries[idx as usize % entries.len()].jd + offset % 1.0)
+    } else {
+        JD::new(offset)
+    };
+
+    let leap_seconds = table.cumulative(jd);
+    assert_eq!(
+        leap_seconds.to_bits(),
+        time::cumulative_leap_seconds(jd).to_bits()
+    );
+    let _ = table.covers(jd);
+    let _ = time::is_extrapolating(jd);
+
+    // SS: from 1961 on, TAI - UTC grew from 1.4 s to 37 s
+    if jd.jd.is_finite() && table.covers(jd) {
+        assert!(
+            (0.0..=37.0).contains(&leap_seconds),
+            "{} {}",
+            jd.jd,
+            leap_seconds
+        );
+    }
+
+    for time_scale in [
+        TimeScale::UTC,
+        TimeScale::UT1,
+        TimeScale::TT,
+        TimeScale::GPS,
+    ] {
+        let scaled = ScaledJD::new(jd, time_scale);
+        let utc = scaled.to_utc(&table);
+        let _ = scaled.to_calendar_date_utc(&table);
+        if jd.jd.is_finite() && jd.jd.abs() < 1e9 {
+            assert!(utc.jd.is_finite(), "{} {:?}", jd.jd, time_scale);
+        }
+    }
+    let _ = time::tt_2_utc(jd, &table);
+    let _ = time::gps_2_utc(jd, &table);
+});
@@ -0,0 +1,246 @@
+//! Common interface of the bodies, so features like rise/set, separations
+//! or occultations can be written once for all of them. Each body implements
+//! CelestialBody with the theory of its own module, e.g. Meeus, chapter 47
+//! for the Moon and VSOP87 for the Sun.
+
+use crate::coordinates;
+use crate::date::jd::JD;
+use crate::ecliptic;
+use crate::events::Body;
+use crate::precession::Equinox;
+use crate::util::{
+    arcsec::ArcSec, declination::Declination, degrees::Degrees, right_ascension::RightAscension,
+};
+use crate::{moon, sun};
+
+/// Semidiameter of the Sun at a distance of 1 AU, Meeus, chapter 55, page 389
+const SUN_SEMIDIAMETER_1_AU: f64 = 959.63;
+
+/// A body seen from the center of the Earth
+pub trait CelestialBody {
+    /// Apparent geocentric ecliptical coordinates
+    /// In: Julian day, in dynamical time
+    /// Out:
+    /// longitude, referred to the true equinox of the date, in degrees [0, 360)
+    /// latitude, referred to the ecliptic of the date, in degrees [-90, 90]
+    fn geocentric_ecliptic(&self, jd: JD) -> (Degrees, Degrees);
+
+    /// Distance from the center of the Earth
+    /// In: Julian day, in dynamical time
+    /// Out: distance, in km
+    fn distance(&self, jd: JD) -> f64;
+
+    /// Geocentric angular radius, i.e. the semidiameter
+    /// In: Julian day, in dynamical time
+    /// Out: angular radius, in degrees
+    fn angular_radius(&self, jd: JD) -> Degrees;
+
+    /// Apparent geocentric equatorial coordinates
+    /// In: Julian day, in dynamical time
+    /// Out:
+    /// right ascension, in degrees [0, 360)
+    /// declination, in degrees [-90, 90)
+    fn equatorial_coordinates(&self, jd: JD) -> (RightAscension, Declination) {
+        let (longitude, latitude) = self.geocentric_ecliptic(jd);
+        let eps = ecliptic::true_obliquity(jd);
+        coordinates::ecliptical_2_equatorial(longitude, latitude, eps)
+    }
+}
+
+/// The Moon, Meeus, chapter 47
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Moon;
+
+/// The Sun, from the VSOP87 series, Meeus, chapter 25
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sun;
+
+impl CelestialBody for Moon {
+    fn geocentric_ecliptic(&self, jd: JD) -> (Degrees, Degrees) {
+        moon::position::ecliptical_coordinates(jd, Equinox::OfDate)
+    }
+
+    fn distance(&self, jd: JD) -> f64 {
+        moon::position::distance_from_earth(jd)
+    }
+
+    fn angular_radius(&self, jd: JD) -> Degrees {
+        Degrees::from(moon::semidiameter::geocentric_semidiameter(jd))
+    }
+
+    fn equatorial_coordinates(&self, jd: JD) -> (RightAscension, Declination) {
+        moon::position::equatorial_coordinates(jd, Equinox::OfDate)
+    }
+}
+
+impl CelestialBody for Sun {
+    fn geocentric_ecliptic(&self, jd: JD) -> (Degrees, Degrees) {
+        let position = sun::position::apparent_position(jd);
+        (position.longitude, position.latitude)
+    }
+
+    fn distance(&self, jd: JD) -> f64 {
+        sun::position::distance_earth_sun(jd)
+    }
+
+    fn angular_radius(&self, jd: JD) -> Degrees {
+        let distance = sun::position::distance_earth_sun_ae(jd);
+        Degrees::from(ArcSec::new(SUN_SEMIDIAMETER_1_AU / distance))
+    }
+
+    fn equatorial_coordinates(&self, jd: JD) -> (RightAscension, Declination) {
+        sun::position::equatorial_coordinates(jd)
+    }
+}
+
+impl CelestialBody for Body {
+    fn geocentric_ecliptic(&self, jd: JD) -> (Degrees, Degrees) {
+        match self {
+            Body::Moon => Moon.geocentric_ecliptic(jd),
+            Body::Sun => Sun.geocentric_ecliptic(jd),
+        }
+    }
+
+    fn distance(&self, jd: JD) -> f64 {
+        match self {
+            Body::Moon => Moon.distance(jd),
+            Body::Sun => Sun.distance(jd),
+        }
+    }
+
+    fn angular_radius(&self, jd: JD) -> Degrees {
+        match self {
+            Body::Moon => Moon.angular_radius(jd),
+            Body::Sun => Sun.angular_radius(jd),
+        }
+    }
+
+    fn equatorial_coordinates(&self, jd: JD) -> (RightAscension, Declination) {
+        match self {
+            Body::Moon => Moon.equatorial_coordinates(jd),
+            Body::Sun => Sun.equatorial_coordinates(jd),
+        }
+    }
+}
+
+impl<T: CelestialBody + ?Sized> CelestialBody for &T {
+    fn geocentric_ecliptic(&self, jd: JD) -> (Degrees, Degrees) {
+        (**self).geocentric_ecliptic(jd)
+    }
+
+    fn distance(&self, jd: JD) -> f64 {
+        (**self).distance(jd)
+    }
+
+    fn angular_radius(&self, jd: JD) -> Degrees {
+        (**self).angular_radius(jd)
+    }
+
+    fn equatorial_coordinates(&self, jd: JD) -> (RightAscension, Declination) {
+        (**self).equatorial_coordinates(jd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn moon_test() {
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let (longitude, latitude) = Moon.geocentric_ecliptic(jd);
+        let distance = Moon.distance(jd);
+        let angular_radius = Moon.angular_radius(jd);
+
+        // Assert
+
+        // SS: Meeus, example 47.a, the horizontal parallax is 0.991990 deg,
+        // so the semidiameter is asin(0.272481 * sin(0.991990 deg))
+        assert_approx_eq!(133.167265, longitude.0, 0.000_01);
+        assert_approx_eq!(-3.229126, latitude.0, 0.000_01);
+        assert_approx_eq!(368_409.7, distance, 0.1);
+        assert_approx_eq!(973.03, angular_radius.0 * 3600.0, 0.01);
+    }
+
+    #[test]
+    fn sun_test() {
+        // Arrange
+
+        // SS: 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let (longitude, latitude) = Sun.geocentric_ecliptic(jd);
+        let distance = Sun.distance(jd);
+        let angular_radius = Sun.angular_radius(jd);
+
+        // Assert
+
+        // SS: Meeus, example 25.b, from the full VSOP87 series
+        assert_approx_eq!(199.905_988, longitude.0, 0.000_01);
+        assert_approx_eq!(0.000_200, latitude.0, 0.000_01);
+        assert_approx_eq!(0.997_607_75, distance / constants::AU, 0.000_001);
+        assert_approx_eq!(959.63 / 0.997_607_75, angular_radius.0 * 3600.0, 0.001);
+    }
+
+    #[test]
+    fn body_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2024, 3, 1.0));
+
+        for (body, generic) in [
+            (Body::Moon, &Moon as &dyn CelestialBody),
+            (Body::Sun, &Sun as &dyn CelestialBody),
+        ] {
+            // Act
+            let (ra, decl) = body.equatorial_coordinates(jd);
+
+            // Assert
+            let (expected_ra, expected_decl) = generic.equatorial_coordinates(jd);
+            assert_eq!(expected_ra.0, ra.0);
+            assert_eq!(expected_decl.0, decl.0);
+            assert_eq!(generic.distance(jd), body.distance(jd));
+            assert_eq!(generic.angular_radius(jd).0, body.angular_radius(jd).0);
+        }
+    }
+
+    #[test]
+    fn equatorial_coordinates_test() {
+        // Arrange
+
+        // SS: a body with the default equatorial coordinates
+        struct EclipticOnly;
+
+        impl CelestialBody for EclipticOnly {
+            fn geocentric_ecliptic(&self, jd: JD) -> (Degrees, Degrees) {
+                Moon.geocentric_ecliptic(jd)
+            }
+
+            fn distance(&self, jd: JD) -> f64 {
+                Moon.distance(jd)
+            }
+
+            fn angular_radius(&self, jd: JD) -> Degrees {
+                Moon.angular_radius(jd)
+            }
+        }
+
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let (ra, decl) = EclipticOnly.equatorial_coordinates(jd);
+
+        // Assert
+        let (expected_ra, expected_decl) = Moon.equatorial_coordinates(jd);
+        assert_approx_eq!(expected_ra.0, ra.0, 1e-9);
+        assert_approx_eq!(expected_decl.0, decl.0, 1e-9);
+    }
+}
@@ -0,0 +1,1302 @@
+//! The computations behind the JNI bridge, without any JNI types. The
+//! structs mirror the Kotlin data classes in NativeAccess.kt, so the bridge
+//! only copies fields and the decisions which field gets which value can be
+//! tested without an emulator.
+//! Input Julian Days are in UTC, as sent by the app, and so are all Julian
+//! Days and date/times returned.
+
+use std::fmt;
+
+use jni_derive::JniStruct;
+
+use crate::config::MoonlibConfig;
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::error_budget::{self, EventKind};
+use crate::horizon::Dip;
+use crate::moon;
+use crate::rise_set_transit::OutputKind;
+use crate::session::Observer;
+use crate::solunar::{self, Period};
+use crate::time::{self, LeapSeconds};
+use crate::util::degrees::Degrees;
+use crate::widget::WidgetData;
+
+/// Mirrors NativeAccess.MoonInputData
+#[derive(Debug, Clone, JniStruct)]
+#[jni(class = "com/svenschmidt/kitana/core/NativeAccess$MoonInputData", read)]
+pub struct MoonInput {
+    /// Julian Day, in UTC
+    pub jd: f64,
+
+    /// Observer's time zone offset, in hours
+    pub timezone_offset: i8,
+
+    /// Observer's IANA time zone name, e.g. "America/Denver". If set and the
+    /// feature tz is enabled, the zone's offset on the input's day is used
+    /// instead of timezone_offset
+    pub timezone: Option<String>,
+
+    /// in degrees [-180, 180), positive west of Greenwich
+    pub longitude_observer: f64,
+
+    /// in degrees [-90, 90)
+    pub latitude_observer: f64,
+
+    /// Observer's height above sea level, in meters
+    pub height_above_sea_observer: f64,
+
+    /// Atmospheric pressure, in millibars
+    pub pressure: f64,
+
+    /// Air temperature, in celsius
+    pub temperature: f64,
+
+    /// Whether the observer sees a sea horizon, e.g. from a ship's deck or a
+    /// cliff at the coast. Rise and set are then for the horizon lowered by
+    /// the dip for height_above_sea_observer, see horizon::Dip. Otherwise,
+    /// the horizon is flat at 0 degrees, as the terrain around an inland
+    /// observer is usually at about the observer's height
+    pub sea_horizon: bool,
+}
+
+/// Values for the fields a Kotlin MoonInputData lacks, e.g. from an app of
+/// a different version. Without the time or the location, nothing can be
+/// calculated, so they are NaN and the output is invalid. The other fields
+/// have usable defaults, e.g. the standard atmosphere of Meeus, chapter 16
+impl Default for MoonInput {
+    fn default() -> Self {
+        Self {
+            jd: f64::NAN,
+            timezone_offset: 0,
+            timezone: None,
+            longitude_observer: f64::NAN,
+            latitude_observer: f64::NAN,
+            height_above_sea_observer: 0.0,
+            pressure: 1010.0,
+            temperature: 10.0,
+            sea_horizon: false,
+        }
+    }
+}
+
+impl MoonInput {
+    /// Observer's time zone offset on the input's day, in hours. Falls back
+    /// to timezone_offset for unknown zones.
+    /// Note: the library's day bounds are in whole hours, so zones like
+    /// "Asia/Kolkata" are rounded to the nearest hour.
+    fn utc_offset(&self) -> i8 {
+        #[cfg(feature = "tz")]
+        if let Some(zone) = &self.timezone {
+            if let Ok(offset) = crate::timezone::utc_offset(zone, JD::new(self.jd)) {
+                return offset.round() as i8;
+            }
+        }
+
+        self.timezone_offset
+    }
+
+    /// Whether all numbers are finite. Infinite input, e.g. for the height,
+    /// can give finite, but meaningless output
+    fn is_finite(&self) -> bool {
+        [
+            self.jd,
+            self.longitude_observer,
+            self.latitude_observer,
+            self.height_above_sea_observer,
+            self.pressure,
+            self.temperature,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+    }
+
+    fn observer(&self) -> Observer {
+        Observer::new(
+            self.longitude_observer,
+            self.latitude_observer,
+            self.utc_offset(),
+            self.pressure,
+            self.temperature,
+        )
+    }
+
+    /// Convert the input's Julian Day from UTC to dynamical time
+    fn tt(&self) -> JD {
+        time::utc_2_tt(JD::new(self.jd))
+    }
+
+    /// Convert a Julian Day in dynamical time back to UTC, for the event's
+    /// own offset, so events after a leap second are shown correctly
+    fn utc(&self, tt: JD) -> JD {
+        time::tt_2_utc(tt, &LeapSeconds::iers())
+    }
+}
+
+/// Mirrors NativeAccess.DateTime, in UTC
+#[derive(Debug, Copy, Clone, Default, PartialEq, JniStruct)]
+#[jni(class = "com/svenschmidt/kitana/core/NativeAccess$DateTime", write)]
+pub struct DateTime {
+    pub is_valid: bool,
+    pub year: i16,
+    pub month: i16,
+    pub day: i16,
+    pub hours: i16,
+    pub minutes: i16,
+    pub seconds: f64,
+}
+
+impl DateTime {
+    /// The date/time of a Julian Day
+    /// In: Julian Day
+    /// Out: Valid date/time
+    pub fn from_jd(jd: JD) -> Self {
+        let date = jd.to_calendar_date();
+        let (hours, minutes, seconds) = crate::date::date::Date::from_fract_day(date.day);
+
+        Self {
+            is_valid: true,
+            year: date.year,
+            month: date.month as i16,
+            day: date.day.trunc() as i16,
+            hours: hours as i16,
+            minutes: minutes as i16,
+            seconds,
+        }
+    }
+
+    /// Date/time for a Julian Day that may not exist
+    /// In: Julian Day
+    /// Out: Valid date/time, if there is a Julian Day. Otherwise, is_valid is false
+    pub fn from_option(jd: Option<JD>) -> Self {
+        jd.map_or_else(Self::default, Self::from_jd)
+    }
+
+    /// The date/time of a Julian Day, rounded to a step, see error_budget
+    /// In:
+    /// jd: Julian Day
+    /// step: in seconds, one of the steps of error_budget::display_step
+    /// Out: Valid date/time, with seconds a multiple of the step
+    pub fn from_jd_rounded(jd: JD, step: f64) -> Self {
+        // SS: a millisecond later, so the conversion to the calendar date
+        // does not round the seconds down to 59.999...
+        let rounded = error_budget::round_to_step(jd, step);
+        let mut date_time = Self::from_jd(JD::new(rounded.jd + 0.001 / 86_400.0));
+        let step_in_minute = step.min(60.0);
+        date_time.seconds = (date_time.seconds / step_in_minute).floor() * step_in_minute;
+        date_time
+    }
+
+    /// Date/time for a Julian Day that may not exist, rounded to a step
+    /// In:
+    /// jd: Julian Day
+    /// step: in seconds
+    /// Out: Valid date/time, if there is a Julian Day. Otherwise, is_valid is false
+    pub fn from_option_rounded(jd: Option<JD>, step: f64) -> Self {
+        jd.map_or_else(Self::default, |jd| Self::from_jd_rounded(jd, step))
+    }
+
+    /// Whether an invalid date/time, or a valid one calculated from a finite
+    /// Julian Day
+    fn is_finite(&self) -> bool {
+        !self.is_valid || self.seconds.is_finite()
+    }
+}
+
+/// ISO 8601 date and time in UTC, e.g. 2000-03-20T12:34:56Z, or "-" if invalid
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_valid {
+            return write!(f, "-");
+        }
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year,
+            self.month,
+            self.day,
+            self.hours,
+            self.minutes,
+            self.seconds.trunc() as u8
+        )
+    }
+}
+
+/// Mirrors NativeAccess.MoonOutputData
+#[derive(Debug, Clone, Default, PartialEq, JniStruct)]
+#[jni(
+    class = "com/svenschmidt/kitana/core/NativeAccess$MoonOutputData",
+    write
+)]
+pub struct MoonOutput {
+    /// false if the calculation produced values that are not finite numbers,
+    /// e.g. for invalid input. All other values are then 0
+    pub is_valid: bool,
+
+    pub phase_angle: f64,
+    pub phase_age: f64,
+    pub illuminated_fraction: f64,
+    pub elongation: f64,
+    pub is_waxing: bool,
+    pub phase_desc: &'static str,
+    pub geocentric_longitude: f64,
+    pub geocentric_latitude: f64,
+    pub distance_from_earth: f64,
+
+    /// Distance, in equatorial radii of the Earth
+    pub distance_earth_radii: f64,
+
+    /// Distance, in percent of the mean distance of 385,000.56 km
+    pub distance_percent_of_mean: f64,
+
+    /// Whether the next apsis is a perigee or an apogee
+    pub next_apsis_is_perigee: bool,
+    pub next_apsis_time: DateTime,
+
+    /// Distance at the next apsis, in km
+    pub next_apsis_distance: f64,
+    pub hour_angle: f64,
+    pub right_ascension: f64,
+    pub declination: f64,
+    pub azimuth: f64,
+    pub altitude: f64,
+    pub uncertainty: f64,
+
+    /// Direction of the bright limb, measured counterclockwise from the
+    /// zenith, in degrees [0, 360). The line of cusps is tilted by the same
+    /// angle against the horizon
+    pub crescent_tilt: f64,
+
+    /// Rate of change of the geocentric longitude, in degrees per day.
+    /// Between about 11.8 (slow moon, apogee) and 15.4 (fast moon, perigee)
+    pub longitudinal_velocity: f64,
+    pub rise_time: DateTime,
+    pub set_time: DateTime,
+    pub transit_time: DateTime,
+}
+
+/// One value per line, with units
+impl fmt::Display for MoonOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_valid {
+            return write!(f, "invalid");
+        }
+
+        let waxing = if self.is_waxing { "waxing" } else { "waning" };
+        writeln!(
+            f,
+            "phase:        {} ({}, {waxing})",
+            Degrees::new(self.phase_angle),
+            self.phase_desc
+        )?;
+        writeln!(f, "phase age:    {:.2} d", self.phase_age)?;
+        writeln!(
+            f,
+            "illuminated:  {:.1} %",
+            100.0 * self.illuminated_fraction
+        )?;
+        writeln!(f, "elongation:   {}", Degrees::new(self.elongation))?;
+        writeln!(
+            f,
+            "ecliptical:   lon. {}, lat. {}",
+            Degrees::new(self.geocentric_longitude),
+            Degrees::new(self.geocentric_latitude)
+        )?;
+        writeln!(
+            f,
+            "distance:     {:.0} km, {:.2} earth radii, {:.1} % of mean",
+            self.distance_from_earth, self.distance_earth_radii, self.distance_percent_of_mean
+        )?;
+        let apsis = if self.next_apsis_is_perigee {
+            "next perigee:"
+        } else {
+            "next apogee:"
+        };
+        writeln!(
+            f,
+            "{apsis:<14}{}, {:.0} km",
+            self.next_apsis_time, self.next_apsis_distance
+        )?;
+        writeln!(
+            f,
+            "equatorial:   RA {}, decl. {}, hour angle {}",
+            Degrees::new(self.right_ascension).to_hms_str(1),
+            Degrees::new(self.declination),
+            Degrees::new(self.hour_angle).to_hms_str(1)
+        )?;
+        writeln!(
+            f,
+            "horizontal:   az. {}, alt. {} ± {}",
+            Degrees::new(self.azimuth),
+            Degrees::new(self.altitude),
+            Degrees::new(self.uncertainty)
+        )?;
+        writeln!(f, "tilt:         {}", Degrees::new(self.crescent_tilt))?;
+        writeln!(f, "velocity:     {:.4} deg/day", self.longitudinal_velocity)?;
+        writeln!(f, "rise:         {}", self.rise_time)?;
+        writeln!(f, "set:          {}", self.set_time)?;
+        write!(f, "transit:      {}", self.transit_time)
+    }
+}
+
+impl MoonOutput {
+    /// Whether all values are finite numbers
+    fn is_finite(&self) -> bool {
+        [
+            self.phase_angle,
+            self.phase_age,
+            self.illuminated_fraction,
+            self.elongation,
+            self.geocentric_longitude,
+            self.geocentric_latitude,
+            self.distance_from_earth,
+            self.distance_earth_radii,
+            self.distance_percent_of_mean,
+            self.next_apsis_distance,
+            self.hour_angle,
+            self.right_ascension,
+            self.declination,
+            self.azimuth,
+            self.altitude,
+            self.uncertainty,
+            self.crescent_tilt,
+            self.longitudinal_velocity,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+            && [
+                self.rise_time,
+                self.set_time,
+                self.transit_time,
+                self.next_apsis_time,
+            ]
+            .iter()
+            .all(DateTime::is_finite)
+    }
+}
+
+/// Mirrors NativeAccess.SolunarOutputData
+#[derive(Debug, Clone, Default, PartialEq, JniStruct)]
+#[jni(
+    class = "com/svenschmidt/kitana/core/NativeAccess$SolunarOutputData",
+    write
+)]
+pub struct SolunarOutput {
+    /// false if the calculation produced times that are not finite numbers,
+    /// e.g. for invalid input. All periods are then invalid
+    pub is_valid: bool,
+
+    /// (start, end) of the major periods, written to majorPeriod1Start etc.
+    #[jni(skip)]
+    pub major_periods: [(DateTime, DateTime); 2],
+
+    /// (start, end) of the minor periods, written to minorPeriod1Start etc.
+    #[jni(skip)]
+    pub minor_periods: [(DateTime, DateTime); 2],
+
+    pub day_rating: i16,
+}
+
+impl SolunarOutput {
+    /// Whether all times are finite numbers
+    fn is_finite(&self) -> bool {
+        self.major_periods
+            .iter()
+            .chain(self.minor_periods.iter())
+            .all(|(start, end)| start.is_finite() && end.is_finite())
+    }
+}
+
+/// One period per line
+impl fmt::Display for SolunarOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.is_valid {
+            return write!(f, "invalid");
+        }
+
+        for (name, periods) in [
+            ("major", &self.major_periods),
+            ("minor", &self.minor_periods),
+        ] {
+            for (start, end) in periods {
+                writeln!(f, "{name}:        {start} - {end}")?;
+            }
+        }
+        write!(f, "day rating:   {}", self.day_rating)
+    }
+}
+
+/// Julian Day of a rise, set or transit event
+/// In:
+/// input: the bridge's input
+/// output: result of the rise, set or transit calculation, in dynamical time
+/// Out: Julian Day in UTC if the event happens on the day
+fn event(input: &MoonInput, output: OutputKind) -> Option<JD> {
+    match output {
+        OutputKind::Time(tt) => Some(input.utc(tt)),
+        OutputKind::NeverRises | OutputKind::NeverSets | OutputKind::NeverTransits => None,
+    }
+}
+
+/// Moon's rise, set and transit
+/// In: the bridge's input
+/// Out: (rise, set, transit), in UTC
+fn events(input: &MoonInput) -> (Option<JD>, Option<JD>, Option<JD>) {
+    let tt = input.tt();
+    let longitude_observer = Degrees::new(input.longitude_observer);
+    let latitude_observer = Degrees::new(input.latitude_observer);
+
+    // SS: above a sea horizon, the horizon is below the astronomical
+    // horizon, so the Moon rises earlier and sets later
+    let horizon = if input.sea_horizon {
+        Dip::new(input.height_above_sea_observer)
+    } else {
+        Dip::new(0.0)
+    };
+    let target_altitude = moon::rise_set_transit::target_altitude(
+        tt,
+        Degrees::new(0.0),
+        longitude_observer,
+        latitude_observer,
+        input.pressure,
+        input.temperature,
+    ) - horizon.dip();
+
+    let rise = moon::rise_set_transit::rise(
+        tt,
+        input.utc_offset(),
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+    );
+    let set = moon::rise_set_transit::set(
+        tt,
+        input.utc_offset(),
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+    );
+    let transit = moon::rise_set_transit::transit(
+        tt,
+        input.utc_offset(),
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+    );
+
+    (event(input, rise), event(input, set), event(input, transit))
+}
+
+/// Moon's rise, set and transit
+/// In: the bridge's input
+/// Out: (rise, set, transit), rounded to their uncertainty, see error_budget
+pub fn rise_set_transit(input: &MoonInput) -> (DateTime, DateTime, DateTime) {
+    let (rise, set, transit) = events(input);
+    let rise_set_step = error_budget::event_display_step(EventKind::RiseSet, input.tt());
+    let transit_step = error_budget::event_display_step(EventKind::Transit, input.tt());
+    (
+        DateTime::from_option_rounded(rise, rise_set_step),
+        DateTime::from_option_rounded(set, rise_set_step),
+        DateTime::from_option_rounded(transit, transit_step),
+    )
+}
+
+/// All Moon data shown by the app
+/// In: the bridge's input
+/// Out: the bridge's output, is_valid is false if any value is not a finite
+/// number
+pub fn moon_data(input: &MoonInput) -> MoonOutput {
+    let tt = input.tt();
+    let topocentric = moon::position::topocentric_coordinates(
+        tt,
+        &input.observer(),
+        input.height_above_sea_observer,
+    );
+    let (rise_time, set_time, transit_time) = rise_set_transit(input);
+    let geocentric = moon::geocentric(tt);
+    let phase = moon::phase_info(tt);
+    let distance = moon::MoonDistance::new(geocentric.delta);
+    let apsis = moon::next_apsis(tt);
+
+    // SS: the app's phase angle is the elongation, from 0 at new moon to 360
+    let output = MoonOutput {
+        is_valid: true,
+        phase_angle: phase.elongation.0,
+        phase_age: moon::phase::phase_age(tt),
+        illuminated_fraction: phase.fraction_illuminated,
+        elongation: phase.elongation.map_neg180_to_180().0,
+        is_waxing: phase.is_waxing,
+        phase_desc: moon::phase::phase_description(tt),
+        geocentric_longitude: geocentric.lambda.0,
+        geocentric_latitude: geocentric.beta.0,
+        distance_from_earth: distance.kilometers,
+        distance_earth_radii: distance.earth_radii,
+        distance_percent_of_mean: distance.percent_of_mean,
+        next_apsis_is_perigee: matches!(apsis.kind, moon::ApsisKind::Perigee),
+        next_apsis_time: DateTime::from_jd_rounded(
+            input.utc(apsis.jd),
+            error_budget::event_display_step(EventKind::Apsis, apsis.jd),
+        ),
+        next_apsis_distance: apsis.distance.kilometers,
+        hour_angle: topocentric.hour_angle.0,
+        right_ascension: topocentric.right_ascension.0,
+        declination: topocentric.declination.0,
+        azimuth: topocentric.azimuth.0,
+        altitude: topocentric.apparent_altitude.0,
+        uncertainty: topocentric.uncertainty.0,
+        crescent_tilt: moon::phase::crescent_tilt(tt, &input.observer()).0,
+        longitudinal_velocity: moon::longitudinal_velocity(tt).longitude,
+        rise_time,
+        set_time,
+        transit_time,
+    };
+
+    // SS: NaN or infinite values would show up as "NaN°" in the app
+    if input.is_finite() && output.is_finite() {
+        output
+    } else {
+        MoonOutput::default()
+    }
+}
+
+/// Solunar periods
+/// In: the bridge's input
+/// Out: the bridge's output. Periods that do not exist on the day are invalid.
+/// is_valid is false if any time is not a finite number
+pub fn solunar_data(input: &MoonInput) -> SolunarOutput {
+    let solunar_day = solunar::solunar_day(
+        input.tt(),
+        input.utc_offset(),
+        Degrees::new(input.longitude_observer),
+        Degrees::new(input.latitude_observer),
+        input.pressure,
+        input.temperature,
+    );
+
+    // SS: the periods are derived from rise, set and transit
+    let step = error_budget::event_display_step(EventKind::RiseSet, input.tt());
+    let period = |periods: &[Period], idx: usize| {
+        let period = periods.get(idx);
+        (
+            DateTime::from_option_rounded(period.map(|p| input.utc(p.start)), step),
+            DateTime::from_option_rounded(period.map(|p| input.utc(p.end)), step),
+        )
+    };
+
+    let output = SolunarOutput {
+        is_valid: true,
+        major_periods: [
+            period(&solunar_day.major_periods, 0),
+            period(&solunar_day.major_periods, 1),
+        ],
+        minor_periods: [
+            period(&solunar_day.minor_periods, 0),
+            period(&solunar_day.minor_periods, 1),
+        ],
+        day_rating: solunar_day.rating as i16,
+    };
+
+    if input.is_finite() && output.is_finite() {
+        output
+    } else {
+        SolunarOutput::default()
+    }
+}
+
+/// The values a home screen widget shows
+/// In: the bridge's input
+/// Out: the widget's data, rise, set and transit in UTC. is_valid is false if
+/// any value is not a finite number
+pub fn widget_data(input: &MoonInput) -> WidgetData {
+    let tt = input.tt();
+    let topocentric = moon::position::topocentric_coordinates(
+        tt,
+        &input.observer(),
+        input.height_above_sea_observer,
+    );
+    let (rise, set, transit) = events(input);
+
+    // SS: Unix milliseconds cannot hold NaN, so check the Julian Days
+    let times_finite = [Some(JD::new(input.jd)), rise, set, transit]
+        .iter()
+        .flatten()
+        .all(|jd| jd.jd.is_finite());
+
+    let phase = moon::phase_info(tt);
+
+    // SS: the widget's phase angle is the elongation, from 0 at new moon to 360
+    let output = WidgetData {
+        is_valid: true,
+        phase_angle: phase.elongation.0 as f32,
+        illuminated_fraction: phase.fraction_illuminated as f32,
+        phase_age: moon::phase::phase_age(tt) as f32,
+        is_waxing: phase.is_waxing,
+        altitude: topocentric.apparent_altitude.0 as f32,
+        azimuth: topocentric.azimuth.0 as f32,
+        distance: moon::position::distance_from_earth(tt) as f32,
+        unix_millis: JD::new(input.jd).to_unix_millis(),
+        rise: rise.map(JD::to_unix_millis),
+        set: set.map(JD::to_unix_millis),
+        transit: transit.map(JD::to_unix_millis),
+    };
+
+    if input.is_finite() && times_finite && output.is_finite() {
+        output
+    } else {
+        WidgetData::default()
+    }
+}
+
+/// Format angles as degrees, minutes and seconds in one call, so a table
+/// needs a single call across JNI per refresh
+/// In:
+/// degrees: angles, in degrees
+/// width: decimals of the seconds
+/// Out: one string per angle, e.g. 13° 46' 10.766"
+pub fn to_dms_batch(degrees: &[f64], width: u8) -> Vec<String> {
+    degrees
+        .iter()
+        .map(|&d| Degrees::new(d).to_dms_str(width))
+        .collect()
+}
+
+/// Format angles as hours, minutes and seconds in one call
+/// In:
+/// degrees: angles, in degrees
+/// width: decimals of the seconds
+/// Out: one string per angle, e.g. 16h 6m 46.994s
+pub fn to_hms_batch(degrees: &[f64], width: u8) -> Vec<String> {
+    degrees
+        .iter()
+        .map(|&d| Degrees::new(d).to_hms_str(width))
+        .collect()
+}
+
+/// Describe the tables and the configuration compiled into the library, so
+/// bug reports tell which delta t and leap second tables the installed
+/// library contains
+/// Out: JSON object, e.g.
+/// {"crate_version":"0.1.0","delta_t":{"first":"1657-01-01",...},...}
+pub fn data_info() -> String {
+    let (delta_t_first, delta_t_last) = time::delta_t_table_range();
+    let leap_seconds = LeapSeconds::iers();
+    let (leap_seconds_first, leap_seconds_last) = leap_seconds.range();
+    let tai_minus_utc = leap_seconds
+        .entries()
+        .last()
+        .map_or(0.0, |entry| entry.tai_minus_utc);
+
+    let features = [
+        ("sun-vsop87", cfg!(feature = "sun-vsop87")),
+        ("high-precision-moon", cfg!(feature = "high-precision-moon")),
+        ("deterministic", cfg!(feature = "deterministic")),
+        ("blob-tables", cfg!(feature = "blob-tables")),
+        ("tz", cfg!(feature = "tz")),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| format!("\"{feature}\""))
+    .collect::<Vec<_>>()
+    .join(",");
+
+    // SS: the bridge calls the library with its defaults
+    let config = MoonlibConfig::default();
+
+    format!(
+        concat!(
+            "{{\"crate_version\":\"{}\",",
+            "\"delta_t\":{{\"first\":\"{}\",\"last\":\"{}\",\"entries\":{}}},",
+            "\"leap_seconds\":{{\"first\":\"{}\",\"last\":\"{}\",\"entries\":{},\"tai_minus_utc\":{}}},",
+            "\"precision\":{{\"config\":\"{}\",\"features\":[{}]}}}}"
+        ),
+        env!("CARGO_PKG_VERSION"),
+        iso_date(delta_t_first),
+        iso_date(delta_t_last),
+        time::delta_t_entries().len(),
+        iso_date(leap_seconds_first),
+        iso_date(leap_seconds_last),
+        leap_seconds.entries().len(),
+        tai_minus_utc,
+        config,
+        features,
+    )
+}
+
+/// Format a date as YYYY-MM-DD, without the time of day
+fn iso_date(date: Date) -> String {
+    format!("{:04}-{:02}-{:02}", date.year, date.month, date.day as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::rise_set_transit::target_altitude;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn munich(jd: JD, timezone_offset: i8) -> MoonInput {
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        MoonInput {
+            jd: jd.jd,
+            timezone_offset,
+            timezone: None,
+            longitude_observer: -11.6,
+            latitude_observer: 48.1,
+            height_above_sea_observer: 0.0,
+            pressure: 1013.0,
+            temperature: 10.0,
+            sea_horizon: false,
+        }
+    }
+
+    fn jd(date_time: DateTime) -> JD {
+        assert!(date_time.is_valid);
+        JD::from_date(Date::from_date_hms(
+            date_time.year,
+            date_time.month as u8,
+            date_time.day as u8,
+            date_time.hours as u8,
+            date_time.minutes as u8,
+            date_time.seconds,
+        ))
+    }
+
+    /// Expected event, calculated without the bridge
+    fn expected(
+        input: &MoonInput,
+        f: fn(JD, i8, Degrees, Degrees, Degrees) -> OutputKind,
+    ) -> OutputKind {
+        let tt = time::utc_2_tt(JD::new(input.jd));
+        let longitude = Degrees::new(input.longitude_observer);
+        let latitude = Degrees::new(input.latitude_observer);
+        let altitude = target_altitude(
+            tt,
+            Degrees::new(0.0),
+            longitude,
+            latitude,
+            input.pressure,
+            input.temperature,
+        );
+        f(tt, input.utc_offset(), altitude, longitude, latitude)
+    }
+
+    fn time(output: OutputKind) -> JD {
+        match output {
+            OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn date_time_test() {
+        // Arrange
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 23, 16, 48, 32.7));
+
+        // Act
+        let date_time = DateTime::from_jd(jd);
+
+        // Assert
+        assert!(date_time.is_valid);
+        assert_eq!(2000, date_time.year);
+        assert_eq!(3, date_time.month);
+        assert_eq!(23, date_time.day);
+        assert_eq!(16, date_time.hours);
+        assert_eq!(48, date_time.minutes);
+        assert_approx_eq!(32.7, date_time.seconds, 0.001);
+    }
+
+    #[test]
+    fn invalid_date_time_test() {
+        // Act
+        let date_time = DateTime::from_option(None);
+
+        // Assert
+        assert!(!date_time.is_valid);
+    }
+
+    #[test]
+    fn date_time_display_test() {
+        // Arrange
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 23, 16, 48, 32.7));
+
+        // Act
+        let valid = DateTime::from_jd(jd).to_string();
+        let invalid = DateTime::from_option(None).to_string();
+
+        // Assert
+        assert_eq!("2000-03-23T16:48:32Z", valid);
+        assert_eq!("-", invalid);
+    }
+
+    #[test]
+    fn never_event_invalid_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+
+        // Act
+        let outputs = [
+            OutputKind::NeverRises,
+            OutputKind::NeverSets,
+            OutputKind::NeverTransits,
+        ]
+        .map(|output| event(&input, output));
+
+        // Assert
+        assert!(outputs.iter().all(|output| output.is_none()));
+    }
+
+    #[test]
+    fn rise_set_transit_slots_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+
+        // Act
+        let (rise, set, transit) = rise_set_transit(&input);
+
+        // Assert
+
+        // SS: each event ends up in its own slot, in UTC, rounded to the
+        // minute for rise and set, and to 10 seconds for the transit
+        let tt_offset = time::utc_2_tt(JD::new(input.jd)).jd - input.jd;
+        let expected_rise = time(expected(&input, moon::rise_set_transit::rise));
+        let expected_set = time(expected(&input, moon::rise_set_transit::set));
+        let expected_transit = time(expected(&input, moon::rise_set_transit::transit));
+        let minute = 30.5 / 86_400.0;
+        let ten_seconds = 5.5 / 86_400.0;
+        assert_approx_eq!(expected_rise.jd - tt_offset, jd(rise).jd, minute);
+        assert_approx_eq!(expected_set.jd - tt_offset, jd(set).jd, minute);
+        assert_approx_eq!(expected_transit.jd - tt_offset, jd(transit).jd, ten_seconds);
+    }
+
+    #[test]
+    fn rise_set_transit_rounded_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+
+        // Act
+        let (rise, set, transit) = rise_set_transit(&input);
+
+        // Assert
+
+        // SS: no seconds that imply more precision than there is
+        assert_eq!(0.0, rise.seconds);
+        assert_eq!(0.0, set.seconds);
+        assert_eq!(0.0, transit.seconds % 10.0);
+    }
+
+    #[test]
+    fn from_jd_rounded_test() {
+        // Arrange
+
+        // SS: 2000 March 23, 23:59:45 UTC
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 23, 23, 59, 45.0));
+
+        // Act
+        let minute = DateTime::from_jd_rounded(jd, 60.0);
+        let ten_seconds = DateTime::from_jd_rounded(jd, 10.0);
+
+        // Assert
+
+        // SS: rounding up carries into the next day
+        assert_eq!("2000-03-24T00:00:00Z", minute.to_string());
+        assert_eq!(0.0, minute.seconds);
+        assert_eq!("2000-03-23T23:59:50Z", ten_seconds.to_string());
+        assert_eq!(50.0, ten_seconds.seconds);
+    }
+
+    #[test]
+    fn rise_set_transit_utc_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+
+        // Act
+        let (rise, _, _) = events(&input);
+
+        // Assert
+
+        // SS: the rise time is reported in UTC, not in dynamical time
+        let tt = time(expected(&input, moon::rise_set_transit::rise));
+        let tt_offset = (time::utc_2_tt(JD::new(input.jd)).jd - input.jd) * 86400.0;
+        assert_approx_eq!(tt_offset, (tt.jd - rise.unwrap().jd) * 86400.0, 0.01);
+        assert!(tt_offset > 60.0);
+    }
+
+    #[test]
+    fn no_transit_test() {
+        // Arrange
+
+        // SS: no transit on 2000 March 20 in Munich
+        let input = munich(JD::from_date(Date::new(2000, 3, 20.0)), 0);
+
+        // Act
+        let (rise, set, transit) = rise_set_transit(&input);
+
+        // Assert
+        assert!(rise.is_valid);
+        assert!(set.is_valid);
+        assert!(!transit.is_valid);
+    }
+
+    #[test]
+    fn circumpolar_test() {
+        // Arrange
+
+        // SS: Tromso, the Moon stays below the horizon on that day
+        let input = MoonInput {
+            jd: JD::from_date(Date::new(2000, 7, 15.0)).jd,
+            timezone_offset: 0,
+            timezone: None,
+            longitude_observer: -18.96,
+            latitude_observer: 69.65,
+            height_above_sea_observer: 0.0,
+            pressure: 1013.0,
+            temperature: 10.0,
+            sea_horizon: false,
+        };
+
+        // Act
+        let (rise, set, transit) = rise_set_transit(&input);
+
+        // Assert
+        assert!(!rise.is_valid);
+        assert!(!set.is_valid);
+        assert!(transit.is_valid);
+    }
+
+    #[test]
+    fn timezone_offset_test() {
+        // Arrange
+
+        // SS: the same calendar day in UTC and 10 hours ahead of UTC
+        let utc = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        let ahead = munich(JD::from_date(Date::new(2000, 3, 23.0)), 10);
+
+        // Act
+        let (_, _, transit_utc) = rise_set_transit(&utc);
+        let (_, _, transit_ahead) = rise_set_transit(&ahead);
+
+        // Assert
+
+        // SS: each event is on the input's day in the observer's time zone
+        for (input, transit) in [(utc, transit_utc), (ahead, transit_ahead)] {
+            let mut local = jd(transit);
+            local.add_hours(input.timezone_offset as f64);
+            assert_eq!(23, local.to_calendar_date().day.trunc() as i16);
+        }
+    }
+
+    #[cfg(feature = "tz")]
+    fn denver(timezone_offset: i8, timezone: Option<&str>) -> MoonInput {
+        // SS: Boulder, CO, on a day with daylight saving time
+        MoonInput {
+            jd: JD::from_date(Date::new(2022, 7, 15.5)).jd,
+            timezone_offset,
+            timezone: timezone.map(str::to_string),
+            longitude_observer: 105.2151,
+            latitude_observer: 40.05972,
+            height_above_sea_observer: 1624.0,
+            pressure: 1013.0,
+            temperature: 10.0,
+            sea_horizon: false,
+        }
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn named_timezone_test() {
+        // Arrange
+
+        // SS: the standard time offset is wrong in July, the zone's is not
+        let named = denver(-7, Some("America/Denver"));
+        let daylight_saving_time = denver(-6, None);
+
+        // Act
+        let events = rise_set_transit(&named);
+        let expected = rise_set_transit(&daylight_saving_time);
+
+        // Assert
+        assert_eq!(-6, named.utc_offset());
+        assert_eq!(expected, events);
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn unknown_timezone_test() {
+        // Arrange
+        let input = denver(-7, Some("America/Boulder"));
+
+        // Act
+        let utc_offset = input.utc_offset();
+
+        // Assert
+        assert_eq!(-7, utc_offset);
+    }
+
+    #[test]
+    fn elevated_observer_events_test() {
+        // Arrange
+        let sea_level = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        let mut mountain = sea_level.clone();
+        mountain.height_above_sea_observer = 2962.0;
+        mountain.sea_horizon = true;
+
+        // Act
+        let (rise, set, transit) = events(&mountain);
+
+        // Assert
+
+        // SS: from 2962m above a sea horizon, the horizon is 1.6 degrees below
+        // the astronomical horizon, which moves rise and set by several minutes
+        let (expected_rise, expected_set, expected_transit) = events(&sea_level);
+        let minutes = |a: Option<JD>, b: Option<JD>| (a.unwrap().jd - b.unwrap().jd) * 24.0 * 60.0;
+        assert!(minutes(rise, expected_rise) < -5.0);
+        assert!(minutes(set, expected_set) > 5.0);
+        assert_eq!(expected_transit, transit);
+    }
+
+    #[test]
+    fn inland_observer_events_test() {
+        // Arrange
+
+        // SS: Munich is 520m above the sea, but surrounded by terrain at about
+        // the same height
+        let sea_level = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        let mut inland = sea_level.clone();
+        inland.height_above_sea_observer = 520.0;
+
+        // Act
+        let events_inland = events(&inland);
+
+        // Assert
+        assert_eq!(events(&sea_level), events_inland);
+        assert!(!MoonInput::default().sea_horizon);
+    }
+
+    #[test]
+    fn default_input_test() {
+        // Arrange
+
+        // SS: an app that does not pass the location
+        let input = MoonInput {
+            jd: JD::from_date(Date::new(2000, 3, 23.0)).jd,
+            ..MoonInput::default()
+        };
+
+        // Act
+        let moon = moon_data(&input);
+        let widget = widget_data(&input);
+        let solunar = solunar_data(&input);
+
+        // Assert
+        assert!(!moon.is_valid);
+        assert!(!widget.is_valid);
+        assert!(!solunar.is_valid);
+
+        // SS: without the height, the observer is at sea level
+        let input = MoonInput {
+            height_above_sea_observer: MoonInput::default().height_above_sea_observer,
+            ..munich(JD::from_date(Date::new(2000, 3, 23.0)), 0)
+        };
+        assert!(moon_data(&input).is_valid);
+    }
+
+    #[test]
+    fn moon_data_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+
+        // Act
+        let output = moon_data(&input);
+
+        // Assert
+        let tt = time::utc_2_tt(JD::new(input.jd));
+        let (rise, set, transit) = rise_set_transit(&input);
+        assert_eq!(
+            moon::phase::fraction_illuminated(tt),
+            output.illuminated_fraction
+        );
+        assert_eq!(moon::is_waxing(tt), output.is_waxing);
+        assert_eq!(rise, output.rise_time);
+        assert_eq!(set, output.set_time);
+        assert_eq!(transit, output.transit_time);
+
+        // SS: apogee on 2000 March 27
+        assert_approx_eq!(
+            output.distance_from_earth / 6378.14,
+            output.distance_earth_radii,
+            1e-9
+        );
+        assert_approx_eq!(
+            output.distance_from_earth / 3850.0056,
+            output.distance_percent_of_mean,
+            1e-9
+        );
+        assert!(!output.next_apsis_is_perigee);
+        assert_eq!((2000, 3, 27), {
+            let t = output.next_apsis_time;
+            (t.year, t.month, t.day)
+        });
+        assert!(output.next_apsis_distance > output.distance_from_earth);
+    }
+
+    #[test]
+    fn moon_data_display_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        let output = moon_data(&input);
+
+        // Act
+        let s = output.to_string();
+
+        // Assert
+        assert_eq!(14, s.lines().count());
+        assert!(s.contains(&format!("{:.0} km", output.distance_from_earth)));
+        assert!(s.contains(&format!("rise:         {}", output.rise_time)));
+        assert!(s.contains(&Degrees::new(output.azimuth).to_string()));
+    }
+
+    #[test]
+    fn solunar_data_test() {
+        // Arrange
+
+        // SS: no transit on 2000 March 20 in Munich, only the lower transit
+        let input = munich(JD::from_date(Date::new(2000, 3, 20.0)), 0);
+
+        // Act
+        let output = solunar_data(&input);
+
+        // Assert
+        assert!(output.major_periods[0].0.is_valid);
+        assert!(output.major_periods[0].1.is_valid);
+        assert!(!output.major_periods[1].0.is_valid);
+        assert!(!output.major_periods[1].1.is_valid);
+        assert!(output
+            .minor_periods
+            .iter()
+            .all(|(start, end)| start.is_valid && end.is_valid));
+        assert!(jd(output.minor_periods[0].0) < jd(output.minor_periods[1].0));
+    }
+
+    #[test]
+    fn widget_data_test() {
+        // Arrange
+
+        // SS: no transit on 2000 March 20 in Munich
+        let input = munich(JD::from_date(Date::new(2000, 3, 20.0)), 0);
+
+        // Act
+        let widget_data = WidgetData::decode(&widget_data(&input).encode()).unwrap();
+
+        // Assert
+        let output = moon_data(&input);
+        assert_approx_eq!(output.phase_angle, widget_data.phase_angle as f64, 0.000_1);
+        assert_approx_eq!(output.altitude, widget_data.altitude as f64, 0.000_1);
+        assert_approx_eq!(output.distance_from_earth, widget_data.distance as f64, 0.1);
+        assert_eq!(output.is_waxing, widget_data.is_waxing);
+
+        // SS: same rise as the date/time, which is rounded to the minute
+        let rise = JD::from_unix_millis(widget_data.rise.unwrap());
+        assert_approx_eq!(jd(output.rise_time).jd, rise.jd, 30.5 / 86_400.0);
+        assert!(widget_data.set.is_some());
+        assert_eq!(None, widget_data.transit);
+    }
+
+    #[test]
+    fn valid_output_test() {
+        // Arrange
+        let input = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+
+        // Act
+        let moon_data = moon_data(&input);
+        let solunar_data = solunar_data(&input);
+        let widget_data = widget_data(&input);
+
+        // Assert
+        assert!(moon_data.is_valid);
+        assert!(solunar_data.is_valid);
+        assert!(widget_data.is_valid);
+    }
+
+    #[test]
+    fn not_finite_output_test() {
+        // Arrange
+        let mut nan_latitude = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        nan_latitude.latitude_observer = f64::NAN;
+
+        let mut infinite_height = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        infinite_height.height_above_sea_observer = f64::INFINITY;
+
+        let mut nan_jd = munich(JD::from_date(Date::new(2000, 3, 23.0)), 0);
+        nan_jd.jd = f64::NAN;
+
+        for input in [nan_latitude, infinite_height, nan_jd] {
+            // Act
+            let moon_data = moon_data(&input);
+            let solunar_data = solunar_data(&input);
+            let widget_data = widget_data(&input);
+
+            // Assert
+
+            // SS: all values replaced, so no NaN reaches the app
+            assert_eq!(MoonOutput::default(), moon_data);
+            assert!(!moon_data.is_valid);
+            assert_eq!(SolunarOutput::default(), solunar_data);
+            assert!(!solunar_data.is_valid);
+            assert_eq!(WidgetData::default(), widget_data);
+            assert!(!widget_data.is_valid);
+        }
+    }
+
+    #[test]
+    fn to_dms_batch_test() {
+        // Arrange
+        let degrees = [13.769657226951539, -5.5, 0.0];
+
+        // Act
+        let dms = to_dms_batch(&degrees, 3);
+
+        // Assert
+        assert_eq!(
+            vec!["13° 46' 10.766\"", "-5° 30' 0.000\"", "0° 0' 0.000\""],
+            dms
+        );
+        assert!(to_dms_batch(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn to_hms_batch_test() {
+        // Arrange
+        let degrees = [241.6958092513155, 15.0];
+
+        // Act
+        let hms = to_hms_batch(&degrees, 3);
+
+        // Assert
+        assert_eq!(vec!["16h 6m 46.994s", "1h 0m 0.000s"], hms);
+    }
+
+    #[test]
+    fn data_info_test() {
+        // Act
+        let info = data_info();
+
+        // Assert
+        let (_, delta_t_last) = time::delta_t_table_range();
+        assert!(info.starts_with(&format!(
+            "{{\"crate_version\":\"{}\",",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(info.contains(&format!("\"last\":\"{}\"", iso_date(delta_t_last))));
+        assert!(info.contains(&format!("\"entries\":{}", time::delta_t_entries().len())));
+
+        // SS: the last leap second was introduced on 2017 January 1
+        assert!(info.contains("\"last\":\"2017-01-01\""));
+        assert!(info.contains("\"tai_minus_utc\":37"));
+        assert!(info.contains(&format!("\"config\":\"{}\"", MoonlibConfig::default())));
+
+        // SS: balanced, for a JSON parser on the Kotlin side
+        assert_eq!(info.matches('{').count(), info.matches('}').count());
+        assert_eq!(info.matches('[').count(), info.matches(']').count());
+        assert_eq!(0, info.matches('"').count() % 2);
+    }
+}
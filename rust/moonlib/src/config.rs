@@ -0,0 +1,528 @@
+//! Accuracy and conventions used for the calculations, chosen once and
+//! passed to a Session instead of to every function. The configuration has
+//! a compact string form, so the app can persist the user's choice, e.g.
+//!
+//! precision=high;refraction=observer;nutation=full;delta_t=table;longitude=west;refine=off;limb=upper
+//!
+//! Keys missing from the string keep their default value.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::date::jd::JD;
+use crate::nutation;
+use crate::session::{Observer, Precision};
+pub use crate::time::DeltaT;
+pub use crate::util::angle_unit::AngleUnit;
+use crate::util::arcsec::ArcSec;
+
+/// Standard atmosphere refraction tables are based on, Meeus, chapter 16
+const STANDARD_PRESSURE: f64 = 1010.0;
+const STANDARD_TEMPERATURE: f64 = 10.0;
+
+/// Atmospheric refraction applied to altitudes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RefractionModel {
+    /// Geometric altitudes, without refraction. Rise and set are then the
+    /// times the body's limb reaches the geometric horizon
+    None,
+
+    /// Refraction for 1010 millibars and 10 celsius
+    Standard,
+
+    /// Refraction for the observer's pressure and temperature
+    Observer,
+}
+
+impl RefractionModel {
+    /// Atmospheric conditions to calculate the refraction for
+    /// In: Observer
+    /// Out: pressure, in millibars, and temperature, in celsius
+    pub(crate) fn atmosphere(self, observer: &Observer) -> (f64, f64) {
+        match self {
+            // SS: a pressure of 0 disables the refraction, see the
+            // refraction module
+            RefractionModel::None => (0.0, STANDARD_TEMPERATURE),
+            RefractionModel::Standard => (STANDARD_PRESSURE, STANDARD_TEMPERATURE),
+            RefractionModel::Observer => (observer.pressure, observer.temperature),
+        }
+    }
+}
+
+/// Nutation used for the Sun's apparent position. The Moon's position is
+/// dominated by its own series and always uses the full nutation series.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NutationModel {
+    /// All terms of the IAU 1980 series, Meeus chapter 22
+    Full,
+
+    /// The four largest terms, accurate to 0.5", Meeus chapter 22, page 144
+    Low,
+}
+
+impl NutationModel {
+    /// In: Julian day in dynamical time
+    /// Out: nutation in longitude and in obliquity, in arcsec
+    pub(crate) fn nutation(self, jd: JD) -> (ArcSec, ArcSec) {
+        match self {
+            NutationModel::Full => (
+                nutation::nutation_in_longitude(jd),
+                nutation::nutation_in_obliquity(jd),
+            ),
+            NutationModel::Low => nutation::nutation_low_accuracy(jd),
+        }
+    }
+}
+
+/// Sign convention of longitudes passed in by the caller. Internally, the
+/// library follows Meeus and counts longitudes positive west of Greenwich.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LongitudeConvention {
+    /// Positive west of Greenwich, as in Meeus
+    PositiveWest,
+
+    /// Positive east of Greenwich, as reported by GPS
+    PositiveEast,
+}
+
+impl LongitudeConvention {
+    /// In: longitude in this convention, in degrees [-180, 180)
+    /// Out: longitude positive west of Greenwich, in degrees [-180, 180)
+    pub fn to_positive_west(self, longitude: f64) -> f64 {
+        match self {
+            LongitudeConvention::PositiveWest => longitude,
+            LongitudeConvention::PositiveEast => -longitude,
+        }
+    }
+}
+
+/// Point of the Moon's disk that rises and sets. Almanacs and apps differ
+/// here, by up to a few minutes, so results can be compared with theirs
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LimbConvention {
+    /// The upper limb touches the apparent horizon, as in Meeus, chapter 15,
+    /// and the USNO
+    UpperLimb,
+
+    /// The center is on the geometric horizon, i.e. without refraction,
+    /// whatever the refraction model
+    CenterGeometric,
+
+    /// The center is on the apparent horizon, i.e. at an apparent altitude of
+    /// 0 degrees
+    CenterRefracted,
+}
+
+impl LimbConvention {
+    /// Depression of the Moon's topocentric center below the geometric
+    /// horizon at rise and set
+    /// In:
+    /// refraction: refraction at the horizon
+    /// semidiameter: Moon's topocentric semidiameter
+    /// Out: depression
+    pub(crate) fn depression(self, refraction: ArcSec, semidiameter: ArcSec) -> ArcSec {
+        match self {
+            LimbConvention::UpperLimb => refraction + semidiameter,
+            LimbConvention::CenterGeometric => ArcSec::new(0.0),
+            LimbConvention::CenterRefracted => refraction,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonlibConfig {
+    pub precision: Precision,
+    pub refraction: RefractionModel,
+    pub nutation: NutationModel,
+    pub delta_t: DeltaT,
+    pub longitude_convention: LongitudeConvention,
+
+    /// Refine the Moon's rise and set times from a few seconds to below a
+    /// second, at the cost of two more positions of the Moon per event
+    pub refine_events: bool,
+
+    /// Point of the Moon's disk the rise and set times are for
+    pub limb: LimbConvention,
+}
+
+impl Default for MoonlibConfig {
+    fn default() -> Self {
+        Self {
+            precision: Precision::High,
+            refraction: RefractionModel::Observer,
+            nutation: NutationModel::Full,
+            delta_t: DeltaT::Table,
+            longitude_convention: LongitudeConvention::PositiveWest,
+            refine_events: false,
+            limb: LimbConvention::UpperLimb,
+        }
+    }
+}
+
+impl MoonlibConfig {
+    /// Highest accuracy, with longitudes positive west of Greenwich
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_refraction(mut self, refraction: RefractionModel) -> Self {
+        self.refraction = refraction;
+        self
+    }
+
+    pub fn with_nutation(mut self, nutation: NutationModel) -> Self {
+        self.nutation = nutation;
+        self
+    }
+
+    pub fn with_delta_t(mut self, delta_t: DeltaT) -> Self {
+        self.delta_t = delta_t;
+        self
+    }
+
+    pub fn with_longitude_convention(mut self, longitude_convention: LongitudeConvention) -> Self {
+        self.longitude_convention = longitude_convention;
+        self
+    }
+
+    pub fn with_refine_events(mut self, refine_events: bool) -> Self {
+        self.refine_events = refine_events;
+        self
+    }
+
+    pub fn with_limb(mut self, limb: LimbConvention) -> Self {
+        self.limb = limb;
+        self
+    }
+
+    /// Create an observer, with the longitude in the configured convention
+    /// In:
+    /// longitude: in degrees [-180, 180)
+    /// latitude: in degrees [-90, 90)
+    /// timezone_offset: Observer's time zone offset, in hours
+    /// pressure: Atmospheric pressure, in millibars
+    /// temperature: Air temperature, in celsius
+    pub fn observer(
+        &self,
+        longitude: f64,
+        latitude: f64,
+        timezone_offset: i8,
+        pressure: f64,
+        temperature: f64,
+    ) -> Observer {
+        Observer::new(
+            self.longitude_convention.to_positive_west(longitude),
+            latitude,
+            timezone_offset,
+            pressure,
+            temperature,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// An entry is not of the form key=value
+    MalformedEntry(String),
+
+    /// The key is not a configuration setting
+    UnknownKey(String),
+
+    /// The value is not valid for the key
+    InvalidValue(String, String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::MalformedEntry(entry) => write!(f, "malformed entry '{entry}'"),
+            ConfigError::UnknownKey(key) => write!(f, "unknown key '{key}'"),
+            ConfigError::InvalidValue(key, value) => {
+                write!(f, "invalid value '{value}' for key '{key}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl fmt::Display for MoonlibConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let precision = match self.precision {
+            Precision::Low => "low",
+            Precision::Standard => "standard",
+            Precision::High => "high",
+        };
+        let refraction = match self.refraction {
+            RefractionModel::None => "none",
+            RefractionModel::Standard => "standard",
+            RefractionModel::Observer => "observer",
+        };
+        let nutation = match self.nutation {
+            NutationModel::Full => "full",
+            NutationModel::Low => "low",
+        };
+        let longitude = match self.longitude_convention {
+            LongitudeConvention::PositiveWest => "west",
+            LongitudeConvention::PositiveEast => "east",
+        };
+        let refine = if self.refine_events { "on" } else { "off" };
+        let limb = match self.limb {
+            LimbConvention::UpperLimb => "upper",
+            LimbConvention::CenterGeometric => "center",
+            LimbConvention::CenterRefracted => "center_refracted",
+        };
+
+        write!(
+            f,
+            "precision={precision};refraction={refraction};nutation={nutation};delta_t="
+        )?;
+        match self.delta_t {
+            DeltaT::Table => write!(f, "table")?,
+            DeltaT::Polynomial => write!(f, "polynomial")?,
+            DeltaT::Stephenson2016 => write!(f, "stephenson2016")?,
+
+            // SS: f64 is displayed with as many digits as needed to parse it
+            // back exactly
+            DeltaT::Fixed(seconds) => write!(f, "{seconds}")?,
+        }
+        write!(f, ";longitude={longitude};refine={refine};limb={limb}")
+    }
+}
+
+impl FromStr for MoonlibConfig {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = MoonlibConfig::default();
+
+        for entry in s
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| ConfigError::MalformedEntry(entry.to_string()))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || ConfigError::InvalidValue(key.to_string(), value.to_string());
+
+            match key {
+                "precision" => {
+                    config.precision = match value {
+                        "low" => Precision::Low,
+                        "standard" => Precision::Standard,
+                        "high" => Precision::High,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "refraction" => {
+                    config.refraction = match value {
+                        "none" => RefractionModel::None,
+                        "standard" => RefractionModel::Standard,
+                        "observer" => RefractionModel::Observer,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "nutation" => {
+                    config.nutation = match value {
+                        "full" => NutationModel::Full,
+                        "low" => NutationModel::Low,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "delta_t" => {
+                    config.delta_t = match value {
+                        "table" => DeltaT::Table,
+                        "polynomial" => DeltaT::Polynomial,
+                        "stephenson2016" => DeltaT::Stephenson2016,
+                        _ => match value.parse::<f64>() {
+                            Ok(seconds) if seconds.is_finite() => DeltaT::Fixed(seconds),
+                            _ => return Err(invalid()),
+                        },
+                    }
+                }
+                "longitude" => {
+                    config.longitude_convention = match value {
+                        "west" => LongitudeConvention::PositiveWest,
+                        "east" => LongitudeConvention::PositiveEast,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "refine" => {
+                    config.refine_events = match value {
+                        "on" => true,
+                        "off" => false,
+                        _ => return Err(invalid()),
+                    }
+                }
+                "limb" => {
+                    config.limb = match value {
+                        "upper" => LimbConvention::UpperLimb,
+                        "center" => LimbConvention::CenterGeometric,
+                        "center_refracted" => LimbConvention::CenterRefracted,
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => return Err(ConfigError::UnknownKey(key.to_string())),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn default_test() {
+        // Act
+        let config = MoonlibConfig::new();
+
+        // Assert
+        assert_eq!(Precision::High, config.precision);
+        assert_eq!(RefractionModel::Observer, config.refraction);
+        assert_eq!(NutationModel::Full, config.nutation);
+        assert_eq!(DeltaT::Table, config.delta_t);
+        assert_eq!(
+            LongitudeConvention::PositiveWest,
+            config.longitude_convention
+        );
+        assert!(!config.refine_events);
+        assert_eq!(LimbConvention::UpperLimb, config.limb);
+    }
+
+    #[test]
+    fn to_string_test() {
+        // Arrange
+        let config = MoonlibConfig::new()
+            .with_precision(Precision::Standard)
+            .with_nutation(NutationModel::Low)
+            .with_delta_t(DeltaT::Fixed(69.184));
+
+        // Act
+        let s = config.to_string();
+
+        // Assert
+        assert_eq!(
+            "precision=standard;refraction=observer;nutation=low;delta_t=69.184;longitude=west;refine=off;limb=upper",
+            s
+        );
+    }
+
+    #[test]
+    fn round_trip_test() {
+        // Arrange
+        let configs = [
+            MoonlibConfig::new(),
+            MoonlibConfig::new()
+                .with_precision(Precision::Low)
+                .with_refraction(RefractionModel::None)
+                .with_delta_t(DeltaT::Polynomial)
+                .with_longitude_convention(LongitudeConvention::PositiveEast),
+            MoonlibConfig::new()
+                .with_refraction(RefractionModel::Standard)
+                .with_nutation(NutationModel::Low)
+                .with_delta_t(DeltaT::Fixed(1.0 / 3.0)),
+            MoonlibConfig::new().with_delta_t(DeltaT::Stephenson2016),
+            MoonlibConfig::new().with_refine_events(true),
+            MoonlibConfig::new().with_limb(LimbConvention::CenterGeometric),
+            MoonlibConfig::new().with_limb(LimbConvention::CenterRefracted),
+        ];
+
+        for config in configs {
+            // Act
+            let parsed = config.to_string().parse::<MoonlibConfig>();
+
+            // Assert
+            assert_eq!(Ok(config), parsed);
+        }
+    }
+
+    #[test]
+    fn from_str_missing_keys_test() {
+        // Act
+        let config = " longitude = east ; ".parse::<MoonlibConfig>().unwrap();
+
+        // Assert
+        assert_eq!(
+            MoonlibConfig::new().with_longitude_convention(LongitudeConvention::PositiveEast),
+            config
+        );
+        assert_eq!(Ok(MoonlibConfig::new()), "".parse::<MoonlibConfig>());
+    }
+
+    #[test]
+    fn from_str_error_test() {
+        // Act
+        let malformed = "precision".parse::<MoonlibConfig>();
+        let unknown = "accuracy=high".parse::<MoonlibConfig>();
+        let invalid = "precision=best".parse::<MoonlibConfig>();
+        let not_finite = "delta_t=NaN".parse::<MoonlibConfig>();
+
+        // Assert
+        assert_eq!(
+            Err(ConfigError::MalformedEntry("precision".to_string())),
+            malformed
+        );
+        assert_eq!(
+            Err(ConfigError::UnknownKey("accuracy".to_string())),
+            unknown
+        );
+        assert_eq!(
+            Err(ConfigError::InvalidValue(
+                "precision".to_string(),
+                "best".to_string()
+            )),
+            invalid
+        );
+        assert_eq!(
+            Err(ConfigError::InvalidValue(
+                "delta_t".to_string(),
+                "NaN".to_string()
+            )),
+            not_finite
+        );
+    }
+
+    #[test]
+    fn observer_test() {
+        // Arrange
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let config =
+            MoonlibConfig::new().with_longitude_convention(LongitudeConvention::PositiveEast);
+
+        // Act
+        let observer = config.observer(11.6, 48.1, 1, 1013.0, 10.0);
+
+        // Assert
+        assert_approx_eq!(-11.6, observer.longitude.0, 1e-12);
+        assert_approx_eq!(48.1, observer.latitude.0, 1e-12);
+    }
+
+    #[test]
+    fn nutation_model_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1987, 4, 10.0));
+
+        // Act
+        let (full_psi, full_epsilon) = NutationModel::Full.nutation(jd);
+        let (low_psi, low_epsilon) = NutationModel::Low.nutation(jd);
+
+        // Assert
+        assert_approx_eq!(-3.788, full_psi.0, 0.001);
+        assert_approx_eq!(9.443, full_epsilon.0, 0.001);
+        assert_approx_eq!(full_psi.0, low_psi.0, 0.5);
+        assert_approx_eq!(full_epsilon.0, low_epsilon.0, 0.1);
+    }
+}
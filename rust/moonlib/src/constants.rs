@@ -6,8 +6,13 @@ pub(crate) const SEC_PER_DAY: i32 = 24 * 60 * 60;
 /// 0:0:0.00 UT on November 17, 1858
 pub(crate) const MJD: f64 = 2_400_000.5;
 
-/// 12:0:0.00 UT on January 1, 2000
-pub(crate) const J2000: f64 = 2_451_545.0;
+/// 0:0:0.00 UTC on January 1, 1970, the Unix epoch
+pub(crate) const UNIX_EPOCH: f64 = 2_440_587.5;
+
+/// 0:0:0.00 UTC on January 6, 1980, the GPS epoch
+pub(crate) const GPS_EPOCH: f64 = 2_444_244.5;
+
+pub(crate) const MILLIS_PER_DAY: f64 = 1000.0 * SEC_PER_DAY as f64;
 
 /// Convert siderial time to solar time: 24h solar time = 23h56m4.0905s siderial time
 pub(crate) const SIDERIAL_TO_SOLAR_TIME: f64 = 23.9344696 / 24.0;
@@ -1,7 +1,11 @@
 //! Coordinate transformations
 
 use crate::date::jd::JD;
-use crate::util::{degrees::Degrees, radians::Radians};
+use crate::util::math;
+use crate::util::radians::Radians;
+pub use crate::util::{
+    declination::Declination, degrees::Degrees, right_ascension::RightAscension,
+};
 use crate::{constants, earth, parallax, util};
 
 /// Convert ecliptical to equatorial coordinates.
@@ -14,65 +18,226 @@ use crate::{constants, earth, parallax, util};
 /// declination, in degrees
 /// Out:
 /// right ascension, in degrees [0, 360)
-/// declination, in degrees [-90, 90)
-pub(crate) fn ecliptical_2_equatorial(
+/// declination, in degrees [-90, 90]
+pub fn ecliptical_2_equatorial(
     lambda: Degrees,
     beta: Degrees,
     eps: Degrees,
-) -> (Degrees, Degrees) {
+) -> (RightAscension, Declination) {
+    let eps_radians = Radians::from(eps);
+    ecliptical_2_equatorial_for(
+        lambda,
+        beta,
+        math::sin(eps_radians.0),
+        math::cos(eps_radians.0),
+    )
+}
+
+/// Convert many ecliptical coordinates to equatorial ones for the same
+/// obliquity, e.g. the positions of several bodies at one time. The
+/// obliquity's sine and cosine are evaluated once
+/// In:
+/// coordinates: longitude and latitude, in degrees, see
+/// ecliptical_2_equatorial
+/// eps: obliquity of the ecliptic, in degrees
+/// Out:
+/// right ascension, in degrees [0, 360), and declination, in degrees
+/// [-90, 90], in the order of coordinates. The same values as
+/// ecliptical_2_equatorial
+pub fn ecliptical_2_equatorial_batch(
+    coordinates: &[(Degrees, Degrees)],
+    eps: Degrees,
+) -> Vec<(RightAscension, Declination)> {
+    let eps_radians = Radians::from(eps);
+    let sin_eps = math::sin(eps_radians.0);
+    let cos_eps = math::cos(eps_radians.0);
+    coordinates
+        .iter()
+        .map(|&(lambda, beta)| ecliptical_2_equatorial_for(lambda, beta, sin_eps, cos_eps))
+        .collect()
+}
+
+/// ecliptical_2_equatorial, for the sine and cosine of the obliquity
+fn ecliptical_2_equatorial_for(
+    lambda: Degrees,
+    beta: Degrees,
+    sin_eps: f64,
+    cos_eps: f64,
+) -> (RightAscension, Declination) {
     let lambda_radians = Radians::from(lambda);
     let beta_radians = Radians::from(beta);
+
+    let alpha = math::atan2(
+        math::sin(lambda_radians.0) * cos_eps - math::tan(beta_radians.0) * sin_eps,
+        math::cos(lambda_radians.0),
+    );
+    let delta = math::asin(
+        math::sin(beta_radians.0) * cos_eps
+            + math::cos(beta_radians.0) * sin_eps * math::sin(lambda_radians.0),
+    );
+
+    (
+        RightAscension::new(Degrees::from(Radians::new(alpha))),
+        Declination::new(Degrees::from(Radians::new(delta))),
+    )
+}
+
+/// Convert equatorial to ecliptical coordinates, the inverse of
+/// ecliptical_2_equatorial.
+/// Meeus, page 93, chapter 13, eqs (13.1) and (13.2)
+/// In:
+/// alpha: right ascension, in degrees [0, 360)
+/// delta: declination, in degrees [-90, 90]
+/// eps: obliquity of the eclipse. Use true obliquity for apparent
+/// right ascension and declination, in degrees
+/// Out:
+/// longitude, in degrees [0, 360)
+/// latitude, in degrees [-90, 90)
+///
+/// Meeus, example 13.a, Pollux
+/// ```
+/// use moonlib::config::AngleUnit;
+/// use moonlib::coordinates::{equatorial_2_ecliptical, Declination, Degrees, RightAscension};
+///
+/// let (lambda, beta) = equatorial_2_ecliptical(
+///     RightAscension::new(Degrees::new(116.328_942)),
+///     Declination::new(Degrees::new(28.026_183)),
+///     Degrees::new(23.439_291_1),
+/// );
+/// assert!((lambda.value(AngleUnit::Degrees) - 113.215_630).abs() < 1e-6);
+/// assert!((beta.value(AngleUnit::Degrees) - 6.684_170).abs() < 1e-6);
+/// ```
+pub fn equatorial_2_ecliptical(
+    alpha: RightAscension,
+    delta: Declination,
+    eps: Degrees,
+) -> (Degrees, Degrees) {
+    let alpha_radians = Radians::from(alpha.degrees());
+    let delta_radians = Radians::from(delta.degrees());
     let eps_radians = Radians::from(eps);
 
-    let alpha = (lambda_radians.0.sin() * eps_radians.0.cos()
-        - beta_radians.0.tan() * eps_radians.0.sin())
-    .atan2(lambda_radians.0.cos());
-    let delta = (beta_radians.0.sin() * eps_radians.0.cos()
-        + beta_radians.0.cos() * eps_radians.0.sin() * lambda_radians.0.sin())
-    .asin();
+    let lambda = math::atan2(
+        math::sin(alpha_radians.0) * math::cos(eps_radians.0)
+            + math::tan(delta_radians.0) * math::sin(eps_radians.0),
+        math::cos(alpha_radians.0),
+    );
+    let beta = math::asin(
+        math::sin(delta_radians.0) * math::cos(eps_radians.0)
+            - math::cos(delta_radians.0) * math::sin(eps_radians.0) * math::sin(alpha_radians.0),
+    );
 
     (
-        Degrees::from(Radians::new(alpha)).map_to_0_to_360(),
-        Degrees::from(Radians::new(delta)).map_to_neg90_to_90(),
+        Degrees::from(Radians::new(lambda)).map_to_0_to_360(),
+        Degrees::from(Radians::new(beta)).map_to_neg90_to_90(),
     )
 }
 
 /// Calculate horizontal from equatorial coordinates. Note that A is measured
 /// eastward from the North, whereas in Meeus, it is measures westward from
-/// the South!
+/// the South! Meeus, chapter 13, page 93
 /// In:
-/// declination, in degrees [-90, 90)
+/// declination, in degrees [-90, 90]
 /// hour_angle, in degrees [0, 360)
 /// observer's latitude, [-90, 90)
 /// Out:
 /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
 /// Altitude: in degrees [-90, 90)
-pub(crate) fn equatorial_2_horizontal(
-    decl: Degrees,
+///
+/// Meeus, example 13.b, Venus seen from the US Naval Observatory in
+/// Washington on 1987 April 10, 19h 21m 00s UT. Meeus' azimuth of 68.0337
+/// degrees, measured from the South, is 248.0337 degrees from the North
+/// ```
+/// use moonlib::config::AngleUnit;
+/// use moonlib::coordinates::{equatorial_2_horizontal, Declination, Degrees, RightAscension};
+/// use moonlib::date::jd::JD;
+/// use moonlib::earth::{hour_angle, local_siderial_time, siderial_time, SiderialTime};
+/// use moonlib::time::{ScaledJD, TimeScale};
+///
+/// let jd = ScaledJD::new(JD::new(2_446_896.306_25), TimeScale::UT1);
+/// let theta0 = siderial_time(jd, SiderialTime::Apparent);
+/// let theta = local_siderial_time(theta0, Degrees::from_dms(77, 3, 56.0));
+/// let h = hour_angle(theta, RightAscension::new(Degrees::new(347.319_337_5)));
+///
+/// let (azimuth, altitude) = equatorial_2_horizontal(
+///     Declination::new(Degrees::new(-6.719_892)),
+///     h,
+///     Degrees::from_dms(38, 55, 17.0),
+/// );
+/// assert!((azimuth.value(AngleUnit::Degrees) - 248.0337).abs() < 0.001);
+/// assert!((altitude.value(AngleUnit::Degrees) - 15.1249).abs() < 0.001);
+/// ```
+pub fn equatorial_2_horizontal(
+    decl: Declination,
     hour_angle: Degrees,
     latitude_observer: Degrees,
 ) -> (Degrees, Degrees) {
-    let decl_radians = Radians::from(decl);
-    let hour_angle_radians = Radians::from(hour_angle);
     let latitude_observer_radians = Radians::from(latitude_observer);
+    equatorial_2_horizontal_for(
+        decl,
+        hour_angle,
+        math::sin(latitude_observer_radians.0),
+        math::cos(latitude_observer_radians.0),
+    )
+}
 
-    let altitude_arg = latitude_observer_radians.0.sin() * decl_radians.0.sin()
-        + latitude_observer_radians.0.cos() * decl_radians.0.cos() * hour_angle_radians.0.cos();
-    let altitude = altitude_arg.asin();
+/// Calculate the horizontal coordinates of many objects at the same time,
+/// e.g. for a sky chart. The observer's latitude's sine and cosine are
+/// evaluated once
+/// In:
+/// coordinates: right ascension, in degrees [0, 360), and declination, in
+/// degrees [-90, 90]
+/// local_siderial_time: in degrees [0, 360), see earth::local_siderial_time
+/// latitude_observer: in degrees [-90, 90)
+/// Out:
+/// Azimuth, measured from North, increasing to the East, in degrees
+/// [0, 360), and altitude, in degrees [-90, 90), in the order of
+/// coordinates. The same values as equatorial_2_horizontal
+pub fn equatorial_2_horizontal_batch(
+    coordinates: &[(RightAscension, Declination)],
+    local_siderial_time: Degrees,
+    latitude_observer: Degrees,
+) -> Vec<(Degrees, Degrees)> {
+    let latitude_observer_radians = Radians::from(latitude_observer);
+    let sin_latitude = math::sin(latitude_observer_radians.0);
+    let cos_latitude = math::cos(latitude_observer_radians.0);
+    coordinates
+        .iter()
+        .map(|&(ra, decl)| {
+            let hour_angle = earth::hour_angle(local_siderial_time, ra);
+            equatorial_2_horizontal_for(decl, hour_angle, sin_latitude, cos_latitude)
+        })
+        .collect()
+}
 
-    let mut azimuth = ((decl_radians.0.sin()
-        - latitude_observer_radians.0.sin() * altitude_arg.sin())
-        / (latitude_observer_radians.0.cos() * altitude_arg.cos()))
-    .acos();
+/// equatorial_2_horizontal, for the sine and cosine of the observer's
+/// latitude
+fn equatorial_2_horizontal_for(
+    decl: Declination,
+    hour_angle: Degrees,
+    sin_latitude: f64,
+    cos_latitude: f64,
+) -> (Degrees, Degrees) {
+    let decl_radians = Radians::from(decl.degrees());
+    let hour_angle_radians = Radians::from(hour_angle);
 
-    // SS: The range of acos is [0, 180), but azimuth should be in the range [0, 360).
-    // Hence, we need a way to extent the result of acos. When the hour angle is positive,
-    // then so is it's sin. In this case, A should be in the western hemisphere, i.e. we
-    // extent A by subtracting 360 (North) from it.
-    let sinH = hour_angle_radians.0.sin();
-    if sinH > 0.0 {
-        azimuth = 2.0 * std::f64::consts::PI - azimuth;
-    }
+    // SS: direction to the object in the horizontal system, x pointing South,
+    // y West and z to the zenith. This is eq (13.5) and (13.6), with the
+    // numerator and denominator of (13.5) multiplied by cos(decl), so they
+    // stay finite at the celestial poles.
+    let x = math::cos(hour_angle_radians.0) * math::cos(decl_radians.0) * sin_latitude
+        - math::sin(decl_radians.0) * cos_latitude;
+    let y = math::sin(hour_angle_radians.0) * math::cos(decl_radians.0);
+    let z = sin_latitude * math::sin(decl_radians.0)
+        + cos_latitude * math::cos(decl_radians.0) * math::cos(hour_angle_radians.0);
+
+    // SS: atan2 yields the correct quadrant and keeps full precision close to
+    // the horizon's North and South points and to the zenith. In the zenith,
+    // the azimuth is undefined and atan2(0, 0) = 0 instead of NaN.
+    let altitude = math::atan2(z, (x * x + y * y).sqrt());
+
+    // SS: Meeus measures A westward from the South, add 180 deg to measure
+    // it eastward from the North
+    let azimuth = math::atan2(y, x) + std::f64::consts::PI;
 
     (
         Degrees::from(Radians::new(azimuth)).map_to_0_to_360(),
@@ -86,7 +251,7 @@ pub(crate) fn equatorial_2_horizontal(
 /// a rotation ellipsoid due to flattening at the poles.
 /// In:
 /// ra: Right ascension, geocentric, apparent, in degrees [0, 360)
-/// decl: Declination, geocentric, apparent, in degrees [-90, 90)
+/// decl: Declination, geocentric, apparent, in degrees [-90, 90]
 /// longitude: observer's longitude, in degrees [-80, 180)
 /// latitude: Observer's geocentric latitude, in degrees [-90, 90)
 /// height: observer's height above sea level, in meters
@@ -94,23 +259,21 @@ pub(crate) fn equatorial_2_horizontal(
 /// jd: Julian Day
 /// Out:
 /// right ascension, topocentric, in dgrees [0, 360)
-/// declination, topocentric, in degrees [-90, 90)
+/// declination, topocentric, in degrees [-90, 90]
 pub(crate) fn equatorial_2_topocentric(
-    ra: Degrees,
-    decl: Degrees,
+    ra: RightAscension,
+    decl: Declination,
     longitude: Degrees,
     latitude: Degrees,
     height: f64,
     distance: f64,
     jd: JD,
-) -> (Degrees, Degrees) {
+) -> (RightAscension, Declination) {
     let (rho_sin_p, rho_cos_p) = parallax::rho_phi_prime(latitude, height);
 
     let delta = distance / constants::AU;
-    let sin_pi = Radians::from(Degrees::from(util::arcsec::ArcSec::new(8.794)))
-        .0
-        .sin()
-        / delta;
+    let sin_pi =
+        math::sin(Radians::from(Degrees::from(util::arcsec::ArcSec::new(8.794))).0) / delta;
 
     // SS: calculate local hour angle
     let siderial_time_greenwich = earth::apparent_siderial_time(jd);
@@ -118,25 +281,151 @@ pub(crate) fn equatorial_2_topocentric(
     let hour_angle = earth::hour_angle(siderial_time_local, ra);
     let hour_angle_radians = Radians::from(hour_angle);
 
-    let ra_radians = Radians::from(ra);
-    let decl_radians = Radians::from(decl);
+    let ra_radians = Radians::from(ra.degrees());
+    let decl_radians = Radians::from(decl.degrees());
 
     // SS: eq (40.2)
-    let delta_ra = (-rho_cos_p * sin_pi * hour_angle_radians.0.sin())
-        .atan2(decl_radians.0.cos() - rho_cos_p * sin_pi * hour_angle_radians.0.cos());
+    let delta_ra = math::atan2(
+        -rho_cos_p * sin_pi * math::sin(hour_angle_radians.0),
+        math::cos(decl_radians.0) - rho_cos_p * sin_pi * math::cos(hour_angle_radians.0),
+    );
     let ra_topocentric = ra_radians + Radians::new(delta_ra);
 
     // SS: eq (40.3)
-    let decl_topocentric = ((decl_radians.0.sin() - rho_sin_p * sin_pi) * delta_ra.cos())
-        .atan2(decl_radians.0.cos() - rho_cos_p * sin_pi * hour_angle_radians.0.cos());
+    let decl_topocentric = math::atan2(
+        (math::sin(decl_radians.0) - rho_sin_p * sin_pi) * math::cos(delta_ra),
+        math::cos(decl_radians.0) - rho_cos_p * sin_pi * math::cos(hour_angle_radians.0),
+    );
     let decl_topocentric = Radians::new(decl_topocentric);
 
     (
-        Degrees::from(ra_topocentric).map_to_0_to_360(),
-        Degrees::from(decl_topocentric).map_to_neg90_to_90(),
+        RightAscension::new(Degrees::from(ra_topocentric)),
+        Declination::new(Degrees::from(decl_topocentric)),
     )
 }
 
+/// Angular separation of two objects on the celestial sphere.
+/// Meeus, chapter 17, page 116. Unlike eq (17.1), this formula is accurate
+/// also for objects very close to each other.
+/// In:
+/// alpha1, delta1: right ascension and declination of the first object, in degrees
+/// alpha2, delta2: right ascension and declination of the second object, in degrees
+/// Out: angular separation, in degrees [0, 180]
+pub(crate) fn angular_separation(
+    alpha1: RightAscension,
+    delta1: Declination,
+    alpha2: RightAscension,
+    delta2: Declination,
+) -> Degrees {
+    let delta1 = Radians::from(delta1).0;
+    let delta2 = Radians::from(delta2).0;
+    let delta_alpha = Radians::from(alpha1 - alpha2).0;
+
+    let x = math::cos(delta1) * math::sin(delta2)
+        - math::sin(delta1) * math::cos(delta2) * math::cos(delta_alpha);
+    let y = math::cos(delta2) * math::sin(delta_alpha);
+    let z = math::sin(delta1) * math::sin(delta2)
+        + math::cos(delta1) * math::cos(delta2) * math::cos(delta_alpha);
+
+    Degrees::from(Radians::new(math::atan2((x * x + y * y).sqrt(), z)))
+}
+
+/// Parallactic angle, i.e. the angle between the direction to the celestial
+/// pole and the direction to the zenith, at the object.
+/// Meeus, chapter 14, eq (14.1)
+/// In:
+/// hour_angle: local hour angle, in degrees [0, 360)
+/// declination: in degrees [-90, 90]
+/// latitude_observer: in degrees [-90, 90)
+/// Out: Parallactic angle, in degrees [-180, 180). Negative before, positive
+/// after the object's transit
+pub(crate) fn parallactic_angle(
+    hour_angle: Degrees,
+    declination: Declination,
+    latitude_observer: Degrees,
+) -> Degrees {
+    let h = Radians::from(hour_angle).0;
+    let delta = Radians::from(declination.degrees()).0;
+    let phi = Radians::from(latitude_observer).0;
+
+    let q = math::atan2(
+        math::sin(h),
+        math::tan(phi) * math::cos(delta) - math::sin(delta) * math::cos(h),
+    );
+    Degrees::from(Radians::new(q))
+}
+
+/// Rate of change of the parallactic angle, from the derivatives of eq (14.1)
+/// with respect to the hour angle and the declination. It grows without
+/// bound close to the zenith.
+/// In:
+/// hour_angle: local hour angle, in degrees [0, 360)
+/// declination: in degrees [-90, 90]
+/// latitude_observer: in degrees [-90, 90)
+/// hour_angle_rate: rate of change of the hour angle, in degrees per unit of time
+/// declination_rate: rate of change of the declination, in degrees per unit
+/// of time
+/// Out: Rate of change of the parallactic angle, in degrees per unit of time
+pub(crate) fn parallactic_angle_rate(
+    hour_angle: Degrees,
+    declination: Declination,
+    latitude_observer: Degrees,
+    hour_angle_rate: f64,
+    declination_rate: f64,
+) -> f64 {
+    let h = Radians::from(hour_angle).0;
+    let delta = Radians::from(declination.degrees()).0;
+    let phi = Radians::from(latitude_observer).0;
+
+    // SS: q = atan2(x, y)
+    let x = math::sin(h);
+    let y = math::tan(phi) * math::cos(delta) - math::sin(delta) * math::cos(h);
+
+    let dx_dh = math::cos(h);
+    let dy_dh = math::sin(delta) * math::sin(h);
+    let dy_ddelta = -math::tan(phi) * math::sin(delta) - math::cos(delta) * math::cos(h);
+
+    let dq_dh = (y * dx_dh - x * dy_dh) / (x * x + y * y);
+    let dq_ddelta = -x * dy_ddelta / (x * x + y * y);
+
+    dq_dh * hour_angle_rate + dq_ddelta * declination_rate
+}
+
+/// Zenith distance, the angle between an object and the zenith
+/// In: altitude, in degrees [-90, 90]
+/// Out: zenith distance, in degrees [0, 180]
+pub(crate) fn zenith_distance(altitude: Degrees) -> Degrees {
+    Degrees::new(90.0 - altitude.0)
+}
+
+/// Nadir distance, the angle between an object and the nadir
+/// In: altitude, in degrees [-90, 90]
+/// Out: nadir distance, in degrees [0, 180]
+pub(crate) fn nadir_distance(altitude: Degrees) -> Degrees {
+    Degrees::new(90.0 + altitude.0)
+}
+
+/// Relative airmass, i.e. the path length of light through the atmosphere
+/// relative to the path length at the zenith. 1 at the zenith, about 2 at
+/// an altitude of 30 degrees and 38 at the horizon.
+/// F. Kasten, A. T. Young, Revised optical air mass tables and approximation
+/// formula, Applied Optics 28, 4735 (1989). The most accurate of the
+/// airmass formulas in this crate, so it is the one reported, e.g. in
+/// moon::TopocentricCoordinates. The brightness models use the airmass
+/// they were built with, see moon::illuminance and sky
+/// In: apparent altitude, i.e. corrected for atmospheric refraction, in
+/// degrees [-90, 90]
+/// Out: airmass, None if the object is below the horizon
+pub(crate) fn airmass_kasten_young(altitude: Degrees) -> Option<f64> {
+    if altitude.0 < 0.0 {
+        return None;
+    }
+
+    let z = zenith_distance(altitude);
+    let x = 1.0 / (math::cos(Radians::from(z).0) + 0.50572 * (96.07995 - z.0).powf(-1.6364));
+    Some(x)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,12 +453,52 @@ mod tests {
         assert_approx_eq!(34.26, s, 0.01);
     }
 
+    #[test]
+    fn equatorial_2_ecliptical_test() {
+        // Meeus, page 95, example 13.a, Pollux
+
+        // Arrange
+        let alpha = RightAscension::new(Degrees::new(116.328942));
+        let delta = Declination::new(Degrees::new(28.026183));
+        let eps = Degrees::new(23.4392911);
+
+        // Act
+        let (lambda, beta) = equatorial_2_ecliptical(alpha, delta, eps);
+
+        // Assert
+        assert_approx_eq!(113.215630, lambda.0, 0.000_001);
+        assert_approx_eq!(6.684170, beta.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_2_ecliptical_round_trip_test() {
+        // Arrange
+        let eps = Degrees::new(23.4392911);
+
+        for (lambda, beta) in [
+            (0.0, 0.0),
+            (45.0, 5.0),
+            (135.0, -5.0),
+            (270.0, 60.0),
+            (359.0, -89.0),
+        ] {
+            // Act
+            let (alpha, delta) =
+                ecliptical_2_equatorial(Degrees::new(lambda), Degrees::new(beta), eps);
+            let (lambda2, beta2) = equatorial_2_ecliptical(alpha, delta, eps);
+
+            // Assert
+            assert_approx_eq!(lambda, lambda2.0, 0.000_001);
+            assert_approx_eq!(beta, beta2.0, 0.000_001);
+        }
+    }
+
     #[test]
     fn equatorial_2_horizontal_test_1() {
         // Meeus, page 96, example 13.b
 
         // Arrange
-        let declination = Degrees::from_dms(-6, 43, 11.61);
+        let declination = Declination::from_dms(-6, 43, 11.61);
         let hour_angle = Degrees::new(64.352133);
         let latitude_observer = Degrees::from_dms(38, 55, 17.0);
 
@@ -189,7 +518,7 @@ mod tests {
         // J.L. Lawrence, Celestial Calculations, 2018, page 90
 
         // Arrange
-        let declination = Degrees::from_dms(-0, 30, 30.0);
+        let declination = Declination::from_dms(-0, 30, 30.0);
         let hour_angle = Degrees::from_hms(16, 29, 45.0);
         let latitude_observer = Degrees::from_dms(25, 0, 0.0);
 
@@ -201,4 +530,383 @@ mod tests {
         assert_approx_eq!(Degrees::from_dms(80, 31, 31.0).0, azimuth.0, 1.0);
         assert_approx_eq!(Degrees::from_dms(-20, 34, 40.0).0, altitude.0, 1.0);
     }
+
+    #[test]
+    fn equatorial_2_horizontal_azimuth_test() {
+        // Meeus, page 96, example 13.b. Azimuth is computed from
+        // sin(altitude) and cos(altitude), not from sin and cos of sin(altitude)
+
+        // Arrange
+        let declination = Declination::from_dms(-6, 43, 11.61);
+        let hour_angle = Degrees::new(64.352133);
+        let latitude_observer = Degrees::from_dms(38, 55, 17.0);
+
+        // Act
+        let (azimuth, _) = equatorial_2_horizontal(declination, hour_angle, latitude_observer);
+
+        // Assert
+        assert_approx_eq!(180.0 + 68.0337, azimuth.0, 0.000_1);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_meridian_test() {
+        // Arrange
+
+        // SS: in the meridian, the azimuth is exactly South
+        let declination = Declination::new(Degrees::new(10.0));
+        let hour_angle = Degrees::new(0.0);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let (azimuth, altitude) =
+            equatorial_2_horizontal(declination, hour_angle, latitude_observer);
+
+        // Assert
+        assert!(!azimuth.0.is_nan());
+        assert_approx_eq!(180.0, azimuth.0, 0.000_001);
+        assert_approx_eq!(90.0 - 48.1 + 10.0, altitude.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_zenith_test() {
+        // Arrange
+
+        // SS: object passes through the zenith
+        let declination = Declination::new(Degrees::new(48.1));
+        let hour_angle = Degrees::new(0.0);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let (azimuth, altitude) =
+            equatorial_2_horizontal(declination, hour_angle, latitude_observer);
+
+        // Assert
+
+        // SS: azimuth is undefined in the zenith, but must not be NaN
+        assert!(!azimuth.0.is_nan());
+        assert_approx_eq!(90.0, altitude.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_close_to_zenith_test() {
+        // Arrange
+
+        // SS: object transits 0.0036 arcsec south and north of the zenith
+        let latitude_observer = Degrees::new(48.1);
+        let south = Declination::new(Degrees::new(48.1 - 0.000_001));
+        let north = Declination::new(Degrees::new(48.1 + 0.000_001));
+
+        // Act
+        let (azimuth_south, altitude_south) =
+            equatorial_2_horizontal(south, Degrees::new(0.0), latitude_observer);
+        let (azimuth_north, altitude_north) =
+            equatorial_2_horizontal(north, Degrees::new(0.0), latitude_observer);
+
+        // Assert
+        assert_approx_eq!(180.0, azimuth_south.0, 0.000_001);
+        assert_approx_eq!(0.0, azimuth_north.0 % 360.0, 0.000_001);
+        assert_approx_eq!(90.0 - 0.000_001, altitude_south.0, 0.000_000_1);
+        assert_approx_eq!(90.0 - 0.000_001, altitude_north.0, 0.000_000_1);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_symmetric_to_meridian_test() {
+        // Arrange
+
+        // SS: object passes 1 arcsec south of the zenith
+        let declination = Declination::new(Degrees::new(48.1 - 1.0 / 3600.0));
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let (azimuth_east, altitude_east) = equatorial_2_horizontal(
+            declination,
+            Degrees::from_hms(23, 59, 0.0),
+            latitude_observer,
+        );
+        let (azimuth_west, altitude_west) =
+            equatorial_2_horizontal(declination, Degrees::from_hms(0, 1, 0.0), latitude_observer);
+
+        // Assert
+
+        // SS: one minute before and after transit, the object is close to due
+        // East and due West, mirrored at the meridian
+        assert_approx_eq!(360.0, azimuth_east.0 + azimuth_west.0, 0.000_000_1);
+        assert_approx_eq!(altitude_east.0, altitude_west.0, 0.000_000_1);
+        assert_approx_eq!(270.0, azimuth_west.0, 0.01);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_north_pole_test() {
+        // Arrange
+
+        // SS: at the North pole, the altitude is the declination and the
+        // azimuth follows the hour angle
+        let declination = Declination::new(Degrees::new(20.0));
+        let hour_angle = Degrees::new(30.0);
+        let latitude_observer = Degrees::new(90.0);
+
+        // Act
+        let (azimuth, altitude) =
+            equatorial_2_horizontal(declination, hour_angle, latitude_observer);
+
+        // Assert
+        assert_approx_eq!(180.0 + 30.0, azimuth.0, 0.000_001);
+        assert_approx_eq!(20.0, altitude.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_south_pole_test() {
+        // Arrange
+        let declination = Declination::new(Degrees::new(-20.0));
+        let hour_angle = Degrees::new(30.0);
+        let latitude_observer = Degrees::new(-90.0);
+
+        // Act
+        let (azimuth, altitude) =
+            equatorial_2_horizontal(declination, hour_angle, latitude_observer);
+
+        // Assert
+
+        // SS: seen from the South pole, every direction is North, and
+        // objects move counterclockwise
+        assert_approx_eq!(360.0 - 30.0, azimuth.0, 0.000_001);
+        assert_approx_eq!(20.0, altitude.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_celestial_pole_test() {
+        // Arrange
+        let declination = Declination::new(Degrees::new(90.0));
+        let hour_angle = Degrees::new(123.0);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let (azimuth, altitude) =
+            equatorial_2_horizontal(declination, hour_angle, latitude_observer);
+
+        // Assert
+
+        // SS: the celestial North pole is due North, at the observer's latitude
+        assert_approx_eq!(0.0, azimuth.0 % 360.0, 0.000_001);
+        assert_approx_eq!(48.1, altitude.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_equator_test() {
+        // Arrange
+
+        // SS: for an observer on the equator, an object on the celestial equator
+        // rises due East and sets due West
+        let declination = Declination::new(Degrees::new(0.0));
+        let latitude_observer = Degrees::new(0.0);
+
+        // Act
+        let (azimuth_rise, altitude_rise) =
+            equatorial_2_horizontal(declination, Degrees::new(270.0), latitude_observer);
+        let (azimuth_set, altitude_set) =
+            equatorial_2_horizontal(declination, Degrees::new(90.0), latitude_observer);
+
+        // Assert
+        assert_approx_eq!(90.0, azimuth_rise.0, 0.000_001);
+        assert_approx_eq!(0.0, altitude_rise.0, 0.000_001);
+        assert_approx_eq!(270.0, azimuth_set.0, 0.000_001);
+        assert_approx_eq!(0.0, altitude_set.0, 0.000_001);
+    }
+
+    #[test]
+    fn ecliptical_2_equatorial_batch_test() {
+        // Arrange
+        let coordinates = (0..100)
+            .map(|i| {
+                (
+                    Degrees::new(i as f64 * 3.7),
+                    Degrees::new((i as f64 * 1.3) % 180.0 - 90.0),
+                )
+            })
+            .collect::<Vec<_>>();
+        let eps = Degrees::new(23.4392911);
+
+        // Act
+        let equatorial = ecliptical_2_equatorial_batch(&coordinates, eps);
+
+        // Assert
+        assert_eq!(coordinates.len(), equatorial.len());
+        for (&(lambda, beta), (ra, decl)) in coordinates.iter().zip(equatorial) {
+            let (expected_ra, expected_decl) = ecliptical_2_equatorial(lambda, beta, eps);
+            assert_eq!(expected_ra.0, ra.0);
+            assert_eq!(expected_decl.0, decl.0);
+        }
+        assert!(ecliptical_2_equatorial_batch(&[], eps).is_empty());
+    }
+
+    #[test]
+    fn equatorial_2_horizontal_batch_test() {
+        // Arrange
+        let coordinates = (0..100)
+            .map(|i| {
+                (
+                    RightAscension::new(Degrees::new(i as f64 * 3.6)),
+                    Declination::new(Degrees::new((i as f64 * 1.7) % 180.0 - 90.0)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let local_siderial_time = Degrees::new(128.7);
+        let latitude = Degrees::new(48.1);
+
+        // Act
+        let horizontal = equatorial_2_horizontal_batch(&coordinates, local_siderial_time, latitude);
+
+        // Assert
+        assert_eq!(coordinates.len(), horizontal.len());
+        for (&(ra, decl), (azimuth, altitude)) in coordinates.iter().zip(horizontal) {
+            let hour_angle = earth::hour_angle(local_siderial_time, ra);
+            let (expected_azimuth, expected_altitude) =
+                equatorial_2_horizontal(decl, hour_angle, latitude);
+            assert_eq!(expected_azimuth.0, azimuth.0);
+            assert_eq!(expected_altitude.0, altitude.0);
+        }
+    }
+
+    #[test]
+    fn angular_separation_test() {
+        // Meeus, example 17.a, page 110
+
+        // Arrange
+
+        // SS: Arcturus
+        let alpha1 = RightAscension::new(Degrees::new(213.9154));
+        let delta1 = Declination::new(Degrees::new(19.1825));
+
+        // SS: Spica
+        let alpha2 = RightAscension::new(Degrees::new(201.2983));
+        let delta2 = Declination::new(Degrees::new(-11.1614));
+
+        // Act
+        let separation = angular_separation(alpha1, delta1, alpha2, delta2);
+
+        // Assert
+        assert_approx_eq!(32.7930, separation.0, 0.0001)
+    }
+
+    #[test]
+    fn angular_separation_close_test() {
+        // Arrange
+
+        // SS: 1 arc second apart, where eq (17.1) loses most of its digits
+        let alpha = RightAscension::new(Degrees::new(123.4));
+        let delta = Declination::new(Degrees::new(45.6));
+
+        // Act
+        let separation = angular_separation(
+            alpha,
+            delta,
+            alpha,
+            Declination::new(Degrees::new(delta.0 + 1.0 / 3600.0)),
+        );
+
+        // Assert
+        assert_approx_eq!(1.0, separation.0 * 3600.0, 1e-6)
+    }
+
+    #[test]
+    fn parallactic_angle_test() {
+        // Arrange
+        let declination = Declination::new(Degrees::new(10.0));
+        let latitude = Degrees::new(48.1);
+
+        // Act
+        let at_transit = parallactic_angle(Degrees::new(0.0), declination, latitude);
+        let before = parallactic_angle(Degrees::new(330.0), declination, latitude);
+        let after = parallactic_angle(Degrees::new(30.0), declination, latitude);
+
+        // Assert
+
+        // SS: at transit, the zenith is straight above the celestial pole's
+        // direction, and the angle is symmetric to the meridian
+        assert_approx_eq!(0.0, at_transit.0, 1e-12);
+        assert!(before.0 < 0.0);
+        assert_approx_eq!(-before.0, after.0, 1e-12);
+    }
+
+    #[test]
+    fn parallactic_angle_equator_test() {
+        // Arrange
+
+        // SS: for an observer on the equator, an object on the celestial
+        // equator rises straight up, with the pole direction along the horizon
+        let declination = Declination::new(Degrees::new(0.0));
+        let latitude = Degrees::new(0.0);
+
+        // Act
+        let rising = parallactic_angle(Degrees::new(270.0), declination, latitude);
+
+        // Assert
+        assert_approx_eq!(-90.0, rising.0, 1e-12);
+    }
+
+    #[test]
+    fn parallactic_angle_rate_test() {
+        // Arrange
+
+        // SS: step for the central differences, in degrees
+        const H: f64 = 0.001;
+
+        let latitude = Degrees::new(48.1);
+
+        for (hour_angle, declination) in [(300.0, 20.0), (15.0, -10.0), (100.0, 60.0)] {
+            let decl = Declination::new(Degrees::new(declination));
+
+            // Act
+            let rate_h = parallactic_angle_rate(Degrees::new(hour_angle), decl, latitude, 1.0, 0.0);
+            let rate_delta =
+                parallactic_angle_rate(Degrees::new(hour_angle), decl, latitude, 0.0, 1.0);
+
+            // Assert
+            let q = |h: f64, d: f64| {
+                parallactic_angle(Degrees::new(h), Declination::new(Degrees::new(d)), latitude).0
+            };
+            assert_approx_eq!(
+                (q(hour_angle + H, declination) - q(hour_angle - H, declination)) / (2.0 * H),
+                rate_h,
+                1e-6
+            );
+            assert_approx_eq!(
+                (q(hour_angle, declination + H) - q(hour_angle, declination - H)) / (2.0 * H),
+                rate_delta,
+                1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn zenith_nadir_distance_test() {
+        // Arrange
+        let altitude = Degrees::new(30.0);
+
+        // Act
+        let zenith = zenith_distance(altitude);
+        let nadir = nadir_distance(altitude);
+
+        // Assert
+        assert_approx_eq!(60.0, zenith.0, 0.000_001);
+        assert_approx_eq!(120.0, nadir.0, 0.000_001);
+    }
+
+    #[test]
+    fn airmass_kasten_young_test() {
+        // Act
+        let zenith = airmass_kasten_young(Degrees::new(90.0)).unwrap();
+        let altitude_30 = airmass_kasten_young(Degrees::new(30.0)).unwrap();
+        let horizon = airmass_kasten_young(Degrees::new(0.0)).unwrap();
+        let below = airmass_kasten_young(Degrees::new(-0.1));
+
+        // Assert
+
+        // SS: Kasten and Young tabulate 1.995 at 30 degrees and 38.09 at the
+        // horizon, where the approximation is off by 0.5%
+        assert_approx_eq!(1.0, zenith, 0.001);
+        assert_approx_eq!(1.995, altitude_30, 0.001);
+        assert_approx_eq!(38.09, horizon, 0.2);
+        assert_eq!(None, below);
+    }
 }
@@ -0,0 +1,100 @@
+//! Standard epochs and strongly typed time spans measured from them
+
+use crate::date::jd::JD;
+
+/// Standard epochs, Meeus chapter 21, page 133
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Epoch {
+    /// 12:0:0.00 TD on January 1, 2000
+    J2000,
+
+    /// Beginning of the Besselian year 1950
+    B1950,
+
+    /// 12:0:0.00 TD on January 0, 1900
+    J1900,
+}
+
+impl Epoch {
+    pub fn jd(self) -> JD {
+        match self {
+            Epoch::J2000 => JD::new(2_451_545.0),
+            Epoch::B1950 => JD::new(2_433_282.423_5),
+            Epoch::J1900 => JD::new(2_415_020.0),
+        }
+    }
+}
+
+/// Time span, in days
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Days(pub f64);
+
+/// Time span, in Julian centuries of 36525 days
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Centuries(pub f64);
+
+/// Time span, in Julian millennia of 365250 days
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct Millennia(pub f64);
+
+impl From<Days> for Centuries {
+    fn from(days: Days) -> Self {
+        // SS: 365.25 = 1 year => 36525 = 100 years
+        Self(days.0 / 36_525.0)
+    }
+}
+
+impl From<Days> for Millennia {
+    fn from(days: Days) -> Self {
+        // SS: 365.25 = 1 year => 365_250 = 1000 years = 1 millennium
+        Self(days.0 / 365_250.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn j2000_test() {
+        // Arrange
+        let date = Date::new(2000, 1, 1.5);
+
+        // Act
+        let jd = Epoch::J2000.jd();
+
+        // Assert
+        assert_eq!(JD::from_date(date).jd, jd.jd)
+    }
+
+    #[test]
+    fn j1900_test() {
+        // Arrange
+        let date = Date::new(1900, 1, 0.5);
+
+        // Act
+        let days = JD::from_date(date).since(Epoch::J2000);
+
+        // Assert
+        assert_approx_eq!(-1.0, Centuries::from(days).0, 0.000_000_1);
+        assert_eq!(Epoch::J1900.jd().jd, JD::from_date(date).jd);
+    }
+
+    #[test]
+    fn since_test() {
+        // SS: 1992 April 12, 0h TD, Meeus example 47.a
+
+        // Arrange
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let days = jd.since(Epoch::J2000);
+
+        // Assert
+        assert_approx_eq!(-2820.5, days.0, 0.000_001);
+        assert_approx_eq!(-0.077221081451, Centuries::from(days).0, 0.000_000_000_001);
+        assert_approx_eq!(-0.0077221081451, Millennia::from(days).0, 0.000_000_000_001);
+    }
+}
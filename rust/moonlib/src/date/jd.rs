@@ -1,6 +1,8 @@
 //! Functions for representing a date as Julian Day
 use crate::constants;
 use crate::date::date::Date;
+use crate::date::epoch::{Centuries, Days, Epoch, Millennia};
+use crate::time::{LeapSeconds, ScaledJD, TimeScale};
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
 pub struct JD {
@@ -22,16 +24,22 @@ impl JD {
         let m = date.month;
         let d = date.day;
 
-        let (mm, yy) = if m < 3 { (m + 12, y - 1) } else { (m, y) };
+        // SS: in floating point, as to_calendar_date saturates the year at
+        // i16::MIN for Julian Days far in the past
+        let (mm, yy) = if m < 3 {
+            (m + 12, y as f64 - 1.0)
+        } else {
+            (m, y as f64)
+        };
 
         let b = if !Date::is_julian_calendar(date) {
-            let a = (yy as f64 / 100.0).trunc();
+            let a = (yy / 100.0).trunc();
             2.0 - a + (a as f64 / 4.0).trunc()
         } else {
             0.0
         };
 
-        let jd = (365.25 * (yy as f64 + 4716.0)).trunc()
+        let jd = (365.25 * (yy + 4716.0)).trunc()
             + (30.6001 * (mm as f64 + 1.0)).trunc()
             + (d as f64)
             + b
@@ -48,16 +56,55 @@ impl JD {
         }
     }
 
+    /// Convert a GPS week and the seconds into it to Julian Day. GPS weeks
+    /// count from 1980 January 6, 0h, the receivers broadcast them modulo
+    /// 1024, so add the rollovers, e.g. 2048 after 2019 April 7
+    /// In:
+    /// week: GPS week, including rollovers
+    /// seconds: seconds into the week, [0, 604800)
+    /// Out: Julian Day, in GPS time, see time::gps_2_utc
+    pub fn from_gps_week_seconds(week: u32, seconds: f64) -> ScaledJD {
+        let jd = Self::new(
+            constants::GPS_EPOCH + week as f64 * 7.0 + seconds / constants::SEC_PER_DAY as f64,
+        );
+        ScaledJD::new(jd, TimeScale::GPS)
+    }
+
+    /// Convert milliseconds since the Unix epoch to Julian Day.
+    /// Unix time does not count leap seconds, so the result is in UTC.
+    /// In: milliseconds since 1970 Jan. 1st, 0h UTC, as returned by
+    /// Java's System.currentTimeMillis()
+    /// Out: Julian Day, in UTC
+    pub fn from_unix_millis(millis: i64) -> Self {
+        // SS: split into whole days and remainder to not lose sub-second precision
+        let days = millis.div_euclid(constants::MILLIS_PER_DAY as i64);
+        let remainder = millis.rem_euclid(constants::MILLIS_PER_DAY as i64);
+        Self {
+            jd: constants::UNIX_EPOCH + days as f64 + remainder as f64 / constants::MILLIS_PER_DAY,
+        }
+    }
+
+    /// Convert Julian Day to milliseconds since the Unix epoch.
+    /// In: Julian Day, in UTC
+    /// Out: milliseconds since 1970 Jan. 1st, 0h UTC
+    pub fn to_unix_millis(self) -> i64 {
+        let days = self.jd - constants::UNIX_EPOCH;
+        (days * constants::MILLIS_PER_DAY).round() as i64
+    }
+
+    /// Time span elapsed since an epoch
+    /// In: epoch
+    /// Out: Days since epoch, negative if before the epoch
+    pub fn since(self, epoch: Epoch) -> Days {
+        Days(self.jd - epoch.jd().jd)
+    }
+
     pub(crate) fn centuries_from_epoch_j2000(self) -> f64 {
-        // SS: convert to dynamical time TD
-        // 365.25 = 1 year => 36525 = 100 years
-        (self.jd - constants::J2000) / 36_525.0
+        Centuries::from(self.since(Epoch::J2000)).0
     }
 
     pub(crate) fn millennia_from_epoch_j2000(self) -> f64 {
-        // SS: convert to dynamical time TD
-        // 365.25 = 1 year => 365_250 = 1000 years = 1 millennium
-        (self.jd - constants::J2000) / 365_250.0
+        Millennia::from(self.since(Epoch::J2000)).0
     }
 
     /// Convert Julian Day to Modified Julian Day MJD
@@ -72,7 +119,8 @@ impl JD {
     /// Convert Julian Day to calendar date
     /// Meeus, page 63, chapter 7
     /// In: Julian Day
-    /// Out: Calendar date
+    /// Out: Calendar date, in the same time scale as the Julian Day. Use
+    /// to_calendar_date_utc for dates shown to users
     pub fn to_calendar_date(self) -> Date {
         let jd_mod = self.jd + 0.5;
         let z = jd_mod.trunc();
@@ -97,6 +145,45 @@ impl JD {
         Date::new(year as i16, m as u8, day_fract)
     }
 
+    /// Convert Julian Day in dynamical time to the civil calendar date in
+    /// UTC. Close to midnight, the date in dynamical time is already the
+    /// next day, as TT is ahead of UTC
+    /// In:
+    /// self: Julian Day, in dynamical time
+    /// table: leap seconds
+    /// Out: Calendar date, in UTC
+    pub fn to_calendar_date_utc(&self, table: &LeapSeconds) -> Date {
+        ScaledJD::new(*self, TimeScale::TT).to_calendar_date_utc(table)
+    }
+
+    /// Start of the local day, i.e. local midnight expressed in UTC
+    /// In: timezone_offset: Observer's time zone offset, in hours
+    /// Out: Julian Day of the local midnight the day containing self starts with
+    pub fn start_of_local_day(self, timezone_offset: i8) -> Self {
+        // SS: Julian Days start at noon, so shift by half a day to count whole
+        // days from midnight
+        let offset = timezone_offset as f64 * constants::HOURS_TO_DAYS;
+        Self {
+            jd: (self.jd + 0.5 + offset).floor() - 0.5 - offset,
+        }
+    }
+
+    /// Round to the nearest full minute
+    pub fn round_to_minute(self) -> Self {
+        const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+        Self {
+            jd: ((self.jd + 0.5) * MINUTES_PER_DAY).round() / MINUTES_PER_DAY - 0.5,
+        }
+    }
+
+    /// Truncate to the full hour
+    pub fn floor_to_hour(self) -> Self {
+        const HOURS_PER_DAY: f64 = 24.0;
+        Self {
+            jd: ((self.jd + 0.5) * HOURS_PER_DAY).floor() / HOURS_PER_DAY - 0.5,
+        }
+    }
+
     /// Add delta_t days to Julian Day
     pub(crate) fn add_hours(&mut self, delta_t: f64) {
         // SS: the unit of a Julian day is days, so convert hours to days
@@ -105,6 +192,26 @@ impl JD {
     }
 }
 
+/// ISO 8601 date and time, rounded to the second, e.g. 2000-03-20T12:34:56.
+/// There is no time zone designator, as the time scale depends on the caller
+impl std::fmt::Display for JD {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // SS: add half a second, so truncating the seconds rounds them
+        let date = JD::new(self.jd + 0.5 / constants::SEC_PER_DAY as f64).to_calendar_date();
+        let (h, m, s) = Date::from_fract_day(date.day);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            date.year,
+            date.month,
+            date.day.trunc() as u8,
+            h,
+            m,
+            s.trunc() as u8
+        )
+    }
+}
+
 impl std::ops::Add for JD {
     type Output = Self;
 
@@ -200,6 +307,71 @@ mod tests {
         assert_approx_eq!(2_452_879.63681, jd.jd, 0.000_01)
     }
 
+    #[test]
+    fn from_unix_millis_test_1() {
+        // arrange
+
+        // SS: Jan 16th, 2022, 2:26:18pm UTC
+        let millis = 1_642_343_178_000;
+
+        // act
+        let jd = JD::from_unix_millis(millis);
+
+        // assert
+        assert_approx_eq!(2_459_596.101598, jd.jd, 0.000_001)
+    }
+
+    #[test]
+    fn from_unix_millis_test_2() {
+        // arrange
+
+        // SS: Dec 31st, 1969, 23:59:59.500 UTC
+        let millis = -500;
+
+        // act
+        let jd = JD::from_unix_millis(millis);
+
+        // assert
+        assert_approx_eq!(constants::UNIX_EPOCH - 0.5 / 86_400.0, jd.jd, 0.000_000_001)
+    }
+
+    #[test]
+    fn from_gps_week_seconds_test() {
+        // arrange
+
+        // SS: GPS week 2300 started on 2024 Feb. 4th, 0h GPS time, week 2048
+        // on 2019 Apr. 7th, after the second rollover
+        let week = 2300;
+
+        // act
+        let start = JD::from_gps_week_seconds(week, 0.0);
+        let noon = JD::from_gps_week_seconds(week, 3.5 * 86_400.0);
+        let rollover = JD::from_gps_week_seconds(2048, 0.0);
+
+        // assert
+        assert_eq!(TimeScale::GPS, start.time_scale);
+        assert_approx_eq!(2_460_344.5, start.jd.jd, 0.000_000_001);
+        assert_approx_eq!(2_460_348.0, noon.jd.jd, 0.000_000_001);
+        assert_approx_eq!(2_458_580.5, rollover.jd.jd, 0.000_000_001);
+
+        // SS: 18s of GPS time ahead of UTC
+        let utc = start.to_utc(&LeapSeconds::iers());
+        assert_approx_eq!(-18.0, (utc.jd - start.jd.jd) * 86_400.0, 0.000_1);
+    }
+
+    #[test]
+    fn to_unix_millis_test() {
+        // arrange
+        let millis = 1_642_343_178_123;
+        let jd = JD::from_unix_millis(millis);
+
+        // act
+        let round_trip = jd.to_unix_millis();
+
+        // assert
+        assert!((millis - round_trip).abs() <= 1)
+    }
+
     #[test]
     fn add_hours_test_1() {
         // arrange
@@ -210,7 +382,7 @@ mod tests {
         jd.add_hours(12.0);
 
         // assert
-        assert_approx_eq!(constants::J2000 + 0.5, jd.jd, 0.000_01)
+        assert_approx_eq!(Epoch::J2000.jd().jd + 0.5, jd.jd, 0.000_01)
     }
 
     #[test]
@@ -229,4 +401,134 @@ mod tests {
             0.000_01
         )
     }
+
+    #[test]
+    fn start_of_local_day_test() {
+        // arrange
+
+        // SS: 2022 March 1st, 1h UTC, in New York still February 28th
+        let jd = JD::from_date(Date::from_date_hms(2022, 3, 1, 1, 0, 0.0));
+
+        // act
+        let start_utc = jd.start_of_local_day(0);
+        let start_new_york = jd.start_of_local_day(-5);
+
+        // assert
+        assert_eq!(JD::from_date(Date::new(2022, 3, 1.0)).jd, start_utc.jd);
+        assert_approx_eq!(
+            JD::from_date(Date::from_date_hms(2022, 2, 28, 5, 0, 0.0)).jd,
+            start_new_york.jd,
+            0.000_000_1
+        )
+    }
+
+    #[test]
+    fn start_of_local_day_midnight_test() {
+        // arrange
+
+        // SS: just before and at midnight at the end of February
+        let midnight = JD::from_date(Date::new(2022, 3, 1.0));
+        let before = JD::new(midnight.jd - 0.000_001);
+
+        // act
+        let start_before = before.start_of_local_day(0);
+        let start_midnight = midnight.start_of_local_day(0);
+
+        // assert
+        assert_eq!(midnight.jd - 1.0, start_before.jd);
+        assert_eq!(midnight.jd, start_midnight.jd);
+    }
+
+    #[test]
+    fn round_to_minute_test() {
+        // arrange
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 23, 16, 48, 32.7));
+
+        // act
+        let rounded = jd.round_to_minute();
+
+        // assert
+        assert_approx_eq!(
+            JD::from_date(Date::from_date_hms(2000, 3, 23, 16, 49, 0.0)).jd,
+            rounded.jd,
+            0.000_000_1
+        )
+    }
+
+    #[test]
+    fn floor_to_hour_test() {
+        // arrange
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 23, 16, 48, 32.7));
+
+        // act
+        let floored = jd.floor_to_hour();
+
+        // assert
+        assert_approx_eq!(
+            JD::from_date(Date::from_date_hms(2000, 3, 23, 16, 0, 0.0)).jd,
+            floored.jd,
+            0.000_000_1
+        )
+    }
+
+    #[test]
+    fn to_calendar_date_utc_test() {
+        // Arrange
+
+        // SS: 2024 March 10, a minute after midnight in dynamical time, is
+        // still March 9 in UTC
+        let utc = JD::from_date(Date::from_date_hms(2024, 3, 9, 23, 59, 0.0));
+        let tt = crate::time::utc_2_tt(utc);
+
+        // Act
+        let date = tt.to_calendar_date_utc(&LeapSeconds::iers());
+
+        // Assert
+        assert_eq!(10, tt.to_calendar_date().day.trunc() as i16);
+        assert_eq!(9, date.day.trunc() as i16);
+        assert_approx_eq!(utc.to_calendar_date().day, date.day, 0.000_001);
+    }
+
+    #[test]
+    fn display_test() {
+        // Arrange
+
+        // SS: 1957 October 4.81, Meeus, example 7.a
+        let jd = JD::new(2_436_116.31);
+
+        // Act
+        let s = jd.to_string();
+
+        // Assert
+        assert_eq!("1957-10-04T19:26:24", s);
+    }
+
+    #[test]
+    fn display_rounding_test() {
+        // Arrange
+        let jd = JD::new(Epoch::J2000.jd().jd - 0.1 / 86400.0);
+
+        // Act
+        let s = jd.to_string();
+
+        // Assert
+        assert_eq!("2000-01-01T12:00:00", s);
+    }
+
+    #[test]
+    fn extreme_julian_day_test() {
+        // Arrange
+
+        // SS: the year saturates at i16::MIN
+        let jd = JD::new(-1e15);
+        let table = LeapSeconds::iers();
+
+        // Act
+        let date = jd.to_calendar_date();
+        let utc = ScaledJD::new(jd, TimeScale::TT).to_calendar_date_utc(&table);
+
+        // Assert
+        assert_eq!(i16::MIN, date.year);
+        assert_eq!(i16::MIN, utc.year);
+    }
 }
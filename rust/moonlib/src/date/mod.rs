@@ -1,2 +1,3 @@
 pub mod date;
+pub mod epoch;
 pub mod jd;
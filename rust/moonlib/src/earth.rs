@@ -14,13 +14,22 @@
 // The length of a solar day varies throughout the year, as the Earth moves around an eclipse, not a
 // perfect circle. Siderial days are always the same length, as they are defined by Earth rotating
 // once around its axis.
+use crate::date::epoch::{Centuries, Epoch};
 use crate::date::jd::JD;
-use crate::ecliptic::true_obliquity;
+/// Obliquity of the ecliptic, i.e. the inclination of Earth's axis, Meeus
+/// chapter 22. The same as in the ecliptic module
+pub use crate::ecliptic::{mean_obliquity, true_obliquity};
 use crate::nutation::nutation_in_longitude;
-use crate::util::{degrees::Degrees, radians::Radians};
+use crate::time::{ScaledJD, TimeScale};
+use crate::util::math;
+use crate::util::{degrees::Degrees, radians::Radians, right_ascension::RightAscension};
 
-/// Calculate Earth's eccentricity, eq (47.6).
+/// Calculate the factor E of the terms of the Moon's series that contain the
+/// Sun's mean anomaly, eq (47.6). It accounts for the decreasing
+/// eccentricity of Earth's orbit and is 1 at J2000.0. For the eccentricity
+/// itself, see orbital_eccentricity.
 /// In: Julian day in dynamical time
+/// Out: Factor E, about 1
 pub fn eccentricity(jd: JD) -> f64 {
     let t = jd.centuries_from_epoch_j2000();
     let t2 = t * t;
@@ -28,32 +37,98 @@ pub fn eccentricity(jd: JD) -> f64 {
     1.0 - 0.002516 * t - 0.0000074 * t2
 }
 
-/// Calculate the mean siderial time at Greenwich
-/// Meeus, page 87, chapter 12
-/// In: Julian Day
-/// Out: Mean siderial time in degrees [0, 360)
-pub(crate) fn mean_siderial_time(jd: JD) -> Degrees {
-    let delta_jd = jd - JD::new(2_451_545.0);
-    let t = delta_jd.jd / 36525.0;
+/// Calculate the eccentricity of Earth's orbit, eq (25.4).
+/// In: Julian day in dynamical time
+/// Out: Eccentricity, about 0.0167
+pub fn orbital_eccentricity(jd: JD) -> f64 {
+    let t = jd.centuries_from_epoch_j2000();
+    let t2 = t * t;
+
+    0.016708634 - 0.000042037 * t - 0.0000001267 * t2
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SiderialTime {
+    /// Referred to the mean equinox of date
+    Mean,
+
+    /// Referred to the true equinox of date, i.e. including Earth's nutation
+    Apparent,
+}
+
+/// Calculate the siderial time at Greenwich
+/// Meeus, page 87, chapter 12, eq (12.4)
+/// In:
+/// jd: Julian Day, tagged with its time scale. The polynomial is in UT1, so
+/// TT is converted with delta_t, while UTC is taken as UT1
+/// kind: mean or apparent siderial time
+/// Out: Siderial time in degrees [0, 360)
+///
+/// Meeus, example 12.a, mean siderial time on 1987 April 10, 0h UT, and
+/// example 12.b, at 19h 21m 00s UT
+/// ```
+/// use moonlib::config::AngleUnit;
+/// use moonlib::date::jd::JD;
+/// use moonlib::earth::{siderial_time, SiderialTime};
+/// use moonlib::time::{ScaledJD, TimeScale};
+///
+/// let midnight = ScaledJD::new(JD::new(2_446_895.5), TimeScale::UT1);
+/// let (h, m, s) = siderial_time(midnight, SiderialTime::Mean).to_hms();
+/// assert_eq!((13, 10), (h, m));
+/// assert!((s - 46.3668).abs() < 0.001);
+///
+/// let evening = ScaledJD::new(JD::new(2_446_896.306_25), TimeScale::UT1);
+/// let theta0 = siderial_time(evening, SiderialTime::Mean).value(AngleUnit::Degrees);
+/// assert!((theta0 - 128.737_873_4).abs() < 1e-6);
+/// ```
+pub fn siderial_time(jd: ScaledJD, kind: SiderialTime) -> Degrees {
+    let delta_jd = jd.to_ut1().since(Epoch::J2000);
+    let t = Centuries::from(delta_jd).0;
     let t2 = t * t;
     let t3 = t * t2;
     let mean_siderial_time =
-        280.46061836 + 360.98564736629 * delta_jd.jd + 0.000387933 * t2 - t3 / 38_710_000.0;
-    Degrees(mean_siderial_time).map_to_0_to_360()
+        Degrees(280.46061836 + 360.98564736629 * delta_jd.0 + 0.000387933 * t2 - t3 / 38_710_000.0)
+            .map_to_0_to_360();
+
+    match kind {
+        SiderialTime::Mean => mean_siderial_time,
+        SiderialTime::Apparent => {
+            // SS: nutation changes too slowly for the time scale to matter
+            let eps = true_obliquity(jd.jd);
+            let delta_psi = nutation_in_longitude(jd.jd);
+
+            let siderial_time =
+                mean_siderial_time + Degrees::from(delta_psi) * math::cos(Radians::from(eps).0);
+            siderial_time.map_to_0_to_360()
+        }
+    }
 }
 
-/// Calculate the apparent siderial time at Greenwich, which
-/// takes Earth's nutation effects into account.
-/// Meeus, page 87, chapter 12
+/// Calculate the Earth rotation angle, the angle between the Celestial and
+/// the Terrestrial Intermediate Origin, IERS Conventions (2010), eq (5.15).
+/// Unlike the siderial time, it is linear in UT1 and does not depend on
+/// precession or nutation.
+/// In: Julian Day, tagged with its time scale. TT is converted to UT1 with
+/// delta_t, while UTC is taken as UT1
+/// Out: Earth rotation angle in degrees [0, 360)
+pub fn rotation_angle(jd: ScaledJD) -> Degrees {
+    let delta_jd = jd.to_ut1().since(Epoch::J2000).0;
+
+    // SS: the fraction of the day is added separately, to keep the
+    // precision of the large number of rotations since J2000.0
+    let rotations = 0.7790572732640 + 0.00273781191135448 * delta_jd + delta_jd.fract();
+    Degrees::new(360.0 * rotations.fract()).map_to_0_to_360()
+}
+
+/// Calculate the apparent siderial time at Greenwich for the Julian Day
+/// passed in, taken as UT1.
+/// The Moon's and Sun's positions are in dynamical time and use it as is,
+/// which turns the Earth too far by delta_t, about 0.27 degrees for
+/// delta_t = 64s. Use siderial_time to pass the time scale explicitly.
 /// In: Julian Day
-/// Out: Mean siderial time in degrees [0, 360)
+/// Out: Apparent siderial time in degrees [0, 360)
 pub(crate) fn apparent_siderial_time(jd: JD) -> Degrees {
-    let mean_siderial_time = mean_siderial_time(jd);
-    let eps = true_obliquity(jd);
-    let delta_psi = nutation_in_longitude(jd);
-
-    let siderial_time = mean_siderial_time + Degrees::from(delta_psi) * Radians::from(eps).0.cos();
-    siderial_time.map_to_0_to_360()
+    siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Apparent)
 }
 
 /// Local siderial time
@@ -63,7 +138,7 @@ pub(crate) fn apparent_siderial_time(jd: JD) -> Degrees {
 /// (positive west, negative east of Greenwich)
 /// Out:
 /// Local siderial time
-pub(crate) fn local_siderial_time(siderial_time: Degrees, longitude_observer: Degrees) -> Degrees {
+pub fn local_siderial_time(siderial_time: Degrees, longitude_observer: Degrees) -> Degrees {
     Degrees::new(siderial_time.0 - longitude_observer.0).map_to_0_to_360()
 }
 
@@ -76,10 +151,22 @@ pub(crate) fn local_siderial_time(siderial_time: Degrees, longitude_observer: De
 /// right ascension: Right ascension of the object whose hour angle we calculate, in degrees [0, 360)
 /// Out:
 /// Hour angle
-pub(crate) fn hour_angle(siderial_time: Degrees, right_ascension: Degrees) -> Degrees {
+pub fn hour_angle(siderial_time: Degrees, right_ascension: RightAscension) -> Degrees {
     Degrees::new(siderial_time.0 - right_ascension.0).map_to_0_to_360()
 }
 
+/// Calculate the dip of the sea horizon, i.e. how far below the astronomical
+/// horizon an observer above the sea level sees the horizon. Including the
+/// terrestrial refraction, as in the dip tables of the Nautical Almanac,
+/// dip = 1.76' * sqrt(height). The geometric dip, without refraction, is
+/// about 1.93' * sqrt(height).
+/// In: height_meters: Observer's height above the sea level, in meters
+/// Out: Dip, in degrees, 0 for a height of 0 or less
+pub fn horizon_dip(height_meters: f64) -> Degrees {
+    // SS: the formula holds up to the cruising altitude of an aircraft
+    Degrees::new(1.76 * height_meters.max(0.0).sqrt() / 60.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,6 +175,26 @@ mod tests {
     use crate::{coordinates, ecliptic};
     use assert_approx_eq::assert_approx_eq;
 
+    #[test]
+    fn horizon_dip_test() {
+        // Arrange
+
+        // SS: Bowditch, The American Practical Navigator, table 22, for 10,
+        // 100 and 1000 feet
+        let table = [(3.048, 3.1), (30.48, 9.7), (304.8, 30.7)];
+
+        for (height, expected) in table {
+            // Act
+            let dip = horizon_dip(height);
+
+            // Assert
+            assert_approx_eq!(expected, dip.0 * 60.0, 0.05);
+        }
+
+        assert_eq!(0.0, horizon_dip(0.0).0);
+        assert_eq!(0.0, horizon_dip(-10.0).0);
+    }
+
     #[test]
     fn eccentricity_test() {
         // Arrange
@@ -122,4 +229,209 @@ mod tests {
 
         assert_approx_eq!(13.769657226951539, dec.0, 0.000_001);
     }
+
+    #[test]
+    fn hour_angle_test() {
+        // Meeus, page 95, example 13.b
+
+        // Arrange
+        let siderial_time_apparent_greenwich = Degrees::from_hms(8, 34, 56.853);
+        let longitude_observer = Degrees::from_hms(5, 8, 15.7);
+        let right_ascension_apparent = RightAscension::from_hms(23, 9, 16.641);
+
+        // Act
+        let siderial_time_local =
+            local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
+        let hour_angle = hour_angle(siderial_time_local, right_ascension_apparent);
+
+        // Assert
+        assert_approx_eq!(64.352133, hour_angle.0, 0.00001)
+    }
+
+    #[test]
+    fn local_siderial_time_test_1() {
+        // Arrange
+
+        // SS: Jan 29th, 2022, 2:32:20pm UTC
+        let jd = JD::new(2_459_609.105793);
+
+        let longitude_observer = Degrees::from_dms(105, 12, 53.8);
+
+        let mean_siderial_time =
+            siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Mean);
+
+        // Act
+        let theta0 = local_siderial_time(mean_siderial_time, longitude_observer);
+        let (h, m, s) = theta0.to_hms();
+
+        // Assert
+        assert_eq!(h, 16);
+        assert_eq!(m, 6);
+        assert_approx_eq!(46.9, s, 0.1)
+    }
+
+    #[test]
+    fn mean_siderial_time_test_1() {
+        // Arrange
+
+        // SS: Jan 16th, 2022, 2:26:18pm UTC
+        let jd = JD::new(2_459_596.101598);
+
+        // Act
+        let theta0 = siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Mean);
+        let (h, m, s) = theta0.to_hms();
+
+        // Assert
+        assert_eq!(h, 22);
+        assert_eq!(m, 10);
+        assert_approx_eq!(19.92073, s, 0.00001)
+    }
+
+    #[test]
+    fn mean_siderial_time_test_2() {
+        // Meeus, example 12.b, page 89
+
+        // Arrange
+
+        // SS: Apr. 10th 1987, 19h:21m:00s UT
+        let jd = JD::new(2_446_896.30625);
+
+        // Act
+        let theta0 = siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Mean);
+        let (h, m, s) = theta0.to_hms();
+
+        // Assert
+        assert_approx_eq!(128.7378734, theta0.0, 0.00001);
+
+        assert_eq!(h, 8);
+        assert_eq!(m, 34);
+        assert_approx_eq!(57.0896, s, 0.0001)
+    }
+
+    #[test]
+    fn apparent_siderial_time_test_1() {
+        // Arrange
+
+        // SS: Jan 16th, 2022, 2:26:18pm UTC
+        let jd = JD::new(2_459_596.101598);
+
+        // Act
+        let theta0 = siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Apparent);
+        let (h, m, s) = theta0.to_hms();
+
+        // Assert
+        assert_eq!(h, 22);
+        assert_eq!(m, 10);
+        assert_approx_eq!(19.10356, s, 0.00001)
+    }
+
+    #[test]
+    fn apparent_siderial_time_test_2() {
+        // Meeus, example 12.a, page 88
+
+        // Arrange
+
+        // SS: Apr. 10th 1987, 0 UT
+        let jd = JD::new(2_446_895.5);
+
+        // Act
+        let theta0 = siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Apparent);
+        let (h, m, s) = theta0.to_hms();
+
+        // Assert
+        assert_eq!(h, 13);
+        assert_eq!(m, 10);
+        assert_approx_eq!(46.1351, s, 0.000_1)
+    }
+
+    #[test]
+    fn siderial_time_time_scale_test() {
+        // Arrange
+
+        // SS: Apr. 10th 1987, 19h:21m:00s UT
+        let jd = JD::new(2_446_896.306_25);
+        let tt = JD::new(jd.jd + 55.3 / 86400.0);
+
+        // Act
+        let ut1 = siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Mean);
+        let utc = siderial_time(ScaledJD::new(jd, TimeScale::UTC), SiderialTime::Mean);
+        let from_tt = siderial_time(ScaledJD::new(tt, TimeScale::TT), SiderialTime::Mean);
+
+        // Assert
+        assert_eq!(ut1.0, utc.0);
+
+        // SS: delta_t in 1987 is about 55s, Meeus, page 78
+        assert_approx_eq!(ut1.0, from_tt.0, 0.005);
+    }
+
+    #[test]
+    fn orbital_eccentricity_test() {
+        // Arrange
+
+        // SS: 1992 October 13, 0h TD
+        let jd = JD::new(2_448_908.5);
+
+        // Act
+        let e = orbital_eccentricity(jd);
+
+        // Assert
+
+        // SS: Meeus, example 25.a
+        assert_approx_eq!(0.016_711_668, e, 0.000_000_001)
+    }
+
+    #[test]
+    fn obliquity_test() {
+        // Arrange
+
+        // SS: 1987 April 10, 0h TD
+        let jd = JD::new(2_446_895.5);
+
+        // Act
+        let eps_0 = mean_obliquity(jd);
+        let eps = true_obliquity(jd);
+
+        // Assert
+
+        // SS: Meeus, example 22.a, 23 deg 26' 27.407" and 23 deg 26' 36.850"
+        assert_approx_eq!(23.440_946, eps_0.0, 0.000_001);
+        assert_approx_eq!(23.443_569, eps.0, 0.000_001);
+    }
+
+    #[test]
+    fn rotation_angle_test() {
+        // Arrange
+
+        // SS: J2000.0, in UT1
+        let jd = ScaledJD::new(Epoch::J2000.jd(), TimeScale::UT1);
+
+        // Act
+        let era = rotation_angle(jd);
+
+        // Assert
+
+        // SS: 2 pi * 0.7790572732640, IERS Conventions (2010), eq (5.15)
+        assert_approx_eq!(280.460_618_375_04, era.0, 1e-9)
+    }
+
+    #[test]
+    fn rotation_angle_siderial_time_test() {
+        // Arrange
+
+        // SS: Apr. 10th 1987, 19h:21m:00s UT
+        let jd = ScaledJD::new(JD::new(2_446_896.306_25), TimeScale::UT1);
+        let t = (jd.jd.jd - Epoch::J2000.jd().jd) / 36_525.0;
+
+        // Act
+        let era = rotation_angle(jd);
+
+        // Assert
+
+        // SS: the mean siderial time exceeds the rotation angle by the
+        // accumulated precession in right ascension, IERS Conventions
+        // (2010), eq (5.32)
+        let gmst = siderial_time(jd, SiderialTime::Mean);
+        let precession = (0.014_506 + 4_612.156_534 * t + 1.391_581_7 * t * t) / 3600.0;
+        assert_approx_eq!(precession, (gmst - era).map_neg180_to_180().0, 0.000_1)
+    }
 }
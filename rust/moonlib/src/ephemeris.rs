@@ -0,0 +1,253 @@
+//! Apparent geocentric positions of the Sun and the Moon for many times at
+//! once, e.g. for plots and ephemeris tables.
+//!
+//! Nutation and obliquity change slowly, the shortest periodic term of the
+//! nutation series with more than 0.01" has a period of 9 days, Meeus,
+//! table 22.A. For times close together, they can be evaluated at knots
+//! half a day apart and interpolated linearly between them, which is
+//! accurate to about 0.003".
+//!
+//! This halves the time per position for Precision::Low, where the nutation
+//! series cost as much as the Moon's series. For Precision::Standard and
+//! High, summing up the VSOP87 series for the Sun dominates, and the time
+//! drops by 15 to 20 percent.
+
+use crate::config::NutationModel;
+use crate::date::jd::JD;
+use crate::moon::position::{geocentric_with_nutation, GeocentricMoon};
+use crate::session::Precision;
+use crate::sun::position::{
+    apparent_position_with_nutation, position_fast, ApparentPosition, STANDARD_SERIES_THRESHOLD,
+};
+use crate::util::arcsec::ArcSec;
+use crate::util::declination::Declination;
+use crate::util::degrees::Degrees;
+use crate::util::right_ascension::RightAscension;
+use crate::{coordinates, ecliptic, sun};
+
+/// Time between the knots nutation is evaluated at, in days
+const KNOT_SPACING: f64 = 0.5;
+
+/// How the nutation is evaluated for each position
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum NutationEvaluation {
+    /// Evaluate the nutation series for each time, as the single position
+    /// functions do
+    Exact,
+
+    /// Evaluate the nutation series at knots half a day apart and
+    /// interpolate it linearly, accurate to about 0.003"
+    Interpolated,
+}
+
+/// Apparent geocentric positions of the Sun and the Moon at one time,
+/// referred to the true equinox of the date
+#[derive(Debug, Copy, Clone)]
+pub struct Positions {
+    /// Julian day, in dynamical time
+    pub jd: JD,
+
+    pub moon: GeocentricMoon,
+
+    /// Sun's ecliptical coordinates and distance. For Precision::Low, the
+    /// latitude is not computed and always 0
+    pub sun: ApparentPosition,
+
+    /// Sun's right ascension, in degrees [0, 360)
+    pub sun_ra: RightAscension,
+
+    /// Sun's declination, in degrees [-90, 90)
+    pub sun_dec: Declination,
+}
+
+/// Nutation in longitude and in obliquity, in arcsec, at evenly spaced knots
+struct NutationKnots {
+    /// Julian day of the first knot, in dynamical time
+    start: f64,
+
+    values: Vec<(f64, f64)>,
+}
+
+impl NutationKnots {
+    /// In: first and last Julian day to interpolate for, in dynamical time
+    fn new(first: f64, last: f64) -> Self {
+        // SS: one more knot, so the last time is between two knots
+        let n = ((last - first) / KNOT_SPACING).floor() as usize + 2;
+        let values = (0..n)
+            .map(|i| {
+                let jd = JD::new(first + i as f64 * KNOT_SPACING);
+                let (delta_psi, delta_epsilon) = NutationModel::Full.nutation(jd);
+                (delta_psi.0, delta_epsilon.0)
+            })
+            .collect();
+        Self {
+            start: first,
+            values,
+        }
+    }
+
+    /// In: Julian day, in dynamical time, between the first and last knot
+    /// Out: nutation in longitude and in obliquity, in arcsec
+    fn nutation(&self, jd: JD) -> (ArcSec, ArcSec) {
+        let x = (jd.jd - self.start) / KNOT_SPACING;
+        let idx = (x.floor() as usize).min(self.values.len() - 2);
+        let fraction = x - idx as f64;
+
+        let (psi_0, epsilon_0) = self.values[idx];
+        let (psi_1, epsilon_1) = self.values[idx + 1];
+        (
+            ArcSec::new(psi_0 + fraction * (psi_1 - psi_0)),
+            ArcSec::new(epsilon_0 + fraction * (epsilon_1 - epsilon_0)),
+        )
+    }
+}
+
+/// Calculate the apparent geocentric positions of the Sun and the Moon
+/// In:
+/// jds: Julian days, in dynamical time, in any order
+/// precision: Sun's position from the full VSOP87 series for High, the
+/// truncated series for Standard and the low accuracy formulas of Meeus,
+/// chapter 25, for Low, which do not use the nutation
+/// nutation: how to evaluate the nutation
+/// Out: positions, in the order of jds
+pub fn positions(jds: &[JD], precision: Precision, nutation: NutationEvaluation) -> Vec<Positions> {
+    let knots = match nutation {
+        NutationEvaluation::Exact => None,
+        NutationEvaluation::Interpolated if jds.is_empty() => None,
+        NutationEvaluation::Interpolated => {
+            let first = jds.iter().map(|jd| jd.jd).fold(f64::INFINITY, f64::min);
+            let last = jds.iter().map(|jd| jd.jd).fold(f64::NEG_INFINITY, f64::max);
+            Some(NutationKnots::new(first, last))
+        }
+    };
+
+    jds.iter()
+        .map(|&jd| {
+            let (delta_psi, delta_epsilon) = match &knots {
+                Some(knots) => knots.nutation(jd),
+                None => NutationModel::Full.nutation(jd),
+            };
+            let eps = ecliptic::mean_obliquity(jd) + Degrees::from(delta_epsilon);
+
+            let moon = geocentric_with_nutation(jd, delta_psi, eps);
+
+            let (sun, (sun_ra, sun_dec)) = match precision {
+                Precision::Low => (
+                    position_fast(jd),
+                    sun::position::equatorial_coordinates_fast(jd),
+                ),
+                Precision::Standard | Precision::High => {
+                    let threshold = match precision {
+                        Precision::High => 0.0,
+                        _ => STANDARD_SERIES_THRESHOLD,
+                    };
+                    let sun = apparent_position_with_nutation(jd, threshold, delta_psi);
+                    let equatorial =
+                        coordinates::ecliptical_2_equatorial(sun.longitude, sun.latitude, eps);
+                    (sun, equatorial)
+                }
+            };
+
+            Positions {
+                jd,
+                moon,
+                sun,
+                sun_ra,
+                sun_dec,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon;
+    use assert_approx_eq::assert_approx_eq;
+
+    /// Times every 10 minutes for a number of days
+    fn times(days: usize) -> Vec<JD> {
+        let start = JD::from_date(Date::new(2024, 3, 1.0));
+        (0..days * 144)
+            .map(|i| JD::new(start.jd + i as f64 / 144.0))
+            .collect()
+    }
+
+    #[test]
+    fn exact_test() {
+        // Arrange
+        let jds = times(2);
+
+        // Act
+        let positions = positions(&jds, Precision::High, NutationEvaluation::Exact);
+
+        // Assert
+
+        // SS: the same as the single position functions
+        assert_eq!(jds.len(), positions.len());
+        for positions in positions.iter().step_by(37) {
+            let moon = moon::position::geocentric(positions.jd);
+            let (sun_ra, sun_dec) = sun::position::equatorial_coordinates(positions.jd);
+            assert_eq!(moon.lambda.0, positions.moon.lambda.0);
+            assert_eq!(moon.ra.0, positions.moon.ra.0);
+            assert_eq!(moon.dec.0, positions.moon.dec.0);
+            assert_approx_eq!(sun_ra.0, positions.sun_ra.0, 1e-12);
+            assert_approx_eq!(sun_dec.0, positions.sun_dec.0, 1e-12);
+        }
+    }
+
+    #[test]
+    fn interpolated_test() {
+        // Arrange
+        let jds = times(30);
+
+        // Act
+        let exact = positions(&jds, Precision::Standard, NutationEvaluation::Exact);
+        let interpolated = positions(&jds, Precision::Standard, NutationEvaluation::Interpolated);
+
+        // Assert
+        let arcsec = |a: f64, b: f64| ((a - b) * 3600.0).abs();
+        let max_error = exact
+            .iter()
+            .zip(&interpolated)
+            .map(|(exact, interpolated)| {
+                arcsec(exact.moon.lambda.0, interpolated.moon.lambda.0)
+                    .max(arcsec(exact.moon.ra.0, interpolated.moon.ra.0))
+                    .max(arcsec(exact.moon.dec.0, interpolated.moon.dec.0))
+                    .max(arcsec(exact.sun.longitude.0, interpolated.sun.longitude.0))
+                    .max(arcsec(exact.sun_ra.0, interpolated.sun_ra.0))
+                    .max(arcsec(exact.sun_dec.0, interpolated.sun_dec.0))
+            })
+            .fold(0.0, f64::max);
+        assert!(max_error < 0.005, "{max_error}");
+    }
+
+    #[test]
+    fn interpolated_unordered_test() {
+        // Arrange
+        let mut jds = times(3);
+        jds.reverse();
+        jds.push(JD::new(jds[17].jd));
+
+        // Act
+        let positions = positions(&jds, Precision::High, NutationEvaluation::Interpolated);
+
+        // Assert
+        assert_eq!(jds.len(), positions.len());
+        for (jd, positions) in jds.iter().zip(&positions) {
+            assert_eq!(jd.jd, positions.jd.jd);
+            let moon = moon::position::geocentric(*jd);
+            assert_approx_eq!(moon.lambda.0, positions.moon.lambda.0, 0.005 / 3600.0);
+        }
+    }
+
+    #[test]
+    fn empty_test() {
+        // Act
+        let positions = positions(&[], Precision::High, NutationEvaluation::Interpolated);
+
+        // Assert
+        assert!(positions.is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+//! A day's precomputed ephemeris, i.e. the Moon's position every few minutes
+//! and its events, in a compact binary format. The app computes it once a
+//! day in a background job, and the widget restores it after a process
+//! restart instead of recalculating it.
+//!
+//! The format is bincode, with a version in front. Caches written with a
+//! different version are rejected, the app then recomputes the day.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::date::jd::JD;
+use crate::moon;
+use crate::rise_set_transit::OutputKind;
+use crate::session::{Observer, Session};
+use crate::time::{self, LeapSeconds};
+
+/// Version of the format, increased for incompatible changes
+pub const EPHEMERIS_CACHE_VERSION: u8 = 1;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EphemerisCacheError {
+    /// The bytes are not a cache, or were cut off
+    Corrupt(String),
+
+    /// The cache was written with a different format
+    UnsupportedVersion(u8),
+
+    /// Step of 0 minutes, or more than a day
+    InvalidStep(u32),
+}
+
+impl fmt::Display for EphemerisCacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EphemerisCacheError::Corrupt(reason) => write!(f, "corrupt ephemeris cache: {reason}"),
+            EphemerisCacheError::UnsupportedVersion(version) => {
+                write!(f, "unsupported ephemeris cache version {version}")
+            }
+            EphemerisCacheError::InvalidStep(minutes) => {
+                write!(f, "invalid step of {minutes} minutes")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EphemerisCacheError {}
+
+/// The Moon's position at one time of the day
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPosition {
+    /// Apparent topocentric altitude, in degrees [-90, 90]
+    pub altitude: f64,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: f64,
+
+    /// Distance from Earth, in kilometers
+    pub distance: f64,
+
+    /// [0, 1]
+    pub illuminated_fraction: f64,
+}
+
+/// The Moon's positions and events for one day and observer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayEphemeris {
+    /// Observer the day was computed for: longitude, positive west, and
+    /// latitude, in degrees, height above sea level, in meters
+    pub longitude: f64,
+    pub latitude: f64,
+    pub height: f64,
+
+    /// Start of the day, in Unix milliseconds
+    pub start: i64,
+
+    /// Minutes between positions
+    pub step_minutes: u32,
+
+    /// Positions every step_minutes from the start of the day, up to its
+    /// end
+    pub positions: Vec<CachedPosition>,
+
+    /// in Unix milliseconds, if the Moon rises on the day
+    pub rise: Option<i64>,
+
+    /// in Unix milliseconds, if the Moon sets on the day
+    pub set: Option<i64>,
+
+    /// in Unix milliseconds, if the Moon transits on the day
+    pub transit: Option<i64>,
+}
+
+impl DayEphemeris {
+    /// Compute a day's ephemeris
+    /// In:
+    /// observer: Observer's location and atmospheric conditions
+    /// height: Observer's height above sea level, in meters
+    /// day: Julian Day of the start of the day, in UTC
+    /// step_minutes: minutes between positions, [1, 1440]
+    /// Out: Ephemeris, or an error for an invalid step
+    pub fn compute(
+        observer: &Observer,
+        height: f64,
+        day: JD,
+        step_minutes: u32,
+    ) -> Result<Self, EphemerisCacheError> {
+        if step_minutes == 0 || step_minutes > MINUTES_PER_DAY {
+            return Err(EphemerisCacheError::InvalidStep(step_minutes));
+        }
+
+        let positions = (0..=MINUTES_PER_DAY / step_minutes)
+            .map(|idx| {
+                let mut jd = day;
+                jd.add_hours((idx * step_minutes) as f64 / 60.0);
+                let tt = time::utc_2_tt(jd);
+                let topocentric = moon::position::topocentric_coordinates(tt, observer, height);
+                CachedPosition {
+                    altitude: topocentric.apparent_altitude.0,
+                    azimuth: topocentric.azimuth.0,
+                    distance: moon::position::distance_from_earth(tt),
+                    illuminated_fraction: moon::phase::fraction_illuminated(tt),
+                }
+            })
+            .collect();
+
+        let session = Session::new(*observer, day, day);
+        let moon = session.moon();
+        let unix_millis = |event: OutputKind| match event {
+            OutputKind::Time(tt) => Some(time::tt_2_utc(tt, &LeapSeconds::iers()).to_unix_millis()),
+            OutputKind::NeverRises | OutputKind::NeverSets | OutputKind::NeverTransits => None,
+        };
+
+        Ok(Self {
+            longitude: observer.longitude.0,
+            latitude: observer.latitude.0,
+            height,
+            start: day.to_unix_millis(),
+            step_minutes,
+            positions,
+            rise: unix_millis(moon.rise()[0]),
+            set: unix_millis(moon.set()[0]),
+            transit: unix_millis(moon.transit()[0]),
+        })
+    }
+
+    /// Whether the cache was computed for an observer and day, e.g. to
+    /// recompute it after the observer moved
+    /// In:
+    /// observer: Observer's location
+    /// day: Julian Day of the start of the day, in UTC
+    /// Out: true, if the cache is for the same location and day
+    pub fn is_for(&self, observer: &Observer, day: JD) -> bool {
+        self.longitude == observer.longitude.0
+            && self.latitude == observer.latitude.0
+            && self.start == day.to_unix_millis()
+    }
+
+    /// Position at a time of the day, linearly interpolated between the
+    /// cached positions
+    /// In: time, in Unix milliseconds
+    /// Out: Position, None if the time is not on the day
+    pub fn position_at(&self, unix_millis: i64) -> Option<CachedPosition> {
+        let step = self.step_minutes as f64 * 60_000.0;
+        let x = (unix_millis - self.start) as f64 / step;
+        if x < 0.0 || x > (self.positions.len() - 1) as f64 {
+            return None;
+        }
+
+        let idx = (x.floor() as usize).min(self.positions.len() - 2);
+        let t = x - idx as f64;
+        let (a, b) = (self.positions[idx], self.positions[idx + 1]);
+        let lerp = |a: f64, b: f64| a + t * (b - a);
+
+        // SS: the azimuth wraps around from 360 to 0
+        let mut azimuth_b = b.azimuth;
+        if azimuth_b - a.azimuth > 180.0 {
+            azimuth_b -= 360.0;
+        } else if a.azimuth - azimuth_b > 180.0 {
+            azimuth_b += 360.0;
+        }
+
+        Some(CachedPosition {
+            altitude: lerp(a.altitude, b.altitude),
+            azimuth: lerp(a.azimuth, azimuth_b).rem_euclid(360.0),
+            distance: lerp(a.distance, b.distance),
+            illuminated_fraction: lerp(a.illuminated_fraction, b.illuminated_fraction),
+        })
+    }
+
+    /// Encode, with the version in front
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![EPHEMERIS_CACHE_VERSION];
+
+        // SS: serializing into memory fails only for types bincode cannot
+        // represent, which this one is not
+        bytes.extend(bincode::serialize(self).expect("serializable"));
+        bytes
+    }
+
+    /// Decode
+    /// In: bytes written by encode
+    /// Out: the ephemeris, or an error if the version does not match or the
+    /// bytes are corrupt
+    pub fn decode(bytes: &[u8]) -> Result<Self, EphemerisCacheError> {
+        let (&version, payload) = bytes
+            .split_first()
+            .ok_or_else(|| EphemerisCacheError::Corrupt("empty".to_string()))?;
+        if version != EPHEMERIS_CACHE_VERSION {
+            return Err(EphemerisCacheError::UnsupportedVersion(version));
+        }
+
+        let ephemeris: Self = bincode::deserialize(payload)
+            .map_err(|error| EphemerisCacheError::Corrupt(error.to_string()))?;
+
+        // SS: position_at needs at least two positions
+        if ephemeris.step_minutes == 0 || ephemeris.positions.len() < 2 {
+            return Err(EphemerisCacheError::Corrupt(
+                "too few positions".to_string(),
+            ));
+        }
+        Ok(ephemeris)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::test_util::munich;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn day() -> JD {
+        JD::from_date(Date::new(2024, 3, 20.0))
+    }
+
+    #[test]
+    fn round_trip_test() {
+        // Arrange
+        let ephemeris = DayEphemeris::compute(&munich(1), 520.0, day(), 10).unwrap();
+
+        // Act
+        let bytes = ephemeris.encode();
+        let decoded = DayEphemeris::decode(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(ephemeris, decoded);
+        assert_eq!(145, decoded.positions.len());
+
+        // SS: 4 values of 8 bytes per position
+        assert!(bytes.len() < 145 * 32 + 100, "{}", bytes.len());
+    }
+
+    #[test]
+    fn events_test() {
+        // Arrange
+        let session = Session::new(munich(1), day(), day());
+
+        // Act
+        let ephemeris = DayEphemeris::compute(&munich(1), 0.0, day(), 60).unwrap();
+
+        // Assert
+        let expected = match session.moon().rise()[0] {
+            OutputKind::Time(tt) => time::tt_2_utc(tt, &LeapSeconds::iers()).to_unix_millis(),
+            _ => panic!("the Moon rises on the day"),
+        };
+        assert_eq!(Some(expected), ephemeris.rise);
+        assert!(ephemeris.set.is_some());
+        assert!(ephemeris.transit.is_some());
+    }
+
+    #[test]
+    fn position_at_test() {
+        // Arrange
+        let ephemeris = DayEphemeris::compute(&munich(1), 0.0, day(), 5).unwrap();
+        let mut jd = day();
+        jd.add_hours(19.0 + 7.0 / 60.0);
+
+        // Act
+        let position = ephemeris.position_at(jd.to_unix_millis()).unwrap();
+
+        // Assert
+        let expected = moon::position::topocentric_coordinates(time::utc_2_tt(jd), &munich(1), 0.0);
+        assert_approx_eq!(expected.apparent_altitude.0, position.altitude, 0.01);
+        assert_approx_eq!(expected.azimuth.0, position.azimuth, 0.02);
+    }
+
+    #[test]
+    fn position_outside_day_test() {
+        // Arrange
+        let ephemeris = DayEphemeris::compute(&munich(1), 0.0, day(), 30).unwrap();
+        let start = day().to_unix_millis();
+
+        // Act
+        let before = ephemeris.position_at(start - 1);
+        let end = ephemeris.position_at(start + 24 * 3_600_000);
+        let after = ephemeris.position_at(start + 24 * 3_600_000 + 1);
+
+        // Assert
+        assert!(before.is_none());
+        assert_eq!(Some(ephemeris.positions[48]), end);
+        assert!(after.is_none());
+    }
+
+    #[test]
+    fn is_for_test() {
+        // Arrange
+        let ephemeris = DayEphemeris::compute(&munich(1), 0.0, day(), 60).unwrap();
+        let next_day = JD::new(day().jd + 1.0);
+        let berlin = Observer::new(-13.4, 52.5, 1, 1013.0, 10.0);
+
+        // Act & Assert
+        assert!(ephemeris.is_for(&munich(1), day()));
+        assert!(!ephemeris.is_for(&munich(1), next_day));
+        assert!(!ephemeris.is_for(&berlin, day()));
+    }
+
+    #[test]
+    fn decode_error_test() {
+        // Arrange
+        let mut bytes = DayEphemeris::compute(&munich(1), 0.0, day(), 60)
+            .unwrap()
+            .encode();
+
+        // Act
+        let truncated = DayEphemeris::decode(&bytes[..bytes.len() / 2]);
+        let empty = DayEphemeris::decode(&[]);
+        bytes[0] = EPHEMERIS_CACHE_VERSION + 1;
+        let version = DayEphemeris::decode(&bytes);
+
+        // Assert
+        assert!(matches!(truncated, Err(EphemerisCacheError::Corrupt(_))));
+        assert!(matches!(empty, Err(EphemerisCacheError::Corrupt(_))));
+        assert_eq!(
+            Err(EphemerisCacheError::UnsupportedVersion(
+                EPHEMERIS_CACHE_VERSION + 1
+            )),
+            version
+        );
+    }
+
+    #[test]
+    fn invalid_step_test() {
+        // Act
+        let zero = DayEphemeris::compute(&munich(1), 0.0, day(), 0);
+        let too_long = DayEphemeris::compute(&munich(1), 0.0, day(), 2 * 24 * 60);
+
+        // Assert
+        assert_eq!(Err(EphemerisCacheError::InvalidStep(0)), zero);
+        assert_eq!(Err(EphemerisCacheError::InvalidStep(2 * 24 * 60)), too_long);
+    }
+}
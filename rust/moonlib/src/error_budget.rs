@@ -0,0 +1,181 @@
+//! Rounding of displayed event times to their uncertainty, so a time is not
+//! shown to the second when it is only known to the minute.
+//!
+//! The uncertainty of an event time is the sum of the uncertainty of the
+//! method, e.g. the refraction at the horizon, which changes with the
+//! weather, for rise and set, and the uncertainty of the models for the date,
+//! see validity::validity_report. Times are rounded to the smallest step of
+//! 1 s, 10 s, 1 min, 5 min, 10 min, 30 min and 1 h that is not smaller than
+//! the uncertainty.
+
+use crate::date::jd::JD;
+use crate::validity;
+
+/// Steps displayed times are rounded to, in seconds
+const STEPS: [f64; 7] = [1.0, 10.0, 60.0, 300.0, 600.0, 1800.0, 3600.0];
+
+/// Uncertainty of rise and set, in seconds. The refraction at the horizon
+/// varies by a few arcminutes with the weather, Meeus, chapter 16, and the
+/// Moon rises by about 0.2 degrees a minute
+const RISE_SET_UNCERTAINTY: f64 = 30.0;
+
+/// Uncertainty of a transit, in seconds. Refraction does not change the
+/// azimuth, so it is the uncertainty of the iteration
+const TRANSIT_UNCERTAINTY: f64 = 5.0;
+
+/// Uncertainty of perigee and apogee, in seconds. The distance hardly
+/// changes close to its extremes, so small errors of the lunar theory move
+/// them by minutes. The apogee of Meeus, example 50.a, is 2 minutes off
+const APSIS_UNCERTAINTY: f64 = 240.0;
+
+/// Earth's rotation rate relative to the stars, in degrees per second
+const EARTH_ROTATION_RATE: f64 = 360.0 / 86_164.090_5;
+
+/// Kind of event, for its uncertainty
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum EventKind {
+    /// Rise or set, and periods derived from them
+    RiseSet,
+
+    Transit,
+
+    /// Perigee or apogee
+    Apsis,
+}
+
+impl EventKind {
+    /// Uncertainty of the method, in seconds
+    fn uncertainty(self) -> f64 {
+        match self {
+            EventKind::RiseSet => RISE_SET_UNCERTAINTY,
+            EventKind::Transit => TRANSIT_UNCERTAINTY,
+            EventKind::Apsis => APSIS_UNCERTAINTY,
+        }
+    }
+}
+
+/// Estimate the uncertainty of an event time
+/// In:
+/// kind: Kind of event
+/// jd: Julian day, in dynamical time, of the day of the event
+/// Out: uncertainty, in seconds
+pub fn time_uncertainty(kind: EventKind, jd: JD) -> f64 {
+    // SS: the uncertainty of the Moon's position includes the one of
+    // Earth's rotation angle due to delta_t. The Moon moves through the
+    // observer's sky at about Earth's rotation rate
+    let report = validity::validity_report(jd);
+    kind.uncertainty() + report.position_uncertainty.0 / EARTH_ROTATION_RATE
+}
+
+/// Step to display a time with the uncertainty passed in
+/// In: uncertainty, in seconds
+/// Out: step, in seconds, at most an hour
+pub fn display_step(uncertainty: f64) -> f64 {
+    STEPS
+        .iter()
+        .copied()
+        .find(|&step| step >= uncertainty)
+        .unwrap_or(STEPS[STEPS.len() - 1])
+}
+
+/// Step to display an event time with
+/// In:
+/// kind: Kind of event
+/// jd: Julian day, in dynamical time, of the day of the event
+/// Out: step, in seconds
+pub fn event_display_step(kind: EventKind, jd: JD) -> f64 {
+    display_step(time_uncertainty(kind, jd))
+}
+
+/// Round a time to a step
+/// In:
+/// jd: Julian day
+/// step: in seconds
+/// Out: Julian day, rounded to the nearest multiple of step since the start
+/// of the day
+pub fn round_to_step(jd: JD, step: f64) -> JD {
+    // SS: Julian days start at noon, all steps divide 12 hours
+    let seconds = jd.jd * 86_400.0;
+    JD::new((seconds / step).round() * step / 86_400.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn present_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2024, 3, 20.0));
+
+        // Act
+        let rise_set = event_display_step(EventKind::RiseSet, jd);
+        let transit = event_display_step(EventKind::Transit, jd);
+        let apsis = event_display_step(EventKind::Apsis, jd);
+
+        // Assert
+        assert_eq!(60.0, rise_set);
+        assert_eq!(10.0, transit);
+        assert_eq!(300.0, apsis);
+    }
+
+    #[test]
+    fn historical_test() {
+        // Arrange
+
+        // SS: delta_t is uncertain by about 7 minutes in 500 BCE
+        let jd = JD::from_date(Date::new(-500, 3, 20.0));
+
+        // Act
+        let uncertainty = time_uncertainty(EventKind::Transit, jd);
+        let step = event_display_step(EventKind::Transit, jd);
+
+        // Assert
+        let delta_t_uncertainty = validity::validity_report(jd).delta_t_uncertainty;
+        assert!(uncertainty > delta_t_uncertainty);
+        assert_approx_eq!(TRANSIT_UNCERTAINTY + delta_t_uncertainty, uncertainty, 1.0);
+        assert_eq!(600.0, step);
+    }
+
+    #[test]
+    fn display_step_test() {
+        // Act
+        let steps = [0.0, 1.0, 1.5, 30.0, 61.0, 3600.0, 1e6].map(display_step);
+
+        // Assert
+        assert_eq!([1.0, 1.0, 10.0, 60.0, 300.0, 3600.0, 3600.0], steps);
+    }
+
+    #[test]
+    fn round_to_step_test() {
+        // Arrange
+
+        // SS: 2024 March 20, 06:29:31 UTC
+        let jd = JD::from_date(Date::from_date_hms(2024, 3, 20, 6, 29, 31.0));
+
+        // Act
+        let minute = round_to_step(jd, 60.0);
+        let ten_seconds = round_to_step(jd, 10.0);
+        let half_hour = round_to_step(jd, 1800.0);
+
+        // Assert
+        let seconds = |jd: JD| jd.jd * 86_400.0;
+        assert_approx_eq!(
+            seconds(JD::from_date(Date::from_date_hms(2024, 3, 20, 6, 30, 0.0))),
+            seconds(minute),
+            1e-3
+        );
+        assert_approx_eq!(
+            seconds(JD::from_date(Date::from_date_hms(2024, 3, 20, 6, 29, 30.0))),
+            seconds(ten_seconds),
+            1e-3
+        );
+        assert_approx_eq!(
+            seconds(JD::from_date(Date::from_date_hms(2024, 3, 20, 6, 30, 0.0))),
+            seconds(half_hour),
+            1e-3
+        );
+    }
+}
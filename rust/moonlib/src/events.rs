@@ -0,0 +1,277 @@
+//! Search for upcoming events, e.g. for notifications.
+//!
+//! Close approaches: the Moon passes the Sun once a month, which is the time
+//! to look for the thin crescent in daylight. The separation of two bodies is
+//! sampled forward in time, local minima between samples are found with a
+//! golden-section search and the time the separation drops below the threshold
+//! is refined by bisection.
+
+use crate::body::CelestialBody;
+use crate::coordinates;
+use crate::date::jd::JD;
+use crate::util::degrees::Degrees;
+
+/// Time between samples of the separation, in days. The Moon moves about
+/// 3 degrees relative to the Sun in this time
+const SEARCH_STEP: f64 = 0.25;
+
+/// How far to search ahead, in days. Longer than a synodic month, so the
+/// Moon passes every body at least once
+const SEARCH_LIMIT: f64 = 35.0;
+
+/// Precision of the event time, in days, about 1 second
+const TOLERANCE: f64 = 1.0 / 86400.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Body {
+    Moon,
+    Sun,
+}
+
+/// Geocentric angular separation of two bodies
+/// In:
+/// body_a, body_b: the two bodies, e.g. Body::Moon or body::Moon
+/// jd: Julian day, in dynamical time
+/// Out: Angular separation, in degrees [0, 180]
+pub fn separation<A: CelestialBody, B: CelestialBody>(body_a: A, body_b: B, jd: JD) -> Degrees {
+    let (alpha_a, delta_a) = body_a.equatorial_coordinates(jd);
+    let (alpha_b, delta_b) = body_b.equatorial_coordinates(jd);
+    coordinates::angular_separation(alpha_a, delta_a, alpha_b, delta_b)
+}
+
+/// Find the next time two bodies come within a given angular distance of
+/// each other, e.g. the Moon within 10 degrees of the Sun.
+/// In:
+/// body_a, body_b: the two bodies
+/// max_separation: angular distance, in degrees
+/// jd: Julian day to start searching at, in dynamical time
+/// Out: Julian day, in dynamical time, when the separation drops to
+/// max_separation, jd itself if the bodies are already close enough, or None
+/// if they do not come that close within the next 35 days
+pub fn next_close_approach<A: CelestialBody, B: CelestialBody>(
+    body_a: A,
+    body_b: B,
+    max_separation: Degrees,
+    jd: JD,
+) -> Option<JD> {
+    let separation = |t: f64| separation(&body_a, &body_b, JD::new(t)).0;
+    let max_separation = max_separation.0;
+
+    let mut t0 = jd.jd;
+    let mut s0 = separation(t0);
+    if s0 <= max_separation {
+        return Some(jd);
+    }
+
+    // SS: separation at the previous sample, to detect a local minimum
+    // between samples
+    let mut previous: Option<(f64, f64)> = None;
+
+    while t0 < jd.jd + SEARCH_LIMIT {
+        let t1 = t0 + SEARCH_STEP;
+        let s1 = separation(t1);
+
+        if s1 <= max_separation {
+            return Some(JD::new(bisect(&separation, max_separation, t0, t1)));
+        }
+
+        if let Some((t_previous, s_previous)) = previous {
+            if s_previous > s0 && s0 < s1 {
+                // SS: the separation has a minimum in [t_previous, t1],
+                // which may dip below the threshold between the samples
+                let t_min = minimize(&separation, t_previous, t1);
+                if separation(t_min) <= max_separation {
+                    return Some(JD::new(bisect(
+                        &separation,
+                        max_separation,
+                        t_previous,
+                        t_min,
+                    )));
+                }
+            }
+        }
+
+        previous = Some((t0, s0));
+        t0 = t1;
+        s0 = s1;
+    }
+
+    None
+}
+
+/// Find the time the separation drops to the threshold by bisection
+/// In:
+/// separation: separation as a function of time
+/// threshold: in degrees
+/// t0, t1: Julian days, with the separation above the threshold at t0 and
+/// at or below it at t1
+/// Out: Julian day
+fn bisect(separation: &impl Fn(f64) -> f64, threshold: f64, mut t0: f64, mut t1: f64) -> f64 {
+    while t1 - t0 > TOLERANCE {
+        let t = 0.5 * (t0 + t1);
+        if separation(t) <= threshold {
+            t1 = t;
+        } else {
+            t0 = t;
+        }
+    }
+    t1
+}
+
+/// Find the minimum of the separation by golden-section search
+/// In:
+/// separation: separation as a function of time, with a single minimum in
+/// [t0, t1]
+/// t0, t1: Julian days
+/// Out: Julian day of the minimum
+fn minimize(separation: &impl Fn(f64) -> f64, mut t0: f64, mut t1: f64) -> f64 {
+    // SS: 1 / golden ratio
+    let r = 0.5 * (5.0_f64.sqrt() - 1.0);
+
+    let mut a = t1 - r * (t1 - t0);
+    let mut b = t0 + r * (t1 - t0);
+    let mut sa = separation(a);
+    let mut sb = separation(b);
+
+    while t1 - t0 > TOLERANCE {
+        if sa < sb {
+            t1 = b;
+            b = a;
+            sb = sa;
+            a = t1 - r * (t1 - t0);
+            sa = separation(a);
+        } else {
+            t0 = a;
+            a = b;
+            sa = sb;
+            b = t0 + r * (t1 - t0);
+            sb = separation(b);
+        }
+    }
+
+    0.5 * (t0 + t1)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::body;
+    use crate::date::date::Date;
+
+    /// New moon of Feb. 18th, 1977, 3h37m42s TD, Meeus, example 49.a
+    const NEW_MOON: f64 = 2_443_192.651_18;
+
+    /// Smallest separation of Moon and Sun around the new moon, sampled
+    /// every minute
+    fn minimum_separation() -> f64 {
+        (-720..=720)
+            .map(|minute| {
+                let jd = JD::new(NEW_MOON + minute as f64 / 1440.0);
+                separation(Body::Moon, Body::Sun, jd).0
+            })
+            .fold(f64::MAX, f64::min)
+    }
+
+    #[test]
+    fn next_close_approach_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1977, 2, 10.0));
+
+        // Act
+        let approach = next_close_approach(Body::Moon, Body::Sun, Degrees::new(10.0), jd).unwrap();
+
+        // Assert
+
+        // SS: the Moon moves about 12 degrees a day relative to the Sun
+        assert!(approach.jd < NEW_MOON);
+        assert!(approach.jd > NEW_MOON - 1.5);
+        assert_approx_eq!(10.0, separation(Body::Moon, Body::Sun, approach).0, 0.001);
+        assert!(
+            separation(
+                Body::Moon,
+                Body::Sun,
+                JD::new(approach.jd - 2.0 * TOLERANCE)
+            )
+            .0 > 10.0
+        );
+    }
+
+    #[test]
+    fn next_close_approach_symmetric_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1977, 2, 10.0));
+
+        // Act
+        let ab = next_close_approach(Body::Moon, Body::Sun, Degrees::new(10.0), jd).unwrap();
+        let ba = next_close_approach(Body::Sun, Body::Moon, Degrees::new(10.0), jd).unwrap();
+
+        // Assert
+        assert_eq!(ab, ba);
+    }
+
+    #[test]
+    fn next_close_approach_generic_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1977, 2, 1.0));
+
+        // Act
+        let approach = next_close_approach(body::Moon, body::Sun, Degrees::new(10.0), jd);
+
+        // Assert
+        assert_eq!(
+            next_close_approach(Body::Moon, Body::Sun, Degrees::new(10.0), jd),
+            approach
+        );
+    }
+
+    #[test]
+    fn next_close_approach_between_samples_test() {
+        // Arrange
+
+        // SS: the separation only drops below the threshold for a few
+        // minutes, between two samples
+        let jd = JD::from_date(Date::new(1977, 2, 10.0));
+        let max_separation = minimum_separation() + 0.001;
+
+        // Act
+        let approach =
+            next_close_approach(Body::Moon, Body::Sun, Degrees::new(max_separation), jd).unwrap();
+
+        // Assert
+        assert_approx_eq!(NEW_MOON, approach.jd, 0.1);
+        assert_approx_eq!(
+            max_separation,
+            separation(Body::Moon, Body::Sun, approach).0,
+            0.001
+        );
+    }
+
+    #[test]
+    fn next_close_approach_already_close_test() {
+        // Arrange
+        let jd = JD::new(NEW_MOON);
+
+        // Act
+        let approach = next_close_approach(Body::Moon, Body::Sun, Degrees::new(10.0), jd);
+
+        // Assert
+        assert_eq!(Some(jd), approach);
+    }
+
+    #[test]
+    fn next_close_approach_not_found_test() {
+        // Arrange
+
+        // SS: no solar eclipse at the new moons of Feb. and Mar. 1977, so
+        // the Moon passes the Sun at a distance of more than 1 degree
+        let jd = JD::from_date(Date::new(1977, 2, 10.0));
+
+        // Act
+        let approach = next_close_approach(Body::Moon, Body::Sun, Degrees::new(0.5), jd);
+
+        // Assert
+        assert_eq!(None, approach);
+    }
+}
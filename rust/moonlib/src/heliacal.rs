@@ -0,0 +1,230 @@
+//! Heliacal rising and setting of bright stars, i.e. the first morning a
+//! star is seen rising in the dawn after its conjunction with the Sun, and
+//! the last evening it is seen setting in the dusk before it.
+//!
+//! A star on the horizon is seen if the Sun is far enough below the
+//! horizon, by the arcus visionis. It depends on the star's brightness,
+//! about 11 degrees for a star of first magnitude and 1 degree more per
+//! magnitude fainter, after C. Schoch, Die Länge der Sichtbarkeitsbogen bei
+//! Mondsicheln und Sternen, 1924. This is a simple estimate. The weather,
+//! the extinction at the site and the observer's eyes move the dates by a
+//! few days.
+
+use crate::coordinates;
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::earth::{self, SiderialTime};
+use crate::precession::Equinox;
+use crate::rise_set_transit::{for_fixed_target, DayVisibility};
+use crate::session::Observer;
+use crate::sun;
+use crate::time::{self, LeapSeconds, ScaledJD, TimeScale};
+use crate::util::declination::Declination;
+use crate::util::degrees::Degrees;
+use crate::util::right_ascension::RightAscension;
+
+/// Arcus visionis of a star of magnitude 0, in degrees
+const ARCUS_VISIONIS: f64 = 10.0;
+
+/// Increase of the arcus visionis per magnitude, in degrees
+const ARCUS_VISIONIS_PER_MAGNITUDE: f64 = 1.0;
+
+/// Time to look ahead to tell whether the Sun rises, in hours
+const RISING_STEP: f64 = 1.0 / 60.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct HeliacalEvents {
+    /// Julian day of the star's rise on the morning of the heliacal rising,
+    /// in dynamical time. None if the star does not disappear in the Sun's
+    /// glare in the year, e.g. if it is circumpolar
+    pub rising: Option<JD>,
+
+    /// Julian day of the star's set on the evening of the heliacal setting,
+    /// in dynamical time
+    pub setting: Option<JD>,
+}
+
+/// Calculate the heliacal rising and setting of a star in a year
+/// In:
+/// ra: Right ascension, in degrees [0, 360)
+/// dec: Declination, in degrees [-90, 90]
+/// equinox: Equinox the coordinates are referred to, see
+/// rise_set_transit::for_fixed_target. Proper motion is ignored, which
+/// matters for millennia
+/// magnitude: Star's visual magnitude, e.g. -1.46 for Sirius
+/// observer: Observer's location and time zone offset
+/// year: Observer's local year, Julian calendar before 1582
+/// Out:
+/// Heliacal rising and setting in the year
+pub fn heliacal_events(
+    ra: RightAscension,
+    dec: Declination,
+    equinox: Equinox,
+    magnitude: f64,
+    observer: &Observer,
+    year: i16,
+) -> HeliacalEvents {
+    let depression = Degrees::new(-(ARCUS_VISIONIS + ARCUS_VISIONIS_PER_MAGNITUDE * magnitude));
+    let start = JD::from_date(Date::new(year, 1, 1.0));
+    let end = JD::from_date(Date::new(year + 1, 1, 1.0));
+    let days = (end.jd - start.jd).round() as i32;
+
+    // SS: one day before and after the year, to tell whether the star is
+    // seen on the first and last day for the first time
+    let visible: Vec<(Option<JD>, Option<JD>)> = (-1..=days)
+        .map(|day| {
+            let date = JD::new(start.jd + day as f64).to_calendar_date();
+            let events = for_fixed_target(ra, dec, equinox, observer, date);
+            match events.visibility {
+                DayVisibility::RisesAndSets { rise, set } => (
+                    is_seen(rise, depression, true, observer).then_some(rise),
+                    is_seen(set, depression, false, observer).then_some(set),
+                ),
+                _ => (None, None),
+            }
+        })
+        .collect();
+
+    // SS: the star rises 4 minutes earlier each day. It is first seen when
+    // it rises before the dawn, and last seen in the morning when it rises
+    // before the Sun's lower culmination, i.e. in the evening. Likewise, it
+    // is first seen setting in the evening when it sets before the Sun's
+    // lower culmination, and last seen when it sets in the dusk
+    let rising =
+        visible
+            .windows(2)
+            .take(days as usize)
+            .find_map(|pair| match (pair[0].0, pair[1].0) {
+                (None, Some(rise)) => Some(rise),
+                _ => None,
+            });
+    let setting = visible
+        .windows(2)
+        .skip(1)
+        .find_map(|pair| match (pair[0].1, pair[1].1) {
+            (Some(set), None) => Some(set),
+            _ => None,
+        });
+
+    HeliacalEvents { rising, setting }
+}
+
+/// Whether a star on the horizon is seen
+/// In:
+/// jd: Julian day of the star's rise or set, in dynamical time
+/// depression: Altitude of the Sun, in degrees, the star is seen below
+/// morning: true to check for a star rising in the morning, false for one
+/// setting in the evening
+/// observer: Observer's location
+/// Out: true, if the Sun is low enough, and rising in the morning and
+/// setting in the evening
+fn is_seen(jd: JD, depression: Degrees, morning: bool, observer: &Observer) -> bool {
+    let altitude = sun_altitude(jd, observer);
+    let mut later = jd;
+    later.add_hours(RISING_STEP);
+    let is_rising = sun_altitude(later, observer).0 > altitude.0;
+    altitude.0 <= depression.0 && is_rising == morning
+}
+
+/// Altitude of the Sun's center, without refraction
+/// In:
+/// jd: Julian day, in dynamical time
+/// observer: Observer's location
+/// Out: altitude, in degrees [-90, 90)
+fn sun_altitude(jd: JD, observer: &Observer) -> Degrees {
+    // SS: the siderial time for UTC, as the star's rise and set were
+    // calculated in UTC
+    let utc = time::tt_2_utc(jd, &LeapSeconds::iers());
+    let siderial_time =
+        earth::siderial_time(ScaledJD::new(utc, TimeScale::UTC), SiderialTime::Apparent);
+    let (ra, dec) = sun::position::equatorial_coordinates(jd);
+    let hour_angle = earth::hour_angle(
+        earth::local_siderial_time(siderial_time, observer.longitude),
+        ra,
+    );
+    coordinates::equatorial_2_horizontal(dec, hour_angle, observer.latitude).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sirius() -> (RightAscension, Declination) {
+        // SS: J2000.0
+        (
+            RightAscension::from_hms(6, 45, 8.917),
+            Declination::from_dms(-16, 42, 58.02),
+        )
+    }
+
+    /// Day of the year of a Julian day, 1 on January 1
+    fn day_of_year(jd: JD, year: i16) -> f64 {
+        jd.jd - JD::from_date(Date::new(year, 1, 1.0)).jd + 1.0
+    }
+
+    #[test]
+    fn sirius_cairo_test() {
+        // Arrange
+        let (ra, dec) = sirius();
+        let cairo = Observer::new(-31.2, 30.0, 2, 1013.0, 20.0);
+
+        // Act
+        let events = heliacal_events(ra, dec, Equinox::J2000, -1.46, &cairo, 2024);
+
+        // Assert
+
+        // SS: Sirius is first seen in the dawn in early August, after about
+        // 70 days in the Sun's glare since it was last seen in the dusk in
+        // late May
+        let rising = events.rising.unwrap();
+        let setting = events.setting.unwrap();
+        let rising_day = day_of_year(rising, 2024);
+        let setting_day = day_of_year(setting, 2024);
+        assert!(214.0 < rising_day && rising_day < 230.0, "{rising_day}");
+        assert!(130.0 < setting_day && setting_day < 160.0, "{setting_day}");
+        assert!(
+            50.0 < rising_day - setting_day && rising_day - setting_day < 90.0,
+            "{setting_day} {rising_day}"
+        );
+
+        // SS: at the heliacal rising, the Sun is below the arcus visionis
+        assert!(sun_altitude(rising, &cairo).0 < -8.5);
+    }
+
+    #[test]
+    fn magnitude_test() {
+        // Arrange
+        let (ra, dec) = sirius();
+        let cairo = Observer::new(-31.2, 30.0, 2, 1013.0, 20.0);
+
+        // Act
+        let bright = heliacal_events(ra, dec, Equinox::J2000, -1.46, &cairo, 2024);
+        let faint = heliacal_events(ra, dec, Equinox::J2000, 3.0, &cairo, 2024);
+
+        // Assert
+
+        // SS: a fainter star needs a darker sky, so it is seen later after
+        // and earlier before the conjunction
+        assert!(faint.rising.unwrap() > bright.rising.unwrap());
+        assert!(faint.setting.unwrap() < bright.setting.unwrap());
+    }
+
+    #[test]
+    fn circumpolar_test() {
+        // Arrange
+
+        // SS: Capella never sets in Tromsø, Norway
+        let capella = (
+            RightAscension::from_hms(5, 16, 41.36),
+            Declination::from_dms(45, 59, 52.8),
+        );
+        let tromso = Observer::new(-18.96, 69.65, 1, 1013.0, 10.0);
+
+        // Act
+        let events = heliacal_events(capella.0, capella.1, Equinox::J2000, 0.08, &tromso, 2024);
+
+        // Assert
+        assert!(events.rising.is_none());
+        assert!(events.setting.is_none());
+    }
+}
@@ -0,0 +1,171 @@
+//! The observer's horizon, i.e. the altitude of the skyline in each
+//! direction. Rise and set assume a flat horizon at 0 degrees, but from a
+//! ship's deck or a hill the sea horizon lies below it, and mountains or
+//! buildings raise it. From 10m above the sea, the dip of the horizon is
+//! about 5.6', which makes the Moon rise about half a minute earlier at
+//! mid-latitudes, and more than a minute earlier from higher up or further
+//! north.
+
+use crate::earth;
+use crate::util::degrees::Degrees;
+
+/// Altitude of the skyline as a function of the direction
+pub trait Horizon {
+    /// In: azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    /// Out: Apparent altitude of the skyline in this direction, in degrees [-90, 90]
+    fn altitude(&self, azimuth: Degrees) -> Degrees;
+}
+
+/// The astronomical horizon, at 0 degrees in every direction
+#[derive(Debug, Copy, Clone, Default)]
+pub struct FlatHorizon;
+
+impl Horizon for FlatHorizon {
+    fn altitude(&self, _azimuth: Degrees) -> Degrees {
+        Degrees::new(0.0)
+    }
+}
+
+/// Horizon given by a function of the azimuth
+impl<F> Horizon for F
+where
+    F: Fn(Degrees) -> Degrees,
+{
+    fn altitude(&self, azimuth: Degrees) -> Degrees {
+        self(azimuth)
+    }
+}
+
+/// Sea horizon seen from above the sea level, e.g. from a ship's deck. It
+/// is below the astronomical horizon by the dip, see earth::horizon_dip
+#[derive(Debug, Copy, Clone)]
+pub struct Dip {
+    /// Dip of the horizon, in degrees
+    dip: Degrees,
+}
+
+impl Dip {
+    /// In: height: Observer's height above the sea level, in meters
+    /// Out: Horizon, flat for a height of 0 or less
+    pub fn new(height: f64) -> Self {
+        Self {
+            dip: earth::horizon_dip(height),
+        }
+    }
+
+    /// Out: Dip of the horizon, in degrees
+    pub fn dip(&self) -> Degrees {
+        self.dip
+    }
+}
+
+impl Horizon for Dip {
+    fn altitude(&self, _azimuth: Degrees) -> Degrees {
+        Degrees::new(-self.dip.0)
+    }
+}
+
+/// Skyline measured at a few azimuths, e.g. with a compass and an
+/// inclinometer, interpolated linearly between them. Between the last and
+/// the first azimuth, it is interpolated across North
+#[derive(Debug, Clone)]
+pub struct HorizonProfile {
+    points: Vec<(Degrees, Degrees)>,
+}
+
+impl HorizonProfile {
+    /// In: points, azimuth in degrees [0, 360) and apparent altitude of the
+    /// skyline there, in degrees [-90, 90]
+    /// Out: Profile, None if there are no points or the azimuths are not
+    /// increasing
+    pub fn new(points: Vec<(Degrees, Degrees)>) -> Option<Self> {
+        let in_range = points
+            .iter()
+            .all(|(azimuth, _)| (0.0..360.0).contains(&azimuth.0));
+        let in_order = points.windows(2).all(|pair| pair[0].0 .0 < pair[1].0 .0);
+        (!points.is_empty() && in_range && in_order).then_some(Self { points })
+    }
+}
+
+impl Horizon for HorizonProfile {
+    fn altitude(&self, azimuth: Degrees) -> Degrees {
+        let azimuth = azimuth.map_to_0_to_360().0;
+        let idx = self.points.partition_point(|(a, _)| a.0 <= azimuth);
+
+        // SS: before the first and after the last point, interpolate between
+        // the last and the first point, across North
+        let n = self.points.len();
+        let (azimuth_0, altitude_0) = self.points[(idx + n - 1) % n];
+        let (azimuth_1, altitude_1) = self.points[idx % n];
+        let width = (azimuth_1 - azimuth_0).map_to_0_to_360().0;
+        if width == 0.0 {
+            return altitude_0;
+        }
+
+        let fraction = (Degrees::new(azimuth) - azimuth_0).map_to_0_to_360().0 / width;
+        Degrees::new(altitude_0.0 + fraction * (altitude_1.0 - altitude_0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn dip_test() {
+        // Act
+        let sea_level = Dip::new(0.0);
+        let deck = Dip::new(10.0);
+
+        // Assert
+        assert_eq!(0.0, sea_level.altitude(Degrees::new(90.0)).0);
+
+        // SS: the dip is about 1.76' times the square root of the height in
+        // meters
+        assert_approx_eq!(
+            -1.76 * 10f64.sqrt() / 60.0,
+            deck.altitude(Degrees::new(90.0)).0,
+            0.001
+        );
+    }
+
+    #[test]
+    fn horizon_profile_test() {
+        // Arrange
+        let profile = HorizonProfile::new(vec![
+            (Degrees::new(10.0), Degrees::new(2.0)),
+            (Degrees::new(90.0), Degrees::new(6.0)),
+            (Degrees::new(330.0), Degrees::new(0.0)),
+        ])
+        .unwrap();
+
+        // Act
+        let between = profile.altitude(Degrees::new(50.0));
+        let on_point = profile.altitude(Degrees::new(90.0));
+        let across_north = profile.altitude(Degrees::new(0.0));
+        let before_north = profile.altitude(Degrees::new(350.0));
+
+        // Assert
+        assert_approx_eq!(4.0, between.0, 1e-12);
+        assert_approx_eq!(6.0, on_point.0, 1e-12);
+        assert_approx_eq!(1.5, across_north.0, 1e-12);
+        assert_approx_eq!(1.0, before_north.0, 1e-12);
+    }
+
+    #[test]
+    fn horizon_profile_invalid_test() {
+        // Act
+        let empty = HorizonProfile::new(vec![]);
+        let unordered = HorizonProfile::new(vec![
+            (Degrees::new(90.0), Degrees::new(1.0)),
+            (Degrees::new(10.0), Degrees::new(1.0)),
+        ]);
+        let out_of_range = HorizonProfile::new(vec![(Degrees::new(360.0), Degrees::new(1.0))]);
+
+        // Assert
+        assert!(empty.is_none());
+        assert!(unordered.is_none());
+        assert!(out_of_range.is_none());
+    }
+}
@@ -0,0 +1,776 @@
+//! Positions from the JPL DE ephemerides, e.g. DE440, read from a SPICE SPK
+//! kernel supplied at runtime. An excerpt of the kernel, e.g. cut to a few
+//! centuries with NAIF's spkmerge, keeps the file small.
+//!
+//! The DE ephemerides are accurate to the milliarcsecond, so they serve as
+//! ground truth for the series of Meeus used everywhere else in this crate.
+//!
+//! Only what the DE kernels contain is supported: little-endian DAF files
+//! with segments of type 2, i.e. Chebyshev polynomials for the position,
+//! referred to the ICRF (NAIF frame J2000). The time argument of the kernels
+//! is TDB, which differs from TT by less than 2 ms.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::date::epoch::Epoch;
+use crate::date::jd::JD;
+use crate::events::Body;
+use crate::util::{degrees::Degrees, math, radians::Radians};
+use crate::{constants, ecliptic};
+
+/// NAIF ids of the bodies and barycenters
+const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
+const SUN: i32 = 10;
+const MOON: i32 = 301;
+const EARTH: i32 = 399;
+
+/// NAIF id of the ICRF
+const FRAME_J2000: i32 = 1;
+
+/// SPK data type of Chebyshev polynomials for the position
+const CHEBYSHEV_POSITION: i32 = 2;
+
+/// Size of a DAF record, in bytes
+const RECORD_SIZE: usize = 1024;
+
+/// Speed of light, in km/s
+const SPEED_OF_LIGHT: f64 = 299_792.458;
+
+/// Time step for Earth's velocity, in seconds
+const VELOCITY_STEP: f64 = 60.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EphemerisError {
+    /// The file could not be read
+    Io(String),
+
+    /// The file is not a DAF/SPK file, or it is truncated
+    InvalidFile(String),
+
+    /// The file is in a binary format other than little-endian IEEE
+    UnsupportedFormat(String),
+
+    /// A segment is of a data type or in a frame other than those of the
+    /// DE kernels
+    UnsupportedSegment {
+        target: i32,
+        frame: i32,
+        data_type: i32,
+    },
+
+    /// No segment covers the body at the Julian Day, in dynamical time
+    NoData { target: i32, jd: f64 },
+}
+
+impl fmt::Display for EphemerisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EphemerisError::Io(message) => write!(f, "cannot read ephemeris: {message}"),
+            EphemerisError::InvalidFile(message) => write!(f, "invalid SPK file: {message}"),
+            EphemerisError::UnsupportedFormat(format) => {
+                write!(f, "unsupported binary format '{format}'")
+            }
+            EphemerisError::UnsupportedSegment {
+                target,
+                frame,
+                data_type,
+            } => write!(
+                f,
+                "unsupported segment for body {target}: frame {frame}, data type {data_type}"
+            ),
+            EphemerisError::NoData { target, jd } => {
+                write!(f, "no data for body {target} at Julian Day {jd}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EphemerisError {}
+
+/// Segment of type 2, the Chebyshev coefficients of the position of a body
+/// relative to its center, in equally long intervals
+#[derive(Debug, Clone)]
+struct Segment {
+    target: i32,
+    center: i32,
+
+    /// Time span covered, in seconds since J2000.0, TDB
+    start: f64,
+    end: f64,
+
+    /// Start of the first interval, in seconds since J2000.0, TDB
+    init: f64,
+
+    /// Length of each interval, in seconds
+    interval: f64,
+
+    /// Number of doubles per interval: midpoint, radius, and the
+    /// coefficients of x, y and z
+    record_size: usize,
+
+    /// Records of all intervals, in order
+    records: Vec<f64>,
+}
+
+impl Segment {
+    /// Position of the target relative to the center
+    /// In: seconds since J2000.0, TDB
+    /// Out: x, y, z, ICRF, in km
+    fn position(&self, et: f64) -> [f64; 3] {
+        let count = self.records.len() / self.record_size;
+
+        // SS: the end of the last interval belongs to the last interval
+        let idx = (((et - self.init) / self.interval).floor().max(0.0) as usize).min(count - 1);
+        let record = &self.records[idx * self.record_size..(idx + 1) * self.record_size];
+
+        let (mid, radius) = (record[0], record[1]);
+        let tau = (et - mid) / radius;
+
+        let n = (self.record_size - 2) / 3;
+        let coefficients = &record[2..];
+        [0, 1, 2].map(|k| chebyshev(&coefficients[k * n..(k + 1) * n], tau))
+    }
+}
+
+/// Sum of Chebyshev polynomials with Clenshaw's recurrence
+/// In:
+/// coefficients: of T0, T1, ...
+/// tau: argument, [-1, 1]
+/// Out: sum
+fn chebyshev(coefficients: &[f64], tau: f64) -> f64 {
+    let (b1, b2) = coefficients
+        .iter()
+        .skip(1)
+        .rev()
+        .fold((0.0, 0.0), |(b1, b2), c| (c + 2.0 * tau * b1 - b2, b1));
+    coefficients[0] + tau * b1 - b2
+}
+
+/// A JPL DE ephemeris, read from a SPICE SPK kernel
+#[derive(Debug, Clone)]
+pub struct JplEphemeris {
+    segments: Vec<Segment>,
+}
+
+impl JplEphemeris {
+    /// Read an SPK kernel, e.g. an excerpt of de440.bsp
+    /// In: path of the kernel
+    /// Out: the ephemeris, or an error if the file cannot be read or is not
+    /// supported
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, EphemerisError> {
+        let bytes = std::fs::read(path).map_err(|e| EphemerisError::Io(e.to_string()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Parse an SPK kernel
+    /// In: content of the kernel
+    /// Out: the ephemeris, or an error if the content is not supported
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EphemerisError> {
+        let truncated = || EphemerisError::InvalidFile("truncated".to_string());
+
+        if bytes.len() < RECORD_SIZE || !bytes.starts_with(b"DAF/SPK") {
+            return Err(EphemerisError::InvalidFile(
+                "not a DAF/SPK file".to_string(),
+            ));
+        }
+
+        let format = String::from_utf8_lossy(&bytes[88..96]).to_string();
+        if format != "LTL-IEEE" {
+            return Err(EphemerisError::UnsupportedFormat(format));
+        }
+
+        let i32_at = |offset: usize| -> Result<i32, EphemerisError> {
+            let value = bytes.get(offset..offset + 4).ok_or_else(truncated)?;
+            Ok(i32::from_le_bytes(value.try_into().unwrap()))
+        };
+        let f64_at = |offset: usize| -> Result<f64, EphemerisError> {
+            let value = bytes.get(offset..offset + 8).ok_or_else(truncated)?;
+            Ok(f64::from_le_bytes(value.try_into().unwrap()))
+        };
+
+        // SS: number of doubles and integers in each segment's summary
+        let nd = i32_at(8)? as usize;
+        let ni = i32_at(12)? as usize;
+        if nd != 2 || ni != 6 {
+            return Err(EphemerisError::InvalidFile(format!(
+                "summary of {nd} doubles and {ni} integers"
+            )));
+        }
+        let summary_size = 8 * (nd + ni.div_ceil(2));
+
+        // SS: the summary records are a linked list, starting at record FWARD
+        let mut segments = vec![];
+        let mut record = i32_at(76)? as usize;
+        let mut visited = 0;
+        while record != 0 {
+            visited += 1;
+            if visited > bytes.len() / RECORD_SIZE {
+                return Err(EphemerisError::InvalidFile(
+                    "cyclic summary records".to_string(),
+                ));
+            }
+
+            let offset = (record - 1)
+                .checked_mul(RECORD_SIZE)
+                .ok_or_else(truncated)?;
+            let next = f64_at(offset)? as usize;
+            let count = f64_at(offset + 16)? as usize;
+
+            for idx in 0..count {
+                let summary = offset + 24 + idx * summary_size;
+                segments.push(Self::segment(
+                    &f64_at,
+                    [f64_at(summary)?, f64_at(summary + 8)?],
+                    [0, 1, 2, 3, 4, 5].map(|k| i32_at(summary + 16 + 4 * k)),
+                )?);
+            }
+
+            record = next;
+        }
+
+        Ok(JplEphemeris { segments })
+    }
+
+    /// Read a segment of type 2
+    /// In:
+    /// f64_at: reads the double at a byte offset
+    /// [start, end]: time span covered, in seconds since J2000.0, TDB
+    /// integers: target, center, frame, data type, first and last address,
+    /// the addresses are 1-based indices of doubles
+    /// Out: the segment, or an error if not supported
+    fn segment(
+        f64_at: &dyn Fn(usize) -> Result<f64, EphemerisError>,
+        [start, end]: [f64; 2],
+        integers: [Result<i32, EphemerisError>; 6],
+    ) -> Result<Segment, EphemerisError> {
+        let [target, center, frame, data_type, first, last] = integers;
+        let (target, center, frame, data_type) = (target?, center?, frame?, data_type?);
+        let (first, last) = (first?, last?);
+
+        if data_type != CHEBYSHEV_POSITION || frame != FRAME_J2000 {
+            return Err(EphemerisError::UnsupportedSegment {
+                target,
+                frame,
+                data_type,
+            });
+        }
+
+        // SS: the addresses are 1-based, and the segment holds at least the 4
+        // doubles at its end
+        if first < 1 || last < 4 {
+            return Err(EphemerisError::InvalidFile(format!(
+                "segment of body {target} at addresses {first} to {last}"
+            )));
+        }
+        let (first, last) = (first as usize, last as usize);
+
+        // SS: the segment ends with INIT, INTLEN, RSIZE and N
+        let address = |idx: usize| 8 * (idx - 1);
+        let init = f64_at(address(last - 3))?;
+        let interval = f64_at(address(last - 2))?;
+        let record_size = f64_at(address(last - 1))? as usize;
+        let count = f64_at(address(last))? as usize;
+
+        let records_end = record_size
+            .checked_mul(count)
+            .and_then(|size| size.checked_add(first));
+        if count == 0
+            || record_size < 5
+            || !(record_size - 2).is_multiple_of(3)
+            || records_end.is_none_or(|records_end| records_end > last - 3)
+        {
+            return Err(EphemerisError::InvalidFile(format!(
+                "segment of body {target} with {count} records of {record_size} doubles"
+            )));
+        }
+
+        let records = (0..record_size * count)
+            .map(|idx| f64_at(address(first + idx)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Segment {
+            target,
+            center,
+            start,
+            end,
+            init,
+            interval,
+            record_size,
+            records,
+        })
+    }
+
+    /// Position of a body relative to the solar system barycenter
+    /// In:
+    /// target: NAIF id of the body
+    /// et: seconds since J2000.0, TDB
+    /// Out: x, y, z, ICRF, in km
+    fn barycentric(&self, target: i32, et: f64) -> Result<[f64; 3], EphemerisError> {
+        let mut position = [0.0; 3];
+        let mut body = target;
+
+        // SS: chain the segments, e.g. Moon - Earth-Moon barycenter - solar
+        // system barycenter
+        for _ in 0..=self.segments.len() {
+            if body == SOLAR_SYSTEM_BARYCENTER {
+                return Ok(position);
+            }
+
+            let segment = self
+                .segments
+                .iter()
+                .find(|s| s.target == body && s.start <= et && et <= s.end)
+                .ok_or(EphemerisError::NoData {
+                    target: body,
+                    jd: Epoch::J2000.jd().jd + et / constants::SEC_PER_DAY as f64,
+                })?;
+
+            let relative = segment.position(et);
+            for k in 0..3 {
+                position[k] += relative[k];
+            }
+            body = segment.center;
+        }
+
+        Err(EphemerisError::InvalidFile(format!(
+            "cyclic centers of body {target}"
+        )))
+    }
+
+    /// Geometric position of a body relative to another
+    /// In:
+    /// target: NAIF id of the body, e.g. 301 for the Moon
+    /// center: NAIF id of the origin, e.g. 399 for the Earth
+    /// jd: Julian Day, in dynamical time
+    /// Out: x, y, z, ICRF, in km
+    pub fn position(&self, target: i32, center: i32, jd: JD) -> Result<[f64; 3], EphemerisError> {
+        let et = seconds_since_j2000(jd);
+        let target = self.barycentric(target, et)?;
+        let center = self.barycentric(center, et)?;
+        Ok([0, 1, 2].map(|k| target[k] - center[k]))
+    }
+
+    /// Geocentric ecliptical coordinates of the Moon or the Sun, corrected
+    /// for light-time and the aberration due to Earth's motion, to compare
+    /// against the series, e.g. moon::position::ecliptical_coordinates with
+    /// Equinox::J2000
+    /// In:
+    /// body: Moon or Sun
+    /// jd: Julian Day, in dynamical time
+    /// Out:
+    /// longitude, mean equinox of J2000.0, in degrees [0, 360)
+    /// latitude, ecliptic of J2000.0, in degrees [-90, 90]
+    /// distance, in km
+    pub fn ecliptical_coordinates(
+        &self,
+        body: Body,
+        jd: JD,
+    ) -> Result<(Degrees, Degrees, f64), EphemerisError> {
+        let target = match body {
+            Body::Moon => MOON,
+            Body::Sun => SUN,
+        };
+        let et = seconds_since_j2000(jd);
+        let earth = self.barycentric(EARTH, et)?;
+
+        // SS: light-time, the body is seen where it was when the light left it
+        let mut position = [0.0; 3];
+        let mut light_time = 0.0;
+        for _ in 0..3 {
+            let target = self.barycentric(target, et - light_time)?;
+            position = [0, 1, 2].map(|k| target[k] - earth[k]);
+            light_time = norm(position) / SPEED_OF_LIGHT;
+        }
+        let distance = norm(position);
+
+        // SS: aberration, to first order in v/c, which is good to 0.001"
+        let before = self.barycentric(EARTH, et - VELOCITY_STEP)?;
+        let after = self.barycentric(EARTH, et + VELOCITY_STEP)?;
+        let direction = [0, 1, 2].map(|k| {
+            let velocity = (after[k] - before[k]) / (2.0 * VELOCITY_STEP);
+            position[k] / distance + velocity / SPEED_OF_LIGHT
+        });
+
+        // SS: rotate from the equator to the ecliptic of J2000.0, with the
+        // obliquity the series are referred to. The frame bias between the
+        // ICRF and the FK5 is below 0.03" and ignored
+        let eps = Radians::from(ecliptic::mean_obliquity(Epoch::J2000.jd())).0;
+        let [x, y, z] = direction;
+        let y_ecliptic = y * math::cos(eps) + z * math::sin(eps);
+        let z_ecliptic = -y * math::sin(eps) + z * math::cos(eps);
+
+        let longitude = Degrees::from(Radians::new(math::atan2(y_ecliptic, x)));
+        let latitude = Degrees::from(Radians::new(math::atan2(
+            z_ecliptic,
+            (x * x + y_ecliptic * y_ecliptic).sqrt(),
+        )));
+
+        Ok((longitude.map_to_0_to_360(), latitude, distance))
+    }
+}
+
+/// Time argument of the kernels
+/// In: Julian Day, in dynamical time
+/// Out: seconds since J2000.0
+fn seconds_since_j2000(jd: JD) -> f64 {
+    (jd.jd - Epoch::J2000.jd().jd) * constants::SEC_PER_DAY as f64
+}
+
+fn norm([x, y, z]: [f64; 3]) -> f64 {
+    (x * x + y * y + z * z).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::precession::Equinox;
+    use crate::{moon, sun};
+    use assert_approx_eq::assert_approx_eq;
+
+    const EARTH_MOON_BARYCENTER: i32 = 3;
+
+    /// A segment to write: target, center, init, interval length and the
+    /// records, each midpoint, radius and the coefficients
+    struct TestSegment {
+        target: i32,
+        center: i32,
+        init: f64,
+        interval: f64,
+        records: Vec<Vec<f64>>,
+    }
+
+    /// Write a little-endian SPK file: the file record, one summary record,
+    /// one name record and the segments
+    fn write_spk(segments: &[TestSegment]) -> Vec<u8> {
+        let mut bytes = vec![0; 3 * RECORD_SIZE];
+        bytes[0..8].copy_from_slice(b"DAF/SPK ");
+        bytes[8..12].copy_from_slice(&2i32.to_le_bytes());
+        bytes[12..16].copy_from_slice(&6i32.to_le_bytes());
+        bytes[76..80].copy_from_slice(&2i32.to_le_bytes());
+        bytes[88..96].copy_from_slice(b"LTL-IEEE");
+
+        let summary_record = RECORD_SIZE;
+        bytes[summary_record + 16..summary_record + 24]
+            .copy_from_slice(&(segments.len() as f64).to_le_bytes());
+
+        for (idx, segment) in segments.iter().enumerate() {
+            let first = bytes.len() / 8 + 1;
+            let record_size = segment.records[0].len();
+            for value in segment.records.iter().flatten().chain(&[
+                segment.init,
+                segment.interval,
+                record_size as f64,
+                segment.records.len() as f64,
+            ]) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            let last = bytes.len() / 8;
+
+            let summary = summary_record + 24 + idx * 40;
+            let end = segment.init + segment.interval * segment.records.len() as f64;
+            bytes[summary..summary + 8].copy_from_slice(&segment.init.to_le_bytes());
+            bytes[summary + 8..summary + 16].copy_from_slice(&end.to_le_bytes());
+            let integers = [
+                segment.target,
+                segment.center,
+                FRAME_J2000,
+                CHEBYSHEV_POSITION,
+                first as i32,
+                last as i32,
+            ];
+            for (k, value) in integers.iter().enumerate() {
+                let offset = summary + 16 + 4 * k;
+                bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        bytes
+    }
+
+    /// A segment with a constant position over +-100 days around J2000.0
+    fn fixed(target: i32, center: i32, [x, y, z]: [f64; 3]) -> TestSegment {
+        let interval = 200.0 * constants::SEC_PER_DAY as f64;
+        TestSegment {
+            target,
+            center,
+            init: -interval / 2.0,
+            interval,
+            records: vec![vec![0.0, interval / 2.0, x, 0.0, y, 0.0, z, 0.0]],
+        }
+    }
+
+    #[test]
+    fn chebyshev_test() {
+        // Arrange
+        let coefficients = [1.0, 2.0, 3.0, 4.0];
+
+        for tau in [-1.0, -0.3, 0.0, 0.5, 1.0] {
+            // Act
+            let sum = chebyshev(&coefficients, tau);
+
+            // Assert
+
+            // SS: T0 = 1, T1 = tau, T2 = 2 tau^2 - 1, T3 = 4 tau^3 - 3 tau
+            let expected = 1.0
+                + 2.0 * tau
+                + 3.0 * (2.0 * tau * tau - 1.0)
+                + 4.0 * (4.0 * tau * tau * tau - 3.0 * tau);
+            assert_approx_eq!(expected, sum, 1e-12);
+        }
+    }
+
+    #[test]
+    fn position_test() {
+        // Arrange
+
+        // SS: two intervals of 10 days, x = 1000 + 10 T1 + T2 in the second
+        let day = constants::SEC_PER_DAY as f64;
+        let segment = TestSegment {
+            target: MOON,
+            center: EARTH,
+            init: 0.0,
+            interval: 10.0 * day,
+            records: vec![
+                vec![
+                    5.0 * day,
+                    5.0 * day,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                    0.0,
+                ],
+                vec![
+                    15.0 * day,
+                    5.0 * day,
+                    1000.0,
+                    10.0,
+                    1.0,
+                    2.0,
+                    0.0,
+                    0.0,
+                    -3.0,
+                    0.0,
+                    0.0,
+                ],
+            ],
+        };
+        let bytes = write_spk(&[segment, fixed(EARTH, SOLAR_SYSTEM_BARYCENTER, [0.0; 3])]);
+        let ephemeris = JplEphemeris::from_bytes(&bytes).unwrap();
+
+        // SS: tau = 0.5 in the second interval
+        let jd = JD::new(Epoch::J2000.jd().jd + 17.5);
+
+        // Act
+        let [x, y, z] = ephemeris.position(MOON, EARTH, jd).unwrap();
+
+        // Assert
+        assert_approx_eq!(1000.0 + 10.0 * 0.5 + (2.0 * 0.25 - 1.0), x, 1e-9);
+        assert_approx_eq!(2.0, y, 1e-9);
+        assert_approx_eq!(-3.0, z, 1e-9);
+    }
+
+    #[test]
+    fn position_chain_test() {
+        // Arrange
+        let bytes = write_spk(&[
+            fixed(
+                EARTH_MOON_BARYCENTER,
+                SOLAR_SYSTEM_BARYCENTER,
+                [1e8, 0.0, 0.0],
+            ),
+            fixed(EARTH, EARTH_MOON_BARYCENTER, [-4000.0, 0.0, 0.0]),
+            fixed(MOON, EARTH_MOON_BARYCENTER, [300_000.0, 50_000.0, 0.0]),
+            fixed(SUN, SOLAR_SYSTEM_BARYCENTER, [0.0, 0.0, 0.0]),
+        ]);
+        let ephemeris = JplEphemeris::from_bytes(&bytes).unwrap();
+        let jd = Epoch::J2000.jd();
+
+        // Act
+        let moon = ephemeris.position(MOON, EARTH, jd).unwrap();
+        let sun = ephemeris.position(SUN, EARTH, jd).unwrap();
+
+        // Assert
+        assert_eq!([304_000.0, 50_000.0, 0.0], moon);
+        assert_eq!([-1e8 + 4000.0, 0.0, 0.0], sun);
+    }
+
+    #[test]
+    fn ecliptical_coordinates_test() {
+        // Arrange
+
+        // SS: the Moon on the equator, 90 degrees from the equinox, i.e. at
+        // the solstice point of the ecliptic
+        let bytes = write_spk(&[
+            fixed(EARTH, SOLAR_SYSTEM_BARYCENTER, [0.0; 3]),
+            fixed(MOON, SOLAR_SYSTEM_BARYCENTER, [0.0, 384_400.0, 0.0]),
+        ]);
+        let ephemeris = JplEphemeris::from_bytes(&bytes).unwrap();
+
+        // Act
+        let (longitude, latitude, distance) = ephemeris
+            .ecliptical_coordinates(Body::Moon, Epoch::J2000.jd())
+            .unwrap();
+
+        // Assert
+        assert_approx_eq!(90.0, longitude.0, 1e-9);
+        assert_approx_eq!(
+            -ecliptic::mean_obliquity(Epoch::J2000.jd()).0,
+            latitude.0,
+            1e-9
+        );
+        assert_approx_eq!(384_400.0, distance, 1e-6);
+    }
+
+    #[test]
+    fn aberration_test() {
+        // Arrange
+
+        // SS: the Earth moves at 30 km/s in y, the Sun is at rest in x
+        let day = constants::SEC_PER_DAY as f64;
+        let radius = 100.0 * day;
+        let earth = TestSegment {
+            target: EARTH,
+            center: SOLAR_SYSTEM_BARYCENTER,
+            init: -radius,
+            interval: 2.0 * radius,
+            records: vec![vec![0.0, radius, 0.0, 0.0, 0.0, 30.0 * radius, 0.0, 0.0]],
+        };
+        let bytes = write_spk(&[
+            earth,
+            fixed(SUN, SOLAR_SYSTEM_BARYCENTER, [constants::AU, 0.0, 0.0]),
+        ]);
+        let ephemeris = JplEphemeris::from_bytes(&bytes).unwrap();
+
+        // Act
+        let (longitude, _, _) = ephemeris
+            .ecliptical_coordinates(Body::Sun, Epoch::J2000.jd())
+            .unwrap();
+
+        // Assert
+
+        // SS: the direction is displaced by v / c towards the motion, the
+        // constant of aberration of about 20.6"
+        let eps = Radians::from(ecliptic::mean_obliquity(Epoch::J2000.jd())).0;
+        let expected = 30.0 / SPEED_OF_LIGHT * math::cos(eps);
+        assert_approx_eq!(
+            Degrees::from(Radians::new(expected)).0 * 3600.0,
+            longitude.0 * 3600.0,
+            0.01
+        );
+    }
+
+    #[test]
+    fn no_data_test() {
+        // Arrange
+        let bytes = write_spk(&[fixed(MOON, EARTH, [1.0, 0.0, 0.0])]);
+        let ephemeris = JplEphemeris::from_bytes(&bytes).unwrap();
+        let jd = JD::from_date(Date::new(2000, 6, 1.0));
+
+        // Act
+        let position = ephemeris.position(MOON, EARTH, jd);
+
+        // Assert
+        assert!(matches!(
+            position,
+            Err(EphemerisError::NoData { target: MOON, .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_file_test() {
+        // Arrange
+        let mut big_endian = write_spk(&[fixed(MOON, EARTH, [1.0, 0.0, 0.0])]);
+        big_endian[88..96].copy_from_slice(b"BIG-IEEE");
+
+        // Act
+        let not_spk = JplEphemeris::from_bytes(b"DAF/PCK");
+        let big_endian = JplEphemeris::from_bytes(&big_endian);
+        let missing = JplEphemeris::open("/nonexistent/de440.bsp");
+
+        // Assert
+        assert!(matches!(not_spk, Err(EphemerisError::InvalidFile(_))));
+        assert_eq!(
+            Err(EphemerisError::UnsupportedFormat("BIG-IEEE".to_string())),
+            big_endian.map(|_| ())
+        );
+        assert!(matches!(missing, Err(EphemerisError::Io(_))));
+    }
+
+    #[test]
+    fn invalid_segment_test() {
+        // Arrange
+        let valid = write_spk(&[fixed(MOON, EARTH, [1.0, 0.0, 0.0])]);
+        let summary = RECORD_SIZE + 24;
+        let last = valid.len();
+
+        // SS: no records, the count N is the segment's last double
+        let mut no_records = valid.clone();
+        no_records[last - 8..last].copy_from_slice(&0f64.to_le_bytes());
+
+        let mut first_zero = valid.clone();
+        first_zero[summary + 32..summary + 36].copy_from_slice(&0i32.to_le_bytes());
+
+        let mut last_too_small = valid.clone();
+        last_too_small[summary + 36..summary + 40].copy_from_slice(&3i32.to_le_bytes());
+
+        // SS: first + record size * count overflows
+        let mut too_many_records = valid.clone();
+        too_many_records[last - 8..last].copy_from_slice(&1e19f64.to_le_bytes());
+
+        for bytes in [no_records, first_zero, last_too_small, too_many_records] {
+            // Act
+            let ephemeris = JplEphemeris::from_bytes(&bytes);
+
+            // Assert
+            assert!(matches!(ephemeris, Err(EphemerisError::InvalidFile(_))));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a DE440 kernel, set MOONLIB_DE440 to its path"]
+    fn de440_ground_truth_test() {
+        // Arrange
+        let path = std::env::var("MOONLIB_DE440").unwrap();
+        let ephemeris = JplEphemeris::open(path).unwrap();
+        let start = JD::from_date(Date::new(1950, 1, 1.0));
+
+        for day in (0..36_525).step_by(37) {
+            let jd = JD::new(start.jd + day as f64);
+
+            // Act
+            let (moon_longitude, moon_latitude, moon_distance) =
+                ephemeris.ecliptical_coordinates(Body::Moon, jd).unwrap();
+            let (sun_longitude, sun_latitude, _) =
+                ephemeris.ecliptical_coordinates(Body::Sun, jd).unwrap();
+
+            // Assert
+
+            // SS: Meeus, chapter 47 is good to 10" in longitude and 4" in
+            // latitude, VSOP87 to 1", plus the precession to J2000.0
+            let (longitude, latitude) = moon::position::ecliptical_coordinates(jd, Equinox::J2000);
+            let distance = moon::position::distance_from_earth(jd);
+            assert_approx_eq!(
+                0.0,
+                (longitude - moon_longitude).map_neg180_to_180().0 * 3600.0,
+                15.0
+            );
+            assert_approx_eq!(moon_latitude.0 * 3600.0, latitude.0 * 3600.0, 6.0);
+            assert_approx_eq!(moon_distance, distance, 10.0);
+
+            let (longitude, latitude) = sun::position::ecliptical_coordinates(jd, Equinox::J2000);
+            assert_approx_eq!(
+                0.0,
+                (longitude - sun_longitude).map_neg180_to_180().0 * 3600.0,
+                2.0
+            );
+            assert_approx_eq!(sun_latitude.0 * 3600.0, latitude.0 * 3600.0, 1.0);
+        }
+    }
+}
@@ -1,15 +1,45 @@
+pub mod body;
+pub mod bridge_core;
+pub mod config;
 mod constants;
-mod coordinates;
+pub mod coordinates;
 pub mod date;
-mod earth;
+pub mod earth;
 mod ecliptic;
-mod moon;
+pub mod ephemeris;
+#[cfg(feature = "ephemeris-cache")]
+pub mod ephemeris_cache;
+pub mod error_budget;
+pub mod events;
+pub mod heliacal;
+pub mod horizon;
+#[cfg(feature = "jpl-ephem")]
+pub mod jpl_ephemeris;
+pub mod moon;
 mod nutation;
+pub mod observation_log;
 mod parallax;
-mod refraction;
-mod sun;
+pub mod planning;
+mod precession;
+pub mod refraction;
+pub mod rise_set_transit;
+pub mod session;
+pub mod sky;
+pub mod solunar;
+pub mod sun;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+pub mod tides;
 pub mod time;
+#[cfg(feature = "tz")]
+pub mod timezone;
+pub mod track;
+pub mod uncertainty;
 mod util;
+pub mod validity;
+pub mod widget;
 
 /// Expose the JNI interface for android below
 #[cfg(target_os = "android")]
@@ -18,14 +48,19 @@ pub mod android {
     use android_logger;
     use android_logger::Config;
     use jni;
-    use log::{debug, Level};
+    use log::{debug, error, Level};
 
+    use crate::bridge_core::MoonInput;
     use crate::date::{date::Date, jd::JD};
+    use crate::moon::jni_bridge::jni_struct::android::{clear_exception, JniRead, JniWrite};
+    use crate::time::{ScaledJD, TimeScale};
     use crate::util::degrees::Degrees;
     use crate::*;
 
-    use self::jni::objects::{JClass, JString};
-    use self::jni::sys::{jbyte, jdouble, jint, jobject, jstring};
+    use self::jni::objects::{JClass, JObject, JString};
+    use self::jni::sys::{
+        jbyte, jbyteArray, jdouble, jdoubleArray, jint, jlong, jobject, jobjectArray, jstring,
+    };
     use self::jni::JNIEnv;
 
     /*
@@ -44,6 +79,26 @@ pub mod android {
         jd.jd as jdouble
     }
 
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1julian_1day_1from_1unix_1millis(
+        _env: JNIEnv,
+        _: JClass,
+        millis: jlong,
+    ) -> jdouble {
+        let jd = JD::from_unix_millis(millis);
+        jd.jd as jdouble
+    }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1unix_1millis_1from_1julian_1day(
+        _env: JNIEnv,
+        _: JClass,
+        jd_value: jdouble,
+    ) -> jlong {
+        let jd = JD::new(jd_value);
+        jd.to_unix_millis() as jlong
+    }
+
     #[no_mangle]
     pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1local_1siderial_1time(
         _env: JNIEnv,
@@ -52,7 +107,10 @@ pub mod android {
         longitude_observer: jdouble,
     ) -> jdouble {
         let jd = JD::new(jd_value);
-        let sd = earth::apparent_siderial_time(jd);
+        let sd = earth::siderial_time(
+            ScaledJD::new(jd, TimeScale::UTC),
+            earth::SiderialTime::Apparent,
+        );
         let lst = earth::local_siderial_time(sd, Degrees::new(longitude_observer));
         lst.0 as jdouble
     }
@@ -71,252 +129,89 @@ pub mod android {
         // SS: configure Android logger
         android_logger::init_once(Config::default().with_min_level(Level::Trace));
 
-        let jd: JD = JD::new(
-            env.get_field(moon_input_data, "jd", "D")
-                .unwrap()
-                .d()
-                .unwrap(),
-        );
-
-        let timezone_offset: i8 = env
-            .get_field(moon_input_data, "timezoneOffset", "S")
-            .unwrap()
-            .s()
-            .unwrap() as i8;
+        let input = MoonInput::read_from(env, moon_input_data.into());
+        let output = bridge_core::moon_data(&input);
 
-        let longitude_observer = Degrees::new(
-            env.get_field(moon_input_data, "longitudeObserver", "D")
-                .unwrap()
-                .d()
-                .unwrap(),
-        );
+        debug!("Moon data:\n{output}");
+        if !output.is_valid {
+            error!("Moon data is not finite for input {input:?}");
+        }
 
-        let latitude_observer = Degrees::new(
-            env.get_field(moon_input_data, "latitudeObserver", "D")
-                .unwrap()
-                .d()
-                .unwrap(),
-        );
-
-        let height_above_sea_observer: f64 = env
-            .get_field(moon_input_data, "heightAboveSeaObserver", "D")
-            .unwrap()
-            .d()
-            .unwrap();
-
-        let pressure: f64 = env
-            .get_field(moon_input_data, "pressure", "D")
-            .unwrap()
-            .d()
-            .unwrap();
+        debug!("Moon's rise: {}", output.rise_time);
+        debug!("Moon's set: {}", output.set_time);
+        debug!("Moon's transit: {}", output.transit_time);
 
-        let temperature: f64 = env
-            .get_field(moon_input_data, "temperature", "D")
-            .unwrap()
-            .d()
-            .unwrap();
-
-        let phase_angle = moon::phase::phase_angle_360(jd);
-        env.set_field(
-            moon_output_data,
-            "phaseAngle",
-            "D",
-            self::jni::objects::JValue::Double(phase_angle.0),
-        )
-        .unwrap();
-
-        let phase_age = moon::phase::phase_age(jd);
-        env.set_field(
-            moon_output_data,
-            "phaseAge",
-            "D",
-            self::jni::objects::JValue::Double(phase_age),
-        )
-        .unwrap();
-
-        debug!("Phase: {}", phase_angle.0);
-        debug!("Phase age: {phase_age}");
-
-        let fraction_illuminated = moon::phase::fraction_illuminated(jd);
-        env.set_field(
-            moon_output_data,
-            "illuminatedFraction",
-            "D",
-            self::jni::objects::JValue::Double(fraction_illuminated),
-        )
-        .unwrap();
-
-        let phase_desc = moon::phase::phase_description(jd);
-        let phase_desc: JString = env.new_string(phase_desc).unwrap();
-        env.set_field(
-            moon_output_data,
-            "phaseDesc",
-            "Ljava/lang/String;",
-            self::jni::objects::JValue::Object(phase_desc.into()),
-        )
-        .unwrap();
-
-        let longitude = moon::position::geocentric_longitude(jd);
-        env.set_field(
-            moon_output_data,
-            "geocentricLongitude",
-            "D",
-            self::jni::objects::JValue::Double(longitude.0),
-        )
-        .unwrap();
-
-        let latitude = moon::position::geocentric_latitude(jd);
-        env.set_field(
-            moon_output_data,
-            "geocentricLatitude",
-            "D",
-            self::jni::objects::JValue::Double(latitude.0),
-        )
-        .unwrap();
-
-        let distance = moon::position::distance_from_earth(jd);
-        env.set_field(
-            moon_output_data,
-            "distanceFromEarth",
-            "D",
-            self::jni::objects::JValue::Double(distance),
-        )
-        .unwrap();
-
-        // SS: Moon's equatorial coordinates
-        let eps = ecliptic::true_obliquity(jd);
-        let (ra, decl) = coordinates::ecliptical_2_equatorial(longitude, latitude, eps);
-        let (ra_topocentric, decl_topocentric) = coordinates::equatorial_2_topocentric(
-            ra,
-            decl,
-            longitude_observer,
-            latitude_observer,
-            height_above_sea_observer,
-            distance,
-            jd,
-        );
-
-        env.set_field(
-            moon_output_data,
-            "rightAscension",
-            "D",
-            self::jni::objects::JValue::Double(ra_topocentric.0),
-        )
-        .unwrap();
-
-        env.set_field(
-            moon_output_data,
-            "declination",
-            "D",
-            self::jni::objects::JValue::Double(decl_topocentric.0),
-        )
-        .unwrap();
-
-        // SS: horizontal topocentric coordinates of the moon
-        let siderial_time_apparent_greenwich = earth::apparent_siderial_time(jd);
-        let siderial_time_local =
-            earth::local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
-        let hour_angle = earth::hour_angle(siderial_time_local, ra_topocentric);
-        let (azimuth, mut altitude) =
-            coordinates::equatorial_2_horizontal(decl_topocentric, hour_angle, latitude_observer);
-
-        // SS: add correction for atmospheric refraction
-        let refraction_correction =
-            refraction::refraction_for_true_altitude(altitude, pressure, temperature);
-        altitude += refraction_correction;
-
-        env.set_field(
-            moon_output_data,
-            "azimuth",
-            "D",
-            self::jni::objects::JValue::Double(azimuth.0),
-        )
-        .unwrap();
-
-        env.set_field(
-            moon_output_data,
-            "altitude",
-            "D",
-            self::jni::objects::JValue::Double(altitude.0),
-        )
-        .unwrap();
-
-        env.set_field(
-            moon_output_data,
-            "hourAngle",
-            "D",
-            self::jni::objects::JValue::Double(hour_angle.0),
-        )
-        .unwrap();
+        output.write_to(env, moon_output_data.into());
+    }
 
-        // SS: Moon's rise time
-        let rise_date_time = env
-            .get_field(
-                moon_output_data,
-                "riseTime",
-                "Lcom/svenschmidt/kitana/core/NativeAccess$DateTime;",
-            )
-            .unwrap()
-            .l()
-            .unwrap();
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1moon_1widget_1data(
+        env: JNIEnv,
+        _: JClass,
+        moon_input_data: jobject,
+        buffer: jbyteArray,
+    ) {
+        // SS: configure Android logger
+        android_logger::init_once(Config::default().with_min_level(Level::Trace));
 
-        use crate::moon::jni_bridge::rise_set_transit::android::rise;
-        rise(
-            env,
-            rise_date_time,
-            jd,
-            timezone_offset,
-            longitude_observer,
-            latitude_observer,
-            pressure,
-            temperature,
-        );
+        let input = MoonInput::read_from(env, moon_input_data.into());
+        let output = bridge_core::widget_data(&input);
+        if !output.is_valid {
+            error!("Widget data is not finite for input {input:?}");
+        }
+        let bytes = output.encode();
+
+        // SS: the caller allocates the buffer with WIDGET_DATA_SIZE bytes. A
+        // buffer of a different size, e.g. from an app of a different version,
+        // gets as many bytes as fit
+        let length = match env.get_array_length(buffer) {
+            Ok(length) => length as usize,
+            Err(e) => {
+                error!("Widget data buffer has no length: {e}");
+                clear_exception(env);
+                return;
+            }
+        };
+        if length != widget::WIDGET_DATA_SIZE {
+            error!(
+                "Widget data buffer has {length} bytes, expected {}",
+                widget::WIDGET_DATA_SIZE
+            );
+        }
+
+        let bytes: Vec<jbyte> = bytes
+            .iter()
+            .take(length.min(widget::WIDGET_DATA_SIZE))
+            .map(|&b| b as jbyte)
+            .collect();
+        if let Err(e) = env.set_byte_array_region(buffer, 0, &bytes) {
+            error!("Cannot write widget data: {e}");
+            clear_exception(env);
+        }
+    }
 
-        // SS: Moon's set time
-        let set_date_time = env
-            .get_field(
-                moon_output_data,
-                "setTime",
-                "Lcom/svenschmidt/kitana/core/NativeAccess$DateTime;",
-            )
-            .unwrap()
-            .l()
-            .unwrap();
+    /*
+     * Solunar
+     */
 
-        use crate::moon::jni_bridge::rise_set_transit::android::set;
-        set(
-            env,
-            set_date_time,
-            jd,
-            timezone_offset,
-            longitude_observer,
-            latitude_observer,
-            pressure,
-            temperature,
-        );
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1solunar_1data(
+        env: JNIEnv,
+        _: JClass,
+        moon_input_data: jobject,
+        solunar_output_data: jobject,
+    ) {
+        // SS: configure Android logger
+        android_logger::init_once(Config::default().with_min_level(Level::Trace));
 
-        // SS: Moon's transit time
-        let transit_date_time = env
-            .get_field(
-                moon_output_data,
-                "transitTime",
-                "Lcom/svenschmidt/kitana/core/NativeAccess$DateTime;",
-            )
-            .unwrap()
-            .l()
-            .unwrap();
+        let input = MoonInput::read_from(env, moon_input_data.into());
+        let output = bridge_core::solunar_data(&input);
+        if !output.is_valid {
+            error!("Solunar data is not finite for input {input:?}");
+        }
 
-        use crate::moon::jni_bridge::rise_set_transit::android::transit;
-        transit(
-            env,
-            transit_date_time,
-            jd,
-            timezone_offset,
-            longitude_observer,
-            latitude_observer,
-            pressure,
-            temperature,
-        );
+        use crate::moon::jni_bridge::solunar::android::solunar;
+        solunar(env, solunar_output_data.into(), &output);
     }
 
     #[no_mangle]
@@ -342,4 +237,59 @@ pub mod android {
         let string: JString = env.new_string(dms_str).unwrap();
         string.into_inner()
     }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1to_1dms_1batch(
+        env: JNIEnv,
+        _: JClass,
+        degrees: jdoubleArray,
+        width: jbyte,
+    ) -> jobjectArray {
+        let degrees = double_array(env, degrees);
+        string_array(env, &bridge_core::to_dms_batch(&degrees, width as u8))
+    }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1to_1hms_1batch(
+        env: JNIEnv,
+        _: JClass,
+        degrees: jdoubleArray,
+        width: jbyte,
+    ) -> jobjectArray {
+        let degrees = double_array(env, degrees);
+        string_array(env, &bridge_core::to_hms_batch(&degrees, width as u8))
+    }
+
+    #[no_mangle]
+    pub extern "system" fn Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1data_1info(
+        env: JNIEnv,
+        _: JClass,
+    ) -> jstring {
+        let string: JString = env.new_string(bridge_core::data_info()).unwrap();
+        string.into_inner()
+    }
+
+    /// Copy a Java double[] into a Vec
+    fn double_array(env: JNIEnv, array: jdoubleArray) -> Vec<f64> {
+        let length = env.get_array_length(array).unwrap();
+        let mut values = vec![0.0; length as usize];
+        env.get_double_array_region(array, 0, &mut values).unwrap();
+        values
+    }
+
+    /// Create a Java String[], one JNI call to return many strings
+    fn string_array(env: JNIEnv, strings: &[String]) -> jobjectArray {
+        let array = env
+            .new_object_array(strings.len() as i32, "java/lang/String", JObject::null())
+            .unwrap();
+        for (i, s) in strings.iter().enumerate() {
+            let string: JString = env.new_string(s).unwrap();
+            env.set_object_array_element(array, i as i32, string)
+                .unwrap();
+            // SS: free the local reference, arrays can be longer than the
+            // local reference table
+            env.delete_local_ref(string.into()).unwrap();
+        }
+        array
+    }
 }
@@ -0,0 +1,228 @@
+//! The Moon's distance from Earth, and its extremes, perigee and apogee.
+//!
+//! The distance varies between about 356,400 km and 406,700 km. Meeus,
+//! chapter 50, gives series for the times of perigee and apogee. Here, they
+//! are found as the extremes of the distance from the series of chapter 47
+//! instead, so they are consistent with the distance reported for any time.
+
+use crate::constants;
+use crate::date::jd::JD;
+use crate::moon::position::distance_from_earth;
+
+/// Mean distance Earth-Moon, in km, the constant term of the distance in
+/// Meeus, chapter 47, page 342
+pub const MEAN_DISTANCE: f64 = 385_000.56;
+
+/// Step for the central differences of the distance, in days
+const RATE_STEP: f64 = 0.01;
+
+/// Perigee and apogee follow each other after about 14 days, so stepping
+/// by a day cannot miss one
+const SEARCH_STEP: f64 = 1.0;
+
+/// Stop refining the time of an apsis at this precision, in days, about a
+/// second
+const PRECISION: f64 = 1e-5;
+
+/// The Moon's distance from Earth
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoonDistance {
+    /// Distance between the centers of Earth and Moon, in km
+    pub kilometers: f64,
+
+    /// Distance, in equatorial radii of the Earth, about 60
+    pub earth_radii: f64,
+
+    /// Distance, in percent of the mean distance, between about 92.6 at
+    /// perigee and 105.6 at apogee
+    pub percent_of_mean: f64,
+}
+
+impl MoonDistance {
+    /// In: Distance between the centers of Earth and Moon, in km
+    pub fn new(kilometers: f64) -> Self {
+        Self {
+            kilometers,
+            earth_radii: kilometers / constants::EARTH_RADIUS,
+            percent_of_mean: 100.0 * kilometers / MEAN_DISTANCE,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ApsisKind {
+    /// Closest to Earth
+    Perigee,
+
+    /// Farthest from Earth
+    Apogee,
+}
+
+/// Perigee or apogee
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Apsis {
+    pub kind: ApsisKind,
+
+    /// Julian day, in dynamical time
+    pub jd: JD,
+
+    pub distance: MoonDistance,
+}
+
+/// Calculate the moon's distance from Earth
+/// In: Julian day, in dynamical time
+/// Out: Distance
+pub fn distance(jd: JD) -> MoonDistance {
+    MoonDistance::new(distance_from_earth(jd))
+}
+
+/// Find the first perigee or apogee after a time
+/// In: Julian day, in dynamical time
+/// Out: Perigee or apogee
+pub fn next_apsis(jd: JD) -> Apsis {
+    find_apsis(jd, SEARCH_STEP)
+}
+
+/// Find the last perigee or apogee before a time
+/// In: Julian day, in dynamical time
+/// Out: Perigee or apogee
+pub fn previous_apsis(jd: JD) -> Apsis {
+    find_apsis(jd, -SEARCH_STEP)
+}
+
+/// Rate of change of the distance
+/// In: Julian day, in dynamical time
+/// Out: Rate of change, in km per day
+fn distance_rate(jd: f64) -> f64 {
+    (distance_from_earth(JD::new(jd + RATE_STEP)) - distance_from_earth(JD::new(jd - RATE_STEP)))
+        / (2.0 * RATE_STEP)
+}
+
+/// Step from a time until the distance rate changes sign, then bisect
+/// In:
+/// jd: Julian day, in dynamical time
+/// step: in days, negative to search backwards in time
+/// Out: Perigee or apogee
+fn find_apsis(jd: JD, step: f64) -> Apsis {
+    let mut a = jd.jd;
+    let mut rate_a = distance_rate(a);
+    let mut b = a + step;
+    let mut rate_b = distance_rate(b);
+    while rate_a.signum() == rate_b.signum() {
+        a = b;
+        rate_a = rate_b;
+        b += step;
+        rate_b = distance_rate(b);
+    }
+
+    // SS: the distance decreases towards perigee
+    let kind = if (rate_a < 0.0) == (step > 0.0) {
+        ApsisKind::Perigee
+    } else {
+        ApsisKind::Apogee
+    };
+
+    while (b - a).abs() > PRECISION {
+        let mid = 0.5 * (a + b);
+        let rate_mid = distance_rate(mid);
+        if rate_mid.signum() == rate_a.signum() {
+            a = mid;
+            rate_a = rate_mid;
+        } else {
+            b = mid;
+        }
+    }
+
+    let apsis = JD::new(0.5 * (a + b));
+    Apsis {
+        kind,
+        jd: apsis,
+        distance: distance(apsis),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn distance_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let distance = distance(jd);
+
+        // Assert
+
+        // SS: Meeus, example 47.a
+        assert_approx_eq!(368_409.7, distance.kilometers, 0.1);
+        assert_approx_eq!(57.76, distance.earth_radii, 0.01);
+        assert_approx_eq!(95.69, distance.percent_of_mean, 0.01);
+    }
+
+    #[test]
+    fn next_apsis_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1988, 10, 1.0));
+
+        // Act
+        let apsis = next_apsis(jd);
+
+        // Assert
+
+        // SS: Meeus, example 50.a, apogee on 1988 October 7 at 20:30 TD,
+        // parallax 3240.679"
+        assert_eq!(ApsisKind::Apogee, apsis.kind);
+        assert_approx_eq!(2_447_442.354_3, apsis.jd.jd, 0.02);
+        assert_approx_eq!(406_000.0, apsis.distance.kilometers, 100.0);
+    }
+
+    #[test]
+    fn previous_apsis_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1988, 10, 14.0));
+
+        // Act
+        let apsis = previous_apsis(jd);
+
+        // Assert
+        assert_eq!(ApsisKind::Apogee, apsis.kind);
+        assert_approx_eq!(2_447_442.354_3, apsis.jd.jd, 0.02);
+    }
+
+    #[test]
+    fn perigee_apogee_alternate_test() {
+        // Arrange
+        let mut jd = JD::from_date(Date::new(2024, 1, 1.0));
+        let mut previous = previous_apsis(jd);
+
+        for _ in 0..10 {
+            // Act
+            let apsis = next_apsis(jd);
+
+            // Assert
+            assert_ne!(previous.kind, apsis.kind);
+            assert!(apsis.jd.jd - previous.jd.jd > 10.0);
+            assert!(apsis.jd.jd - previous.jd.jd < 19.0);
+            match apsis.kind {
+                ApsisKind::Perigee => assert!(apsis.distance.kilometers < 371_000.0),
+                ApsisKind::Apogee => assert!(apsis.distance.kilometers > 404_000.0),
+            }
+
+            // SS: the distance at any time is between the two
+            let between = distance(JD::new(0.5 * (previous.jd.jd + apsis.jd.jd)));
+            let (near, far) = match apsis.kind {
+                ApsisKind::Perigee => (apsis, previous),
+                ApsisKind::Apogee => (previous, apsis),
+            };
+            assert!(between.kilometers > near.distance.kilometers);
+            assert!(between.kilometers < far.distance.kilometers);
+
+            previous = apsis;
+            jd = JD::new(apsis.jd.jd + 0.01);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Hour-by-hour table of the Moon's position in the sky
+
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::rise_set_transit::bound_julian_day;
+use crate::session::Observer;
+use crate::util::compass;
+use crate::util::degrees::Degrees;
+use crate::{moon, time};
+
+/// Smallest time between two table entries, in hours, one second. Smaller
+/// steps would not change the Julian day, or make the table huge
+const MIN_STEP: f64 = 1.0 / 3600.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Bearing {
+    /// Julian day, in UTC
+    pub jd: JD,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: Degrees,
+
+    /// Altitude, not corrected for atmospheric refraction, in degrees [-90, 90)
+    pub altitude: Degrees,
+
+    /// Compass point closest to the azimuth, i.e. N, NNE, NE, ...
+    pub compass_point: &'static str,
+}
+
+/// Calculate the Moon's azimuth and altitude over the course of a day.
+/// In:
+/// observer: Observer's location and time zone offset
+/// date: Day to calculate the table for, in the observer's time zone. The
+/// fraction of the day is ignored
+/// step: time between two table entries, in hours
+/// Out:
+/// Bearings from local midnight to the next local midnight. Empty if step
+/// is less than a second or not a number
+pub fn bearing_table(observer: &Observer, date: Date, step: f64) -> Vec<Bearing> {
+    if step.is_nan() || step < MIN_STEP {
+        return vec![];
+    }
+
+    let day = Date::new(date.year, date.month, date.day.trunc());
+    let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(day), observer.timezone_offset);
+
+    // SS: count the entries up front, adding the step up would accumulate
+    // rounding errors. The tolerance keeps the next midnight for steps that
+    // divide the day
+    let n = ((jd_max.jd - jd_min.jd) * 24.0 / step + 1e-9).floor() as usize;
+
+    (0..=n)
+        .map(|idx| {
+            let current = JD::new(jd_min.jd + idx as f64 * step / 24.0);
+
+            // SS: the table is in UTC, the Moon's position needs dynamical time
+            let tt = time::utc_2_tt(current);
+            let (azimuth, altitude) =
+                moon::position::horizontal_coordinates(tt, observer.longitude, observer.latitude);
+
+            Bearing {
+                jd: current,
+                azimuth,
+                altitude,
+                compass_point: compass::compass_point(azimuth),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn bearing_table_test() {
+        // Arrange
+        let date = Date::new(2000, 3, 23.5);
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let table = bearing_table(&observer, date, 1.0);
+
+        // Assert
+        assert_eq!(25, table.len());
+        assert_approx_eq!(2_451_626.5, table[0].jd.jd, 0.000_001);
+
+        // SS: at 2am the Moon transited about 20 minutes ago, i.e. is a bit west of South
+        assert_eq!("S", table[2].compass_point);
+        assert!(table[2].azimuth.0 > 180.0);
+        assert!(table[2].altitude.0 > 0.0);
+    }
+
+    #[test]
+    fn bearing_table_step_test() {
+        // Arrange
+        let date = Date::new(2000, 3, 23.5);
+        let observer = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+
+        // Act
+        let table = bearing_table(&observer, date, 24.0);
+
+        // Assert
+
+        // SS: local midnight and the next local midnight, in UTC
+        assert_eq!(2, table.len());
+        assert_approx_eq!(2_451_626.5 - 1.0 / 24.0, table[0].jd.jd, 0.000_001);
+    }
+
+    #[test]
+    fn bearing_table_minimum_step_test() {
+        // Arrange
+        let date = Date::new(2000, 3, 23.0);
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let table = bearing_table(&observer, date, MIN_STEP);
+
+        // Assert
+
+        // SS: one entry per second, both midnights included
+        assert_eq!(86_401, table.len());
+        assert_approx_eq!(table[0].jd.jd + 1.0, table[86_400].jd.jd, 1e-9);
+    }
+
+    #[test]
+    fn bearing_table_invalid_step_test() {
+        // Arrange
+        let date = Date::new(2000, 3, 23.0);
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let zero = bearing_table(&observer, date, 0.0);
+        let nan = bearing_table(&observer, date, f64::NAN);
+        let tiny = bearing_table(&observer, date, 1e-12);
+        let infinite = bearing_table(&observer, date, f64::INFINITY);
+
+        // Assert
+        assert!(zero.is_empty());
+        assert!(nan.is_empty());
+        assert!(tiny.is_empty());
+
+        // SS: only local midnight
+        assert_eq!(1, infinite.len());
+    }
+}
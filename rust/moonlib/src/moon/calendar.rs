@@ -0,0 +1,401 @@
+//! The Moon's principal phases in a calendar month, for a month view.
+//!
+//! Months with two full moons have a blue moon, months with two new moons a
+//! black moon. An astronomical season, from an equinox to the next solstice
+//! or vice versa, usually has three full moons. If it has four, the third
+//! is a seasonal blue moon, the older meaning of the term.
+//!
+//! Phases are the times the Moon's apparent geocentric longitude exceeds the
+//! Sun's by 0, 90, 180 and 270 degrees, Meeus, chapter 49. Instead of the
+//! series of chapter 49, the difference is sampled and the phases are found
+//! by bisection, so they are consistent with the Moon's phase reported for
+//! any time.
+
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::moon::names::{self, FullMoonName};
+use crate::time::{self, LeapSeconds};
+use crate::util::degrees::Degrees;
+use crate::{moon, sun};
+
+/// Time between samples of the elongation, in days. The elongation grows by
+/// about 12 degrees a day
+const SAMPLE_STEP: f64 = 1.0;
+
+/// Stop refining a phase or season at this precision, in days, about a
+/// second
+const PRECISION: f64 = 1e-5;
+
+/// Longest season, in days. The Sun's longitude changes by more than 90
+/// degrees in this time
+const MAX_SEASON: f64 = 95.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PhaseKind {
+    NewMoon,
+    FirstQuarter,
+    FullMoon,
+    LastQuarter,
+}
+
+impl PhaseKind {
+    const ALL: [PhaseKind; 4] = [
+        PhaseKind::NewMoon,
+        PhaseKind::FirstQuarter,
+        PhaseKind::FullMoon,
+        PhaseKind::LastQuarter,
+    ];
+
+    /// Elongation of the Moon at this phase, in degrees
+    fn elongation(self) -> Degrees {
+        match self {
+            PhaseKind::NewMoon => Degrees::new(0.0),
+            PhaseKind::FirstQuarter => Degrees::new(90.0),
+            PhaseKind::FullMoon => Degrees::new(180.0),
+            PhaseKind::LastQuarter => Degrees::new(270.0),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PhaseEvent {
+    pub kind: PhaseKind,
+
+    /// Traditional name of a full moon, None for the other phases
+    pub name: Option<FullMoonName>,
+
+    /// Julian day, in dynamical time for phase_events, in UTC for
+    /// month_phases
+    pub jd: JD,
+}
+
+/// The principal phases in a calendar month, in the observer's time zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthPhases {
+    /// Phases in the month, in time order. Julian days are in UTC
+    pub events: Vec<PhaseEvent>,
+
+    /// Two full moons in the month
+    pub blue_moon: bool,
+
+    /// Two new moons in the month
+    pub black_moon: bool,
+
+    /// A full moon in the month is the third of four in its season
+    pub seasonal_blue_moon: bool,
+}
+
+/// Elongation of the Moon, the difference of the apparent geocentric
+/// longitudes of Moon and Sun
+/// In: Julian day, in dynamical time
+/// Out: elongation, in degrees [0, 360)
+fn elongation(jd: JD) -> Degrees {
+    (moon::geocentric(jd).lambda - sun::apparent_position(jd).longitude).map_to_0_to_360()
+}
+
+/// Refine a time where a function increases through 0 by bisection
+/// In:
+/// f: function, increasing between a and b
+/// a, b: times f changes sign between
+/// Out: time of the root
+fn bisect<F>(f: F, mut a: f64, mut b: f64) -> JD
+where
+    F: Fn(JD) -> f64,
+{
+    while b - a > PRECISION {
+        let mid = 0.5 * (a + b);
+        if f(JD::new(mid)) < 0.0 {
+            a = mid;
+        } else {
+            b = mid;
+        }
+    }
+    JD::new(0.5 * (a + b))
+}
+
+/// Calculate the Moon's principal phases in a time range
+/// In:
+/// start, end: Julian days, in dynamical time
+/// Out: phases from start to end, in time order. Full moons are named after
+/// the calendar month in UTC they fall in
+pub fn phase_events(start: JD, end: JD) -> Vec<PhaseEvent> {
+    with_names(search(start, end), 0)
+}
+
+/// Name the full moons
+/// In:
+/// events: phases, in dynamical time
+/// timezone_offset: Observer's time zone offset, in hours
+/// Out: phases, with the names of the full moons
+fn with_names(events: Vec<PhaseEvent>, timezone_offset: i8) -> Vec<PhaseEvent> {
+    events
+        .into_iter()
+        .map(|event| PhaseEvent {
+            name: (event.kind == PhaseKind::FullMoon)
+                .then(|| names::full_moon_name(event.jd, timezone_offset)),
+            ..event
+        })
+        .collect()
+}
+
+/// Find the Moon's principal phases in a time range, without naming them
+/// In:
+/// start, end: Julian days, in dynamical time
+/// Out: phases from start to end, in time order
+pub(crate) fn search(start: JD, end: JD) -> Vec<PhaseEvent> {
+    let mut events = vec![];
+
+    let mut a = start.jd;
+    let mut elongation_a = elongation(start);
+    while a < end.jd {
+        let b = (a + SAMPLE_STEP).min(end.jd);
+        let elongation_b = elongation(JD::new(b));
+
+        for kind in PhaseKind::ALL {
+            let before = elongation_a.signed_difference(kind.elongation()).0;
+            let after = elongation_b.signed_difference(kind.elongation()).0;
+
+            // SS: the difference also jumps from 180 to -180 half a month
+            // after the phase
+            if before < 0.0 && after >= 0.0 && after - before < 90.0 {
+                let difference = |jd: JD| elongation(jd).signed_difference(kind.elongation()).0;
+                events.push(PhaseEvent {
+                    kind,
+                    name: None,
+                    jd: bisect(difference, a, b),
+                });
+            }
+        }
+
+        a = b;
+        elongation_a = elongation_b;
+    }
+
+    events
+}
+
+/// Start and end of the astronomical season containing a time, i.e. the
+/// times the Sun's apparent longitude is the multiples of 90 degrees before
+/// and after it
+/// In: Julian day, in dynamical time
+/// Out: (start, end) of the season, in dynamical time
+pub fn season(jd: JD) -> (JD, JD) {
+    let longitude = sun::apparent_position(jd).longitude;
+    let start_longitude = Degrees::new((longitude.0 / 90.0).floor() * 90.0);
+    let end_longitude = start_longitude + Degrees::new(90.0);
+
+    // SS: the Sun moves less than 95 degrees in 95 days, so the difference
+    // does not wrap around in the bracket
+    let start = solar_longitude_crossing(start_longitude, JD::new(jd.jd - MAX_SEASON), jd);
+    let end = solar_longitude_crossing(end_longitude, jd, JD::new(jd.jd + MAX_SEASON));
+    (start, end)
+}
+
+/// Time the Sun's apparent longitude reaches a value
+/// In:
+/// longitude: Sun's apparent longitude, in degrees
+/// a, b: Julian days, in dynamical time, the Sun's longitude is less than
+/// 180 degrees before and after the value at
+/// Out: Julian day, in dynamical time
+pub(crate) fn solar_longitude_crossing(longitude: Degrees, a: JD, b: JD) -> JD {
+    let difference = |jd: JD| {
+        sun::apparent_position(jd)
+            .longitude
+            .signed_difference(longitude)
+            .0
+    };
+    bisect(difference, a.jd, b.jd)
+}
+
+/// Whether a full moon is the third of four in its season
+/// In: Julian day of the full moon, in dynamical time
+/// Out: true for a seasonal blue moon
+pub fn is_seasonal_blue_moon(jd: JD) -> bool {
+    let (start, end) = season(jd);
+    let full_moons: Vec<JD> = search(start, end)
+        .into_iter()
+        .filter(|event| event.kind == PhaseKind::FullMoon)
+        .map(|event| event.jd)
+        .collect();
+
+    full_moons.len() == 4 && (full_moons[2].jd - jd.jd).abs() < SAMPLE_STEP
+}
+
+/// Calculate the Moon's principal phases in a calendar month
+/// In:
+/// year: Year, e.g. 2024
+/// month: Month, 1 to 12
+/// timezone_offset: Observer's time zone offset, in hours. The month starts
+/// at local midnight
+/// Out: Phases and flags for the month view
+pub fn month_phases(year: i16, month: u8, timezone_offset: i8) -> MonthPhases {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    let local_midnight = |year: i16, month: u8| {
+        let mut jd = JD::from_date(Date::new(year, month, 1.0));
+        jd.add_hours(-timezone_offset as f64);
+        time::utc_2_tt(jd)
+    };
+    let start = local_midnight(year, month);
+    let end = local_midnight(next_year, next_month);
+
+    let events = with_names(search(start, end), timezone_offset);
+    let count = |kind: PhaseKind| events.iter().filter(|event| event.kind == kind).count();
+    let seasonal_blue_moon = events
+        .iter()
+        .filter(|event| event.kind == PhaseKind::FullMoon)
+        .any(|event| is_seasonal_blue_moon(event.jd));
+
+    let table = LeapSeconds::iers();
+    MonthPhases {
+        blue_moon: count(PhaseKind::FullMoon) == 2,
+        black_moon: count(PhaseKind::NewMoon) == 2,
+        seasonal_blue_moon,
+        events: events
+            .into_iter()
+            .map(|event| PhaseEvent {
+                jd: time::tt_2_utc(event.jd, &table),
+                ..event
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn phase_events_test() {
+        // Arrange
+
+        // SS: Meeus, example 49.a, new moon on 1977 February 18, 3h 37m 42s TD
+        let start = JD::from_date(Date::new(1977, 2, 15.0));
+        let end = JD::from_date(Date::new(1977, 2, 22.0));
+
+        // Act
+        let events = phase_events(start, end);
+
+        // Assert
+        assert_eq!(1, events.len());
+        assert_eq!(PhaseKind::NewMoon, events[0].kind);
+        assert_approx_eq!(2_443_192.651_18, events[0].jd.jd, 1.0 / 1440.0);
+    }
+
+    #[test]
+    fn phase_events_order_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2024, 1, 1.0));
+        let end = JD::from_date(Date::new(2024, 7, 1.0));
+
+        // Act
+        let events = phase_events(start, end);
+
+        // Assert
+
+        // SS: the phases follow each other about a week apart
+        assert!(events.len() >= 24);
+        for pair in events.windows(2) {
+            let next = PhaseKind::ALL
+                .iter()
+                .position(|&kind| kind == pair[0].kind)
+                .map(|idx| PhaseKind::ALL[(idx + 1) % 4])
+                .unwrap();
+            assert_eq!(next, pair[1].kind);
+            let days = pair[1].jd.jd - pair[0].jd.jd;
+            assert!(days > 5.5 && days < 9.0, "{days}");
+        }
+    }
+
+    #[test]
+    fn blue_moon_test() {
+        // Act
+
+        // SS: full moons on 2023 August 1 and 31
+        let august = month_phases(2023, 8, 0);
+        let september = month_phases(2023, 9, 0);
+
+        // Assert
+        assert!(august.blue_moon);
+        assert!(!august.black_moon);
+        assert!(!september.blue_moon);
+        let names: Vec<Option<FullMoonName>> = august
+            .events
+            .iter()
+            .filter(|event| event.kind == PhaseKind::FullMoon)
+            .map(|event| event.name)
+            .collect();
+        assert_eq!(
+            vec![Some(FullMoonName::Sturgeon), Some(FullMoonName::Sturgeon)],
+            names
+        );
+    }
+
+    #[test]
+    fn black_moon_test() {
+        // Act
+
+        // SS: new moons on 2022 April 1, 6h 24m UTC, and April 30, 20h 28m
+        // UTC
+        let april = month_phases(2022, 4, 0);
+
+        // Assert
+        assert!(april.black_moon);
+        assert!(!april.blue_moon);
+        let new_moons: Vec<&PhaseEvent> = april
+            .events
+            .iter()
+            .filter(|event| event.kind == PhaseKind::NewMoon)
+            .collect();
+        assert_approx_eq!(2_459_670.5 + 6.4 / 24.0, new_moons[0].jd.jd, 0.01);
+    }
+
+    #[test]
+    fn time_zone_test() {
+        // Act
+
+        // SS: the new moon on 2022 April 30, 20h 28m UTC is on May 1 in
+        // Tokyo
+        let utc = month_phases(2022, 4, 0);
+        let tokyo = month_phases(2022, 4, 9);
+
+        // Assert
+        assert!(utc.black_moon);
+        assert!(!tokyo.black_moon);
+    }
+
+    #[test]
+    fn seasonal_blue_moon_test() {
+        // Act
+
+        // SS: full moons on 2024 June 22, July 21, August 19 and September
+        // 18, between the solstice on June 20 and the equinox on September 22
+        let july = month_phases(2024, 7, 0);
+        let august = month_phases(2024, 8, 0);
+
+        // Assert
+        assert!(!july.seasonal_blue_moon);
+        assert!(august.seasonal_blue_moon);
+        assert!(!august.blue_moon);
+    }
+
+    #[test]
+    fn season_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2024, 8, 1.0));
+
+        // Act
+        let (start, end) = season(jd);
+
+        // Assert
+
+        // SS: solstice on 2024 June 20, 20h 51m UTC, equinox on September 22,
+        // 12h 44m UTC
+        assert_approx_eq!(2_460_481.5 + 20.85 / 24.0, start.jd, 0.01);
+        assert_approx_eq!(2_460_575.5 + 12.73 / 24.0, end.jd, 0.01);
+    }
+}
@@ -0,0 +1,506 @@
+//! Times the Moon passes a given direction in the sky, e.g. to photograph it
+//! rising at the end of a street ("Moonhenge"), or a given altitude, e.g. to
+//! see it clear a rooftop.
+//!
+//! Unlike rise and set, Meeus chapter 15, which find at most one event of
+//! each kind per day, the day is sampled and all crossings are returned.
+//! Sampling also works for an observer who moves, see rise_set_along.
+
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::horizon::{FlatHorizon, Horizon};
+use crate::rise_set_transit::bound_julian_day;
+use crate::session::Observer;
+use crate::track::ObserverTrack;
+use crate::util::degrees::Degrees;
+use crate::{moon, time};
+
+/// Time between samples, in hours. The Moon's azimuth changes by less than
+/// 90 degrees in this time unless it passes within a degree of the zenith
+const SAMPLE_STEP: f64 = 5.0 / 60.0;
+
+/// Stop refining a crossing at this precision, in days, about a second
+const PRECISION: f64 = 1e-5;
+
+/// Time to look ahead to tell whether the Moon rises, in hours
+const RISING_STEP: f64 = 1.0 / 60.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Crossing {
+    /// Julian day, in UTC
+    pub jd: JD,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: Degrees,
+
+    /// Apparent altitude, in degrees [-90, 90]. Negative if the Moon is
+    /// below the horizon
+    pub altitude: Degrees,
+
+    /// Whether the Moon's altitude increases
+    pub is_rising: bool,
+}
+
+/// Calculate the times the Moon's topocentric azimuth equals a bearing
+/// In:
+/// observer: Observer's location, time zone offset and atmospheric conditions
+/// date: Day to search, in the observer's time zone. The fraction of the day
+/// is ignored
+/// azimuth: Bearing, measured from North, increasing to the East, in degrees
+/// Out:
+/// Crossings from local midnight to the next local midnight, in time order.
+/// They include crossings while the Moon is below the horizon, see
+/// Crossing::altitude
+pub fn azimuth_crossings(observer: &Observer, date: Date, azimuth: f64) -> Vec<Crossing> {
+    let target = Degrees::new(azimuth);
+
+    // SS: the difference is discontinuous where the Moon passes the opposite
+    // direction, it jumps by 360 degrees there
+    let difference = |jd: JD| {
+        (position(observer, jd).azimuth - target)
+            .map_to_0_to_360()
+            .map_neg180_to_180()
+            .0
+    };
+    crossings(observer, date, |jd| {
+        let difference = difference(jd);
+        (difference.abs() < 90.0).then_some(difference)
+    })
+}
+
+/// Calculate the times the Moon's center reaches an apparent altitude, i.e.
+/// including the atmospheric refraction at the observer's pressure and
+/// temperature
+/// In:
+/// observer: Observer's location, time zone offset and atmospheric conditions
+/// date: Day to search, in the observer's time zone. The fraction of the day
+/// is ignored
+/// altitude: Apparent altitude, in degrees [-90, 90]
+/// Out:
+/// Crossings from local midnight to the next local midnight, in time order.
+/// Empty if the Moon stays above or below the altitude all day
+pub fn altitude_crossings(observer: &Observer, date: Date, altitude: f64) -> Vec<Crossing> {
+    crossings(observer, date, |jd| {
+        Some(position(observer, jd).altitude.0 - altitude)
+    })
+}
+
+/// Calculate the times the Moon rises and sets for an observer who moves,
+/// e.g. on a ship. The Moon's position is calculated for the observer's
+/// position at each time, so the times and bearings are those seen from
+/// the ship, not from where it was at the start
+/// In:
+/// track: Observer's position over time
+/// start: Julian day to start the search at, in UTC
+/// end: Julian day to end the search at, in UTC
+/// Out:
+/// Times the Moon's upper limb touches the horizon, in time order, with
+/// the bearing, see Crossing::azimuth. Rises have is_rising set
+pub fn rise_set_along<T: ObserverTrack>(track: &T, start: JD, end: JD) -> Vec<Crossing> {
+    rise_set_with_horizon(track, start, end, &FlatHorizon)
+}
+
+/// Calculate the times the Moon rises above and sets below the observer's
+/// skyline, e.g. the sea horizon seen from a ship's deck, see horizon::Dip,
+/// or mountains, see horizon::HorizonProfile
+/// In:
+/// track: Observer's position over time, an Observer if it does not move
+/// start: Julian day to start the search at, in UTC
+/// end: Julian day to end the search at, in UTC
+/// horizon: Altitude of the skyline
+/// Out:
+/// Times the Moon's upper limb touches the skyline, in time order, with
+/// the bearing, see Crossing::azimuth. Rises have is_rising set
+pub fn rise_set_with_horizon<T, H>(track: &T, start: JD, end: JD, horizon: &H) -> Vec<Crossing>
+where
+    T: ObserverTrack,
+    H: Horizon + ?Sized,
+{
+    // SS: the upper limb touches the skyline when the center is a
+    // semidiameter below it
+    crossings_between(track, start, end, |jd| {
+        let tt = time::utc_2_tt(jd);
+        let semidiameter = Degrees::from(moon::semidiameter::geocentric_semidiameter(tt));
+        let topocentric = moon::position::topocentric_coordinates(tt, &track.observer(jd), 0.0);
+        let skyline = horizon.altitude(topocentric.azimuth);
+        Some(topocentric.apparent_altitude.0 + semidiameter.0 - skyline.0)
+    })
+}
+
+/// Moon's position for a time in UTC
+fn position<T: ObserverTrack + ?Sized>(track: &T, jd: JD) -> Crossing {
+    let altitude = |jd: JD| {
+        let tt = time::utc_2_tt(jd);
+        moon::position::topocentric_coordinates(tt, &track.observer(jd), 0.0)
+    };
+
+    let topocentric = altitude(jd);
+    let mut later = jd;
+    later.add_hours(RISING_STEP);
+
+    Crossing {
+        jd,
+        azimuth: topocentric.azimuth,
+        altitude: topocentric.apparent_altitude,
+        is_rising: altitude(later).apparent_altitude.0 > topocentric.apparent_altitude.0,
+    }
+}
+
+/// Find the roots of a function over the local day, by sampling it and
+/// bisecting the samples it changes sign between
+/// In:
+/// observer: Observer's location and time zone offset
+/// date: Day to search, in the observer's time zone
+/// f: function of the time in UTC, None where it has no meaningful sign
+/// Out:
+/// Positions at the roots, in time order
+fn crossings<F>(observer: &Observer, date: Date, f: F) -> Vec<Crossing>
+where
+    F: Fn(JD) -> Option<f64>,
+{
+    let day = Date::new(date.year, date.month, date.day.trunc());
+    let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(day), observer.timezone_offset);
+    crossings_between(observer, jd_min, jd_max, f)
+}
+
+/// Find the roots of a function between two times, by sampling it and
+/// bisecting the samples it changes sign between
+/// In:
+/// track: Observer's position over time
+/// jd_min, jd_max: times to search between, in UTC
+/// f: function of the time in UTC, None where it has no meaningful sign
+/// Out:
+/// Positions at the roots, in time order
+fn crossings_between<T, F>(track: &T, jd_min: JD, jd_max: JD, f: F) -> Vec<Crossing>
+where
+    T: ObserverTrack + ?Sized,
+    F: Fn(JD) -> Option<f64>,
+{
+    let mut crossings = vec![];
+    let mut a = jd_min;
+    let mut f_a = f(a);
+    while a < jd_max {
+        let mut b = a;
+        b.add_hours(SAMPLE_STEP);
+        if b > jd_max {
+            b = jd_max;
+        }
+        let f_b = f(b);
+
+        if let (Some(value_a), Some(value_b)) = (f_a, f_b) {
+            // SS: a root on a sample is found in the interval it ends
+            if value_a * value_b < 0.0 || value_b == 0.0 {
+                crossings.push(bisect(&f, a, b, value_a));
+            }
+        }
+
+        a = b;
+        f_a = f_b;
+    }
+
+    crossings
+        .into_iter()
+        .map(|jd| position(track, jd))
+        .collect()
+}
+
+/// Refine a root by bisection
+/// In:
+/// f: function of the time in UTC
+/// a, b: times f changes sign between
+/// f_a: f(a)
+/// Out: time of the root, in UTC
+fn bisect<F>(f: &F, a: JD, b: JD, f_a: f64) -> JD
+where
+    F: Fn(JD) -> Option<f64>,
+{
+    let (mut a, mut b, mut f_a) = (a.jd, b.jd, f_a);
+    while b - a > PRECISION {
+        let mid = 0.5 * (a + b);
+
+        // SS: the function is meaningful between the two samples
+        let f_mid = f(JD::new(mid)).unwrap_or(f_a);
+        if f_mid * f_a > 0.0 {
+            a = mid;
+            f_a = f_mid;
+        } else {
+            b = mid;
+        }
+    }
+    JD::new(0.5 * (a + b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::horizon::{Dip, HorizonProfile};
+    use crate::moon::bearing_table;
+    use crate::test_util::munich;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn azimuth_crossings_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+
+        let mut count = 0;
+        for azimuth in (0..360).step_by(15) {
+            // Act
+            let crossings = azimuth_crossings(&munich(1), date, azimuth as f64);
+
+            // Assert
+
+            // SS: the Moon goes around once in 24h 50m, so each azimuth is
+            // crossed once a day, except for those it passes in the 50
+            // minutes after the local day ends
+            assert!(crossings.len() <= 1, "{azimuth}");
+            for crossing in &crossings {
+                let difference = (crossing.azimuth - Degrees::new(azimuth as f64))
+                    .map_to_0_to_360()
+                    .map_neg180_to_180();
+                assert_approx_eq!(0.0, difference.0, 0.01);
+            }
+            count += crossings.len();
+        }
+        assert!(count >= 22, "{count}");
+    }
+
+    #[test]
+    fn azimuth_crossings_south_test() {
+        // Arrange
+        let date = Date::new(2000, 3, 23.5);
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let crossings = azimuth_crossings(&observer, date, 180.0);
+
+        // Assert
+
+        // SS: the Moon transits at about 1:35 UTC, due South, up to the
+        // parallax in azimuth
+        assert_eq!(1, crossings.len());
+        assert_approx_eq!(2_451_626.5 + 1.58 / 24.0, crossings[0].jd.jd, 0.01);
+        assert!(crossings[0].altitude.0 > 0.0);
+    }
+
+    #[test]
+    fn azimuth_crossings_table_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+        let table = bearing_table(&munich(1), date, 1.0);
+
+        // Act
+        let crossings = azimuth_crossings(&munich(1), date, table[7].azimuth.0);
+
+        // Assert
+
+        // SS: the table gives the geocentric azimuth, the crossing the
+        // topocentric one, which differs by up to a degree
+        assert_eq!(1, crossings.len());
+        assert_approx_eq!(table[7].jd.jd, crossings[0].jd.jd, 0.05);
+    }
+
+    #[test]
+    fn azimuth_crossings_time_order_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+
+        // Act
+        let crossings = azimuth_crossings(&munich(1), date, 100.0);
+
+        // Assert
+        let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(date), munich(1).timezone_offset);
+        assert!(crossings
+            .iter()
+            .all(|crossing| jd_min <= crossing.jd && crossing.jd <= jd_max));
+        assert!(crossings.windows(2).all(|pair| pair[0].jd < pair[1].jd));
+    }
+
+    #[test]
+    fn altitude_crossings_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+
+        // Act
+
+        // SS: clears a 12 degree rooftop
+        let crossings = altitude_crossings(&munich(1), date, 12.0);
+
+        // Assert
+
+        // SS: the Moon is up at local midnight, it sets below the rooftop
+        // in the morning and rises above it again in the afternoon
+        assert_eq!(2, crossings.len());
+        assert!(!crossings[0].is_rising);
+        assert!(crossings[1].is_rising);
+        for crossing in crossings {
+            assert_approx_eq!(12.0, crossing.altitude.0, 0.001);
+        }
+    }
+
+    #[test]
+    fn altitude_crossings_rise_set_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+        let session =
+            crate::session::Session::new(munich(1), JD::from_date(date), JD::from_date(date));
+
+        // Act
+
+        // SS: the upper limb touches the horizon when the center is a
+        // semidiameter below it
+        let crossings = altitude_crossings(&munich(1), date, -0.26);
+
+        // Assert
+        let rise = match session.moon().rise()[0] {
+            moon::rise_set_transit::OutputKind::Time(tt) => {
+                time::tt_2_utc(tt, &time::LeapSeconds::iers())
+            }
+            _ => panic!("the Moon rises on 2024 March 20"),
+        };
+        let rising: Vec<&Crossing> = crossings.iter().filter(|c| c.is_rising).collect();
+        assert_eq!(1, rising.len());
+        assert_approx_eq!(rise.jd, rising[0].jd.jd, 2.0 / (24.0 * 60.0));
+    }
+
+    #[test]
+    fn altitude_crossings_never_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+
+        // Act
+        let above_zenith = altitude_crossings(&munich(1), date, 89.0);
+        let below_nadir = altitude_crossings(&munich(1), date, -89.0);
+
+        // Assert
+        assert!(above_zenith.is_empty());
+        assert!(below_nadir.is_empty());
+    }
+
+    #[test]
+    fn rise_set_along_stationary_test() {
+        // Arrange
+        let date = Date::new(2024, 3, 20.0);
+        let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(date), munich(1).timezone_offset);
+
+        // Act
+        let events = rise_set_along(&munich(1), jd_min, jd_max);
+
+        // Assert
+
+        // SS: the same as the crossings of a semidiameter below the horizon
+        let crossings = altitude_crossings(&munich(1), date, -0.26);
+        assert_eq!(crossings.len(), events.len());
+        for (event, crossing) in events.iter().zip(crossings) {
+            assert_eq!(crossing.is_rising, event.is_rising);
+            assert_approx_eq!(crossing.jd.jd, event.jd.jd, 1.0 / (24.0 * 60.0));
+        }
+    }
+
+    #[test]
+    fn rise_set_along_ship_test() {
+        // Arrange
+
+        // SS: a ship in the North Atlantic, steaming east at 20 knots, i.e.
+        // 20 arc minutes of a great circle per hour
+        let start = JD::from_date(Date::new(2024, 3, 20.0));
+        let departure = Observer::new(30.0, 40.0, -2, 1013.0, 15.0);
+        let knots = 20.0 / 60.0 / departure.latitude.0.to_radians().cos();
+        let ship = |jd: JD| {
+            let hours = (jd.jd - start.jd) * 24.0;
+            Observer::new(
+                departure.longitude.0 - knots * hours,
+                40.0,
+                -2,
+                1013.0,
+                15.0,
+            )
+        };
+        let end = JD::new(start.jd + 3.0);
+
+        // Act
+        let events = rise_set_along(&ship, start, end);
+
+        // Assert
+        let stationary = rise_set_along(&departure, start, end);
+        assert_eq!(stationary.len(), events.len());
+        assert!(events.len() >= 5);
+        for (event, stationary) in events.iter().zip(stationary) {
+            // SS: the upper limb is on the horizon seen from the ship
+            let tt = time::utc_2_tt(event.jd);
+            let altitude =
+                moon::position::topocentric_coordinates(tt, &ship(event.jd), 0.0).apparent_altitude;
+            let semidiameter = Degrees::from(moon::semidiameter::geocentric_semidiameter(tt));
+            assert_approx_eq!(0.0, altitude.0 + semidiameter.0, 0.001);
+
+            // SS: sailing east, the Moon rises and sets earlier than at the
+            // point of departure, by 4 minutes per degree of longitude
+            let hours = (event.jd.jd - start.jd) * 24.0;
+            let expected = 4.0 * knots * hours / (24.0 * 60.0);
+            assert!(event.jd < stationary.jd);
+            assert_approx_eq!(expected, stationary.jd.jd - event.jd.jd, 0.3 * expected);
+        }
+    }
+
+    #[test]
+    fn rise_set_with_dip_test() {
+        // Arrange
+
+        // SS: from a ship's bridge 30m above the sea
+        let date = Date::new(2024, 3, 20.0);
+        let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(date), munich(1).timezone_offset);
+        let bridge = Dip::new(30.0);
+
+        // Act
+        let events = rise_set_with_horizon(&munich(1), jd_min, jd_max, &bridge);
+
+        // Assert
+        let flat = rise_set_along(&munich(1), jd_min, jd_max);
+        assert_eq!(flat.len(), events.len());
+        assert!(!events.is_empty());
+        for (event, flat) in events.iter().zip(flat) {
+            // SS: the Moon rises earlier and sets later, by more than a minute
+            let minutes = (event.jd.jd - flat.jd.jd) * 24.0 * 60.0;
+            if event.is_rising {
+                assert!(-2.0 < minutes && minutes < -1.0, "{minutes}");
+            } else {
+                assert!(1.0 < minutes && minutes < 2.0, "{minutes}");
+            }
+        }
+    }
+
+    #[test]
+    fn rise_set_with_horizon_profile_test() {
+        // Arrange
+
+        // SS: mountains in the south-east and a flat horizon to the west
+        let date = Date::new(2024, 3, 20.0);
+        let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(date), munich(1).timezone_offset);
+        let profile = HorizonProfile::new(vec![
+            (Degrees::new(60.0), Degrees::new(5.0)),
+            (Degrees::new(150.0), Degrees::new(5.0)),
+            (Degrees::new(200.0), Degrees::new(0.0)),
+            (Degrees::new(350.0), Degrees::new(0.0)),
+        ])
+        .unwrap();
+
+        // Act
+        let events = rise_set_with_horizon(&munich(1), jd_min, jd_max, &profile);
+
+        // Assert
+        let flat = rise_set_along(&munich(1), jd_min, jd_max);
+        assert_eq!(flat.len(), events.len());
+        for (event, flat) in events.iter().zip(flat) {
+            let tt = time::utc_2_tt(event.jd);
+            let semidiameter = Degrees::from(moon::semidiameter::geocentric_semidiameter(tt));
+            let skyline = profile.altitude(event.azimuth);
+            assert_approx_eq!(skyline.0, event.altitude.0 + semidiameter.0, 0.001);
+
+            if event.is_rising {
+                assert!(event.jd.jd - flat.jd.jd > 20.0 / (24.0 * 60.0));
+            } else {
+                assert_approx_eq!(flat.jd.jd, event.jd.jd, 1e-4);
+            }
+        }
+    }
+}
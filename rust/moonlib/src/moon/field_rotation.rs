@@ -0,0 +1,177 @@
+//! Field rotation, i.e. how fast the Moon's image turns in the field of view
+//! of a telescope on an alt-az mount. The image turns with the parallactic
+//! angle, so a derotator has to follow its rate of change to keep the Moon's
+//! orientation fixed during an exposure.
+//!
+//! The rate is fastest close to the zenith, where it grows without bound,
+//! and slowest close to the horizon. The change of the Moon's parallax as
+//! it moves across the sky is ignored, which changes the rate by about 1%.
+use crate::date::jd::JD;
+use crate::moon::position::GeocentricMoon;
+use crate::moon::velocity::MoonVelocity;
+use crate::session::Observer;
+use crate::util::{degrees::Degrees, math, radians::Radians};
+use crate::{coordinates, earth, ecliptic, moon};
+
+/// Rate of Earth's rotation relative to the equinox, in degrees per day,
+/// Meeus, eq (12.4)
+const SIDERIAL_RATE: f64 = 360.985_647_366_29;
+
+const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+
+/// Calculate the rate of change of the moon's parallactic angle for an
+/// observer at sea level
+/// In:
+/// jd: Julian day, in dynamical time
+/// observer: Observer's location
+/// Out: Rate of change of the parallactic angle, in degrees per minute.
+/// Positive if the image turns counterclockwise, as seen by the observer
+pub fn field_rotation_rate(jd: JD, observer: &Observer) -> f64 {
+    let moon = moon::geocentric(jd);
+    let velocity = moon::longitudinal_velocity(jd);
+    let (ra_rate, decl_rate) = equatorial_rates(&moon, &velocity, ecliptic::true_obliquity(jd));
+
+    let (ra, decl) = coordinates::equatorial_2_topocentric(
+        moon.ra,
+        moon.dec,
+        observer.longitude,
+        observer.latitude,
+        0.0,
+        moon.delta,
+        jd,
+    );
+    let siderial_time =
+        earth::local_siderial_time(earth::apparent_siderial_time(jd), observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time, ra);
+
+    // SS: the moon moves eastward, so its hour angle increases slower than
+    // that of a star
+    let rate = coordinates::parallactic_angle_rate(
+        hour_angle,
+        decl,
+        observer.latitude,
+        SIDERIAL_RATE - ra_rate,
+        decl_rate,
+    );
+
+    rate / MINUTES_PER_DAY
+}
+
+/// Convert the rate of change of the moon's ecliptical coordinates to the
+/// rate of change of its equatorial coordinates, by differentiating the unit
+/// vector towards the moon and rotating it by the obliquity of the ecliptic
+/// In:
+/// moon: Moon's apparent geocentric position
+/// velocity: rates of change of the ecliptical coordinates
+/// eps: obliquity of the ecliptic, in degrees
+/// Out:
+/// rates of change of right ascension and declination, in degrees per day
+fn equatorial_rates(moon: &GeocentricMoon, velocity: &MoonVelocity, eps: Degrees) -> (f64, f64) {
+    let lambda = Radians::from(moon.lambda).0;
+    let beta = Radians::from(moon.beta).0;
+    let eps = Radians::from(eps).0;
+
+    let x = math::cos(beta) * math::cos(lambda);
+    let y = math::cos(beta) * math::sin(lambda);
+    let z = math::sin(beta);
+
+    let dx = -math::sin(beta) * math::cos(lambda) * velocity.latitude
+        - math::cos(beta) * math::sin(lambda) * velocity.longitude;
+    let dy = -math::sin(beta) * math::sin(lambda) * velocity.latitude
+        + math::cos(beta) * math::cos(lambda) * velocity.longitude;
+    let dz = math::cos(beta) * velocity.latitude;
+
+    // SS: rotate about the x axis, towards the equator
+    let y_equatorial = y * math::cos(eps) - z * math::sin(eps);
+    let dy_equatorial = dy * math::cos(eps) - dz * math::sin(eps);
+    let dz_equatorial = dy * math::sin(eps) + dz * math::cos(eps);
+
+    let rho2 = x * x + y_equatorial * y_equatorial;
+    let ra_rate = (x * dy_equatorial - y_equatorial * dx) / rho2;
+    let decl_rate = dz_equatorial / rho2.sqrt();
+
+    (ra_rate, decl_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::render::render_params;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn equatorial_rates_test() {
+        // Arrange
+
+        // SS: step for the central differences, in days
+        const H: f64 = 0.01;
+
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let moon = moon::geocentric(jd);
+        let velocity = moon::longitudinal_velocity(jd);
+
+        // Act
+        let (ra_rate, decl_rate) = equatorial_rates(&moon, &velocity, ecliptic::true_obliquity(jd));
+
+        // Assert
+        let before = moon::geocentric(JD::new(jd.jd - H));
+        let after = moon::geocentric(JD::new(jd.jd + H));
+        let ra = (after.ra.degrees() - before.ra.degrees()).map_neg180_to_180();
+        let decl = after.dec.degrees() - before.dec.degrees();
+
+        assert_approx_eq!(ra.0 / (2.0 * H), ra_rate, 0.001);
+        assert_approx_eq!(decl.0 / (2.0 * H), decl_rate, 0.001);
+    }
+
+    #[test]
+    fn field_rotation_rate_test() {
+        // Arrange
+
+        // SS: step for the central differences, in minutes
+        const H: f64 = 0.5;
+
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+        let start = JD::from_date(Date::new(2024, 3, 1.0));
+
+        for minutes in (0..24 * 60).step_by(37) {
+            let jd = JD::new(start.jd + minutes as f64 / MINUTES_PER_DAY);
+
+            // Act
+            let rate = field_rotation_rate(jd, &observer);
+
+            // Assert
+            let q = |minutes: f64| {
+                render_params(JD::new(jd.jd + minutes / MINUTES_PER_DAY), &observer)
+                    .parallactic_angle
+            };
+            let expected = (q(H) - q(-H)).map_neg180_to_180().0 / (2.0 * H);
+            assert_approx_eq!(expected, rate, 0.015 * expected.abs() + 5e-4);
+        }
+    }
+
+    #[test]
+    fn field_rotation_rate_transit_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // SS: 2024 March 1, the moon transits at about 3:05 UTC, about 21
+        // degrees above the horizon
+        let jd = JD::from_date(Date::new(2024, 3, 1.128));
+
+        // Act
+        let rate = field_rotation_rate(jd, &observer);
+
+        // Assert
+
+        // SS: for an object south of the zenith, the image turns fastest at
+        // transit, by the rate of its hour angle times cos(latitude) /
+        // cos(altitude). The moon's hour angle increases by about 348 degrees
+        // a day
+        let altitude = moon::position::topocentric_coordinates(jd, &observer, 0.0).true_altitude;
+        let expected = 348.0 / MINUTES_PER_DAY * math::cos(Radians::from(observer.latitude).0)
+            / math::cos(Radians::from(altitude).0);
+        assert!(rate > 0.0);
+        assert_approx_eq!(expected, rate, 0.01 * expected);
+    }
+}
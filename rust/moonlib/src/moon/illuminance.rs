@@ -0,0 +1,158 @@
+//! Estimate of the ground illuminance due to moonlight, based on
+//! K. Krisciunas and B. E. Schaefer, "A model of the brightness of moonlight",
+//! PASP 103, 1991
+
+use crate::date::jd::JD;
+use crate::moon;
+use crate::session::Observer;
+use crate::util::radians::Radians;
+
+/// Mean distance Earth-Moon the Moon's magnitude refers to, in km
+const MEAN_DISTANCE: f64 = 384_400.0;
+
+/// Extinction coefficient in the V band, in magnitudes per airmass
+pub(crate) const EXTINCTION_COEFFICIENT: f64 = 0.172;
+
+/// Illuminance of a 0 magnitude star, m = -14.18 - 2.5 log10(E)
+const ZERO_MAGNITUDE_LUX_OFFSET: f64 = 14.18;
+
+/// Incident light meter calibration constant, E = 2.5 * 2^EV at ISO 100
+const INCIDENT_LIGHT_CALIBRATION: f64 = 2.5;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Illuminance {
+    /// Illuminance of a horizontal surface, in lux
+    pub lux: f64,
+
+    /// Exposure value at ISO 100. Negative infinity when the Moon is below the horizon
+    pub ev: f64,
+}
+
+/// Apparent visual magnitude of the Moon at mean distance, K&S eq. (9)
+/// In: Phase angle, in degrees [0, 180], 0 being full moon
+/// Out: Visual magnitude
+fn magnitude(phase_angle: f64) -> f64 {
+    -12.73 + 0.026 * phase_angle.abs() + 4.0e-9 * phase_angle.powi(4)
+}
+
+/// Apparent visual magnitude of the Moon outside the atmosphere, corrected
+/// for the Moon's actual distance
+/// In: Julian day in dynamical time
+/// Out: Visual magnitude
+pub(crate) fn apparent_magnitude(jd: JD) -> f64 {
+    let phase_angle = moon::phase::phase_info(jd).phase_angle.0;
+    let distance = moon::position::distance_from_earth(jd);
+    magnitude(phase_angle) + 5.0 * (distance / MEAN_DISTANCE).log10()
+}
+
+/// Relative airmass, G. V. Rozenberg, "Twilight: A Study in Atmospheric Optics",
+/// Plenum Press, 1966. Unlike K&S eq. (3), it stays finite at the horizon.
+/// Unlike coordinates::airmass_kasten_young, it takes the geometric zenith
+/// distance and is defined at and below the horizon, so the extinction
+/// changes smoothly as the Moon rises
+/// In: Zenith distance, in radians
+/// Out: Airmass, 1 in the zenith
+fn airmass_rozenberg(zenith_distance: Radians) -> f64 {
+    let cos_z = zenith_distance.0.cos();
+    1.0 / (cos_z + 0.025 * (-11.0 * cos_z).exp())
+}
+
+/// Estimate the illuminance of a horizontal surface due to moonlight.
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location
+/// Out:
+/// Illuminance in lux and the equivalent exposure value at ISO 100
+pub fn illuminance(jd: JD, observer: &Observer) -> Illuminance {
+    let (_, altitude) =
+        moon::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+
+    if altitude.0 <= 0.0 {
+        return Illuminance {
+            lux: 0.0,
+            ev: f64::NEG_INFINITY,
+        };
+    }
+
+    // SS: magnitude corrected for atmospheric extinction
+    let m = apparent_magnitude(jd);
+    let zenith_distance = std::f64::consts::FRAC_PI_2 - Radians::from(altitude).0;
+    let m = m + EXTINCTION_COEFFICIENT * airmass_rozenberg(Radians::new(zenith_distance));
+
+    // SS: illuminance perpendicular to the Moon's direction, then projected
+    // onto the horizontal plane
+    let lux_normal = 10_f64.powf(-0.4 * (m + ZERO_MAGNITUDE_LUX_OFFSET));
+    let lux = lux_normal * Radians::from(altitude).0.sin();
+
+    Illuminance {
+        lux,
+        ev: (lux / INCIDENT_LIGHT_CALIBRATION).log2(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn magnitude_full_moon_test() {
+        // Arrange
+        let phase_angle = 0.0;
+
+        // Act
+        let m = magnitude(phase_angle);
+
+        // Assert
+        assert_approx_eq!(-12.73, m, 0.000_001);
+    }
+
+    #[test]
+    fn airmass_rozenberg_test() {
+        // Arrange
+        let zenith = Radians::new(0.0);
+        let horizon = Radians::new(std::f64::consts::FRAC_PI_2);
+
+        // Act
+        let airmass_zenith = airmass_rozenberg(zenith);
+        let airmass_horizon = airmass_rozenberg(horizon);
+
+        // Assert
+        assert_approx_eq!(1.0, airmass_zenith, 0.000_01);
+        assert_approx_eq!(40.0, airmass_horizon, 0.000_01);
+    }
+
+    #[test]
+    fn full_moon_high_in_sky_test() {
+        // Arrange
+
+        // SS: full moon, Jan. 17th 2022, 11:48pm UTC, Munich
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 17, 23, 48, 0.0));
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let illuminance = illuminance(jd, &observer);
+
+        // Assert
+
+        // SS: the full moon yields about 0.1 to 0.3 lux, EV -3 to -5
+        assert!(illuminance.lux > 0.1 && illuminance.lux < 0.3);
+        assert!(illuminance.ev > -5.0 && illuminance.ev < -3.0);
+    }
+
+    #[test]
+    fn moon_below_horizon_test() {
+        // Arrange
+
+        // SS: new moon, Jan. 2nd 2022, 6:33pm UTC, the Moon set with the Sun in Munich
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 2, 23, 0, 0.0));
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let illuminance = illuminance(jd, &observer);
+
+        // Assert
+        assert_eq!(0.0, illuminance.lux);
+    }
+}
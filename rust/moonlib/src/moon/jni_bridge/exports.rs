@@ -0,0 +1,297 @@
+//! Registry of the functions exported to the Android app over JNI.
+//!
+//! The JVM finds a native method by a symbol name mangled from the Kotlin
+//! package, class and method name. If the Kotlin side is renamed or moved,
+//! or a Rust export is renamed, the app only fails at runtime with an
+//! UnsatisfiedLinkError. The exports are compiled for Android only, so the
+//! tests below check the registry against the exports in lib.rs and against
+//! the external declarations in NativeAccess.kt on the host.
+
+/// Class the native methods are declared in, with '/' as package separator
+const JNI_CLASS: &str = "com/svenschmidt/kitana/core/NativeAccess$Companion";
+
+/// Class the data classes passed to native methods are nested in
+pub(super) const KOTLIN_CLASS: &str = "com/svenschmidt/kitana/core/NativeAccess";
+
+/// Kotlin source with the external declarations and the data classes,
+/// relative to the crate
+pub(super) const KOTLIN_SOURCE: &str =
+    "../../Kitana/app/src/main/java/com/svenschmidt/kitana/core/NativeAccess.kt";
+
+struct JniExport {
+    /// Kotlin method name
+    method: &'static str,
+
+    /// JNI method descriptor, e.g. (DB)Ljava/lang/String;
+    signature: &'static str,
+}
+
+impl JniExport {
+    /// Symbol name the JVM looks up for the method
+    fn symbol(&self) -> String {
+        format!("Java_{}_{}", mangle(JNI_CLASS), mangle(self.method))
+    }
+}
+
+macro_rules! jni_exports {
+    ($($method:ident: $signature:literal),* $(,)?) => {
+        const JNI_EXPORTS: &[JniExport] = &[
+            $(JniExport { method: stringify!($method), signature: $signature },)*
+        ];
+    };
+}
+
+jni_exports! {
+    rust_julian_day: "(IID)D",
+    rust_julian_day_from_unix_millis: "(J)D",
+    rust_unix_millis_from_julian_day: "(D)J",
+    rust_local_siderial_time: "(DD)D",
+    rust_moon_data: "(Lcom/svenschmidt/kitana/core/NativeAccess$MoonInputData;Lcom/svenschmidt/kitana/core/NativeAccess$MoonOutputData;)V",
+    rust_moon_widget_data: "(Lcom/svenschmidt/kitana/core/NativeAccess$MoonInputData;[B)V",
+    rust_solunar_data: "(Lcom/svenschmidt/kitana/core/NativeAccess$MoonInputData;Lcom/svenschmidt/kitana/core/NativeAccess$SolunarOutputData;)V",
+    rust_to_dms: "(DB)Ljava/lang/String;",
+    rust_to_hms: "(DB)Ljava/lang/String;",
+    rust_to_dms_batch: "([DB)[Ljava/lang/String;",
+    rust_to_hms_batch: "([DB)[Ljava/lang/String;",
+    rust_data_info: "()Ljava/lang/String;",
+}
+
+/// JNI type descriptor of a Kotlin type
+pub(super) fn kotlin_descriptor(kotlin_type: &str) -> String {
+    match kotlin_type {
+        "Double" => "D".to_string(),
+        "Int" => "I".to_string(),
+        "Long" => "J".to_string(),
+        "Short" => "S".to_string(),
+        "Byte" => "B".to_string(),
+        "Boolean" => "Z".to_string(),
+        "Unit" => "V".to_string(),
+        "ByteArray" => "[B".to_string(),
+        "DoubleArray" => "[D".to_string(),
+        "String" => "Ljava/lang/String;".to_string(),
+        "Array<String>" => "[Ljava/lang/String;".to_string(),
+        // SS: data classes nested in NativeAccess
+        t => format!("L{KOTLIN_CLASS}${t};"),
+    }
+}
+
+/// Mangle a class or method name for a native method's symbol, see
+/// "Resolving Native Method Names" in the JNI specification
+fn mangle(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' => "_".to_string(),
+            '_' => "_1".to_string(),
+            ';' => "_2".to_string(),
+            '[' => "_3".to_string(),
+            c if c.is_ascii_alphanumeric() => c.to_string(),
+            c => format!("_0{:04x}", c as u32),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// Split a method descriptor into parameter and return type descriptors
+    fn split_descriptor(signature: &str) -> (Vec<String>, String) {
+        let (params, ret) = signature[1..].split_once(')').unwrap();
+
+        let mut types = vec![];
+        let mut current = String::new();
+        let mut in_class = false;
+        for c in params.chars() {
+            current.push(c);
+            if in_class {
+                // SS: class names end with ';'
+                if c == ';' {
+                    in_class = false;
+                    types.push(std::mem::take(&mut current));
+                }
+            } else if c == 'L' {
+                in_class = true;
+            } else if c != '[' {
+                // SS: '[' prefixes the element type of an array
+                types.push(std::mem::take(&mut current));
+            }
+        }
+
+        (types, ret.to_string())
+    }
+
+    /// JNI type of a parameter or return value in the Rust export
+    fn jni_type(descriptor: &str) -> &'static str {
+        match descriptor {
+            "D" => "jdouble",
+            "I" => "jint",
+            "J" => "jlong",
+            "B" => "jbyte",
+            "V" => "",
+            "[B" => "jbyteArray",
+            "[D" => "jdoubleArray",
+            "Ljava/lang/String;" => "jstring",
+            d if d.starts_with("[L") => "jobjectArray",
+            d if d.starts_with('L') => "jobject",
+            d => panic!("no JNI type for {d}"),
+        }
+    }
+
+    /// Native exports in lib.rs, by symbol: parameter types after JNIEnv
+    /// and JClass, and the return type
+    fn rust_exports() -> BTreeMap<String, (Vec<String>, String)> {
+        let source = include_str!("../../lib.rs");
+        let mut exports = BTreeMap::new();
+
+        for (i, _) in source.match_indices("#[no_mangle]") {
+            let export = &source[i..];
+            let start = export.find("extern \"system\" fn ").unwrap() + 19;
+            let (symbol, rest) = export[start..].split_once('(').unwrap();
+            let (params, rest) = rest.split_once(')').unwrap();
+
+            let params = params
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| p.split_once(':').unwrap().1.trim().to_string())
+                .skip(2)
+                .collect::<Vec<_>>();
+            let ret = rest
+                .split_once('{')
+                .unwrap()
+                .0
+                .trim()
+                .trim_start_matches("->")
+                .trim()
+                .to_string();
+
+            exports.insert(symbol.to_string(), (params, ret));
+        }
+
+        exports
+    }
+
+    /// External declarations in NativeAccess.kt, by method name: JNI
+    /// method descriptor
+    fn kotlin_declarations() -> BTreeMap<String, String> {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(KOTLIN_SOURCE);
+        let source = std::fs::read_to_string(path).unwrap();
+        let mut declarations = BTreeMap::new();
+
+        for line in source.lines() {
+            let Some(declaration) = line.trim().strip_prefix("external fun ") else {
+                continue;
+            };
+            let (method, rest) = declaration.split_once('(').unwrap();
+            let (params, ret) = rest.rsplit_once(')').unwrap();
+
+            let params = params
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| kotlin_descriptor(p.split_once(':').unwrap().1.trim()))
+                .collect::<String>();
+            let ret = match ret.trim().strip_prefix(':') {
+                Some(ret) => kotlin_descriptor(ret.trim()),
+                None => kotlin_descriptor("Unit"),
+            };
+
+            declarations.insert(method.to_string(), format!("({params}){ret}"));
+        }
+
+        declarations
+    }
+
+    #[test]
+    fn symbol_golden_test() {
+        // Arrange
+        let export = JniExport {
+            method: "rust_to_dms_batch",
+            signature: "([DB)[Ljava/lang/String;",
+        };
+
+        // Act
+        let symbol = export.symbol();
+
+        // Assert
+        assert_eq!(
+            "Java_com_svenschmidt_kitana_core_NativeAccess_00024Companion_rust_1to_1dms_1batch",
+            symbol
+        );
+    }
+
+    #[test]
+    fn mangle_test() {
+        // Arrange
+        let name = "a/b_c;[dé$";
+
+        // Act
+        let mangled = mangle(name);
+
+        // Assert
+        assert_eq!("a_b_1c_2_3d_000e9_00024", mangled);
+    }
+
+    #[test]
+    fn split_descriptor_test() {
+        // Arrange
+        let signature = "(IJ[DLjava/lang/String;[Ljava/lang/Object;)[B";
+
+        // Act
+        let (params, ret) = split_descriptor(signature);
+
+        // Assert
+        assert_eq!(
+            vec!["I", "J", "[D", "Ljava/lang/String;", "[Ljava/lang/Object;"],
+            params
+        );
+        assert_eq!("[B", ret);
+    }
+
+    #[test]
+    fn rust_exports_test() {
+        // Arrange
+        let exports = rust_exports();
+
+        // Act
+        let symbols = JNI_EXPORTS
+            .iter()
+            .map(JniExport::symbol)
+            .collect::<Vec<_>>();
+
+        // Assert
+
+        // SS: every registered method is exported, and every export is registered
+        assert_eq!(JNI_EXPORTS.len(), exports.len());
+        for (export, symbol) in JNI_EXPORTS.iter().zip(symbols) {
+            let (params, ret) = exports
+                .get(&symbol)
+                .unwrap_or_else(|| panic!("{symbol} is not exported"));
+
+            let (expected_params, expected_ret) = split_descriptor(export.signature);
+            let expected_params = expected_params
+                .iter()
+                .map(|p| jni_type(p))
+                .collect::<Vec<_>>();
+            assert_eq!(expected_params, *params, "{}", export.method);
+            assert_eq!(jni_type(&expected_ret), ret, "{}", export.method);
+        }
+    }
+
+    #[test]
+    fn kotlin_declarations_test() {
+        // Arrange
+        let declarations = kotlin_declarations();
+
+        // Act
+        let registered = JNI_EXPORTS
+            .iter()
+            .map(|export| (export.method.to_string(), export.signature.to_string()))
+            .collect::<BTreeMap<_, _>>();
+
+        // Assert
+        assert_eq!(registered, declarations);
+    }
+}
@@ -0,0 +1,322 @@
+//! Traits implemented by #[derive(JniStruct)], see crate jni_derive, and
+//! the marshalling of the field types used in the bridge's structs.
+
+/// JNI type signature of a field type, e.g. D for f64
+pub(crate) trait JniSignature {
+    const SIGNATURE: &'static str;
+}
+
+/// Kotlin field names and type signatures of a struct's marshalled fields
+// SS: only used by the tests against the Kotlin sources
+#[cfg_attr(not(test), allow(dead_code))]
+pub(crate) trait JniFields {
+    const FIELDS: &'static [(&'static str, &'static str)];
+}
+
+impl JniSignature for f64 {
+    const SIGNATURE: &'static str = "D";
+}
+
+impl JniSignature for bool {
+    const SIGNATURE: &'static str = "Z";
+}
+
+impl JniSignature for i16 {
+    const SIGNATURE: &'static str = "S";
+}
+
+/// Time zone offsets are Kotlin Shorts
+impl JniSignature for i8 {
+    const SIGNATURE: &'static str = "S";
+}
+
+impl JniSignature for &str {
+    const SIGNATURE: &'static str = "Ljava/lang/String;";
+}
+
+/// An empty Kotlin String means None
+impl JniSignature for Option<String> {
+    const SIGNATURE: &'static str = "Ljava/lang/String;";
+}
+
+/// Expose the JNI interface for android below
+///
+/// The app and the library can be of different versions, e.g. during a
+/// staged rollout, so a Kotlin class can lack a field the library knows, or
+/// have one it does not know. Fields the library does not know are ignored.
+/// Fields the Kotlin class lacks are set to their default when read, and
+/// skipped when written. Their names are logged.
+#[cfg(target_os = "android")]
+#[allow(non_snake_case)]
+pub(crate) mod android {
+    use self::jni::objects::{JObject, JString, JValue};
+    use self::jni::JNIEnv;
+    use jni;
+    use log::warn;
+
+    use super::JniSignature;
+
+    /// Read a struct from a Kotlin object
+    pub(crate) trait JniRead: Sized {
+        /// Read the fields. Fields the Kotlin object lacks are set to their
+        /// default and their names added to missing
+        fn read_fields(env: JNIEnv, object: JObject, missing: &mut Vec<String>) -> Self;
+
+        /// Read the fields, and log the ones the Kotlin object lacks
+        fn read_from(env: JNIEnv, object: JObject) -> Self {
+            let mut missing = vec![];
+            let value = Self::read_fields(env, object, &mut missing);
+            log_missing::<Self>(&missing);
+            value
+        }
+    }
+
+    /// Write a struct into a Kotlin object
+    pub(crate) trait JniWrite {
+        /// Write the fields. The names of the fields the Kotlin object lacks
+        /// are added to missing
+        fn write_fields(&self, env: JNIEnv, object: JObject, missing: &mut Vec<String>);
+
+        /// Write the fields, and log the ones the Kotlin object lacks
+        fn write_to(&self, env: JNIEnv, object: JObject) {
+            let mut missing = vec![];
+            self.write_fields(env, object, &mut missing);
+            log_missing::<Self>(&missing);
+        }
+    }
+
+    /// Read a field of a Kotlin object
+    pub(crate) trait JniGet: JniSignature + Sized {
+        /// None if the object has no such field, or not of this type
+        fn get(env: JNIEnv, object: JObject, name: &str) -> Option<Self>;
+    }
+
+    /// Write a field of a Kotlin object
+    pub(crate) trait JniSet: JniSignature {
+        /// false if the object has no such field, or not of this type
+        fn set(&self, env: JNIEnv, object: JObject, name: &str) -> bool;
+    }
+
+    /// Log the fields a Kotlin object lacks
+    pub(crate) fn log_missing<T: ?Sized>(missing: &[String]) {
+        if !missing.is_empty() {
+            warn!(
+                "Kotlin object for {} lacks the fields {missing:?}, app and library versions differ?",
+                std::any::type_name::<T>()
+            );
+        }
+    }
+
+    /// Clear the exception a failed JNI call raised, e.g. NoSuchFieldError.
+    /// JNI calls must not be made while an exception is pending
+    pub(crate) fn clear_exception(env: JNIEnv) {
+        if env.exception_check().unwrap_or(false) {
+            let _ = env.exception_clear();
+        }
+    }
+
+    pub(crate) fn get_field<'a>(
+        env: JNIEnv<'a>,
+        object: JObject<'a>,
+        name: &str,
+        signature: &str,
+    ) -> Option<JValue<'a>> {
+        let value = env.get_field(object, name, signature).ok();
+        if value.is_none() {
+            clear_exception(env);
+        }
+        value
+    }
+
+    fn set_field(env: JNIEnv, object: JObject, name: &str, signature: &str, value: JValue) -> bool {
+        let is_set = env.set_field(object, name, signature, value).is_ok();
+        if !is_set {
+            clear_exception(env);
+        }
+        is_set
+    }
+
+    impl JniGet for f64 {
+        fn get(env: JNIEnv, object: JObject, name: &str) -> Option<Self> {
+            get_field(env, object, name, Self::SIGNATURE)?.d().ok()
+        }
+    }
+
+    impl JniSet for f64 {
+        fn set(&self, env: JNIEnv, object: JObject, name: &str) -> bool {
+            set_field(env, object, name, Self::SIGNATURE, JValue::Double(*self))
+        }
+    }
+
+    impl JniGet for bool {
+        fn get(env: JNIEnv, object: JObject, name: &str) -> Option<Self> {
+            get_field(env, object, name, Self::SIGNATURE)?.z().ok()
+        }
+    }
+
+    impl JniSet for bool {
+        fn set(&self, env: JNIEnv, object: JObject, name: &str) -> bool {
+            set_field(
+                env,
+                object,
+                name,
+                Self::SIGNATURE,
+                JValue::Bool(*self as u8),
+            )
+        }
+    }
+
+    impl JniGet for i16 {
+        fn get(env: JNIEnv, object: JObject, name: &str) -> Option<Self> {
+            get_field(env, object, name, Self::SIGNATURE)?.s().ok()
+        }
+    }
+
+    impl JniSet for i16 {
+        fn set(&self, env: JNIEnv, object: JObject, name: &str) -> bool {
+            set_field(env, object, name, Self::SIGNATURE, JValue::Short(*self))
+        }
+    }
+
+    impl JniGet for i8 {
+        fn get(env: JNIEnv, object: JObject, name: &str) -> Option<Self> {
+            let value = get_field(env, object, name, Self::SIGNATURE)?.s().ok()?;
+            Some(value as i8)
+        }
+    }
+
+    impl JniSet for &str {
+        fn set(&self, env: JNIEnv, object: JObject, name: &str) -> bool {
+            let string: JString = match env.new_string(self) {
+                Ok(string) => string,
+                Err(_) => {
+                    clear_exception(env);
+                    return false;
+                }
+            };
+            set_field(
+                env,
+                object,
+                name,
+                Self::SIGNATURE,
+                JValue::Object(string.into()),
+            )
+        }
+    }
+
+    impl JniGet for Option<String> {
+        fn get(env: JNIEnv, object: JObject, name: &str) -> Option<Self> {
+            let string: JString = get_field(env, object, name, Self::SIGNATURE)?
+                .l()
+                .ok()?
+                .into();
+
+            // SS: a null String is None, like an empty one
+            if string.is_null() {
+                return Some(None);
+            }
+            match env.get_string(string) {
+                Ok(string) => {
+                    let string: String = string.into();
+                    Some((!string.is_empty()).then_some(string))
+                }
+                Err(_) => {
+                    clear_exception(env);
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::bridge_core::{DateTime, MoonInput, MoonOutput, SolunarOutput};
+    use crate::moon::jni_bridge::exports::{kotlin_descriptor, KOTLIN_SOURCE};
+
+    /// Fields of a data class in NativeAccess.kt, by name: JNI type descriptor
+    fn kotlin_fields(class: &str) -> BTreeMap<String, String> {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(KOTLIN_SOURCE);
+        let source = std::fs::read_to_string(path).unwrap();
+
+        let header = format!("data class {class}(");
+        let start = source.find(&header).unwrap() + header.len();
+
+        source[start..]
+            .lines()
+            .map(str::trim)
+            .take_while(|line| !line.starts_with(')'))
+            .filter_map(|line| line.strip_prefix("var "))
+            .map(|field| {
+                let (name, rest) = field.split_once(':').unwrap();
+                let kotlin_type = rest.split(['=', ',']).next().unwrap().trim();
+                (name.to_string(), kotlin_descriptor(kotlin_type))
+            })
+            .collect()
+    }
+
+    fn rust_fields<T: JniFields>() -> BTreeMap<String, String> {
+        T::FIELDS
+            .iter()
+            .map(|(name, signature)| (name.to_string(), signature.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn moon_input_test() {
+        // Arrange
+        let kotlin = kotlin_fields("MoonInputData");
+
+        // Act
+        let rust = rust_fields::<MoonInput>();
+
+        // Assert
+        assert_eq!(kotlin, rust);
+        assert_eq!(
+            "Lcom/svenschmidt/kitana/core/NativeAccess$MoonInputData;",
+            MoonInput::SIGNATURE
+        );
+    }
+
+    #[test]
+    fn date_time_test() {
+        // Arrange
+        let kotlin = kotlin_fields("DateTime");
+
+        // Act
+        let rust = rust_fields::<DateTime>();
+
+        // Assert
+        assert_eq!(kotlin, rust);
+    }
+
+    #[test]
+    fn moon_output_test() {
+        // Arrange
+        let kotlin = kotlin_fields("MoonOutputData");
+
+        // Act
+        let rust = rust_fields::<MoonOutput>();
+
+        // Assert
+        assert_eq!(kotlin, rust);
+    }
+
+    #[test]
+    fn solunar_output_test() {
+        // Arrange
+        let mut kotlin = kotlin_fields("SolunarOutputData");
+
+        // Act
+        let rust = rust_fields::<SolunarOutput>();
+
+        // Assert
+
+        // SS: the periods are written by solunar::android::solunar
+        kotlin.retain(|name, _| !name.contains("Period"));
+        assert_eq!(kotlin, rust);
+    }
+}
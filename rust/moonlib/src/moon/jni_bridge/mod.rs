@@ -1 +1,4 @@
-pub(crate) mod rise_set_transit;
+#[cfg(test)]
+mod exports;
+pub(crate) mod jni_struct;
+pub(crate) mod solunar;
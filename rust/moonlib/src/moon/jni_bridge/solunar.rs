@@ -0,0 +1,42 @@
+/// Expose the JNI interface for android below
+#[cfg(target_os = "android")]
+#[allow(non_snake_case)]
+pub(crate) mod android {
+    use self::jni::objects::JObject;
+    use self::jni::JNIEnv;
+    use jni;
+    use log::debug;
+
+    use crate::bridge_core::SolunarOutput;
+    use crate::moon::jni_bridge::jni_struct::android::{log_missing, JniSet, JniWrite};
+
+    /// Write the solunar periods into NativeAccess.SolunarOutputData
+    pub(crate) fn solunar(env: JNIEnv, solunar_output_data: JObject, output: &SolunarOutput) {
+        // SS: the periods are arrays in Rust, but numbered fields in Kotlin
+        let major_fields = [
+            ("majorPeriod1Start", "majorPeriod1End"),
+            ("majorPeriod2Start", "majorPeriod2End"),
+        ];
+        let minor_fields = [
+            ("minorPeriod1Start", "minorPeriod1End"),
+            ("minorPeriod2Start", "minorPeriod2End"),
+        ];
+        let periods = major_fields
+            .iter()
+            .zip(output.major_periods.iter())
+            .chain(minor_fields.iter().zip(output.minor_periods.iter()));
+        let mut missing = vec![];
+        for ((start_field, end_field), (start, end)) in periods {
+            for (field, time) in [(start_field, start), (end_field, end)] {
+                if !time.set(env, solunar_output_data, field) {
+                    missing.push(field.to_string());
+                }
+            }
+        }
+        log_missing::<SolunarOutput>(&missing);
+
+        debug!("Solunar data:\n{output}");
+
+        output.write_to(env, solunar_output_data);
+    }
+}
@@ -0,0 +1,390 @@
+//! Libration of the moon, i.e. the apparent oscillation that lets an
+//! observer see about 59% of its surface over time, and the position angle
+//! of its axis of rotation. Meeus, chapter 53
+use crate::date::jd::JD;
+use crate::moon::position::{argument_of_latitude, mean_anomaly, mean_elongation, GeocentricMoon};
+use crate::nutation;
+use crate::session::Observer;
+use crate::sun::sun;
+use crate::util::{degrees::Degrees, math, radians::Radians};
+use crate::{coordinates, earth, ecliptic, moon};
+
+/// Inclination of the mean lunar equator to the ecliptic, IAU, page 372
+const INCLINATION: f64 = 1.54242;
+
+/// Libration and orientation of the moon's disk
+#[derive(Debug, Copy, Clone)]
+pub struct Libration {
+    /// Selenographic longitude of the center of the disk, in degrees
+    /// [-180, 180). Positive if the western limb, as seen from Earth, i.e.
+    /// Mare Crisium, is turned towards the observer
+    pub longitude: Degrees,
+
+    /// Selenographic latitude of the center of the disk, in degrees
+    /// [-90, 90]. Positive if the northern limb is turned towards the
+    /// observer
+    pub latitude: Degrees,
+
+    /// Position angle of the moon's axis of rotation, i.e. of its northern
+    /// end, measured from the North Point of the disk towards the East, in
+    /// degrees [-180, 180)
+    pub position_angle: Degrees,
+}
+
+/// Calculate the geocentric libration, optical and physical, and the
+/// position angle of the axis, Meeus, chapter 53
+/// In: Julian day, in dynamical time
+/// Out: Libration
+pub fn libration(jd: JD) -> Libration {
+    geocentric_libration(jd, &moon::geocentric(jd))
+}
+
+/// Calculate the libration seen by an observer. The moon's parallax of
+/// about 1 degree shifts the center of the disk by up to the same amount,
+/// Meeus, chapter 53, page 375
+/// In:
+/// jd: Julian day, in dynamical time
+/// observer: Observer's location
+/// Out: Libration
+pub fn topocentric_libration(jd: JD, observer: &Observer) -> Libration {
+    let moon = moon::geocentric(jd);
+    let libration = geocentric_libration(jd, &moon);
+
+    let siderial_time =
+        earth::local_siderial_time(earth::apparent_siderial_time(jd), observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time, moon.ra);
+    topocentric_correction(libration, &moon, hour_angle, observer.latitude)
+}
+
+/// Orientation of the moon's axis of rotation as seen by an observer, to
+/// turn a labeled map of the moon like the moon in the sky
+#[derive(Debug, Copy, Clone)]
+pub struct AxisOrientation {
+    /// Position angle of the northern end of the axis, measured from the
+    /// North Point of the disk, i.e. the direction to the celestial pole,
+    /// towards the East, in degrees [-180, 180), see Libration
+    pub position_angle: Degrees,
+
+    /// Parallactic angle, i.e. the angle from the North Point to the
+    /// direction to the zenith, in degrees [-180, 180)
+    pub parallactic_angle: Degrees,
+
+    /// Direction of the northern end of the axis, measured counterclockwise
+    /// from the direction to the zenith, in degrees [0, 360). 0 if the
+    /// moon's north pole points straight up, 90 if it points to the left
+    pub tilt: Degrees,
+}
+
+/// Calculate the orientation of the moon's axis seen by an observer. The
+/// position angle is topocentric, see topocentric_libration, the
+/// parallactic angle is for the moon's topocentric position
+/// In:
+/// jd: Julian day, in dynamical time
+/// observer: Observer's location
+/// Out: Orientation of the axis
+pub fn axis_orientation(jd: JD, observer: &Observer) -> AxisOrientation {
+    let moon = moon::geocentric(jd);
+    let siderial_time =
+        earth::local_siderial_time(earth::apparent_siderial_time(jd), observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time, moon.ra);
+    let libration = topocentric_correction(
+        geocentric_libration(jd, &moon),
+        &moon,
+        hour_angle,
+        observer.latitude,
+    );
+
+    let (ra, decl) = coordinates::equatorial_2_topocentric(
+        moon.ra,
+        moon.dec,
+        observer.longitude,
+        observer.latitude,
+        0.0,
+        moon.delta,
+        jd,
+    );
+    let parallactic_angle = coordinates::parallactic_angle(
+        earth::hour_angle(siderial_time, ra),
+        decl,
+        observer.latitude,
+    );
+
+    AxisOrientation {
+        position_angle: libration.position_angle,
+        parallactic_angle,
+        tilt: (libration.position_angle - parallactic_angle).map_to_0_to_360(),
+    }
+}
+
+/// Geocentric libration for the moon's geocentric position passed in
+/// In:
+/// jd: Julian day, in dynamical time
+/// moon: Moon's apparent geocentric position
+/// Out: Libration
+pub(crate) fn geocentric_libration(jd: JD, moon: &GeocentricMoon) -> Libration {
+    let i = Radians::from(Degrees::new(INCLINATION)).0;
+    let delta_psi = Degrees::from(nutation::nutation_in_longitude(jd));
+    let omega = moon::mean_node(jd);
+    let (longitude, latitude) = selenographic_position(jd, moon.lambda, moon.beta);
+    let (rho, sigma, _) = physical_libration(jd);
+
+    // SS: position angle of the axis, page 374
+    let rho_rad = Radians::from(Degrees::new(rho)).0;
+    let v = Radians::from(omega + delta_psi + Degrees::new(sigma / math::sin(i))).0;
+    let eps = Radians::from(ecliptic::true_obliquity(jd)).0;
+    let x = math::sin(i + rho_rad) * math::sin(v);
+    let y = math::sin(i + rho_rad) * math::cos(v) * math::cos(eps)
+        - math::cos(i + rho_rad) * math::sin(eps);
+    let omega_axis = math::atan2(x, y);
+    let ra = Radians::from(moon.ra).0;
+    let position_angle = math::asin(
+        (x * x + y * y).sqrt() * math::cos(ra - omega_axis) / math::cos(Radians::from(latitude).0),
+    );
+
+    Libration {
+        longitude,
+        latitude,
+        position_angle: Degrees::from(Radians::new(position_angle)),
+    }
+}
+
+/// Selenographic position of the point on the moon's surface a body is in
+/// the zenith of, including the physical libration, Meeus, chapter 53. For
+/// the Earth, this is the libration
+/// In:
+/// jd: Julian day, in dynamical time
+/// lambda: Apparent ecliptic longitude of the moon as seen from the body, in
+/// degrees [0, 360)
+/// beta: Ecliptic latitude of the moon as seen from the body, in degrees
+/// [-90, 90]
+/// Out:
+/// Selenographic longitude, in degrees [-180, 180), and latitude, in
+/// degrees [-90, 90]
+pub(crate) fn selenographic_position(jd: JD, lambda: Degrees, beta: Degrees) -> (Degrees, Degrees) {
+    let i = Radians::from(Degrees::new(INCLINATION)).0;
+    let delta_psi = Degrees::from(nutation::nutation_in_longitude(jd));
+    let omega = moon::mean_node(jd);
+    let f = argument_of_latitude(jd);
+
+    // SS: optical libration, eq (53.1)
+    let w = Radians::from(lambda - delta_psi - omega).0;
+    let beta = Radians::from(beta).0;
+    let a = math::atan2(
+        math::sin(w) * math::cos(beta) * math::cos(i) - math::sin(beta) * math::sin(i),
+        math::cos(w) * math::cos(beta),
+    );
+    let l_optical = Degrees::from(Radians::new(a)) - f;
+    let b_optical =
+        math::asin(-math::sin(w) * math::cos(beta) * math::sin(i) - math::sin(beta) * math::cos(i));
+
+    let (rho, sigma, tau) = physical_libration(jd);
+    let l_physical = -tau + (rho * math::cos(a) + sigma * math::sin(a)) * math::tan(b_optical);
+    let b_physical = sigma * math::cos(a) - rho * math::sin(a);
+
+    // SS: A - F can be beyond -360 degrees
+    let longitude = (l_optical + Degrees::new(l_physical))
+        .map_to_0_to_360()
+        .map_neg180_to_180();
+    let latitude = Degrees::from(Radians::new(b_optical)) + Degrees::new(b_physical);
+    (longitude, latitude)
+}
+
+/// Quantities of the physical libration, Meeus, chapter 53, page 373
+/// In: Julian day, in dynamical time
+/// Out: rho, sigma and tau, in degrees
+fn physical_libration(jd: JD) -> (f64, f64, f64) {
+    let t = jd.centuries_from_epoch_j2000();
+    let k1 = Radians::from(Degrees::new(119.75 + 131.849 * t)).0;
+    let k2 = Radians::from(Degrees::new(72.56 + 20.186 * t)).0;
+    let m = Radians::from(sun::mean_anomaly(jd)).0;
+    let m_prime = Radians::from(mean_anomaly(jd)).0;
+    let d = Radians::from(mean_elongation(jd)).0;
+    let f = Radians::from(argument_of_latitude(jd)).0;
+    let omega_rad = Radians::from(moon::mean_node(jd)).0;
+    let e = earth::eccentricity(jd);
+
+    let rho = -0.02752 * math::cos(m_prime) - 0.02245 * math::sin(f)
+        + 0.00684 * math::cos(m_prime - 2.0 * f)
+        - 0.00293 * math::cos(2.0 * f)
+        - 0.00085 * math::cos(2.0 * f - 2.0 * d)
+        - 0.00054 * math::cos(m_prime - 2.0 * d)
+        - 0.00020 * math::sin(m_prime + f)
+        - 0.00020 * math::cos(m_prime + 2.0 * f)
+        - 0.00020 * math::cos(m_prime - f)
+        + 0.00014 * math::cos(m_prime + 2.0 * f - 2.0 * d);
+
+    let sigma = -0.02816 * math::sin(m_prime) + 0.02244 * math::cos(f)
+        - 0.00682 * math::sin(m_prime - 2.0 * f)
+        - 0.00279 * math::sin(2.0 * f)
+        - 0.00083 * math::sin(2.0 * f - 2.0 * d)
+        + 0.00069 * math::sin(m_prime - 2.0 * d)
+        + 0.00040 * math::cos(m_prime + f)
+        - 0.00025 * math::sin(2.0 * m_prime)
+        - 0.00023 * math::sin(m_prime + 2.0 * f)
+        + 0.00020 * math::cos(m_prime - f)
+        + 0.00019 * math::sin(m_prime - f)
+        + 0.00013 * math::sin(m_prime + 2.0 * f - 2.0 * d)
+        - 0.00010 * math::cos(m_prime - 3.0 * f);
+
+    let tau = 0.02520 * e * math::sin(m) + 0.00473 * math::sin(2.0 * m_prime - 2.0 * f)
+        - 0.00467 * math::sin(m_prime)
+        + 0.00396 * math::sin(k1)
+        + 0.00276 * math::sin(2.0 * m_prime - 2.0 * d)
+        + 0.00196 * math::sin(omega_rad)
+        - 0.00183 * math::cos(m_prime - f)
+        + 0.00115 * math::sin(m_prime - 2.0 * d)
+        - 0.00096 * math::sin(m_prime - d)
+        + 0.00046 * math::sin(2.0 * f - 2.0 * d)
+        - 0.00039 * math::sin(m_prime - f)
+        - 0.00032 * math::sin(m_prime - m - d)
+        + 0.00027 * math::sin(2.0 * m_prime - m - 2.0 * d)
+        + 0.00023 * math::sin(k2)
+        - 0.00014 * math::sin(2.0 * d)
+        + 0.00014 * math::cos(2.0 * m_prime - 2.0 * f)
+        - 0.00012 * math::sin(m_prime - 2.0 * f)
+        - 0.00012 * math::sin(2.0 * m_prime)
+        + 0.00011 * math::sin(2.0 * m_prime - 2.0 * m - 2.0 * d);
+
+    (rho, sigma, tau)
+}
+
+/// Correct the geocentric libration for the observer's position on Earth,
+/// Meeus, chapter 53, page 375
+/// In:
+/// libration: geocentric libration
+/// moon: Moon's apparent geocentric position
+/// hour_angle: Moon's geocentric local hour angle, in degrees
+/// latitude_observer: in degrees [-90, 90)
+/// Out: Topocentric libration
+pub(crate) fn topocentric_correction(
+    libration: Libration,
+    moon: &GeocentricMoon,
+    hour_angle: Degrees,
+    latitude_observer: Degrees,
+) -> Libration {
+    let h = Radians::from(hour_angle).0;
+    let decl = Radians::from(moon.dec).0;
+    let phi = Radians::from(latitude_observer).0;
+
+    let q = math::atan2(
+        math::cos(phi) * math::sin(h),
+        math::cos(decl) * math::sin(phi) - math::sin(decl) * math::cos(phi) * math::cos(h),
+    );
+    let z = math::acos(
+        math::sin(decl) * math::sin(phi) + math::cos(decl) * math::cos(phi) * math::cos(h),
+    );
+
+    // SS: horizontal parallax, for the moon's distance
+    let parallax = moon::parallax::horizontal_parallax_from_distance(moon.delta);
+    let pi_prime = Radians::from(parallax).0 * (math::sin(z) + 0.0084 * math::sin(2.0 * z));
+
+    let p = Radians::from(libration.position_angle).0;
+    let b = Radians::from(libration.latitude).0;
+
+    let delta_l = -pi_prime * math::sin(q - p) / math::cos(b);
+    let delta_b = pi_prime * math::cos(q - p);
+    let delta_p = delta_l * math::sin(b + delta_b) - pi_prime * math::sin(q) * math::tan(decl);
+
+    Libration {
+        longitude: (libration.longitude + Degrees::from(Radians::new(delta_l))).map_neg180_to_180(),
+        latitude: libration.latitude + Degrees::from(Radians::new(delta_b)),
+        position_angle: libration.position_angle + Degrees::from(Radians::new(delta_p)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn libration_test() {
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let libration = libration(jd);
+
+        // Assert
+
+        // SS: Meeus, example 53.a
+        assert_approx_eq!(-1.23, libration.longitude.0, 0.005);
+        assert_approx_eq!(4.20, libration.latitude.0, 0.005);
+        assert_approx_eq!(15.08, libration.position_angle.0, 0.005);
+    }
+
+    #[test]
+    fn topocentric_libration_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+        let start = JD::from_date(Date::new(2024, 3, 1.0));
+
+        for hours in (0..48).step_by(3) {
+            let jd = JD::new(start.jd + hours as f64 / 24.0);
+
+            // Act
+            let topocentric = topocentric_libration(jd, &observer);
+
+            // Assert
+
+            // SS: the shift is at most the moon's horizontal parallax
+            let geocentric = libration(jd);
+            let parallax = Degrees::from(moon::parallax::horizontal_equatorial_parallax(jd)).0;
+            let shift = ((topocentric.longitude - geocentric.longitude).0.powi(2)
+                + (topocentric.latitude - geocentric.latitude).0.powi(2))
+            .sqrt();
+            assert!(shift <= parallax * 1.01, "{shift} {parallax}");
+            assert!(shift > 0.0);
+        }
+    }
+
+    #[test]
+    fn axis_orientation_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+        let start = JD::from_date(Date::new(2024, 3, 1.0));
+
+        for hours in (0..48).step_by(3) {
+            let jd = JD::new(start.jd + hours as f64 / 24.0);
+
+            // Act
+            let orientation = axis_orientation(jd, &observer);
+
+            // Assert
+            let libration = topocentric_libration(jd, &observer);
+            assert_eq!(libration.position_angle.0, orientation.position_angle.0);
+            assert_approx_eq!(
+                (orientation.position_angle - orientation.parallactic_angle)
+                    .map_to_0_to_360()
+                    .0,
+                orientation.tilt.0,
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn axis_orientation_transit_test() {
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD, the moon on the meridian of an observer
+        // at latitude 48. There, the North Point is straight up
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let moon = moon::geocentric(jd);
+        let siderial_time = earth::apparent_siderial_time(jd);
+        let longitude = (siderial_time - moon.ra.degrees()).map_neg180_to_180();
+        let observer = Observer::new(longitude.0, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let orientation = axis_orientation(jd, &observer);
+
+        // Assert
+        assert_approx_eq!(0.0, orientation.parallactic_angle.0, 0.01);
+
+        // SS: Meeus, example 53.a, up to the moon's parallax
+        assert_approx_eq!(15.08, orientation.tilt.0, 1.0);
+    }
+}
@@ -1,6 +1,44 @@
+pub mod apsides;
+pub mod bearing;
+pub mod calendar;
+pub mod crossings;
+pub mod field_rotation;
+pub mod illuminance;
 pub(crate) mod jni_bridge;
+pub mod libration;
+pub mod names;
+pub mod orbital_elements;
 pub mod parallax;
 pub mod phase;
 pub mod position;
+pub mod render;
 pub mod rise_set_transit;
+pub mod selenography;
 pub mod semidiameter;
+pub mod tracker;
+pub mod velocity;
+pub mod visibility;
+
+pub use apsides::{distance, next_apsis, previous_apsis, Apsis, ApsisKind, MoonDistance};
+pub use bearing::bearing_table;
+pub use calendar::{month_phases, phase_events, MonthPhases, PhaseEvent, PhaseKind};
+pub use crossings::{
+    altitude_crossings, azimuth_crossings, rise_set_along, rise_set_with_horizon, Crossing,
+};
+pub use field_rotation::field_rotation_rate;
+pub use illuminance::illuminance;
+pub use libration::{axis_orientation, AxisOrientation};
+pub use names::{full_moon_name, FullMoonName};
+pub use orbital_elements::{mean_node, mean_perigee, orbital_elements};
+pub use phase::{
+    is_waxing, phase_at_local_midnight, phase_at_noon_utc, phase_info, PhaseAt, PhaseInfo,
+};
+pub use position::{
+    full_topocentric, full_topocentric_along, geocentric, topocentric_ecliptic, FullMoonState,
+    GeocentricMoon,
+};
+pub use render::{render_params, RenderParams};
+pub use selenography::{illumination, Illumination};
+pub use tracker::{TrackedPosition, Tracker};
+pub use velocity::{longitudinal_velocity, MoonVelocity};
+pub use visibility::{day_visibility, next_visible, next_visible_with_horizon, DayVisibility};
@@ -0,0 +1,226 @@
+//! Traditional names of the full moons, as used in North American almanacs.
+//!
+//! Most full moons are named after the calendar month they fall in. The
+//! Harvest Moon is the full moon closest to the September equinox, so it
+//! falls in September or October, and replaces the month's name. The full
+//! moon after it is the Hunter's Moon.
+//!
+//! The names are enum values, so the app can translate them.
+
+use crate::date::jd::JD;
+use crate::moon::calendar::{self, PhaseKind};
+use crate::time::{self, LeapSeconds};
+use crate::util::degrees::Degrees;
+
+/// Longest synodic month, in days, Meeus, chapter 49
+const MAX_SYNODIC_MONTH: f64 = 29.9;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FullMoonName {
+    Wolf,
+    Snow,
+    Worm,
+    Pink,
+    Flower,
+    Strawberry,
+    Buck,
+    Sturgeon,
+    Corn,
+    Harvest,
+    Hunters,
+    Beaver,
+    Cold,
+}
+
+impl FullMoonName {
+    /// Name of the full moons in a calendar month
+    /// In: month, 1 to 12
+    fn for_month(month: u8) -> Self {
+        match month {
+            1 => FullMoonName::Wolf,
+            2 => FullMoonName::Snow,
+            3 => FullMoonName::Worm,
+            4 => FullMoonName::Pink,
+            5 => FullMoonName::Flower,
+            6 => FullMoonName::Strawberry,
+            7 => FullMoonName::Buck,
+            8 => FullMoonName::Sturgeon,
+            9 => FullMoonName::Corn,
+            10 => FullMoonName::Hunters,
+            11 => FullMoonName::Beaver,
+            _ => FullMoonName::Cold,
+        }
+    }
+}
+
+/// Name a full moon
+/// In:
+/// jd: Julian day of the full moon, in dynamical time
+/// timezone_offset: Observer's time zone offset, in hours, for the calendar
+/// month the full moon falls in
+/// Out: Traditional name
+pub fn full_moon_name(jd: JD, timezone_offset: i8) -> FullMoonName {
+    let mut local = time::tt_2_utc(jd, &LeapSeconds::iers());
+    local.add_hours(timezone_offset as f64);
+    let month = local.to_calendar_date().month;
+
+    // SS: the Harvest Moon falls between September 8 and October 7, the
+    // Hunter's Moon between October 8 and November 6
+    if !(9..=11).contains(&month) {
+        return FullMoonName::for_month(month);
+    }
+
+    // SS: two months before, so the full moon before the Harvest Moon is in
+    // the window for the Hunter's Moon
+    let full_moons: Vec<JD> = calendar::search(
+        JD::new(jd.jd - 2.0 * MAX_SYNODIC_MONTH),
+        JD::new(jd.jd + MAX_SYNODIC_MONTH),
+    )
+    .into_iter()
+    .filter(|event| event.kind == PhaseKind::FullMoon)
+    .map(|event| event.jd)
+    .collect();
+    let Some(idx) = full_moons
+        .iter()
+        .position(|full_moon| (full_moon.jd - jd.jd).abs() < 1.0)
+    else {
+        return FullMoonName::for_month(month);
+    };
+
+    let equinox = september_equinox(jd);
+    let is_harvest = |idx: usize| {
+        let distance = |idx: usize| (full_moons[idx].jd - equinox.jd).abs();
+        let closer_than_previous = idx == 0 || distance(idx) < distance(idx - 1);
+        let closer_than_next = idx + 1 == full_moons.len() || distance(idx) < distance(idx + 1);
+        closer_than_previous && closer_than_next
+    };
+
+    if is_harvest(idx) {
+        FullMoonName::Harvest
+    } else if idx > 0 && is_harvest(idx - 1) {
+        FullMoonName::Hunters
+    } else {
+        FullMoonName::for_month(month)
+    }
+}
+
+/// Time of the September equinox before or after a time in September to
+/// November
+/// In: Julian day, in dynamical time
+/// Out: Julian day of the equinox, in dynamical time
+fn september_equinox(jd: JD) -> JD {
+    // SS: from August 23 to December 6, the Sun's longitude is between 150
+    // and 255 degrees
+    calendar::solar_longitude_crossing(
+        Degrees::new(180.0),
+        JD::new(jd.jd - 75.0),
+        JD::new(jd.jd + 30.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::calendar::phase_events;
+
+    /// Full moon in a month, in dynamical time
+    fn full_moon(year: i16, month: u8) -> JD {
+        let start = JD::from_date(Date::new(year, month, 1.0));
+        let end = JD::new(start.jd + 30.0);
+        phase_events(start, end)
+            .into_iter()
+            .find(|event| event.kind == PhaseKind::FullMoon)
+            .unwrap()
+            .jd
+    }
+
+    #[test]
+    fn month_names_test() {
+        // Act
+        let names =
+            [1, 2, 3, 4, 5, 6, 7, 8, 11, 12].map(|month| full_moon_name(full_moon(2024, month), 0));
+
+        // Assert
+        assert_eq!(
+            [
+                FullMoonName::Wolf,
+                FullMoonName::Snow,
+                FullMoonName::Worm,
+                FullMoonName::Pink,
+                FullMoonName::Flower,
+                FullMoonName::Strawberry,
+                FullMoonName::Buck,
+                FullMoonName::Sturgeon,
+                FullMoonName::Beaver,
+                FullMoonName::Cold,
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn harvest_moon_september_test() {
+        // Act
+
+        // SS: 2024, equinox on September 22, full moons on September 18 and
+        // October 17
+        let september = full_moon_name(full_moon(2024, 9), 0);
+        let october = full_moon_name(full_moon(2024, 10), 0);
+
+        // Assert
+        assert_eq!(FullMoonName::Harvest, september);
+        assert_eq!(FullMoonName::Hunters, october);
+    }
+
+    #[test]
+    fn harvest_moon_october_test() {
+        // Act
+
+        // SS: 2020, equinox on September 22, full moons on September 2,
+        // October 1, October 31 and November 30
+        let september = full_moon_name(full_moon(2020, 9), 0);
+        let october = full_moon_name(full_moon(2020, 10), 0);
+        let november = full_moon_name(full_moon(2020, 11), 0);
+
+        // Assert
+        assert_eq!(FullMoonName::Corn, september);
+        assert_eq!(FullMoonName::Harvest, october);
+        assert_eq!(FullMoonName::Beaver, november);
+    }
+
+    #[test]
+    fn hunters_moon_november_test() {
+        // Act
+
+        // SS: 2017, full moons on October 5, the Harvest Moon, and November 4
+        let october = full_moon_name(full_moon(2017, 10), 0);
+        let november = full_moon_name(full_moon(2017, 11), 0);
+
+        // Assert
+        assert_eq!(FullMoonName::Harvest, october);
+        assert_eq!(FullMoonName::Hunters, november);
+    }
+
+    #[test]
+    fn time_zone_test() {
+        // Arrange
+
+        // SS: full moon on 2018 January 31, 13h 27m UTC, which is already
+        // February 1 in Sydney
+        let start = JD::from_date(Date::new(2018, 1, 29.0));
+        let full_moon = phase_events(start, JD::new(start.jd + 5.0))
+            .into_iter()
+            .find(|event| event.kind == PhaseKind::FullMoon)
+            .unwrap()
+            .jd;
+
+        // Act
+        let utc = full_moon_name(full_moon, 0);
+        let sydney = full_moon_name(full_moon, 11);
+
+        // Assert
+        assert_eq!(FullMoonName::Wolf, utc);
+        assert_eq!(FullMoonName::Snow, sydney);
+    }
+}
@@ -0,0 +1,131 @@
+//! Mean orbital elements of the Moon, referred to the mean equinox of the date.
+//! see J. Meeus, Astronomical Algorithms, chapters 47 and 53
+
+use crate::date::jd::JD;
+use crate::util::degrees::Degrees;
+
+/// Mean inclination of the Moon's orbit w.r.t. the ecliptic, in degrees.
+/// Meeus, chapter 53, page 372
+const MEAN_INCLINATION: f64 = 5.145_396;
+
+/// Mean eccentricity of the Moon's orbit
+const MEAN_ECCENTRICITY: f64 = 0.054_900;
+
+/// Semi-major axis of the Moon's orbit, in km
+const SEMI_MAJOR_AXIS: f64 = 384_400.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct OrbitalElements {
+    /// Longitude of the mean ascending node, in degrees [0, 360)
+    pub mean_ascending_node: Degrees,
+
+    /// Inclination of the orbit w.r.t. the ecliptic, in degrees
+    pub inclination: Degrees,
+
+    /// Longitude of the mean perigee, in degrees [0, 360)
+    pub mean_perigee: Degrees,
+
+    /// Argument of the perigee, measured from the ascending node, in degrees [0, 360)
+    pub argument_of_perigee: Degrees,
+
+    /// Eccentricity of the orbit
+    pub eccentricity: f64,
+
+    /// Semi-major axis, in km
+    pub semi_major_axis: f64,
+}
+
+/// Calculate the longitude of the Moon's mean ascending node, eq (47.7).
+/// This is also the argument Omega of the nutation series, chapter 22.
+/// In: Julian day in dynamical time
+/// Out: Longitude of the mean ascending node, in degrees [0, 360)
+pub fn mean_node(jd: JD) -> Degrees {
+    let t = jd.centuries_from_epoch_j2000();
+
+    let t2 = t * t;
+    let t3 = t * t2;
+    let t4 = t * t3;
+
+    let omega =
+        125.0445479 - 1934.1362891 * t + 0.0020754 * t2 + t3 / 467_441.0 - t4 / 60_616_000.0;
+
+    Degrees::new(omega).map_to_0_to_360()
+}
+
+/// Calculate the longitude of the Moon's mean perigee, page 343.
+/// In: Julian day in dynamical time
+/// Out: Longitude of the mean perigee, in degrees [0, 360)
+pub fn mean_perigee(jd: JD) -> Degrees {
+    let t = jd.centuries_from_epoch_j2000();
+
+    let t2 = t * t;
+    let t3 = t * t2;
+    let t4 = t * t3;
+
+    let pi = 83.3532465 + 4069.0137287 * t - 0.0103200 * t2 - t3 / 80_053.0 + t4 / 18_999_000.0;
+
+    Degrees::new(pi).map_to_0_to_360()
+}
+
+/// Calculate the Moon's mean orbital elements.
+/// In: Julian day in dynamical time
+/// Out: Mean orbital elements, referred to the mean equinox of the date
+pub fn orbital_elements(jd: JD) -> OrbitalElements {
+    let mean_ascending_node = mean_node(jd);
+    let mean_perigee = mean_perigee(jd);
+    let argument_of_perigee = (mean_perigee - mean_ascending_node).map_to_0_to_360();
+
+    OrbitalElements {
+        mean_ascending_node,
+        inclination: Degrees::new(MEAN_INCLINATION),
+        mean_perigee,
+        argument_of_perigee,
+        eccentricity: MEAN_ECCENTRICITY,
+        semi_major_axis: SEMI_MAJOR_AXIS,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn mean_node_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let omega = mean_node(jd);
+
+        // Assert
+        assert_approx_eq!(274.400656, omega.0, 0.000_001)
+    }
+
+    #[test]
+    fn mean_perigee_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let pi = mean_perigee(jd);
+
+        // Assert
+        assert_approx_eq!(129.139544, pi.0, 0.000_001)
+    }
+
+    #[test]
+    fn orbital_elements_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let elements = orbital_elements(jd);
+
+        // Assert
+        assert_approx_eq!(129.139544, elements.mean_perigee.0, 0.000_001);
+        assert_approx_eq!(214.738888, elements.argument_of_perigee.0, 0.000_001);
+        assert_approx_eq!(5.145396, elements.inclination.0, 0.000_001);
+    }
+}
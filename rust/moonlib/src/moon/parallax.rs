@@ -5,6 +5,7 @@ use crate::date::jd::JD;
 use crate::moon::position::distance_from_earth;
 use crate::util::arcsec::ArcSec;
 use crate::util::degrees::Degrees;
+use crate::util::math;
 use crate::util::radians::Radians;
 
 /// Calculate the Moon's equatorial horizontal parallax.
@@ -12,7 +13,13 @@ use crate::util::radians::Radians;
 /// In: Julian Day
 /// Out: horizontal parallax, in degrees
 pub(crate) fn horizontal_equatorial_parallax(jd: JD) -> ArcSec {
-    let distance = distance_from_earth(jd);
+    horizontal_parallax_from_distance(distance_from_earth(jd))
+}
+
+/// Calculate the Moon's equatorial horizontal parallax for its distance
+/// In: Moon's distance from Earth, in kilometers
+/// Out: horizontal parallax, in arcsec
+pub(crate) fn horizontal_parallax_from_distance(distance: f64) -> ArcSec {
     ArcSec::from(Radians::new(constants::EARTH_RADIUS / distance))
 }
 
@@ -25,8 +32,8 @@ pub(crate) fn horizontal_parallax(jd: JD, altitude: Degrees) -> ArcSec {
     let altitude_rad = Radians::from(altitude);
 
     let sin_pi = Radians::from(horizontal_equatorial_parallax(jd));
-    let sin_p = sin_pi.0 * altitude_rad.0.cos();
-    let p = sin_p.asin();
+    let sin_p = sin_pi.0 * math::cos(altitude_rad.0);
+    let p = math::asin(sin_p);
     ArcSec::from(Radians::new(p))
 }
 
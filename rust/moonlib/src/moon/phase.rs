@@ -1,75 +1,159 @@
 //! Phase of the moon
+use crate::date::date::Date;
 use crate::date::jd::JD;
-use crate::sun::position::{
-    apparent_geocentric_longitude, apparent_geometric_latitude, distance_earth_sun,
+use crate::precession::Equinox;
+use crate::session::Observer;
+use crate::sun::position::apparent_position;
+use crate::time::{ScaledJD, TimeScale};
+use crate::util::math;
+use crate::util::{
+    declination::Declination, degrees::Degrees, radians::Radians, right_ascension::RightAscension,
 };
-use crate::util::{degrees::Degrees, radians::Radians};
-use crate::{constants, coordinates, ecliptic, moon};
+use crate::{constants, coordinates, earth, ecliptic, moon, sun};
+
+/// The moon's phase. Two angles describe it, which are easily confused:
+/// - the elongation is the difference of the geocentric longitudes of the
+///   moon and the sun. It increases from 0 at new moon over 90 at first
+///   quarter and 180 at full moon to 360 at the next new moon
+/// - the phase angle is the angle between the sun and the earth, as seen
+///   from the moon, Meeus, chapter 48. It decreases from about 180 at new
+///   moon to about 0 at full moon and increases again, so it does not tell
+///   the waxing from the waning moon
+#[derive(Debug, Copy, Clone)]
+pub struct PhaseInfo {
+    /// Elongation of the moon from the sun, measured eastwards along the
+    /// ecliptic, in degrees [0, 360)
+    pub elongation: Degrees,
+
+    /// Phase angle, Meeus, eq. (48.3), in degrees [0, 180]
+    pub phase_angle: Degrees,
+
+    /// Illuminated fraction of the disk, from the phase angle, Meeus,
+    /// eq. (48.1), [0, 1]
+    pub fraction_illuminated: f64,
+
+    /// true between new moon and full moon, i.e. for an elongation in
+    /// (0, 180)
+    pub is_waxing: bool,
+}
 
-/// Calculate the phase angle or age of the moon.
-/// Meeus, chapter 48, eq. (48.1) or Duffett-Smith and Zwart, chapter 67, page 171
-/// In: Julian day
-/// Out: Phase angle, in degrees [0, 360)
-pub fn phase_angle(jd: JD) -> Degrees {
+/// Calculate the moon's phase from one evaluation of the positions of the
+/// moon and the sun
+/// In: Julian day, in dynamical time
+/// Out: Phase
+pub fn phase_info(jd: JD) -> PhaseInfo {
     // SS: position of the moon, from Earth
-    let longitude = moon::position::geocentric_longitude(jd);
-    let latitude = moon::position::geocentric_latitude(jd);
-    let true_obliquity = ecliptic::true_obliquity(jd);
-    let (ra_moon, dec_moon) =
-        coordinates::ecliptical_2_equatorial(longitude, latitude, true_obliquity);
-    let (ra_moon, dec_moon) = (Radians::from(ra_moon), Radians::from(dec_moon));
+    let moon = moon::geocentric(jd);
 
     // SS: position of the sun, from Earth
-    let longitude = apparent_geocentric_longitude(jd);
-    let latitude = apparent_geometric_latitude(jd);
-    let r = distance_earth_sun(jd);
+    let sun = apparent_position(jd);
+    let r = sun.distance * constants::AU;
+    let true_obliquity = ecliptic::true_obliquity(jd);
     let (ra_sun, dec_sun) =
-        coordinates::ecliptical_2_equatorial(longitude, latitude, true_obliquity);
+        coordinates::ecliptical_2_equatorial(sun.longitude, sun.latitude, true_obliquity);
+
+    let elongation = (moon.lambda - sun.longitude).map_to_0_to_360();
+    let phase_angle = phase_angle_of((moon.ra, moon.dec, moon.delta), (ra_sun, dec_sun, r));
+
+    PhaseInfo {
+        elongation,
+        phase_angle,
+        fraction_illuminated: (1.0 + math::cos(Radians::from(phase_angle).0)) / 2.0,
+        is_waxing: elongation.0 > 0.0 && elongation.0 < 180.0,
+    }
+}
+
+/// Calculate the phase angle of the moon.
+/// Meeus, chapter 48, eq. (48.1)
+/// In: Julian day, in dynamical time, like the positions of the moon and the
+/// sun. See phase_angle_utc for a Julian day in UTC
+/// Out: Phase angle, in degrees [0, 180]
+#[deprecated(note = "use phase_info(jd).phase_angle")]
+pub fn phase_angle(jd: JD) -> Degrees {
+    phase_info(jd).phase_angle
+}
+
+/// Phase angle of the moon, for the positions of the moon and the sun
+/// passed in. Meeus, chapter 48, eq. (48.2) and (48.3)
+/// In:
+/// moon: moon's right ascension and declination, in degrees, and distance,
+/// in km
+/// sun: sun's right ascension and declination, in degrees, and distance,
+/// in km
+/// Out: Phase angle, in degrees [0, 180]
+pub(crate) fn phase_angle_of(
+    moon: (RightAscension, Declination, f64),
+    sun: (RightAscension, Declination, f64),
+) -> Degrees {
+    let (ra_moon, dec_moon, delta) = moon;
+    let (ra_sun, dec_sun, r) = sun;
+    let (ra_moon, dec_moon) = (Radians::from(ra_moon), Radians::from(dec_moon));
     let (ra_sun, dec_sun) = (Radians::from(ra_sun), Radians::from(dec_sun));
 
-    // SS: geocentric elongation of the moon from the sun
+    // SS: elongation of the moon from the sun
     // Meeus, eq. (48.2)
-    let psi = (dec_sun.0.sin() * dec_moon.0.sin()
-        + dec_sun.0.cos() * dec_moon.0.cos() * (ra_sun.0 - ra_moon.0).cos())
-    .acos();
-
-    let delta = moon::position::distance_from_earth(jd);
+    let psi = math::acos(
+        math::sin(dec_sun.0) * math::sin(dec_moon.0)
+            + math::cos(dec_sun.0) * math::cos(dec_moon.0) * math::cos(ra_sun.0 - ra_moon.0),
+    );
 
     // SS: phase angle
-    let phase_angle = (r * psi.sin()).atan2(delta - r * psi.cos());
+    let phase_angle = math::atan2(r * math::sin(psi), delta - r * math::cos(psi));
     Degrees::from(Radians::new(phase_angle)).map_to_0_to_360()
 }
 
-/// Calculate the phase angle or age of the moon.
+/// Calculate the phase angle of the moon for a Julian day in UTC, e.g. from
+/// the device's clock, converting it to dynamical time first. Passing UTC to
+/// phase_angle instead is off by the moon's motion during TT - UTC, i.e.
+/// about 0.01 degrees per minute.
+/// In: Julian day, in UTC
+/// Out: Phase angle, in degrees [0, 180]
+pub fn phase_angle_utc(jd: JD) -> Degrees {
+    phase_info(ScaledJD::new(jd, TimeScale::UTC).to_tt()).phase_angle
+}
+
+/// Calculate the elongation of the moon from the sun, which is not its
+/// phase angle despite the name.
 /// Duffett-Smith and Zwart, chapter 67, page 171
 /// In: Julian day
-/// Out: Phase angle, in degrees [0, 360)
+/// Out: Elongation, in degrees [0, 360)
+#[deprecated(note = "use phase_info(jd).elongation")]
 pub fn phase_angle_360(jd: JD) -> Degrees {
-    // SS: position of the moon, from Earth
-    let longitude_moon = moon::position::geocentric_longitude(jd);
+    phase_info(jd).elongation
+}
 
-    // SS: position of the sun, from Earth
-    let longitude_sun = apparent_geocentric_longitude(jd);
+/// Signed elongation of the moon from the sun, measured along the ecliptic.
+/// Unlike the phase angle, it distinguishes the waxing from the waning moon.
+/// In: Julian day
+/// Out: Elongation, in degrees [-180, 180). Positive when the moon is east of
+/// the sun, i.e. waxing, negative when it is west of the sun, i.e. waning
+pub fn signed_elongation(jd: JD) -> Degrees {
+    phase_info(jd).elongation.map_neg180_to_180()
+}
 
-    (longitude_moon - longitude_sun).map_to_0_to_360()
+/// Whether the illuminated fraction of the moon increases
+/// In: Julian day
+/// Out: true between new moon and full moon, false between full moon and new moon
+pub fn is_waxing(jd: JD) -> bool {
+    phase_info(jd).is_waxing
 }
 
 /// Age of the moon phase in fractions of a day.
 /// In: Julian day
 /// Out: Phase age, in fractional days
 pub(crate) fn phase_age(jd: JD) -> f64 {
-    let phase_angle = phase_angle_360(jd);
+    let elongation = phase_info(jd).elongation;
 
     // SS: Avg. degrees the Moon advances in its orbit around the Earth
     // per day.
-    phase_angle.0 / constants::MOON_DAY
+    elongation.0 / constants::MOON_DAY
 }
 
 /// Textual description of the moon's phase
 /// In: Julian day
 /// Out: Textual description
 pub(crate) fn phase_description(jd: JD) -> &'static str {
-    let phase_angle = phase_angle_360(jd).0;
+    let phase_angle = phase_info(jd).elongation.0;
 
     const SECTION: f64 = 360.0 / (2.0 * 8.0);
 
@@ -93,19 +177,154 @@ pub(crate) fn phase_description(jd: JD) -> &'static str {
     }
 }
 
+/// Fraction of the Moon's disk that is illuminated, Meeus, chapter 48
+/// In: Julian day, in dynamical time
+/// Out: fraction, in [0, 1]
+///
+/// Meeus, example 48.a, 1992 April 12, 0h TD
+/// ```
+/// use moonlib::date::jd::JD;
+/// use moonlib::moon::phase::fraction_illuminated;
+///
+/// let k = fraction_illuminated(JD::new(2_448_724.5));
+/// assert!((k - 0.6786).abs() < 0.0001);
+/// ```
 pub fn fraction_illuminated(jd: JD) -> f64 {
-    let phase_angle = Radians::from(phase_angle(jd));
-    (1.0 + phase_angle.0.cos()) / 2.0
+    phase_info(jd).fraction_illuminated
+}
+
+/// Position angle of the moon's bright limb, i.e. the direction from the
+/// center of the disk to the midpoint of the illuminated limb, measured from
+/// the North Point of the disk towards the East.
+/// Meeus, chapter 48, eq. (48.5)
+/// In: Julian day, in dynamical time
+/// Out: Position angle, in degrees [0, 360)
+pub fn bright_limb_position_angle(jd: JD) -> Degrees {
+    let (ra, decl) = moon::position::equatorial_coordinates(jd, Equinox::OfDate);
+    let (ra_sun, decl_sun) = sun::position::equatorial_coordinates(jd);
+    bright_limb((ra_sun, decl_sun), ra, decl)
+}
+
+/// Position angle of the moon's bright limb, for the positions of the sun
+/// and the moon passed in
+/// In:
+/// sun: sun's right ascension and declination, in degrees
+/// ra, decl: moon's right ascension and declination, in degrees
+/// Out: Position angle, in degrees [0, 360)
+pub(crate) fn bright_limb(
+    sun: (RightAscension, Declination),
+    ra: RightAscension,
+    decl: Declination,
+) -> Degrees {
+    let (ra_sun, decl_sun) = sun;
+
+    let delta_ra = Radians::from(ra_sun - ra).0;
+    let decl = Radians::from(decl).0;
+    let decl_sun = Radians::from(decl_sun).0;
+
+    let chi = math::atan2(
+        math::cos(decl_sun) * math::sin(delta_ra),
+        math::sin(decl_sun) * math::cos(decl)
+            - math::cos(decl_sun) * math::sin(decl) * math::cos(delta_ra),
+    );
+    Degrees::from(Radians::new(chi)).map_to_0_to_360()
+}
+
+/// Tilt of the moon's crescent as seen by the observer, i.e. the zenith
+/// angle of the bright limb, chi - q. Meeus, chapter 48, page 347
+/// In:
+/// jd: Julian day, in dynamical time
+/// observer: Observer's location
+/// Out: Direction of the bright limb, measured counterclockwise from the
+/// direction to the zenith, in degrees [0, 360). 0 if the bright limb points
+/// straight up, 90 if it points to the left. The line of cusps is tilted by
+/// the same angle against the horizon
+pub fn crescent_tilt(jd: JD, observer: &Observer) -> Degrees {
+    let (ra, decl) = moon::position::equatorial_coordinates(jd, Equinox::OfDate);
+    let chi = bright_limb(sun::position::equatorial_coordinates(jd), ra, decl);
+
+    let siderial_time =
+        earth::local_siderial_time(earth::apparent_siderial_time(jd), observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time, ra);
+    let q = coordinates::parallactic_angle(hour_angle, decl, observer.latitude);
+
+    (chi - q).map_to_0_to_360()
+}
+
+/// The moon's phase at an instant chosen for a calendar day, so that a
+/// day's phase does not change with the time it is looked at, like in a
+/// printed almanac
+#[derive(Debug, Copy, Clone)]
+pub struct PhaseAt {
+    /// Instant the phase is given for, Julian day in UTC
+    pub jd: JD,
+
+    /// Phase angle, in degrees [0, 180], see PhaseInfo
+    pub phase_angle: Degrees,
+
+    /// Illuminated fraction of the disk, [0, 1]
+    pub illuminated_fraction: f64,
+
+    pub is_waxing: bool,
+
+    /// Textual description, e.g. "Waxing Crescent"
+    pub description: &'static str,
+}
+
+impl PhaseAt {
+    /// In: Julian day, in UTC
+    fn new(jd: JD) -> Self {
+        let tt = ScaledJD::new(jd, TimeScale::UTC).to_tt();
+        let phase = phase_info(tt);
+        Self {
+            jd,
+            phase_angle: phase.phase_angle,
+            illuminated_fraction: phase.fraction_illuminated,
+            is_waxing: phase.is_waxing,
+            description: phase_description(tt),
+        }
+    }
+}
+
+/// The moon's phase at 12h UTC of a calendar day, the epoch of many
+/// almanacs
+/// In: Date, the fraction of the day is ignored
+/// Out: Phase at 12h UTC
+pub fn phase_at_noon_utc(date: Date) -> PhaseAt {
+    let noon = Date::new(date.year, date.month, date.day.trunc() + 0.5);
+    PhaseAt::new(JD::from_date(noon))
+}
+
+/// The moon's phase at the observer's local apparent solar midnight starting
+/// a calendar day, i.e. when the sun crosses the lower meridian. Unlike
+/// midnight in the observer's time zone, it does not depend on the zone's
+/// boundaries or on daylight saving time.
+/// In:
+/// date: Date, the fraction of the day is ignored
+/// observer: Observer's location
+/// Out: Phase at local apparent solar midnight
+pub fn phase_at_local_midnight(date: Date, observer: &Observer) -> PhaseAt {
+    // SS: mean midnight at the observer's longitude, in UTC. The mean sun
+    // moves 15 degrees per hour and is in the lower meridian at Greenwich
+    // at 0h
+    let mut mean_midnight = JD::from_date(Date::new(date.year, date.month, date.day.trunc()));
+    mean_midnight.add_hours(observer.longitude.to_hours());
+
+    // SS: the apparent sun is ahead of the mean sun by the equation of time
+    let mut midnight = mean_midnight;
+    midnight.add_hours(-sun::equation_of_time(mean_midnight) / 60.0);
+
+    PhaseAt::new(midnight)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::date::date::Date;
     use crate::date::jd::JD;
     use assert_approx_eq::assert_approx_eq;
 
     #[test]
+    #[allow(deprecated)]
     fn phase_angle_test_1() {
         // Arrange
         let jd = JD::from_date(Date::new(1992, 4, 12.0));
@@ -118,6 +337,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn phase_angle_test_2() {
         // J.L. Lawrence, Celestial Calculations, 2018, page 180
 
@@ -156,6 +376,50 @@ mod tests {
         assert_approx_eq!(24.37, phase_age, 0.01)
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn signed_elongation_test() {
+        // Arrange
+        let waxing = JD::from_date(Date::new(1992, 4, 12.0));
+        let waning = JD::from_date(Date::new(2022, 2, 26.0));
+
+        // Act
+        let elongation_waxing = signed_elongation(waxing);
+        let elongation_waning = signed_elongation(waning);
+
+        // Assert
+        assert_approx_eq!(phase_angle_360(waxing).0, elongation_waxing.0, 0.000_001);
+        assert_approx_eq!(
+            phase_angle_360(waning).0 - 360.0,
+            elongation_waning.0,
+            0.000_001
+        );
+    }
+
+    #[test]
+    fn is_waxing_test() {
+        // Arrange
+
+        // SS: full moon on 2022 March 18, about 7h UT
+        let before_full_moon = JD::from_date(Date::new(2022, 3, 17.0));
+        let after_full_moon = JD::from_date(Date::new(2022, 3, 19.0));
+
+        // Act
+        let waxing = is_waxing(before_full_moon);
+        let waning = is_waxing(after_full_moon);
+
+        // Assert
+
+        // SS: about the same fraction is illuminated on both days
+        assert_approx_eq!(
+            fraction_illuminated(before_full_moon),
+            fraction_illuminated(after_full_moon),
+            0.02
+        );
+        assert!(waxing);
+        assert!(!waning);
+    }
+
     #[test]
     fn fraction_illuminated_test_1() {
         // Arrange
@@ -267,4 +531,212 @@ mod tests {
         // Assert
         assert_eq!("Waxing Gibbous", phase_desc)
     }
+
+    #[test]
+    fn bright_limb_position_angle_test() {
+        // Meeus, example 48.a, page 347
+
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let chi = bright_limb_position_angle(jd);
+
+        // Assert
+        assert_approx_eq!(285.0, chi.0, 0.1)
+    }
+
+    #[test]
+    fn crescent_tilt_test() {
+        // Arrange
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian, in the evening
+        // of Feb. 20th, 1977, 2 days after new moon, with the crescent low in
+        // the West
+        let observer = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+        let jd = JD::from_date(Date::new(1977, 2, 20.75));
+
+        // Act
+        let tilt = crescent_tilt(jd, &observer);
+
+        // Assert
+
+        // SS: the bright limb points towards the Sun, which is below the
+        // horizon, i.e. down and to the right
+        let (ra, decl) = moon::position::equatorial_coordinates(jd, Equinox::OfDate);
+        let siderial_time =
+            earth::local_siderial_time(earth::apparent_siderial_time(jd), observer.longitude);
+        let hour_angle = earth::hour_angle(siderial_time, ra);
+        let (azimuth, altitude) =
+            coordinates::equatorial_2_horizontal(decl, hour_angle, observer.latitude);
+        let (azimuth_sun, altitude_sun) =
+            sun::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+        assert!(altitude.0 > 0.0 && altitude_sun.0 < 0.0);
+
+        // SS: eq. (48.5) in the horizontal system. The azimuth increases to
+        // the right, i.e. clockwise, so the difference is reversed
+        let delta_azimuth = Radians::from(azimuth - azimuth_sun).0;
+        let altitude = Radians::from(altitude).0;
+        let altitude_sun = Radians::from(altitude_sun).0;
+        let expected = Degrees::from(Radians::new(math::atan2(
+            math::cos(altitude_sun) * math::sin(delta_azimuth),
+            math::sin(altitude_sun) * math::cos(altitude)
+                - math::cos(altitude_sun) * math::sin(altitude) * math::cos(delta_azimuth),
+        )))
+        .map_to_0_to_360();
+
+        assert!(tilt.0 > 180.0 && tilt.0 < 270.0, "tilt {}", tilt.0);
+        assert_approx_eq!(expected.0, tilt.0, 0.001);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn phase_angle_utc_test() {
+        // Arrange
+
+        // SS: Jan 16th, 2022, 2:26:18pm UTC
+        let jd = JD::new(2_459_596.101598);
+        let tt = crate::time::utc_2_tt(jd);
+
+        // Act
+        let from_utc = phase_angle_utc(jd);
+
+        // Assert
+        assert_eq!(phase_angle(tt).0, from_utc.0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn phase_angle_utc_error_test() {
+        // Arrange
+
+        // SS: Jan 16th, 2022, 2:26:18pm UTC
+        let jd = JD::new(2_459_596.101598);
+        let tt_minus_utc = (crate::time::utc_2_tt(jd).jd - jd.jd) * 86400.0;
+
+        // Act
+        let from_utc = phase_angle_utc(jd);
+        let utc_as_tt = phase_angle(jd);
+
+        // Assert
+
+        // SS: passing UTC as TT evaluates the phase angle TT - UTC too early,
+        // so the error is the phase angle's rate of change times TT - UTC
+        let error = (from_utc - utc_as_tt).map_neg180_to_180().0;
+        let rate = (phase_angle(JD::new(jd.jd + 0.5)) - phase_angle(JD::new(jd.jd - 0.5)))
+            .map_neg180_to_180()
+            .0
+            .abs();
+        assert!(tt_minus_utc > 60.0);
+        assert_approx_eq!(rate * tt_minus_utc / 86400.0, error.abs(), 0.001);
+        assert!(error.abs() < 0.05, "error {error}");
+    }
+
+    #[test]
+    fn phase_info_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let phase = phase_info(jd);
+
+        // Assert
+
+        // SS: Meeus, example 48.a
+        assert_approx_eq!(69.0756, phase.phase_angle.0, 0.0001);
+        assert_approx_eq!(0.6786, phase.fraction_illuminated, 0.0001);
+        assert!(phase.is_waxing);
+        assert_approx_eq!(
+            moon::geocentric(jd).lambda.0 - apparent_position(jd).longitude.0,
+            phase.elongation.0,
+            1e-9
+        );
+    }
+
+    #[test]
+    fn phase_info_consistency_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2024, 1, 1.0));
+
+        for hours in (0..60 * 24).step_by(5) {
+            let jd = JD::new(start.jd + hours as f64 / 24.0);
+
+            // Act
+            let phase = phase_info(jd);
+
+            // Assert
+
+            // SS: both angles agree up to the moon's latitude and the
+            // sun's parallax as seen from the moon
+            let elongation = phase.elongation.map_neg180_to_180().0.abs();
+            assert_approx_eq!(180.0 - elongation, phase.phase_angle.0, 6.0);
+
+            // SS: the illuminated fraction increases while the moon waxes,
+            // except within a few hours of new and full moon
+            if elongation > 5.0 && elongation < 175.0 {
+                let later = phase_info(JD::new(jd.jd + 1.0 / 24.0));
+                assert_eq!(
+                    phase.is_waxing,
+                    later.fraction_illuminated > phase.fraction_illuminated
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn phase_at_noon_utc_test() {
+        // Arrange
+
+        // SS: full moon on 2024 February 24, 12:30 UTC
+        let morning = Date::new(2024, 2, 24.1);
+        let evening = Date::new(2024, 2, 24.9);
+
+        // Act
+        let phase_morning = phase_at_noon_utc(morning);
+        let phase_evening = phase_at_noon_utc(evening);
+
+        // Assert
+        assert_eq!(2_460_365.0, phase_morning.jd.jd);
+        assert_eq!(phase_morning.jd.jd, phase_evening.jd.jd);
+        assert_eq!("Full Moon", phase_morning.description);
+        assert!(phase_morning.is_waxing);
+        assert!(phase_morning.illuminated_fraction > 0.999);
+
+        // SS: not 0, as the moon passes up to 5 degrees north or south of
+        // the point opposite the sun
+        assert!(phase_morning.phase_angle.0 < 5.0);
+    }
+
+    #[test]
+    fn phase_at_local_midnight_test() {
+        // Arrange
+        let date = Date::new(2024, 2, 11.5);
+        let greenwich = Observer::new(0.0, 51.5, 0, 1013.0, 10.0);
+        let munich = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+
+        // Act
+        let phase_greenwich = phase_at_local_midnight(date, &greenwich);
+        let phase_munich = phase_at_local_midnight(date, &munich);
+
+        // Assert
+
+        // SS: the equation of time is -14.2 minutes in mid February, so
+        // the apparent sun is in the lower meridian at Greenwich at 0:14 UTC
+        assert_approx_eq!(
+            2_460_351.5 + 14.2 / 1440.0,
+            phase_greenwich.jd.jd,
+            0.1 / 1440.0
+        );
+
+        // SS: 11.6 degrees east of Greenwich is 46.4 minutes earlier
+        assert_approx_eq!(
+            -46.4 / 1440.0,
+            phase_munich.jd.jd - phase_greenwich.jd.jd,
+            0.01 / 1440.0
+        );
+        assert_eq!("New Moon", phase_greenwich.description);
+        assert!(phase_greenwich.is_waxing);
+    }
 }
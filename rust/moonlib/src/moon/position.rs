@@ -1,14 +1,96 @@
 //! Calculate the moon's position for given Julian day.
 //! see J. Meeus, Astronomical Algorithms, chapter 47
 use crate::date::jd::JD;
-use crate::util::{degrees::Degrees, radians::Radians};
-use crate::{earth, nutation, sun::sun};
+use crate::moon::parallax;
+use crate::observation_log::{FixedEquatorial, Rounding};
+pub use crate::precession::Equinox;
+use crate::refraction::{self, Refraction};
+use crate::session::Observer;
+use crate::time::{self, LeapSeconds};
+use crate::track::ObserverTrack;
+use crate::util::{
+    arcsec::ArcSec, declination::Declination, degrees::Degrees, math, radians::Radians,
+    right_ascension::RightAscension,
+};
+use crate::{coordinates, earth, ecliptic, nutation, precession, sun::sun};
+#[cfg(feature = "high-precision-moon")]
 use tabular::moon_position_data;
 
+// SS: without feature high-precision-moon, only the terms of tables 47.A and
+// 47.B larger than 0.01 deg, resp. 10 km, are summed up, good to 0.05 deg and
+// 50 km from 1900 to 2100. The terms are (D, M, M', F, coefficient), the
+// coefficients in 1e-6 degrees, resp. meters
+
+/// Largest terms of the Moon's longitude, table 47.A
+#[cfg(not(feature = "high-precision-moon"))]
+const LARGEST_SIGMA_L_TERMS: [(i8, i8, i8, i8, f64); 18] = [
+    (0, 0, 1, 0, 6288774.0),
+    (2, 0, -1, 0, 1274027.0),
+    (2, 0, 0, 0, 658314.0),
+    (0, 0, 2, 0, 213618.0),
+    (0, 1, 0, 0, -185116.0),
+    (0, 0, 0, 2, -114332.0),
+    (2, 0, -2, 0, 58793.0),
+    (2, -1, -1, 0, 57066.0),
+    (2, 0, 1, 0, 53322.0),
+    (2, -1, 0, 0, 45758.0),
+    (0, 1, -1, 0, -40923.0),
+    (1, 0, 0, 0, -34720.0),
+    (0, 1, 1, 0, -30383.0),
+    (2, 0, 0, -2, 15327.0),
+    (0, 0, 1, 2, -12528.0),
+    (0, 0, 1, -2, 10980.0),
+    (4, 0, -1, 0, 10675.0),
+    (0, 0, 3, 0, 10034.0),
+];
+
+/// Largest terms of the Moon's distance, table 47.A
+#[cfg(not(feature = "high-precision-moon"))]
+const LARGEST_SIGMA_R_TERMS: [(i8, i8, i8, i8, f64); 25] = [
+    (0, 0, 1, 0, -20905355.0),
+    (2, 0, -1, 0, -3699111.0),
+    (2, 0, 0, 0, -2955968.0),
+    (0, 0, 2, 0, -569925.0),
+    (0, 1, 0, 0, 48888.0),
+    (2, 0, -2, 0, 246158.0),
+    (2, -1, -1, 0, -152138.0),
+    (2, 0, 1, 0, -170733.0),
+    (2, -1, 0, 0, -204586.0),
+    (0, 1, -1, 0, -129620.0),
+    (1, 0, 0, 0, 108743.0),
+    (0, 1, 1, 0, 104755.0),
+    (2, 0, 0, -2, 10321.0),
+    (0, 0, 1, -2, 79661.0),
+    (4, 0, -1, 0, -34782.0),
+    (0, 0, 3, 0, -23210.0),
+    (4, 0, -2, 0, -21636.0),
+    (2, 1, -1, 0, 24208.0),
+    (2, 1, 0, 0, 30824.0),
+    (1, 1, 0, 0, -16675.0),
+    (2, -1, 1, 0, -12831.0),
+    (2, 0, 2, 0, -10445.0),
+    (4, 0, 0, 0, -11650.0),
+    (2, 0, -3, 0, 14403.0),
+    (2, -1, -2, 0, 10056.0),
+];
+
+/// Largest terms of the Moon's latitude, table 47.B
+#[cfg(not(feature = "high-precision-moon"))]
+const LARGEST_SIGMA_B_TERMS: [(i8, i8, i8, i8, f64); 8] = [
+    (0, 0, 0, 1, 5128122.0),
+    (0, 0, 1, 1, 280602.0),
+    (0, 0, 1, -1, 277693.0),
+    (2, 0, 0, -1, 173237.0),
+    (2, 0, -1, 1, 55413.0),
+    (2, 0, -1, -1, 46271.0),
+    (2, 0, 0, 1, 32573.0),
+    (0, 0, 2, 1, 17198.0),
+];
+
 /// Calculate the moon's mean longitude, eq (47.1).
 /// In: Julian day in dynamical time
 /// Out: Moon's mean longitude in degrees, [0, 360)
-fn mean_longitude(jd: JD) -> Degrees {
+pub(super) fn mean_longitude(jd: JD) -> Degrees {
     let t = jd.centuries_from_epoch_j2000();
 
     let t2 = t * t;
@@ -24,7 +106,7 @@ fn mean_longitude(jd: JD) -> Degrees {
 /// Calculate the moon's mean elongation, eq (47.2).
 /// In: Julian day in dynamical time
 /// Out: Moon's mean elongation in degrees, [0, 360)
-fn mean_elongation(jd: JD) -> Degrees {
+pub(super) fn mean_elongation(jd: JD) -> Degrees {
     let t = jd.centuries_from_epoch_j2000();
 
     let t2 = t * t;
@@ -40,7 +122,7 @@ fn mean_elongation(jd: JD) -> Degrees {
 /// Calculate the moon's mean anomaly, eq (47.4).
 /// In: Julian day in dynamical time
 /// Out: Moon's mean anomaly in degrees, [0, 360)
-fn mean_anomaly(jd: JD) -> Degrees {
+pub(super) fn mean_anomaly(jd: JD) -> Degrees {
     let t = jd.centuries_from_epoch_j2000();
 
     let t2 = t * t;
@@ -56,7 +138,7 @@ fn mean_anomaly(jd: JD) -> Degrees {
 /// Calculate the moon's argument of latitude, eq (47.5).
 /// In: Julian day in dynamical time
 /// Out: Moon's argument of latitude in degrees, [0, 360)
-fn argument_of_latitude(jd: JD) -> Degrees {
+pub(super) fn argument_of_latitude(jd: JD) -> Degrees {
     let t = jd.centuries_from_epoch_j2000();
 
     let t2 = t * t;
@@ -69,137 +151,707 @@ fn argument_of_latitude(jd: JD) -> Degrees {
     Degrees::new(argument_of_latitude).map_to_0_to_360()
 }
 
-/// Calculate the moon's longitude (lambda), page 342
-/// In: Julian day in dynamical time
-/// Out: Moon's longitude in degrees, [0, 360)
-pub(crate) fn geocentric_longitude(jd: JD) -> Degrees {
-    let t = jd.centuries_from_epoch_j2000();
+/// Sum of the periodic terms of tables 47.A and 47.B, Meeus page 338
+/// In:
+/// terms: multiples of D, M, M' and F and the coefficient of each term
+/// [d, m, m_prime, f]: fundamental arguments
+/// e: eccentricity of Earth's orbit, eq (47.6)
+/// trig: sine or cosine
+/// Out:
+/// Sum of the terms
+#[cfg(feature = "high-precision-moon")]
+fn sum_of_terms(
+    terms: impl Iterator<Item = (i8, i8, i8, i8, i64)>,
+    [d, m, m_prime, f]: [Radians; 4],
+    e: f64,
+    trig: fn(f64) -> f64,
+) -> f64 {
+    terms.fold(0.0, |accum, c| {
+        let arg = c.0 as f64 * d.0 + c.1 as f64 * m.0 + c.2 as f64 * m_prime.0 + c.3 as f64 * f.0;
+        let mut coeff = c.4 as f64;
+
+        // SS: terms containing M depend on the eccentricity of Earth's orbit
+        if c.1 != 0 {
+            coeff *= e;
+        }
+
+        if c.1 == -2 || c.1 == 2 {
+            coeff *= e;
+        }
+
+        accum + coeff * trig(arg)
+    })
+}
+
+/// Sum of the largest periodic terms of tables 47.A and 47.B, see
+/// sum_of_terms
+#[cfg(not(feature = "high-precision-moon"))]
+fn sum_of_largest_terms(
+    terms: &[(i8, i8, i8, i8, f64)],
+    [d, m, m_prime, f]: [Radians; 4],
+    e: f64,
+    trig: fn(f64) -> f64,
+) -> f64 {
+    terms
+        .iter()
+        .fold(0.0, |accum, &(d_k, m_k, m_prime_k, f_k, coeff)| {
+            let arg = d_k as f64 * d.0
+                + m_k as f64 * m.0
+                + m_prime_k as f64 * m_prime.0
+                + f_k as f64 * f.0;
+
+            // SS: terms containing M depend on the eccentricity of Earth's orbit
+            accum + coeff * e.powi(m_k.abs() as i32) * trig(arg)
+        })
+}
+
+/// Sum of the periodic terms for the Moon's longitude, in 1e-6 degrees
+#[cfg(feature = "high-precision-moon")]
+fn sigma_l(arguments: [Radians; 4], e: f64) -> f64 {
+    let terms = moon_position_data::SIGMA_L_AND_R_COEFFICIENTS
+        .iter()
+        .map(|&(d, m, m_prime, f, l, _)| (d, m, m_prime, f, l));
+    sum_of_terms(terms, arguments, e, math::sin)
+}
+
+/// Sum of the periodic terms for the Moon's latitude, in 1e-6 degrees
+#[cfg(feature = "high-precision-moon")]
+fn sigma_b(arguments: [Radians; 4], e: f64) -> f64 {
+    let terms = moon_position_data::SIGMA_B_COEFFICIENTS.iter().copied();
+    sum_of_terms(terms, arguments, e, math::sin)
+}
+
+/// Sum of the periodic terms for the Moon's distance, in meters
+#[cfg(feature = "high-precision-moon")]
+fn sigma_r(arguments: [Radians; 4], e: f64) -> f64 {
+    let terms = moon_position_data::SIGMA_L_AND_R_COEFFICIENTS
+        .iter()
+        .map(|&(d, m, m_prime, f, _, r)| (d, m, m_prime, f, r));
+    sum_of_terms(terms, arguments, e, math::cos)
+}
+
+#[cfg(not(feature = "high-precision-moon"))]
+fn sigma_l(arguments: [Radians; 4], e: f64) -> f64 {
+    sum_of_largest_terms(&LARGEST_SIGMA_L_TERMS, arguments, e, math::sin)
+}
+
+#[cfg(not(feature = "high-precision-moon"))]
+fn sigma_b(arguments: [Radians; 4], e: f64) -> f64 {
+    sum_of_largest_terms(&LARGEST_SIGMA_B_TERMS, arguments, e, math::sin)
+}
+
+#[cfg(not(feature = "high-precision-moon"))]
+fn sigma_r(arguments: [Radians; 4], e: f64) -> f64 {
+    sum_of_largest_terms(&LARGEST_SIGMA_R_TERMS, arguments, e, math::cos)
+}
+
+/// Fundamental arguments of the series, eq (47.1) to (47.6)
+struct Arguments {
+    /// Julian centuries from J2000.0
+    t: f64,
+    l_prime: Radians,
+    d: Radians,
+    m: Radians,
+    m_prime: Radians,
+    f: Radians,
+    e: f64,
+}
+
+impl Arguments {
+    /// In: Julian day in dynamical time
+    fn new(jd: JD) -> Self {
+        Self {
+            t: jd.centuries_from_epoch_j2000(),
+            l_prime: Radians::from(mean_longitude(jd)),
+            d: Radians::from(mean_elongation(jd)),
+            m: Radians::from(sun::mean_anomaly(jd)),
+            m_prime: Radians::from(mean_anomaly(jd)),
+            f: Radians::from(argument_of_latitude(jd)),
+            e: earth::eccentricity(jd),
+        }
+    }
+}
+
+/// Moon's longitude (lambda) from the fundamental arguments, page 342
+fn longitude(arguments: &Arguments, jd: JD) -> Degrees {
+    longitude_with_nutation(arguments, nutation::nutation_in_longitude(jd))
+}
+
+/// Moon's longitude (lambda) from the fundamental arguments and the
+/// nutation in longitude passed in
+fn longitude_with_nutation(arguments: &Arguments, nutation_delta: ArcSec) -> Degrees {
+    let Arguments {
+        t,
+        l_prime,
+        d,
+        m,
+        m_prime,
+        f,
+        e,
+    } = *arguments;
 
     let a1 = Radians::from(Degrees::new(119.75 + 131.849 * t).map_to_0_to_360());
     let a2 = Radians::from(Degrees::new(53.09 + 479264.290 * t).map_to_0_to_360());
 
-    let l_prime = Radians::from(mean_longitude(jd));
-    let d = Radians::from(mean_elongation(jd));
-    let m = Radians::from(sun::mean_anomaly(jd));
-    let m_prime = Radians::from(mean_anomaly(jd));
-    let f = Radians::from(argument_of_latitude(jd));
-    let e = earth::eccentricity(jd);
-
     // SS: perturbation term for moon's longitude
-    let mut sigma_l =
-        moon_position_data::SIGMA_L_AND_R_COEFFICIENTS
-            .iter()
-            .fold(0.0, |accum, &c| {
-                let sin_arg =
-                    c.0 as f64 * d.0 + c.1 as f64 * m.0 + c.2 as f64 * m_prime.0 + c.3 as f64 * f.0;
-                let mut coeff = c.4 as f64;
-
-                if c.1 != 0 {
-                    coeff *= e;
-                }
-
-                if c.1 == -2 || c.1 == 2 {
-                    coeff *= e;
-                }
-
-                let value = coeff * sin_arg.sin();
-                accum + value
-            });
-
-    sigma_l += 3958.0 * a1.0.sin();
-    sigma_l += 1962.0 * (l_prime - f).0.sin();
-    sigma_l += 318.0 * a2.0.sin();
-
-    let nutation_delta = nutation::nutation_in_longitude(jd);
+    let mut sigma_l = sigma_l([d, m, m_prime, f], e);
+
+    sigma_l += 3958.0 * math::sin(a1.0);
+    sigma_l += 1962.0 * math::sin((l_prime - f).0);
+    sigma_l += 318.0 * math::sin(a2.0);
+
     let l_prime_degrees = Degrees::from(l_prime);
 
     Degrees::new(l_prime_degrees.0 + sigma_l / 1_000_000.0 + Degrees::from(nutation_delta).0)
 }
 
-/// Calculate the moon's latitude (beta), page 342
-/// In: Julian day in dynamical time
-/// Out: Moon's latitude in degrees, [0, 360)
-pub(crate) fn geocentric_latitude(jd: JD) -> Degrees {
-    let t = jd.centuries_from_epoch_j2000();
+/// Moon's latitude (beta) from the fundamental arguments, page 342
+fn latitude(arguments: &Arguments) -> Degrees {
+    let Arguments {
+        t,
+        l_prime,
+        d,
+        m,
+        m_prime,
+        f,
+        e,
+    } = *arguments;
 
     let a1 = Radians::from(Degrees::new(119.75 + 131.849 * t).map_to_0_to_360());
     let a3 = Radians::from(Degrees::new(313.45 + 481266.484 * t).map_to_0_to_360());
 
-    let l_prime = Radians::from(mean_longitude(jd));
-    let d = Radians::from(mean_elongation(jd));
-    let m = Radians::from(sun::mean_anomaly(jd));
-    let m_prime = Radians::from(mean_anomaly(jd));
-    let f = Radians::from(argument_of_latitude(jd));
-    let e = earth::eccentricity(jd);
-
     // SS: perturbation term for moon's latitude
-    let mut sigma_b = moon_position_data::SIGMA_B_COEFFICIENTS
-        .iter()
-        .fold(0.0, |accum, &c| {
-            let sin_arg =
-                c.0 as f64 * d.0 + c.1 as f64 * m.0 + c.2 as f64 * m_prime.0 + c.3 as f64 * f.0;
-            let mut coeff = c.4 as f64;
+    let mut sigma_b = sigma_b([d, m, m_prime, f], e);
 
-            if c.1 != 0 {
-                coeff *= e;
-            }
+    sigma_b -= 2235.0 * math::sin(l_prime.0);
+    sigma_b += 382.0 * math::sin(a3.0);
+    sigma_b += 175.0 * math::sin((a1 - f).0);
+    sigma_b += 175.0 * math::sin((a1 + f).0);
+    sigma_b += 127.0 * math::sin((l_prime - m_prime).0);
+    sigma_b -= 115.0 * math::sin((l_prime + m_prime).0);
 
-            if c.1 == -2 || c.1 == 2 {
-                coeff *= e;
-            }
+    Degrees::new(sigma_b / 1_000_000.0)
+}
 
-            accum + coeff * sin_arg.sin()
-        });
+/// Moon's distance (delta) from the fundamental arguments, page 342
+fn distance(arguments: &Arguments) -> f64 {
+    let Arguments {
+        d,
+        m,
+        m_prime,
+        f,
+        e,
+        ..
+    } = *arguments;
 
-    sigma_b -= 2235.0 * l_prime.0.sin();
-    sigma_b += 382.0 * a3.0.sin();
-    sigma_b += 175.0 * (a1 - f).0.sin();
-    sigma_b += 175.0 * (a1 + f).0.sin();
-    sigma_b += 127.0 * (l_prime - m_prime).0.sin();
-    sigma_b -= 115.0 * (l_prime + m_prime).0.sin();
+    // SS: perturbation term for moon's distance
+    let sigma_r = sigma_r([d, m, m_prime, f], e);
 
-    Degrees::new(sigma_b / 1_000_000.0)
+    // SS: 385,000.56 is the mean distance Earth-Moon,
+    // now add the perturbation term
+    385_000.56 + sigma_r / 1000.0
+}
+
+/// Calculate the moon's longitude (lambda), page 342
+/// In: Julian day in dynamical time
+/// Out: Moon's longitude in degrees, [0, 360)
+pub fn geocentric_longitude(jd: JD) -> Degrees {
+    longitude(&Arguments::new(jd), jd)
 }
 
 /// Calculate the moon's distance (delta) from earth, page 342
 /// In: Julian day in dynamical time
 /// Out: Moon's distance from Earth, in kilometers
 pub fn distance_from_earth(jd: JD) -> f64 {
-    let d = Radians::from(mean_elongation(jd));
-    let m = Radians::from(sun::mean_anomaly(jd));
-    let m_prime = Radians::from(mean_anomaly(jd));
-    let f = Radians::from(argument_of_latitude(jd));
-    let e = earth::eccentricity(jd);
+    distance(&Arguments::new(jd))
+}
 
-    // SS: perturbation term for moon's longitude
-    let sigma_r = moon_position_data::SIGMA_L_AND_R_COEFFICIENTS
-        .iter()
-        .fold(0.0, |accum, &c| {
-            let cos_arg =
-                c.0 as f64 * d.0 + c.1 as f64 * m.0 + c.2 as f64 * m_prime.0 + c.3 as f64 * f.0;
-            let mut coeff = c.5 as f64;
+/// The moon's apparent geocentric position, referred to the true equinox
+/// of the date
+#[derive(Debug, Copy, Clone)]
+pub struct GeocentricMoon {
+    /// Longitude, in degrees [0, 360)
+    pub lambda: Degrees,
 
-            if c.1 != 0 {
-                coeff *= e;
-            }
+    /// Latitude, in degrees [-90, 90]
+    pub beta: Degrees,
 
-            if c.1 == -2 || c.1 == 2 {
-                coeff *= e;
-            }
+    /// Distance from the center of the Earth, in kilometers
+    pub delta: f64,
 
-            accum + coeff * cos_arg.cos()
-        });
+    /// Right ascension, in degrees [0, 360)
+    pub ra: RightAscension,
 
-    // SS: 385,000.56 is the mean distance Earth-Moon,
-    // now add the perturbation term
-    385_000.56 + sigma_r / 1000.0
+    /// Declination, in degrees [-90, 90)
+    pub dec: Declination,
+}
+
+/// Calculate the moon's apparent geocentric ecliptical and equatorial
+/// coordinates, evaluating the series of chapter 47 once. Prefer this to
+/// calling geocentric_longitude, distance_from_earth and the transformation
+/// to equatorial coordinates one after the other.
+/// In: Julian day in dynamical time
+/// Out: Moon's geocentric position
+///
+/// Meeus, example 47.a, 1992 April 12, 0h TD. The longitude includes the
+/// nutation in longitude of 0.004610 degrees
+/// ```
+/// use moonlib::config::AngleUnit;
+/// use moonlib::date::jd::JD;
+/// use moonlib::moon::geocentric;
+///
+/// let moon = geocentric(JD::new(2_448_724.5));
+/// assert!((moon.lambda.value(AngleUnit::Degrees) - 133.167_265).abs() < 1e-5);
+/// assert!((moon.beta.value(AngleUnit::Degrees) + 3.229_126).abs() < 1e-6);
+/// assert!((moon.delta - 368_409.7).abs() < 0.1);
+/// assert!((moon.ra.degrees().value(AngleUnit::Degrees) - 134.688_470).abs() < 1e-4);
+/// assert!((moon.dec.degrees().value(AngleUnit::Degrees) - 13.768_368).abs() < 1e-4);
+/// ```
+pub fn geocentric(jd: JD) -> GeocentricMoon {
+    geocentric_with_nutation(
+        jd,
+        nutation::nutation_in_longitude(jd),
+        ecliptic::true_obliquity(jd),
+    )
+}
+
+/// Calculate the moon's apparent geocentric position for the nutation passed
+/// in, e.g. interpolated for many positions close in time
+/// In:
+/// jd: Julian day in dynamical time
+/// delta_psi: nutation in longitude, in arcsec
+/// eps: true obliquity of the ecliptic, in degrees
+/// Out: Moon's geocentric position
+pub(crate) fn geocentric_with_nutation(jd: JD, delta_psi: ArcSec, eps: Degrees) -> GeocentricMoon {
+    let arguments = Arguments::new(jd);
+    let lambda = longitude_with_nutation(&arguments, delta_psi);
+    let beta = latitude(&arguments);
+    let delta = distance(&arguments);
+
+    let (ra, dec) = coordinates::ecliptical_2_equatorial(lambda, beta, eps);
+
+    GeocentricMoon {
+        lambda,
+        beta,
+        delta,
+        ra,
+        dec,
+    }
+}
+
+/// Calculate the moon's geocentric ecliptical coordinates.
+/// In:
+/// jd: Julian day in dynamical time
+/// equinox: Equinox and ecliptic the coordinates are referred to. For the
+/// equinox of the date, the apparent coordinates are returned, as by
+/// geocentric. For J2000, the effect of
+/// nutation is removed and the coordinates are precessed to the mean
+/// equinox and ecliptic of J2000.0, e.g. to compare them with a DE ephemeris.
+/// Out:
+/// longitude, in degrees [0, 360)
+/// latitude, in degrees [-90, 90]
+pub fn ecliptical_coordinates(jd: JD, equinox: Equinox) -> (Degrees, Degrees) {
+    let arguments = Arguments::new(jd);
+    let longitude = longitude(&arguments, jd).map_to_0_to_360();
+    let latitude = latitude(&arguments);
+    precession::apparent_ecliptical_2_equinox(longitude, latitude, jd, equinox)
+}
+
+/// Calculate the moon's geocentric equatorial coordinates.
+/// In:
+/// jd: Julian day in dynamical time
+/// equinox: Equinox the coordinates are referred to. For the true equinox
+/// of the date, the apparent coordinates are returned. For J2000, the effect
+/// of nutation is removed and the coordinates are precessed to the mean
+/// equinox of J2000.0.
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+pub fn equatorial_coordinates(jd: JD, equinox: Equinox) -> (RightAscension, Declination) {
+    match equinox {
+        Equinox::OfDate => {
+            let moon = geocentric(jd);
+            (moon.ra, moon.dec)
+        }
+        Equinox::J2000 => {
+            let arguments = Arguments::new(jd);
+            let longitude = longitude(&arguments, jd);
+            let latitude = latitude(&arguments);
+
+            // SS: referred to the mean equinox of the date, i.e. without nutation
+            let nutation_delta = Degrees::from(nutation::nutation_in_longitude(jd));
+            let eps = ecliptic::mean_obliquity(jd);
+            let (ra, decl) =
+                coordinates::ecliptical_2_equatorial(longitude - nutation_delta, latitude, eps);
+            precession::equatorial_of_date_2_j2000(ra, decl, jd)
+        }
+    }
+}
+
+/// Calculate the moon's geocentric equatorial coordinates in integer
+/// milliarcseconds, for observation logs.
+/// In:
+/// jd: Julian day in dynamical time
+/// equinox: Equinox the coordinates are referred to
+/// rounding: Rounding rule
+/// Out:
+/// right ascension and declination, in milliarcseconds
+pub fn equatorial_coordinates_fixed(
+    jd: JD,
+    equinox: Equinox,
+    rounding: Rounding,
+) -> FixedEquatorial {
+    let (ra, decl) = equatorial_coordinates(jd, equinox);
+    FixedEquatorial::new(ra.0, decl.0, rounding)
+}
+
+/// Calculate the moon's topocentric horizontal coordinates for an observer
+/// at sea level. Atmospheric refraction is not taken into account.
+/// In:
+/// jd: Julian day in dynamical time
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// Out:
+/// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+/// Altitude, in degrees [-90, 90)
+pub(crate) fn horizontal_coordinates(
+    jd: JD,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+) -> (Degrees, Degrees) {
+    let moon = geocentric(jd);
+
+    // SS: equatorial geocentric coordinates to equatorial topocentric coordinates
+    let (ra_topocentric, decl_topocentric) = coordinates::equatorial_2_topocentric(
+        moon.ra,
+        moon.dec,
+        longitude_observer,
+        latitude_observer,
+        0.0,
+        moon.delta,
+        jd,
+    );
+
+    let siderial_time_apparent_greenwich = earth::apparent_siderial_time(jd);
+    let siderial_time_local =
+        earth::local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
+    let hour_angle = earth::hour_angle(siderial_time_local, ra_topocentric);
+    coordinates::equatorial_2_horizontal(decl_topocentric, hour_angle, latitude_observer)
+}
+
+/// Accuracy of the truncated ELP-2000/82 series in chapter 47, in arcseconds.
+/// Meeus, chapter 47: about 10" in longitude and 4" in latitude
+const SERIES_UNCERTAINTY: f64 = 10.8;
+
+/// Error of the refraction model relative to the refraction itself. Close to
+/// the horizon, the actual refraction depends on the temperature profile along
+/// the line of sight and may differ from eq (16.4) by several arcminutes.
+/// Meeus, chapter 16
+const REFRACTION_RELATIVE_UNCERTAINTY: f64 = 0.1;
+
+/// The Moon's topocentric position for an observer
+#[derive(Debug, Copy, Clone)]
+pub struct Topocentric {
+    /// Topocentric right ascension, in degrees [0, 360)
+    pub right_ascension: RightAscension,
+
+    /// Topocentric declination, in degrees [-90, 90)
+    pub declination: Declination,
+
+    /// Local hour angle, in degrees [0, 360)
+    pub hour_angle: Degrees,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: Degrees,
+
+    /// True altitude, i.e. without atmospheric refraction, in degrees [-90, 90)
+    pub true_altitude: Degrees,
+
+    /// Apparent altitude, i.e. corrected for atmospheric refraction, in
+    /// degrees [-90, 90)
+    pub apparent_altitude: Degrees,
+
+    /// Apparent zenith distance, i.e. 90 degrees minus the apparent
+    /// altitude, in degrees [0, 180]
+    pub zenith_distance: Degrees,
+
+    /// Apparent nadir distance, i.e. 90 degrees plus the apparent altitude,
+    /// in degrees [0, 180]
+    pub nadir_distance: Degrees,
+
+    /// Relative airmass along the line of sight, 1 at the zenith, see
+    /// coordinates::airmass_kasten_young. None if the Moon is below the horizon
+    pub airmass: Option<f64>,
+
+    /// Radius of the cone around azimuth and altitude the Moon's center is
+    /// expected in, in degrees
+    pub uncertainty: Degrees,
+}
+
+/// Right ascension in hours, all other angles in degrees, on one line
+impl std::fmt::Display for Topocentric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "RA {}, decl. {}, hour angle {}, az. {}, alt. {} (true {}) ± {}",
+            self.right_ascension.to_hms_str(1),
+            self.declination,
+            self.hour_angle.to_hms_str(1),
+            self.azimuth,
+            self.apparent_altitude,
+            self.true_altitude,
+            self.uncertainty
+        )?;
+        match self.airmass {
+            Some(airmass) => write!(f, ", airmass {airmass:.2}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Estimate the angular uncertainty of the Moon's horizontal coordinates.
+/// Combines the truncation error of the series with the error of the
+/// refraction model, which dominates near the horizon.
+/// In:
+/// refraction: correction applied to the true altitude, in degrees
+/// Out:
+/// Radius of the uncertainty cone, in degrees
+fn uncertainty(refraction: Degrees) -> Degrees {
+    let series = Degrees::from(ArcSec::new(SERIES_UNCERTAINTY)).0;
+    let refraction = REFRACTION_RELATIVE_UNCERTAINTY * refraction.0;
+    Degrees::new((series * series + refraction * refraction).sqrt())
+}
+
+/// Every intermediate of the moon's topocentric position, from the
+/// geocentric series of Meeus, chapter 47, to the altitude including the
+/// atmospheric refraction, e.g. for debugging and teaching
+#[derive(Debug, Copy, Clone)]
+pub struct FullMoonState {
+    /// Julian day, in dynamical time
+    pub jd: JD,
+
+    /// Apparent geocentric ecliptical and equatorial coordinates and
+    /// distance
+    pub geocentric: GeocentricMoon,
+
+    /// Equatorial horizontal parallax, in arcsec, Meeus, chapter 47
+    pub horizontal_parallax: ArcSec,
+
+    /// Apparent siderial time at Greenwich, in degrees [0, 360)
+    pub siderial_time: Degrees,
+
+    /// Apparent local siderial time, in degrees [0, 360)
+    pub local_siderial_time: Degrees,
+
+    /// Topocentric right ascension, corrected for the parallax, Meeus,
+    /// chapter 40, in degrees [0, 360)
+    pub topocentric_ra: RightAscension,
+
+    /// Topocentric declination, in degrees [-90, 90)
+    pub topocentric_dec: Declination,
+
+    /// Local hour angle of the topocentric right ascension, in degrees
+    /// [0, 360)
+    pub hour_angle: Degrees,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: Degrees,
+
+    /// True altitude, i.e. without atmospheric refraction, in degrees [-90, 90)
+    pub true_altitude: Degrees,
+
+    /// Apparent altitude, i.e. corrected for atmospheric refraction, in
+    /// degrees [-90, 90)
+    pub apparent_altitude: Degrees,
+
+    /// Atmospheric refraction, the apparent minus the true altitude, in
+    /// degrees
+    pub refraction: Degrees,
+}
+
+/// One intermediate per line, in the order they are calculated in
+impl std::fmt::Display for FullMoonState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "geocentric: long. {}, lat. {}, dist. {:.1} km",
+            self.geocentric.lambda, self.geocentric.beta, self.geocentric.delta
+        )?;
+        writeln!(
+            f,
+            "geocentric: RA {}, decl. {}",
+            self.geocentric.ra.to_hms_str(1),
+            self.geocentric.dec
+        )?;
+        writeln!(
+            f,
+            "horizontal parallax: {:.2}\"",
+            self.horizontal_parallax.0
+        )?;
+        writeln!(
+            f,
+            "siderial time: Greenwich {}, local {}",
+            self.siderial_time.to_hms_str(1),
+            self.local_siderial_time.to_hms_str(1)
+        )?;
+        writeln!(
+            f,
+            "topocentric: RA {}, decl. {}, hour angle {}",
+            self.topocentric_ra.to_hms_str(1),
+            self.topocentric_dec,
+            self.hour_angle.to_hms_str(1)
+        )?;
+        write!(
+            f,
+            "horizontal: az. {}, alt. {} (true {}, refraction {})",
+            self.azimuth, self.apparent_altitude, self.true_altitude, self.refraction
+        )
+    }
+}
+
+/// Calculate the moon's topocentric position with every intermediate, for
+/// an observer at sea level
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Geocentric and topocentric coordinates, see FullMoonState
+pub fn full_topocentric(jd: JD, observer: &Observer) -> FullMoonState {
+    full_topocentric_with_height(jd, observer, 0.0)
+}
+
+/// Calculate the moon's topocentric position with every intermediate for an
+/// observer who moves, e.g. on a ship, at the observer's position at jd
+/// In:
+/// jd: Julian day in dynamical time
+/// track: Observer's position over time, see track::ObserverTrack
+/// Out:
+/// Geocentric and topocentric coordinates, see FullMoonState
+pub fn full_topocentric_along<T: ObserverTrack>(jd: JD, track: &T) -> FullMoonState {
+    // SS: tracks are in UTC, like the fixes they come from
+    let utc = time::tt_2_utc(jd, &LeapSeconds::iers());
+    full_topocentric(jd, &track.observer(utc))
+}
+
+/// Calculate the moon's topocentric position with every intermediate
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// height: observer's height above sea level, in meters
+/// Out:
+/// Geocentric and topocentric coordinates
+fn full_topocentric_with_height(jd: JD, observer: &Observer, height: f64) -> FullMoonState {
+    let moon = geocentric(jd);
+
+    let (ra_topocentric, decl_topocentric) = coordinates::equatorial_2_topocentric(
+        moon.ra,
+        moon.dec,
+        observer.longitude,
+        observer.latitude,
+        height,
+        moon.delta,
+        jd,
+    );
+
+    let siderial_time_apparent_greenwich = earth::apparent_siderial_time(jd);
+    let siderial_time_local =
+        earth::local_siderial_time(siderial_time_apparent_greenwich, observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time_local, ra_topocentric);
+    let (azimuth, true_altitude) =
+        coordinates::equatorial_2_horizontal(decl_topocentric, hour_angle, observer.latitude);
+
+    // SS: add correction for atmospheric refraction
+    let apparent_altitude = refraction::correct_altitude(
+        true_altitude,
+        Refraction::TrueToApparent,
+        observer.pressure,
+        observer.temperature,
+    );
+
+    FullMoonState {
+        jd,
+        geocentric: moon,
+        horizontal_parallax: parallax::horizontal_parallax_from_distance(moon.delta),
+        siderial_time: siderial_time_apparent_greenwich,
+        local_siderial_time: siderial_time_local,
+        topocentric_ra: ra_topocentric,
+        topocentric_dec: decl_topocentric,
+        hour_angle,
+        azimuth,
+        true_altitude,
+        apparent_altitude,
+        refraction: apparent_altitude - true_altitude,
+    }
+}
+
+/// Calculate the moon's topocentric position, corrected for the observer's
+/// height and for atmospheric refraction at the observer's pressure and
+/// temperature.
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// height: observer's height above sea level, in meters
+/// Out:
+/// Topocentric position
+pub fn topocentric_coordinates(jd: JD, observer: &Observer, height: f64) -> Topocentric {
+    let state = full_topocentric_with_height(jd, observer, height);
+    let apparent_altitude = state.apparent_altitude;
+
+    Topocentric {
+        right_ascension: state.topocentric_ra,
+        declination: state.topocentric_dec,
+        hour_angle: state.hour_angle,
+        azimuth: state.azimuth,
+        true_altitude: state.true_altitude,
+        apparent_altitude,
+        zenith_distance: coordinates::zenith_distance(apparent_altitude),
+        nadir_distance: coordinates::nadir_distance(apparent_altitude),
+        airmass: coordinates::airmass_kasten_young(apparent_altitude),
+        uncertainty: uncertainty(state.refraction),
+    }
+}
+
+/// Calculate the moon's topocentric ecliptical coordinates, i.e. the
+/// topocentric equatorial coordinates referred to the ecliptic, as reported
+/// by planetarium software. Refraction is not included.
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location
+/// height: observer's height above sea level, in meters
+/// equinox: Equinox and ecliptic the coordinates are referred to, see
+/// ecliptical_coordinates
+/// Out:
+/// longitude, in degrees [0, 360)
+/// latitude, in degrees [-90, 90)
+pub fn topocentric_ecliptic(
+    jd: JD,
+    observer: &Observer,
+    height: f64,
+    equinox: Equinox,
+) -> (Degrees, Degrees) {
+    let moon = geocentric(jd);
+
+    let (ra_topocentric, decl_topocentric) = coordinates::equatorial_2_topocentric(
+        moon.ra,
+        moon.dec,
+        observer.longitude,
+        observer.latitude,
+        height,
+        moon.delta,
+        jd,
+    );
+
+    let eps = ecliptic::true_obliquity(jd);
+    let (longitude, latitude) =
+        coordinates::equatorial_2_ecliptical(ra_topocentric, decl_topocentric, eps);
+    precession::apparent_ecliptical_2_equinox(longitude, latitude, jd, equinox)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::date::date::Date;
-    use crate::{coordinates, earth, ecliptic, refraction};
+    use crate::{coordinates, earth, ecliptic, moon, refraction};
     use assert_approx_eq::assert_approx_eq;
 
     #[test]
@@ -268,7 +920,7 @@ mod tests {
         let jd = JD::from_date(Date::new(1992, 4, 12.0));
 
         // Act
-        let latitude = geocentric_latitude(jd);
+        let latitude = geocentric(jd).beta;
 
         // Assert
         assert_approx_eq!(-3.229126, latitude.0, 0.000_001)
@@ -286,6 +938,91 @@ mod tests {
         assert_approx_eq!(368_409.7, distance, 0.1)
     }
 
+    #[test]
+    fn geocentric_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let moon = geocentric(jd);
+
+        // Assert
+
+        // SS: the same values as from the separate calls
+        let (ra, decl) = equatorial_coordinates(jd, Equinox::OfDate);
+        assert_eq!(geocentric_longitude(jd).0, moon.lambda.0);
+        assert_eq!(distance_from_earth(jd), moon.delta);
+        assert_eq!(ra.0, moon.ra.0);
+        assert_eq!(decl.0, moon.dec.0);
+
+        // SS: Meeus, example 47.a
+        assert_approx_eq!(133.167265, moon.lambda.0, 0.000_01);
+        assert_approx_eq!(-3.229126, moon.beta.0, 0.000_001);
+        assert_approx_eq!(368_409.7, moon.delta, 0.1);
+        assert_approx_eq!(134.688470, moon.ra.0, 0.000_1);
+        assert_approx_eq!(13.768368, moon.dec.0, 0.000_1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "high-precision-moon"))]
+    fn largest_terms_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let longitude = geocentric_longitude(jd);
+        let latitude = geocentric(jd).beta;
+        let distance = distance_from_earth(jd);
+
+        // Assert
+
+        // SS: Meeus, example 47.a, to the accuracy of the truncated series
+        assert_approx_eq!(133.16726428105474, longitude.0, 0.05);
+        assert_approx_eq!(-3.229126, latitude.0, 0.05);
+        assert_approx_eq!(368_409.7, distance, 50.0);
+    }
+
+    #[test]
+    fn equatorial_coordinates_of_date_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let (ra, decl) = equatorial_coordinates(jd, Equinox::OfDate);
+
+        // Assert
+        assert_approx_eq!(134.688470, ra.0, 0.000_1);
+        assert_approx_eq!(13.768368, decl.0, 0.000_1);
+    }
+
+    #[test]
+    fn equatorial_coordinates_j2000_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let (ra_of_date, decl_of_date) = equatorial_coordinates(jd, Equinox::OfDate);
+        let (ra, decl) = equatorial_coordinates(jd, Equinox::J2000);
+
+        // Assert
+
+        // SS: general precession is about 50" per year, i.e. ~0.1 deg in 7.7 years
+        assert!((ra_of_date.0 - ra.0).abs() > 0.05);
+        assert!((ra_of_date.0 - ra.0).abs() < 0.2);
+
+        let (ra_back, decl_back) =
+            precession::precess_equatorial(ra, decl, crate::date::epoch::Epoch::J2000.jd(), jd);
+        let nutation_delta = Degrees::from(nutation::nutation_in_longitude(jd));
+        let (ra_mean, decl_mean) = coordinates::ecliptical_2_equatorial(
+            geocentric_longitude(jd) - nutation_delta,
+            geocentric(jd).beta,
+            ecliptic::mean_obliquity(jd),
+        );
+        assert_approx_eq!(ra_mean.0, ra_back.0, 0.000_001);
+        assert_approx_eq!(decl_mean.0, decl_back.0, 0.000_001);
+        assert_approx_eq!(decl_of_date.0, decl.0, 0.1);
+    }
+
     #[test]
     fn equatorial_2_topocentric_moon_test_1() {
         // Act
@@ -298,7 +1035,7 @@ mod tests {
 
         // SS: ecliptical geocentric coordinates of the moon
         let longitude = geocentric_longitude(jd);
-        let latitude = geocentric_latitude(jd);
+        let latitude = geocentric(jd).beta;
 
         // SS: equatorial geocentric coordinates of the moon
         let eps = ecliptic::true_obliquity(jd);
@@ -321,22 +1058,82 @@ mod tests {
         let siderial_time_local =
             earth::local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
         let hour_angle = earth::hour_angle(siderial_time_local, ra_topocentric_moon);
-        let (azimuth, mut altitude) = coordinates::equatorial_2_horizontal(
+        let (azimuth, altitude) = coordinates::equatorial_2_horizontal(
             decl_topocentric_moon,
             hour_angle,
             latitude_observer,
         );
 
         // SS: add correction for atmospheric refraction
-        let refraction_correction =
-            refraction::refraction_for_true_altitude(altitude, 1013.0, 10.0);
-        altitude += refraction_correction;
+        let altitude =
+            refraction::correct_altitude(altitude, Refraction::TrueToApparent, 1013.0, 10.0);
 
         // Assert
         assert_approx_eq!(180.0 + 108.74082230643148, azimuth.0, 0.02);
         assert_approx_eq!(-5.7132731871712839, altitude.0, 0.001);
     }
 
+    #[test]
+    fn equatorial_coordinates_fixed_test() {
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let fixed = equatorial_coordinates_fixed(jd, Equinox::OfDate, Rounding::HalfEven);
+
+        // Assert
+        let (ra, decl) = equatorial_coordinates(jd, Equinox::OfDate);
+        assert_approx_eq!(ra.0 * 3_600_000.0, fixed.ra as f64, 0.5);
+        assert_approx_eq!(decl.0 * 3_600_000.0, fixed.decl as f64, 0.5);
+    }
+
+    #[test]
+    fn horizontal_coordinates_test() {
+        // Arrange
+        let jd = JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0));
+
+        // SS: Mount Palomar, the observer's height has negligible effect here
+        let longitude_observer = Degrees::from_hms(7, 47, 27.0);
+        let latitude_observer = Degrees::from_dms(33, 21, 22.0);
+
+        // Act
+        let (azimuth, altitude) = horizontal_coordinates(jd, longitude_observer, latitude_observer);
+
+        // Assert
+        let apparent_altitude =
+            refraction::correct_altitude(altitude, Refraction::TrueToApparent, 1013.0, 10.0);
+        assert_approx_eq!(180.0 + 108.74082230643148, azimuth.0, 0.02);
+        assert_approx_eq!(-5.713_273_187_171_284, apparent_altitude.0, 0.002);
+    }
+
+    #[test]
+    fn horizontal_coordinates_transit_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.5));
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+        let transit = match moon::rise_set_transit::transit(
+            jd,
+            0,
+            Degrees::new(0.0),
+            longitude_observer,
+            latitude_observer,
+        ) {
+            moon::rise_set_transit::OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        };
+
+        // Act
+        let (azimuth, _) = horizontal_coordinates(transit, longitude_observer, latitude_observer);
+
+        // Assert
+
+        // SS: at transit, the Moon is due South
+        assert_approx_eq!(180.0, azimuth.0, 0.5);
+    }
+
     #[test]
     fn equatorial_2_topocentric_moon_test_2() {
         // Act
@@ -351,7 +1148,7 @@ mod tests {
 
         // SS: ecliptical geocentric coordinates of the moon
         let longitude = geocentric_longitude(jd);
-        let latitude = geocentric_latitude(jd);
+        let latitude = geocentric(jd).beta;
 
         // SS: equatorial geocentric coordinates of the moon
         let eps = ecliptic::true_obliquity(jd);
@@ -374,19 +1171,278 @@ mod tests {
         let siderial_time_local =
             earth::local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
         let hour_angle = earth::hour_angle(siderial_time_local, ra_topocentric_moon);
-        let (azimuth, mut altitude) = coordinates::equatorial_2_horizontal(
+        let (azimuth, altitude) = coordinates::equatorial_2_horizontal(
             decl_topocentric_moon,
             hour_angle,
             latitude_observer,
         );
 
         // SS: add correction for atmospheric refraction
-        let refraction_correction =
-            refraction::refraction_for_true_altitude(altitude, 1013.0, 10.0);
-        altitude += refraction_correction;
+        let altitude =
+            refraction::correct_altitude(altitude, Refraction::TrueToApparent, 1013.0, 10.0);
 
         // Assert
         assert_approx_eq!((180.0 + 303.5642283477215) % 360.0, azimuth.0, 0.001);
         assert_approx_eq!(1.6965870451518825, altitude.0, 0.001);
     }
+
+    #[test]
+    fn topocentric_coordinates_test() {
+        // Arrange
+
+        // SS: Sunday, Jan. 30th 2022, 1:55:57PM UTC
+        let jd = JD::new(2_459_610.080526);
+
+        // SS: Mount Palomar
+        let observer = Observer::new(
+            Degrees::from_hms(7, 47, 27.0).0,
+            Degrees::from_dms(33, 21, 22.0).0,
+            0,
+            1013.0,
+            10.0,
+        );
+        let palomar_height_above_sea = 1706.0;
+
+        // Act
+        let topocentric = topocentric_coordinates(jd, &observer, palomar_height_above_sea);
+
+        // Assert
+        assert_approx_eq!(
+            (180.0 + 303.5642283477215) % 360.0,
+            topocentric.azimuth.0,
+            0.001
+        );
+        assert_approx_eq!(1.6965870451518825, topocentric.apparent_altitude.0, 0.001);
+
+        // SS: close to the horizon, the light passes through about 21 times
+        // as much air as from the zenith
+        assert_approx_eq!(
+            90.0 - topocentric.apparent_altitude.0,
+            topocentric.zenith_distance.0,
+            0.000_001
+        );
+        assert_approx_eq!(21.2, topocentric.airmass.unwrap(), 0.1);
+    }
+
+    #[test]
+    fn topocentric_display_test() {
+        // Arrange
+        let topocentric = Topocentric {
+            right_ascension: RightAscension::from_hms(8, 58, 44.1),
+            declination: Declination::new(Degrees::new(13.769657226951539)),
+            hour_angle: Degrees::new(64.352133),
+            azimuth: Degrees::new(123.5),
+            true_altitude: Degrees::new(-0.5),
+            apparent_altitude: Degrees::new(-0.25),
+            zenith_distance: Degrees::new(90.25),
+            nadir_distance: Degrees::new(89.75),
+            airmass: None,
+            uncertainty: Degrees::new(0.01),
+        };
+
+        // Act
+        let s = topocentric.to_string();
+
+        // Assert
+        assert_eq!(
+            "RA 8h 58m 44.1s, decl. 13° 46' 10.8\", hour angle 4h 17m 24.5s, az. 123° 30' 00.0\", alt. -0° 15' 00.0\" (true -0° 30' 00.0\") ± 0° 00' 36.0\"",
+            s
+        );
+    }
+
+    #[test]
+    fn full_topocentric_along_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2024, 3, 20.0));
+        let start = Observer::new(30.0, 40.0, -2, 1013.0, 15.0);
+        let ship = |utc: JD| {
+            let hours = (utc.jd - jd.jd) * 24.0;
+            Observer::new(30.0 - hours, 40.0, -2, 1013.0, 15.0)
+        };
+
+        // Act
+        let state = full_topocentric_along(jd, &ship);
+        let stationary = full_topocentric_along(jd, &start);
+
+        // Assert
+        let utc = time::tt_2_utc(jd, &LeapSeconds::iers());
+        let expected = full_topocentric(jd, &ship(utc));
+        assert_eq!(expected.azimuth.0, state.azimuth.0);
+        assert_eq!(expected.apparent_altitude.0, state.apparent_altitude.0);
+        assert_eq!(
+            full_topocentric(jd, &start).apparent_altitude.0,
+            stationary.apparent_altitude.0
+        );
+    }
+
+    #[test]
+    fn full_topocentric_test() {
+        // Arrange
+
+        // SS: Meeus, example 47.a, 1992 April 12, 0h TD, Munich
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let state = full_topocentric(jd, &observer);
+
+        // Assert
+        let moon = geocentric(jd);
+        assert_eq!(moon.lambda.0, state.geocentric.lambda.0);
+        assert_eq!(moon.ra.0, state.geocentric.ra.0);
+
+        // SS: Meeus, example 47.a, the parallax is 0.991990 degrees. The
+        // arcsine of Meeus, page 337, is replaced by its argument, which
+        // makes it 0.18" smaller
+        assert_approx_eq!(0.991_990, state.horizontal_parallax.0 / 3600.0, 0.000_06);
+
+        // SS: each step follows from the previous ones
+        assert_approx_eq!(
+            state.siderial_time.0 - observer.longitude.0,
+            state.local_siderial_time.0,
+            1e-9
+        );
+        assert_approx_eq!(
+            0.0,
+            state
+                .hour_angle
+                .signed_difference(state.local_siderial_time - Degrees::new(state.topocentric_ra.0))
+                .0,
+            1e-9
+        );
+        assert_approx_eq!(
+            state.apparent_altitude.0 - state.true_altitude.0,
+            state.refraction.0,
+            1e-12
+        );
+
+        // SS: the parallax lowers the Moon in the sky, the topocentric
+        // declination is smaller than the geocentric one in the north
+        assert!(state.topocentric_dec.0 < state.geocentric.dec.0);
+
+        let topocentric = topocentric_coordinates(jd, &observer, 0.0);
+        assert_eq!(topocentric.azimuth.0, state.azimuth.0);
+        assert_eq!(topocentric.apparent_altitude.0, state.apparent_altitude.0);
+        assert_eq!(topocentric.hour_angle.0, state.hour_angle.0);
+    }
+
+    #[test]
+    fn full_topocentric_display_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let s = full_topocentric(jd, &observer).to_string();
+
+        // Assert
+        let lines: Vec<&str> = s.lines().collect();
+        assert_eq!(6, lines.len());
+        assert!(
+            lines[0].starts_with("geocentric: long. 133° 10' 0"),
+            "{}",
+            lines[0]
+        );
+        assert!(
+            lines[2].starts_with("horizontal parallax: 3570.99"),
+            "{}",
+            lines[2]
+        );
+    }
+
+    #[test]
+    fn uncertainty_test() {
+        // Arrange
+        let horizon = refraction::refraction_for_true_altitude(Degrees::new(0.0), 1013.0, 10.0);
+        let zenith = Degrees::new(0.0);
+
+        // Act
+        let uncertainty_horizon = uncertainty(horizon);
+        let uncertainty_zenith = uncertainty(zenith);
+
+        // Assert
+
+        // SS: at the horizon, the refraction model dominates, in the zenith
+        // the truncation of the series
+        assert_approx_eq!(0.048_5, uncertainty_horizon.0, 0.000_1);
+        assert_approx_eq!(10.8 / 3600.0, uncertainty_zenith.0, 0.000_1);
+    }
+
+    #[test]
+    fn ecliptical_coordinates_test() {
+        // SS: Meeus, example 47.a, 1992 April 12, 0h TD
+
+        // Arrange
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let (lambda, beta) = ecliptical_coordinates(jd, Equinox::OfDate);
+        let (lambda_j2000, beta_j2000) = ecliptical_coordinates(jd, Equinox::J2000);
+
+        // Assert
+        assert_approx_eq!(133.167265, lambda.0, 0.000_01);
+        assert_approx_eq!(-3.229126, beta.0, 0.000_001);
+
+        // SS: without nutation in longitude, 133.162655 deg, and precessed
+        // by 50.29" per year for 7.72 years
+        assert_approx_eq!(133.162655 + 50.29 * 7.72 / 3600.0, lambda_j2000.0, 0.000_5);
+
+        // SS: the ecliptic itself moves by about 0.47" per year
+        assert_approx_eq!(-3.229126, beta_j2000.0, 0.47 * 7.72 / 3600.0);
+    }
+
+    #[test]
+    fn topocentric_ecliptic_test() {
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD, Munich
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+        let height = 500.0;
+
+        // Act
+        let (lambda, beta) = topocentric_ecliptic(jd, &observer, height, Equinox::OfDate);
+
+        // Assert
+
+        // SS: Meeus, page 282, eq (40.6), topocentric ecliptical coordinates
+        // directly from the geocentric ones
+        let lambda_geocentric = Radians::from(geocentric_longitude(jd));
+        let beta_geocentric = Radians::from(geocentric(jd).beta);
+        let eps = Radians::from(ecliptic::true_obliquity(jd));
+        let (rho_sin_p, rho_cos_p) = crate::parallax::rho_phi_prime(observer.latitude, height);
+        let sin_pi = math::sin(Radians::from(Degrees::from(ArcSec::new(8.794))).0)
+            / (distance_from_earth(jd) / crate::constants::AU);
+        let theta = Radians::from(earth::local_siderial_time(
+            earth::apparent_siderial_time(jd),
+            observer.longitude,
+        ));
+
+        let n = math::cos(lambda_geocentric.0) * math::cos(beta_geocentric.0)
+            - rho_cos_p * sin_pi * math::cos(theta.0);
+        let expected_lambda = math::atan2(
+            math::sin(lambda_geocentric.0) * math::cos(beta_geocentric.0)
+                - sin_pi
+                    * (rho_sin_p * math::sin(eps.0)
+                        + rho_cos_p * math::cos(eps.0) * math::sin(theta.0)),
+            n,
+        );
+        let expected_beta = math::atan(
+            math::cos(expected_lambda)
+                * (math::sin(beta_geocentric.0)
+                    - sin_pi
+                        * (rho_sin_p * math::cos(eps.0)
+                            - rho_cos_p * math::sin(eps.0) * math::sin(theta.0)))
+                / n,
+        );
+
+        let expected_lambda = Degrees::from(Radians::new(expected_lambda)).map_to_0_to_360();
+        let expected_beta = Degrees::from(Radians::new(expected_beta));
+        assert_approx_eq!(expected_lambda.0, lambda.0, 0.000_01);
+        assert_approx_eq!(expected_beta.0, beta.0, 0.000_01);
+
+        // SS: the parallax shifts the Moon by up to about 1 deg
+        assert!((lambda.0 - Degrees::from(lambda_geocentric).0).abs() < 1.1);
+        assert!((beta.0 - Degrees::from(beta_geocentric).0).abs() < 1.1);
+    }
 }
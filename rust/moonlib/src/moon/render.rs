@@ -0,0 +1,185 @@
+//! Everything needed to draw the moon as seen by an observer: its phase,
+//! orientation, size and libration, all from one evaluation of the moon's
+//! and the sun's positions
+use crate::date::jd::JD;
+use crate::moon::libration::{self, Libration};
+use crate::moon::{phase, semidiameter};
+use crate::session::Observer;
+use crate::sun::position::apparent_position;
+use crate::util::{degrees::Degrees, math, radians::Radians};
+use crate::{constants, coordinates, earth, ecliptic, moon};
+
+/// Parameters for drawing the moon's disk. The angles are topocentric, i.e.
+/// corrected for the moon's parallax, so they can differ by about 1 degree
+/// from the geocentric ones of moon::phase
+#[derive(Debug, Copy, Clone)]
+pub struct RenderParams {
+    /// Phase angle, in degrees [0, 360)
+    pub phase_angle: Degrees,
+
+    /// Illuminated fraction of the disk, [0, 1]
+    pub illuminated_fraction: f64,
+
+    /// Position angle of the bright limb, measured from the North Point of
+    /// the disk towards the East, in degrees [0, 360)
+    pub bright_limb_angle: Degrees,
+
+    /// Parallactic angle, i.e. the angle from the North Point to the
+    /// direction to the zenith, in degrees [-180, 180)
+    pub parallactic_angle: Degrees,
+
+    /// Direction of the bright limb, measured counterclockwise from the
+    /// direction to the zenith, in degrees [0, 360), see moon::phase::crescent_tilt
+    pub crescent_tilt: Degrees,
+
+    /// Direction of the northern end of the moon's axis, measured
+    /// counterclockwise from the direction to the zenith, in degrees
+    /// [0, 360), see moon::libration::axis_orientation
+    pub axis_tilt: Degrees,
+
+    /// Apparent diameter of the disk, in degrees
+    pub apparent_diameter: Degrees,
+
+    /// Topocentric libration and position angle of the moon's axis
+    pub libration: Libration,
+}
+
+/// Calculate the parameters for drawing the moon for an observer at sea level
+/// In:
+/// jd: Julian day, in dynamical time
+/// observer: Observer's location
+/// Out: Render parameters
+pub fn render_params(jd: JD, observer: &Observer) -> RenderParams {
+    let moon = moon::geocentric(jd);
+
+    let sun = apparent_position(jd);
+    let eps = ecliptic::true_obliquity(jd);
+    let (ra_sun, decl_sun) = coordinates::ecliptical_2_equatorial(sun.longitude, sun.latitude, eps);
+
+    let siderial_time =
+        earth::local_siderial_time(earth::apparent_siderial_time(jd), observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time, moon.ra);
+
+    // SS: the moon as seen by the observer. The sun's parallax is less than 9"
+    let (ra, decl) = coordinates::equatorial_2_topocentric(
+        moon.ra,
+        moon.dec,
+        observer.longitude,
+        observer.latitude,
+        0.0,
+        moon.delta,
+        jd,
+    );
+    let hour_angle_topocentric = earth::hour_angle(siderial_time, ra);
+
+    let phase_angle = phase::phase_angle_of(
+        (ra, decl, moon.delta),
+        (ra_sun, decl_sun, sun.distance * constants::AU),
+    );
+    let illuminated_fraction = (1.0 + math::cos(Radians::from(phase_angle).0)) / 2.0;
+
+    let bright_limb_angle = phase::bright_limb((ra_sun, decl_sun), ra, decl);
+    let parallactic_angle =
+        coordinates::parallactic_angle(hour_angle_topocentric, decl, observer.latitude);
+
+    let semidiameter =
+        semidiameter::topocentric_semidiameter(jd, hour_angle, moon.dec, observer.latitude, 0.0);
+
+    let libration = libration::topocentric_correction(
+        libration::geocentric_libration(jd, &moon),
+        &moon,
+        hour_angle,
+        observer.latitude,
+    );
+
+    RenderParams {
+        phase_angle,
+        illuminated_fraction,
+        bright_limb_angle,
+        parallactic_angle,
+        crescent_tilt: (bright_limb_angle - parallactic_angle).map_to_0_to_360(),
+        axis_tilt: (libration.position_angle - parallactic_angle).map_to_0_to_360(),
+        apparent_diameter: Degrees::from(semidiameter) * 2.0,
+        libration,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn render_params_test() {
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD, Munich
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+        // Act
+        let params = render_params(jd, &observer);
+
+        // Assert
+
+        // SS: the geocentric values of Meeus, examples 48.a and 53.a. The
+        // parallax shifts the moon by less than 1 degree
+        assert_approx_eq!(0.6786, params.illuminated_fraction, 0.01);
+        assert_approx_eq!(285.0, params.bright_limb_angle.0, 1.0);
+        assert_approx_eq!(-1.23, params.libration.longitude.0, 1.0);
+        assert_approx_eq!(4.20, params.libration.latitude.0, 1.0);
+        assert_approx_eq!(15.08, params.libration.position_angle.0, 1.0);
+
+        let geocentric_diameter = 2.0 * Degrees::from(semidiameter::geocentric_semidiameter(jd)).0;
+        assert_approx_eq!(geocentric_diameter, params.apparent_diameter.0, 0.01);
+
+        // SS: all angles from the same positions
+        assert_approx_eq!(
+            (params.bright_limb_angle - params.parallactic_angle)
+                .map_to_0_to_360()
+                .0,
+            params.crescent_tilt.0,
+            1e-12
+        );
+        let orientation = libration::axis_orientation(jd, &observer);
+        assert_approx_eq!(orientation.tilt.0, params.axis_tilt.0, 1e-9);
+        assert_approx_eq!(
+            (1.0 + math::cos(Radians::from(params.phase_angle).0)) / 2.0,
+            params.illuminated_fraction,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn render_params_geocentric_test() {
+        // Arrange
+
+        // SS: the moon in the zenith, where the parallax vanishes
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+        let moon = moon::geocentric(jd);
+        let siderial_time = earth::apparent_siderial_time(jd);
+        // SS: the longitude is positive west of Greenwich, where the local
+        // siderial time is smaller
+        let longitude = (siderial_time - moon.ra.degrees()).map_neg180_to_180();
+        let observer = Observer::new(longitude.0, moon.dec.0, 0, 1013.0, 10.0);
+
+        // Act
+        let params = render_params(jd, &observer);
+
+        // Assert
+        let geocentric = libration::libration(jd);
+        assert_approx_eq!(
+            phase::fraction_illuminated(jd),
+            params.illuminated_fraction,
+            0.000_1
+        );
+        assert_approx_eq!(
+            phase::bright_limb_position_angle(jd).0,
+            params.bright_limb_angle.0,
+            0.01
+        );
+        assert_approx_eq!(geocentric.longitude.0, params.libration.longitude.0, 0.01);
+        assert_approx_eq!(geocentric.latitude.0, params.libration.latitude.0, 0.01);
+    }
+}
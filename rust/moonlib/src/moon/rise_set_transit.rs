@@ -1,25 +1,21 @@
 //! Calculate rise, set and transit times for the moon
 
-use crate::date::date::Date;
+use crate::config::LimbConvention;
 use crate::date::jd::JD;
-use crate::moon::position::{geocentric_latitude, geocentric_longitude};
-use crate::refraction::refraction_for_true_altitude;
+use crate::refraction::{self, Refraction};
+use crate::rise_set_transit::{
+    calculate_rise_set_transit, calculate_rise_set_transit_geometry, interpolate_rise_set,
+    search_rise_set_transit, InputKind, Search,
+};
+use crate::session::Observer;
 use crate::util::arcsec::ArcSec;
+use crate::util::declination::Declination;
 use crate::util::degrees::Degrees;
 use crate::util::radians::Radians;
-use crate::{constants, coordinates, earth, ecliptic, moon};
+use crate::util::right_ascension::RightAscension;
+use crate::{earth, moon};
 
-pub(crate) enum OutputKind {
-    Time(JD),
-    NeverRises,
-    NeverSets,
-}
-
-enum InputKind {
-    Rise,
-    Set,
-    Transit,
-}
+pub use crate::rise_set_transit::{OutputKind, RiseSetGeometry};
 
 /// Compute the time the moon rises
 /// In:
@@ -42,6 +38,7 @@ pub(crate) fn rise(
         target_altitude,
         longitude_observer,
         latitude_observer,
+        equatorial_position,
     )
 }
 
@@ -66,6 +63,7 @@ pub(crate) fn set(
         target_altitude,
         longitude_observer,
         latitude_observer,
+        equatorial_position,
     )
 }
 
@@ -90,6 +88,139 @@ pub(crate) fn transit(
         target_altitude,
         longitude_observer,
         latitude_observer,
+        equatorial_position,
+    )
+}
+
+/// Compute the time the moon rises, sets or transits, and the geometry the
+/// iteration ended with
+/// In:
+/// kind: the event to compute
+/// jd: Julian Day to compute the event for
+/// timezone_offset: Observer's time zone offset
+/// target_altitude: altitude of Moon above horizon, in degrees [-90, 90)
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+pub(crate) fn rise_set_transit_geometry(
+    kind: InputKind,
+    jd: JD,
+    timezone_offset: i8,
+    target_altitude: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+) -> (OutputKind, RiseSetGeometry) {
+    calculate_rise_set_transit_geometry(
+        kind,
+        jd,
+        timezone_offset,
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+        equatorial_position,
+    )
+}
+
+/// Find the next time the Moon rises. Unlike rise, the event is not
+/// restricted to the observer's day.
+/// In:
+/// jd: Julian Day to start the search at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Time of the first rise at or after jd, in dynamical time. NeverRises if the
+/// Moon does not rise within 30 days
+pub fn next_rise(jd: JD, observer: &Observer) -> OutputKind {
+    search(InputKind::Rise, jd, Search::Forward, observer)
+}
+
+/// Find the previous time the Moon rose. Unlike rise, the event is not
+/// restricted to the observer's day.
+/// In:
+/// jd: Julian Day to start the search at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Time of the last rise at or before jd, in dynamical time. NeverRises if
+/// the Moon did not rise within 30 days
+pub fn previous_rise(jd: JD, observer: &Observer) -> OutputKind {
+    search(InputKind::Rise, jd, Search::Backward, observer)
+}
+
+/// Find the next time the Moon sets. Unlike set, the event is not
+/// restricted to the observer's day.
+/// In:
+/// jd: Julian Day to start the search at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Time of the first set at or after jd, in dynamical time. NeverSets if the
+/// Moon does not set within 30 days
+pub fn next_set(jd: JD, observer: &Observer) -> OutputKind {
+    search(InputKind::Set, jd, Search::Forward, observer)
+}
+
+/// Find the previous time the Moon set. Unlike set, the event is not
+/// restricted to the observer's day.
+/// In:
+/// jd: Julian Day to start the search at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Time of the last set at or before jd, in dynamical time. NeverSets if the
+/// Moon did not set within 30 days
+pub fn previous_set(jd: JD, observer: &Observer) -> OutputKind {
+    search(InputKind::Set, jd, Search::Backward, observer)
+}
+
+/// Find the next time the Moon transits. Unlike transit, the event is not
+/// restricted to the observer's day.
+/// In:
+/// jd: Julian Day to start the search at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Time of the first transit at or after jd, in dynamical time
+pub fn next_transit(jd: JD, observer: &Observer) -> OutputKind {
+    search(InputKind::Transit, jd, Search::Forward, observer)
+}
+
+/// Compute moonrise and moonset for the observer's day by interpolating the
+/// Moon's altitude, the method of the US Naval Observatory. Slower than rise
+/// and set, it is meant to cross-check them.
+/// In:
+/// jd: Julian Day to compute the events for, in dynamical time
+/// observer: Observer's location, time zone and atmospheric conditions
+/// Out:
+/// (rise, set), in dynamical time, or NeverRises/NeverSets
+pub fn interpolated_rise_set(jd: JD, observer: &Observer) -> (OutputKind, OutputKind) {
+    interpolate_rise_set(
+        jd,
+        observer.timezone_offset,
+        observer_target_altitude(jd, observer),
+        observer.longitude,
+        observer.latitude,
+        equatorial_position,
+    )
+}
+
+fn search(kind: InputKind, jd: JD, search: Search, observer: &Observer) -> OutputKind {
+    search_rise_set_transit(
+        kind,
+        jd,
+        search,
+        observer_target_altitude(jd, observer),
+        observer.longitude,
+        observer.latitude,
+        equatorial_position,
+    )
+}
+
+/// Target altitude of the Moon for an observer
+fn observer_target_altitude(jd: JD, observer: &Observer) -> Degrees {
+    // SS: the Moon's parallax and semidiameter hardly change within a day,
+    // so the target altitude at jd is used for the whole day
+    target_altitude(
+        jd,
+        Degrees::new(0.0),
+        observer.longitude,
+        observer.latitude,
+        observer.pressure,
+        observer.temperature,
     )
 }
 
@@ -102,7 +233,8 @@ pub(crate) fn transit(
 /// (typically 0 deg)
 /// longitude_observer: Observer's longitude, in degrees [-180, 180)
 /// latitude_observer: Observer's latitude, in degrees [-90, 90)
-/// pressure: Atmospheric pressure, in milibars. For atmospheric refraction effect
+/// pressure: Atmospheric pressure, in milibars. For atmospheric refraction effect,
+/// 0 or less for none
 /// temperature: Ait temperature, in celsius. For atmospheric refraction effect
 /// Out:
 /// altitude, geocentric, at which the Moon's upper limb touches the observer's horizon,
@@ -115,142 +247,71 @@ pub(crate) fn target_altitude(
     pressure: f64,
     temperature: f64,
 ) -> Degrees {
-    // SS:Moon's horizontal parallax at 0 deg altitude (i.e. at the horizon)
-    let parallax = moon::parallax::horizontal_parallax(jd, altitude);
-
-    // SS: refraction effects
-    let refraction = ArcSec::from(refraction_for_true_altitude(
+    limb_target_altitude(
+        jd,
         altitude,
+        longitude_observer,
+        latitude_observer,
         pressure,
         temperature,
-    ));
-
-    // SS: Moon's topocentric semidiameter
-    let longitude = geocentric_longitude(jd);
-    let latitude = geocentric_latitude(jd);
-    let eps = ecliptic::true_obliquity(jd);
-    let (ra, decl) = coordinates::ecliptical_2_equatorial(longitude, latitude, eps);
-    let theta0 = earth::apparent_siderial_time(jd);
-    let theta = earth::local_siderial_time(theta0, longitude_observer);
-    let hour_angle = (theta - ra).map_neg180_to_180();
-    let semidiameter =
-        moon::semidiameter::topocentric_semidiameter(jd, hour_angle, decl, latitude_observer, 0.0);
-
-    let target_altitude_radians = Radians::from(parallax - refraction - semidiameter);
-    Degrees::from(target_altitude_radians)
+        LimbConvention::UpperLimb,
+    )
 }
 
-fn calculate_rise_set_transit(
-    kind: InputKind,
+/// Compute the geocentric altitude of the Moon at rise/set, for a choice of
+/// the point of the Moon's disk that rises and sets
+/// In:
+/// jd: Julian Day
+/// altitude: Altitude at which to calculate the horizontal parallax effect for
+/// (typically 0 deg)
+/// longitude_observer: Observer's longitude, in degrees [-180, 180)
+/// latitude_observer: Observer's latitude, in degrees [-90, 90)
+/// pressure: Atmospheric pressure, in milibars, 0 or less for none
+/// temperature: Air temperature, in celsius
+/// limb: Point of the Moon's disk on the horizon
+/// Out:
+/// altitude, geocentric, at which the point is on the observer's horizon,
+/// in degrees [-90, 90). Typically, < 1 deg
+pub(crate) fn limb_target_altitude(
     jd: JD,
-    timezone_offset: i8,
-    target_altitude: Degrees,
+    altitude: Degrees,
     longitude_observer: Degrees,
     latitude_observer: Degrees,
-) -> OutputKind {
-    let latitude_observer_radians = Radians::from(latitude_observer);
-    let sin_latitude_observer = latitude_observer_radians.0.sin();
-    let cos_latitude_observer = latitude_observer_radians.0.cos();
-
-    // SS: bound time based on observer's timezone offset
-    let (jd_min, mut prev_jd, jd_max) = bound_julian_day(jd, timezone_offset);
-
-    let sin_h0 = Radians::from(target_altitude).0.sin();
-
-    // SS: if time change is less than a minute, we are done with iteration
-    let delta_t_threshold = 1.0 / 60.0;
-
-    let mut iter = 0;
-    const MAX_ITER: u8 = 10;
-
-    loop {
-        // SS: ecliptical geocentric coordinates of the moon
-        let longitude = geocentric_longitude(prev_jd);
-        let latitude = geocentric_latitude(prev_jd);
-
-        // SS: equatorial geocentric coordinates of the moon
-        let eps = ecliptic::true_obliquity(prev_jd);
-        let (ra, decl) = coordinates::ecliptical_2_equatorial(longitude, latitude, eps);
-
-        let decl_radians = Radians::from(decl);
-        let sin_decl = decl_radians.0.sin();
-        let cos_decl = decl_radians.0.cos();
-        let cos_hour_angle =
-            (sin_h0 - sin_latitude_observer * sin_decl) / (cos_latitude_observer * cos_decl);
-
-        let hour_angle;
-        if cos_hour_angle < -1.0 {
-            return OutputKind::NeverRises;
-        } else if cos_hour_angle > 1.0 {
-            return OutputKind::NeverSets;
-        } else {
-            hour_angle = Degrees::from(Radians::new(cos_hour_angle.acos()));
-        }
-
-        // SS: calculate the local hour angle for current time
-        let theta0 = earth::apparent_siderial_time(prev_jd);
-        let theta = earth::local_siderial_time(theta0, longitude_observer);
-        let hour_angle2 = (theta - ra).map_neg180_to_180();
-
-        let delta_hour_angle = match kind {
-            InputKind::Rise => (hour_angle2 + hour_angle).map_neg180_to_180(),
-            InputKind::Set => (hour_angle2 - hour_angle).map_neg180_to_180(),
-            InputKind::Transit => hour_angle2,
-        };
-
-        // SS: convert degrees to solar time hours
-        let delta_t = delta_hour_angle.to_hours() * constants::SIDERIAL_TO_SOLAR_TIME;
-
-        // SS: correction step
-        prev_jd.add_hours(-delta_t);
+    pressure: f64,
+    temperature: f64,
+    limb: LimbConvention,
+) -> Degrees {
+    // SS:Moon's horizontal parallax at 0 deg altitude (i.e. at the horizon)
+    let parallax = moon::parallax::horizontal_parallax(jd, altitude);
 
-        if delta_t.abs() < delta_t_threshold || iter > MAX_ITER {
-            break;
-        }
+    // SS: refraction effects. The Moon's upper limb is observed on the horizon,
+    // so the altitude is an apparent one
+    let true_altitude =
+        refraction::correct_altitude(altitude, Refraction::ApparentToTrue, pressure, temperature);
+    let refraction = ArcSec::from(altitude - true_altitude);
 
-        iter += 1;
-    }
+    // SS: Moon's topocentric semidiameter
+    let moon = moon::geocentric(jd);
+    let (ra, decl) = (moon.ra, moon.dec);
+    let theta0 = earth::apparent_siderial_time(jd);
+    let theta = earth::local_siderial_time(theta0, longitude_observer);
+    let hour_angle = theta.signed_difference(ra.degrees());
+    let semidiameter =
+        moon::semidiameter::topocentric_semidiameter(jd, hour_angle, decl, latitude_observer, 0.0);
 
-    // SS: check whether we have the correct day
-    if prev_jd >= jd_min && prev_jd <= jd_max {
-        OutputKind::Time(prev_jd)
-    } else {
-        match kind {
-            InputKind::Rise => OutputKind::NeverRises,
-            InputKind::Set => OutputKind::NeverSets,
-            InputKind::Transit => {
-                unreachable!()
-            }
-        }
-    }
+    let target_altitude_radians =
+        Radians::from(parallax - limb.depression(refraction, semidiameter));
+    Degrees::from(target_altitude_radians)
 }
 
-/// Calculate the min and max Julian Day the event has to be in
-/// to be on the same day as the observer due to local time zone
-/// offsets.
-/// In:
-/// jd: Julian Day to calculate the event for, in UTC
-/// timezone_offset: Observer's time zone offset
-fn bound_julian_day(jd: JD, timezone_offset: i8) -> (JD, JD, JD) {
-    // SS: calculate midday UTC for the event
-    let date = Date::from(jd);
-    let midday = Date::new(date.year, date.month, date.day.trunc() + 0.5);
-    let mut jd_midday = JD::from_date(midday);
-
-    // SS: We return events (rise, set, transit) in UTC, so the caller has to convert
-    // UTC to the local time by adding the timezone offset. Hence, here, we have to
-    // subtract it to shift the 24 hour day window accordingly.
-    jd_midday.add_hours(-timezone_offset as f64);
-
-    // SS: calculate midnight in local time zone expressed in UTC
-    let mut jd_min = jd_midday;
-    jd_min.add_hours(-12.0);
-
-    // SS: calculate midnight in local time zone expressed in UTC
-    let mut jd_max = jd_midday;
-    jd_max.add_hours(12.0);
-
-    (jd_min, jd_midday, jd_max)
+/// Geocentric equatorial coordinates of the Moon
+/// In: Julian Day, in dynamical time
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+pub(crate) fn equatorial_position(jd: JD) -> (RightAscension, Declination) {
+    let moon = moon::geocentric(jd);
+    (moon.ra, moon.dec)
 }
 
 #[cfg(test)]
@@ -261,30 +322,7 @@ mod tests {
     use crate::time;
 
     use super::*;
-
-    #[test]
-    fn timezone_offset_test_1() {
-        // Arrange
-        let date = Date::new(2022, 2, 21.0);
-        let jd = JD::from_date(date);
-        let timezone_offset = 1;
-
-        // Act
-        let (jd_min, jd_midday, jd_max) = bound_julian_day(jd, timezone_offset);
-
-        // Assert
-        let mut min = JD::from_date(date);
-        min.add_hours(-timezone_offset as f64);
-        assert_approx_eq!(min.jd, jd_min.jd, 0.001);
-
-        let mut mid = min;
-        mid.add_hours(12.0);
-        assert_approx_eq!(mid.jd, jd_midday.jd, 0.001);
-
-        let mut max = min;
-        max.add_hours(24.0);
-        assert_approx_eq!(max.jd, jd_max.jd, 0.001);
-    }
+    use crate::test_util::munich;
 
     #[test]
     fn rise_test_1() {
@@ -339,6 +377,10 @@ mod tests {
             OutputKind::NeverSets => {
                 unreachable!()
             }
+
+            OutputKind::NeverTransits => {
+                unreachable!()
+            }
         }
     }
 
@@ -417,6 +459,10 @@ mod tests {
             OutputKind::NeverSets => {
                 unreachable!()
             }
+
+            OutputKind::NeverTransits => {
+                unreachable!()
+            }
         }
     }
 
@@ -474,6 +520,10 @@ mod tests {
             OutputKind::NeverSets => {
                 unreachable!()
             }
+
+            OutputKind::NeverTransits => {
+                unreachable!()
+            }
         }
     }
 
@@ -530,6 +580,10 @@ mod tests {
             OutputKind::NeverSets => {
                 unreachable!()
             }
+
+            OutputKind::NeverTransits => {
+                unreachable!()
+            }
         }
     }
 
@@ -620,6 +674,334 @@ mod tests {
             OutputKind::NeverSets => {
                 unreachable!()
             }
+
+            OutputKind::NeverTransits => {
+                unreachable!()
+            }
+        }
+    }
+
+    #[test]
+    fn no_transit_test() {
+        // Arrange
+
+        // SS: the Moon transits Greenwich on Jan. 14th, 1900 at 23:29 and
+        // next on Jan. 16th at 00:17
+        let jd = JD::from_date(Date::new(1900, 1, 15.0));
+        let longitude_observer = Degrees::new(0.0);
+        let latitude_observer = Degrees::new(51.48);
+
+        // Act
+        let result = transit(
+            jd,
+            0,
+            Degrees::new(0.125),
+            longitude_observer,
+            latitude_observer,
+        );
+
+        // Assert
+        assert!(matches!(result, OutputKind::NeverTransits));
+    }
+
+    #[test]
+    fn circumpolar_transit_test() {
+        // Arrange
+
+        // SS: Tromso, the Moon does not rise on July 15th, 2000, but it
+        // transits below the horizon
+        let jd = JD::from_date(Date::new(2000, 7, 15.0));
+        let longitude_observer = Degrees::new(-18.96);
+        let latitude_observer = Degrees::new(69.65);
+        let target_altitude = Degrees::new(0.125);
+
+        // Act
+        let set = set(
+            jd,
+            0,
+            target_altitude,
+            longitude_observer,
+            latitude_observer,
+        );
+        let transit = transit(
+            jd,
+            0,
+            target_altitude,
+            longitude_observer,
+            latitude_observer,
+        );
+
+        // Assert
+        assert!(matches!(set, OutputKind::NeverRises));
+        match transit {
+            OutputKind::Time(jd) => assert_approx_eq!(2451741.4303, jd.jd, 0.001),
+            _ => unreachable!(),
         }
     }
+
+    fn time(output: OutputKind) -> JD {
+        match output {
+            OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn next_rise_test() {
+        // Arrange
+        let observer = munich(0);
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+
+        // Act
+        let next_rise = time(next_rise(jd, &observer));
+
+        // Assert
+
+        // SS: same as the rise on that day, see rise_test_1
+        let rise_date = Date::from_date_hms(2000, 3, 23, 21, 12, 13.0);
+        assert_approx_eq!(JD::from_date(rise_date).jd, next_rise.jd, 0.001)
+    }
+
+    #[test]
+    fn next_transit_test() {
+        // Arrange
+        let observer = munich(0);
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+
+        // Act
+        let next_transit = time(next_transit(jd, &observer));
+
+        // Assert
+
+        // SS: same as the transit on that day
+        let expected = time(transit(
+            jd,
+            0,
+            Degrees::new(0.125),
+            observer.longitude,
+            observer.latitude,
+        ));
+        assert_approx_eq!(expected.jd, next_transit.jd, 0.001)
+    }
+
+    #[test]
+    fn next_rise_after_rise_test() {
+        // Arrange
+        let observer = munich(0);
+
+        // SS: shortly after the Moon rose on March 23rd, 2000
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 23, 21, 30, 0.0));
+
+        // Act
+        let next_rise = time(next_rise(jd, &observer));
+        let previous_rise = time(previous_rise(jd, &observer));
+
+        // Assert
+
+        // SS: the Moon rises about 50 minutes later each day, at the end of March
+        // a bit more
+        let rise_date = Date::from_date_hms(2000, 3, 23, 21, 12, 13.0);
+        assert_approx_eq!(JD::from_date(rise_date).jd, previous_rise.jd, 0.001);
+        assert!(next_rise > jd);
+        assert_approx_eq!(1.05, next_rise.jd - previous_rise.jd, 0.05);
+    }
+
+    #[test]
+    fn moon_up_test() {
+        // Arrange
+        let observer = munich(0);
+
+        // SS: the Moon is up at midnight after it rose on March 23rd, 2000
+        let jd = JD::from_date(Date::new(2000, 3, 24.0));
+
+        // Act
+        let previous_rise = time(previous_rise(jd, &observer));
+        let previous_set = time(previous_set(jd, &observer));
+        let next_set = time(next_set(jd, &observer));
+        let next_rise = time(next_rise(jd, &observer));
+
+        // Assert
+        assert!(previous_set < previous_rise);
+        assert!(previous_rise < jd);
+        assert!(jd < next_set);
+        assert!(next_set < next_rise);
+    }
+
+    #[test]
+    fn previous_set_test() {
+        // Arrange
+        let observer = munich(0);
+        let date = Date::new(2000, 3, 23.5);
+        let jd = JD::from_date(date);
+        let target_altitude = target_altitude(
+            jd,
+            Degrees::new(0.0),
+            observer.longitude,
+            observer.latitude,
+            observer.pressure,
+            observer.temperature,
+        );
+        let set = time(set(
+            jd,
+            0,
+            target_altitude,
+            observer.longitude,
+            observer.latitude,
+        ));
+
+        // Act
+
+        // SS: search back from the end of the day
+        let previous_set = time(previous_set(
+            JD::from_date(Date::new(2000, 3, 24.0)),
+            &observer,
+        ));
+
+        // Assert
+        assert_approx_eq!(set.jd, previous_set.jd, 0.001);
+    }
+
+    #[test]
+    fn next_rise_circumpolar_test() {
+        // Arrange
+
+        // SS: Tromso, the Moon stays below the horizon on July 15th, 2000
+        let observer = Observer::new(-18.96, 69.65, 0, 1013.0, 10.0);
+        let jd = JD::from_date(Date::new(2000, 7, 15.0));
+
+        // Act
+        let next_rise = time(next_rise(jd, &observer));
+        let previous_set = time(previous_set(jd, &observer));
+
+        // Assert
+
+        // SS: the events are days away
+        assert!(next_rise.jd - jd.jd > 1.0);
+        assert!(jd.jd - previous_set.jd > 1.0);
+    }
+
+    #[test]
+    fn interpolated_rise_set_test() {
+        // Arrange
+        let observer = munich(0);
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+
+        // Act
+        let (rise, _) = interpolated_rise_set(jd, &observer);
+
+        // Assert
+
+        // SS: see rise_test_1
+        let rise_date = Date::from_date_hms(2000, 3, 23, 21, 12, 13.0);
+        assert_approx_eq!(JD::from_date(rise_date).jd, time(rise).jd, 0.001)
+    }
+
+    /// Compare rise and set with interpolated_rise_set across a grid of
+    /// observers and days
+    /// Out: disagreements of more than 2 minutes
+    fn cross_check(latitudes: &[f64]) -> Vec<String> {
+        let tolerance = 2.0 / 1440.0;
+
+        let longitudes = (-3..3).map(|i| i as f64 * 60.0);
+        let days = (0..10).map(|i| JD::from_date(Date::new(2000, 1, 1.0 + i as f64 * 37.0)));
+
+        let mut disagreements = vec![];
+
+        for &latitude in latitudes {
+            for longitude in longitudes.clone() {
+                for jd in days.clone() {
+                    let observer = Observer::new(longitude, latitude, 0, 1013.0, 10.0);
+                    let target_altitude = observer_target_altitude(jd, &observer);
+
+                    let rise = rise(
+                        jd,
+                        0,
+                        target_altitude,
+                        observer.longitude,
+                        observer.latitude,
+                    );
+                    let set = set(
+                        jd,
+                        0,
+                        target_altitude,
+                        observer.longitude,
+                        observer.latitude,
+                    );
+                    let (interpolated_rise, interpolated_set) =
+                        interpolated_rise_set(jd, &observer);
+
+                    let (jd_min, _, jd_max) = crate::rise_set_transit::bound_julian_day(jd, 0);
+                    let near_midnight = |event: JD| {
+                        event.jd - jd_min.jd < tolerance || jd_max.jd - event.jd < tolerance
+                    };
+
+                    for (event, interpolated) in
+                        [(rise, interpolated_rise), (set, interpolated_set)]
+                    {
+                        let agree = match (event, interpolated) {
+                            (OutputKind::Time(a), OutputKind::Time(b)) => {
+                                (a.jd - b.jd).abs() < tolerance
+                            }
+                            // SS: the event may fall on either side of midnight
+                            (OutputKind::Time(a), _) | (_, OutputKind::Time(a)) => near_midnight(a),
+                            _ => true,
+                        };
+                        if !agree {
+                            disagreements.push(format!(
+                                "lat {latitude}, lon {longitude}, {jd}: {event} vs {interpolated}"
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        disagreements
+    }
+
+    #[test]
+    fn interpolated_rise_set_cross_check_test() {
+        // Arrange
+        let latitudes = [-60.0, -45.0, -30.0, -15.0, 0.0, 15.0, 30.0, 45.0, 60.0];
+
+        // Act
+        let disagreements = cross_check(&latitudes);
+
+        // Assert
+        assert!(disagreements.is_empty(), "{disagreements:#?}");
+    }
+
+    #[test]
+    fn interpolated_rise_set_high_latitude_test() {
+        // Arrange
+        let observer = Observer::new(-180.0, 70.0, 0, 1013.0, 10.0);
+        let jd = JD::from_date(Date::new(2000, 3, 15.0));
+        let target_altitude = observer_target_altitude(jd, &observer);
+
+        // Act
+        let rise = rise(
+            jd,
+            0,
+            target_altitude,
+            observer.longitude,
+            observer.latitude,
+        );
+        let (interpolated_rise, _) = interpolated_rise_set(jd, &observer);
+
+        // Assert
+
+        // SS: Meeus' iteration decides whether the Moon rises from its
+        // declination at midday, when it stays above the horizon. Its
+        // declination changes fast enough for it to set and rise again in
+        // the evening, which the interpolation finds
+        assert!(!matches!(rise, OutputKind::Time(_)));
+        assert_approx_eq!(
+            JD::from_date(Date::from_date_hms(2000, 3, 15, 21, 1, 36.0)).jd,
+            time(interpolated_rise).jd,
+            0.001
+        );
+        assert!(cross_check(&[70.0])
+            .iter()
+            .any(|disagreement| disagreement.contains("lon -180, 2000-03-15")));
+    }
 }
@@ -0,0 +1,187 @@
+//! Selenographic position of the sun, i.e. the point on the moon's surface
+//! the sun is in the zenith of, the terminator, and the directions to the
+//! sun and the Earth in the moon's body frame, to light a 3D model of the
+//! moon. Meeus, chapter 53
+use crate::constants;
+use crate::date::jd::JD;
+use crate::moon;
+use crate::moon::libration;
+use crate::moon::position::GeocentricMoon;
+use crate::sun::position::apparent_position;
+use crate::util::{degrees::Degrees, math, radians::Radians};
+
+/// Illumination of the moon's globe
+#[derive(Debug, Copy, Clone)]
+pub struct Illumination {
+    /// Selenographic longitude of the subsolar point, in degrees [-180, 180).
+    /// Positive towards Mare Crisium, i.e. towards the East
+    pub subsolar_longitude: Degrees,
+
+    /// Selenographic latitude of the subsolar point, in degrees [-90, 90]
+    pub subsolar_latitude: Degrees,
+
+    /// Sun's selenographic colongitude, in degrees [0, 360). About 270 at
+    /// new moon, 0 at first quarter, 90 at full moon and 180 at last quarter
+    pub colongitude: Degrees,
+
+    /// Selenographic longitude of the morning terminator, i.e. where the sun
+    /// rises, in degrees [-180, 180), at the moon's equator
+    pub morning_terminator: Degrees,
+
+    /// Selenographic longitude of the evening terminator, i.e. where the sun
+    /// sets, in degrees [-180, 180), at the moon's equator
+    pub evening_terminator: Degrees,
+
+    /// Unit vector from the moon's center towards the sun, in the
+    /// selenographic frame, see direction_cosines
+    pub sun_direction: [f64; 3],
+
+    /// Unit vector from the moon's center towards the Earth's center, in the
+    /// selenographic frame
+    pub earth_direction: [f64; 3],
+}
+
+/// Calculate the illumination of the moon's globe, Meeus, chapter 53,
+/// page 376
+/// In: Julian day, in dynamical time
+/// Out: Illumination
+pub fn illumination(jd: JD) -> Illumination {
+    illumination_of(jd, &moon::geocentric(jd))
+}
+
+/// Illumination for the moon's geocentric position passed in
+/// In:
+/// jd: Julian day, in dynamical time
+/// moon: Moon's apparent geocentric position
+/// Out: Illumination
+pub(crate) fn illumination_of(jd: JD, moon: &GeocentricMoon) -> Illumination {
+    let (subsolar_longitude, subsolar_latitude) = subsolar_point(jd, moon);
+    let libration = libration::geocentric_libration(jd, moon);
+
+    // SS: the terminator is 90 degrees from the subsolar point
+    let colongitude = (Degrees::new(90.0) - subsolar_longitude).map_to_0_to_360();
+
+    Illumination {
+        subsolar_longitude,
+        subsolar_latitude,
+        colongitude,
+        morning_terminator: (subsolar_longitude - Degrees::new(90.0))
+            .map_to_0_to_360()
+            .map_neg180_to_180(),
+        evening_terminator: (subsolar_longitude + Degrees::new(90.0))
+            .map_to_0_to_360()
+            .map_neg180_to_180(),
+        sun_direction: direction_cosines(subsolar_longitude, subsolar_latitude),
+        earth_direction: direction_cosines(libration.longitude, libration.latitude),
+    }
+}
+
+/// Selenographic position of the subsolar point, Meeus, chapter 53, page 376
+/// In:
+/// jd: Julian day, in dynamical time
+/// moon: Moon's apparent geocentric position
+/// Out:
+/// Selenographic longitude, in degrees [-180, 180), and latitude, in
+/// degrees [-90, 90]
+fn subsolar_point(jd: JD, moon: &GeocentricMoon) -> (Degrees, Degrees) {
+    let sun = apparent_position(jd);
+    let r = sun.distance * constants::AU;
+
+    // SS: heliocentric position of the moon, as seen from the sun
+    let ratio = moon.delta / r;
+    let beta = Radians::from(moon.beta).0;
+    let lambda_h = sun.longitude
+        + Degrees::new(180.0)
+        + Degrees::from(Radians::new(
+            ratio * math::cos(beta) * math::sin(Radians::from(sun.longitude - moon.lambda).0),
+        ));
+    let beta_h = Degrees::new(ratio * moon.beta.0);
+
+    libration::selenographic_position(jd, lambda_h.map_to_0_to_360(), beta_h)
+}
+
+/// Unit vector in the selenographic frame, whose x axis points towards
+/// selenographic longitude and latitude 0, i.e. the mean center of the disk,
+/// the y axis towards longitude 90 degrees East, and the z axis towards the
+/// moon's north pole
+/// In:
+/// longitude: Selenographic longitude, in degrees
+/// latitude: Selenographic latitude, in degrees
+/// Out: Direction cosines x, y and z
+pub fn direction_cosines(longitude: Degrees, latitude: Degrees) -> [f64; 3] {
+    let l = Radians::from(longitude).0;
+    let b = Radians::from(latitude).0;
+    [
+        math::cos(b) * math::cos(l),
+        math::cos(b) * math::sin(l),
+        math::sin(b),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::phase;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn illumination_test() {
+        // Arrange
+
+        // SS: 1992 April 12, 0h TD
+        let jd = JD::from_date(Date::new(1992, 4, 12.0));
+
+        // Act
+        let illumination = illumination(jd);
+
+        // Assert
+
+        // SS: Meeus, example 53.a
+        assert_approx_eq!(67.89, illumination.subsolar_longitude.0, 0.005);
+        assert_approx_eq!(1.46, illumination.subsolar_latitude.0, 0.005);
+        assert_approx_eq!(22.11, illumination.colongitude.0, 0.005);
+        assert_approx_eq!(-22.11, illumination.morning_terminator.0, 0.005);
+        assert_approx_eq!(157.89, illumination.evening_terminator.0, 0.005);
+    }
+
+    #[test]
+    fn direction_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2024, 3, 1.0));
+
+        for day in 0..30 {
+            let jd = JD::new(start.jd + day as f64);
+
+            // Act
+            let illumination = illumination(jd);
+
+            // Assert
+            let sun = illumination.sun_direction;
+            let earth = illumination.earth_direction;
+            assert_approx_eq!(1.0, sun.iter().map(|c| c * c).sum::<f64>(), 1e-12);
+            assert_approx_eq!(1.0, earth.iter().map(|c| c * c).sum::<f64>(), 1e-12);
+
+            // SS: the angle between the directions to the sun and the Earth,
+            // seen from the moon, is the phase angle, up to the sun's
+            // parallax as seen from the moon
+            let cos_phase_angle: f64 = sun.iter().zip(earth.iter()).map(|(s, e)| s * e).sum();
+            let phase_angle = Degrees::from(Radians::new(math::acos(cos_phase_angle)));
+            let expected = phase::phase_info(jd).phase_angle;
+            assert_approx_eq!(expected.0, phase_angle.0, 0.2);
+        }
+    }
+
+    #[test]
+    fn direction_cosines_test() {
+        // Act
+        let center = direction_cosines(Degrees::new(0.0), Degrees::new(0.0));
+        let east = direction_cosines(Degrees::new(90.0), Degrees::new(0.0));
+        let north = direction_cosines(Degrees::new(0.0), Degrees::new(90.0));
+
+        // Assert
+        assert_approx_eq!(1.0, center[0], 1e-12);
+        assert_approx_eq!(1.0, east[1], 1e-12);
+        assert_approx_eq!(1.0, north[2], 1e-12);
+    }
+}
@@ -4,17 +4,19 @@ use crate::date::jd::JD;
 use crate::moon::parallax::horizontal_equatorial_parallax;
 use crate::parallax;
 use crate::util::arcsec::ArcSec;
+use crate::util::declination::Declination;
 use crate::util::degrees::Degrees;
+use crate::util::math;
 use crate::util::radians::Radians;
 
 /// Calculates the geocentric semidiameter of the Moon
 /// Meeus, chapter 55, page 390
 /// In: Julian Day
 /// Out: Moon's semidiameter in arcsec
-fn geocentric_semidiameter(jd: JD) -> ArcSec {
+pub(crate) fn geocentric_semidiameter(jd: JD) -> ArcSec {
     const K: f64 = 0.272_481;
     let sin_s = K * Radians::from(horizontal_equatorial_parallax(jd)).0;
-    let s = sin_s.asin();
+    let s = math::asin(sin_s);
     ArcSec::from(Radians::new(s))
 }
 
@@ -31,7 +33,7 @@ fn geocentric_semidiameter(jd: JD) -> ArcSec {
 pub(crate) fn topocentric_semidiameter(
     jd: JD,
     hour_angle: Degrees,
-    decl: Degrees,
+    decl: Declination,
     latitude_observer: Degrees,
     height_observer: f64,
 ) -> ArcSec {
@@ -42,14 +44,14 @@ pub(crate) fn topocentric_semidiameter(
 
     // SS: eq. (40.7), page 280
     let sin_pi = Radians::from(horizontal_equatorial_parallax(jd));
-    let a = decl_rad.0.cos() * hour_angle_rad.0.sin();
-    let b = decl_rad.0.cos() * hour_angle_rad.0.cos() - rho_cos_p * sin_pi.0;
-    let c = decl_rad.0.sin() - rho_sin_p * sin_pi.0;
+    let a = math::cos(decl_rad.0) * math::sin(hour_angle_rad.0);
+    let b = math::cos(decl_rad.0) * math::cos(hour_angle_rad.0) - rho_cos_p * sin_pi.0;
+    let c = math::sin(decl_rad.0) - rho_sin_p * sin_pi.0;
     let q = (a * a + b * b + c * c).sqrt();
 
     let geocentric_semidiameter = Radians::from(geocentric_semidiameter(jd));
-    let sin_sprime = Radians::new(geocentric_semidiameter.0.sin() / q);
-    ArcSec::from(Radians::new(sin_sprime.0.asin()))
+    let sin_sprime = Radians::new(math::sin(geocentric_semidiameter.0) / q);
+    ArcSec::from(Radians::new(math::asin(sin_sprime.0)))
 }
 
 #[cfg(test)]
@@ -57,7 +59,7 @@ mod tests {
     use super::*;
     use crate::date::date::Date;
     use crate::date::jd::JD;
-    use crate::moon::position::{geocentric_latitude, geocentric_longitude};
+    use crate::moon::position::{geocentric, geocentric_longitude};
     use crate::{coordinates, ecliptic, moon};
     use assert_approx_eq::assert_approx_eq;
 
@@ -74,7 +76,7 @@ mod tests {
         let height_above_sea_level_observer = 1706.0;
 
         let longitude = geocentric_longitude(jd);
-        let latitude = geocentric_latitude(jd);
+        let latitude = geocentric(jd).beta;
         let eps = ecliptic::true_obliquity(jd);
         let (_, decl) = coordinates::ecliptical_2_equatorial(longitude, latitude, eps);
 
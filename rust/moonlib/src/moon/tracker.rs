@@ -0,0 +1,315 @@
+//! Track the Moon's azimuth and altitude at a high rate, e.g. for a telescope
+//! mount or an AR view updating at 10 Hz. Calculating the full topocentric
+//! position for every update is wasteful, as it changes smoothly. The tracker
+//! calculates it fully every few seconds only and interpolates linearly in
+//! between.
+//!
+//! Error bound: the Moon's altitude changes by at most about 15 degrees an
+//! hour, i.e. 0.0042 deg/s, with a second derivative below 3e-7 deg/s^2. The
+//! error of linear interpolation over an interval of N seconds is at most
+//! 1/8 of the second derivative times N^2, about 0.5" for the default 60s.
+//! Atmospheric refraction bends the curve close to the horizon, where the
+//! error grows to a few arc seconds. The azimuth behaves the same except
+//! close to the zenith, where it changes quickly, so the tracker is not
+//! suited for a Moon within a few degrees of the zenith.
+
+use crate::date::jd::JD;
+use crate::moon::position::topocentric_coordinates;
+use crate::session::Observer;
+use crate::util::degrees::Degrees;
+
+/// Default time between full calculations of the Moon's position, in seconds
+pub const DEFAULT_RECOMPUTE_INTERVAL: f64 = 60.0;
+
+/// Shortest time between full calculations, in seconds. Shorter intervals
+/// would not change the Julian day, so the interpolation would divide by 0
+pub const MIN_RECOMPUTE_INTERVAL: f64 = 0.01;
+
+const SEC_PER_DAY: f64 = 86400.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct TrackedPosition {
+    /// Julian day, in dynamical time
+    pub jd: JD,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: Degrees,
+
+    /// Apparent altitude, i.e. corrected for atmospheric refraction, in
+    /// degrees [-90, 90)
+    pub altitude: Degrees,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    observer: Observer,
+
+    /// Observer's height above sea level, in meters
+    height: f64,
+
+    /// Time between full calculations, in seconds
+    recompute_interval: f64,
+
+    /// Current position
+    position: TrackedPosition,
+
+    /// Fully calculated positions at the start and end of the current
+    /// interval
+    start: TrackedPosition,
+    end: TrackedPosition,
+}
+
+impl Tracker {
+    /// In:
+    /// observer: Observer's location and atmospheric conditions
+    /// jd: Julian day to start tracking at, in dynamical time
+    pub fn new(observer: &Observer, jd: JD) -> Self {
+        let start = position(jd, observer, 0.0);
+        let end = position(
+            JD::new(jd.jd + DEFAULT_RECOMPUTE_INTERVAL / SEC_PER_DAY),
+            observer,
+            0.0,
+        );
+        Self {
+            observer: *observer,
+            height: 0.0,
+            recompute_interval: DEFAULT_RECOMPUTE_INTERVAL,
+            position: start,
+            start,
+            end,
+        }
+    }
+
+    /// Observer's height above sea level, in meters. 0 if not set
+    pub fn with_height(mut self, height: f64) -> Self {
+        self.height = height;
+        self.recompute(self.position.jd);
+        self
+    }
+
+    /// Time between full calculations, in seconds. The interpolation error
+    /// grows with the square of the interval, see the module documentation.
+    /// Intervals below MIN_RECOMPUTE_INTERVAL, including 0 and negative
+    /// ones, are raised to it. An interval that is not a finite number is
+    /// ignored
+    pub fn with_recompute_interval(mut self, seconds: f64) -> Self {
+        if seconds.is_finite() {
+            self.recompute_interval = seconds.max(MIN_RECOMPUTE_INTERVAL);
+        }
+        self.recompute(self.position.jd);
+        self
+    }
+
+    /// Current position
+    pub fn position(&self) -> TrackedPosition {
+        self.position
+    }
+
+    /// Move the tracker forward in time. Calculates the position fully if the
+    /// new time is outside the current interval, otherwise interpolates.
+    /// In: dt_seconds: time to advance by, in seconds. May be negative
+    /// Out: Position at the new time
+    pub fn advance(&mut self, dt_seconds: f64) -> TrackedPosition {
+        let jd = JD::new(self.position.jd.jd + dt_seconds / SEC_PER_DAY);
+
+        let interval = self.recompute_interval / SEC_PER_DAY;
+        if jd > self.end.jd && jd.jd <= self.end.jd.jd + interval {
+            // SS: when tracking forward, the end of the current interval is
+            // the start of the next one
+            self.start = self.end;
+            self.end = position(
+                JD::new(self.start.jd.jd + interval),
+                &self.observer,
+                self.height,
+            );
+        } else if jd < self.start.jd || jd > self.end.jd {
+            self.recompute(jd);
+        }
+
+        let fraction = (jd.jd - self.start.jd.jd) / (self.end.jd.jd - self.start.jd.jd);
+
+        // SS: interpolate the azimuth the short way around
+        let delta_azimuth = (self.end.azimuth - self.start.azimuth).map_neg180_to_180();
+        let delta_altitude = self.end.altitude - self.start.altitude;
+
+        self.position = TrackedPosition {
+            jd,
+            azimuth: (self.start.azimuth + delta_azimuth * fraction).map_to_0_to_360(),
+            altitude: self.start.altitude + delta_altitude * fraction,
+        };
+        self.position
+    }
+
+    /// Calculate the positions at the start and end of a new interval
+    /// In: jd: start of the interval, in dynamical time
+    fn recompute(&mut self, jd: JD) {
+        self.start = position(jd, &self.observer, self.height);
+
+        let end_jd = JD::new(jd.jd + self.recompute_interval / SEC_PER_DAY);
+        self.end = position(end_jd, &self.observer, self.height);
+        self.position = self.start;
+    }
+}
+
+/// Full calculation of the Moon's position
+fn position(jd: JD, observer: &Observer, height: f64) -> TrackedPosition {
+    let topocentric = topocentric_coordinates(jd, observer, height);
+    TrackedPosition {
+        jd,
+        azimuth: topocentric.azimuth,
+        altitude: topocentric.apparent_altitude,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::rise_set_transit::{set, OutputKind};
+    use crate::test_util::munich;
+
+    /// Track at 10 Hz and return the largest differences in azimuth and
+    /// altitude from the full calculation, in arc seconds
+    fn max_error(tracker: &mut Tracker, observer: &Observer, seconds: usize) -> (f64, f64) {
+        let mut max_azimuth: f64 = 0.0;
+        let mut max_altitude: f64 = 0.0;
+
+        for i in 1..=10 * seconds {
+            let tracked = tracker.advance(0.1);
+
+            // SS: the full calculation is what the tracker saves, so only
+            // compare every 1.7 seconds
+            if i % 17 == 0 {
+                let expected = topocentric_coordinates(tracked.jd, observer, 0.0);
+                let azimuth = (tracked.azimuth - expected.azimuth).map_neg180_to_180();
+                let altitude = tracked.altitude - expected.apparent_altitude;
+                max_azimuth = max_azimuth.max(azimuth.0.abs() * 3600.0);
+                max_altitude = max_altitude.max(altitude.0.abs() * 3600.0);
+            }
+        }
+
+        (max_azimuth, max_altitude)
+    }
+
+    #[test]
+    fn new_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+        let observer = munich(0);
+
+        // Act
+        let tracker = Tracker::new(&observer, jd);
+
+        // Assert
+        let expected = topocentric_coordinates(jd, &observer, 0.0);
+        assert_eq!(jd, tracker.position().jd);
+        assert_eq!(expected.azimuth.0, tracker.position().azimuth.0);
+        assert_eq!(expected.apparent_altitude.0, tracker.position().altitude.0);
+    }
+
+    #[test]
+    fn advance_test() {
+        // Arrange
+
+        // SS: the Moon is high in the sky in the early morning
+        let jd = JD::from_date(Date::new(2000, 3, 23.1));
+        let observer = munich(0);
+        let mut tracker = Tracker::new(&observer, jd);
+
+        // Act
+        let (azimuth, altitude) = max_error(&mut tracker, &observer, 600);
+
+        // Assert
+        assert!(azimuth < 1.0, "azimuth error {azimuth}\"");
+        assert!(altitude < 1.0, "altitude error {altitude}\"");
+    }
+
+    #[test]
+    fn advance_at_horizon_test() {
+        // Arrange
+
+        // SS: start 10 minutes before moonset, where refraction changes quickly
+        let observer = munich(0);
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+        let set = match set(
+            jd,
+            0,
+            Degrees::new(0.125),
+            observer.longitude,
+            observer.latitude,
+        ) {
+            OutputKind::Time(set) => set,
+            _ => unreachable!(),
+        };
+        let mut tracker = Tracker::new(&observer, JD::new(set.jd - 600.0 / SEC_PER_DAY));
+
+        // Act
+        let (azimuth, altitude) = max_error(&mut tracker, &observer, 1200);
+
+        // Assert
+        assert!(azimuth < 5.0, "azimuth error {azimuth}\"");
+        assert!(altitude < 5.0, "altitude error {altitude}\"");
+    }
+
+    #[test]
+    fn advance_backward_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.1));
+        let observer = munich(0);
+        let mut tracker = Tracker::new(&observer, jd);
+
+        // Act
+        let tracked = tracker.advance(-90.0);
+
+        // Assert
+
+        // SS: outside of the interval, so calculated fully
+        let expected = topocentric_coordinates(tracked.jd, &observer, 0.0);
+        assert_approx_eq!(jd.jd - 90.0 / SEC_PER_DAY, tracked.jd.jd, 1e-9);
+        assert_eq!(expected.azimuth.0, tracked.azimuth.0);
+        assert_eq!(expected.apparent_altitude.0, tracked.altitude.0);
+    }
+
+    #[test]
+    fn recompute_interval_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.1));
+        let observer = munich(0);
+        let mut short = Tracker::new(&observer, jd).with_recompute_interval(10.0);
+        let mut long = Tracker::new(&observer, jd).with_recompute_interval(600.0);
+
+        // Act
+        let (_, altitude_short) = max_error(&mut short, &observer, 600);
+        let (_, altitude_long) = max_error(&mut long, &observer, 600);
+
+        // Assert
+
+        // SS: the error grows with the square of the interval
+        assert!(altitude_short < altitude_long);
+        assert!(altitude_long < 60.0, "altitude error {altitude_long}\"");
+    }
+
+    #[test]
+    fn invalid_recompute_interval_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.1));
+        let observer = munich(0);
+
+        for seconds in [0.0, -60.0, 1e-300, f64::NAN, f64::INFINITY] {
+            let mut tracker = Tracker::new(&observer, jd).with_recompute_interval(seconds);
+
+            // Act
+            let positions: Vec<TrackedPosition> = (0..20).map(|_| tracker.advance(0.1)).collect();
+
+            // Assert
+            for tracked in positions {
+                let expected = topocentric_coordinates(tracked.jd, &observer, 0.0);
+                let altitude = tracked.altitude - expected.apparent_altitude;
+                assert!(altitude.0.abs() * 3600.0 < 1.0, "{seconds}: {tracked:?}");
+                assert!(tracked.azimuth.0.is_finite(), "{seconds}: {tracked:?}");
+            }
+        }
+    }
+}
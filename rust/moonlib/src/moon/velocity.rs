@@ -0,0 +1,237 @@
+//! Rate of change of the moon's geocentric longitude, latitude and distance,
+//! from the derivatives of the periodic terms of Meeus, chapter 47.
+//!
+//! The moon always moves eastward along the ecliptic, i.e. its longitude
+//! never decreases. It moves fastest close to perigee, at about 15.4 degrees
+//! a day, and slowest close to apogee, at about 11.8 degrees a day.
+//!
+//! Without feature high-precision-moon, the rates are central differences of
+//! the truncated series instead.
+
+use crate::date::jd::JD;
+#[cfg(feature = "high-precision-moon")]
+use crate::{
+    constants, earth,
+    moon::position::{argument_of_latitude, mean_anomaly, mean_elongation, mean_longitude},
+    sun::sun,
+    util::{degrees::Degrees, math, radians::Radians},
+};
+#[cfg(feature = "high-precision-moon")]
+use tabular::moon_position_data;
+
+/// Days per Julian century, to convert rates per century to rates per day
+#[cfg(feature = "high-precision-moon")]
+const DAYS_PER_CENTURY: f64 = 36_525.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct MoonVelocity {
+    /// Rate of change of the geocentric longitude, in degrees per day
+    pub longitude: f64,
+
+    /// Rate of change of the geocentric latitude, in degrees per day
+    pub latitude: f64,
+
+    /// Rate of change of the distance from Earth, in km per day
+    pub distance: f64,
+}
+
+/// Rates of change of the fundamental arguments, i.e. the derivatives of
+/// eqs (47.1) to (47.5)
+/// In: Julian day in dynamical time
+/// Out: rates of L', D, M, M' and F, in radians per day
+#[cfg(feature = "high-precision-moon")]
+fn argument_rates(jd: JD) -> [f64; 5] {
+    let t = jd.centuries_from_epoch_j2000();
+    let t2 = t * t;
+    let t3 = t * t2;
+
+    let l_prime =
+        481_267.881_234_21 - 2.0 * 0.0015786 * t + 3.0 * t2 / 538_841.0 - 4.0 * t3 / 65_194_000.0;
+    let d =
+        445_267.111_403_4 - 2.0 * 0.0018819 * t + 3.0 * t2 / 545_868.0 - 4.0 * t3 / 113_065_000.0;
+    let m = 35_999.050_290_9 - 2.0 * 0.0001536 * t + 3.0 * t2 / 24_490_000.0;
+    let m_prime =
+        477_198.867_505_5 + 2.0 * 0.0087414 * t + 3.0 * t2 / 69_699.0 - 4.0 * t3 / 14_712_000.0;
+    let f =
+        483_202.017_523_3 - 2.0 * 0.0036539 * t - 3.0 * t2 / 3_526_000.0 + 4.0 * t3 / 863_310_000.0;
+
+    [l_prime, d, m, m_prime, f].map(per_day)
+}
+
+/// Convert a rate in degrees per century to radians per day
+#[cfg(feature = "high-precision-moon")]
+fn per_day(rate: f64) -> f64 {
+    rate * constants::DEGREES_TO_RADIANS / DAYS_PER_CENTURY
+}
+
+/// Derivative of the sum of the periodic terms of tables 47.A and 47.B
+/// In:
+/// terms: multiples of D, M, M' and F and the coefficient of each term
+/// [d, m, m_prime, f]: fundamental arguments
+/// rates: rates of change of D, M, M' and F, in radians per day
+/// e: eccentricity of Earth's orbit, eq (47.6). It changes too slowly for
+/// its rate to matter
+/// trig_derivative: derivative of the sine or cosine of the terms
+/// Out:
+/// Rate of change of the sum, per day
+#[cfg(feature = "high-precision-moon")]
+fn rate_of_terms(
+    terms: impl Iterator<Item = (i8, i8, i8, i8, i64)>,
+    [d, m, m_prime, f]: [Radians; 4],
+    rates: [f64; 4],
+    e: f64,
+    trig_derivative: fn(f64) -> f64,
+) -> f64 {
+    terms.fold(0.0, |accum, c| {
+        let multiples = [c.0 as f64, c.1 as f64, c.2 as f64, c.3 as f64];
+        let arg =
+            multiples[0] * d.0 + multiples[1] * m.0 + multiples[2] * m_prime.0 + multiples[3] * f.0;
+        let arg_rate = (0..4).map(|k| multiples[k] * rates[k]).sum::<f64>();
+
+        // SS: terms containing M depend on the eccentricity of Earth's orbit
+        let coeff = c.4 as f64 * e.powi(c.1.abs() as i32);
+
+        accum + coeff * trig_derivative(arg) * arg_rate
+    })
+}
+
+/// Calculate the rate of change of the moon's geocentric position by
+/// differentiating the series of Meeus, chapter 47, page 342. The change of
+/// the nutation in longitude is below 0.0001 degrees a day and ignored.
+/// In: Julian day in dynamical time
+/// Out: Rates of change of longitude, latitude and distance
+#[cfg(feature = "high-precision-moon")]
+pub fn longitudinal_velocity(jd: JD) -> MoonVelocity {
+    let t = jd.centuries_from_epoch_j2000();
+
+    let a1 = Radians::from(Degrees::new(119.75 + 131.849 * t).map_to_0_to_360());
+    let a2 = Radians::from(Degrees::new(53.09 + 479264.290 * t).map_to_0_to_360());
+    let a3 = Radians::from(Degrees::new(313.45 + 481266.484 * t).map_to_0_to_360());
+    let [a1_rate, a2_rate, a3_rate] = [131.849, 479_264.290, 481_266.484].map(per_day);
+
+    let l_prime = Radians::from(mean_longitude(jd));
+    let d = Radians::from(mean_elongation(jd));
+    let m = Radians::from(sun::mean_anomaly(jd));
+    let m_prime = Radians::from(mean_anomaly(jd));
+    let f = Radians::from(argument_of_latitude(jd));
+    let e = earth::eccentricity(jd);
+
+    let [l_prime_rate, d_rate, m_rate, m_prime_rate, f_rate] = argument_rates(jd);
+    let arguments = [d, m, m_prime, f];
+    let rates = [d_rate, m_rate, m_prime_rate, f_rate];
+
+    // SS: longitude, the terms are in 1e-6 degrees
+    let terms_l = moon_position_data::SIGMA_L_AND_R_COEFFICIENTS
+        .iter()
+        .map(|&(d, m, m_prime, f, l, _)| (d, m, m_prime, f, l));
+    let mut sigma_l = rate_of_terms(terms_l, arguments, rates, e, math::cos);
+    sigma_l += 3958.0 * math::cos(a1.0) * a1_rate;
+    sigma_l += 1962.0 * math::cos((l_prime - f).0) * (l_prime_rate - f_rate);
+    sigma_l += 318.0 * math::cos(a2.0) * a2_rate;
+
+    // SS: latitude, the terms are in 1e-6 degrees
+    let terms_b = moon_position_data::SIGMA_B_COEFFICIENTS.iter().copied();
+    let mut sigma_b = rate_of_terms(terms_b, arguments, rates, e, math::cos);
+    sigma_b -= 2235.0 * math::cos(l_prime.0) * l_prime_rate;
+    sigma_b += 382.0 * math::cos(a3.0) * a3_rate;
+    sigma_b += 175.0 * math::cos((a1 - f).0) * (a1_rate - f_rate);
+    sigma_b += 175.0 * math::cos((a1 + f).0) * (a1_rate + f_rate);
+    sigma_b += 127.0 * math::cos((l_prime - m_prime).0) * (l_prime_rate - m_prime_rate);
+    sigma_b -= 115.0 * math::cos((l_prime + m_prime).0) * (l_prime_rate + m_prime_rate);
+
+    // SS: distance, the terms are in meters
+    let terms_r = moon_position_data::SIGMA_L_AND_R_COEFFICIENTS
+        .iter()
+        .map(|&(d, m, m_prime, f, _, r)| (d, m, m_prime, f, r));
+    let sigma_r = rate_of_terms(terms_r, arguments, rates, e, |x| -math::sin(x));
+
+    MoonVelocity {
+        longitude: l_prime_rate * constants::RADIANS_TO_DEGREES + sigma_l / 1_000_000.0,
+        latitude: sigma_b / 1_000_000.0,
+        distance: sigma_r / 1000.0,
+    }
+}
+
+/// Calculate the rate of change of the moon's geocentric position from
+/// central differences of the truncated series
+/// In: Julian day in dynamical time
+/// Out: Rates of change of longitude, latitude and distance
+#[cfg(not(feature = "high-precision-moon"))]
+pub fn longitudinal_velocity(jd: JD) -> MoonVelocity {
+    use crate::moon;
+
+    // SS: step, in days
+    const H: f64 = 0.01;
+
+    let before = moon::geocentric(JD::new(jd.jd - H));
+    let after = moon::geocentric(JD::new(jd.jd + H));
+
+    let longitude = (after.lambda - before.lambda).map_neg180_to_180();
+    let latitude = after.beta - before.beta;
+    let distance = after.delta - before.delta;
+
+    MoonVelocity {
+        longitude: longitude.0 / (2.0 * H),
+        latitude: latitude.0 / (2.0 * H),
+        distance: distance / (2.0 * H),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::position::{distance_from_earth, geocentric, geocentric_longitude};
+
+    #[test]
+    fn longitudinal_velocity_test() {
+        // Arrange
+
+        // SS: step for the central differences, in days
+        const H: f64 = 0.01;
+
+        let start = JD::from_date(Date::new(1992, 4, 12.0));
+
+        for hours in (0..30 * 24).step_by(7) {
+            let jd = JD::new(start.jd + hours as f64 / 24.0);
+            let before = JD::new(jd.jd - H);
+            let after = JD::new(jd.jd + H);
+
+            // Act
+            let velocity = longitudinal_velocity(jd);
+
+            // Assert
+            let longitude = (geocentric_longitude(after) - geocentric_longitude(before))
+                .map_neg180_to_180()
+                .0
+                / (2.0 * H);
+            let latitude = (geocentric(after).beta - geocentric(before).beta).0 / (2.0 * H);
+            let distance = (distance_from_earth(after) - distance_from_earth(before)) / (2.0 * H);
+
+            assert_approx_eq!(longitude, velocity.longitude, 0.0001);
+            assert_approx_eq!(latitude, velocity.latitude, 0.0001);
+            assert_approx_eq!(distance, velocity.distance, 0.01);
+        }
+    }
+
+    #[test]
+    fn longitudinal_velocity_range_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2022, 1, 1.0));
+
+        // Act
+        let velocities = (0..365)
+            .map(|day| longitudinal_velocity(JD::new(start.jd + day as f64)).longitude)
+            .collect::<Vec<_>>();
+
+        // Assert
+
+        // SS: never retrograde, and on average 360 degrees per sidereal month
+        // of 27.32 days
+        let mean = velocities.iter().sum::<f64>() / velocities.len() as f64;
+        assert!(velocities.iter().all(|v| *v > 11.5 && *v < 15.5));
+        assert_approx_eq!(360.0 / 27.32, mean, 0.05);
+    }
+}
@@ -0,0 +1,397 @@
+//! Classify a day by whether the Moon rises and sets, is above the horizon
+//! all day, or below it all day. Unlike rise and set, which only tell that
+//! there is no event on the day, this distinguishes a circumpolar Moon from
+//! one that merely skips an event.
+//!
+//! Also search for the next time the Moon can be observed, i.e. it is high
+//! enough above the horizon, the sky is dark enough and the Moon is not too
+//! close to new moon.
+
+use crate::date::jd::JD;
+use crate::horizon::{FlatHorizon, Horizon};
+use crate::moon::phase::fraction_illuminated;
+use crate::moon::position::topocentric_coordinates;
+use crate::moon::rise_set_transit::{equatorial_position, target_altitude};
+use crate::rise_set_transit::{
+    altitude_above_target, bound_julian_day, classify_day, crossing, SCAN_STEP,
+};
+use crate::session::Observer;
+use crate::sun;
+use crate::util::degrees::Degrees;
+
+pub use crate::rise_set_transit::DayVisibility;
+
+/// How far to search ahead for the Moon to become visible, in days. Longer
+/// than a synodic month
+const SEARCH_LIMIT: f64 = 35.0;
+
+/// Below this illuminated fraction, the crescent cannot be seen. It
+/// corresponds to the Danjon limit of 7 degrees elongation from the Sun,
+/// (1 - cos 7 deg) / 2
+const MIN_ILLUMINATED_FRACTION: f64 = 0.003_73;
+
+/// Classify a day by the Moon's rise and set, by scanning the Moon's
+/// altitude over the observer's day.
+/// In:
+/// jd: Julian Day, in dynamical time. Its calendar date is the observer's local date
+/// observer: Observer's location, time zone and atmospheric conditions
+/// Out:
+/// The Moon's visibility on the day, rise and set times in dynamical time
+pub fn day_visibility(jd: JD, observer: &Observer) -> DayVisibility {
+    let (jd_min, jd_midday, jd_max) = bound_julian_day(jd, observer.timezone_offset);
+
+    // SS: the Moon's parallax and semidiameter hardly change within a day
+    let h0 = target_altitude(
+        jd_midday,
+        Degrees::new(0.0),
+        observer.longitude,
+        observer.latitude,
+        observer.pressure,
+        observer.temperature,
+    );
+    let above = |jd: JD| {
+        altitude_above_target(
+            jd,
+            h0,
+            observer.longitude,
+            observer.latitude,
+            equatorial_position,
+        )
+    };
+
+    classify_day(jd_min, jd_max, &above)
+}
+
+/// Find the next interval the Moon can be observed, i.e. it is at least
+/// min_altitude above the horizon, the Sun is at most max_sun_altitude
+/// above the horizon and the Moon is not too close to new moon to be seen.
+/// In:
+/// jd: Julian day to start searching at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// min_altitude: minimum apparent altitude of the Moon, in degrees
+/// max_sun_altitude: maximum altitude of the Sun, in degrees, e.g. -6 for
+/// civil twilight or 90 to include daylight
+/// Out:
+/// Start and end of the interval, in dynamical time. The start is jd if the
+/// Moon is already visible, and the end is limited to 35 days after jd.
+/// None if the Moon is not visible within the next 35 days
+pub fn next_visible(
+    jd: JD,
+    observer: &Observer,
+    min_altitude: Degrees,
+    max_sun_altitude: Degrees,
+) -> Option<(JD, JD)> {
+    next_visible_with_horizon(jd, observer, &FlatHorizon, min_altitude, max_sun_altitude)
+}
+
+/// Find the next interval the Moon can be observed above the observer's
+/// skyline, e.g. from a valley, see next_visible
+/// In:
+/// jd: Julian day to start searching at, in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// horizon: Altitude of the skyline
+/// min_altitude: minimum apparent altitude of the Moon above the skyline
+/// in its direction, in degrees
+/// max_sun_altitude: maximum altitude of the Sun, in degrees
+/// Out:
+/// Start and end of the interval, in dynamical time, see next_visible
+pub fn next_visible_with_horizon<H: Horizon + ?Sized>(
+    jd: JD,
+    observer: &Observer,
+    horizon: &H,
+    min_altitude: Degrees,
+    max_sun_altitude: Degrees,
+) -> Option<(JD, JD)> {
+    let margin = |jd: JD| visibility_margin(jd, observer, horizon, min_altitude, max_sun_altitude);
+    let jd_max = JD::new(jd.jd + SEARCH_LIMIT);
+
+    let mut prev_jd = jd;
+    let mut prev_margin = margin(prev_jd);
+    let mut start = if prev_margin > 0.0 { Some(jd) } else { None };
+
+    while prev_jd < jd_max {
+        let mut next_jd = prev_jd;
+        next_jd.add_hours(SCAN_STEP);
+        let next_jd = if next_jd > jd_max { jd_max } else { next_jd };
+        let next_margin = margin(next_jd);
+
+        match start {
+            None if prev_margin <= 0.0 && next_margin > 0.0 => {
+                start = Some(crossing(prev_jd, next_jd, &margin));
+            }
+            Some(start) if prev_margin > 0.0 && next_margin <= 0.0 => {
+                return Some((start, crossing(prev_jd, next_jd, &margin)));
+            }
+            _ => {}
+        }
+
+        prev_jd = next_jd;
+        prev_margin = next_margin;
+    }
+
+    start.map(|start| (start, jd_max))
+}
+
+/// How far the Moon is from being observable. Positive if it is observable,
+/// the smallest of the margins of the Moon's altitude and the Sun's altitude,
+/// in degrees, and of the illuminated fraction
+fn visibility_margin<H: Horizon + ?Sized>(
+    jd: JD,
+    observer: &Observer,
+    horizon: &H,
+    min_altitude: Degrees,
+    max_sun_altitude: Degrees,
+) -> f64 {
+    let moon = topocentric_coordinates(jd, observer, 0.0);
+    let moon_altitude = moon.apparent_altitude - horizon.altitude(moon.azimuth);
+    let (_, sun_altitude) =
+        sun::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+    let illuminated = fraction_illuminated(jd) - MIN_ILLUMINATED_FRACTION;
+
+    (moon_altitude - min_altitude)
+        .0
+        .min((max_sun_altitude - sun_altitude).0)
+        .min(illuminated)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::date::date::Date;
+    use crate::moon::rise_set_transit::{rise, set, OutputKind};
+    use crate::test_util::{munich, tromso};
+
+    #[test]
+    fn next_visible_test() {
+        // Arrange
+
+        // SS: the Moon is past full and rises in the late evening
+        let jd = JD::from_date(Date::new(2000, 3, 23.5));
+        let observer = munich(0);
+        let min_altitude = Degrees::new(10.0);
+        let max_sun_altitude = Degrees::new(-6.0);
+        let margin =
+            |jd: JD| visibility_margin(jd, &observer, &FlatHorizon, min_altitude, max_sun_altitude);
+
+        // Act
+        let (start, end) = next_visible(jd, &observer, min_altitude, max_sun_altitude).unwrap();
+
+        // Assert
+        assert!(jd < start && start < end);
+        assert!(end.jd - start.jd < 0.5);
+        assert!(margin(JD::new(start.jd - 1.0 / 1440.0)) <= 0.0);
+        assert!(margin(JD::new(0.5 * (start.jd + end.jd))) > 0.0);
+        assert!(margin(JD::new(end.jd + 1.0 / 1440.0)) <= 0.0);
+
+        // SS: not visible before, sampled every 5 minutes
+        let samples = ((start.jd - jd.jd) * 288.0) as usize;
+        assert!((0..samples).all(|i| margin(JD::new(jd.jd + i as f64 / 288.0)) <= 0.0));
+    }
+
+    #[test]
+    fn next_visible_with_horizon_test() {
+        // Arrange
+
+        // SS: a valley with a skyline 5 degrees high all around
+        let jd = JD::from_date(Date::new(2000, 3, 23.5));
+        let observer = munich(0);
+        let valley = |_azimuth: Degrees| Degrees::new(5.0);
+
+        // Act
+        let (start, end) = next_visible_with_horizon(
+            jd,
+            &observer,
+            &valley,
+            Degrees::new(5.0),
+            Degrees::new(-6.0),
+        )
+        .unwrap();
+
+        // Assert
+
+        // SS: 5 degrees above a 5 degree skyline is 10 degrees above the horizon
+        let (expected_start, expected_end) =
+            next_visible(jd, &observer, Degrees::new(10.0), Degrees::new(-6.0)).unwrap();
+        assert_approx_eq!(expected_start.jd, start.jd, 1e-6);
+        assert_approx_eq!(expected_end.jd, end.jd, 1e-6);
+    }
+
+    #[test]
+    fn next_visible_now_test() {
+        // Arrange
+
+        // SS: full moon on March 20th, 2000, up around local midnight
+        let jd = JD::from_date(Date::new(2000, 3, 19.98));
+        let observer = munich(0);
+
+        // Act
+        let (start, end) =
+            next_visible(jd, &observer, Degrees::new(10.0), Degrees::new(-6.0)).unwrap();
+
+        // Assert
+        assert_eq!(jd, start);
+        assert!(start < end);
+    }
+
+    #[test]
+    fn next_visible_midnight_sun_test() {
+        // Arrange
+
+        // SS: Tromso, the Sun does not get 6 degrees below the horizon
+        // between May and August
+        let jd = JD::from_date(Date::new(2022, 6, 1.0));
+
+        // Act
+        let visible = next_visible(jd, &tromso(0), Degrees::new(0.0), Degrees::new(-6.0));
+
+        // Assert
+        assert!(visible.is_none());
+    }
+
+    #[test]
+    fn rises_and_sets_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+        let observer = munich(0);
+        let h0 = target_altitude(
+            JD::from_date(Date::new(2000, 3, 23.5)),
+            Degrees::new(0.0),
+            observer.longitude,
+            observer.latitude,
+            observer.pressure,
+            observer.temperature,
+        );
+        let expected_rise = rise(jd, 0, h0, observer.longitude, observer.latitude);
+        let expected_set = set(jd, 0, h0, observer.longitude, observer.latitude);
+
+        // Act
+        let visibility = day_visibility(jd, &observer);
+
+        // Assert
+
+        // SS: same as the iterative solution, to within the iteration's accuracy
+        match (visibility, expected_rise, expected_set) {
+            (
+                DayVisibility::RisesAndSets { rise, set },
+                OutputKind::Time(expected_rise),
+                OutputKind::Time(expected_set),
+            ) => {
+                assert_approx_eq!(expected_rise.jd, rise.jd, 1.0 / 1440.0);
+                assert_approx_eq!(expected_set.jd, set.jd, 1.0 / 1440.0);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn always_below_test() {
+        // Arrange
+
+        // SS: Tromso, the full Moon on July 16th, 2000 is far south of the
+        // equator and does not rise
+        let jd = JD::from_date(Date::new(2000, 7, 15.0));
+
+        // Act
+        let visibility = day_visibility(jd, &tromso(0));
+
+        // Assert
+        assert_eq!(DayVisibility::AlwaysBelow, visibility);
+    }
+
+    #[test]
+    fn always_above_test() {
+        // Arrange
+
+        // SS: Tromso, two weeks later the Moon does not set
+        let jd = JD::from_date(Date::new(2000, 7, 29.0));
+
+        // Act
+        let visibility = day_visibility(jd, &tromso(0));
+
+        // Assert
+        assert_eq!(DayVisibility::AlwaysAbove, visibility);
+    }
+
+    #[test]
+    fn rises_only_test() {
+        // Arrange
+
+        // SS: the Moon rises about 50 minutes later each day, so once a month it
+        // rises just before midnight and sets after the next midnight
+        let jd = JD::from_date(Date::new(2000, 7, 10.0));
+
+        // Act
+        let visibility = day_visibility(jd, &munich(0));
+
+        // Assert
+        match visibility {
+            DayVisibility::RisesOnly(rise) => {
+                assert!(rise.jd - jd.jd > 0.5);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn sets_only_test() {
+        // Arrange
+
+        // SS: once a month, the Moon sets, but rises after the next midnight
+        let jd = JD::from_date(Date::new(2000, 7, 27.0));
+
+        // Act
+        let visibility = day_visibility(jd, &munich(0));
+
+        // Assert
+        assert!(matches!(visibility, DayVisibility::SetsOnly(_)));
+    }
+
+    #[test]
+    fn consistent_with_rise_set_test() {
+        // Arrange
+        let observer = tromso(0);
+        let target_altitude = Degrees::new(0.125);
+
+        for (day, expected) in [
+            (15.0, DayVisibility::AlwaysBelow),
+            (29.0, DayVisibility::AlwaysAbove),
+        ] {
+            let jd = JD::from_date(Date::new(2000, 7, day));
+
+            // Act
+            let visibility = day_visibility(jd, &observer);
+            let rise = rise(
+                jd,
+                0,
+                target_altitude,
+                observer.longitude,
+                observer.latitude,
+            );
+            let set = set(
+                jd,
+                0,
+                target_altitude,
+                observer.longitude,
+                observer.latitude,
+            );
+
+            // Assert
+
+            // SS: a Moon below the horizon all day never rises, one above it
+            // never sets
+            assert_eq!(expected, visibility);
+            match expected {
+                DayVisibility::AlwaysBelow => {
+                    assert!(matches!(rise, OutputKind::NeverRises));
+                    assert!(matches!(set, OutputKind::NeverRises));
+                }
+                _ => {
+                    assert!(matches!(rise, OutputKind::NeverSets));
+                    assert!(matches!(set, OutputKind::NeverSets));
+                }
+            }
+        }
+    }
+}
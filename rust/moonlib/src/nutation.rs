@@ -2,7 +2,8 @@
 #![allow(clippy::type_complexity)]
 
 use crate::date::jd::JD;
-use crate::util::{arcsec::ArcSec, degrees::Degrees, radians::Radians};
+use crate::moon;
+use crate::util::{arcsec::ArcSec, degrees::Degrees, math, radians::Radians};
 
 const NUTATION_PERTURBATION_TERMS: [(i8, i8, i8, i8, i8, i64, f64, i64, f64); 63] = [
     (0, 0, 0, 0, 1, -171996, -174.2, 92025, 8.9),
@@ -90,8 +91,7 @@ pub fn nutation_in_longitude(jd: JD) -> ArcSec {
     let f = Degrees::new(93.27191 + (483_202.017538 * t) - (0.0036825 * t2) + (t3 / 327_270.0))
         .map_to_0_to_360();
 
-    let omega = Degrees::new(125.04452 - (1934.136261 * t) + (0.0020708 * t2) + (t3 / 450_000.0))
-        .map_to_0_to_360();
+    let omega = moon::mean_node(jd);
 
     let delta_psi = NUTATION_PERTURBATION_TERMS.iter().fold(0.0, |accum, &c| {
         let sin_arg = c.0 as f64 * d.0
@@ -100,7 +100,7 @@ pub fn nutation_in_longitude(jd: JD) -> ArcSec {
             + c.3 as f64 * f.0
             + c.4 as f64 * omega.0;
         let sin_arg = Radians::from(Degrees::new(sin_arg));
-        let value = (c.5 as f64 + c.6 * t) * sin_arg.0.sin() * 0.0001;
+        let value = (c.5 as f64 + c.6 * t) * math::sin(sin_arg.0) * 0.0001;
         accum + value
     });
 
@@ -123,8 +123,7 @@ pub fn nutation_in_obliquity(jd: JD) -> ArcSec {
             .map_to_0_to_360();
     let f = Degrees::new(93.27191 + (483_202.017538 * t) - (0.0036825 * t2) + (t3 / 327_270.0))
         .map_to_0_to_360();
-    let omega = Degrees::new(125.04452 - (1934.136261 * t) + (0.0020708 * t2) + (t3 / 450_000.0))
-        .map_to_0_to_360();
+    let omega = moon::mean_node(jd);
 
     let delta_epsilon = NUTATION_PERTURBATION_TERMS.iter().fold(0.0, |accum, &c| {
         let cos_arg = c.0 as f64 * d.0
@@ -133,13 +132,37 @@ pub fn nutation_in_obliquity(jd: JD) -> ArcSec {
             + c.3 as f64 * f.0
             + c.4 as f64 * omega.0;
         let cos_arg = Radians::from(Degrees::new(cos_arg));
-        let value = (c.7 as f64 + c.8 * t) * cos_arg.0.cos() * 0.0001;
+        let value = (c.7 as f64 + c.8 * t) * math::cos(cos_arg.0) * 0.0001;
         accum + value
     });
 
     ArcSec::new(delta_epsilon)
 }
 
+/// Nutation in longitude and obliquity from the four largest terms,
+/// Meeus chapter 22, page 144. Accurate to 0.5" in longitude and 0.1" in
+/// obliquity, and much faster than summing up the full series.
+/// In: Julian day in dynamical time
+/// Out: nutation in longitude and in obliquity, in arcsec
+pub(crate) fn nutation_low_accuracy(jd: JD) -> (ArcSec, ArcSec) {
+    let t = jd.centuries_from_epoch_j2000();
+
+    // SS: mean longitudes of the Sun and the Moon, and the longitude of the
+    // ascending node of the Moon's mean orbit
+    let l = Radians::from(Degrees::new(280.4665 + 36_000.769_8 * t)).0;
+    let l_prime = Radians::from(Degrees::new(218.3165 + 481_267.881_3 * t)).0;
+    let omega = Radians::from(moon::mean_node(jd)).0;
+
+    let delta_psi =
+        -17.20 * math::sin(omega) - 1.32 * math::sin(2.0 * l) - 0.23 * math::sin(2.0 * l_prime)
+            + 0.21 * math::sin(2.0 * omega);
+    let delta_epsilon =
+        9.20 * math::cos(omega) + 0.57 * math::cos(2.0 * l) + 0.10 * math::cos(2.0 * l_prime)
+            - 0.09 * math::cos(2.0 * omega);
+
+    (ArcSec::new(delta_psi), ArcSec::new(delta_epsilon))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,4 +206,36 @@ mod tests {
         // Assert
         assert_approx_eq!(9.443, delta_epsilon.0, 0.001)
     }
+
+    #[test]
+    fn nutation_low_accuracy_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1987, 4, 10.0));
+
+        // Act
+        let (delta_psi, delta_epsilon) = nutation_low_accuracy(jd);
+
+        // Assert
+
+        // SS: Meeus, example 22.a, and the accuracy given on page 144
+        assert_approx_eq!(-3.788, delta_psi.0, 0.5);
+        assert_approx_eq!(9.443, delta_epsilon.0, 0.1);
+    }
+
+    #[test]
+    fn nutation_low_accuracy_range_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(1900, 1, 1.0));
+
+        for day in (0..73_000).step_by(37) {
+            let jd = JD::new(jd.jd + day as f64);
+
+            // Act
+            let (delta_psi, delta_epsilon) = nutation_low_accuracy(jd);
+
+            // Assert
+            assert_approx_eq!(nutation_in_longitude(jd).0, delta_psi.0, 0.5);
+            assert_approx_eq!(nutation_in_obliquity(jd).0, delta_epsilon.0, 0.1);
+        }
+    }
 }
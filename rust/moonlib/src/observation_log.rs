@@ -0,0 +1,209 @@
+//! Fixed-point equatorial coordinates for observation logs.
+//! Coordinates are rounded to integer milliarcseconds with an explicit
+//! rounding rule, so that logs written on different devices compare equal
+//! and do not depend on f64 formatting.
+
+use crate::util::degrees::Degrees;
+
+const MAS_PER_DEGREE: f64 = 3_600_000.0;
+
+/// 360 degrees, in milliarcseconds
+const MAS_PER_CIRCLE: i64 = 1_296_000_000;
+
+/// 1/100 second of time is 0.15 arcsec
+const MAS_PER_CENTISECOND_OF_TIME: i64 = 150;
+
+/// 1/10 arcsec
+const MAS_PER_DECIARCSEC: i64 = 100;
+
+/// Rounding rule applied when converting to integer units
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round to the nearest integer, ties away from zero
+    HalfAwayFromZero,
+
+    /// Round to the nearest integer, ties to the even integer
+    HalfEven,
+
+    /// Truncate, i.e. round toward zero
+    TowardZero,
+}
+
+impl Rounding {
+    fn round(self, value: f64) -> i64 {
+        match self {
+            Rounding::HalfAwayFromZero => value.round() as i64,
+            Rounding::HalfEven => {
+                let rounded = value.round();
+                if (value - value.trunc()).abs() == 0.5 {
+                    (2.0 * (value / 2.0).round()) as i64
+                } else {
+                    rounded as i64
+                }
+            }
+            Rounding::TowardZero => value.trunc() as i64,
+        }
+    }
+
+    /// Integer division numerator / denominator, with denominator > 0
+    fn div(self, numerator: i64, denominator: i64) -> i64 {
+        let quotient = numerator / denominator;
+        let remainder = numerator % denominator;
+
+        // SS: compare twice the remainder to the denominator to avoid fractions
+        let twice = 2 * remainder.abs();
+        let away = if numerator < 0 { -1 } else { 1 };
+
+        match self {
+            Rounding::TowardZero => quotient,
+            Rounding::HalfAwayFromZero => {
+                if twice >= denominator {
+                    quotient + away
+                } else {
+                    quotient
+                }
+            }
+            Rounding::HalfEven => {
+                if twice > denominator || (twice == denominator && quotient % 2 != 0) {
+                    quotient + away
+                } else {
+                    quotient
+                }
+            }
+        }
+    }
+}
+
+/// Equatorial coordinates in integer milliarcseconds
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FixedEquatorial {
+    /// Right ascension, in milliarcseconds [0, 1_296_000_000)
+    pub ra: i64,
+
+    /// Declination, in milliarcseconds [-324_000_000, 324_000_000]
+    pub decl: i64,
+
+    /// Rounding rule, also used when formatting
+    pub rounding: Rounding,
+}
+
+impl FixedEquatorial {
+    /// Convert equatorial coordinates to integer milliarcseconds.
+    /// In:
+    /// ra: Right ascension, in degrees [0, 360)
+    /// decl: Declination, in degrees [-90, 90]
+    /// rounding: Rounding rule, also used when formatting
+    pub fn new(ra: f64, decl: f64, rounding: Rounding) -> Self {
+        // SS: rounding may yield 360 deg, which is 0 deg
+        let ra = rounding
+            .round(Degrees::new(ra).map_to_0_to_360().0 * MAS_PER_DEGREE)
+            .rem_euclid(MAS_PER_CIRCLE);
+        let decl = rounding.round(decl * MAS_PER_DEGREE);
+
+        Self { ra, decl, rounding }
+    }
+
+    /// Format as used in AAVSO observation logs, i.e. right ascension as
+    /// "hh:mm:ss.ss" and declination as "+dd:mm:ss.s", separated by a space.
+    /// The values are rounded with the rounding rule given at construction.
+    pub fn to_aavso(&self) -> String {
+        let rounding = self.rounding;
+
+        // SS: right ascension in 1/100 seconds of time, wrapping at 24h
+        let centiseconds_per_day = MAS_PER_CIRCLE / MAS_PER_CENTISECOND_OF_TIME;
+        let ra = rounding
+            .div(self.ra, MAS_PER_CENTISECOND_OF_TIME)
+            .rem_euclid(centiseconds_per_day);
+        let (h, m, s, cs) = (ra / 360_000, ra / 6_000 % 60, ra / 100 % 60, ra % 100);
+
+        // SS: declination in 1/10 arcsec
+        let decl = rounding.div(self.decl, MAS_PER_DECIARCSEC);
+        let sign = if decl < 0 { '-' } else { '+' };
+        let decl = decl.abs();
+        let (d, dm, ds, dds) = (decl / 36_000, decl / 600 % 60, decl / 10 % 60, decl % 10);
+
+        format!("{h:02}:{m:02}:{s:02}.{cs:02} {sign}{d:02}:{dm:02}:{ds:02}.{dds}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounding_test() {
+        // Arrange
+        let values = [2.5, 3.5, -2.5, 2.4, -2.6];
+
+        // Act
+        let away: Vec<i64> = values
+            .iter()
+            .map(|&v| Rounding::HalfAwayFromZero.round(v))
+            .collect();
+        let even: Vec<i64> = values
+            .iter()
+            .map(|&v| Rounding::HalfEven.round(v))
+            .collect();
+        let zero: Vec<i64> = values
+            .iter()
+            .map(|&v| Rounding::TowardZero.round(v))
+            .collect();
+
+        // Assert
+        assert_eq!(vec![3, 4, -3, 2, -3], away);
+        assert_eq!(vec![2, 4, -2, 2, -3], even);
+        assert_eq!(vec![2, 3, -2, 2, -2], zero);
+    }
+
+    #[test]
+    fn rounding_div_test() {
+        // Arrange
+        let values = [(25, 10), (35, 10), (-25, 10), (24, 10), (-26, 10)];
+
+        // Act
+        let away: Vec<i64> = values
+            .iter()
+            .map(|&(n, d)| Rounding::HalfAwayFromZero.div(n, d))
+            .collect();
+        let even: Vec<i64> = values
+            .iter()
+            .map(|&(n, d)| Rounding::HalfEven.div(n, d))
+            .collect();
+
+        // Assert
+        assert_eq!(vec![3, 4, -3, 2, -3], away);
+        assert_eq!(vec![2, 4, -2, 2, -3], even);
+    }
+
+    #[test]
+    fn fixed_equatorial_test() {
+        // Arrange
+
+        // SS: Meeus, example 21.b, theta Persei
+        let ra = 41.054063;
+        let decl = 49.227750;
+
+        // Act
+        let fixed = FixedEquatorial::new(ra, decl, Rounding::HalfAwayFromZero);
+
+        // Assert
+        assert_eq!(147_794_627, fixed.ra);
+        assert_eq!(177_219_900, fixed.decl);
+        assert_eq!("02:44:12.98 +49:13:39.9", fixed.to_aavso());
+    }
+
+    #[test]
+    fn fixed_equatorial_wrap_test() {
+        // Arrange
+        let ra = 359.999_999_9;
+        let decl = -0.000_01;
+
+        // Act
+        let fixed = FixedEquatorial::new(ra, decl, Rounding::HalfAwayFromZero);
+
+        // Assert
+        assert_eq!(0, fixed.ra);
+        assert_eq!(-36, fixed.decl);
+        assert_eq!("00:00:00.00 +00:00:00.0", fixed.to_aavso());
+    }
+}
@@ -3,6 +3,7 @@
 //! coordinates is due to parallax.
 
 use crate::util::degrees::Degrees;
+use crate::util::math;
 use crate::util::radians::Radians;
 
 /// Calculate the corrections needed to convert from geographical observer
@@ -16,10 +17,11 @@ pub(crate) fn rho_phi_prime(latitude_geocentric: Degrees, height: f64) -> (f64,
 
     const B_OVER_A: f64 = 0.996_647_19;
 
-    let u = (B_OVER_A * phi_p_radians.0.tan()).atan();
+    let u = math::atan(B_OVER_A * math::tan(phi_p_radians.0));
 
-    let rho_sin_phi_p = B_OVER_A * u.sin() + height / (6_378_140.0) * phi_p_radians.0.sin();
-    let rho_cos_phi_p = u.cos() + height / (6_378_140.0) * phi_p_radians.0.cos();
+    let rho_sin_phi_p =
+        B_OVER_A * math::sin(u) + height / (6_378_140.0) * math::sin(phi_p_radians.0);
+    let rho_cos_phi_p = math::cos(u) + height / (6_378_140.0) * math::cos(phi_p_radians.0);
 
     (rho_sin_phi_p, rho_cos_phi_p)
 }
@@ -29,6 +31,7 @@ mod tests {
     use super::*;
     use crate::date::date::Date;
     use crate::date::jd::JD;
+    use crate::util::{declination::Declination, right_ascension::RightAscension};
     use crate::{constants, coordinates};
     use assert_approx_eq::assert_approx_eq;
 
@@ -60,8 +63,8 @@ mod tests {
         let latitude_observer = Degrees::from_dms(33, 21, 22.0);
         let palomar_height_above_sea = 1706.0;
 
-        let ra_geocentric_mars = Degrees::from_hms(22, 38, 7.25);
-        let decl_geocentric_mars = Degrees::from_dms(-15, 46, 15.9);
+        let ra_geocentric_mars = RightAscension::from_hms(22, 38, 7.25);
+        let decl_geocentric_mars = Declination::from_dms(-15, 46, 15.9);
         let distance_mars = 0.37276 * constants::AU;
 
         let (ra_topocentric_mars, decl_topocentric_mars) = coordinates::equatorial_2_topocentric(
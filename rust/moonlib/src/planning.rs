@@ -0,0 +1,516 @@
+//! Observation planning: when to look at the Moon.
+//!
+//! The best time to observe the Moon at night is when it is highest in the
+//! sky while the sky is dark, i.e. the Sun is more than 6 degrees below the
+//! horizon. Over one night, the Moon's altitude peaks at its transit, so the
+//! best time is either the transit, if it falls into the dark part of the
+//! night, or the begin or end of darkness.
+//!
+//! Equatorial mounts on a pier, e.g. German equatorial mounts, have to flip
+//! the telescope to the other side of the pier when the Moon crosses the
+//! meridian. The Moon's hour angle, the local siderial time minus its
+//! topocentric right ascension, tells which side the telescope is on.
+
+use crate::config::MoonlibConfig;
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::moon::position::topocentric_coordinates;
+use crate::moon::rise_set_transit::{next_transit, OutputKind};
+use crate::session::Observer;
+use crate::sun::twilight::{self, Twilight};
+use crate::util::degrees::Degrees;
+use crate::{moon, sun};
+
+/// Darkness starts and ends when the Sun is this far below the horizon
+const DARKNESS: Twilight = Twilight::Civil;
+
+/// Time between samples of the hour angle, in hours. It increases by about
+/// 14.5 degrees an hour
+const HOUR_ANGLE_SAMPLE_STEP: f64 = 1.0;
+
+/// Stop refining a meridian crossing at this precision, in days, about a
+/// second
+const PRECISION: f64 = 1e-5;
+
+#[derive(Debug, Copy, Clone)]
+pub struct BestMoonTime {
+    /// Julian day, in dynamical time
+    pub jd: JD,
+
+    /// Apparent altitude of the Moon, in degrees [0, 90)
+    pub altitude: Degrees,
+
+    /// Fraction of the Moon's disk illuminated, [0, 1]
+    pub illuminated_fraction: f64,
+}
+
+/// Side of the pier the telescope of an equatorial mount is on
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PierSide {
+    /// Telescope on the east side of the pier, pointing west of the
+    /// meridian, hour angle from 0h to 12h
+    East,
+
+    /// Telescope on the west side of the pier, pointing east of the
+    /// meridian, hour angle from 12h to 24h
+    West,
+}
+
+impl PierSide {
+    /// Side of the pier for an hour angle
+    /// In: Local hour angle, in degrees
+    pub fn from_hour_angle(hour_angle: Degrees) -> Self {
+        if hour_angle.map_to_0_to_360().0 < 180.0 {
+            PierSide::East
+        } else {
+            PierSide::West
+        }
+    }
+}
+
+/// Times the Moon crosses the meridian, when the mount flips the telescope
+#[derive(Debug, Copy, Clone)]
+pub struct MeridianFlips {
+    /// Julian day of the upper transit, in dynamical time. The Moon is due
+    /// south from the northern hemisphere
+    pub transit: Option<JD>,
+
+    /// Julian day of the lower transit, in dynamical time, 12 hours of hour
+    /// angle later
+    pub anti_transit: Option<JD>,
+}
+
+/// The Moon as seen by an equatorial mount at one time
+#[derive(Debug, Copy, Clone)]
+pub struct MountSample {
+    /// Julian day, in dynamical time
+    pub jd: JD,
+
+    /// Topocentric local hour angle, in degrees [-180, 180). Negative east
+    /// of the meridian
+    pub hour_angle: Degrees,
+
+    /// Apparent altitude of the Moon, in degrees [-90, 90)
+    pub altitude: Degrees,
+
+    pub pier_side: PierSide,
+}
+
+/// Best time to observe the Moon tonight, i.e. when it is highest in the sky
+/// while the Sun is more than 6 degrees below the horizon.
+/// In:
+/// observer: Observer's location and atmospheric conditions
+/// date: Observer's local date, in dynamical time. The night starts in the
+/// evening of this day
+/// Out: Time, altitude and illumination of the Moon. None if the sky does
+/// not get dark, or the Moon stays below the horizon while it is dark
+pub fn best_moon_time(observer: &Observer, date: Date) -> Option<BestMoonTime> {
+    let (start, end) = darkness(observer, date)?;
+
+    // SS: the altitude peaks at the transit, otherwise it is highest at the
+    // begin or end of darkness
+    let mut candidates = vec![start, end];
+    if let OutputKind::Time(transit) = next_transit(start, observer) {
+        if transit < end {
+            candidates.push(transit);
+        }
+    }
+
+    candidates
+        .into_iter()
+        .map(|jd| {
+            (
+                jd,
+                topocentric_coordinates(jd, observer, 0.0).apparent_altitude,
+            )
+        })
+        .max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+        .filter(|(_, altitude)| altitude.0 > 0.0)
+        .map(|(jd, altitude)| BestMoonTime {
+            jd,
+            altitude,
+            illuminated_fraction: moon::phase::fraction_illuminated(jd),
+        })
+}
+
+/// Times the Moon crosses the meridian and the anti-meridian in the night
+/// following a date
+/// In:
+/// observer: Observer's location
+/// date: Observer's local date, in dynamical time. The night lasts from
+/// local noon of this day to local noon of the next day
+/// Out: Times of the meridian crossings. The Moon crosses each meridian
+/// every 24h 50m, so it misses one of them on some days
+pub fn meridian_flip_times(observer: &Observer, date: Date) -> MeridianFlips {
+    let (start, end) = night(observer, date);
+    MeridianFlips {
+        transit: hour_angle_crossing(observer, start, end, Degrees::new(0.0)),
+        anti_transit: hour_angle_crossing(observer, start, end, Degrees::new(180.0)),
+    }
+}
+
+/// The Moon's hour angle, altitude and the side of the pier over the night
+/// following a date, e.g. to plot the altitude with the meridian flips
+/// In:
+/// observer: Observer's location and atmospheric conditions
+/// date: Observer's local date, in dynamical time. The night lasts from
+/// local noon of this day to local noon of the next day
+/// step: Time between samples, in minutes
+/// Out: Samples from local noon to the next local noon, in time order
+pub fn mount_track(observer: &Observer, date: Date, step: f64) -> Vec<MountSample> {
+    let (start, end) = night(observer, date);
+    let samples = ((end.jd - start.jd) * 24.0 * 60.0 / step).floor() as usize;
+    (0..=samples)
+        .map(|i| {
+            let mut jd = start;
+            jd.add_hours(i as f64 * step / 60.0);
+            let topocentric = topocentric_coordinates(jd, observer, 0.0);
+            MountSample {
+                jd,
+                hour_angle: topocentric.hour_angle.signed_difference(Degrees::new(0.0)),
+                altitude: topocentric.apparent_altitude,
+                pier_side: PierSide::from_hour_angle(topocentric.hour_angle),
+            }
+        })
+        .collect()
+}
+
+/// Time the Moon's topocentric hour angle passes a value
+/// In:
+/// observer: Observer's location
+/// start, end: Julian days to search between, in dynamical time
+/// target: hour angle, in degrees
+/// Out: Julian day of the first crossing, in dynamical time. None if the
+/// hour angle does not pass target
+fn hour_angle_crossing(observer: &Observer, start: JD, end: JD, target: Degrees) -> Option<JD> {
+    let difference = |jd: JD| {
+        topocentric_coordinates(jd, observer, 0.0)
+            .hour_angle
+            .signed_difference(target)
+            .0
+    };
+
+    let mut a = start;
+    let mut difference_a = difference(a);
+    while a < end {
+        let mut b = a;
+        b.add_hours(HOUR_ANGLE_SAMPLE_STEP);
+        if b > end {
+            b = end;
+        }
+        let difference_b = difference(b);
+
+        // SS: the difference also jumps from 180 to -180 degrees 12 hours
+        // after the crossing
+        if difference_a < 0.0 && difference_b >= 0.0 && difference_b - difference_a < 90.0 {
+            let (mut a, mut b) = (a.jd, b.jd);
+            while b - a > PRECISION {
+                let mid = 0.5 * (a + b);
+                if difference(JD::new(mid)) < 0.0 {
+                    a = mid;
+                } else {
+                    b = mid;
+                }
+            }
+            return Some(JD::new(0.5 * (a + b)));
+        }
+
+        a = b;
+        difference_a = difference_b;
+    }
+
+    None
+}
+
+/// From local noon of a date to local noon of the next day
+/// In:
+/// observer: Observer's time zone offset
+/// date: Observer's local date, in dynamical time
+/// Out: Julian days of the two noons, in dynamical time
+fn night(observer: &Observer, date: Date) -> (JD, JD) {
+    let mut noon = JD::from_date(Date::new(date.year, date.month, date.day.trunc() + 0.5));
+    noon.add_hours(-observer.timezone_offset as f64);
+    (noon, JD::new(noon.jd + 1.0))
+}
+
+/// Dark part of the night following a date
+/// In:
+/// observer: Observer's location
+/// date: Observer's local date, in dynamical time
+/// Out: Begin and end of darkness, in dynamical time. None if the Sun does
+/// not get 6 degrees below the horizon
+fn darkness(observer: &Observer, date: Date) -> Option<(JD, JD)> {
+    let (noon, next_noon) = night(observer, date);
+
+    let config = MoonlibConfig::new();
+    let dusk = twilight::dusk(
+        noon,
+        observer.timezone_offset,
+        DARKNESS,
+        observer.longitude,
+        observer.latitude,
+        &config,
+    );
+    let dawn = twilight::dawn(
+        next_noon,
+        observer.timezone_offset,
+        DARKNESS,
+        observer.longitude,
+        observer.latitude,
+        &config,
+    );
+
+    // SS: close to the polar circles, the Sun may not cross the twilight
+    // altitude on one of the days, then darkness lasts until noon
+    let start = match dusk {
+        OutputKind::Time(jd) => jd,
+        _ => noon,
+    };
+    let end = match dawn {
+        OutputKind::Time(jd) => jd,
+        _ => next_noon,
+    };
+
+    let midnight = JD::new(0.5 * (start.jd + end.jd));
+    let (_, sun_altitude) =
+        sun::position::horizontal_coordinates(midnight, observer.longitude, observer.latitude);
+    if start < end && sun_altitude.0 < DARKNESS.altitude().0 {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::test_util::{munich, tromso};
+
+    fn longyearbyen() -> Observer {
+        // SS: Longyearbyen, Svalbard, 12 deg north of the polar circle
+        Observer::new(-15.65, 78.22, 0, 1013.0, 10.0)
+    }
+
+    /// Highest apparent altitude of the Moon during darkness, sampled every
+    /// 5 minutes
+    fn sampled_maximum(observer: &Observer, date: Date) -> Option<f64> {
+        let (start, end) = darkness(observer, date)?;
+        let samples = ((end.jd - start.jd) * 288.0) as usize;
+        (0..=samples)
+            .map(|i| {
+                let jd = JD::new(start.jd + (end.jd - start.jd) * i as f64 / samples as f64);
+                topocentric_coordinates(jd, observer, 0.0)
+                    .apparent_altitude
+                    .0
+            })
+            .max_by(f64::total_cmp)
+    }
+
+    #[test]
+    fn full_moon_test() {
+        // Arrange
+
+        // SS: full moon on March 20th, 2000, the Moon transits around midnight
+        let observer = munich(0);
+        let date = Date::new(2000, 3, 19.0);
+
+        // Act
+        let best = best_moon_time(&observer, date).unwrap();
+
+        // Assert
+        let (start, end) = darkness(&observer, date).unwrap();
+        let transit = match next_transit(start, &observer) {
+            OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        };
+        assert_eq!(transit, best.jd);
+        assert!(start < best.jd && best.jd < end);
+        assert!(best.illuminated_fraction > 0.95);
+
+        // SS: at transit, the altitude is 90 deg - latitude + declination
+        let topocentric = topocentric_coordinates(best.jd, &observer, 0.0);
+        assert_approx_eq!(
+            90.0 - observer.latitude.0 + topocentric.declination.0,
+            best.altitude.0,
+            0.1
+        );
+    }
+
+    #[test]
+    fn sampled_test() {
+        // Arrange
+        let observer = munich(0);
+
+        for day in 1..=30 {
+            let date = Date::new(2000, 4, day as f64);
+
+            // Act
+            let best = best_moon_time(&observer, date);
+
+            // Assert
+            let maximum = sampled_maximum(&observer, date).unwrap();
+            match best {
+                Some(best) => assert!(best.altitude.0 >= maximum - 0.01, "day {day}"),
+                None => assert!(maximum < 0.01, "day {day}"),
+            }
+        }
+    }
+
+    #[test]
+    fn no_darkness_test() {
+        // Arrange
+
+        // SS: midnight sun
+        let date = Date::new(2022, 6, 21.0);
+
+        // Act
+        let best = best_moon_time(&tromso(0), date);
+
+        // Assert
+        assert!(best.is_none());
+    }
+
+    #[test]
+    fn polar_night_test() {
+        // Arrange
+
+        // SS: polar night, the Sun does not rise above -6 deg around noon
+        // either, so it is dark from noon to noon
+        let observer = longyearbyen();
+        let date = Date::new(2022, 12, 21.0);
+
+        // Act
+        let (start, end) = darkness(&observer, date).unwrap();
+
+        // Assert
+        assert_approx_eq!(1.0, end.jd - start.jd, 1e-9);
+    }
+
+    #[test]
+    fn meridian_flip_times_test() {
+        // Arrange
+
+        // SS: full moon on March 20th, 2000, the Moon transits around
+        // midnight and crosses the anti-meridian around noon
+        let observer = munich(0);
+        let date = Date::new(2000, 3, 19.0);
+
+        // Act
+        let flips = meridian_flip_times(&observer, date);
+
+        // Assert
+        let transit = flips.transit.unwrap();
+        let anti_transit = flips.anti_transit.unwrap();
+        let hour_angle = |jd: JD| topocentric_coordinates(jd, &observer, 0.0).hour_angle;
+        assert_approx_eq!(
+            0.0,
+            hour_angle(transit).signed_difference(Degrees::new(0.0)).0,
+            1e-3
+        );
+        assert_approx_eq!(
+            0.0,
+            hour_angle(anti_transit)
+                .signed_difference(Degrees::new(180.0))
+                .0,
+            1e-3
+        );
+
+        // SS: the transit of Meeus, chapter 15, uses the geocentric right
+        // ascension, the parallax in right ascension moves it by minutes
+        let geocentric = match next_transit(JD::new(transit.jd - 0.1), &observer) {
+            OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        };
+        assert_approx_eq!(geocentric.jd, transit.jd, 5.0 / (24.0 * 60.0));
+
+        // SS: half a lunar day apart
+        assert_approx_eq!(0.5175, (anti_transit.jd - transit.jd).abs(), 0.01);
+    }
+
+    #[test]
+    fn meridian_flip_times_missed_test() {
+        // Arrange
+        let observer = munich(0);
+
+        // Act
+        let flips: Vec<MeridianFlips> = (1..=30)
+            .map(|day| meridian_flip_times(&observer, Date::new(2000, 4, day as f64)))
+            .collect();
+
+        // Assert
+
+        // SS: in 30 days, the Moon crosses each meridian 29 times
+        let transits = flips.iter().filter(|flips| flips.transit.is_some()).count();
+        let anti_transits = flips
+            .iter()
+            .filter(|flips| flips.anti_transit.is_some())
+            .count();
+        assert_eq!(29, transits);
+        assert_eq!(29, anti_transits);
+    }
+
+    #[test]
+    fn mount_track_test() {
+        // Arrange
+        let observer = munich(0);
+        let date = Date::new(2000, 3, 19.0);
+
+        // Act
+        let track = mount_track(&observer, date, 10.0);
+
+        // Assert
+        assert_eq!(24 * 6 + 1, track.len());
+        let flips = meridian_flip_times(&observer, date);
+        let transit = flips.transit.unwrap();
+        for sample in &track {
+            assert!(-180.0 <= sample.hour_angle.0 && sample.hour_angle.0 < 180.0);
+            let expected = if sample.hour_angle.0 < 0.0 {
+                PierSide::West
+            } else {
+                PierSide::East
+            };
+            assert_eq!(expected, sample.pier_side);
+        }
+
+        // SS: the telescope is on the west side of the pier before the
+        // transit and on the east side after it
+        let before = track
+            .iter()
+            .rev()
+            .find(|sample| sample.jd < transit)
+            .unwrap();
+        let after = track.iter().find(|sample| sample.jd > transit).unwrap();
+        assert_eq!(PierSide::West, before.pier_side);
+        assert_eq!(PierSide::East, after.pier_side);
+
+        // SS: the altitude peaks at the transit
+        let highest = track
+            .iter()
+            .max_by(|a, b| a.altitude.0.total_cmp(&b.altitude.0))
+            .unwrap();
+        assert_approx_eq!(transit.jd, highest.jd.jd, 10.0 / (24.0 * 60.0));
+    }
+
+    #[test]
+    fn pier_side_test() {
+        // Act
+        let sides = [0.0, 90.0, 179.9, 180.0, 270.0, 359.9, -10.0]
+            .map(|hour_angle| PierSide::from_hour_angle(Degrees::new(hour_angle)));
+
+        // Assert
+        assert_eq!(
+            [
+                PierSide::East,
+                PierSide::East,
+                PierSide::East,
+                PierSide::West,
+                PierSide::West,
+                PierSide::West,
+                PierSide::West,
+            ],
+            sides
+        );
+    }
+}
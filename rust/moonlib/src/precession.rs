@@ -0,0 +1,276 @@
+//! Precession of equatorial and ecliptical coordinates
+
+use crate::date::epoch::{Centuries, Days, Epoch};
+use crate::date::jd::JD;
+use crate::nutation;
+use crate::util::math;
+use crate::util::{
+    arcsec::ArcSec, declination::Declination, degrees::Degrees, radians::Radians,
+    right_ascension::RightAscension,
+};
+
+/// Equinox the equatorial or ecliptical coordinates are referred to. For
+/// ecliptical coordinates, this is also the ecliptic they are referred to,
+/// i.e. the ecliptic of date or the ecliptic of J2000.0
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Equinox {
+    /// True equinox of the date, i.e. apparent coordinates
+    OfDate,
+
+    /// Mean equinox of J2000.0, as used by star catalogs, planetarium
+    /// software like Stellarium and the DE ephemerides
+    J2000,
+}
+
+/// Precess equatorial coordinates from one epoch to another, using the
+/// rigorous method.
+/// Meeus, chapter 21, page 134, eqs. (21.2) and (21.4)
+/// In:
+/// ra: Right ascension, referred to the mean equinox of jd0, in degrees [0, 360)
+/// decl: Declination, referred to the mean equinox of jd0, in degrees [-90, 90]
+/// jd0: Julian Day of the initial epoch, in dynamical time
+/// jd: Julian Day of the final epoch, in dynamical time
+/// Out:
+/// right ascension, referred to the mean equinox of jd, in degrees [0, 360)
+/// declination, referred to the mean equinox of jd, in degrees [-90, 90]
+pub(crate) fn precess_equatorial(
+    ra: RightAscension,
+    decl: Declination,
+    jd0: JD,
+    jd: JD,
+) -> (RightAscension, Declination) {
+    let t_capital = Centuries::from(jd0.since(Epoch::J2000)).0;
+    let t_capital2 = t_capital * t_capital;
+
+    let t = Centuries::from(Days(jd.jd - jd0.jd)).0;
+    let t2 = t * t;
+    let t3 = t * t2;
+
+    // SS: eq. (21.2), in arcsec
+    let c = 2306.2181 + 1.39656 * t_capital - 0.000139 * t_capital2;
+    let zeta = c * t + (0.30188 - 0.000344 * t_capital) * t2 + 0.017998 * t3;
+    let z = c * t + (1.09468 + 0.000066 * t_capital) * t2 + 0.018203 * t3;
+    let theta = (2004.3109 - 0.85330 * t_capital - 0.000217 * t_capital2) * t
+        - (0.42665 + 0.000217 * t_capital) * t2
+        - 0.041833 * t3;
+
+    let zeta = Radians::from(ArcSec::new(zeta));
+    let z = Radians::from(ArcSec::new(z));
+    let theta = Radians::from(ArcSec::new(theta));
+
+    let ra_radians = Radians::from(ra);
+    let decl_radians = Radians::from(decl);
+
+    // SS: eq. (21.4)
+    let a = math::cos(decl_radians.0) * math::sin(ra_radians.0 + zeta.0);
+    let b = math::cos(theta.0) * math::cos(decl_radians.0) * math::cos(ra_radians.0 + zeta.0)
+        - math::sin(theta.0) * math::sin(decl_radians.0);
+    let c = math::sin(theta.0) * math::cos(decl_radians.0) * math::cos(ra_radians.0 + zeta.0)
+        + math::cos(theta.0) * math::sin(decl_radians.0);
+
+    let ra_precessed = Radians::new(math::atan2(a, b) + z.0);
+    let decl_precessed = Radians::new(math::asin(c));
+
+    (
+        RightAscension::new(Degrees::from(ra_precessed)),
+        Declination::new(Degrees::from(decl_precessed)),
+    )
+}
+
+/// Precess equatorial coordinates referred to the mean equinox of the date
+/// to the mean equinox of J2000.0.
+/// In:
+/// ra: Right ascension, mean equinox of date, in degrees [0, 360)
+/// decl: Declination, mean equinox of date, in degrees [-90, 90]
+/// jd: Julian Day, in dynamical time
+/// Out:
+/// right ascension, mean equinox of J2000.0, in degrees [0, 360)
+/// declination, mean equinox of J2000.0, in degrees [-90, 90]
+pub(crate) fn equatorial_of_date_2_j2000(
+    ra: RightAscension,
+    decl: Declination,
+    jd: JD,
+) -> (RightAscension, Declination) {
+    precess_equatorial(ra, decl, jd, Epoch::J2000.jd())
+}
+
+/// Precess ecliptical coordinates from one epoch to another, using the
+/// rigorous method.
+/// Meeus, chapter 21, page 136, eqs. (21.5) and (21.7)
+/// In:
+/// lambda: longitude, referred to the mean equinox and ecliptic of jd0, in
+/// degrees [0, 360)
+/// beta: latitude, referred to the ecliptic of jd0, in degrees [-90, 90]
+/// jd0: Julian Day of the initial epoch, in dynamical time
+/// jd: Julian Day of the final epoch, in dynamical time
+/// Out:
+/// longitude, referred to the mean equinox and ecliptic of jd, in degrees [0, 360)
+/// latitude, referred to the ecliptic of jd, in degrees [-90, 90]
+pub(crate) fn precess_ecliptical(
+    lambda: Degrees,
+    beta: Degrees,
+    jd0: JD,
+    jd: JD,
+) -> (Degrees, Degrees) {
+    let t_capital = Centuries::from(jd0.since(Epoch::J2000)).0;
+    let t_capital2 = t_capital * t_capital;
+
+    let t = Centuries::from(Days(jd.jd - jd0.jd)).0;
+    let t2 = t * t;
+    let t3 = t * t2;
+
+    // SS: eq. (21.5), in arcsec, except for the constant term of pi
+    let eta = (47.0029 - 0.06603 * t_capital + 0.000598 * t_capital2) * t
+        + (-0.03302 + 0.000598 * t_capital) * t2
+        + 0.000060 * t3;
+    let pi = 3289.4789 * t_capital + 0.60622 * t_capital2 - (869.8089 + 0.50491 * t_capital) * t
+        + 0.03536 * t2;
+    let p = (5029.0966 + 2.22226 * t_capital - 0.000042 * t_capital2) * t
+        + (1.11113 - 0.000042 * t_capital) * t2
+        - 0.000006 * t3;
+
+    let eta = Radians::from(ArcSec::new(eta));
+    let pi = Radians::from(Degrees::new(174.876384) + Degrees::from(ArcSec::new(pi)));
+    let p = Radians::from(ArcSec::new(p));
+
+    let lambda_radians = Radians::from(lambda);
+    let beta_radians = Radians::from(beta);
+
+    // SS: eq. (21.7)
+    let a = math::cos(eta.0) * math::cos(beta_radians.0) * math::sin(pi.0 - lambda_radians.0)
+        - math::sin(eta.0) * math::sin(beta_radians.0);
+    let b = math::cos(beta_radians.0) * math::cos(pi.0 - lambda_radians.0);
+    let c = math::cos(eta.0) * math::sin(beta_radians.0)
+        + math::sin(eta.0) * math::cos(beta_radians.0) * math::sin(pi.0 - lambda_radians.0);
+
+    let lambda_precessed = Radians::new(p.0 + pi.0 - math::atan2(a, b));
+    let beta_precessed = Radians::new(math::asin(c));
+
+    (
+        Degrees::from(lambda_precessed).map_to_0_to_360(),
+        Degrees::from(beta_precessed),
+    )
+}
+
+/// Precess ecliptical coordinates referred to the mean equinox and ecliptic
+/// of the date to the mean equinox and ecliptic of J2000.0.
+/// In:
+/// lambda: longitude, mean equinox of date, in degrees [0, 360)
+/// beta: latitude, ecliptic of date, in degrees [-90, 90]
+/// jd: Julian Day, in dynamical time
+/// Out:
+/// longitude, mean equinox of J2000.0, in degrees [0, 360)
+/// latitude, ecliptic of J2000.0, in degrees [-90, 90]
+pub(crate) fn ecliptical_of_date_2_j2000(
+    lambda: Degrees,
+    beta: Degrees,
+    jd: JD,
+) -> (Degrees, Degrees) {
+    precess_ecliptical(lambda, beta, jd, Epoch::J2000.jd())
+}
+
+/// Refer apparent ecliptical coordinates, i.e. referred to the true equinox
+/// and the ecliptic of date, to an equinox.
+/// In:
+/// lambda: apparent longitude, in degrees [0, 360)
+/// beta: apparent latitude, in degrees [-90, 90]
+/// jd: Julian Day, in dynamical time
+/// equinox: Equinox to refer the coordinates to. For J2000, the effect of
+/// nutation is removed and the coordinates are precessed to the mean
+/// equinox and ecliptic of J2000.0
+/// Out:
+/// longitude, in degrees [0, 360)
+/// latitude, in degrees [-90, 90]
+pub(crate) fn apparent_ecliptical_2_equinox(
+    lambda: Degrees,
+    beta: Degrees,
+    jd: JD,
+    equinox: Equinox,
+) -> (Degrees, Degrees) {
+    match equinox {
+        Equinox::OfDate => (lambda, beta),
+        Equinox::J2000 => {
+            // SS: referred to the mean equinox of the date, i.e. without nutation
+            let nutation_delta = Degrees::from(nutation::nutation_in_longitude(jd));
+            ecliptical_of_date_2_j2000(lambda - nutation_delta, beta, jd)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn precess_equatorial_test() {
+        // Meeus, page 135, example 21.b, theta Persei
+
+        // Arrange
+        let ra = RightAscension::new(Degrees::new(41.054063));
+        let decl = Declination::new(Degrees::new(49.227750));
+        let jd = JD::new(2_462_088.69);
+
+        // Act
+        let (ra_precessed, decl_precessed) = precess_equatorial(ra, decl, Epoch::J2000.jd(), jd);
+
+        // Assert
+        assert_approx_eq!(41.547214, ra_precessed.0, 0.000_001);
+        assert_approx_eq!(49.348483, decl_precessed.0, 0.000_001);
+    }
+
+    #[test]
+    fn equatorial_of_date_2_j2000_test() {
+        // Meeus, page 135, example 21.b, theta Persei, in reverse
+
+        // Arrange
+        let ra = RightAscension::new(Degrees::new(41.547214));
+        let decl = Declination::new(Degrees::new(49.348483));
+        let jd = JD::new(2_462_088.69);
+
+        // Act
+        let (ra_j2000, decl_j2000) = equatorial_of_date_2_j2000(ra, decl, jd);
+
+        // Assert
+        assert_approx_eq!(41.054063, ra_j2000.0, 0.000_001);
+        assert_approx_eq!(49.227750, decl_j2000.0, 0.000_001);
+    }
+
+    #[test]
+    fn precess_ecliptical_test() {
+        // Meeus, page 137, example 21.c, Venus at -214 June 30.0
+
+        // Arrange
+        let lambda = Degrees::new(149.48194);
+        let beta = Degrees::new(1.76549);
+        let jd = JD::new(1_643_074.5);
+
+        // Act
+        let (lambda_precessed, beta_precessed) =
+            precess_ecliptical(lambda, beta, Epoch::J2000.jd(), jd);
+
+        // Assert
+        assert_approx_eq!(118.704, lambda_precessed.0, 0.001);
+        assert_approx_eq!(1.615, beta_precessed.0, 0.001);
+    }
+
+    #[test]
+    fn ecliptical_of_date_2_j2000_test() {
+        // Arrange
+        let lambda = Degrees::new(123.4);
+        let beta = Degrees::new(-4.5);
+        let jd = JD::new(2_462_088.69);
+
+        // Act
+        let (lambda_j2000, beta_j2000) = ecliptical_of_date_2_j2000(lambda, beta, jd);
+        let (lambda_of_date, beta_of_date) =
+            precess_ecliptical(lambda_j2000, beta_j2000, Epoch::J2000.jd(), jd);
+
+        // Assert
+
+        // SS: general precession of about 50.3" per year, for 28.7 years
+        assert_approx_eq!(lambda.0 - 50.3 * 28.7 / 3600.0, lambda_j2000.0, 0.005);
+        assert_approx_eq!(lambda.0, lambda_of_date.0, 1e-9);
+        assert_approx_eq!(beta.0, beta_of_date.0, 1e-9);
+    }
+}
@@ -1,8 +1,48 @@
 //! Refraction-related calculation
+//!
+//! A pressure of 0 millibars or less disables the refraction, i.e. the
+//! corrections are exactly 0, for geometric altitudes or an observer
+//! without atmosphere. RefractionModel::None uses this.
+//!
+//! Meeus' eqs (16.3) and (16.4) are not exact inverses of each other, they
+//! disagree by up to 4" close to the horizon. Code that converts an
+//! altitude back and forth, e.g. for rise and set, uses
+//! exact_refraction_from_apparent_altitude, the inverse of (16.4), so both
+//! directions agree.
 
 use crate::util::degrees::Degrees;
+use crate::util::math;
 use crate::util::radians::Radians;
 
+/// Direction of the refraction correction. The true altitude is the one
+/// computed from the coordinates, the apparent one the altitude observed
+/// through the atmosphere.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Refraction {
+    /// Correct a true altitude, i.e. calculate the apparent altitude
+    TrueToApparent,
+
+    /// Correct an apparent altitude, i.e. calculate the true altitude
+    ApparentToTrue,
+}
+
+/// Refraction at the horizon included in the standard altitudes of rise and
+/// set, 34', Meeus, chapter 15, page 101
+pub(crate) const HORIZON_REFRACTION: f64 = 34.0 / 60.0;
+
+/// Whether the atmospheric conditions disable the refraction
+/// In: pressure, in millibars
+/// Out: true for a pressure of 0 or less
+pub(crate) fn is_disabled(pressure: f64) -> bool {
+    pressure <= 0.0
+}
+
+/// Factor for pressure and temperature different from 1010 millibars and
+/// 10 celsius. Meeus, chapter 16
+fn atmospheric_factor(pressure: f64, temperature: f64) -> f64 {
+    pressure / 1010.0 * 283.0 / (273.0 + temperature)
+}
+
 /// Given the true altitude of an object and atmospheric conditions,
 /// calculate the refraction, i.e. the correction in altitude to get the
 /// apparent altitude. To do so, add the value returned to the true
@@ -10,35 +50,150 @@ use crate::util::radians::Radians;
 /// Meeus, chapter 16, page 106
 /// In:
 /// altitude, in degrees [0, 90)
-/// pressure: atmospheric pressure, in millibars
+/// pressure: atmospheric pressure, in millibars, 0 or less for no refraction
+/// temperature, in celsius
+/// Out:
+/// Correction for altitude, in degrees [0, 360)
+pub fn refraction_for_true_altitude(altitude: Degrees, pressure: f64, temperature: f64) -> Degrees {
+    if is_disabled(pressure) {
+        return Degrees::new(0.0);
+    }
+
+    let refraction =
+        true_altitude_refraction(altitude.0) * atmospheric_factor(pressure, temperature);
+
+    // SS: refraction is in minutes of arc
+    let refraction_degrees = refraction / 60.0;
+
+    Degrees::new(refraction_degrees)
+}
+
+/// Refraction of eq (16.4) for 1010 millibars and 10 celsius
+/// In: true altitude, in degrees
+/// Out: Refraction, in minutes of arc
+fn true_altitude_refraction(altitude: f64) -> f64 {
+    // SS: not sure where this constant comes from, taken from PJ Naughter's Astronomical Algorithms.
+    // It is where the argument of the cotangent is smallest, i.e. where the
+    // refraction is largest
+    let h = altitude.max(-1.9006387000003735);
+
+    // SS: equ (16.4), with the constant to make R = 0 in the zenith
+    1.02 / math::tan((Radians::from(Degrees::new(h + 10.3 / (h + 5.11)))).0) + 0.0019279
+}
+
+/// Derivative of true_altitude_refraction with respect to the altitude
+/// In: true altitude, in degrees
+/// Out: Derivative, in minutes of arc per degree
+fn true_altitude_refraction_derivative(altitude: f64) -> f64 {
+    if altitude <= -1.9006387000003735 {
+        return 0.0;
+    }
+
+    let x = Radians::from(Degrees::new(altitude + 10.3 / (altitude + 5.11))).0;
+    let dx_dh = 1.0 - 10.3 / ((altitude + 5.11) * (altitude + 5.11));
+    let sin_x = math::sin(x);
+    -1.02 / (sin_x * sin_x) * dx_dh * std::f64::consts::PI / 180.0
+}
+
+/// Given the apparent altitude of an object and atmospheric conditions,
+/// calculate the refraction, i.e. the correction in altitude to get the
+/// true altitude. To do so, subtract the value returned from the apparent
+/// altitude of an object.
+/// This is Bennett's approximation, consistent with
+/// refraction_for_true_altitude to within 4" only, see
+/// exact_refraction_from_apparent_altitude.
+/// Meeus, chapter 16, page 106
+/// In:
+/// altitude, in degrees [0, 90)
+/// pressure: atmospheric pressure, in millibars, 0 or less for no refraction
 /// temperature, in celsius
 /// Out:
 /// Correction for altitude, in degrees [0, 360)
-pub(crate) fn refraction_for_true_altitude(
+pub fn refraction_from_apparent_altitude(
     altitude: Degrees,
     pressure: f64,
     temperature: f64,
 ) -> Degrees {
-    // SS: not sure where this constant comes from, taken from PJ Naughter's Astronomical Algorithms
-    let h = if altitude.0 <= -1.9006387000003735 {
-        Degrees::new(-1.9006387000003735)
-    } else {
-        altitude
-    };
-
-    // SS: equ (16.4)
-    let r = 1.02
-        / (Radians::from(Degrees::new(h.0 + 10.3 / (h.0 + 5.11))))
-            .0
-            .atan()
-        + 0.0019279;
-    let d = pressure / 1010.0 * 283.0 / (273.0 + temperature);
-    let refraction = r * d;
+    if is_disabled(pressure) {
+        return Degrees::new(0.0);
+    }
+
+    // SS: eq (16.3) grows without bounds close to h0 = -2.4 deg, keep the
+    // refraction constant below the horizon
+    let h0 = altitude.0.max(-1.0);
+
+    // SS: Bennett's eq (16.3), with the constant to make R = 0 in the zenith
+    let r = 1.0 / math::tan((Radians::from(Degrees::new(h0 + 7.31 / (h0 + 4.4)))).0) + 0.0013515;
+    let refraction = r * atmospheric_factor(pressure, temperature);
 
     // SS: refraction is in minutes of arc
-    let refraction_degrees = refraction / 60.0;
+    Degrees::new(refraction / 60.0)
+}
 
-    Degrees::new(refraction_degrees)
+/// Given the apparent altitude of an object and atmospheric conditions,
+/// calculate the refraction such that refraction_for_true_altitude gives
+/// back the apparent altitude, i.e. the exact inverse of eq (16.4). To do
+/// so, solve h + R(h) = h0 for the true altitude h with Newton's method,
+/// starting from Bennett's eq (16.3). Subtract the value returned from the
+/// apparent altitude of an object.
+/// In:
+/// altitude, in degrees [-90, 90)
+/// pressure: atmospheric pressure, in millibars, 0 or less for no refraction
+/// temperature, in celsius
+/// Out:
+/// Correction for altitude, in degrees
+pub fn exact_refraction_from_apparent_altitude(
+    altitude: Degrees,
+    pressure: f64,
+    temperature: f64,
+) -> Degrees {
+    if is_disabled(pressure) {
+        return Degrees::new(0.0);
+    }
+
+    let factor = atmospheric_factor(pressure, temperature) / 60.0;
+    let h0 = altitude.0;
+
+    let mut h = h0 - refraction_from_apparent_altitude(altitude, pressure, temperature).0;
+    for _ in 0..10 {
+        let f = h + factor * true_altitude_refraction(h) - h0;
+        let df = 1.0 + factor * true_altitude_refraction_derivative(h);
+        let dh = f / df;
+        h -= dh;
+
+        // SS: converges in 2 to 3 iterations
+        if dh.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    Degrees::new(h0 - h)
+}
+
+/// Correct an altitude for atmospheric refraction. This is the single place
+/// that decides which refraction formula is used. Both directions are based
+/// on eq (16.4), so correcting an altitude back and forth gives it back.
+/// In:
+/// altitude: true or apparent altitude, in degrees [-90, 90)
+/// direction: Direction of the correction
+/// pressure: atmospheric pressure, in millibars, 0 or less for no refraction
+/// temperature, in celsius
+/// Out:
+/// apparent or true altitude, in degrees [-90, 90)
+pub fn correct_altitude(
+    altitude: Degrees,
+    direction: Refraction,
+    pressure: f64,
+    temperature: f64,
+) -> Degrees {
+    match direction {
+        Refraction::TrueToApparent => {
+            altitude + refraction_for_true_altitude(altitude, pressure, temperature)
+        }
+        Refraction::ApparentToTrue => {
+            altitude - exact_refraction_from_apparent_altitude(altitude, pressure, temperature)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -46,6 +201,24 @@ mod tests {
     use super::*;
     use assert_approx_eq::assert_approx_eq;
 
+    #[test]
+    fn no_refraction_test() {
+        // Arrange
+        let altitude = Degrees::new(0.5);
+
+        for pressure in [0.0, -1013.0] {
+            // Act
+            let true_to_apparent = refraction_for_true_altitude(altitude, pressure, 10.0);
+            let apparent_to_true = refraction_from_apparent_altitude(altitude, pressure, 10.0);
+            let corrected = correct_altitude(altitude, Refraction::ApparentToTrue, pressure, 10.0);
+
+            // Assert
+            assert_eq!(0.0, true_to_apparent.0);
+            assert_eq!(0.0, apparent_to_true.0);
+            assert_eq!(altitude.0, corrected.0);
+        }
+    }
+
     #[test]
     fn refraction_for_true_altitude_test_1() {
         // Arrange
@@ -73,6 +246,97 @@ mod tests {
         // Assert
         assert_eq!(0, d);
         assert_eq!(29, m);
-        assert_approx_eq!(5.636, s, 0.001);
+        assert_approx_eq!(4.197, s, 0.001);
+    }
+
+    #[test]
+    fn refraction_in_zenith_test() {
+        // Arrange
+        let zenith = Degrees::new(90.0);
+
+        // Act
+        let true_to_apparent = refraction_for_true_altitude(zenith, 1010.0, 10.0);
+        let apparent_to_true = refraction_from_apparent_altitude(zenith, 1010.0, 10.0);
+
+        // Assert
+
+        // SS: Meeus, page 106, with the constants added, both formulas are 0 in the zenith
+        assert_approx_eq!(0.0, true_to_apparent.0 * 60.0, 0.000_1);
+        assert_approx_eq!(0.0, apparent_to_true.0 * 60.0, 0.000_1);
+    }
+
+    #[test]
+    fn refraction_from_apparent_altitude_test() {
+        // Arrange
+
+        // SS: Meeus, page 106, at the horizon, the refraction is about 35'
+        let height = Degrees::new(0.0);
+
+        // Act
+        let refraction = refraction_from_apparent_altitude(height, 1010.0, 10.0);
+
+        // Assert
+        assert_approx_eq!(34.48, refraction.0 * 60.0, 0.01);
+    }
+
+    #[test]
+    fn refraction_round_trip_test() {
+        // Arrange
+
+        // SS: Meeus, page 106: (16.4) is consistent with (16.3) to within 4"
+        let altitudes = [0.0, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 45.0, 90.0];
+
+        for altitude in altitudes {
+            let h = Degrees::new(altitude);
+
+            // Act
+            let apparent = h + refraction_for_true_altitude(h, 1010.0, 10.0);
+            let round_trip = apparent - refraction_from_apparent_altitude(apparent, 1010.0, 10.0);
+
+            // Assert
+            assert_approx_eq!(h.0, round_trip.0, 4.0 / 3600.0);
+        }
+    }
+
+    #[test]
+    fn exact_refraction_round_trip_test() {
+        // Arrange
+        let conditions = [(1010.0, 10.0), (1030.0, -30.0), (700.0, 35.0)];
+
+        for (pressure, temperature) in conditions {
+            for i in -40..=180 {
+                let h = Degrees::new(i as f64 * 0.5);
+
+                // Act
+                let apparent =
+                    correct_altitude(h, Refraction::TrueToApparent, pressure, temperature);
+                let round_trip =
+                    correct_altitude(apparent, Refraction::ApparentToTrue, pressure, temperature);
+
+                // Assert
+                assert_approx_eq!(h.0, round_trip.0, 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_refraction_from_apparent_altitude_test() {
+        // Arrange
+        let height = Degrees::new(0.0);
+
+        // Act
+        let exact = exact_refraction_from_apparent_altitude(height, 1010.0, 10.0);
+        let bennett = refraction_from_apparent_altitude(height, 1010.0, 10.0);
+
+        // Assert
+
+        // SS: eq (16.3) and the inverse of eq (16.4) agree to within 4"
+        assert_approx_eq!(bennett.0 * 3600.0, exact.0 * 3600.0, 4.0);
+        let true_altitude = height - exact;
+        assert_approx_eq!(
+            height.0,
+            (true_altitude + refraction_for_true_altitude(true_altitude, 1010.0, 10.0)).0,
+            1e-12
+        );
     }
 }
@@ -0,0 +1,1005 @@
+//! Calculate rise, set and transit times for a body given its geocentric
+//! equatorial coordinates, Meeus chapter 15. Used for the Moon and the Sun,
+//! and for stars and deep-sky objects, whose coordinates do not change
+//! within a day.
+
+use crate::constants;
+use crate::date::date::Date;
+use crate::date::epoch::Epoch;
+use crate::date::jd::JD;
+use crate::earth::{self, SiderialTime};
+use crate::precession::{self, Equinox};
+use crate::refraction;
+use crate::session::Observer;
+use crate::time::{self, ScaledJD, TimeScale};
+use crate::util::declination::Declination;
+use crate::util::degrees::Degrees;
+use crate::util::math;
+use crate::util::radians::Radians;
+use crate::util::right_ascension::RightAscension;
+
+#[derive(Debug, Copy, Clone)]
+pub enum OutputKind {
+    Time(JD),
+
+    /// The body does not rise on that day. Either it rises on the day before
+    /// and the day after only, or it stays below the horizon, then also for
+    /// set. See moon::day_visibility to tell these apart
+    NeverRises,
+
+    /// The body does not set on that day. Either it sets on the day before
+    /// and the day after only, or it stays above the horizon, then also for
+    /// rise. See moon::day_visibility to tell these apart
+    NeverSets,
+
+    /// The body does not cross the meridian on that day. The Moon transits
+    /// about 50 minutes later each day, so it skips a day once a month.
+    NeverTransits,
+}
+
+/// Time of the event, in the time scale it was calculated in, or why there
+/// is none
+impl std::fmt::Display for OutputKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputKind::Time(jd) => write!(f, "{jd}"),
+            OutputKind::NeverRises => write!(f, "does not rise"),
+            OutputKind::NeverSets => write!(f, "does not set"),
+            OutputKind::NeverTransits => write!(f, "does not transit"),
+        }
+    }
+}
+
+/// Geometry at the end of the iteration for an event, to diagnose
+/// differences to other sources, e.g. a rise time a few minutes off
+#[derive(Debug, Copy, Clone)]
+pub struct RiseSetGeometry {
+    /// Time of the last step of the iteration, within a minute of the event
+    /// and before restricting it to the observer's day
+    pub jd: JD,
+
+    /// Geocentric right ascension of the body at jd, in degrees [0, 360)
+    pub right_ascension: RightAscension,
+
+    /// Geocentric declination of the body at jd, in degrees [-90, 90]
+    pub declination: Declination,
+
+    /// Local apparent siderial time at jd, in degrees [0, 360)
+    pub siderial_time: Degrees,
+
+    /// Local hour angle of the body at jd, in degrees [-180, 180)
+    pub hour_angle: Degrees,
+
+    /// Cosine of the hour angle at which the body reaches the target
+    /// altitude, eq (15.1). Greater than 1 if it stays below, less than -1
+    /// if it stays above all day
+    pub cos_event_hour_angle: f64,
+
+    /// Altitude of the body at the event, in degrees [-90, 90)
+    pub target_altitude: Degrees,
+
+    /// Number of correction steps taken
+    pub iterations: u8,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum InputKind {
+    Rise,
+    Set,
+    Transit,
+}
+
+/// Direction to search for an event in
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Search {
+    /// First event at or after a Julian Day
+    Forward,
+
+    /// Last event at or before a Julian Day
+    Backward,
+}
+
+/// Step of the altitude scan, in hours. A body does not rise and set within
+/// 10 minutes, except when grazing the horizon at high latitudes.
+pub(crate) const SCAN_STEP: f64 = 1.0 / 6.0;
+
+/// Accuracy of the rise and set times of the altitude scan, in days
+const TIME_ACCURACY: f64 = 1.0 / 86400.0;
+
+/// Time before and after an event the refinement samples the altitude at,
+/// in minutes, see refine_rise_set
+const REFINEMENT_STEP: f64 = 2.0;
+
+/// Classification of a day by a body's rise and set. Unlike OutputKind, this
+/// distinguishes a body that stays above or below the horizon, e.g. the Sun
+/// during polar day and night, from one that merely skips an event.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DayVisibility {
+    /// The body rises and sets on the day, in either order. At high
+    /// latitudes, the Moon can rise or set twice on a day, then the first
+    /// events are given.
+    RisesAndSets { rise: JD, set: JD },
+
+    /// The body is above the horizon all day
+    AlwaysAbove,
+
+    /// The body is below the horizon all day
+    AlwaysBelow,
+
+    /// The body rises, but does not set on the day
+    RisesOnly(JD),
+
+    /// The body sets, but does not rise on the day
+    SetsOnly(JD),
+}
+
+/// Standard altitude of a star at rise and set, in degrees, accounting for
+/// refraction. Meeus, chapter 15, page 102
+const STAR_STANDARD_ALTITUDE: f64 = -0.5667;
+
+/// Rate of the siderial time, in degrees per day of UT1. Meeus, chapter 15,
+/// page 103
+const SIDERIAL_RATE: f64 = 360.985_647;
+
+/// Rise, set and transit of a star or deep-sky object on the observer's day
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FixedTargetDay {
+    /// Rise and set, or whether the target stays above or below the
+    /// horizon all day. A star rises and sets once a siderial day, 23h 56m,
+    /// so on one day a year it rises or sets twice, then the first
+    /// events are given
+    pub visibility: DayVisibility,
+
+    /// First time the target crosses the observer's meridian, also if it
+    /// stays above or below the horizon
+    pub transit: JD,
+}
+
+/// Maximum number of days to search for an event. At high latitudes, the
+/// Moon can stay above or below the horizon for up to half a month.
+const MAX_SEARCH_DAYS: f64 = 30.0;
+
+/// Iteratively compute the time a body rises, sets or transits, Meeus chapter 15.
+/// In:
+/// kind: the event to compute
+/// jd: Julian Day to compute the event for
+/// timezone_offset: Observer's time zone offset
+/// target_altitude: altitude of the body above horizon, in degrees [-90, 90)
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// position: geocentric equatorial coordinates of the body for a Julian Day
+pub(crate) fn calculate_rise_set_transit(
+    kind: InputKind,
+    jd: JD,
+    timezone_offset: i8,
+    target_altitude: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> OutputKind {
+    calculate_rise_set_transit_geometry(
+        kind,
+        jd,
+        timezone_offset,
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+        position,
+    )
+    .0
+}
+
+/// Same as calculate_rise_set_transit, but also returns the geometry the
+/// iteration ended with
+pub(crate) fn calculate_rise_set_transit_geometry(
+    kind: InputKind,
+    jd: JD,
+    timezone_offset: i8,
+    target_altitude: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> (OutputKind, RiseSetGeometry) {
+    // SS: bound time based on observer's timezone offset
+    let (jd_min, jd_midday, jd_max) = bound_julian_day(jd, timezone_offset);
+
+    let (event, geometry) = iterate(
+        &kind,
+        jd_midday,
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+        position,
+    );
+
+    // SS: check whether we have the correct day
+    let event = match event {
+        OutputKind::Time(event_jd) if event_jd < jd_min || event_jd > jd_max => never(&kind),
+        _ => event,
+    };
+    (event, geometry)
+}
+
+/// Search for the next or previous time a body rises, sets or transits,
+/// without restricting the event to the observer's day.
+/// In:
+/// kind: the event to search for
+/// jd: Julian Day to start the search at
+/// search: direction of the search
+/// target_altitude: altitude of the body above horizon, in degrees [-90, 90)
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// position: geocentric equatorial coordinates of the body for a Julian Day
+/// Out:
+/// Time of the event, or NeverRises, NeverSets or NeverTransits if there is
+/// no event within 30 days
+pub(crate) fn search_rise_set_transit(
+    kind: InputKind,
+    jd: JD,
+    search: Search,
+    target_altitude: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> OutputKind {
+    // SS: the iteration converges to the event closest to its start, i.e.
+    // within about 12 hours. Events of the same kind are about a day apart, so
+    // starting every 12 hours, the first event on the correct side of jd is
+    // the one we are looking for.
+    let step = match search {
+        Search::Forward => 12.0,
+        Search::Backward => -12.0,
+    };
+    let max_steps = (MAX_SEARCH_DAYS * 2.0) as usize;
+
+    let mut start = jd;
+    for _ in 0..=max_steps {
+        if let (OutputKind::Time(event_jd), _) = iterate(
+            &kind,
+            start,
+            target_altitude,
+            longitude_observer,
+            latitude_observer,
+            position,
+        ) {
+            let found = match search {
+                Search::Forward => event_jd >= jd,
+                Search::Backward => event_jd <= jd,
+            };
+            if found {
+                return OutputKind::Time(event_jd);
+            }
+        }
+
+        start.add_hours(step);
+    }
+
+    never(&kind)
+}
+
+/// Calculate the times a body rises and sets by interpolating its altitude,
+/// the method of the US Naval Observatory, see O. Montenbruck, T. Pfleger,
+/// Astronomy on the Personal Computer, chapter 3.8. The altitude is
+/// calculated every hour of the observer's day, and a parabola through three
+/// consecutive values is solved for the target altitude. Independent of
+/// Meeus' iteration, to cross-check it.
+/// In:
+/// jd: Julian Day to compute the events for
+/// timezone_offset: Observer's time zone offset
+/// target_altitude: altitude of the body above horizon, in degrees [-90, 90)
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// position: geocentric equatorial coordinates of the body for a Julian Day
+/// Out:
+/// First rise and first set of the day, or NeverRises/NeverSets
+pub(crate) fn interpolate_rise_set(
+    jd: JD,
+    timezone_offset: i8,
+    target_altitude: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> (OutputKind, OutputKind) {
+    let (jd_min, _, _) = bound_julian_day(jd, timezone_offset);
+
+    let latitude_observer_radians = Radians::from(latitude_observer);
+    let sin_latitude_observer = math::sin(latitude_observer_radians.0);
+    let cos_latitude_observer = math::cos(latitude_observer_radians.0);
+    let sin_h0 = math::sin(Radians::from(target_altitude).0);
+
+    // SS: sine of the altitude above the target altitude, hours after the
+    // start of the day
+    let altitude = |hours: f64| {
+        let mut jd = jd_min;
+        jd.add_hours(hours);
+
+        let (ra, decl) = position(jd);
+        let theta0 = earth::apparent_siderial_time(jd);
+        let theta = earth::local_siderial_time(theta0, longitude_observer);
+        let hour_angle = Radians::from(theta - ra.degrees());
+        let decl_radians = Radians::from(decl);
+
+        sin_latitude_observer * math::sin(decl_radians.0)
+            + cos_latitude_observer * math::cos(decl_radians.0) * math::cos(hour_angle.0)
+            - sin_h0
+    };
+
+    let to_jd = |hours: f64| {
+        let mut jd = jd_min;
+        jd.add_hours(hours);
+        jd
+    };
+
+    let mut rise = None;
+    let mut set = None;
+
+    let mut y_minus = altitude(0.0);
+    let mut hour = 1.0;
+    while hour < 24.0 {
+        let y0 = altitude(hour);
+        let y_plus = altitude(hour + 1.0);
+
+        // SS: parabola y = a x^2 + b x + y0 through x = -1, 0, 1
+        let a = 0.5 * (y_plus + y_minus) - y0;
+        let b = 0.5 * (y_plus - y_minus);
+        let (x1, x2) = quadratic_roots(a, b, y0);
+        let in_interval = |x: f64| (-1.0..=1.0).contains(&x);
+
+        match (
+            x1.filter(|&x| in_interval(x)),
+            x2.filter(|&x| in_interval(x)),
+        ) {
+            (Some(x), None) | (None, Some(x)) => {
+                // SS: the body rises if it is below the target altitude
+                // at the start of the interval
+                if y_minus < 0.0 {
+                    rise = rise.or(Some(to_jd(hour + x)));
+                } else {
+                    set = set.or(Some(to_jd(hour + x)));
+                }
+            }
+            (Some(x1), Some(x2)) => {
+                // SS: the body rises and sets within the interval, x1 < x2
+                let y_extremum = y0 - b * b / (4.0 * a);
+                let (x_rise, x_set) = if y_extremum < 0.0 { (x2, x1) } else { (x1, x2) };
+                rise = rise.or(Some(to_jd(hour + x_rise)));
+                set = set.or(Some(to_jd(hour + x_set)));
+            }
+            (None, None) => {}
+        }
+
+        y_minus = y_plus;
+        hour += 2.0;
+    }
+
+    (
+        rise.map_or(OutputKind::NeverRises, OutputKind::Time),
+        set.map_or(OutputKind::NeverSets, OutputKind::Time),
+    )
+}
+
+/// Classify a day by scanning a body's altitude
+/// In:
+/// jd_min, jd_max: start and end of the observer's day
+/// above: altitude of the body above the target altitude, positive if it is
+/// above, e.g. altitude_above_target
+/// Out:
+/// The body's visibility on the day, with the first rise and set
+pub(crate) fn classify_day(jd_min: JD, jd_max: JD, above: &dyn Fn(JD) -> f64) -> DayVisibility {
+    let mut rise = None;
+    let mut set = None;
+
+    let mut prev_jd = jd_min;
+    let mut prev_above = above(prev_jd);
+    let is_up_at_start = prev_above > 0.0;
+
+    while prev_jd < jd_max {
+        let mut next_jd = prev_jd;
+        next_jd.add_hours(SCAN_STEP);
+        let next_jd = if next_jd > jd_max { jd_max } else { next_jd };
+        let next_above = above(next_jd);
+
+        if prev_above <= 0.0 && next_above > 0.0 && rise.is_none() {
+            rise = Some(crossing(prev_jd, next_jd, above));
+        } else if prev_above > 0.0 && next_above <= 0.0 && set.is_none() {
+            set = Some(crossing(prev_jd, next_jd, above));
+        }
+
+        prev_jd = next_jd;
+        prev_above = next_above;
+    }
+
+    match (rise, set) {
+        (Some(rise), Some(set)) => DayVisibility::RisesAndSets { rise, set },
+        (Some(rise), None) => DayVisibility::RisesOnly(rise),
+        (None, Some(set)) => DayVisibility::SetsOnly(set),
+        (None, None) if is_up_at_start => DayVisibility::AlwaysAbove,
+        (None, None) => DayVisibility::AlwaysBelow,
+    }
+}
+
+/// Sine of a body's geocentric altitude minus sine of the target altitude.
+/// Positive if the body is above the target altitude, eq (13.6)
+/// In:
+/// jd: Julian Day
+/// h0: target altitude, in degrees [-90, 90)
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// position: geocentric equatorial coordinates of the body for a Julian Day
+pub(crate) fn altitude_above_target(
+    jd: JD,
+    h0: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> f64 {
+    let (ra, decl) = position(jd);
+    let theta0 = earth::apparent_siderial_time(jd);
+    let hour_angle = earth::local_siderial_time(theta0, longitude_observer) - ra.degrees();
+
+    let latitude = Radians::from(latitude_observer).0;
+    let decl = Radians::from(decl).0;
+    let hour_angle = Radians::from(hour_angle).0;
+
+    let sin_altitude = math::sin(latitude) * math::sin(decl)
+        + math::cos(latitude) * math::cos(decl) * math::cos(hour_angle);
+    sin_altitude - math::sin(Radians::from(h0).0)
+}
+
+/// Refine the time a body crosses the target altitude by bisection
+/// In:
+/// jd0, jd1: Julian Days bracketing the crossing
+/// above: altitude above the target altitude
+/// Out:
+/// Time of the crossing
+pub(crate) fn crossing(jd0: JD, jd1: JD, above: &dyn Fn(JD) -> f64) -> JD {
+    let mut lo = jd0.jd;
+    let mut hi = jd1.jd;
+    let sign_lo = above(jd0) > 0.0;
+
+    while hi - lo > TIME_ACCURACY {
+        let mid = 0.5 * (lo + hi);
+        if (above(JD::new(mid)) > 0.0) == sign_lo {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    JD::new(0.5 * (lo + hi))
+}
+
+/// Refine the time of a rise or set from Meeus' iteration, which stops once
+/// its correction is below a minute and leaves an error of a few seconds. A
+/// parabola through the altitude at the iteration's last step and two
+/// minutes before and after the event is solved for the target altitude,
+/// which brings the error below a second at the cost of two more positions
+/// of the body.
+/// In:
+/// event: time of the rise or set from the iteration
+/// geometry: geometry of the iteration's last step
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// position: geocentric equatorial coordinates of the body for a Julian Day
+/// Out:
+/// Refined time of the event, the time passed in if the parabola does not
+/// reach the target altitude within two minutes of it
+pub(crate) fn refine_rise_set(
+    event: JD,
+    geometry: &RiseSetGeometry,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> JD {
+    let above = |minutes: f64| {
+        let mut jd = event;
+        jd.add_hours(minutes / 60.0);
+        altitude_above_target(
+            jd,
+            geometry.target_altitude,
+            longitude_observer,
+            latitude_observer,
+            position,
+        )
+    };
+
+    // SS: the iteration's last step is less than a minute from the event if
+    // it converged, and its altitude does not need the body's position again
+    let x_last = (geometry.jd.jd - event.jd) * 24.0 * 60.0;
+    let (x1, y1) = if x_last.abs() < 0.5 * REFINEMENT_STEP {
+        let latitude = Radians::from(latitude_observer).0;
+        let decl = Radians::from(geometry.declination).0;
+        let hour_angle = Radians::from(geometry.hour_angle).0;
+        let sin_altitude = math::sin(latitude) * math::sin(decl)
+            + math::cos(latitude) * math::cos(decl) * math::cos(hour_angle);
+        (
+            x_last,
+            sin_altitude - math::sin(Radians::from(geometry.target_altitude).0),
+        )
+    } else {
+        (0.0, above(0.0))
+    };
+    let (x0, y0) = (-REFINEMENT_STEP, above(-REFINEMENT_STEP));
+    let (x2, y2) = (REFINEMENT_STEP, above(REFINEMENT_STEP));
+
+    // SS: parabola y = a x^2 + b x + c through the three points, from the
+    // divided differences
+    let d01 = (y1 - y0) / (x1 - x0);
+    let d12 = (y2 - y1) / (x2 - x1);
+    let a = (d12 - d01) / (x2 - x0);
+    let b = d01 - a * (x0 + x1);
+    let c = y0 - a * x0 * x0 - b * x0;
+
+    let (root1, root2) = quadratic_roots(a, b, c);
+    let closest = [root1, root2]
+        .into_iter()
+        .flatten()
+        .filter(|x| x.abs() <= REFINEMENT_STEP)
+        .min_by(|x, y| x.abs().total_cmp(&y.abs()));
+
+    match closest {
+        Some(minutes) => {
+            let mut refined = event;
+            refined.add_hours(minutes / 60.0);
+            refined
+        }
+        None => event,
+    }
+}
+
+/// Real roots of a x^2 + b x + c = 0
+/// Out: roots in ascending order, None if there is no such root
+fn quadratic_roots(a: f64, b: f64, c: f64) -> (Option<f64>, Option<f64>) {
+    if a == 0.0 {
+        // SS: a straight line
+        return if b == 0.0 {
+            (None, None)
+        } else {
+            (Some(-c / b), None)
+        };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return (None, None);
+    }
+
+    let x_extremum = -b / (2.0 * a);
+    let dx = 0.5 * discriminant.sqrt() / a.abs();
+    (Some(x_extremum - dx), Some(x_extremum + dx))
+}
+
+/// The result for an event that does not happen
+fn never(kind: &InputKind) -> OutputKind {
+    match kind {
+        InputKind::Rise => OutputKind::NeverRises,
+        InputKind::Set => OutputKind::NeverSets,
+        InputKind::Transit => OutputKind::NeverTransits,
+    }
+}
+
+/// Meeus' iteration for the time of an event, starting at a Julian Day
+/// In:
+/// kind: the event to compute
+/// start: Julian Day to start the iteration at
+/// target_altitude: altitude of the body above horizon, in degrees [-90, 90)
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// position: geocentric equatorial coordinates of the body for a Julian Day
+/// Out:
+/// Time of the event closest to start, or NeverRises/NeverSets if the body
+/// does not reach the target altitude, and the geometry of the last step
+fn iterate(
+    kind: &InputKind,
+    start: JD,
+    target_altitude: Degrees,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    position: fn(JD) -> (RightAscension, Declination),
+) -> (OutputKind, RiseSetGeometry) {
+    let latitude_observer_radians = Radians::from(latitude_observer);
+    let sin_latitude_observer = math::sin(latitude_observer_radians.0);
+    let cos_latitude_observer = math::cos(latitude_observer_radians.0);
+
+    let mut prev_jd = start;
+
+    let sin_h0 = math::sin(Radians::from(target_altitude).0);
+
+    // SS: if time change is less than a minute, we are done with iteration
+    let delta_t_threshold = 1.0 / 60.0;
+
+    let mut iter = 0;
+    const MAX_ITER: u8 = 10;
+
+    let geometry = loop {
+        // SS: equatorial geocentric coordinates of the body
+        let (ra, decl) = position(prev_jd);
+
+        let decl_radians = Radians::from(decl);
+        let sin_decl = math::sin(decl_radians.0);
+        let cos_decl = math::cos(decl_radians.0);
+        let cos_hour_angle =
+            (sin_h0 - sin_latitude_observer * sin_decl) / (cos_latitude_observer * cos_decl);
+
+        // SS: calculate the local hour angle for current time
+        let theta0 = earth::apparent_siderial_time(prev_jd);
+        let theta = earth::local_siderial_time(theta0, longitude_observer);
+        let hour_angle2 = theta.signed_difference(ra.degrees());
+
+        let geometry = RiseSetGeometry {
+            jd: prev_jd,
+            right_ascension: ra,
+            declination: decl,
+            siderial_time: theta,
+            hour_angle: hour_angle2,
+            cos_event_hour_angle: cos_hour_angle,
+            target_altitude,
+            iterations: iter + 1,
+        };
+
+        // SS: the body transits even if it never rises or sets
+        let is_transit = matches!(kind, InputKind::Transit);
+        let hour_angle;
+        // SS: cos H > 1, the body stays below the target altitude all day,
+        // cos H < -1, it stays above
+        if cos_hour_angle > 1.0 && !is_transit {
+            return (OutputKind::NeverRises, geometry);
+        } else if cos_hour_angle < -1.0 && !is_transit {
+            return (OutputKind::NeverSets, geometry);
+        } else {
+            hour_angle = Degrees::from(Radians::new(math::acos(cos_hour_angle.clamp(-1.0, 1.0))));
+        }
+
+        let delta_hour_angle = match kind {
+            InputKind::Rise => hour_angle2.signed_difference(-hour_angle),
+            InputKind::Set => hour_angle2.signed_difference(hour_angle),
+            InputKind::Transit => hour_angle2,
+        };
+
+        // SS: convert degrees to solar time hours
+        let delta_t = delta_hour_angle.to_hours() * constants::SIDERIAL_TO_SOLAR_TIME;
+
+        // SS: correction step
+        prev_jd.add_hours(-delta_t);
+
+        if delta_t.abs() < delta_t_threshold || iter > MAX_ITER {
+            break geometry;
+        }
+
+        iter += 1;
+    };
+
+    (OutputKind::Time(prev_jd), geometry)
+}
+
+/// Calculate the min and max Julian Day the event has to be in
+/// to be on the same day as the observer due to local time zone
+/// offsets.
+/// In:
+/// jd: Julian Day to calculate the event for, in UTC. Its calendar date is
+/// the observer's local date
+/// timezone_offset: Observer's time zone offset
+pub(crate) fn bound_julian_day(jd: JD, timezone_offset: i8) -> (JD, JD, JD) {
+    // SS: calculate midday UTC for the event
+    let mut jd_midday = jd.start_of_local_day(0);
+    jd_midday.add_hours(12.0);
+
+    // SS: We return events (rise, set, transit) in UTC, so the caller has to convert
+    // UTC to the local time by adding the timezone offset. Hence, here, we have to
+    // subtract it to shift the 24 hour day window accordingly.
+    jd_midday.add_hours(-timezone_offset as f64);
+
+    // SS: calculate midnight in local time zone expressed in UTC
+    let mut jd_min = jd_midday;
+    jd_min.add_hours(-12.0);
+
+    // SS: calculate midnight in local time zone expressed in UTC
+    let mut jd_max = jd_midday;
+    jd_max.add_hours(12.0);
+
+    (jd_min, jd_midday, jd_max)
+}
+
+/// Calculate the times a star or deep-sky object rises, sets and transits.
+/// Its coordinates do not change within a day, so unlike for the Moon and
+/// the Sun, the hour angles of the events, eq (15.1), give the times
+/// directly, without iterating.
+/// In:
+/// ra: Right ascension, in degrees [0, 360)
+/// dec: Declination, in degrees [-90, 90]
+/// equinox: Equinox the coordinates are referred to. Catalog coordinates
+/// are referred to J2000.0 and are precessed to the date. Nutation and
+/// aberration, less than 2 seconds of time, and proper motion are ignored
+/// observer: Observer's location, time zone offset and pressure. A pressure
+/// of 0 or less disables refraction
+/// date: Observer's local date. The fraction of the day is ignored
+/// Out:
+/// Rise, set and transit, in dynamical time
+pub fn for_fixed_target(
+    ra: RightAscension,
+    dec: Declination,
+    equinox: Equinox,
+    observer: &Observer,
+    date: Date,
+) -> FixedTargetDay {
+    let day = Date::new(date.year, date.month, date.day.trunc());
+    let (jd_min, _, _) = bound_julian_day(JD::from_date(day), observer.timezone_offset);
+
+    let (ra, dec) = match equinox {
+        Equinox::OfDate => (ra, dec),
+        Equinox::J2000 => {
+            precession::precess_equatorial(ra, dec, Epoch::J2000.jd(), time::utc_2_tt(jd_min))
+        }
+    };
+
+    // SS: the siderial time at the observer's local midnight, UTC taken as
+    // UT1
+    let siderial_time = earth::siderial_time(
+        ScaledJD::new(jd_min, TimeScale::UTC),
+        SiderialTime::Apparent,
+    );
+
+    // SS: first time the hour angle is the one passed in
+    let event = |hour_angle: Degrees| {
+        let angle =
+            (ra.0 + observer.longitude.0 + hour_angle.0 - siderial_time.0).rem_euclid(360.0);
+        time::utc_2_tt(JD::new(jd_min.jd + angle / SIDERIAL_RATE))
+    };
+
+    let target_altitude = if refraction::is_disabled(observer.pressure) {
+        Degrees::new(STAR_STANDARD_ALTITUDE + refraction::HORIZON_REFRACTION)
+    } else {
+        Degrees::new(STAR_STANDARD_ALTITUDE)
+    };
+    let h0 = Radians::from(target_altitude);
+    let phi = Radians::from(observer.latitude);
+    let delta = Radians::from(dec.degrees());
+
+    // SS: eq (15.1)
+    let cos_hour_angle = (math::sin(h0.0) - math::sin(phi.0) * math::sin(delta.0))
+        / (math::cos(phi.0) * math::cos(delta.0));
+
+    let visibility = if cos_hour_angle > 1.0 {
+        DayVisibility::AlwaysBelow
+    } else if cos_hour_angle < -1.0 {
+        DayVisibility::AlwaysAbove
+    } else {
+        let hour_angle = Degrees::from(Radians::new(math::acos(cos_hour_angle)));
+        DayVisibility::RisesAndSets {
+            rise: event(-hour_angle),
+            set: event(hour_angle),
+        }
+    };
+
+    // SS: a siderial day is shorter than the observer's day, so there is
+    // always a transit
+    FixedTargetDay {
+        visibility,
+        transit: event(Degrees::new(0.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::date::date::Date;
+
+    #[test]
+    fn quadratic_roots_test() {
+        // Arrange
+        let parabola = (1.0, -1.0, -2.0);
+        let line = (0.0, 2.0, -1.0);
+        let no_roots = (1.0, 0.0, 1.0);
+
+        // Act
+        let parabola = quadratic_roots(parabola.0, parabola.1, parabola.2);
+        let line = quadratic_roots(line.0, line.1, line.2);
+        let no_roots = quadratic_roots(no_roots.0, no_roots.1, no_roots.2);
+
+        // Assert
+        assert_eq!((Some(-1.0), Some(2.0)), parabola);
+        assert_eq!((Some(0.5), None), line);
+        assert_eq!((None, None), no_roots);
+    }
+
+    #[test]
+    fn output_kind_display_test() {
+        // Arrange
+        let time = OutputKind::Time(JD::from_date(Date::new(2000, 3, 20.5)));
+
+        // Act
+        let time = time.to_string();
+        let never_rises = OutputKind::NeverRises.to_string();
+        let never_transits = OutputKind::NeverTransits.to_string();
+
+        // Assert
+        assert_eq!("2000-03-20T12:00:00", time);
+        assert_eq!("does not rise", never_rises);
+        assert_eq!("does not transit", never_transits);
+    }
+
+    #[test]
+    fn timezone_offset_test_1() {
+        // Arrange
+        let date = Date::new(2022, 2, 21.0);
+        let jd = JD::from_date(date);
+        let timezone_offset = 1;
+
+        // Act
+        let (jd_min, jd_midday, jd_max) = bound_julian_day(jd, timezone_offset);
+
+        // Assert
+        let mut min = JD::from_date(date);
+        min.add_hours(-timezone_offset as f64);
+        assert_approx_eq!(min.jd, jd_min.jd, 0.001);
+
+        let mut mid = min;
+        mid.add_hours(12.0);
+        assert_approx_eq!(mid.jd, jd_midday.jd, 0.001);
+
+        let mut max = min;
+        max.add_hours(24.0);
+        assert_approx_eq!(max.jd, jd_max.jd, 0.001);
+    }
+
+    #[test]
+    fn end_of_month_test() {
+        // Arrange
+
+        // SS: just before midnight, at the end of February
+        let jd = JD::new(JD::from_date(Date::new(2022, 3, 1.0)).jd - 0.000_001);
+
+        // Act
+        let (jd_min, jd_midday, jd_max) = bound_julian_day(jd, 0);
+
+        // Assert
+        assert_eq!(JD::from_date(Date::new(2022, 2, 28.0)).jd, jd_min.jd);
+        assert_eq!(JD::from_date(Date::new(2022, 2, 28.5)).jd, jd_midday.jd);
+        assert_eq!(JD::from_date(Date::new(2022, 3, 1.0)).jd, jd_max.jd);
+    }
+
+    /// Altitude of a star at a time, in degrees
+    fn star_altitude(ra: RightAscension, dec: Declination, observer: &Observer, jd: JD) -> f64 {
+        let utc = time::tt_2_utc(jd, &time::LeapSeconds::iers());
+        let siderial_time =
+            earth::siderial_time(ScaledJD::new(utc, TimeScale::UTC), SiderialTime::Apparent);
+        let hour_angle = earth::hour_angle(
+            earth::local_siderial_time(siderial_time, observer.longitude),
+            ra,
+        );
+        crate::coordinates::equatorial_2_horizontal(dec, hour_angle, observer.latitude)
+            .1
+             .0
+    }
+
+    fn sirius() -> (RightAscension, Declination) {
+        // SS: J2000.0
+        (
+            RightAscension::from_hms(6, 45, 8.917),
+            Declination::from_dms(-16, 42, 58.02),
+        )
+    }
+
+    #[test]
+    fn for_fixed_target_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+        let date = Date::new(2024, 1, 15.0);
+        let (ra, dec) = sirius();
+
+        // Act
+        let day = for_fixed_target(ra, dec, Equinox::OfDate, &observer, date);
+
+        // Assert
+        let (rise, set) = match day.visibility {
+            DayVisibility::RisesAndSets { rise, set } => (rise, set),
+            _ => panic!("Sirius rises and sets in Munich"),
+        };
+        assert_approx_eq!(
+            STAR_STANDARD_ALTITUDE,
+            star_altitude(ra, dec, &observer, rise),
+            0.001
+        );
+        assert_approx_eq!(
+            STAR_STANDARD_ALTITUDE,
+            star_altitude(ra, dec, &observer, set),
+            0.001
+        );
+        assert_approx_eq!(
+            90.0 - observer.latitude.0 + dec.0,
+            star_altitude(ra, dec, &observer, day.transit),
+            0.001
+        );
+
+        // SS: in mid January, Sirius transits shortly before local midnight.
+        // It sets in the morning, after the transit of the night before,
+        // and rises in the evening
+        assert!(set < rise && rise < day.transit);
+        let (jd_min, _, jd_max) = bound_julian_day(JD::from_date(date), 1);
+        for jd in [rise, set, day.transit] {
+            let utc = time::tt_2_utc(jd, &time::LeapSeconds::iers());
+            assert!(jd_min <= utc && utc < jd_max);
+        }
+    }
+
+    #[test]
+    fn for_fixed_target_j2000_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+        let date = Date::new(2024, 1, 15.0);
+        let (ra, dec) = sirius();
+
+        // Act
+        let of_date = for_fixed_target(ra, dec, Equinox::OfDate, &observer, date);
+        let j2000 = for_fixed_target(ra, dec, Equinox::J2000, &observer, date);
+
+        // Assert
+
+        // SS: in 24 years, precession increases Sirius' right ascension by
+        // 24 * 2.64s
+        assert_approx_eq!(
+            24.0 * 2.64 / 86_400.0 * 360.0 / SIDERIAL_RATE,
+            j2000.transit.jd - of_date.transit.jd,
+            2.0 / 86_400.0
+        );
+    }
+
+    #[test]
+    fn for_fixed_target_circumpolar_test() {
+        // Arrange
+        let observer = Observer::new(-11.6, 48.1, 1, 1013.0, 10.0);
+        let date = Date::new(2024, 1, 15.0);
+
+        // SS: Polaris and Canopus, J2000.0
+        let polaris = (
+            RightAscension::from_hms(2, 31, 49.09),
+            Declination::from_dms(89, 15, 50.8),
+        );
+        let canopus = (
+            RightAscension::from_hms(6, 23, 57.11),
+            Declination::from_dms(-52, 41, 44.4),
+        );
+
+        // Act
+        let polaris = for_fixed_target(polaris.0, polaris.1, Equinox::J2000, &observer, date);
+        let canopus = for_fixed_target(canopus.0, canopus.1, Equinox::J2000, &observer, date);
+
+        // Assert
+        assert_eq!(DayVisibility::AlwaysAbove, polaris.visibility);
+        assert_eq!(DayVisibility::AlwaysBelow, canopus.visibility);
+    }
+
+    #[test]
+    fn for_fixed_target_refraction_test() {
+        // Arrange
+        let date = Date::new(2024, 1, 15.0);
+        let (ra, dec) = sirius();
+        let observer = Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+        let vacuum = Observer::new(-11.6, 48.1, 0, 0.0, 10.0);
+
+        // Act
+        let day = for_fixed_target(ra, dec, Equinox::OfDate, &observer, date);
+        let without = for_fixed_target(ra, dec, Equinox::OfDate, &vacuum, date);
+
+        // Assert
+
+        // SS: refraction lifts the star above the horizon earlier, keeps it
+        // up longer and does not change the transit
+        match (day.visibility, without.visibility) {
+            (
+                DayVisibility::RisesAndSets { rise, set },
+                DayVisibility::RisesAndSets {
+                    rise: rise_vacuum,
+                    set: set_vacuum,
+                },
+            ) => {
+                assert!(rise < rise_vacuum);
+                assert!(set > set_vacuum);
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(day.transit, without.transit);
+    }
+}
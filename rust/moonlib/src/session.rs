@@ -0,0 +1,946 @@
+//! An observing session bundles the observer, a range of days and the
+//! preferences used for all calculations, so callers do not have to pass
+//! them to every function. Intermediate results are cached per day and shared
+//! between the Moon and Sun calculations.
+
+use std::cell::RefCell;
+
+use crate::config::{LimbConvention, MoonlibConfig};
+use crate::date::jd::JD;
+use crate::moon;
+use crate::moon::rise_set_transit::{OutputKind, RiseSetGeometry};
+use crate::refraction;
+use crate::rise_set_transit::{self, InputKind};
+use crate::solunar::{self, SolunarDay};
+use crate::sun::rise_set::{self, SolarDay};
+use crate::sun::twilight::{self, Twilight};
+use crate::time;
+pub use crate::time::TimeScale;
+use crate::util::arcsec::ArcSec;
+use crate::util::degrees::Degrees;
+use crate::validity::{self, ValidityReport};
+
+/// Standard altitude of the Moon's center at rise/set, in degrees.
+/// Meeus, chapter 15, page 101
+const MOON_STANDARD_ALTITUDE: f64 = 0.125;
+
+/// Mean semidiameter of the Moon included in the standard altitude, 0.2725
+/// times the mean horizontal parallax of 0.9507 degrees, in degrees
+const MOON_MEAN_SEMIDIAMETER: f64 = 0.2591;
+
+/// Standard altitude of the Moon's center at rise/set
+/// In:
+/// pressure: in millibars, 0 or less for no refraction
+/// limb: Point of the Moon's disk on the horizon
+/// Out: altitude, in degrees
+fn moon_standard_altitude(pressure: f64, limb: LimbConvention) -> Degrees {
+    let refraction = if refraction::is_disabled(pressure) {
+        0.0
+    } else {
+        refraction::HORIZON_REFRACTION
+    };
+
+    // SS: the standard altitude is for the upper limb, with the standard
+    // refraction
+    let upper_limb = ArcSec::from(Degrees::new(
+        refraction::HORIZON_REFRACTION + MOON_MEAN_SEMIDIAMETER,
+    ));
+    let depression = limb.depression(
+        ArcSec::from(Degrees::new(refraction)),
+        ArcSec::from(Degrees::new(MOON_MEAN_SEMIDIAMETER)),
+    );
+    Degrees::new(MOON_STANDARD_ALTITUDE) + Degrees::from(upper_limb - depression)
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Observer {
+    /// in degrees [-180, 180), positive west of Greenwich
+    pub longitude: Degrees,
+
+    /// in degrees [-90, 90)
+    pub latitude: Degrees,
+
+    /// Observer's time zone offset, in hours
+    pub timezone_offset: i8,
+
+    /// Atmospheric pressure, in millibars. 0 or less disables the refraction
+    pub pressure: f64,
+
+    /// Air temperature, in celsius
+    pub temperature: f64,
+}
+
+impl Observer {
+    /// In:
+    /// longitude: in degrees [-180, 180), positive west of Greenwich
+    /// latitude: in degrees [-90, 90)
+    /// timezone_offset: Observer's time zone offset, in hours
+    /// pressure: Atmospheric pressure, in millibars
+    /// temperature: Air temperature, in celsius
+    pub fn new(
+        longitude: f64,
+        latitude: f64,
+        timezone_offset: i8,
+        pressure: f64,
+        temperature: f64,
+    ) -> Self {
+        Self {
+            longitude: Degrees::new(longitude),
+            latitude: Degrees::new(latitude),
+            timezone_offset,
+            pressure,
+            temperature,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Precision {
+    /// Like Standard, but use the Sun's low accuracy position of Meeus,
+    /// chapter 25, which is good to about a minute for twilight
+    Low,
+
+    /// Use the Moon's mean standard altitude for rise and set, and skip the
+    /// VSOP87 terms for the Sun's position that contribute less than 0.02"
+    Standard,
+
+    /// Account for the Moon's parallax, semidiameter and the atmospheric
+    /// refraction at the observer's pressure and temperature, and use the
+    /// full VSOP87 series for the Sun's position
+    High,
+}
+
+#[derive(Default, Clone)]
+struct DayCache {
+    tt: Option<JD>,
+    moon_target_altitude: Option<Degrees>,
+    moon_rise: Option<OutputKind>,
+    moon_set: Option<OutputKind>,
+    moon_transit: Option<OutputKind>,
+
+    /// Geometry of moonrise, moonset and transit, in debug mode only
+    moon_geometry: [Option<RiseSetGeometry>; 3],
+    moon_fraction_illuminated: Option<f64>,
+    moon_solunar: Option<SolunarDay>,
+
+    sun_day: Option<SolarDay>,
+
+    /// (dawn, dusk), indexed by kind of twilight
+    sun_twilight: [Option<(OutputKind, OutputKind)>; 3],
+}
+
+pub struct Session {
+    pub observer: Observer,
+    pub time_scale: TimeScale,
+    pub config: MoonlibConfig,
+
+    /// Keep the geometry of the Moon's events, see MoonSession::rise_geometry
+    pub debug: bool,
+    days: Vec<JD>,
+    cache: RefCell<Vec<DayCache>>,
+}
+
+impl Session {
+    /// Create a session for all days from start to end, inclusive.
+    /// In:
+    /// observer: Observer's location and atmospheric conditions
+    /// start: Julian Day of the first day
+    /// end: Julian Day of the last day
+    pub fn new(observer: Observer, start: JD, end: JD) -> Self {
+        let mut days = vec![];
+        let mut jd = start;
+        while jd.jd <= end.jd {
+            days.push(jd);
+            jd = JD::new(jd.jd + 1.0);
+        }
+
+        let cache = RefCell::new(vec![DayCache::default(); days.len()]);
+
+        Self {
+            observer,
+            time_scale: TimeScale::UTC,
+            config: MoonlibConfig::default(),
+            debug: false,
+            days,
+            cache,
+        }
+    }
+
+    pub fn with_time_scale(mut self, time_scale: TimeScale) -> Self {
+        self.time_scale = time_scale;
+        self.clear_cache();
+        self
+    }
+
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.config.precision = precision;
+        self.clear_cache();
+        self
+    }
+
+    /// Accuracy settings for all calculations of the session
+    pub fn with_config(mut self, config: MoonlibConfig) -> Self {
+        self.config = config;
+        self.clear_cache();
+        self
+    }
+
+    /// Keep the hour angle, declination and siderial time the Moon's rise,
+    /// set and transit converged with, to diagnose differences to other
+    /// sources
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self.clear_cache();
+        self
+    }
+
+    /// The days of the session, in the session's time scale
+    pub fn days(&self) -> &[JD] {
+        &self.days
+    }
+
+    pub fn moon(&self) -> MoonSession<'_> {
+        MoonSession { session: self }
+    }
+
+    pub fn sun(&self) -> SunSession<'_> {
+        SunSession { session: self }
+    }
+
+    /// Which models are extrapolating for each day of the session, to check
+    /// results for historical dates
+    pub fn validity(&self) -> Vec<ValidityReport> {
+        (0..self.days.len())
+            .map(|idx| validity::validity_report(self.tt(idx)))
+            .collect()
+    }
+
+    fn clear_cache(&mut self) {
+        self.cache = RefCell::new(vec![DayCache::default(); self.days.len()]);
+    }
+
+    /// Dynamical time for a day of the session
+    fn tt(&self, idx: usize) -> JD {
+        if let Some(tt) = self.cache.borrow()[idx].tt {
+            return tt;
+        }
+
+        let jd = self.days[idx];
+        let tt = match self.time_scale {
+            TimeScale::UTC => time::utc_2_tt_with(jd, self.config.delta_t),
+            TimeScale::UT1 => time::ut1_to_tt_with(jd, self.config.delta_t),
+            TimeScale::TT => jd,
+            TimeScale::GPS => time::utc_2_tt_with(
+                time::gps_2_utc(jd, &time::LeapSeconds::iers()),
+                self.config.delta_t,
+            ),
+        };
+        self.cache.borrow_mut()[idx].tt = Some(tt);
+        tt
+    }
+
+    fn moon_target_altitude(&self, idx: usize) -> Degrees {
+        if let Some(altitude) = self.cache.borrow()[idx].moon_target_altitude {
+            return altitude;
+        }
+
+        let altitude = match self.config.precision {
+            Precision::Low | Precision::Standard => {
+                let (pressure, _) = self.config.refraction.atmosphere(&self.observer);
+                moon_standard_altitude(pressure, self.config.limb)
+            }
+            Precision::High => {
+                let (pressure, temperature) = self.config.refraction.atmosphere(&self.observer);
+                moon::rise_set_transit::limb_target_altitude(
+                    self.tt(idx),
+                    Degrees::new(0.0),
+                    self.observer.longitude,
+                    self.observer.latitude,
+                    pressure,
+                    temperature,
+                    self.config.limb,
+                )
+            }
+        };
+        self.cache.borrow_mut()[idx].moon_target_altitude = Some(altitude);
+        altitude
+    }
+
+    /// Look up a cached result for a day, calculating it if missing
+    fn cached_day<T, F, G>(&self, idx: usize, get: G, calculate: F) -> T
+    where
+        T: Clone,
+        G: Fn(&mut DayCache) -> &mut Option<T>,
+        F: Fn(usize) -> T,
+    {
+        if let Some(output) = get(&mut self.cache.borrow_mut()[idx]).clone() {
+            return output;
+        }
+
+        let output = calculate(idx);
+        *get(&mut self.cache.borrow_mut()[idx]) = Some(output.clone());
+        output
+    }
+
+    /// Look up a cached result for each day, calculating missing ones
+    fn cached<T, F, G>(&self, get: G, calculate: F) -> Vec<T>
+    where
+        T: Clone,
+        G: Fn(&mut DayCache) -> &mut Option<T>,
+        F: Fn(usize) -> T,
+    {
+        (0..self.days.len())
+            .map(|idx| self.cached_day(idx, &get, &calculate))
+            .collect()
+    }
+
+    fn moon_rise(&self, idx: usize) -> OutputKind {
+        self.cached_day(
+            idx,
+            |cache| &mut cache.moon_rise,
+            |idx| self.moon_event(idx, InputKind::Rise),
+        )
+    }
+
+    fn moon_set(&self, idx: usize) -> OutputKind {
+        self.cached_day(
+            idx,
+            |cache| &mut cache.moon_set,
+            |idx| self.moon_event(idx, InputKind::Set),
+        )
+    }
+
+    fn moon_transit(&self, idx: usize) -> OutputKind {
+        self.cached_day(
+            idx,
+            |cache| &mut cache.moon_transit,
+            |idx| self.moon_event(idx, InputKind::Transit),
+        )
+    }
+
+    /// Calculate the Moon's rise, set or transit for a day, keeping the
+    /// geometry in debug mode
+    fn moon_event(&self, idx: usize, kind: InputKind) -> OutputKind {
+        let (event, geometry) = moon::rise_set_transit::rise_set_transit_geometry(
+            kind,
+            self.tt(idx),
+            self.observer.timezone_offset,
+            self.moon_target_altitude(idx),
+            self.observer.longitude,
+            self.observer.latitude,
+        );
+        let event = match (event, kind) {
+            (OutputKind::Time(jd), InputKind::Rise | InputKind::Set)
+                if self.config.refine_events =>
+            {
+                OutputKind::Time(rise_set_transit::refine_rise_set(
+                    jd,
+                    &geometry,
+                    self.observer.longitude,
+                    self.observer.latitude,
+                    moon::rise_set_transit::equatorial_position,
+                ))
+            }
+            _ => event,
+        };
+        if self.debug {
+            self.cache.borrow_mut()[idx].moon_geometry[kind as usize] = Some(geometry);
+        }
+        event
+    }
+
+    /// Geometry of the Moon's rise, set or transit for each day, None if
+    /// not in debug mode
+    fn moon_geometry(&self, kind: InputKind) -> Vec<Option<RiseSetGeometry>> {
+        (0..self.days.len())
+            .map(|idx| {
+                // SS: calculate the event if it is not cached yet
+                match kind {
+                    InputKind::Rise => self.moon_rise(idx),
+                    InputKind::Set => self.moon_set(idx),
+                    InputKind::Transit => self.moon_transit(idx),
+                };
+                self.cache.borrow()[idx].moon_geometry[kind as usize]
+            })
+            .collect()
+    }
+
+    /// Moon's transit on the day before a day of the session
+    fn moon_previous_transit(&self, idx: usize) -> OutputKind {
+        if idx > 0 {
+            return self.moon_transit(idx - 1);
+        }
+
+        let mut previous_day = self.tt(idx);
+        previous_day.add_hours(-24.0);
+        moon::rise_set_transit::transit(
+            previous_day,
+            self.observer.timezone_offset,
+            self.moon_target_altitude(idx),
+            self.observer.longitude,
+            self.observer.latitude,
+        )
+    }
+}
+
+/// Moon calculations for all days of a session
+pub struct MoonSession<'a> {
+    session: &'a Session,
+}
+
+impl MoonSession<'_> {
+    /// Moonrise for each day of the session
+    pub fn rise(&self) -> Vec<OutputKind> {
+        let s = self.session;
+        (0..s.days.len()).map(|idx| s.moon_rise(idx)).collect()
+    }
+
+    /// Moonset for each day of the session
+    pub fn set(&self) -> Vec<OutputKind> {
+        let s = self.session;
+        (0..s.days.len()).map(|idx| s.moon_set(idx)).collect()
+    }
+
+    /// Moon's transit for each day of the session
+    pub fn transit(&self) -> Vec<OutputKind> {
+        let s = self.session;
+        (0..s.days.len()).map(|idx| s.moon_transit(idx)).collect()
+    }
+
+    /// Geometry moonrise converged with for each day of the session, None
+    /// unless the session is in debug mode, see Session::with_debug
+    pub fn rise_geometry(&self) -> Vec<Option<RiseSetGeometry>> {
+        self.session.moon_geometry(InputKind::Rise)
+    }
+
+    /// Geometry moonset converged with for each day of the session, None
+    /// unless the session is in debug mode
+    pub fn set_geometry(&self) -> Vec<Option<RiseSetGeometry>> {
+        self.session.moon_geometry(InputKind::Set)
+    }
+
+    /// Geometry the Moon's transit converged with for each day of the
+    /// session, None unless the session is in debug mode
+    pub fn transit_geometry(&self) -> Vec<Option<RiseSetGeometry>> {
+        self.session.moon_geometry(InputKind::Transit)
+    }
+
+    /// Fraction of the Moon's disk illuminated, for each day of the session
+    pub fn fraction_illuminated(&self) -> Vec<f64> {
+        let s = self.session;
+        s.cached(
+            |cache| &mut cache.moon_fraction_illuminated,
+            |idx| moon::phase::fraction_illuminated(s.tt(idx)),
+        )
+    }
+
+    /// Solunar periods for each day of the session, based on the session's
+    /// cached moonrise, moonset and transit
+    pub fn solunar(&self) -> Vec<SolunarDay> {
+        let s = self.session;
+        s.cached(
+            |cache| &mut cache.moon_solunar,
+            |idx| {
+                solunar::solunar_day_from_events(
+                    s.tt(idx),
+                    s.observer.timezone_offset,
+                    s.moon_rise(idx),
+                    s.moon_set(idx),
+                    s.moon_transit(idx),
+                    || s.moon_previous_transit(idx),
+                )
+            },
+        )
+    }
+}
+
+/// Sun calculations for all days of a session
+pub struct SunSession<'a> {
+    session: &'a Session,
+}
+
+impl SunSession<'_> {
+    /// Sunrise, sunset and solar noon for each day of the session
+    pub fn day(&self) -> Vec<SolarDay> {
+        let s = self.session;
+        s.cached(
+            |cache| &mut cache.sun_day,
+            |idx| rise_set::solar_day(s.tt(idx), &s.observer, &s.config),
+        )
+    }
+
+    /// Begin and end of twilight for each day of the session
+    /// In: kind of twilight
+    /// Out: (dawn, dusk) for each day
+    pub fn twilight(&self, twilight: Twilight) -> Vec<(OutputKind, OutputKind)> {
+        let s = self.session;
+        s.cached(
+            |cache| &mut cache.sun_twilight[twilight as usize],
+            |idx| {
+                let tt = s.tt(idx);
+                let dawn = twilight::dawn(
+                    tt,
+                    s.observer.timezone_offset,
+                    twilight,
+                    s.observer.longitude,
+                    s.observer.latitude,
+                    &s.config,
+                );
+                let dusk = twilight::dusk(
+                    tt,
+                    s.observer.timezone_offset,
+                    twilight,
+                    s.observer.longitude,
+                    s.observer.latitude,
+                    &s.config,
+                );
+                (dawn, dusk)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DeltaT, RefractionModel};
+    use crate::date::date::Date;
+    use crate::rise_set_transit::DayVisibility;
+    use crate::test_util::munich;
+    use crate::util::radians::Radians;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn days_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let end = JD::from_date(Date::new(2000, 3, 25.5));
+
+        // Act
+        let session = Session::new(munich(0), start, end);
+
+        // Assert
+        assert_eq!(3, session.days().len());
+        assert_approx_eq!(start.jd + 2.0, session.days()[2].jd, 0.000_001);
+    }
+
+    #[test]
+    fn moon_rise_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = Session::new(munich(0), start, start).with_time_scale(TimeScale::TT);
+
+        // Act
+        let rise = session.moon().rise();
+
+        // Assert
+        let expected = JD::from_date(Date::from_date_hms(2000, 3, 23, 21, 12, 13.0));
+        match rise[0] {
+            OutputKind::Time(jd) => assert_approx_eq!(expected.jd, jd.jd, 0.001),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn moon_rise_cached_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = Session::new(munich(0), start, start);
+        let rise = session.moon().rise();
+
+        // Act
+        let rise_cached = session.moon().rise();
+
+        // Assert
+        match (rise[0], rise_cached[0]) {
+            (OutputKind::Time(jd), OutputKind::Time(jd_cached)) => {
+                assert_eq!(jd.jd, jd_cached.jd)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn moon_geometry_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = Session::new(munich(0), start, start)
+            .with_time_scale(TimeScale::TT)
+            .with_debug(true);
+
+        // Act
+        let rise = session.moon().rise();
+        let rise_geometry = session.moon().rise_geometry();
+        let transit_geometry = session.moon().transit_geometry();
+
+        // Assert
+        let rise = time(rise[0]);
+        let geometry = rise_geometry[0].unwrap();
+        assert_approx_eq!(rise.jd, geometry.jd.jd, 1.0 / 1440.0);
+
+        // SS: the Moon rises east of the meridian, at the hour angle of eq (15.1)
+        let event_hour_angle = Degrees::from(Radians::new(geometry.cos_event_hour_angle.acos()));
+        assert_approx_eq!(-event_hour_angle.0, geometry.hour_angle.0, 0.3);
+        assert_approx_eq!(
+            (geometry.siderial_time - geometry.right_ascension.degrees())
+                .map_neg180_to_180()
+                .0,
+            geometry.hour_angle.0,
+            0.000_001
+        );
+        assert!(geometry.iterations >= 1);
+
+        // SS: the Moon transits in the meridian
+        assert_approx_eq!(0.0, transit_geometry[0].unwrap().hour_angle.0, 0.3);
+    }
+
+    #[test]
+    fn refine_events_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2024, 3, 1.0));
+        let end = JD::new(start.jd + 29.0);
+        let session = Session::new(munich(0), start, end).with_time_scale(TimeScale::TT);
+        let refined = Session::new(munich(0), start, end)
+            .with_time_scale(TimeScale::TT)
+            .with_config(MoonlibConfig::new().with_refine_events(true));
+
+        // Act
+        let events = [session.moon().rise(), session.moon().set()];
+        let refined_events = [refined.moon().rise(), refined.moon().set()];
+
+        // Assert
+        let mut max_error = 0.0_f64;
+        let mut max_refined_error = 0.0_f64;
+        for (events, refined_events) in events.iter().zip(&refined_events) {
+            for (idx, (&event, &refined_event)) in events.iter().zip(refined_events).enumerate() {
+                let (OutputKind::Time(event), OutputKind::Time(refined_event)) =
+                    (event, refined_event)
+                else {
+                    assert!(!matches!(refined_event, OutputKind::Time(_)));
+                    continue;
+                };
+
+                // SS: the time the Moon reaches the target altitude, to a
+                // second, by bisection
+                let above = |jd: JD| {
+                    rise_set_transit::altitude_above_target(
+                        jd,
+                        session.moon_target_altitude(idx),
+                        session.observer.longitude,
+                        session.observer.latitude,
+                        moon::rise_set_transit::equatorial_position,
+                    )
+                };
+                let expected = rise_set_transit::crossing(
+                    JD::new(event.jd - 3.0 / 1440.0),
+                    JD::new(event.jd + 3.0 / 1440.0),
+                    &above,
+                );
+
+                max_error = max_error.max((event.jd - expected.jd).abs() * 86400.0);
+                max_refined_error =
+                    max_refined_error.max((refined_event.jd - expected.jd).abs() * 86400.0);
+            }
+        }
+
+        // SS: the iteration stops once its correction is below a minute,
+        // which leaves an error of a few seconds. The refinement is within
+        // the bisection's accuracy
+        assert!(max_error < 60.0, "{max_error}");
+        assert!(max_refined_error < 1.0, "{max_refined_error}");
+        assert!(max_refined_error < max_error);
+    }
+
+    #[test]
+    fn moon_geometry_debug_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = Session::new(munich(0), start, start);
+
+        // Act
+        let rise_geometry = session.moon().rise_geometry();
+
+        // Assert
+        assert!(rise_geometry[0].is_none());
+    }
+
+    #[test]
+    fn sun_twilight_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = Session::new(munich(0), start, start);
+
+        // Act
+        let civil = session.sun().twilight(Twilight::Civil);
+        let nautical = session.sun().twilight(Twilight::Nautical);
+
+        // Assert
+        match (civil[0], nautical[0]) {
+            (
+                (OutputKind::Time(civil_dawn), OutputKind::Time(civil_dusk)),
+                (OutputKind::Time(nautical_dawn), OutputKind::Time(nautical_dusk)),
+            ) => {
+                assert!(nautical_dawn < civil_dawn);
+                assert!(civil_dusk < nautical_dusk);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn sun_day_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.0));
+        let session = Session::new(munich(0), start, start).with_time_scale(TimeScale::TT);
+
+        // Act
+        let day = session.sun().day();
+
+        // Assert
+        assert_eq!(
+            rise_set::solar_day(start, &munich(0), &session.config),
+            day[0]
+        );
+    }
+
+    #[test]
+    fn moon_solunar_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 19.5));
+        let end = JD::from_date(Date::new(2000, 3, 21.5));
+        let observer = munich(0);
+        let session = Session::new(observer, start, end).with_time_scale(TimeScale::TT);
+
+        // Act
+        let solunar = session.moon().solunar();
+
+        // Assert
+        for (idx, day) in solunar.iter().enumerate() {
+            let expected = solunar::solunar_day(
+                session.days()[idx],
+                observer.timezone_offset,
+                observer.longitude,
+                observer.latitude,
+                observer.pressure,
+                observer.temperature,
+            );
+            assert_eq!(expected.major_periods.len(), day.major_periods.len());
+            assert_eq!(expected.minor_periods.len(), day.minor_periods.len());
+            for (expected, period) in expected.major_periods.iter().zip(&day.major_periods) {
+                assert_approx_eq!(expected.start.jd, period.start.jd, 0.000_001);
+            }
+            for (expected, period) in expected.minor_periods.iter().zip(&day.minor_periods) {
+                assert_approx_eq!(expected.start.jd, period.start.jd, 0.000_001);
+            }
+        }
+    }
+
+    #[test]
+    fn sun_twilight_cached_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = Session::new(munich(0), start, start);
+        let civil = session.sun().twilight(Twilight::Civil);
+
+        // Act
+        let civil_cached = session.sun().twilight(Twilight::Civil);
+        let nautical = session.sun().twilight(Twilight::Nautical);
+
+        // Assert
+        match (civil[0], civil_cached[0], nautical[0]) {
+            (
+                (OutputKind::Time(dawn), _),
+                (OutputKind::Time(dawn_cached), _),
+                (OutputKind::Time(nautical_dawn), _),
+            ) => {
+                assert_eq!(dawn.jd, dawn_cached.jd);
+                assert!(nautical_dawn < dawn);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn time(output: OutputKind) -> JD {
+        match output {
+            OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn config_delta_t_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let table = Session::new(munich(0), start, start).with_time_scale(TimeScale::UT1);
+        let fixed = Session::new(munich(0), start, start)
+            .with_time_scale(TimeScale::UT1)
+            .with_config(MoonlibConfig::new().with_delta_t(DeltaT::Fixed(0.0)));
+
+        // Act
+        let tt_table = table.tt(0);
+        let tt_fixed = fixed.tt(0);
+
+        // Assert
+
+        // SS: delta_t is about 64s in 2000
+        assert_approx_eq!(64.0, (tt_table.jd - start.jd) * 86400.0, 1.0);
+        assert_eq!(start, tt_fixed);
+    }
+
+    #[test]
+    fn config_refraction_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let observer = Session::new(munich(0), start, start);
+        let none = Session::new(munich(0), start, start)
+            .with_config(MoonlibConfig::new().with_refraction(RefractionModel::None));
+
+        // Act
+        let rise_observer = time(observer.moon().rise()[0]);
+        let rise_none = time(none.moon().rise()[0]);
+
+        // Assert
+
+        // SS: refraction lifts the Moon by about 35' at the horizon, so it
+        // rises a few minutes earlier
+        assert!(rise_observer < rise_none);
+        assert_approx_eq!(rise_observer.jd, rise_none.jd, 10.0 / 1440.0);
+    }
+
+    #[test]
+    fn config_refraction_none_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let none = MoonlibConfig::new().with_refraction(RefractionModel::None);
+        let standard = Session::new(munich(0), start, start).with_precision(Precision::Standard);
+        let geometric = Session::new(munich(0), start, start)
+            .with_config(none)
+            .with_precision(Precision::Standard);
+
+        // Act
+        let rise_standard = time(standard.moon().rise()[0]);
+        let rise_geometric = time(geometric.moon().rise()[0]);
+        let sun_standard = standard.sun().day()[0].visibility;
+        let sun_geometric = geometric.sun().day()[0].visibility;
+
+        // Assert
+
+        // SS: without refraction, the Moon and the Sun rise later, also for
+        // the standard altitudes of Meeus, chapter 15
+        assert!(rise_standard < rise_geometric);
+        assert_approx_eq!(rise_standard.jd, rise_geometric.jd, 10.0 / 1440.0);
+        match (sun_standard, sun_geometric) {
+            (
+                DayVisibility::RisesAndSets { rise, set },
+                DayVisibility::RisesAndSets {
+                    rise: rise_geometric,
+                    set: set_geometric,
+                },
+            ) => {
+                assert!(rise < rise_geometric);
+                assert!(set > set_geometric);
+                assert_approx_eq!(rise.jd, rise_geometric.jd, 10.0 / 1440.0);
+            }
+            _ => panic!("the Sun rises and sets in Munich in March"),
+        }
+    }
+
+    #[test]
+    fn config_limb_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let session = |limb| {
+            Session::new(munich(0), start, start).with_config(
+                MoonlibConfig::new()
+                    .with_limb(limb)
+                    .with_refine_events(true),
+            )
+        };
+
+        // Act
+        let upper_limb = time(session(LimbConvention::UpperLimb).moon().rise()[0]);
+        let center_refracted = time(session(LimbConvention::CenterRefracted).moon().rise()[0]);
+        let center_geometric = time(session(LimbConvention::CenterGeometric).moon().rise()[0]);
+
+        // Assert
+
+        // SS: the upper limb rises first, the center a semidiameter later,
+        // and later still without the refraction lifting it
+        assert!(upper_limb < center_refracted);
+        assert!(center_refracted < center_geometric);
+
+        let position = |tt| moon::position::topocentric_coordinates(tt, &munich(0), 0.0);
+        let (upper_limb, center_refracted, center_geometric) = (
+            position(upper_limb),
+            position(center_refracted),
+            position(center_geometric),
+        );
+        assert_approx_eq!(0.0, center_refracted.apparent_altitude.0, 0.01);
+        assert_approx_eq!(0.0, center_geometric.true_altitude.0, 0.01);
+
+        // SS: the upper limb is on the apparent horizon when the center is a
+        // topocentric semidiameter, about 16', below the center's position
+        // on it
+        assert_approx_eq!(
+            center_refracted.true_altitude.0 - 0.26,
+            upper_limb.true_altitude.0,
+            0.02
+        );
+    }
+
+    #[test]
+    fn moon_standard_altitude_test() {
+        // Act
+        let upper_limb = moon_standard_altitude(1013.0, LimbConvention::UpperLimb);
+        let upper_limb_geometric = moon_standard_altitude(0.0, LimbConvention::UpperLimb);
+        let center_refracted = moon_standard_altitude(1013.0, LimbConvention::CenterRefracted);
+        let center_geometric = moon_standard_altitude(1013.0, LimbConvention::CenterGeometric);
+
+        // Assert
+
+        // SS: Meeus, chapter 15, h0 = 0.7275 * parallax - 34' for the upper
+        // limb, the center on the geometric horizon is at the mean parallax
+        assert_approx_eq!(MOON_STANDARD_ALTITUDE, upper_limb.0, 1e-12);
+        assert_approx_eq!(
+            MOON_STANDARD_ALTITUDE + refraction::HORIZON_REFRACTION,
+            upper_limb_geometric.0,
+            1e-12
+        );
+        assert_approx_eq!(0.9507 - 34.0 / 60.0, center_refracted.0, 0.001);
+        assert_approx_eq!(0.9507, center_geometric.0, 0.001);
+    }
+
+    #[test]
+    fn config_precision_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let config = MoonlibConfig::new().with_precision(Precision::Low);
+
+        // Act
+        let session = Session::new(munich(0), start, start).with_config(config);
+
+        // Assert
+        assert_eq!(config, session.config);
+        assert_eq!(
+            Precision::Standard,
+            session.with_precision(Precision::Standard).config.precision
+        );
+    }
+
+    #[test]
+    fn validity_test() {
+        // Arrange
+        let start = JD::from_date(Date::new(2000, 3, 23.5));
+        let historical = JD::from_date(Date::new(600, 3, 23.5));
+
+        // Act
+        let validity = Session::new(munich(0), start, JD::new(start.jd + 1.0)).validity();
+        let historical_validity = Session::new(munich(0), historical, historical).validity();
+
+        // Assert
+        assert_eq!(2, validity.len());
+        assert!(validity.iter().all(ValidityReport::is_reliable));
+        assert!(historical_validity[0].historical);
+        assert!(!historical_validity[0].is_reliable());
+    }
+}
@@ -0,0 +1,230 @@
+//! Brightness of the night sky at the zenith and the resulting naked-eye
+//! limiting magnitude. The sky brightness is the sum of the dark sky,
+//! twilight and scattered moonlight. Moonlight scattering follows
+//! K. Krisciunas and B. E. Schaefer, "A model of the brightness of moonlight",
+//! PASP 103, 1991
+
+use crate::date::jd::JD;
+use crate::moon::illuminance::{apparent_magnitude, EXTINCTION_COEFFICIENT};
+use crate::refraction::{self, Refraction};
+use crate::session::Observer;
+use crate::util::degrees::Degrees;
+use crate::util::radians::Radians;
+use crate::{moon, sun};
+
+/// Brightness of the dark sky at the zenith, in nanoLamberts, K&S page 1035
+const DARK_SKY_BRIGHTNESS: f64 = 79.0;
+
+/// Sun's altitude below which twilight no longer brightens the sky, in degrees
+const END_OF_TWILIGHT: f64 = -18.0;
+
+/// Range of the Sun's zenith distance the twilight fit is based on, in degrees
+const TWILIGHT_FIT_MIN_ZENITH_DISTANCE: f64 = 95.0;
+
+/// Coefficients of the quadratic fit to the zenith sky brightness in the V band
+/// during twilight, in magnitudes per square arcsec, see twilight_brightness
+const TWILIGHT_FIT: [f64; 3] = [11.84, 1.518, -0.057];
+
+#[derive(Debug, Copy, Clone)]
+pub struct SkyBrightness {
+    /// Surface brightness of the sky at the zenith, in V magnitudes per square arcsec
+    pub zenith_brightness: f64,
+
+    /// Faintest star visible to the naked eye at the zenith, in V magnitudes
+    pub limiting_magnitude: f64,
+}
+
+/// Convert surface brightness to nanoLamberts, K&S eq. (1)
+fn nanolamberts(magnitude: f64) -> f64 {
+    34.08 * (20.7233 - 0.92104 * magnitude).exp()
+}
+
+/// Convert nanoLamberts to surface brightness, K&S eq. (1)
+fn magnitude(nanolamberts: f64) -> f64 {
+    (20.7233 - (nanolamberts / 34.08).ln()) / 0.92104
+}
+
+/// Relative airmass, K&S eq. (3). The scattering of moonlight, K&S eqs.
+/// (15), (20) and (21), was fitted to observations with this airmass, so it
+/// is used here instead of coordinates::airmass_kasten_young, although it
+/// underestimates the airmass near the horizon
+/// In: Zenith distance, in radians
+/// Out: Airmass, 1 in the zenith
+fn airmass_krisciunas_schaefer(zenith_distance: Radians) -> f64 {
+    let sin_z = zenith_distance.0.sin();
+    (1.0 - 0.96 * sin_z * sin_z).powf(-0.5)
+}
+
+/// Apparent altitude of an object, i.e. corrected for atmospheric refraction
+fn apparent_altitude(altitude: Degrees, observer: &Observer) -> Degrees {
+    refraction::correct_altitude(
+        altitude,
+        Refraction::TrueToApparent,
+        observer.pressure,
+        observer.temperature,
+    )
+}
+
+/// Brightness of the zenith sky due to scattered moonlight, K&S eqs. (15), (20) and (21)
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location
+/// Out:
+/// Brightness, in nanoLamberts
+fn moonlight_brightness(jd: JD, observer: &Observer) -> f64 {
+    let (_, altitude) =
+        moon::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+    let altitude = apparent_altitude(altitude, observer);
+    if altitude.0 <= 0.0 {
+        return 0.0;
+    }
+
+    // SS: angular distance Moon-zenith
+    let rho = 90.0 - altitude.0;
+    let cos_rho = Radians::from(Degrees::new(rho)).0.cos();
+    let scattering =
+        10_f64.powf(5.36) * (1.06 + cos_rho * cos_rho) + 10_f64.powf(6.15 - rho / 40.0);
+
+    // SS: illuminance outside the atmosphere, in foot-candles
+    let illuminance = 10_f64.powf(-0.4 * (apparent_magnitude(jd) + 16.57));
+
+    let airmass_moon = airmass_krisciunas_schaefer(Radians::from(Degrees::new(rho)));
+    let airmass_zenith = 1.0;
+
+    scattering
+        * illuminance
+        * 10_f64.powf(-0.4 * EXTINCTION_COEFFICIENT * airmass_moon)
+        * (1.0 - 10_f64.powf(-0.4 * EXTINCTION_COEFFICIENT * airmass_zenith))
+}
+
+/// Brightness of the zenith sky due to twilight, based on the fit to
+/// V band measurements at Paranal for Sun zenith distances from 95 to 105 deg,
+/// F. Patat et al., "The ESO twilight sky brightness", A&A 455, 2006.
+/// The fit includes the dark sky, which is subtracted. For the Sun higher
+/// than 5 deg below the horizon, the sky brightness at 95 deg is used.
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location
+/// Out:
+/// Brightness, in nanoLamberts
+fn twilight_brightness(jd: JD, observer: &Observer) -> f64 {
+    let (_, altitude) =
+        sun::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+    let altitude = apparent_altitude(altitude, observer);
+    if altitude.0 <= END_OF_TWILIGHT {
+        return 0.0;
+    }
+
+    // SS: Sun's zenith distance relative to the start of the fit
+    let z = (90.0 - altitude.0 - TWILIGHT_FIT_MIN_ZENITH_DISTANCE).max(0.0);
+    let zenith_brightness = TWILIGHT_FIT[0] + TWILIGHT_FIT[1] * z + TWILIGHT_FIT[2] * z * z;
+    (nanolamberts(zenith_brightness) - DARK_SKY_BRIGHTNESS).max(0.0)
+}
+
+/// Naked-eye limiting magnitude for a given sky brightness,
+/// B. Schaefer, "Telescopic limiting magnitudes", PASP 102, 1990
+/// In: Surface brightness of the sky, in V magnitudes per square arcsec
+/// Out: Limiting magnitude
+fn limiting_magnitude(zenith_brightness: f64) -> f64 {
+    7.93 - 5.0 * (10_f64.powf(4.316 - zenith_brightness / 5.0) + 1.0).log10()
+}
+
+/// Estimate the brightness of the sky at the zenith and the naked-eye
+/// limiting magnitude.
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Sky brightness and limiting magnitude at the zenith
+pub fn sky_brightness(jd: JD, observer: &Observer) -> SkyBrightness {
+    let brightness = DARK_SKY_BRIGHTNESS
+        + twilight_brightness(jd, observer)
+        + moonlight_brightness(jd, observer);
+
+    let zenith_brightness = magnitude(brightness);
+
+    SkyBrightness {
+        zenith_brightness,
+        limiting_magnitude: limiting_magnitude(zenith_brightness),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::test_util::munich;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn dark_sky_test() {
+        // Arrange
+
+        // SS: new moon, Jan. 2nd 2022, Moon and Sun are below the horizon at midnight
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 2, 23, 0, 0.0));
+
+        // Act
+        let sky = sky_brightness(jd, &munich(0));
+
+        // Assert
+        assert_approx_eq!(21.587, sky.zenith_brightness, 0.001);
+        assert_approx_eq!(6.4, sky.limiting_magnitude, 0.1);
+    }
+
+    #[test]
+    fn full_moon_test() {
+        // Arrange
+
+        // SS: full moon, Jan. 17th 2022, 11:48pm UTC
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 17, 23, 48, 0.0));
+
+        // Act
+        let sky = sky_brightness(jd, &munich(0));
+
+        // Assert
+        assert!(sky.zenith_brightness < 20.0);
+        assert!(sky.limiting_magnitude < 5.5);
+    }
+
+    #[test]
+    fn civil_twilight_test() {
+        // Arrange
+
+        // SS: about 30 minutes after sunset, new moon
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 2, 16, 0, 0.0));
+
+        // Act
+        let sky = sky_brightness(jd, &munich(0));
+
+        // Assert
+        assert!(sky.zenith_brightness < 12.0);
+        assert!(sky.limiting_magnitude < 2.0);
+    }
+
+    #[test]
+    fn airmass_krisciunas_schaefer_test() {
+        // Arrange
+        let zenith = Radians::new(0.0);
+        let horizon = Radians::new(std::f64::consts::FRAC_PI_2);
+
+        // Act
+        let airmass_zenith = airmass_krisciunas_schaefer(zenith);
+        let airmass_horizon = airmass_krisciunas_schaefer(horizon);
+
+        // Assert
+        assert_approx_eq!(1.0, airmass_zenith, 0.000_001);
+        assert_approx_eq!(5.0, airmass_horizon, 0.000_001);
+    }
+
+    #[test]
+    fn brightness_conversion_test() {
+        // Arrange
+        let brightness = 21.0;
+
+        // Act
+        let converted = magnitude(nanolamberts(brightness));
+
+        // Assert
+        assert_approx_eq!(brightness, converted, 0.000_001);
+    }
+}
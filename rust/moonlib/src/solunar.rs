@@ -0,0 +1,319 @@
+//! Solunar times, i.e. the periods of the day during which fish and game
+//! are said to be most active.
+//! Major periods are centered on the Moon's upper and lower transit,
+//! minor periods on moonrise and moonset.
+
+use crate::date::jd::JD;
+use crate::moon;
+use crate::rise_set_transit::{bound_julian_day, OutputKind};
+use crate::util::degrees::Degrees;
+
+/// Half the length of a major period, in hours
+const MAJOR_PERIOD_HALF_WIDTH: f64 = 1.0;
+
+/// Half the length of a minor period, in hours
+const MINOR_PERIOD_HALF_WIDTH: f64 = 0.5;
+
+/// Half a lunar day, i.e. the time between upper and lower transit, in hours
+const HALF_LUNAR_DAY: f64 = 24.8412 / 2.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Period {
+    pub start: JD,
+    pub end: JD,
+}
+
+impl Period {
+    fn around(jd: JD, half_width: f64) -> Self {
+        let mut start = jd;
+        start.add_hours(-half_width);
+
+        let mut end = jd;
+        end.add_hours(half_width);
+
+        Self { start, end }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SolunarDay {
+    /// Periods around the Moon's upper and lower transit, sorted by start time
+    pub major_periods: Vec<Period>,
+
+    /// Periods around moonrise and moonset, sorted by start time
+    pub minor_periods: Vec<Period>,
+
+    /// Rating of the day, from 1 (poor) to 4 (best)
+    pub rating: u8,
+}
+
+/// Calculate the solunar periods for a day.
+/// In:
+/// jd: Julian Day, in dynamical time
+/// timezone_offset: Observer's time zone offset
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// pressure: Atmospheric pressure, in millibars
+/// temperature: Air temperature, in celsius
+/// Out:
+/// Major and minor periods and the day's rating
+pub fn solunar_day(
+    jd: JD,
+    timezone_offset: i8,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    pressure: f64,
+    temperature: f64,
+) -> SolunarDay {
+    let target_altitude = moon::rise_set_transit::target_altitude(
+        jd,
+        Degrees::new(0.0),
+        longitude_observer,
+        latitude_observer,
+        pressure,
+        temperature,
+    );
+
+    let transit = |jd: JD| {
+        moon::rise_set_transit::transit(
+            jd,
+            timezone_offset,
+            target_altitude,
+            longitude_observer,
+            latitude_observer,
+        )
+    };
+
+    let rise = moon::rise_set_transit::rise(
+        jd,
+        timezone_offset,
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+    );
+
+    let set = moon::rise_set_transit::set(
+        jd,
+        timezone_offset,
+        target_altitude,
+        longitude_observer,
+        latitude_observer,
+    );
+
+    let mut previous_day = jd;
+    previous_day.add_hours(-24.0);
+
+    solunar_day_from_events(jd, timezone_offset, rise, set, transit(jd), || {
+        transit(previous_day)
+    })
+}
+
+/// Calculate the solunar periods for a day from the Moon's rise, set and
+/// transit, so callers that already have them do not recompute them.
+/// In:
+/// jd: Julian Day, in dynamical time
+/// timezone_offset: Observer's time zone offset
+/// rise: Moonrise on that day
+/// set: Moonset on that day
+/// transit: Moon's transit on that day
+/// previous_transit: Moon's transit on the previous day, only evaluated
+/// when the Moon does not transit on that day
+/// Out:
+/// Major and minor periods and the day's rating
+pub(crate) fn solunar_day_from_events<F>(
+    jd: JD,
+    timezone_offset: i8,
+    rise: OutputKind,
+    set: OutputKind,
+    transit: OutputKind,
+    previous_transit: F,
+) -> SolunarDay
+where
+    F: FnOnce() -> OutputKind,
+{
+    let mut major_periods = vec![];
+    let mut minor_periods = vec![];
+
+    if let OutputKind::Time(transit) = transit {
+        major_periods.push(Period::around(transit, MAJOR_PERIOD_HALF_WIDTH));
+
+        // SS: the lower transit is half a lunar day before or after the upper transit,
+        // pick the one that falls on the same day in the observer's time zone
+        let local_day = |jd: JD| {
+            let mut local = jd;
+            local.add_hours(timezone_offset as f64);
+            local.to_calendar_date().day.trunc()
+        };
+
+        let mut lower_transit = transit;
+        lower_transit.add_hours(HALF_LUNAR_DAY);
+        if local_day(lower_transit) != local_day(transit) {
+            lower_transit.add_hours(-2.0 * HALF_LUNAR_DAY);
+        }
+        major_periods.push(Period::around(lower_transit, MAJOR_PERIOD_HALF_WIDTH));
+    } else if let OutputKind::Time(previous_transit) = previous_transit() {
+        // SS: once a month, the Moon does not transit on a day. The lower transit
+        // is then half a lunar day after the previous day's late transit
+        let (jd_min, _, jd_max) = bound_julian_day(jd, timezone_offset);
+        let mut lower_transit = previous_transit;
+        lower_transit.add_hours(HALF_LUNAR_DAY);
+        if lower_transit >= jd_min && lower_transit <= jd_max {
+            major_periods.push(Period::around(lower_transit, MAJOR_PERIOD_HALF_WIDTH));
+        }
+    }
+
+    if let OutputKind::Time(rise) = rise {
+        minor_periods.push(Period::around(rise, MINOR_PERIOD_HALF_WIDTH));
+    }
+
+    if let OutputKind::Time(set) = set {
+        minor_periods.push(Period::around(set, MINOR_PERIOD_HALF_WIDTH));
+    }
+
+    // SS: total order, so invalid input producing NaN does not panic
+    major_periods.sort_by(|a, b| a.start.jd.total_cmp(&b.start.jd));
+    minor_periods.sort_by(|a, b| a.start.jd.total_cmp(&b.start.jd));
+
+    SolunarDay {
+        major_periods,
+        minor_periods,
+        rating: rating(jd),
+    }
+}
+
+/// Rate the day based on the Moon's phase. Days close to new and full moon
+/// are considered best.
+/// In: Julian Day, in dynamical time
+/// Out: Rating from 1 (poor) to 4 (best)
+fn rating(jd: JD) -> u8 {
+    let elongation = moon::phase::phase_info(jd).elongation.0;
+
+    // SS: angular distance from the closest syzygy, [0, 90]
+    let distance = (elongation % 180.0).min(180.0 - elongation % 180.0);
+
+    if distance < 15.0 {
+        4
+    } else if distance < 40.0 {
+        3
+    } else if distance < 65.0 {
+        2
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn solunar_day_test() {
+        // Arrange
+        let date = Date::new(2000, 3, 23.5);
+        let jd = JD::from_date(date);
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let solunar = solunar_day(jd, 0, longitude_observer, latitude_observer, 1013.0, 10.0);
+
+        // Assert
+        assert_eq!(2, solunar.major_periods.len());
+        assert_eq!(2, solunar.minor_periods.len());
+
+        // SS: transit at 1:38:01, lower transit about 12h25m later
+        let transit = JD::from_date(Date::from_date_hms(2000, 3, 23, 1, 38, 1.0));
+        assert_approx_eq!(
+            transit.jd - 1.0 / 24.0,
+            solunar.major_periods[0].start.jd,
+            0.001
+        );
+        assert_approx_eq!(
+            transit.jd + 1.0 / 24.0,
+            solunar.major_periods[0].end.jd,
+            0.001
+        );
+        assert_approx_eq!(
+            transit.jd + HALF_LUNAR_DAY / 24.0,
+            (solunar.major_periods[1].start.jd + solunar.major_periods[1].end.jd) / 2.0,
+            0.001
+        );
+
+        // SS: moon sets at 7:01:03 and rises at 21:12:13
+        let set = JD::from_date(Date::from_date_hms(2000, 3, 23, 7, 1, 3.0));
+        assert_approx_eq!(
+            set.jd - 0.5 / 24.0,
+            solunar.minor_periods[0].start.jd,
+            0.001
+        );
+        let rise = JD::from_date(Date::from_date_hms(2000, 3, 23, 21, 12, 13.0));
+        assert_approx_eq!(rise.jd + 0.5 / 24.0, solunar.minor_periods[1].end.jd, 0.001);
+    }
+
+    #[test]
+    fn solunar_day_without_transit_test() {
+        // Arrange
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let days: Vec<(JD, SolunarDay)> = (1..=31)
+            .map(|day| {
+                let jd = JD::from_date(Date::new(2000, 3, day as f64 + 0.5));
+                let solunar =
+                    solunar_day(jd, 0, longitude_observer, latitude_observer, 1013.0, 10.0);
+                (jd, solunar)
+            })
+            .collect();
+
+        // Assert
+
+        // SS: on Mar. 20th 2000, the Moon transits at 23:17 on the 19th and
+        // next at 00:05 on the 21st, the lower transit is at 11:42 on the 20th
+        let (jd, no_transit_day) = &days[19];
+        assert!(matches!(
+            moon::rise_set_transit::transit(
+                *jd,
+                0,
+                Degrees::new(0.125),
+                longitude_observer,
+                latitude_observer
+            ),
+            OutputKind::NeverTransits
+        ));
+        assert_eq!(1, no_transit_day.major_periods.len());
+        let lower_transit = JD::from_date(Date::from_date_hms(2000, 3, 20, 11, 42, 0.0));
+        assert_approx_eq!(
+            lower_transit.jd,
+            (no_transit_day.major_periods[0].start.jd + no_transit_day.major_periods[0].end.jd)
+                / 2.0,
+            0.001
+        );
+        assert!(days.iter().all(|(_, day)| !day.major_periods.is_empty()));
+    }
+
+    #[test]
+    fn rating_test() {
+        // Arrange
+
+        // SS: full moon, Jan. 17th 2022, 11:48pm UTC
+        let full_moon = JD::from_date(Date::from_date_hms(2022, 1, 17, 23, 48, 0.0));
+
+        // SS: last quarter, Jan. 25th 2022, 1:41pm UTC
+        let last_quarter = JD::from_date(Date::from_date_hms(2022, 1, 25, 13, 41, 0.0));
+
+        // Act
+        let full_moon_rating = rating(full_moon);
+        let last_quarter_rating = rating(last_quarter);
+
+        // Assert
+        assert_eq!(4, full_moon_rating);
+        assert_eq!(1, last_quarter_rating);
+    }
+}
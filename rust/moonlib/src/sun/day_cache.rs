@@ -0,0 +1,238 @@
+//! Sun's position for many times within a day, e.g. to scan its altitude
+//! minute by minute for dark windows or the golden hour.
+//!
+//! The Sun's right ascension and declination change by about a degree and
+//! less than half a degree per day, and smoothly. So they are evaluated
+//! from the VSOP87 series at knots 6 hours apart and interpolated with
+//! Meeus' eq (3.3) between them, which is accurate to better than 0.01".
+//! A scan of a day at 1 minute steps needs 5 evaluations of the series
+//! instead of 1441.
+
+use crate::date::jd::JD;
+use crate::earth::{self, SiderialTime};
+use crate::time::{ScaledJD, TimeScale};
+use crate::util::declination::Declination;
+use crate::util::degrees::Degrees;
+use crate::util::right_ascension::RightAscension;
+use crate::{coordinates, sun};
+
+/// Time between the knots, in days
+const KNOT_SPACING: f64 = 0.25;
+
+/// Number of knots, covering a day
+pub const KNOTS: usize = 5;
+
+/// Sun's apparent geocentric position at knots 6 hours apart over a day
+#[derive(Debug, Clone)]
+pub struct SunDayCache {
+    /// Julian day of the first knot, in dynamical time
+    start: JD,
+
+    /// Right ascension, continued across 360 degrees, declination and
+    /// equation of the equinoxes, in degrees, at the knots
+    knots: [(f64, f64, f64); KNOTS],
+}
+
+impl SunDayCache {
+    /// Evaluate the Sun's position from the full VSOP87 series at the knots
+    /// In: Julian day of the start of the day, in dynamical time
+    /// Out: Cache for the day from jd to jd + 1
+    pub fn new(jd: JD) -> Self {
+        Self::with_position(jd, sun::position::equatorial_coordinates)
+    }
+
+    /// Evaluate the Sun's position from a function at the knots, e.g. the
+    /// one for a precision, see sun::twilight
+    /// In:
+    /// jd: Julian day of the start of the day, in dynamical time
+    /// position: Sun's apparent right ascension and declination for a
+    /// Julian day in dynamical time
+    /// Out: Cache for the day from jd to jd + 1
+    pub fn with_position<F>(jd: JD, position: F) -> Self
+    where
+        F: Fn(JD) -> (RightAscension, Declination),
+    {
+        let mut knots = [(0.0, 0.0, 0.0); KNOTS];
+        let mut ra_0 = 0.0;
+        for (idx, knot) in knots.iter_mut().enumerate() {
+            let t = JD::new(jd.jd + idx as f64 * KNOT_SPACING);
+            let (ra, dec) = position(t);
+
+            // SS: the right ascension jumps from 360 to 0 degrees at the
+            // March equinox, continue it so it can be interpolated
+            let ra = if idx == 0 {
+                ra.0
+            } else {
+                ra_0 + (ra.degrees() - Degrees::new(ra_0)).map_neg180_to_180().0
+            };
+            ra_0 = ra;
+
+            *knot = (ra, dec.0, equation_of_equinoxes(t).0);
+        }
+
+        Self { start: jd, knots }
+    }
+
+    /// Sun's apparent geocentric position
+    /// In: Julian day, in dynamical time, within the cached day. Outside
+    /// of it, the position is extrapolated and less accurate
+    /// Out:
+    /// right ascension, in degrees [0, 360)
+    /// declination, in degrees [-90, 90)
+    pub fn ra_dec(&self, jd: JD) -> (RightAscension, Declination) {
+        let (ra, dec, _) = self.interpolate(jd);
+        (
+            RightAscension::new(Degrees::new(ra).map_to_0_to_360()),
+            Declination::new(Degrees::new(dec)),
+        )
+    }
+
+    /// Sun's horizontal coordinates, the same as
+    /// sun::position::horizontal_coordinates. The nutation in the siderial
+    /// time is interpolated as well
+    /// In:
+    /// jd: Julian day, in dynamical time, within the cached day
+    /// longitude_observer: in degrees [-180, 180), positive west of Greenwich
+    /// latitude_observer: in degrees, [-90, 90)
+    /// Out:
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    /// Altitude, in degrees [-90, 90)
+    pub fn horizontal(
+        &self,
+        jd: JD,
+        longitude_observer: Degrees,
+        latitude_observer: Degrees,
+    ) -> (Degrees, Degrees) {
+        let (ra, dec, equation_of_equinoxes) = self.interpolate(jd);
+
+        let mean_siderial_time =
+            earth::siderial_time(ScaledJD::new(jd, TimeScale::UT1), SiderialTime::Mean);
+        let siderial_time =
+            (mean_siderial_time + Degrees::new(equation_of_equinoxes)).map_to_0_to_360();
+        let siderial_time_local = earth::local_siderial_time(siderial_time, longitude_observer);
+        let hour_angle = earth::hour_angle(
+            siderial_time_local,
+            RightAscension::new(Degrees::new(ra).map_to_0_to_360()),
+        );
+        coordinates::equatorial_2_horizontal(
+            Declination::new(Degrees::new(dec)),
+            hour_angle,
+            latitude_observer,
+        )
+    }
+
+    /// Interpolate the knots with Meeus, eq (3.3), around the knot closest
+    /// to jd
+    fn interpolate(&self, jd: JD) -> (f64, f64, f64) {
+        let x = (jd.jd - self.start.jd) / KNOT_SPACING;
+        let middle = (x.round() as isize).clamp(1, KNOTS as isize - 2) as usize;
+        let n = x - middle as f64;
+
+        let interpolate = |value: fn(&(f64, f64, f64)) -> f64| {
+            let (y1, y2, y3) = (
+                value(&self.knots[middle - 1]),
+                value(&self.knots[middle]),
+                value(&self.knots[middle + 1]),
+            );
+            let a = y2 - y1;
+            let b = y3 - y2;
+            let c = b - a;
+            y2 + n / 2.0 * (a + b + n * c)
+        };
+
+        (
+            interpolate(|knot| knot.0),
+            interpolate(|knot| knot.1),
+            interpolate(|knot| knot.2),
+        )
+    }
+}
+
+/// Equation of the equinoxes, i.e. the nutation in right ascension, the
+/// difference between apparent and mean siderial time
+/// In: Julian day
+/// Out: Equation of the equinoxes, in degrees [-180, 180)
+fn equation_of_equinoxes(jd: JD) -> Degrees {
+    let jd = ScaledJD::new(jd, TimeScale::UT1);
+    (earth::siderial_time(jd, SiderialTime::Apparent)
+        - earth::siderial_time(jd, SiderialTime::Mean))
+    .map_to_0_to_360()
+    .map_neg180_to_180()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+    use std::cell::Cell;
+
+    #[test]
+    fn ra_dec_test() {
+        // Arrange
+
+        // SS: the March equinox, where the right ascension jumps from 360 to 0
+        let start = JD::from_date(Date::new(2024, 3, 20.0));
+        let cache = SunDayCache::new(start);
+
+        for minute in 0..=1440 {
+            let jd = JD::new(start.jd + minute as f64 / 1440.0);
+
+            // Act
+            let (ra, dec) = cache.ra_dec(jd);
+
+            // Assert
+            let (expected_ra, expected_dec) = sun::position::equatorial_coordinates(jd);
+            let ra_difference = (ra.degrees() - expected_ra.degrees()).map_neg180_to_180();
+            assert_approx_eq!(0.0, ra_difference.0 * 3600.0, 0.01);
+            assert_approx_eq!(expected_dec.0 * 3600.0, dec.0 * 3600.0, 0.01);
+        }
+    }
+
+    #[test]
+    fn horizontal_test() {
+        // Arrange
+
+        // SS: Munich, midsummer
+        let start = JD::from_date(Date::new(2024, 6, 21.0));
+        let cache = SunDayCache::new(start);
+        let longitude = Degrees::new(-11.6);
+        let latitude = Degrees::new(48.1);
+
+        for minute in (0..=1440).step_by(10) {
+            let jd = JD::new(start.jd + minute as f64 / 1440.0);
+
+            // Act
+            let (azimuth, altitude) = cache.horizontal(jd, longitude, latitude);
+
+            // Assert
+            let (expected_azimuth, expected_altitude) =
+                sun::position::horizontal_coordinates(jd, longitude, latitude);
+            let azimuth_difference = (azimuth - expected_azimuth)
+                .map_to_0_to_360()
+                .map_neg180_to_180();
+            assert_approx_eq!(0.0, azimuth_difference.0 * 3600.0, 0.05);
+            assert_approx_eq!(expected_altitude.0 * 3600.0, altitude.0 * 3600.0, 0.05);
+        }
+    }
+
+    #[test]
+    fn evaluations_test() {
+        // Arrange
+        let evaluations = Cell::new(0);
+        let position = |jd: JD| {
+            evaluations.set(evaluations.get() + 1);
+            sun::position::equatorial_coordinates(jd)
+        };
+        let start = JD::from_date(Date::new(2024, 3, 20.0));
+
+        // Act
+        let cache = SunDayCache::with_position(start, position);
+        for minute in 0..=1440 {
+            cache.ra_dec(JD::new(start.jd + minute as f64 / 1440.0));
+        }
+
+        // Assert
+        assert_eq!(KNOTS, evaluations.get());
+    }
+}
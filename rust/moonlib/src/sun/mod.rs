@@ -1,2 +1,13 @@
+pub mod day_cache;
 pub mod position;
+pub mod rise_set;
 pub mod sun;
+pub mod twilight;
+
+pub use day_cache::SunDayCache;
+pub use position::{
+    apparent_position, ecliptical_coordinates, equatorial_coordinates_fast, geometric_position,
+    position_fast, topocentric_ra_dec, true_longitude_kepler, ApparentPosition, GeometricPosition,
+    Topocentric,
+};
+pub use rise_set::{equation_of_time, solar_day, SolarDay};
@@ -1,23 +1,53 @@
-use crate::constants;
+use crate::config::NutationModel;
 use crate::date::jd::JD;
 use crate::nutation::nutation_in_longitude;
-use crate::util::{arcsec::ArcSec, degrees::Degrees, radians::Radians};
+use crate::precession::{self, Equinox};
+use crate::refraction::{self, Refraction};
+use crate::session::Observer;
+use crate::util::{
+    arcsec::ArcSec, declination::Declination, degrees::Degrees, math, radians::Radians,
+    right_ascension::RightAscension,
+};
+use crate::{constants, coordinates, earth, ecliptic, moon};
+#[cfg(feature = "sun-vsop87")]
 use tabular::vsop87d_ear;
 
-/// Calculate the heliocentric ecliptical longitude using the VSOP87
-/// theory. Meeus, chapter 32, eq. (32.2)
-/// In: Julian day
-/// Out: Longitude in degrees [0, 360)
-pub fn heliocentric_ecliptical_longitude(jd: JD) -> Degrees {
+/// Amplitude below which VSOP87 terms are skipped for Precision::Standard,
+/// in radians for longitude and latitude and in AU for the distance. The
+/// truncated series is accurate to about 0.1"
+pub(crate) const STANDARD_SERIES_THRESHOLD: f64 = 1e-7;
+
+/// Blocks of VSOP87 terms (A, B, C)
+#[cfg(feature = "sun-vsop87")]
+type Series = [&'static [[f64; 3]]];
+
+/// Sum up a VSOP87 series, Meeus, chapter 32, eq. (32.2). Terms contributing
+/// less than threshold are skipped, so callers can trade accuracy for speed.
+/// In:
+/// series: blocks of terms, multiplied by tau^0, tau^1, ...
+/// jd: Julian day
+/// threshold: smallest contribution of a term to evaluate, 0 for the full
+/// series
+/// Out: Sum of the series
+#[cfg(feature = "sun-vsop87")]
+fn vsop87(series: &Series, jd: JD, threshold: f64) -> f64 {
     let millennia_from_j2000 = jd.millennia_from_epoch_j2000();
 
     let mut total_sum = 0.0;
     let mut tau = 1.0;
-    for (coeff, _) in vsop87d_ear::VSOP87D_L_EARTH {
+    for coeff in series {
+        // SS: a term contributes at most A * tau^k, so terms with an amplitude
+        // below threshold / tau^k can be skipped for this block. The cutoff is
+        // NaN for tau^k = 0 and a threshold of 0, which skips no terms.
+        let cutoff = threshold / f64::abs(tau);
+
         let mut sum = 0.0;
+        for &[a, b, c] in coeff.iter() {
+            if a < cutoff {
+                continue;
+            }
 
-        for &(a, b, c) in coeff.iter() {
-            let local_sum = a * (b + c * millennia_from_j2000).cos();
+            let local_sum = a * math::cos(b + c * millennia_from_j2000);
             sum += local_sum;
         }
 
@@ -25,6 +55,59 @@ pub fn heliocentric_ecliptical_longitude(jd: JD) -> Degrees {
         tau *= millennia_from_j2000;
     }
 
+    total_sum
+}
+
+/// Earth's heliocentric ecliptical longitude from the VSOP87 series
+/// In:
+/// jd: Julian day
+/// threshold: smallest contribution of a VSOP87 term to evaluate, 0 for the
+/// full series
+/// Out: longitude, in radians
+#[cfg(feature = "sun-vsop87")]
+fn heliocentric_l(jd: JD, threshold: f64) -> f64 {
+    vsop87(&vsop87d_ear::vsop87d_l_earth(), jd, threshold)
+}
+
+/// Earth's heliocentric ecliptical latitude, in radians, see heliocentric_l
+#[cfg(feature = "sun-vsop87")]
+fn heliocentric_b(jd: JD, threshold: f64) -> f64 {
+    vsop87(&vsop87d_ear::vsop87d_b_earth(), jd, threshold)
+}
+
+/// Earth's distance from the Sun, in AU, see heliocentric_l
+#[cfg(feature = "sun-vsop87")]
+fn heliocentric_r(jd: JD, threshold: f64) -> f64 {
+    vsop87(&vsop87d_ear::vsop87d_r_earth(), jd, threshold)
+}
+
+// SS: without feature sun-vsop87, Earth's heliocentric position is the
+// opposite of the Sun's geometric position from the low accuracy formulas,
+// and the latitude is 0. Good to 0.01 deg, the threshold is ignored
+#[cfg(not(feature = "sun-vsop87"))]
+fn heliocentric_l(jd: JD, _threshold: f64) -> f64 {
+    let (true_longitude, _) = true_longitude_and_distance(jd);
+    Radians::from(true_longitude + Degrees::new(180.0)).0
+}
+
+#[cfg(not(feature = "sun-vsop87"))]
+fn heliocentric_b(_jd: JD, _threshold: f64) -> f64 {
+    0.0
+}
+
+#[cfg(not(feature = "sun-vsop87"))]
+fn heliocentric_r(jd: JD, _threshold: f64) -> f64 {
+    let (_, distance) = true_longitude_and_distance(jd);
+    distance
+}
+
+/// Calculate the heliocentric ecliptical longitude using the VSOP87
+/// theory. Meeus, chapter 32, eq. (32.2)
+/// In: Julian day
+/// Out: Longitude in degrees [0, 360)
+pub fn heliocentric_ecliptical_longitude(jd: JD) -> Degrees {
+    let total_sum = heliocentric_l(jd, 0.0);
+
     Degrees::from(Radians::new(total_sum)).map_to_0_to_360()
 }
 
@@ -33,21 +116,7 @@ pub fn heliocentric_ecliptical_longitude(jd: JD) -> Degrees {
 /// In: Julian day
 /// Out: Latitude in degrees [0, 360)
 pub fn heliocentric_ecliptical_latitude(jd: JD) -> Degrees {
-    let millennia_from_j2000 = jd.millennia_from_epoch_j2000();
-
-    let mut total_sum = 0.0;
-    let mut tau = 1.0;
-    for (coeff, _) in vsop87d_ear::VSOP87D_B_EARTH {
-        let mut sum = 0.0;
-
-        for &(a, b, c) in coeff.iter() {
-            let local_sum = a * (b + c * millennia_from_j2000).cos();
-            sum += local_sum;
-        }
-
-        total_sum += sum * tau;
-        tau *= millennia_from_j2000;
-    }
+    let total_sum = heliocentric_b(jd, 0.0);
 
     // SS: latitude is defined for [-90, 90]
     Degrees::from(Radians::new(total_sum)).map_to_neg90_to_90()
@@ -67,23 +136,7 @@ pub fn distance_earth_sun(jd: JD) -> f64 {
 /// In: Julian day
 /// Out: Distance of the Earth, in AU
 pub fn distance_earth_sun_ae(jd: JD) -> f64 {
-    let millennia_from_j2000 = jd.millennia_from_epoch_j2000();
-
-    let mut total_sum = 0.0;
-    let mut tau = 1.0;
-    for (coeff, _) in vsop87d_ear::VSOP87D_R_EARTH {
-        let mut sum = 0.0;
-
-        for &(a, b, c) in coeff.iter() {
-            let local_sum = a * (b + c * millennia_from_j2000).cos();
-            sum += local_sum;
-        }
-
-        total_sum += sum * tau;
-        tau *= millennia_from_j2000;
-    }
-
-    total_sum
+    heliocentric_r(jd, 0.0)
 }
 
 /// Calculate the geocentric ecliptical longitude
@@ -125,11 +178,13 @@ pub fn geocentric_ecliptical_to_fk5(
     let lambda_prime = Radians::from(Degrees::new(lambda_prime).map_to_0_to_360());
 
     let delta_longitude = -0.09033
-        + 0.03916 * (lambda_prime.0.cos() + lambda_prime.0.sin()) * Radians::from(latitude).0.tan();
+        + 0.03916
+            * (math::cos(lambda_prime.0) + math::sin(lambda_prime.0))
+            * math::tan(Radians::from(latitude).0);
     let delta_longitude = Degrees::from(ArcSec::new(delta_longitude));
     ecliptical_longitude += delta_longitude;
 
-    let delta_latitude = 0.03916 * (lambda_prime.0.cos() - lambda_prime.0.sin());
+    let delta_latitude = 0.03916 * (math::cos(lambda_prime.0) - math::sin(lambda_prime.0));
     let delta_latitude = Degrees::from(ArcSec::new(delta_latitude));
     ecliptical_latitude += delta_latitude;
 
@@ -146,129 +201,467 @@ fn variation_geocentric_longitude(jd: JD) -> ArcSec {
     let tau3 = tau2 * tau;
 
     let delta_lambda = 3548.193
-        + 118.568
-            * Radians::from(Degrees::new(87.5287 + 359993.7286 * tau))
-                .0
-                .sin()
-        + 2.476
-            * Radians::from(Degrees::new(85.0561 + 719987.4571 * tau))
-                .0
-                .sin()
-        + 1.376
-            * Radians::from(Degrees::new(27.8502 + 4452671.1152 * tau))
-                .0
-                .sin()
-        + 0.119
-            * Radians::from(Degrees::new(73.1375 + 450368.8564 * tau))
-                .0
-                .sin()
-        + 0.114
-            * Radians::from(Degrees::new(337.2264 + 329644.6718 * tau))
-                .0
-                .sin()
-        + 0.086
-            * Radians::from(Degrees::new(222.5400 + 659289.3436 * tau))
-                .0
-                .sin()
-        + 0.078
-            * Radians::from(Degrees::new(162.8136 + 9224659.7915 * tau))
-                .0
-                .sin()
-        + 0.054
-            * Radians::from(Degrees::new(82.5823 + 1079981.1857 * tau))
-                .0
-                .sin()
-        + 0.052
-            * Radians::from(Degrees::new(171.5189 + 225184.4282 * tau))
-                .0
-                .sin()
-        + 0.034
-            * Radians::from(Degrees::new(30.3214 + 4092677.3866 * tau))
-                .0
-                .sin()
-        + 0.033
-            * Radians::from(Degrees::new(119.8105 + 337181.4711 * tau))
-                .0
-                .sin()
-        + 0.023
-            * Radians::from(Degrees::new(247.5418 + 299295.6151 * tau))
-                .0
-                .sin()
-        + 0.023
-            * Radians::from(Degrees::new(325.1526 + 315559.5560 * tau))
-                .0
-                .sin()
-        + 0.021
-            * Radians::from(Degrees::new(155.1241 + 675553.2846 * tau))
-                .0
-                .sin()
-        + 7.311
-            * tau
-            * Radians::from(Degrees::new(333.4515 + 359993.7286 * tau))
-                .0
-                .sin()
-        + 0.305
-            * tau
-            * Radians::from(Degrees::new(330.9814 + 719987.4571 * tau))
-                .0
-                .sin()
-        + 0.010
-            * tau
-            * Radians::from(Degrees::new(328.5170 + 1079981.1857 * tau))
-                .0
-                .sin()
-        + 0.309
-            * tau2
-            * Radians::from(Degrees::new(241.4518 + 359993.7286 * tau))
-                .0
-                .sin()
-        + 0.021
-            * tau2
-            * Radians::from(Degrees::new(205.0482 + 719987.4571 * tau))
-                .0
-                .sin()
-        + 0.004
-            * tau2
-            * Radians::from(Degrees::new(297.8610 + 4452671.1152 * tau))
-                .0
-                .sin()
-        + 0.010
-            * tau3
-            * Radians::from(Degrees::new(154.7066 + 359993.7286 * tau))
-                .0
-                .sin();
+        + 118.568 * math::sin(Radians::from(Degrees::new(87.5287 + 359993.7286 * tau)).0)
+        + 2.476 * math::sin(Radians::from(Degrees::new(85.0561 + 719987.4571 * tau)).0)
+        + 1.376 * math::sin(Radians::from(Degrees::new(27.8502 + 4452671.1152 * tau)).0)
+        + 0.119 * math::sin(Radians::from(Degrees::new(73.1375 + 450368.8564 * tau)).0)
+        + 0.114 * math::sin(Radians::from(Degrees::new(337.2264 + 329644.6718 * tau)).0)
+        + 0.086 * math::sin(Radians::from(Degrees::new(222.5400 + 659289.3436 * tau)).0)
+        + 0.078 * math::sin(Radians::from(Degrees::new(162.8136 + 9224659.7915 * tau)).0)
+        + 0.054 * math::sin(Radians::from(Degrees::new(82.5823 + 1079981.1857 * tau)).0)
+        + 0.052 * math::sin(Radians::from(Degrees::new(171.5189 + 225184.4282 * tau)).0)
+        + 0.034 * math::sin(Radians::from(Degrees::new(30.3214 + 4092677.3866 * tau)).0)
+        + 0.033 * math::sin(Radians::from(Degrees::new(119.8105 + 337181.4711 * tau)).0)
+        + 0.023 * math::sin(Radians::from(Degrees::new(247.5418 + 299295.6151 * tau)).0)
+        + 0.023 * math::sin(Radians::from(Degrees::new(325.1526 + 315559.5560 * tau)).0)
+        + 0.021 * math::sin(Radians::from(Degrees::new(155.1241 + 675553.2846 * tau)).0)
+        + 7.311 * tau * math::sin(Radians::from(Degrees::new(333.4515 + 359993.7286 * tau)).0)
+        + 0.305 * tau * math::sin(Radians::from(Degrees::new(330.9814 + 719987.4571 * tau)).0)
+        + 0.010 * tau * math::sin(Radians::from(Degrees::new(328.5170 + 1079981.1857 * tau)).0)
+        + 0.309 * tau2 * math::sin(Radians::from(Degrees::new(241.4518 + 359993.7286 * tau)).0)
+        + 0.021 * tau2 * math::sin(Radians::from(Degrees::new(205.0482 + 719987.4571 * tau)).0)
+        + 0.004 * tau2 * math::sin(Radians::from(Degrees::new(297.8610 + 4452671.1152 * tau)).0)
+        + 0.010 * tau3 * math::sin(Radians::from(Degrees::new(154.7066 + 359993.7286 * tau)).0);
 
     ArcSec::new(delta_lambda)
 }
 
-/// Calculate the corrections in geocentric longitude of the sun due to
-/// both nutation and aberration. Meeus, chapter 25, pages 167, 168
+/// Geometric position of the Sun, i.e. without the effects of nutation and
+/// aberration, referred to the mean equinox of the date
+#[derive(Debug, Copy, Clone)]
+pub struct GeometricPosition {
+    /// Geocentric ecliptical longitude, FK5, in degrees [0, 360)
+    pub longitude: Degrees,
+
+    /// Geocentric ecliptical latitude, FK5, in degrees [-90, 90)
+    pub latitude: Degrees,
+
+    /// Distance Earth-Sun, in AU
+    pub distance: f64,
+}
+
+/// Apparent position of the Sun, i.e. corrected for nutation and aberration,
+/// referred to the true equinox of the date
+#[derive(Debug, Copy, Clone)]
+pub struct ApparentPosition {
+    /// Apparent geocentric ecliptical longitude, in degrees [0, 360)
+    pub longitude: Degrees,
+
+    /// Apparent geocentric ecliptical latitude, in degrees [-90, 90)
+    pub latitude: Degrees,
+
+    /// Distance Earth-Sun, in AU
+    pub distance: f64,
+}
+
+/// Calculate the geometric position of the sun. Meeus, chapter 25, page 166
 /// In: Julian day
-/// Out: Apparent geocentric longitude of the sun, in degrees [0, 360)
-pub fn apparent_geocentric_longitude(jd: JD) -> Degrees {
-    let longitude = geocentric_ecliptical_longitude(jd);
-    let latitude = geocentric_ecliptical_latitude(jd);
-    let (long, _) = geocentric_ecliptical_to_fk5(jd, longitude, latitude);
+/// Out: Geometric position, w.r.t. the mean equinox of the date
+pub fn geometric_position(jd: JD) -> GeometricPosition {
+    geometric_position_truncated(jd, 0.0)
+}
 
-    let r = distance_earth_sun_ae(jd);
+/// Calculate the geometric position of the sun from the truncated VSOP87 series
+/// In:
+/// jd: Julian day
+/// threshold: smallest contribution of a VSOP87 term to evaluate
+/// Out: Geometric position, w.r.t. the mean equinox of the date
+fn geometric_position_truncated(jd: JD, threshold: f64) -> GeometricPosition {
+    let heliocentric_longitude =
+        Degrees::from(Radians::new(heliocentric_l(jd, threshold))).map_to_0_to_360();
+    let heliocentric_latitude =
+        Degrees::from(Radians::new(heliocentric_b(jd, threshold))).map_to_neg90_to_90();
+
+    // SS: Meeus, chapter 25, page 166
+    let longitude = heliocentric_longitude + Degrees::new(180.0);
+    let latitude = -heliocentric_latitude;
+    let (longitude, latitude) = geocentric_ecliptical_to_fk5(jd, longitude, latitude);
+
+    GeometricPosition {
+        longitude: longitude.map_to_0_to_360(),
+        latitude: latitude.map_to_neg90_to_90(),
+        distance: heliocentric_r(jd, threshold),
+    }
+}
+
+/// Calculate the apparent position of the sun, i.e. the geometric position
+/// corrected for both nutation and aberration. Meeus, chapter 25, pages 167, 168
+/// In: Julian day
+/// Out: Apparent position, w.r.t. the true equinox of the date
+pub fn apparent_position(jd: JD) -> ApparentPosition {
+    apparent_position_truncated(jd, 0.0)
+}
+
+/// Calculate the apparent position of the sun from the VSOP87 series, skipping
+/// all terms that contribute less than threshold. A threshold of 1e-7 gives
+/// about 0.1", 1e-6 about 0.5"
+/// In:
+/// jd: Julian day
+/// threshold: smallest contribution of a VSOP87 term to evaluate, in radians
+/// for longitude and latitude, and in AU for the distance. 0 for the full series
+/// Out: Apparent position, w.r.t. the true equinox of the date
+pub fn apparent_position_truncated(jd: JD, threshold: f64) -> ApparentPosition {
+    apparent_position_with_nutation(jd, threshold, nutation_in_longitude(jd))
+}
+
+/// Calculate the apparent position of the sun from the VSOP87 series and the
+/// nutation in longitude passed in
+/// In:
+/// jd: Julian day
+/// threshold: smallest contribution of a VSOP87 term to evaluate, 0 for the
+/// full series
+/// delta_psi: nutation in longitude, in arcsec
+/// Out: Apparent position, w.r.t. the true equinox of the date
+pub(crate) fn apparent_position_with_nutation(
+    jd: JD,
+    threshold: f64,
+    delta_psi: ArcSec,
+) -> ApparentPosition {
+    let geometric = geometric_position_truncated(jd, threshold);
 
     // SS: correction due to nutation
-    let delta_psi = Degrees::from(nutation_in_longitude(jd));
+    let delta_psi = Degrees::from(delta_psi);
 
     let delta_lambda = Degrees::from(variation_geocentric_longitude(jd));
-    let aberration_correction = delta_lambda * (-0.005_775_518 * r);
+    let aberration_correction = delta_lambda * (-0.005_775_518 * geometric.distance);
+
+    // SS: nutation and aberration only affect the longitude
+    ApparentPosition {
+        longitude: (geometric.longitude + delta_psi + aberration_correction).map_to_0_to_360(),
+        latitude: geometric.latitude,
+        distance: geometric.distance,
+    }
+}
+
+/// Calculate the apparent position of the sun with the low accuracy
+/// formulas of Meeus, chapter 25, pages 163, 164. Accurate to 0.01 deg,
+/// which is sufficient for sunrise and sunset to the minute, and much
+/// faster than summing up the VSOP87 series.
+/// In: Julian day, in dynamical time
+/// Out: Apparent position, w.r.t. the true equinox of the date. The latitude
+/// is not computed and always 0
+///
+/// Meeus, example 25.a, 1992 October 13, 0h TD
+/// ```
+/// use moonlib::config::AngleUnit;
+/// use moonlib::date::jd::JD;
+/// use moonlib::sun::position_fast;
+///
+/// let position = position_fast(JD::new(2_448_908.5));
+/// assert!((position.longitude.value(AngleUnit::Degrees) - 199.908_95).abs() < 1e-5);
+/// assert!((position.distance - 0.997_66).abs() < 1e-5);
+/// ```
+pub fn position_fast(jd: JD) -> ApparentPosition {
+    let (true_longitude, distance) = true_longitude_and_distance(jd);
+
+    // SS: correction for nutation and aberration
+    let omega = Radians::from(moon::mean_node(jd)).0;
+    let longitude = true_longitude + Degrees::new(-0.00569 - 0.00478 * math::sin(omega));
+
+    ApparentPosition {
+        longitude: longitude.map_to_0_to_360(),
+        latitude: Degrees::new(0.0),
+        distance,
+    }
+}
+
+/// Calculate the Sun's geometric true longitude and distance with the low
+/// accuracy formulas of Meeus, chapter 25, pages 163, 164
+/// In: Julian day, in dynamical time
+/// Out: true longitude, w.r.t. the mean equinox of the date, in degrees, and
+/// distance Earth-Sun, in AU
+fn true_longitude_and_distance(jd: JD) -> (Degrees, f64) {
+    let t = jd.centuries_from_epoch_j2000();
+    let t2 = t * t;
+
+    // SS: eq (25.2), (25.3) and (25.4)
+    let l0 = Degrees::new(280.46646 + 36000.76983 * t + 0.0003032 * t2);
+    let m = Degrees::new(357.52911 + 35999.05029 * t - 0.0001537 * t2);
+    let e = earth::orbital_eccentricity(jd);
+
+    // SS: Sun's equation of the center
+    let m_radians = Radians::from(m).0;
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t2) * math::sin(m_radians)
+        + (0.019993 - 0.000101 * t) * math::sin(2.0 * m_radians)
+        + 0.000289 * math::sin(3.0 * m_radians);
+
+    // SS: true longitude and true anomaly
+    let true_longitude = l0 + Degrees::new(c);
+    let nu = m + Degrees::new(c);
+
+    // SS: eq (25.5)
+    let distance = 1.000001018 * (1.0 - e * e) / (1.0 + e * math::cos(Radians::from(nu).0));
+
+    (true_longitude, distance)
+}
+
+/// Calculate the Sun's geometric true longitude from its mean orbital
+/// elements by solving Kepler's equation, Meeus, chapter 30. This is
+/// independent of the VSOP87 tables and of the series for the equation of
+/// the center in position_fast, so it can be used to cross-check both.
+/// It ignores the perturbations by the Moon and the planets and is good to
+/// about 0.01 deg.
+/// In: Julian day, in dynamical time
+/// Out: Geometric true longitude, w.r.t. the mean equinox of the date, in
+/// degrees [0, 360)
+pub fn true_longitude_kepler(jd: JD) -> Degrees {
+    let t = jd.centuries_from_epoch_j2000();
+    let t2 = t * t;
+
+    // SS: mean elements, eq (25.2), (25.3) and (25.4)
+    let l0 = Degrees::new(280.46646 + 36000.76983 * t + 0.0003032 * t2);
+    let m = Degrees::new(357.52911 + 35999.05029 * t - 0.0001537 * t2).map_to_0_to_360();
+    let e = earth::orbital_eccentricity(jd);
+
+    let eccentric_anomaly = solve_kepler(Radians::from(m).0, e);
+
+    // SS: true anomaly, eq (30.1)
+    let nu = 2.0
+        * math::atan2(
+            (1.0 + e).sqrt() * math::sin(eccentric_anomaly / 2.0),
+            (1.0 - e).sqrt() * math::cos(eccentric_anomaly / 2.0),
+        );
+
+    // SS: the longitude of the perihelion is L0 - M
+    (l0 - m + Degrees::from(Radians::new(nu))).map_to_0_to_360()
+}
+
+/// Solve Kepler's equation E = M + e sin E with Newton's method,
+/// Meeus, chapter 30, eq (30.7)
+/// In:
+/// m: mean anomaly, in radians
+/// e: eccentricity of the orbit, < 1
+/// Out: eccentric anomaly, in radians
+fn solve_kepler(m: f64, e: f64) -> f64 {
+    let mut eccentric_anomaly = m;
+    loop {
+        let delta = (m + e * math::sin(eccentric_anomaly) - eccentric_anomaly)
+            / (1.0 - e * math::cos(eccentric_anomaly));
+        eccentric_anomaly += delta;
+        if delta.abs() < 1e-12 {
+            return eccentric_anomaly;
+        }
+    }
+}
+
+/// Geocentric ecliptical coordinates of the sun
+/// In:
+/// jd: Julian day, in dynamical time
+/// equinox: Equinox and ecliptic the coordinates are referred to. For the
+/// equinox of the date, the apparent coordinates are returned, as by
+/// apparent_position. For J2000, the effect of nutation is removed and the
+/// coordinates are precessed to the mean equinox and ecliptic of J2000.0.
+/// Aberration is kept in both cases.
+/// Out:
+/// longitude, in degrees [0, 360)
+/// latitude, in degrees [-90, 90]
+pub fn ecliptical_coordinates(jd: JD, equinox: Equinox) -> (Degrees, Degrees) {
+    let position = apparent_position(jd);
+    precession::apparent_ecliptical_2_equinox(position.longitude, position.latitude, jd, equinox)
+}
 
-    (long + delta_psi + aberration_correction).map_to_0_to_360()
+/// Apparent geocentric equatorial coordinates of the sun, with the low
+/// accuracy formulas of Meeus, chapter 25, pages 163, 164
+/// In: Julian day, in dynamical time
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+///
+/// Meeus, example 25.a, 1992 October 13, 0h TD
+/// ```
+/// use moonlib::config::AngleUnit;
+/// use moonlib::date::jd::JD;
+/// use moonlib::sun::equatorial_coordinates_fast;
+///
+/// let (ra, dec) = equatorial_coordinates_fast(JD::new(2_448_908.5));
+/// assert!((ra.degrees().value(AngleUnit::Degrees) - 198.380_83).abs() < 1e-4);
+/// assert!((dec.degrees().value(AngleUnit::Degrees) + 7.785_07).abs() < 1e-4);
+/// ```
+pub fn equatorial_coordinates_fast(jd: JD) -> (RightAscension, Declination) {
+    let position = position_fast(jd);
+
+    // SS: eq (25.8), instead of the true obliquity, which requires the nutation series
+    let omega = Radians::from(moon::mean_node(jd)).0;
+    let eps = ecliptic::mean_obliquity(jd) + Degrees::new(0.00256 * math::cos(omega));
+
+    coordinates::ecliptical_2_equatorial(position.longitude, position.latitude, eps)
 }
 
-/// Apparent geocentric latitude of the sun. Meeus, chapter 25, pages 167, 168
+/// Apparent geocentric equatorial coordinates of the sun
+/// In: Julian day, in dynamical time
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+pub(crate) fn equatorial_coordinates(jd: JD) -> (RightAscension, Declination) {
+    let position = apparent_position(jd);
+    let eps = ecliptic::true_obliquity(jd);
+    coordinates::ecliptical_2_equatorial(position.longitude, position.latitude, eps)
+}
+
+/// Apparent geocentric equatorial coordinates of the sun, from the VSOP87
+/// series truncated for Precision::Standard
+/// In: Julian day, in dynamical time
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+pub(crate) fn equatorial_coordinates_standard(jd: JD) -> (RightAscension, Declination) {
+    let position = apparent_position_truncated(jd, STANDARD_SERIES_THRESHOLD);
+    let eps = ecliptic::true_obliquity(jd);
+    coordinates::ecliptical_2_equatorial(position.longitude, position.latitude, eps)
+}
+
+/// Apparent geocentric equatorial coordinates of the sun, with the low
+/// accuracy nutation of Meeus, chapter 22, page 144
+/// In: Julian day, in dynamical time
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+pub(crate) fn equatorial_coordinates_low_nutation(jd: JD) -> (RightAscension, Declination) {
+    equatorial_coordinates_with_nutation(jd, 0.0, NutationModel::Low)
+}
+
+/// Apparent geocentric equatorial coordinates of the sun, from the VSOP87
+/// series truncated for Precision::Standard and the low accuracy nutation
+/// of Meeus, chapter 22, page 144
+/// In: Julian day, in dynamical time
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+pub(crate) fn equatorial_coordinates_standard_low_nutation(
+    jd: JD,
+) -> (RightAscension, Declination) {
+    equatorial_coordinates_with_nutation(jd, STANDARD_SERIES_THRESHOLD, NutationModel::Low)
+}
+
+/// Apparent geocentric equatorial coordinates of the sun
+/// In:
+/// jd: Julian day, in dynamical time
+/// threshold: smallest contribution of a VSOP87 term to evaluate, 0 for the
+/// full series
+/// nutation: nutation model
+/// Out:
+/// right ascension, in degrees [0, 360)
+/// declination, in degrees [-90, 90)
+fn equatorial_coordinates_with_nutation(
+    jd: JD,
+    threshold: f64,
+    nutation: NutationModel,
+) -> (RightAscension, Declination) {
+    let (delta_psi, delta_epsilon) = nutation.nutation(jd);
+    let position = apparent_position_with_nutation(jd, threshold, delta_psi);
+    let eps = ecliptic::mean_obliquity(jd) + Degrees::from(delta_epsilon);
+    coordinates::ecliptical_2_equatorial(position.longitude, position.latitude, eps)
+}
+
+/// Horizontal coordinates of the sun. The sun's parallax of less than 9"
+/// and atmospheric refraction are not taken into account.
+/// In:
+/// jd: Julian day, in dynamical time
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// Out:
+/// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+/// Altitude, in degrees [-90, 90)
+pub(crate) fn horizontal_coordinates(
+    jd: JD,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+) -> (Degrees, Degrees) {
+    let (ra, decl) = equatorial_coordinates(jd);
+    let siderial_time_apparent_greenwich = earth::apparent_siderial_time(jd);
+    let siderial_time_local =
+        earth::local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
+    let hour_angle = earth::hour_angle(siderial_time_local, ra);
+    coordinates::equatorial_2_horizontal(decl, hour_angle, latitude_observer)
+}
+
+/// The sun's topocentric position for an observer
+#[derive(Debug, Copy, Clone)]
+pub struct Topocentric {
+    /// Topocentric right ascension, in degrees [0, 360)
+    pub right_ascension: RightAscension,
+
+    /// Topocentric declination, in degrees [-90, 90)
+    pub declination: Declination,
+
+    /// Local hour angle, in degrees [0, 360)
+    pub hour_angle: Degrees,
+
+    /// Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+    pub azimuth: Degrees,
+
+    /// True altitude, i.e. without atmospheric refraction, in degrees [-90, 90)
+    pub true_altitude: Degrees,
+
+    /// Apparent altitude, i.e. corrected for atmospheric refraction, in
+    /// degrees [-90, 90)
+    pub apparent_altitude: Degrees,
+}
+
+/// Calculate the sun's topocentric position, corrected for the solar
+/// parallax of about 8.8" and for atmospheric refraction at the observer's
+/// pressure and temperature, the same way as the Moon's in
+/// moon::position::topocentric_coordinates.
+/// The observer is taken to be at sea level, the observer's height changes
+/// the sun's parallax by less than 0.01".
+/// In:
+/// jd: Julian day in dynamical time
+/// observer: Observer's location and atmospheric conditions
+/// Out:
+/// Topocentric position
+pub fn topocentric_ra_dec(jd: JD, observer: &Observer) -> Topocentric {
+    let (ra, decl) = equatorial_coordinates(jd);
+
+    let distance = distance_earth_sun(jd);
+    let (ra_topocentric, decl_topocentric) = coordinates::equatorial_2_topocentric(
+        ra,
+        decl,
+        observer.longitude,
+        observer.latitude,
+        0.0,
+        distance,
+        jd,
+    );
+
+    let siderial_time_apparent_greenwich = earth::apparent_siderial_time(jd);
+    let siderial_time_local =
+        earth::local_siderial_time(siderial_time_apparent_greenwich, observer.longitude);
+    let hour_angle = earth::hour_angle(siderial_time_local, ra_topocentric);
+    let (azimuth, true_altitude) =
+        coordinates::equatorial_2_horizontal(decl_topocentric, hour_angle, observer.latitude);
+
+    // SS: add correction for atmospheric refraction
+    let apparent_altitude = refraction::correct_altitude(
+        true_altitude,
+        Refraction::TrueToApparent,
+        observer.pressure,
+        observer.temperature,
+    );
+
+    Topocentric {
+        right_ascension: ra_topocentric,
+        declination: decl_topocentric,
+        hour_angle,
+        azimuth,
+        true_altitude,
+        apparent_altitude,
+    }
+}
+
+/// Apparent geocentric longitude of the sun.
+/// In: Julian day
+/// Out: Apparent geocentric longitude of the sun, in degrees [0, 360)
+#[deprecated(note = "use apparent_position(jd).longitude")]
+pub fn apparent_geocentric_longitude(jd: JD) -> Degrees {
+    apparent_position(jd).longitude
+}
+
+/// Apparent geocentric latitude of the sun.
 /// In: Julian day
 /// Out: Apparent geocentric latitude of the sun, in degrees [-90, 90)
+#[deprecated(note = "use apparent_position(jd).latitude")]
 pub fn apparent_geometric_latitude(jd: JD) -> Degrees {
-    let longitude = geocentric_ecliptical_longitude(jd);
-    let latitude = geocentric_ecliptical_latitude(jd);
-    let (_, lat) = geocentric_ecliptical_to_fk5(jd, longitude, latitude);
-    lat.map_to_neg90_to_90()
+    apparent_position(jd).latitude
 }
 
 #[cfg(test)]
@@ -338,6 +731,21 @@ mod tests {
     }
 
     #[test]
+    fn geometric_position_test() {
+        // SS: Meeus, example 25.b, 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let position = geometric_position(jd);
+
+        // Assert
+        assert_approx_eq!(199.907272, position.longitude.0, 0.000_01);
+        assert_approx_eq!(0.000200, position.latitude.0, 0.000_01);
+        assert_approx_eq!(0.997608, position.distance, 0.000_001);
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn geocentric_ecliptical_to_fk5_test() {
         // SS: 1992 October 13, 0h TD
         let jd = JD::from_date(Date::new(1992, 10, 13.0));
@@ -348,4 +756,255 @@ mod tests {
         // Assert
         assert_approx_eq!(199.90598818016153, longitude.0, 0.000_001);
     }
+
+    #[test]
+    fn apparent_position_test() {
+        // SS: 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let position = apparent_position(jd);
+
+        // Assert
+        assert_approx_eq!(199.90598818016153, position.longitude.0, 0.000_001);
+        assert_approx_eq!(0.000200, position.latitude.0, 0.000_01);
+    }
+
+    #[test]
+    #[cfg(not(feature = "sun-vsop87"))]
+    fn apparent_position_low_accuracy_test() {
+        // SS: 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let position = apparent_position(jd);
+
+        // Assert
+
+        // SS: Meeus, example 25.a, the low accuracy formulas are good to 0.01 deg
+        assert_approx_eq!(199.90598818016153, position.longitude.0, 0.01);
+        assert_approx_eq!(0.0, position.latitude.0, 0.001);
+        assert_approx_eq!(0.99766, position.distance, 0.0001);
+    }
+
+    #[test]
+    fn horizontal_coordinates_test() {
+        // Arrange
+
+        // SS: Munich, local noon close to the March equinox
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 20, 11, 21, 0.0));
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let (azimuth, altitude) = horizontal_coordinates(jd, longitude_observer, latitude_observer);
+
+        // Assert
+        assert_approx_eq!(180.0, azimuth.0, 0.5);
+        assert_approx_eq!(90.0 - 48.1, altitude.0, 0.5);
+    }
+
+    #[test]
+    fn topocentric_ra_dec_test() {
+        // Arrange
+
+        // SS: Munich, local noon close to the March equinox
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 20, 11, 21, 0.0));
+        let observer = Observer::new(-11.6, 48.1, 1, 1010.0, 10.0);
+        let (ra, decl) = equatorial_coordinates(jd);
+        let (_, altitude) = horizontal_coordinates(jd, observer.longitude, observer.latitude);
+
+        // Act
+        let topocentric = topocentric_ra_dec(jd, &observer);
+
+        // Assert
+
+        // SS: the parallax lowers the sun by 8.8" cos(altitude), here about
+        // 6.5", mostly in declination, as the sun is close to the meridian
+        let parallax =
+            8.794 / 3600.0 / distance_earth_sun_ae(jd) * math::cos(Radians::from(altitude).0);
+        assert_approx_eq!(
+            parallax,
+            (altitude - topocentric.true_altitude).0,
+            0.1 / 3600.0
+        );
+        assert_approx_eq!(ra.0, topocentric.right_ascension.0, 1.0 / 3600.0);
+        assert!(topocentric.declination.0 < decl.0);
+
+        // SS: refraction at 42 degrees altitude is about 1'
+        let refraction = (topocentric.apparent_altitude - topocentric.true_altitude).0;
+        assert_approx_eq!(1.0 / 60.0, refraction, 0.2 / 60.0);
+    }
+
+    #[test]
+    fn topocentric_ra_dec_horizon_test() {
+        // Arrange
+
+        // SS: Munich, close to sunset on the March equinox
+        let observer = Observer::new(-11.6, 48.1, 1, 1010.0, 10.0);
+        let jd = JD::from_date(Date::from_date_hms(2000, 3, 20, 17, 20, 0.0));
+
+        // Act
+        let topocentric = topocentric_ra_dec(jd, &observer);
+
+        // Assert
+
+        // SS: close to the horizon, the parallax is the full 8.8" and
+        // refraction lifts the sun by about 0.5 degrees
+        let (_, altitude) = horizontal_coordinates(jd, observer.longitude, observer.latitude);
+        assert!(altitude.0.abs() < 2.0);
+        assert_approx_eq!(
+            8.8 / 3600.0,
+            (altitude - topocentric.true_altitude).0,
+            0.2 / 3600.0
+        );
+        assert!(topocentric.apparent_altitude.0 - topocentric.true_altitude.0 > 0.3);
+    }
+
+    #[test]
+    fn ecliptical_coordinates_test() {
+        // SS: Meeus, example 25.b, 1992 October 13, 0h TD
+
+        // Arrange
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let (lambda, beta) = ecliptical_coordinates(jd, Equinox::OfDate);
+        let (lambda_j2000, beta_j2000) = ecliptical_coordinates(jd, Equinox::J2000);
+
+        // Assert
+        assert_approx_eq!(Degrees::from_dms(199, 54, 21.818).0, lambda.0, 0.000_1);
+        assert_approx_eq!(Degrees::from_dms(0, 0, 0.72).0, beta.0, 0.000_01);
+
+        // SS: without nutation in longitude, 15.908", and precessed by
+        // 50.29" per year for 7.22 years
+        assert_approx_eq!(
+            lambda.0 + (-15.908 + 50.29 * 7.22) / 3600.0,
+            lambda_j2000.0,
+            0.000_5
+        );
+        assert!(beta_j2000.0.abs() < 1.0 / 3600.0);
+    }
+
+    #[test]
+    fn position_fast_test() {
+        // SS: Meeus, example 25.a, 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let position = position_fast(jd);
+
+        // Assert
+        assert_approx_eq!(199.90895, position.longitude.0, 0.000_01);
+        assert_approx_eq!(0.99766, position.distance, 0.000_01);
+
+        // SS: the low accuracy position is within 0.01 deg of VSOP87
+        assert_approx_eq!(
+            apparent_position(jd).longitude.0,
+            position.longitude.0,
+            0.01
+        );
+    }
+
+    #[test]
+    fn equatorial_coordinates_fast_test() {
+        // SS: Meeus, example 25.a, 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let (ra, decl) = equatorial_coordinates_fast(jd);
+
+        // Assert
+        assert_approx_eq!(198.38083, ra.0, 0.000_1);
+        assert_approx_eq!(-7.78507, decl.0, 0.000_1);
+    }
+
+    #[test]
+    fn apparent_position_truncated_test() {
+        // SS: 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let full = apparent_position(jd);
+        let standard = apparent_position_truncated(jd, STANDARD_SERIES_THRESHOLD);
+        let coarse = apparent_position_truncated(jd, 1e-6);
+
+        // Assert
+        assert_approx_eq!(full.longitude.0, standard.longitude.0, 0.2 / 3600.0);
+        assert_approx_eq!(full.latitude.0, standard.latitude.0, 0.2 / 3600.0);
+        assert_approx_eq!(full.distance, standard.distance, 1e-6);
+        assert_approx_eq!(full.longitude.0, coarse.longitude.0, 1.0 / 3600.0);
+        assert_approx_eq!(full.distance, coarse.distance, 1e-5);
+    }
+
+    #[test]
+    fn apparent_position_truncated_full_series_test() {
+        // SS: 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let full = apparent_position(jd);
+        let truncated = apparent_position_truncated(jd, 0.0);
+
+        // Assert
+        assert_eq!(full.longitude.0, truncated.longitude.0);
+        assert_eq!(full.latitude.0, truncated.latitude.0);
+        assert_eq!(full.distance, truncated.distance);
+    }
+
+    #[test]
+    fn solve_kepler_test() {
+        // SS: Meeus, example 30.a, e = 0.1, M = 5 deg
+
+        // Act
+        let eccentric_anomaly = solve_kepler(Radians::from(Degrees::new(5.0)).0, 0.1);
+
+        // Assert
+        assert_approx_eq!(
+            5.554_589_253,
+            Degrees::from(Radians::new(eccentric_anomaly)).0,
+            0.000_000_001
+        );
+    }
+
+    #[test]
+    fn true_longitude_kepler_test() {
+        // SS: Meeus, example 25.a, 1992 October 13, 0h TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let longitude = true_longitude_kepler(jd);
+
+        // Assert
+        assert_approx_eq!(199.90988, longitude.0, 0.000_1);
+    }
+
+    #[test]
+    fn true_longitude_kepler_vsop87_cross_check_test() {
+        // SS: the VSOP87 tables are generated and large, so a transcription
+        // error in a single term would easily go unnoticed. Compare against
+        // the Keplerian orbit every 11.3 days from 1800 to 2200, so the
+        // dates sample all anomalies and many lunar and planetary periods.
+        let start = JD::from_date(Date::new(1800, 1, 1.0));
+
+        for i in 0..13_000 {
+            let jd = JD::new(start.jd + i as f64 * 11.3);
+
+            // Act
+            let kepler = true_longitude_kepler(jd);
+            let vsop87 = geometric_position(jd).longitude;
+
+            // Assert
+            // SS: the largest difference from 1800 to 2200 is 0.0103 deg,
+            // caused by the perturbations the Keplerian orbit ignores
+            let difference = (kepler - vsop87).map_neg180_to_180();
+            assert!(
+                difference.0.abs() < 0.012,
+                "JD {}: Kepler {}, VSOP87 {}",
+                jd.jd,
+                kepler.0,
+                vsop87.0
+            );
+        }
+    }
 }
@@ -0,0 +1,235 @@
+//! Sunrise, sunset and solar noon. Sunrise and sunset are classified like
+//! the Moon's rise and set, see moon::day_visibility, so polar day and
+//! polar night are told apart from a Sun that rises and sets.
+
+use crate::config::MoonlibConfig;
+use crate::date::jd::JD;
+use crate::ecliptic;
+use crate::nutation::nutation_in_longitude;
+use crate::refraction;
+use crate::rise_set_transit::{altitude_above_target, bound_julian_day, classify_day};
+use crate::session::Observer;
+use crate::sun::position::equatorial_coordinates;
+use crate::sun::twilight;
+use crate::util::degrees::Degrees;
+use crate::util::math;
+use crate::util::radians::Radians;
+
+pub use crate::rise_set_transit::DayVisibility;
+
+/// Standard altitude of the Sun's center at rise and set, in degrees,
+/// accounting for refraction and the Sun's semidiameter.
+/// Meeus, chapter 15, page 101
+const SUN_STANDARD_ALTITUDE: f64 = -0.8333;
+
+/// Altitude of the Sun's center at rise and set
+/// In: pressure, in millibars, 0 or less for no refraction
+/// Out: altitude, in degrees
+fn standard_altitude(pressure: f64) -> Degrees {
+    if refraction::is_disabled(pressure) {
+        Degrees::new(SUN_STANDARD_ALTITUDE + refraction::HORIZON_REFRACTION)
+    } else {
+        Degrees::new(SUN_STANDARD_ALTITUDE)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SolarDay {
+    /// Sunrise and sunset, or whether the Sun stays above or below the
+    /// horizon all day
+    pub visibility: DayVisibility,
+
+    /// Time the Sun crosses the observer's meridian, also during polar
+    /// day and night
+    pub noon: JD,
+}
+
+/// Calculate the equation of time, the difference between apparent and
+/// mean solar time, Meeus chapter 28, eq (28.3)
+/// In: Julian day, in dynamical time
+/// Out: Equation of time, in minutes [-20, 20). Positive if the Sun
+/// crosses the meridian before mean noon
+pub fn equation_of_time(jd: JD) -> f64 {
+    let tau = jd.millennia_from_epoch_j2000();
+    let tau2 = tau * tau;
+    let tau3 = tau * tau2;
+    let tau4 = tau * tau3;
+    let tau5 = tau * tau4;
+
+    // SS: Sun's mean longitude, eq (28.2)
+    let l0 = 280.4664567 + 360_007.6982779 * tau + 0.03032028 * tau2 + tau3 / 49_931.0
+        - tau4 / 15_300.0
+        - tau5 / 2_000_000.0;
+
+    let (ra, _) = equatorial_coordinates(jd);
+    let delta_psi = Degrees::from(nutation_in_longitude(jd));
+    let eps = Radians::from(ecliptic::true_obliquity(jd));
+
+    let e = Degrees::new(l0 - 0.0057183 - ra.0 + delta_psi.0 * math::cos(eps.0));
+
+    // SS: 1 degree is 4 minutes of time
+    e.map_neg180_to_180().0 * 4.0
+}
+
+/// Calculate sunrise, sunset and solar noon for the observer's day
+/// In:
+/// jd: Julian Day, in dynamical time. Its calendar date is the observer's local date
+/// observer: Observer's location, time zone and atmospheric conditions
+/// config: Precision and nutation model of the Sun's position
+/// Out:
+/// Sunrise, sunset and solar noon, in dynamical time
+pub fn solar_day(jd: JD, observer: &Observer, config: &MoonlibConfig) -> SolarDay {
+    let (jd_min, _, jd_max) = bound_julian_day(jd, observer.timezone_offset);
+
+    let position = twilight::position(config);
+    let (pressure, _) = config.refraction.atmosphere(observer);
+    let target = standard_altitude(pressure);
+    let above =
+        |jd: JD| altitude_above_target(jd, target, observer.longitude, observer.latitude, position);
+
+    SolarDay {
+        visibility: classify_day(jd_min, jd_max, &above),
+        noon: solar_noon(jd_min, jd_max, observer.longitude),
+    }
+}
+
+/// Time the Sun crosses the meridian, from the equation of time
+/// In:
+/// jd_min, jd_max: start and end of the observer's day
+/// longitude_observer: in degrees [-180, 180), positive west of Greenwich
+/// Out:
+/// Solar noon on the observer's day
+fn solar_noon(jd_min: JD, jd_max: JD, longitude_observer: Degrees) -> JD {
+    // SS: mean noon at the observer's longitude. The mean Sun moves 15
+    // degrees per hour and is in the meridian at Greenwich at 12h
+    let mut mean_noon = jd_min.start_of_local_day(0);
+    mean_noon.add_hours(12.0 + longitude_observer.to_hours());
+    while mean_noon < jd_min {
+        mean_noon.add_hours(24.0);
+    }
+    while mean_noon >= jd_max {
+        mean_noon.add_hours(-24.0);
+    }
+
+    // SS: the equation of time changes by less than a second per hour, so
+    // one correction step is enough
+    let mut noon = mean_noon;
+    noon.add_hours(-equation_of_time(mean_noon) / 60.0);
+    let mut refined = mean_noon;
+    refined.add_hours(-equation_of_time(noon) / 60.0);
+    refined
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::date::date::Date;
+    use crate::earth;
+    use crate::rise_set_transit::{calculate_rise_set_transit, InputKind, OutputKind};
+    use crate::test_util::{munich, tromso};
+
+    #[test]
+    fn equation_of_time_test() {
+        // Meeus, example 28.a, page 184
+
+        // Arrange
+
+        // SS: 1992 October 13.0 TD
+        let jd = JD::from_date(Date::new(1992, 10, 13.0));
+
+        // Act
+        let e = equation_of_time(jd);
+
+        // Assert
+
+        // SS: 13m 42.6s
+        assert_approx_eq!(13.0 + 42.6 / 60.0, e, 0.001);
+    }
+
+    #[test]
+    fn rises_and_sets_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+        let observer = munich(0);
+        let config = MoonlibConfig::new();
+
+        // Act
+        let day = solar_day(jd, &observer, &config);
+
+        // Assert
+        match day.visibility {
+            DayVisibility::RisesAndSets { rise, set } => {
+                // SS: the Sun's declination hardly changes within a day, so
+                // noon is halfway between sunrise and sunset
+                assert!(rise < day.noon && day.noon < set);
+                assert_approx_eq!(0.5 * (rise.jd + set.jd), day.noon.jd, 1.0 / 1440.0);
+
+                // SS: the same as Meeus' iteration
+                let expected = calculate_rise_set_transit(
+                    InputKind::Rise,
+                    jd,
+                    0,
+                    Degrees::new(SUN_STANDARD_ALTITUDE),
+                    observer.longitude,
+                    observer.latitude,
+                    equatorial_coordinates,
+                );
+                match expected {
+                    OutputKind::Time(expected) => {
+                        assert_approx_eq!(expected.jd, rise.jd, 1.0 / 1440.0)
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn noon_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.0));
+        let observer = munich(0);
+
+        // Act
+        let day = solar_day(jd, &observer, &MoonlibConfig::new());
+
+        // Assert
+
+        // SS: the Sun is in the meridian
+        let (ra, _) = equatorial_coordinates(day.noon);
+        let theta0 = earth::apparent_siderial_time(day.noon);
+        let theta = earth::local_siderial_time(theta0, observer.longitude);
+        let hour_angle = (theta - ra.degrees()).map_neg180_to_180();
+        assert_approx_eq!(0.0, hour_angle.0, 0.01);
+    }
+
+    #[test]
+    fn midnight_sun_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 6, 21.0));
+
+        // Act
+        let day = solar_day(jd, &tromso(0), &MoonlibConfig::new());
+
+        // Assert
+        assert_eq!(DayVisibility::AlwaysAbove, day.visibility);
+
+        // SS: the Sun still culminates on that day
+        assert!(jd <= day.noon && day.noon.jd < jd.jd + 1.0);
+    }
+
+    #[test]
+    fn polar_night_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 12, 21.0));
+
+        // Act
+        let day = solar_day(jd, &tromso(0), &MoonlibConfig::new());
+
+        // Assert
+        assert_eq!(DayVisibility::AlwaysBelow, day.visibility);
+    }
+}
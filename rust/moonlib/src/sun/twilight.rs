@@ -0,0 +1,249 @@
+//! Calculate the begin and end of twilight
+
+use crate::config::{MoonlibConfig, NutationModel};
+use crate::date::jd::JD;
+use crate::rise_set_transit::{calculate_rise_set_transit, InputKind, OutputKind};
+use crate::session::Precision;
+use crate::sun::position::{
+    equatorial_coordinates, equatorial_coordinates_fast, equatorial_coordinates_low_nutation,
+    equatorial_coordinates_standard, equatorial_coordinates_standard_low_nutation,
+};
+use crate::util::declination::Declination;
+use crate::util::degrees::Degrees;
+use crate::util::right_ascension::RightAscension;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Twilight {
+    Civil,
+    Nautical,
+    Astronomical,
+}
+
+impl Twilight {
+    /// Altitude of the Sun's center at the begin/end of twilight, in degrees
+    pub fn altitude(self) -> Degrees {
+        match self {
+            Twilight::Civil => Degrees::new(-6.0),
+            Twilight::Nautical => Degrees::new(-12.0),
+            Twilight::Astronomical => Degrees::new(-18.0),
+        }
+    }
+}
+
+/// Sun's position for the precision and nutation model requested
+pub(crate) fn position(config: &MoonlibConfig) -> fn(JD) -> (RightAscension, Declination) {
+    match (config.precision, config.nutation) {
+        // SS: the low accuracy position has its own approximation for nutation
+        (Precision::Low, _) => equatorial_coordinates_fast,
+        (Precision::Standard, NutationModel::Full) => equatorial_coordinates_standard,
+        (Precision::Standard, NutationModel::Low) => equatorial_coordinates_standard_low_nutation,
+        (Precision::High, NutationModel::Full) => equatorial_coordinates,
+        (Precision::High, NutationModel::Low) => equatorial_coordinates_low_nutation,
+    }
+}
+
+/// Compute the time twilight begins in the morning
+/// In:
+/// jd: Julian Day, in dynamical time
+/// timezone_offset: Observer's time zone offset
+/// twilight: kind of twilight
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// config: Precision and nutation model of the Sun's position
+pub(crate) fn dawn(
+    jd: JD,
+    timezone_offset: i8,
+    twilight: Twilight,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    config: &MoonlibConfig,
+) -> OutputKind {
+    calculate_rise_set_transit(
+        InputKind::Rise,
+        jd,
+        timezone_offset,
+        twilight.altitude(),
+        longitude_observer,
+        latitude_observer,
+        position(config),
+    )
+}
+
+/// Compute the time twilight ends in the evening
+/// In:
+/// jd: Julian Day, in dynamical time
+/// timezone_offset: Observer's time zone offset
+/// twilight: kind of twilight
+/// longitude_observer: in degrees [-180, 180)
+/// latitude_observer: in degrees, [-90, 90)
+/// config: Precision and nutation model of the Sun's position
+pub(crate) fn dusk(
+    jd: JD,
+    timezone_offset: i8,
+    twilight: Twilight,
+    longitude_observer: Degrees,
+    latitude_observer: Degrees,
+    config: &MoonlibConfig,
+) -> OutputKind {
+    calculate_rise_set_transit(
+        InputKind::Set,
+        jd,
+        timezone_offset,
+        twilight.altitude(),
+        longitude_observer,
+        latitude_observer,
+        position(config),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn time(output: OutputKind) -> JD {
+        match output {
+            OutputKind::Time(jd) => jd,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn dawn_dusk_order_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.5));
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let civil_dawn = time(dawn(
+            jd,
+            0,
+            Twilight::Civil,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        ));
+        let astronomical_dawn = time(dawn(
+            jd,
+            0,
+            Twilight::Astronomical,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        ));
+        let civil_dusk = time(dusk(
+            jd,
+            0,
+            Twilight::Civil,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        ));
+        let astronomical_dusk = time(dusk(
+            jd,
+            0,
+            Twilight::Astronomical,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        ));
+
+        // Assert
+        assert!(astronomical_dawn < civil_dawn);
+        assert!(civil_dawn < civil_dusk);
+        assert!(civil_dusk < astronomical_dusk);
+    }
+
+    #[test]
+    fn no_astronomical_twilight_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 6, 21.5));
+
+        // SS: Oslo, the Sun does not get below -18 deg around summer solstice
+        let longitude_observer = Degrees::new(-10.75);
+        let latitude_observer = Degrees::new(59.91);
+
+        // Act
+        let result = dawn(
+            jd,
+            0,
+            Twilight::Astronomical,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        );
+
+        // Assert
+        assert!(!matches!(result, OutputKind::Time(_)));
+    }
+
+    #[test]
+    fn low_precision_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.5));
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let high = time(dawn(
+            jd,
+            0,
+            Twilight::Civil,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        ));
+        let low = time(dawn(
+            jd,
+            0,
+            Twilight::Civil,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new().with_precision(Precision::Low),
+        ));
+
+        // Assert
+
+        // SS: within a minute
+        assert_approx_eq!(high.jd, low.jd, 1.0 / (24.0 * 60.0));
+    }
+
+    #[test]
+    fn low_nutation_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 3, 23.5));
+
+        // SS: Munich, 11.6 deg east from Greenwich meridian
+        let longitude_observer = Degrees::new(-11.6);
+        let latitude_observer = Degrees::new(48.1);
+
+        // Act
+        let full = time(dusk(
+            jd,
+            0,
+            Twilight::Civil,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new(),
+        ));
+        let low = time(dusk(
+            jd,
+            0,
+            Twilight::Civil,
+            longitude_observer,
+            latitude_observer,
+            &MoonlibConfig::new().with_nutation(NutationModel::Low),
+        ));
+
+        // Assert
+
+        // SS: 0.5" in the Sun's position is well below a second
+        assert_approx_eq!(full.jd, low.jd, 1.0 / 86400.0);
+    }
+}
@@ -0,0 +1,15 @@
+//! Observers shared by the unit tests.
+
+use crate::session::Observer;
+
+/// Munich, 11.6 deg east from Greenwich meridian
+/// In: Observer's time zone offset, in hours
+pub(crate) fn munich(timezone_offset: i8) -> Observer {
+    Observer::new(-11.6, 48.1, timezone_offset, 1013.0, 10.0)
+}
+
+/// Tromsø, Norway, north of the polar circle
+/// In: Observer's time zone offset, in hours
+pub(crate) fn tromso(timezone_offset: i8) -> Observer {
+    Observer::new(-18.96, 69.65, timezone_offset, 1013.0, 10.0)
+}
@@ -0,0 +1,365 @@
+//! Accuracy test vectors: the crate's outputs for a standard grid of dates
+//! and locations, written as CSV. Used to diff behavior before and after a
+//! refactoring and to cross-validate against other libraries like Skyfield
+//! or AA+. All Julian Days are in dynamical time and all angles in degrees.
+//! Floating point values are written with the shortest representation that
+//! round-trips, so any change in the results shows up in a diff.
+//! Also writes moonrise and moonset times for high latitudes found by a
+//! brute-force scan, to check the rise/set algorithm for consistency, see
+//! write_high_latitude_rise_set.
+
+use std::io::Write;
+
+use crate::date::date::Date;
+use crate::date::jd::JD;
+use crate::moon::rise_set_transit::OutputKind;
+use crate::precession::Equinox;
+use crate::rise_set_transit;
+use crate::session::{Observer, Precision, Session, TimeScale};
+use crate::sun::twilight::Twilight;
+use crate::{moon, sun};
+
+const HEADER: &[&str] = &[
+    "location",
+    "longitude",
+    "latitude",
+    "jd",
+    "moon_longitude",
+    "moon_latitude",
+    "moon_distance",
+    "moon_ra",
+    "moon_decl",
+    "moon_ra_j2000",
+    "moon_decl_j2000",
+    "moon_azimuth",
+    "moon_altitude",
+    "moon_phase_angle",
+    "moon_fraction_illuminated",
+    "moon_rise",
+    "moon_transit",
+    "moon_set",
+    "sun_longitude",
+    "sun_latitude",
+    "sun_distance",
+    "sun_azimuth",
+    "sun_altitude",
+    "civil_dawn",
+    "civil_dusk",
+];
+
+pub struct Location {
+    pub name: &'static str,
+    pub observer: Observer,
+}
+
+/// Locations of the standard grid. They cover both hemispheres, the
+/// equator and a location north of the polar circle, where the Moon
+/// and the Sun may not rise or set.
+pub fn standard_locations() -> Vec<Location> {
+    [
+        ("greenwich", 0.0, 51.48),
+        ("munich", -11.6, 48.1),
+        ("quito", 78.5, -0.2),
+        ("sydney", -151.2, -33.87),
+        ("tromso", -18.96, 69.65),
+    ]
+    .iter()
+    .map(|&(name, longitude, latitude)| Location {
+        name,
+        observer: Observer::new(longitude, latitude, 0, 1013.0, 10.0),
+    })
+    .collect()
+}
+
+/// Dates of the standard grid, 0h dynamical time on the 15th of January,
+/// April, July and October, every 25 years from 1900 to 2100
+pub fn standard_dates() -> Vec<JD> {
+    (1900..=2100)
+        .step_by(25)
+        .flat_map(|year| {
+            [1, 4, 7, 10]
+                .iter()
+                .map(move |&month| JD::from_date(Date::new(year, month, 15.0)))
+        })
+        .collect()
+}
+
+fn format_output(output: OutputKind) -> String {
+    match output {
+        OutputKind::Time(jd) => jd.jd.to_string(),
+        OutputKind::NeverRises => "never_rises".to_string(),
+        OutputKind::NeverSets => "never_sets".to_string(),
+        OutputKind::NeverTransits => "never_transits".to_string(),
+    }
+}
+
+fn row(jd: JD, location: &Location) -> Vec<String> {
+    let observer = &location.observer;
+
+    let session = Session::new(*observer, jd, jd)
+        .with_time_scale(TimeScale::TT)
+        .with_precision(Precision::Standard);
+
+    let (moon_ra, moon_decl) = moon::position::equatorial_coordinates(jd, Equinox::OfDate);
+    let (moon_ra_j2000, moon_decl_j2000) =
+        moon::position::equatorial_coordinates(jd, Equinox::J2000);
+    let (moon_azimuth, moon_altitude) =
+        moon::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+
+    let sun = sun::apparent_position(jd);
+    let (sun_azimuth, sun_altitude) =
+        sun::position::horizontal_coordinates(jd, observer.longitude, observer.latitude);
+    let (civil_dawn, civil_dusk) = session.sun().twilight(Twilight::Civil)[0];
+
+    vec![
+        location.name.to_string(),
+        observer.longitude.0.to_string(),
+        observer.latitude.0.to_string(),
+        jd.jd.to_string(),
+        moon::position::geocentric_longitude(jd).0.to_string(),
+        moon::geocentric(jd).beta.0.to_string(),
+        moon::position::distance_from_earth(jd).to_string(),
+        moon_ra.0.to_string(),
+        moon_decl.0.to_string(),
+        moon_ra_j2000.0.to_string(),
+        moon_decl_j2000.0.to_string(),
+        moon_azimuth.0.to_string(),
+        moon_altitude.0.to_string(),
+        moon::phase::phase_info(jd).phase_angle.0.to_string(),
+        moon::phase::fraction_illuminated(jd).to_string(),
+        format_output(session.moon().rise()[0]),
+        format_output(session.moon().transit()[0]),
+        format_output(session.moon().set()[0]),
+        sun.longitude.0.to_string(),
+        sun.latitude.0.to_string(),
+        sun.distance.to_string(),
+        sun_azimuth.0.to_string(),
+        sun_altitude.0.to_string(),
+        format_output(civil_dawn),
+        format_output(civil_dusk),
+    ]
+}
+
+/// Write the test vectors for all combinations of dates and locations.
+/// In:
+/// writer: CSV output
+/// dates: Julian Days in dynamical time
+/// locations: Observer locations
+pub fn write_csv<W: Write>(
+    writer: &mut W,
+    dates: &[JD],
+    locations: &[Location],
+) -> std::io::Result<()> {
+    writeln!(writer, "{}", HEADER.join(","))?;
+
+    for location in locations {
+        for &jd in dates {
+            writeln!(writer, "{}", row(jd, location).join(","))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A location of the high-latitude rise/set scan, with the time zone
+/// offset that defines its local days
+pub struct HighLatitudeLocation {
+    pub name: &'static str,
+    pub observer: Observer,
+}
+
+/// Locations between 54 and 78 degrees north and south, where the Moon
+/// stays above or below the horizon for days and crosses it at a shallow
+/// angle
+pub fn high_latitude_locations() -> Vec<HighLatitudeLocation> {
+    [
+        ("tromso", -18.96, 69.65, 1),
+        ("utqiagvik", 156.79, 71.29, -9),
+        ("ushuaia", 68.30, -54.80, -3),
+        ("mcmurdo", -166.67, -77.85, 13),
+    ]
+    .iter()
+    .map(
+        |&(name, longitude, latitude, timezone_offset)| HighLatitudeLocation {
+            name,
+            observer: Observer::new(longitude, latitude, timezone_offset, 1013.25, 10.0),
+        },
+    )
+    .collect()
+}
+
+/// Days of the high-latitude rise/set scan, 0h dynamical time of 30
+/// consecutive days from 2024 March 10, i.e. a full lunar month
+pub fn lunar_month() -> Vec<JD> {
+    let start = JD::from_date(Date::new(2024, 3, 10.0));
+    (0..30).map(|day| JD::new(start.jd + day as f64)).collect()
+}
+
+/// Altitude of the Moon's upper limb, corrected for refraction
+fn upper_limb_altitude(jd: JD, observer: &Observer) -> f64 {
+    let topocentric = moon::position::topocentric_coordinates(jd, observer, 0.0);
+    let semidiameter = moon::render::render_params(jd, observer)
+        .apparent_diameter
+        .0
+        / 2.0;
+    topocentric.apparent_altitude.0 + semidiameter
+}
+
+/// Find the Moon's rises and sets in a time interval independently of
+/// moon::rise_set_transit, by sampling the altitude of the upper limb every
+/// minute and bisecting each sign change down to 0.1 seconds.
+/// In:
+/// observer: Observer's location and atmospheric conditions
+/// jd_min: start of the interval, in dynamical time
+/// jd_max: end of the interval, in dynamical time
+/// Out:
+/// rises, sets, and whether the Moon is above the horizon at jd_min
+fn scan_rise_set(observer: &Observer, jd_min: JD, jd_max: JD) -> (Vec<JD>, Vec<JD>, bool) {
+    const STEP: f64 = 1.0 / (24.0 * 60.0);
+
+    let mut rises = vec![];
+    let mut sets = vec![];
+
+    let mut jd = jd_min.jd;
+    let mut altitude = upper_limb_altitude(jd_min, observer);
+    let above = altitude >= 0.0;
+
+    while jd < jd_max.jd {
+        let next = (jd + STEP).min(jd_max.jd);
+        let next_altitude = upper_limb_altitude(JD::new(next), observer);
+
+        if (altitude >= 0.0) != (next_altitude >= 0.0) {
+            let rising = altitude < 0.0;
+            let (mut low, mut high) = (jd, next);
+            while (high - low) * 86_400.0 > 0.1 {
+                let middle = 0.5 * (low + high);
+                if (upper_limb_altitude(JD::new(middle), observer) >= 0.0) == rising {
+                    high = middle;
+                } else {
+                    low = middle;
+                }
+            }
+
+            let event = JD::new(0.5 * (low + high));
+            if rising {
+                rises.push(event);
+            } else {
+                sets.push(event);
+            }
+        }
+
+        jd = next;
+        altitude = next_altitude;
+    }
+
+    (rises, sets, above)
+}
+
+fn format_events(events: &[JD]) -> String {
+    events
+        .iter()
+        .map(|jd| format!("{:.5}", jd.jd))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Write the scanned moonrise and moonset times for the high-latitude
+/// locations. Each row is a local day of a location, as in Session, with
+/// all rises and sets of the upper limb found by a brute-force scan of the
+/// refracted topocentric altitude. The times test the rise/set algorithm,
+/// i.e. the iteration and the handling of days without events, not the
+/// ephemeris.
+/// In:
+/// writer: CSV output
+/// days: Julian Days in dynamical time, 0h of the local days
+/// locations: Observer locations
+pub fn write_high_latitude_rise_set<W: Write>(
+    writer: &mut W,
+    days: &[JD],
+    locations: &[HighLatitudeLocation],
+) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "location,longitude,latitude,timezone_offset,day,rises,sets,all_day"
+    )?;
+
+    for location in locations {
+        let observer = &location.observer;
+
+        for &day in days {
+            let (jd_min, _, jd_max) =
+                rise_set_transit::bound_julian_day(day, observer.timezone_offset);
+            let (rises, sets, above) = scan_rise_set(observer, jd_min, jd_max);
+
+            // SS: whether the Moon stays above or below the horizon all day
+            let all_day = match (rises.is_empty() && sets.is_empty(), above) {
+                (false, _) => "",
+                (true, true) => "above",
+                (true, false) => "below",
+            };
+
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                location.name,
+                observer.longitude.0,
+                observer.latitude.0,
+                observer.timezone_offset,
+                day.jd,
+                format_events(&rises),
+                format_events(&sets),
+                all_day
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_grid_test() {
+        // Arrange
+        let dates = standard_dates();
+        let locations = standard_locations();
+
+        // Act
+        let mut output = vec![];
+        write_csv(&mut output, &dates[..2], &locations).unwrap();
+
+        // Assert
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(36, dates.len());
+        assert_eq!(1 + 2 * locations.len(), lines.len());
+        assert!(lines
+            .iter()
+            .all(|line| line.split(',').count() == HEADER.len()));
+    }
+
+    #[test]
+    fn high_latitude_rise_set_test() {
+        // Arrange
+        let days = &lunar_month()[..1];
+        let locations = high_latitude_locations();
+
+        // Act
+        let mut output = vec![];
+        write_high_latitude_rise_set(&mut output, days, &locations[..1]).unwrap();
+
+        // Assert
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(2, lines.len());
+
+        // SS: Tromso, 2024 March 10, moonrise at 7:08 and moonset at 17:33
+        // local time
+        assert_eq!(
+            "tromso,-18.96,69.65,1,2460379.5,2460379.75552,2460380.18927,",
+            lines[1]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Rough tide indicator based on the relative position of Moon and Sun.
+//! This is not a harmonic tide model, it merely estimates whether we are
+//! close to spring or neap tides, expressed as a tidal coefficient on the
+//! scale used by the French SHOM (20 to 120, with 70 being average).
+
+use crate::date::jd::JD;
+use crate::moon;
+use crate::moon::apsides::MEAN_DISTANCE;
+use crate::sun::position::distance_earth_sun_ae;
+use crate::util::radians::Radians;
+
+/// Ratio of the Sun's tide-generating force to the Moon's, at mean distances
+const SUN_TO_MOON_TIDAL_RATIO: f64 = 0.46;
+
+/// Tidal coefficient of the average spring tide, SHOM scale
+const MEAN_SPRING_COEFFICIENT: f64 = 95.0;
+
+/// Tidal coefficient of the average neap tide, SHOM scale
+const MEAN_NEAP_COEFFICIENT: f64 = 45.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TideKind {
+    Spring,
+    Neap,
+}
+
+/// Estimate the tidal coefficient from the Moon-Sun elongation and the
+/// distances of Moon and Sun. The tide-generating forces of Moon and Sun
+/// scale with 1/r^3 and add up at syzygy (new and full moon) and partially
+/// cancel at quadrature (first and last quarter).
+/// In: Julian day in dynamical time
+/// Out: Tidal coefficient, [20, 120]
+pub fn coefficient(jd: JD) -> f64 {
+    let moon_distance = moon::position::distance_from_earth(jd);
+    let sun_distance = distance_earth_sun_ae(jd);
+
+    let moon_amplitude = (MEAN_DISTANCE / moon_distance).powi(3);
+    let sun_amplitude = SUN_TO_MOON_TIDAL_RATIO * (1.0 / sun_distance).powi(3);
+
+    // SS: the tidal bulge has a period of half a revolution, hence twice the elongation
+    let elongation = Radians::from(moon::phase::phase_info(jd).elongation);
+    let amplitude = (moon_amplitude * moon_amplitude
+        + sun_amplitude * sun_amplitude
+        + 2.0 * moon_amplitude * sun_amplitude * (2.0 * elongation.0).cos())
+    .sqrt();
+
+    // SS: map linearly such that the mean spring and neap tides end up at their
+    // nominal coefficients
+    let spring = 1.0 + SUN_TO_MOON_TIDAL_RATIO;
+    let neap = 1.0 - SUN_TO_MOON_TIDAL_RATIO;
+    let slope = (MEAN_SPRING_COEFFICIENT - MEAN_NEAP_COEFFICIENT) / (spring - neap);
+    let coefficient = MEAN_NEAP_COEFFICIENT + slope * (amplitude - neap);
+
+    coefficient.clamp(20.0, 120.0)
+}
+
+/// Classify the tide as spring or neap tide.
+/// In: Julian day in dynamical time
+/// Out: Spring tide for coefficients of 70 and above, neap tide otherwise
+pub fn kind(jd: JD) -> TideKind {
+    if coefficient(jd) >= 70.0 {
+        TideKind::Spring
+    } else {
+        TideKind::Neap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+
+    #[test]
+    fn coefficient_full_moon_test() {
+        // Arrange
+
+        // SS: full moon, Jan. 17th 2022, 11:48pm UTC
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 17, 23, 48, 0.0));
+
+        // Act
+        let coefficient = coefficient(jd);
+
+        // Assert
+        assert!((85.0..110.0).contains(&coefficient));
+        assert_eq!(TideKind::Spring, kind(jd));
+    }
+
+    #[test]
+    fn coefficient_last_quarter_test() {
+        // Arrange
+
+        // SS: last quarter, Jan. 25th 2022, 1:41pm UTC
+        let jd = JD::from_date(Date::from_date_hms(2022, 1, 25, 13, 41, 0.0));
+
+        // Act
+        let coefficient = coefficient(jd);
+
+        // Assert
+        assert!((35.0..55.0).contains(&coefficient));
+        assert_eq!(TideKind::Neap, kind(jd));
+    }
+
+    #[test]
+    fn coefficient_perigee_full_moon_test() {
+        // Arrange
+
+        // SS: "super moon", full moon close to perigee, March 19th 2011, 6:10pm UTC
+        let jd = JD::from_date(Date::from_date_hms(2011, 3, 19, 18, 10, 0.0));
+
+        // Act
+        let coefficient = coefficient(jd);
+
+        // Assert
+        assert!(coefficient > 105.0);
+    }
+}
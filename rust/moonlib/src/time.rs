@@ -1,36 +1,278 @@
 //! Time-related function.
 //!
+use crate::date::date::Date;
 use crate::date::jd::JD;
 use crate::{constants, util};
-use tabular::time::delta_t_data::{DeltaTValue, DELTA_T_DATA};
-use tabular::time::leap_second_data::{LeapSecondCoefficient, LEAP_SECOND_DATA};
+pub use tabular::time::leap_second_data::LeapSecondCoefficient;
+use tabular::time::leap_second_data::LEAP_SECOND_DATA;
+use tabular::time::{delta_t_table, DeltaTValue};
+
+/// TAI - GPS time, in seconds. Both are atomic time scales without leap
+/// seconds
+const TAI_MINUS_GPS: f64 = 19.0;
+
+/// Time scale a Julian Day is given in
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimeScale {
+    /// Coordinated universal time, converted to dynamical time internally
+    UTC,
+
+    /// Universal time, i.e. Earth's rotation angle
+    UT1,
+
+    /// Dynamical time, used as is
+    TT,
+
+    /// GPS time, which runs 19s behind TAI and has no leap seconds.
+    /// Converted to UTC internally, see gps_2_utc
+    GPS,
+}
+
+/// Julian Day tagged with the time scale it is in, for calculations that
+/// need a particular time scale, like siderial time
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScaledJD {
+    pub jd: JD,
+    pub time_scale: TimeScale,
+}
+
+impl ScaledJD {
+    pub fn new(jd: JD, time_scale: TimeScale) -> Self {
+        Self { jd, time_scale }
+    }
+
+    /// Convert to UT1
+    /// Out: Julian Day, in UT1
+    pub(crate) fn to_ut1(self) -> JD {
+        match self.time_scale {
+            // SS: UT1 - UTC is kept below 0.9s by leap seconds and is not
+            // tabulated, so UTC is taken as UT1
+            TimeScale::UTC | TimeScale::UT1 => self.jd,
+            TimeScale::TT => JD::new(self.jd.jd - delta_t(self.jd) / constants::SEC_PER_DAY as f64),
+            TimeScale::GPS => gps_2_utc(self.jd, &LeapSeconds::iers()),
+        }
+    }
+
+    /// Convert to UTC, e.g. to show a time to users
+    /// In: leap seconds
+    /// Out: Julian Day, in UTC
+    pub fn to_utc(self, table: &LeapSeconds) -> JD {
+        match self.time_scale {
+            // SS: UT1 - UTC is not tabulated, see to_ut1
+            TimeScale::UTC | TimeScale::UT1 => self.jd,
+            TimeScale::TT => tt_2_utc(self.jd, table),
+            TimeScale::GPS => gps_2_utc(self.jd, table),
+        }
+    }
+
+    /// Civil date and time in UTC, e.g. to show a time to users
+    /// In: leap seconds
+    /// Out: Date, in UTC
+    pub fn to_calendar_date_utc(self, table: &LeapSeconds) -> Date {
+        self.to_utc(table).to_calendar_date()
+    }
+
+    /// Convert to dynamical time, which the positions of the Moon and the
+    /// Sun are calculated in
+    /// Out: Julian Day, in TT
+    pub(crate) fn to_tt(self) -> JD {
+        match self.time_scale {
+            TimeScale::UTC => utc_2_tt(self.jd),
+            TimeScale::UT1 => ut1_to_tt_with(self.jd, DeltaT::Table),
+            TimeScale::TT => self.jd,
+            // SS: via UTC rather than TT = GPS + 51.184s, so a time gives
+            // the same results whether it is passed in GPS time or in UTC
+            TimeScale::GPS => utc_2_tt(gps_2_utc(self.jd, &LeapSeconds::iers())),
+        }
+    }
+}
+
+/// Source of delta_t = TT - UT1
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DeltaT {
+    /// Interpolate the IERS table, and use the polynomial expressions of
+    /// Espenak & Meeus 2006 outside of it
+    Table,
+
+    /// Use the polynomial expressions of Espenak & Meeus 2006 for all dates
+    Polynomial,
+
+    /// Interpolate the IERS table, use the spline of Stephenson, Morrison &
+    /// Hohenkerk 2016 before it and the polynomial expressions of Espenak &
+    /// Meeus 2006 after it. More accurate than Table for historical dates
+    Stephenson2016,
+
+    /// Use a fixed value, in seconds, e.g. a recent value published by the
+    /// IERS
+    Fixed(f64),
+}
+
+impl DeltaT {
+    /// In: Julian Day in UTC
+    /// Out: delta_t, in seconds
+    pub(crate) fn seconds(self, jd: JD) -> f64 {
+        match self {
+            DeltaT::Table => delta_t(jd),
+            DeltaT::Polynomial => delta_t_polynomial(jd),
+            DeltaT::Stephenson2016 => {
+                if jd.jd < delta_t_table()[0].jd {
+                    delta_t_spline_2016(jd)
+                } else {
+                    delta_t(jd)
+                }
+            }
+            DeltaT::Fixed(seconds) => seconds,
+        }
+    }
+}
+
+/// Table of leap seconds, i.e. TAI - UTC, for converting between UTC and
+/// dynamical time
+#[derive(Copy, Clone)]
+pub struct LeapSeconds {
+    table: &'static [LeapSecondCoefficient],
+}
+
+impl LeapSeconds {
+    /// Table of the leap seconds published by the IERS, up to the last one
+    /// known when the crate was built
+    pub fn iers() -> Self {
+        Self {
+            table: &LEAP_SECOND_DATA,
+        }
+    }
+
+    /// Table of leap seconds, e.g. to take leap seconds into account that
+    /// were announced after the crate was built
+    /// In: coefficients, sorted by date, not empty
+    pub fn new(table: &'static [LeapSecondCoefficient]) -> Self {
+        assert!(!table.is_empty());
+        Self { table }
+    }
+
+    /// Whether UTC is defined by this table for the date passed in. Before
+    /// and after the table, UTC is taken as UT1
+    /// In: Julian Day, in UTC
+    /// Out: true, if the date is within the table
+    pub fn covers(&self, jd: JD) -> bool {
+        jd.jd >= self.table[0].jd && jd.jd <= self.table[self.table.len() - 1].jd
+    }
+
+    /// Calculate the amount of leap seconds for the date passed in.
+    /// This is to calculate TAI from UTC, i.e. TAI - UTC = cumulative(UTC)
+    /// In: Julian Day, in UTC
+    /// Out: cumulative leap seconds for input date
+    pub fn cumulative(&self, jd: JD) -> f64 {
+        let mut cumulative_leap_secs = 0.0;
+
+        let mut idx = self.table.len();
+
+        if jd.jd >= self.table[0].jd {
+            if jd.jd < self.table[idx - 1].jd {
+                let to_find = LeapSecondCoefficient {
+                    jd: jd.jd,
+                    leap_seconds: 0.0,
+                    base_mjd: 0.0,
+                    coefficient: 0.0,
+                };
+                idx = util::binary_search::upper_bound(self.table, &to_find);
+            }
+
+            let leap_item = &self.table[idx - 1];
+            cumulative_leap_secs = leap_item.leap_seconds
+                + (jd.to_mjd() - JD::new(leap_item.base_mjd)).jd * leap_item.coefficient;
+        }
+
+        cumulative_leap_secs
+    }
+
+    /// Dates at which TAI - UTC changes in this table, e.g. to show users
+    /// where the time conversions come from
+    /// Out: entries, in date order
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = LeapSecondEntry> {
+        let table = *self;
+        self.table.iter().map(move |coefficient| {
+            let jd = JD::new(coefficient.jd);
+            LeapSecondEntry {
+                jd,
+                tai_minus_utc: table.cumulative(jd),
+            }
+        })
+    }
+
+    /// Range of dates covered by this table, see covers
+    /// Out: (first, last) date of the table
+    pub fn range(&self) -> (Date, Date) {
+        let first = JD::new(self.table[0].jd);
+        let last = JD::new(self.table[self.table.len() - 1].jd);
+        (first.to_calendar_date(), last.to_calendar_date())
+    }
+}
+
+/// Entry of a leap second table
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LeapSecondEntry {
+    /// Julian Day, in UTC, from which the entry applies
+    pub jd: JD,
+
+    /// TAI - UTC at jd, in seconds. Before 1972, TAI - UTC changes
+    /// continuously until the next entry
+    pub tai_minus_utc: f64,
+}
+
+/// Tabulated value of delta_t
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DeltaTEntry {
+    /// Julian Day, in UTC
+    pub jd: JD,
+
+    /// TT - UT1, in seconds
+    pub delta_t: f64,
+}
+
+impl Default for LeapSeconds {
+    fn default() -> Self {
+        Self::iers()
+    }
+}
 
 /// Calculate the amount of leap seconds for the date passed in.
 /// This is to calculate TAI from UTC, i.e. TAI - UTC = cumulative_leap_seconds(UTC)
 /// In: Julian Day, in UTC
 /// Out: cumulative leap seconds for input date
 pub fn cumulative_leap_seconds(jd: JD) -> f64 {
-    let mut cumulative_leap_secs = 0.0;
-
-    let mut idx = LEAP_SECOND_DATA.len();
-
-    if jd.jd >= LEAP_SECOND_DATA[0].jd {
-        if jd.jd < LEAP_SECOND_DATA[idx - 1].jd {
-            let to_find = LeapSecondCoefficient {
-                jd: jd.jd,
-                leap_seconds: 0.0,
-                base_mjd: 0.0,
-                coefficient: 0.0,
-            };
-            idx = util::binary_search::upper_bound(&LEAP_SECOND_DATA, &to_find);
-        }
+    LeapSeconds::iers().cumulative(jd)
+}
 
-        let leap_item = &LEAP_SECOND_DATA[idx - 1];
-        cumulative_leap_secs = leap_item.leap_seconds
-            + (jd.to_mjd() - JD::new(leap_item.base_mjd)).jd * leap_item.coefficient;
-    }
+/// Range of dates covered by the tabulated delta_t values. Outside of this
+/// range, delta_t is extrapolated from polynomial expressions, see delta_t.
+/// Out: (first, last) date of the table
+pub fn delta_t_table_range() -> (Date, Date) {
+    let table = delta_t_table();
+    let first = JD::new(table[0].jd);
+    let last = JD::new(table[table.len() - 1].jd);
+    (first.to_calendar_date(), last.to_calendar_date())
+}
 
-    cumulative_leap_secs
+/// Tabulated delta_t values, e.g. to show users where the time conversions
+/// come from. Between them, delta_t is interpolated linearly
+/// Out: entries, in date order
+pub fn delta_t_entries() -> impl ExactSizeIterator<Item = DeltaTEntry> {
+    delta_t_table().iter().map(|value| DeltaTEntry {
+        jd: JD::new(value.jd),
+        delta_t: value.delta_t,
+    })
+}
+
+/// Whether delta_t for a date is extrapolated from polynomial expressions
+/// rather than interpolated from the table, e.g. to warn users that
+/// predictions beyond the end of the table are less accurate.
+/// In: Julian Day in UTC
+/// Out: true, if the date is outside the table
+pub fn is_extrapolating(jd: JD) -> bool {
+    // SS: written so that NaN is not in the table, which has no index for it
+    let table = delta_t_table();
+    !(jd.jd >= table[0].jd && jd.jd < table[table.len() - 1].jd)
 }
 
 /// Calculate the correction delta_t between UT1 and TT, i.e.
@@ -38,167 +280,240 @@ pub fn cumulative_leap_seconds(jd: JD) -> f64 {
 /// In: Julian Day in UTC
 /// Out: delta_t, in seconds
 fn delta_t(jd: JD) -> f64 {
-    let delta_t;
+    if is_extrapolating(jd) {
+        // SS: Julian Day outside of tabular data range
+        return delta_t_polynomial(jd);
+    }
 
-    if jd.jd >= DELTA_T_DATA[0].jd && jd.jd < DELTA_T_DATA[DELTA_T_DATA.len() - 1].jd {
-        // SS: calculate delta_t by using tabular data from
-        // https://cddis.nasa.gov/archive/products/iers/historic_deltat.data
-        // and
-        // https://cddis.nasa.gov/archive/products/iers/finals2000A.all
+    // SS: calculate delta_t by using tabular data from
+    // https://cddis.nasa.gov/archive/products/iers/historic_deltat.data
+    // and
+    // https://cddis.nasa.gov/archive/products/iers/finals2000A.all
 
-        let to_find = DeltaTValue {
-            jd: jd.jd,
-            delta_t: 0.0,
-        };
-        let idx = util::binary_search::upper_bound(&DELTA_T_DATA, &to_find);
+    let to_find = DeltaTValue {
+        jd: jd.jd,
+        delta_t: 0.0,
+    };
+    let table = delta_t_table();
+    let idx = util::binary_search::upper_bound(table, &to_find);
+
+    let prev = &table[idx - 1];
+    let curr = &table[idx];
 
-        let prev = &DELTA_T_DATA[idx - 1];
-        let curr = &DELTA_T_DATA[idx];
+    (jd.jd - prev.jd) / (curr.jd - prev.jd) * (curr.delta_t - prev.delta_t) + prev.delta_t
+}
 
+/// Calculate delta_t from the polynomial expressions of Espenak & Meeus 2006,
+/// also for dates covered by the table
+/// In: Julian Day in UTC
+/// Out: delta_t, in seconds
+fn delta_t_polynomial(jd: JD) -> f64 {
+    let delta_t;
+
+    // SS: calculate delta_t based on polynomial expressions from
+    // Espenak & Meeus 2006.
+    // References: http://eclipse.gsfc.nasa.gov/SEcat5/deltatpoly.html and
+    // http://www.staff.science.uu.nl/~gent0113/deltat/deltat_old.htm,
+    // see Espenak & Meeus 2006 section at the bottom
+    let date = jd.to_calendar_date();
+    let y = date.fractional_year().trunc() as i16;
+
+    if y < -500 {
+        let u = (y as f64 - 1820.0) / 100.0;
+        let u2 = u * u;
+        delta_t = -20.0 + (32.0 * u2);
+    } else if y < 500 {
+        let u = y as f64 / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        let u5 = u4 * u;
+        let u6 = u5 * u;
+        delta_t = 10583.6
+            + (-1014.41 * u)
+            + (33.78311 * u2)
+            + (-5.952053 * u3)
+            + (-0.1798452 * u4)
+            + (0.022174192 * u5)
+            + (0.0090316521 * u6);
+    } else if y < 1600 {
+        let u = (y as f64 - 1000.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        let u5 = u4 * u;
+        let u6 = u5 * u;
+        delta_t = 1574.2
+            + (-556.01 * u)
+            + (71.23472 * u2)
+            + (0.319781 * u3)
+            + (-0.8503463 * u4)
+            + (-0.005050998 * u5)
+            + (0.0083572073 * u6);
+    } else if y < 1700 {
+        let u = (y as f64 - 1600.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        delta_t = 120.0 + (-98.08 * u) + (-153.2 * u2) + (u3 / 0.007129);
+    } else if y < 1800 {
+        let u = (y as f64 - 1700.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        delta_t = 8.83 + (16.03 * u) + (-59.285 * u2) + (133.36 * u3) + (-u4 / 0.01174);
+    } else if y < 1860 {
+        let u = (y as f64 - 1800.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        let u5 = u4 * u;
+        let u6 = u5 * u;
+        let u7 = u6 * u;
+        delta_t = 13.72
+            + (-33.2447 * u)
+            + (68.612 * u2)
+            + (4111.6 * u3)
+            + (-37436.0 * u4)
+            + (121272.0 * u5)
+            + (-169900.0 * u6)
+            + (87500.0 * u7);
+    } else if y < 1900 {
+        let u = (y as f64 - 1860.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        let u5 = u4 * u;
+        delta_t = 7.62
+            + (57.37 * u)
+            + (-2517.54 * u2)
+            + (16806.68 * u3)
+            + (-44736.24 * u4)
+            + (u5 / 0.0000233174);
+    } else if y < 1920 {
+        let u = (y as f64 - 1900.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        delta_t = -2.79 + (149.4119 * u) + (-598.939 * u2) + (6196.6 * u3) + (-19700.0 * u4);
+    } else if y < 1941 {
+        let u = (y as f64 - 1920.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        delta_t = 21.20 + (84.493 * u) + (-761.00 * u2) + (2093.6 * u3);
+    } else if y < 1961 {
+        let u = (y as f64 - 1950.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        delta_t = 29.07 + (40.7 * u) + (-u2 / 0.0233) + (u3 / 0.002547);
+    } else if y < 1986 {
+        let u = (y as f64 - 1975.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        delta_t = 45.45 + 106.7 * u - u2 / 0.026 - u3 / 0.000718;
+    } else if y < 2005 {
+        let u = (y as f64 - 2000.0) / 100.0;
+        let u2 = u * u;
+        let u3 = u2 * u;
+        let u4 = u3 * u;
+        let u5 = u4 * u;
         delta_t =
-            (jd.jd - prev.jd) / (curr.jd - prev.jd) * (curr.delta_t - prev.delta_t) + prev.delta_t;
+            63.86 + (33.45 * u) + (-603.74 * u2) + (1727.5 * u3) + (65181.4 * u4) + (237359.9 * u5);
+    } else if y < 2050 {
+        let u = (y as f64 - 2000.0) / 100.0;
+        let u2 = u * u;
+        delta_t = 62.92 + (32.217 * u) + (55.89 * u2);
+    } else if y < 2150 {
+        let u = (y as f64 - 1820.0) / 100.0;
+        let u2 = u * u;
+        delta_t = -205.72 + (56.28 * u) + (32.0 * u2);
     } else {
-        // SS: Julian Day outside of tabular data range, calculate delta_t based on
-        // polynomial expressions from Espenak & Meeus 2006.
-        // References: http://eclipse.gsfc.nasa.gov/SEcat5/deltatpoly.html and
-        // http://www.staff.science.uu.nl/~gent0113/deltat/deltat_old.htm,
-        // see Espenak & Meeus 2006 section at the bottom
-        let date = jd.to_calendar_date();
-        let y = date.fractional_year().trunc() as i16;
-
-        if y < -500 {
-            let u = (y as f64 - 1820.0) / 100.0;
-            let u2 = u * u;
-            delta_t = -20.0 + (32.0 * u2);
-        } else if y < 500 {
-            let u = y as f64 / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            let u5 = u4 * u;
-            let u6 = u5 * u;
-            delta_t = 10583.6
-                + (-1014.41 * u)
-                + (33.78311 * u2)
-                + (-5.952053 * u3)
-                + (-0.1798452 * u4)
-                + (0.022174192 * u5)
-                + (0.0090316521 * u6);
-        } else if y < 1600 {
-            let u = (y as f64 - 1000.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            let u5 = u4 * u;
-            let u6 = u5 * u;
-            delta_t = 1574.2
-                + (-556.01 * u)
-                + (71.23472 * u2)
-                + (0.319781 * u3)
-                + (-0.8503463 * u4)
-                + (-0.005050998 * u5)
-                + (0.0083572073 * u6);
-        } else if y < 1700 {
-            let u = (y as f64 - 1600.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            delta_t = 120.0 + (-98.08 * u) + (-153.2 * u2) + (u3 / 0.007129);
-        } else if y < 1800 {
-            let u = (y as f64 - 1700.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            delta_t = 8.83 + (16.03 * u) + (-59.285 * u2) + (133.36 * u3) + (-u4 / 0.01174);
-        } else if y < 1860 {
-            let u = (y as f64 - 1800.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            let u5 = u4 * u;
-            let u6 = u5 * u;
-            let u7 = u6 * u;
-            delta_t = 13.72
-                + (-33.2447 * u)
-                + (68.612 * u2)
-                + (4111.6 * u3)
-                + (-37436.0 * u4)
-                + (121272.0 * u5)
-                + (-169900.0 * u6)
-                + (87500.0 * u7);
-        } else if y < 1900 {
-            let u = (y as f64 - 1860.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            let u5 = u4 * u;
-            delta_t = 7.62
-                + (57.37 * u)
-                + (-2517.54 * u2)
-                + (16806.68 * u3)
-                + (-44736.24 * u4)
-                + (u5 / 0.0000233174);
-        } else if y < 1920 {
-            let u = (y as f64 - 1900.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            delta_t = -2.79 + (149.4119 * u) + (-598.939 * u2) + (6196.6 * u3) + (-19700.0 * u4);
-        } else if y < 1941 {
-            let u = (y as f64 - 1920.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            delta_t = 21.20 + (84.493 * u) + (-761.00 * u2) + (2093.6 * u3);
-        } else if y < 1961 {
-            let u = (y as f64 - 1950.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            delta_t = 29.07 + (40.7 * u) + (-u2 / 0.0233) + (u3 / 0.002547);
-        } else if y < 1986 {
-            let u = (y as f64 - 1975.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            delta_t = 45.45 + 106.7 * u - u2 / 0.026 - u3 / 0.000718;
-        } else if y < 2005 {
-            let u = (y as f64 - 2000.0) / 100.0;
-            let u2 = u * u;
-            let u3 = u2 * u;
-            let u4 = u3 * u;
-            let u5 = u4 * u;
-            delta_t = 63.86
-                + (33.45 * u)
-                + (-603.74 * u2)
-                + (1727.5 * u3)
-                + (65181.4 * u4)
-                + (237359.9 * u5);
-        } else if y < 2050 {
-            let u = (y as f64 - 2000.0) / 100.0;
-            let u2 = u * u;
-            delta_t = 62.92 + (32.217 * u) + (55.89 * u2);
-        } else if y < 2150 {
-            let u = (y as f64 - 1820.0) / 100.0;
-            let u2 = u * u;
-            delta_t = -205.72 + (56.28 * u) + (32.0 * u2);
-        } else {
-            let u = (y as f64 - 1820.0) / 100.0;
-            let u2 = u * u;
-            delta_t = -20.0 + (32.0 * u2);
-        }
+        let u = (y as f64 - 1820.0) / 100.0;
+        let u2 = u * u;
+        delta_t = -20.0 + (32.0 * u2);
     }
 
     delta_t
 }
 
+/// Cubic spline fitted to historical eclipse and occultation records, from
+/// Stephenson, Morrison & Hohenkerk 2016, Measurement of the Earth's rotation:
+/// 720 BC to AD 2015, Proc. R. Soc. A 472, table S15. Only the intervals up
+/// to the start of the IERS table in 1657 are needed.
+/// (first year, last year, a0, a1, a2, a3), delta_t in seconds is
+/// a0 + a1 t + a2 t^2 + a3 t^3, with t the fraction of the interval
+const SPLINE_2016: [(f64, f64, f64, f64, f64, f64); 9] = [
+    (-720.0, -100.0, 20371.848, -9999.586, 776.247, 409.160),
+    (-100.0, 400.0, 11557.668, -5822.270, 1303.151, -503.428),
+    (400.0, 1000.0, 6535.116, -5671.519, -298.291, 1085.087),
+    (1000.0, 1150.0, 1650.393, -753.210, 184.811, -25.346),
+    (1150.0, 1300.0, 1056.647, -459.628, 108.771, -24.641),
+    (1300.0, 1500.0, 681.149, -421.345, 61.953, -29.414),
+    (1500.0, 1600.0, 292.343, -192.841, -6.572, 16.197),
+    (1600.0, 1650.0, 109.127, -78.697, 10.505, 3.024),
+    (1650.0, 1720.0, 43.952, -68.089, 38.333, -2.388),
+];
+
+/// Long-term parabola of Stephenson, Morrison & Hohenkerk 2016, for dates
+/// before the spline
+/// In: fractional year
+/// Out: delta_t, in seconds
+fn parabola_2016(y: f64) -> f64 {
+    let u = (y - 1825.0) / 100.0;
+    -320.0 + 32.5 * u * u
+}
+
+/// Calculate delta_t from the spline of Stephenson, Morrison & Hohenkerk
+/// 2016. Before 720 BC, their long-term parabola is used, shifted so it
+/// joins the spline
+/// In: Julian Day in UTC
+/// Out: delta_t, in seconds
+fn delta_t_spline_2016(jd: JD) -> f64 {
+    let y = jd.to_calendar_date().fractional_year();
+
+    let (first, _, a0, _, _, _) = SPLINE_2016[0];
+    if y < first {
+        return parabola_2016(y) - parabola_2016(first) + a0;
+    }
+
+    // SS: the last interval extends beyond the start of the IERS table
+    let (y0, y1, a0, a1, a2, a3) = *SPLINE_2016
+        .iter()
+        .find(|(_, y1, ..)| y < *y1)
+        .unwrap_or(&SPLINE_2016[SPLINE_2016.len() - 1]);
+    let t = (y - y0) / (y1 - y0);
+    a0 + t * (a1 + t * (a2 + t * a3))
+}
+
 /// Convert UTC to TT
 /// In: Julian Day, in UTC
 /// Out: TT, in days
 pub(crate) fn utc_2_tt(jd: JD) -> JD {
+    utc_2_tt_with(jd, DeltaT::Table)
+}
+
+/// Convert UTC to TT
+/// In:
+/// jd: Julian Day, in UTC
+/// delta_t: source of delta_t
+/// Out: TT, in days
+pub(crate) fn utc_2_tt_with(jd: JD, delta_t: DeltaT) -> JD {
+    utc_2_tt_table(jd, delta_t, &LeapSeconds::iers())
+}
+
+/// Convert UTC to TT
+/// In:
+/// jd: Julian Day, in UTC
+/// delta_t: source of delta_t
+/// table: leap seconds
+/// Out: TT, in days
+fn utc_2_tt_table(jd: JD, delta_t: DeltaT, table: &LeapSeconds) -> JD {
     // SS: If the date falls outside the range we have leap second data for, we
     // interpret the input date in UT1 rather than UTC. Same as PJ Naughter
-    if jd.jd < LEAP_SECOND_DATA[0].jd || jd.jd > LEAP_SECOND_DATA.last().unwrap().jd {
-        ut1_to_tt(jd)
+    if !table.covers(jd) {
+        ut1_to_tt_with(jd, delta_t)
     } else {
-        let delta_t = delta_t(jd);
-        let cumulative_leap_seconds = cumulative_leap_seconds(jd);
+        let delta_t = delta_t.seconds(jd);
+        let cumulative_leap_seconds = table.cumulative(jd);
 
         // SS: calculate UT1 from UTC
         let ut1 =
@@ -210,11 +525,80 @@ pub(crate) fn utc_2_tt(jd: JD) -> JD {
     }
 }
 
+/// Convert UTC to GPS time. GPS time was set to UTC on 1980 January 6 and
+/// has not been adjusted for leap seconds since, so it runs ahead of UTC
+/// by the leap seconds introduced after that date, e.g. 18s in 2017
+/// In:
+/// jd: Julian Day, in UTC
+/// table: leap seconds
+/// Out: Julian Day, in GPS time
+pub fn utc_2_gps(jd: JD, table: &LeapSeconds) -> JD {
+    let gps_minus_utc = table.cumulative(jd) - TAI_MINUS_GPS;
+    JD::new(jd.jd + gps_minus_utc / constants::SEC_PER_DAY as f64)
+}
+
+/// Convert GPS time to UTC, the inverse of utc_2_gps. GPS receivers
+/// broadcast GPS - UTC, but timestamps are often logged in GPS time only
+/// In:
+/// jd: Julian Day, in GPS time
+/// table: leap seconds
+/// Out: Julian Day, in UTC
+pub fn gps_2_utc(jd: JD, table: &LeapSeconds) -> JD {
+    // SS: the leap seconds are looked up by UTC, which is not known yet.
+    // They change by at most a second, so two steps suffice, except for the
+    // second inserted at a leap second, which GPS time counts and UTC
+    // repeats as 23:59:60
+    let mut utc = jd;
+    for _ in 0..2 {
+        utc = JD::new(
+            jd.jd - (table.cumulative(utc) - TAI_MINUS_GPS) / constants::SEC_PER_DAY as f64,
+        );
+    }
+    utc
+}
+
+/// Convert TT back to UTC, the inverse of utc_2_tt
+/// In:
+/// jd: Julian Day, in TT
+/// table: leap seconds
+/// Out: UTC, in days
+pub fn tt_2_utc(jd: JD, table: &LeapSeconds) -> JD {
+    tt_2_utc_with(jd, DeltaT::Table, table)
+}
+
+/// Convert TT back to UTC, the inverse of utc_2_tt_with
+/// In:
+/// jd: Julian Day, in TT
+/// delta_t: source of delta_t
+/// table: leap seconds
+/// Out: UTC, in days
+pub(crate) fn tt_2_utc_with(jd: JD, delta_t: DeltaT, table: &LeapSeconds) -> JD {
+    // SS: delta_t and the leap seconds are looked up by UTC, which is not
+    // known yet. Both hardly change within minutes, so a fixed point
+    // iteration converges in a couple of steps. Close to a leap second,
+    // the offset jumps by a second, hence the limit on the iterations.
+    // After the last leap second, UTC is taken as UT1 by utc_2_tt, and the
+    // offset drops by minutes. A few minutes of TT map to two UTC times
+    // there, the iteration returns the later one
+    let mut utc = jd;
+    for _ in 0..5 {
+        let offset = utc_2_tt_table(utc, delta_t, table).jd - utc.jd;
+        let next = JD::new(jd.jd - offset);
+        if next.jd == utc.jd {
+            break;
+        }
+        utc = next;
+    }
+    utc
+}
+
 /// Convert UT1 to T(erestial) T(ime)
-/// In: Julian Day, in UT1
-/// Out: TT, in seconds
-fn ut1_to_tt(jd: JD) -> JD {
-    let delta_t = delta_t(jd);
+/// In:
+/// jd: Julian Day, in UT1
+/// delta_t: source of delta_t
+/// Out: TT, in days
+pub(crate) fn ut1_to_tt_with(jd: JD, delta_t: DeltaT) -> JD {
+    let delta_t = delta_t.seconds(jd);
 
     // SS: Julian Day is in units of days, so convert
     // delta_t from seconds to days
@@ -226,12 +610,6 @@ fn ut1_to_tt(jd: JD) -> JD {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::date::date::Date;
-    use crate::date::jd::JD;
-    use crate::earth::{
-        apparent_siderial_time, hour_angle, local_siderial_time, mean_siderial_time,
-    };
-    use crate::util::degrees::Degrees;
     use assert_approx_eq::assert_approx_eq;
 
     #[test]
@@ -286,139 +664,369 @@ mod tests {
     }
 
     #[test]
-    fn cumulative_leap_seconds_test1() {
+    fn tt_2_utc_test() {
         // Arrange
-        let jd = JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0));
+        let table = LeapSeconds::iers();
+        let jd_in_utc = [
+            JD::new(2457204.5),
+            JD::new(2459610.080526),
+            JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0)),
+            JD::from_date(Date::from_date_hms(1947, 3, 15, 7, 17, 56.0)),
+        ];
+
+        for utc in jd_in_utc {
+            let tt = utc_2_tt(utc);
+
+            // Act
+            let round_trip = tt_2_utc(tt, &table);
+
+            // Assert
+            assert_approx_eq!(utc.jd, round_trip.jd, 0.000_000_1);
+        }
+    }
+
+    #[test]
+    fn tt_2_utc_leap_second_test() {
+        // Arrange
+        let table = LeapSeconds::iers();
+
+        // SS: a leap second was inserted at the end of 2015 June 30
+        let before = JD::new(2_457_204.5 - 10.0 / constants::SEC_PER_DAY as f64);
+        let after = JD::new(2_457_204.5 + 10.0 / constants::SEC_PER_DAY as f64);
 
         // Act
-        let leap_seconds = cumulative_leap_seconds(jd);
+        let before_round_trip = tt_2_utc(utc_2_tt(before), &table);
+        let after_round_trip = tt_2_utc(utc_2_tt(after), &table);
 
         // Assert
-        assert_approx_eq!(32.0, leap_seconds, 0.1)
+        assert_approx_eq!(35.0, table.cumulative(before), 0.1);
+        assert_approx_eq!(36.0, table.cumulative(after), 0.1);
+        assert_approx_eq!(0.0, (before_round_trip.jd - before.jd) * 86400.0, 0.001);
+        assert_approx_eq!(0.0, (after_round_trip.jd - after.jd) * 86400.0, 0.001);
     }
 
     #[test]
-    fn cumulative_leap_seconds_test2() {
+    fn scaled_jd_to_utc_test() {
         // Arrange
-        let jd = JD::new(2_457_754.5);
+        let table = LeapSeconds::iers();
+        let utc = JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0));
+        let tt = utc_2_tt(utc);
 
         // Act
-        let leap_seconds = cumulative_leap_seconds(jd);
+        let from_tt = ScaledJD::new(tt, TimeScale::TT).to_utc(&table);
+        let from_utc = ScaledJD::new(utc, TimeScale::UTC).to_utc(&table);
+        let date = ScaledJD::new(tt, TimeScale::TT).to_calendar_date_utc(&table);
 
         // Assert
-        assert_approx_eq!(37.0, leap_seconds, 0.1)
+        assert_approx_eq!(utc.jd, from_tt.jd, 0.000_000_1);
+        assert_eq!(utc, from_utc);
+        assert_eq!(8, date.month);
+        assert_approx_eq!(utc.to_calendar_date().day, date.day, 0.000_001);
     }
 
     #[test]
-    fn hour_angle_test() {
-        // Meeus, page 95, example 13.b
+    fn scaled_jd_to_tt_test() {
+        // Arrange
+        let jd = JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0));
+
+        // Act
+        let from_utc = ScaledJD::new(jd, TimeScale::UTC).to_tt();
+        let from_ut1 = ScaledJD::new(jd, TimeScale::UT1).to_tt();
+        let from_tt = ScaledJD::new(jd, TimeScale::TT).to_tt();
+
+        // Assert
+        assert_eq!(utc_2_tt(jd), from_utc);
+        assert_approx_eq!(64.533476, (from_ut1.jd - jd.jd) * 86400.0, 0.001);
+        assert_eq!(jd, from_tt);
+    }
 
+    #[test]
+    fn utc_2_gps_test() {
         // Arrange
-        let siderial_time_apparent_greenwich = Degrees::from_hms(8, 34, 56.853);
-        let longitude_observer = Degrees::from_hms(5, 8, 15.7);
-        let right_ascension_apparent = Degrees::from_hms(23, 9, 16.641);
+        let table = LeapSeconds::iers();
+        let epoch = JD::new(constants::GPS_EPOCH);
+        let utc = JD::from_date(Date::from_date_hms(2024, 3, 20, 12, 0, 0.0));
 
         // Act
-        let siderial_time_local =
-            local_siderial_time(siderial_time_apparent_greenwich, longitude_observer);
-        let hour_angle = hour_angle(siderial_time_local, right_ascension_apparent);
+        let gps_epoch = utc_2_gps(epoch, &table);
+        let gps = utc_2_gps(utc, &table);
 
         // Assert
-        assert_approx_eq!(64.352133, hour_angle.0, 0.00001)
+
+        // SS: GPS time started at UTC, and is 18s ahead since the leap second
+        // at the end of 2016
+        assert_approx_eq!(0.0, (gps_epoch.jd - epoch.jd) * 86400.0, 0.000_1);
+        assert_approx_eq!(18.0, (gps.jd - utc.jd) * 86400.0, 0.000_1);
+    }
+
+    #[test]
+    fn gps_2_utc_test() {
+        // Arrange
+        let table = LeapSeconds::iers();
+        let jd_in_utc = [
+            JD::new(constants::GPS_EPOCH),
+            JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0)),
+            JD::from_date(Date::from_date_hms(2024, 3, 20, 12, 0, 0.0)),
+            // SS: the seconds around the leap second at the end of 2015 June 30
+            JD::new(2_457_204.5 - 10.0 / constants::SEC_PER_DAY as f64),
+            JD::new(2_457_204.5 + 10.0 / constants::SEC_PER_DAY as f64),
+        ];
+
+        for utc in jd_in_utc {
+            let gps = utc_2_gps(utc, &table);
+
+            // Act
+            let round_trip = gps_2_utc(gps, &table);
+
+            // Assert
+            assert_approx_eq!(0.0, (round_trip.jd - utc.jd) * 86400.0, 0.000_1);
+        }
     }
 
     #[test]
-    fn local_siderial_time_test_1() {
+    fn scaled_jd_gps_test() {
         // Arrange
+        let table = LeapSeconds::iers();
+        let utc = JD::from_date(Date::from_date_hms(2024, 3, 20, 12, 0, 0.0));
+        let gps = ScaledJD::new(utc_2_gps(utc, &table), TimeScale::GPS);
 
-        // SS: Jan 29th, 2022, 2:32:20pm UTC
-        let jd = JD::new(2_459_609.105793);
+        // Act
+        let to_utc = gps.to_utc(&table);
+        let to_ut1 = gps.to_ut1();
+        let to_tt = gps.to_tt();
 
-        let longitude_observer = Degrees::from_dms(105, 12, 53.8);
+        // Assert
 
-        let mean_siderial_time = mean_siderial_time(jd);
+        // SS: the same instant as in UTC
+        assert_approx_eq!(utc.jd, to_utc.jd, 1e-9);
+        assert_approx_eq!(utc.jd, to_ut1.jd, 1e-9);
+        assert_approx_eq!(utc_2_tt(utc).jd, to_tt.jd, 1e-9);
+    }
+
+    #[test]
+    fn delta_t_provider_test() {
+        // Arrange
+        let jd = JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0));
 
         // Act
-        let theta0 = local_siderial_time(mean_siderial_time, longitude_observer);
-        let (h, m, s) = theta0.to_hms();
+        let table = DeltaT::Table.seconds(jd);
+        let polynomial = DeltaT::Polynomial.seconds(jd);
+        let fixed = DeltaT::Fixed(65.0).seconds(jd);
 
         // Assert
-        assert_eq!(h, 16);
-        assert_eq!(m, 6);
-        assert_approx_eq!(46.9, s, 0.1)
+        assert_approx_eq!(64.533476, table, 0.000_001);
+
+        // SS: Espenak & Meeus 2006 give 64.5s for 2003
+        assert_approx_eq!(table, polynomial, 0.5);
+        assert_eq!(65.0, fixed);
     }
 
     #[test]
-    fn mean_siderial_time_test_1() {
+    fn spline_2016_continuous_test() {
         // Arrange
+        let intervals = SPLINE_2016.windows(2);
+
+        for interval in intervals {
+            let (_, y1, a0, a1, a2, a3) = interval[0];
+            let (y0, _, next_a0, ..) = interval[1];
+
+            // Act
+            let end = a0 + a1 + a2 + a3;
 
-        // SS: Jan 16th, 2022, 2:26:18pm UTC
-        let jd = JD::new(2_459_596.101598);
+            // Assert
+            assert_eq!(y1, y0);
+            assert_approx_eq!(next_a0, end, 0.01);
+        }
+    }
+
+    #[test]
+    fn delta_t_spline_2016_test() {
+        // Arrange
+
+        // SS: 500 BC, 1 BC, AD 1000 and AD 1500. Espenak & Meeus 2006 give
+        // about 17190s, 10580s, 1570s and 200s
+        let jd = [
+            JD::from_date(Date::new(-499, 1, 1.0)),
+            JD::from_date(Date::new(0, 1, 1.0)),
+            JD::from_date(Date::new(1000, 1, 1.0)),
+            JD::from_date(Date::new(1500, 1, 1.0)),
+        ];
 
         // Act
-        let theta0 = mean_siderial_time(jd);
-        let (h, m, s) = theta0.to_hms();
+        let delta_t = jd.map(|jd| DeltaT::Stephenson2016.seconds(jd));
 
         // Assert
-        assert_eq!(h, 22);
-        assert_eq!(m, 10);
-        assert_approx_eq!(19.92073, s, 0.00001)
+        assert_approx_eq!(16925.0, delta_t[0], 1.0);
+        assert_approx_eq!(10441.0, delta_t[1], 1.0);
+
+        // SS: the knots are the a0 coefficients
+        assert_approx_eq!(1650.393, delta_t[2], 0.001);
+        assert_approx_eq!(292.343, delta_t[3], 0.001);
     }
 
     #[test]
-    fn mean_siderial_time_test_2() {
-        // Meeus, example 12.b, page 89
+    fn delta_t_spline_2016_table_test() {
+        // Arrange
+        let (first, _) = delta_t_table_range();
+        let mut before_first = JD::from_date(first);
+        before_first.add_hours(-24.0);
+        let in_table = JD::from_date(Date::new(2000, 1, 1.0));
+
+        // Act
+        let spline = DeltaT::Stephenson2016.seconds(before_first);
+        let table = DeltaT::Table.seconds(JD::from_date(first));
+
+        // Assert
 
+        // SS: the spline is fitted to observations, so it continues the table
+        // more closely than the polynomial
+        assert_approx_eq!(table, spline, 10.0);
+        assert_eq!(
+            DeltaT::Table.seconds(in_table),
+            DeltaT::Stephenson2016.seconds(in_table)
+        );
+    }
+
+    #[test]
+    fn delta_t_spline_2016_parabola_test() {
         // Arrange
+        let (first, ..) = SPLINE_2016[0];
+        let before = JD::from_date(Date::new(first as i16 - 1, 1, 1.0));
+        let after = JD::from_date(Date::new(first as i16 + 1, 1, 1.0));
 
-        // SS: Apr. 10th 1987, 19h:21m:00s UT
-        let jd = JD::new(2_446_896.30625);
+        // Act
+        let before = delta_t_spline_2016(before);
+        let after = delta_t_spline_2016(after);
 
+        // Assert
+
+        // SS: delta_t changes by about 30s per year in the 8th century BC
+        assert_approx_eq!(before, after, 100.0);
+    }
+
+    #[test]
+    fn delta_t_table_range_test() {
         // Act
-        let theta0 = mean_siderial_time(jd);
-        let (h, m, s) = theta0.to_hms();
+        let (first, last) = delta_t_table_range();
 
         // Assert
-        assert_approx_eq!(128.7378734, theta0.0, 0.00001);
 
-        assert_eq!(h, 8);
-        assert_eq!(m, 34);
-        assert_approx_eq!(57.0896, s, 0.0001)
+        // SS: the table starts with historical data in 1657 and ends with
+        // predictions
+        assert_eq!(1657, first.year);
+        assert_eq!(1, first.month);
+        assert!(last.year >= 2027);
     }
 
     #[test]
-    fn apparent_siderial_time_test_1() {
+    fn delta_t_entries_test() {
         // Arrange
+        let (first, last) = delta_t_table_range();
+
+        // Act
+        let entries: Vec<DeltaTEntry> = delta_t_entries().collect();
+
+        // Assert
+        assert_eq!(delta_t_table().len(), entries.len());
+        assert_eq!(JD::from_date(first), entries[0].jd);
+        assert_eq!(JD::from_date(last), entries[entries.len() - 1].jd);
+        assert!(entries.windows(2).all(|pair| pair[0].jd < pair[1].jd));
+
+        // SS: delta_t interpolates the entries
+        let entry = entries[entries.len() / 2];
+        assert_approx_eq!(entry.delta_t, delta_t(entry.jd), 1e-9);
+    }
 
-        // SS: Jan 16th, 2022, 2:26:18pm UTC
-        let jd = JD::new(2_459_596.101598);
+    #[test]
+    fn leap_second_entries_test() {
+        // Arrange
+        let table = LeapSeconds::iers();
 
         // Act
-        let theta0 = apparent_siderial_time(jd);
-        let (h, m, s) = theta0.to_hms();
+        let entries: Vec<LeapSecondEntry> = table.entries().collect();
+        let (first, last) = table.range();
 
         // Assert
-        assert_eq!(h, 22);
-        assert_eq!(m, 10);
-        assert_approx_eq!(19.10356, s, 0.00001)
+        assert_eq!(LEAP_SECOND_DATA.len(), entries.len());
+        assert!(entries.windows(2).all(|pair| pair[0].jd < pair[1].jd));
+        assert_eq!(1961, first.year);
+        assert!(last.year >= 2017);
+
+        // SS: TAI - UTC was 10 s when leap seconds were introduced on 1972
+        // January 1, and 37 s after the leap second at the end of 2016
+        let introduced = entries
+            .iter()
+            .find(|entry| entry.jd == JD::from_date(Date::new(1972, 1, 1.0)))
+            .unwrap();
+        assert_approx_eq!(10.0, introduced.tai_minus_utc, 1e-9);
+        assert_approx_eq!(37.0, entries[entries.len() - 1].tai_minus_utc, 1e-9);
+        assert_approx_eq!(1.422_818, entries[0].tai_minus_utc, 1e-6);
     }
 
     #[test]
-    fn apparent_siderial_time_test_2() {
-        // Meeus, example 12.a, page 88
+    fn is_extrapolating_test() {
+        // Arrange
+        let (first, last) = delta_t_table_range();
+        let mut after_last = JD::from_date(last);
+        after_last.add_hours(24.0);
+
+        // Act
+        let in_table = is_extrapolating(JD::from_date(Date::new(2000, 1, 1.0)));
+        let before = is_extrapolating(JD::from_date(Date::new(1600, 1, 1.0)));
+        let at_first = is_extrapolating(JD::from_date(first));
+        let at_last = is_extrapolating(JD::from_date(last));
+        let after = is_extrapolating(after_last);
 
+        // Assert
+        assert!(!in_table);
+        assert!(before);
+        assert!(!at_first);
+        assert!(at_last);
+        assert!(after);
+    }
+
+    #[test]
+    fn delta_t_continuous_at_table_end_test() {
+        // Arrange
+        let (_, last) = delta_t_table_range();
+        let mut before_last = JD::from_date(last);
+        before_last.add_hours(-1.0);
+        let mut after_last = JD::from_date(last);
+        after_last.add_hours(1.0);
+
+        // Act
+        let table = delta_t(before_last);
+        let extrapolated = delta_t(after_last);
+
+        // Assert
+
+        // SS: the polynomial does not continue the table exactly
+        assert!(!is_extrapolating(before_last));
+        assert!(is_extrapolating(after_last));
+        assert_approx_eq!(table, extrapolated, 5.0);
+    }
+
+    #[test]
+    fn cumulative_leap_seconds_test1() {
         // Arrange
+        let jd = JD::from_date(Date::from_date_hms(2003, 8, 28, 3, 17, 0.0));
+
+        // Act
+        let leap_seconds = cumulative_leap_seconds(jd);
+
+        // Assert
+        assert_approx_eq!(32.0, leap_seconds, 0.1)
+    }
 
-        // SS: Apr. 10th 1987, 0 UT
-        let jd = JD::new(2_446_895.5);
+    #[test]
+    fn cumulative_leap_seconds_test2() {
+        // Arrange
+        let jd = JD::new(2_457_754.5);
 
         // Act
-        let theta0 = apparent_siderial_time(jd);
-        let (h, m, s) = theta0.to_hms();
+        let leap_seconds = cumulative_leap_seconds(jd);
 
         // Assert
-        assert_eq!(h, 13);
-        assert_eq!(m, 10);
-        assert_approx_eq!(46.1351, s, 0.000_1)
+        assert_approx_eq!(37.0, leap_seconds, 0.1)
     }
 }
@@ -0,0 +1,163 @@
+//! Named time zones from the IANA time zone database, e.g. "America/Denver".
+//! Unlike a fixed offset, the offset of a named zone depends on the date, so
+//! daylight saving time is taken into account.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Offset, TimeZone};
+use chrono_tz::Tz;
+
+use crate::date::date::Date;
+use crate::date::jd::JD;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimezoneError {
+    /// The name is not in the time zone database
+    UnknownZone(String),
+
+    /// The Julian Day is outside the range of dates supported
+    OutOfRange(f64),
+}
+
+impl fmt::Display for TimezoneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimezoneError::UnknownZone(zone) => write!(f, "unknown time zone '{zone}'"),
+            TimezoneError::OutOfRange(jd) => write!(f, "Julian Day {jd} out of range"),
+        }
+    }
+}
+
+impl std::error::Error for TimezoneError {}
+
+/// Offset of local civil time from UTC
+/// In:
+/// zone: IANA time zone name, e.g. "America/Denver"
+/// jd: Julian Day, in UTC
+/// Out:
+/// Offset in hours, positive east of Greenwich. Not necessarily a whole
+/// number, e.g. 5.5 for "Asia/Kolkata"
+pub fn utc_offset(zone: &str, jd: JD) -> Result<f64, TimezoneError> {
+    let tz = Tz::from_str(zone).map_err(|_| TimezoneError::UnknownZone(zone.to_string()))?;
+    let utc = chrono::DateTime::from_timestamp_millis(jd.to_unix_millis())
+        .ok_or(TimezoneError::OutOfRange(jd.jd))?;
+    let seconds = tz
+        .offset_from_utc_datetime(&utc.naive_utc())
+        .fix()
+        .local_minus_utc();
+    Ok(seconds as f64 / 3600.0)
+}
+
+/// Local civil time of a Julian Day
+/// In:
+/// zone: IANA time zone name, e.g. "America/Denver"
+/// jd: Julian Day, in UTC
+/// Out:
+/// Julian Day, in local civil time
+pub fn to_local(zone: &str, jd: JD) -> Result<JD, TimezoneError> {
+    let mut local = jd;
+    local.add_hours(utc_offset(zone, jd)?);
+    Ok(local)
+}
+
+/// Local civil date and time of a Julian Day
+/// In:
+/// zone: IANA time zone name, e.g. "America/Denver"
+/// jd: Julian Day, in UTC
+/// Out:
+/// Date, in local civil time
+pub fn local_date(zone: &str, jd: JD) -> Result<Date, TimezoneError> {
+    Ok(to_local(zone, jd)?.to_calendar_date())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn utc_offset_standard_time_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 1, 15.5));
+
+        // Act
+        let offset = utc_offset("America/Denver", jd).unwrap();
+
+        // Assert
+        assert_eq!(-7.0, offset);
+    }
+
+    #[test]
+    fn utc_offset_daylight_saving_time_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 7, 15.5));
+
+        // Act
+        let offset = utc_offset("America/Denver", jd).unwrap();
+
+        // Assert
+        assert_eq!(-6.0, offset);
+    }
+
+    #[test]
+    fn utc_offset_switch_test() {
+        // Arrange
+
+        // SS: in 2022, DST in the US started on March 13th, 2am local time, i.e.
+        // 9am UTC in Denver
+        let before = JD::from_date(Date::from_date_hms(2022, 3, 13, 8, 59, 0.0));
+        let after = JD::from_date(Date::from_date_hms(2022, 3, 13, 9, 1, 0.0));
+
+        // Act
+        let offset_before = utc_offset("America/Denver", before).unwrap();
+        let offset_after = utc_offset("America/Denver", after).unwrap();
+
+        // Assert
+        assert_eq!(-7.0, offset_before);
+        assert_eq!(-6.0, offset_after);
+    }
+
+    #[test]
+    fn utc_offset_half_hour_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 7, 15.5));
+
+        // Act
+        let offset = utc_offset("Asia/Kolkata", jd).unwrap();
+
+        // Assert
+        assert_eq!(5.5, offset);
+    }
+
+    #[test]
+    fn unknown_zone_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2022, 7, 15.5));
+
+        // Act
+        let offset = utc_offset("Europe/Atlantis", jd);
+
+        // Assert
+        assert_eq!(
+            Err(TimezoneError::UnknownZone("Europe/Atlantis".to_string())),
+            offset
+        );
+    }
+
+    #[test]
+    fn local_date_test() {
+        // Arrange
+
+        // SS: July 15th, 2022, 3am UTC is still July 14th in Denver
+        let jd = JD::from_date(Date::from_date_hms(2022, 7, 15, 3, 0, 0.0));
+
+        // Act
+        let date = local_date("America/Denver", jd).unwrap();
+
+        // Assert
+        assert_eq!(2022, date.year);
+        assert_eq!(7, date.month);
+        assert_approx_eq!(14.875, date.day, 0.000_001);
+    }
+}
@@ -0,0 +1,156 @@
+//! Observers whose position changes with time, e.g. on a ship or a plane.
+//! The Moon's parallax shifts it by up to a degree with the observer's
+//! position, and the time it rises by about 4 minutes per degree of
+//! longitude, so for a moving observer the topocentric position has to be
+//! calculated for the observer's position at each time.
+
+use crate::date::jd::JD;
+use crate::session::Observer;
+use crate::util::degrees::Degrees;
+
+/// Observer's position as a function of time
+pub trait ObserverTrack {
+    /// In: Julian day, in UTC
+    /// Out: Observer's location and atmospheric conditions at jd
+    fn observer(&self, jd: JD) -> Observer;
+}
+
+/// An observer who does not move
+impl ObserverTrack for Observer {
+    fn observer(&self, _jd: JD) -> Observer {
+        *self
+    }
+}
+
+/// A dead-reckoned track, e.g. from the ship's course and speed
+impl<F> ObserverTrack for F
+where
+    F: Fn(JD) -> Observer,
+{
+    fn observer(&self, jd: JD) -> Observer {
+        self(jd)
+    }
+}
+
+/// Track sampled at a few times, e.g. from GPS fixes, interpolated
+/// linearly between the samples
+#[derive(Debug, Clone)]
+pub struct SampledTrack {
+    samples: Vec<(JD, Observer)>,
+}
+
+impl SampledTrack {
+    /// In: samples, Julian day in UTC and the observer's position then
+    /// Out: Track, None if there are no samples or they are not in time order
+    pub fn new(samples: Vec<(JD, Observer)>) -> Option<Self> {
+        let in_order = samples.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        (!samples.is_empty() && in_order).then_some(Self { samples })
+    }
+}
+
+impl ObserverTrack for SampledTrack {
+    /// Before the first and after the last sample, the observer stays at the
+    /// first and last position. The time zone offset is the one of the
+    /// sample before jd
+    fn observer(&self, jd: JD) -> Observer {
+        let idx = self.samples.partition_point(|(sample, _)| *sample <= jd);
+        if idx == 0 {
+            return self.samples[0].1;
+        }
+        if idx == self.samples.len() {
+            return self.samples[idx - 1].1;
+        }
+
+        let (jd_0, observer_0) = self.samples[idx - 1];
+        let (jd_1, observer_1) = self.samples[idx];
+        let fraction = (jd.jd - jd_0.jd) / (jd_1.jd - jd_0.jd);
+        let interpolate = |a: f64, b: f64| a + fraction * (b - a);
+
+        // SS: the shorter way round, e.g. across the date line
+        let longitude_difference = (observer_1.longitude - observer_0.longitude)
+            .map_to_0_to_360()
+            .map_neg180_to_180();
+        let longitude = (observer_0.longitude + Degrees::new(fraction * longitude_difference.0))
+            .map_to_0_to_360()
+            .map_neg180_to_180();
+
+        Observer {
+            longitude,
+            latitude: Degrees::new(interpolate(observer_0.latitude.0, observer_1.latitude.0)),
+            timezone_offset: observer_0.timezone_offset,
+            pressure: interpolate(observer_0.pressure, observer_1.pressure),
+            temperature: interpolate(observer_0.temperature, observer_1.temperature),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn sampled_track_test() {
+        // Arrange
+        let start = JD::new(2_460_390.0);
+        let track = SampledTrack::new(vec![
+            (start, Observer::new(-10.0, 40.0, 1, 1010.0, 20.0)),
+            (
+                JD::new(start.jd + 1.0),
+                Observer::new(-12.0, 42.0, 2, 1020.0, 10.0),
+            ),
+        ])
+        .unwrap();
+
+        // Act
+        let before = track.observer(JD::new(start.jd - 1.0));
+        let between = track.observer(JD::new(start.jd + 0.25));
+        let after = track.observer(JD::new(start.jd + 2.0));
+
+        // Assert
+        assert_eq!(-10.0, before.longitude.0);
+        assert_approx_eq!(-10.5, between.longitude.0, 1e-9);
+        assert_approx_eq!(40.5, between.latitude.0, 1e-9);
+        assert_approx_eq!(1012.5, between.pressure, 1e-9);
+        assert_approx_eq!(17.5, between.temperature, 1e-9);
+        assert_eq!(1, between.timezone_offset);
+        assert_eq!(-12.0, after.longitude.0);
+    }
+
+    #[test]
+    fn sampled_track_date_line_test() {
+        // Arrange
+        let start = JD::new(2_460_390.0);
+        let track = SampledTrack::new(vec![
+            (start, Observer::new(179.0, -17.0, 12, 1013.0, 25.0)),
+            (
+                JD::new(start.jd + 1.0),
+                Observer::new(-177.0, -17.0, 12, 1013.0, 25.0),
+            ),
+        ])
+        .unwrap();
+
+        // Act
+        let observer = track.observer(JD::new(start.jd + 0.75));
+
+        // Assert
+
+        // SS: 4 degrees across the date line, not 356 degrees around the globe
+        assert_approx_eq!(-178.0, observer.longitude.0, 1e-9);
+    }
+
+    #[test]
+    fn sampled_track_invalid_test() {
+        // Arrange
+        let observer = Observer::new(-10.0, 40.0, 1, 1010.0, 20.0);
+        let jd = JD::new(2_460_390.0);
+
+        // Act
+        let empty = SampledTrack::new(vec![]);
+        let unordered = SampledTrack::new(vec![(jd, observer), (jd, observer)]);
+
+        // Assert
+        assert!(empty.is_none());
+        assert!(unordered.is_none());
+    }
+}
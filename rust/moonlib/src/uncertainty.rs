@@ -0,0 +1,396 @@
+//! Experimental: how much moonrise, moonset and the Moon's altitude depend
+//! on inputs that are not known exactly, or that apps choose differently.
+//!
+//! Apps disagree on the times of moonrise and moonset by a minute or two,
+//! because they use different values for delta T, a different refraction at
+//! the horizon, or round the observer's location. A Monte Carlo run
+//! perturbs these inputs uniformly over configured ranges and reports the
+//! spread of the results.
+//!
+//! The runs are reproducible: the same seed gives the same distribution on
+//! all platforms.
+
+use crate::config::{MoonlibConfig, RefractionModel};
+use crate::date::jd::JD;
+use crate::moon;
+use crate::rise_set_transit::OutputKind;
+use crate::session::{Observer, Session};
+use crate::time::{self, DeltaT};
+use crate::util::degrees::Degrees;
+use crate::util::random::Random;
+
+/// Seed used unless the caller passes one, see MonteCarlo::with_seed
+const DEFAULT_SEED: u64 = 0x6d6f_6f6e;
+
+const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+
+/// Half widths of the ranges the inputs are perturbed over, i.e. an input x
+/// is drawn from [x - range, x + range)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ranges {
+    /// delta_t, in seconds
+    pub delta_t: f64,
+
+    /// Atmospheric pressure, in millibars
+    pub pressure: f64,
+
+    /// Air temperature, in celsius
+    pub temperature: f64,
+
+    /// Observer's longitude, in degrees
+    pub longitude: f64,
+
+    /// Observer's latitude, in degrees
+    pub latitude: f64,
+
+    /// Observer's height above sea level, in meters. Only affects the
+    /// altitude, rise and set are calculated for sea level
+    pub height: f64,
+}
+
+impl Default for Ranges {
+    /// Differences between apps: delta_t from an outdated table, standard
+    /// atmosphere vs. local weather, and the location of a city rather than
+    /// the observer's, about 1 km
+    fn default() -> Self {
+        Self {
+            delta_t: 1.0,
+            pressure: 30.0,
+            temperature: 15.0,
+            longitude: 0.01,
+            latitude: 0.01,
+            height: 100.0,
+        }
+    }
+}
+
+/// Spread of a result over all samples
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Distribution {
+    /// Result for the unperturbed inputs
+    pub nominal: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+
+    /// Sample standard deviation
+    pub std_dev: f64,
+
+    /// Number of samples with a result
+    pub samples: usize,
+
+    /// Number of samples without a result, e.g. the Moon does not rise on
+    /// the day for a perturbed latitude
+    pub missed: usize,
+}
+
+impl Distribution {
+    fn new(nominal: f64, values: &[f64], missed: usize) -> Self {
+        let samples = values.len();
+        let mean = values.iter().sum::<f64>() / samples as f64;
+        let variance = values
+            .iter()
+            .map(|value| (value - mean).powi(2))
+            .sum::<f64>()
+            / (samples as f64 - 1.0).max(1.0);
+
+        Self {
+            nominal,
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            mean,
+            std_dev: variance.sqrt(),
+            samples,
+            missed,
+        }
+    }
+}
+
+/// Perturbed inputs for one sample
+struct Sample {
+    observer: Observer,
+    delta_t: f64,
+    height: f64,
+}
+
+/// Monte Carlo run, perturbing delta_t, the refraction and the observer's
+/// location
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MonteCarlo {
+    /// Number of perturbed samples
+    pub samples: usize,
+    pub seed: u64,
+    pub ranges: Ranges,
+
+    /// Configuration for the unperturbed inputs. The refraction is always
+    /// calculated for the observer's pressure and temperature
+    pub config: MoonlibConfig,
+}
+
+impl MonteCarlo {
+    /// In: number of perturbed samples
+    pub fn new(samples: usize) -> Self {
+        Self {
+            samples,
+            seed: DEFAULT_SEED,
+            ranges: Ranges::default(),
+            config: MoonlibConfig::default(),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_ranges(mut self, ranges: Ranges) -> Self {
+        self.ranges = ranges;
+        self
+    }
+
+    pub fn with_config(mut self, config: MoonlibConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Spread of the moonrise time.
+    /// The library calculates siderial time from dynamical time, so a change
+    /// of delta_t shifts the time in UTC by about the same amount
+    /// In:
+    /// observer: Observer's location and atmospheric conditions
+    /// day: Julian Day of the day, in UTC
+    /// Out: Difference to the nominal moonrise, in minutes. The nominal
+    /// value is the moonrise, as Julian Day in UTC. None if the Moon does
+    /// not rise on the day for the unperturbed inputs
+    pub fn moon_rise(&self, observer: &Observer, day: JD) -> Option<Distribution> {
+        self.event(observer, day, |session| session.moon().rise()[0])
+    }
+
+    /// Spread of the moonset time, see moon_rise
+    /// In:
+    /// observer: Observer's location and atmospheric conditions
+    /// day: Julian Day of the day, in UTC
+    /// Out: Difference to the nominal moonset, in minutes. The nominal
+    /// value is the moonset, as Julian Day in UTC. None if the Moon does
+    /// not set on the day for the unperturbed inputs
+    pub fn moon_set(&self, observer: &Observer, day: JD) -> Option<Distribution> {
+        self.event(observer, day, |session| session.moon().set()[0])
+    }
+
+    /// Spread of the Moon's apparent topocentric altitude
+    /// In:
+    /// observer: Observer's location and atmospheric conditions
+    /// jd: Julian Day, in UTC
+    /// height: Observer's height above sea level, in meters
+    /// Out: Altitude, in degrees
+    pub fn moon_altitude(&self, observer: &Observer, jd: JD, height: f64) -> Distribution {
+        let altitude = |sample: &Sample| {
+            let tt = time::utc_2_tt_with(jd, DeltaT::Fixed(sample.delta_t));
+            moon::position::topocentric_coordinates(tt, &sample.observer, sample.height)
+                .apparent_altitude
+                .0
+        };
+
+        let nominal = altitude(&self.nominal(observer, jd, height));
+        let values: Vec<f64> = self
+            .perturbed(observer, jd, height)
+            .iter()
+            .map(altitude)
+            .collect();
+        Distribution::new(nominal, &values, 0)
+    }
+
+    /// Spread of the time of an event on a day
+    fn event<F>(&self, observer: &Observer, day: JD, event: F) -> Option<Distribution>
+    where
+        F: Fn(&Session) -> OutputKind,
+    {
+        // SS: the event in UTC, converted back with the sample's own delta_t
+        let utc = |sample: &Sample| {
+            let delta_t = DeltaT::Fixed(sample.delta_t);
+            let config = self
+                .config
+                .with_refraction(RefractionModel::Observer)
+                .with_delta_t(delta_t);
+            let session = Session::new(sample.observer, day, day).with_config(config);
+            match event(&session) {
+                OutputKind::Time(tt) => {
+                    let offset = time::utc_2_tt_with(day, delta_t).jd - day.jd;
+                    Some(tt.jd - offset)
+                }
+                _ => None,
+            }
+        };
+
+        let nominal = utc(&self.nominal(observer, day, 0.0))?;
+        let samples = self.perturbed(observer, day, 0.0);
+        let values: Vec<f64> = samples
+            .iter()
+            .filter_map(utc)
+            .map(|jd| (jd - nominal) * MINUTES_PER_DAY)
+            .collect();
+        let missed = samples.len() - values.len();
+        Some(Distribution::new(nominal, &values, missed))
+    }
+
+    /// Unperturbed inputs
+    fn nominal(&self, observer: &Observer, jd: JD, height: f64) -> Sample {
+        Sample {
+            observer: *observer,
+            delta_t: self.config.delta_t.seconds(jd),
+            height,
+        }
+    }
+
+    /// Perturbed inputs, the same for the same seed
+    fn perturbed(&self, observer: &Observer, jd: JD, height: f64) -> Vec<Sample> {
+        let mut random = Random::new(self.seed);
+        let nominal = self.nominal(observer, jd, height);
+
+        (0..self.samples)
+            .map(|_| {
+                let mut observer = nominal.observer;
+                observer.longitude += Degrees::new(random.symmetric(self.ranges.longitude));
+                observer.latitude += Degrees::new(random.symmetric(self.ranges.latitude));
+                observer.pressure =
+                    (observer.pressure + random.symmetric(self.ranges.pressure)).max(0.0);
+                observer.temperature += random.symmetric(self.ranges.temperature);
+
+                Sample {
+                    observer,
+                    delta_t: nominal.delta_t + random.symmetric(self.ranges.delta_t),
+                    height: nominal.height + random.symmetric(self.ranges.height),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::date::Date;
+    use crate::test_util::munich;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn same_seed_test() {
+        // Arrange
+        let day = JD::from_date(Date::new(2024, 3, 20.0));
+        let monte_carlo = MonteCarlo::new(20).with_seed(1);
+
+        // Act
+        let a = monte_carlo.moon_rise(&munich(0), day).unwrap();
+        let b = monte_carlo.moon_rise(&munich(0), day).unwrap();
+        let c = monte_carlo.with_seed(2).moon_rise(&munich(0), day).unwrap();
+
+        // Assert
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn moon_rise_test() {
+        // Arrange
+        let day = JD::from_date(Date::new(2024, 3, 20.0));
+        let monte_carlo = MonteCarlo::new(50);
+
+        // Act
+        let rise = monte_carlo.moon_rise(&munich(0), day).unwrap();
+
+        // Assert
+        assert_eq!(50, rise.samples);
+        assert_eq!(0, rise.missed);
+        assert!(rise.min < 0.0 && rise.max > 0.0);
+        assert!(rise.min <= rise.mean && rise.mean <= rise.max);
+
+        // SS: apps disagree by a minute or two
+        assert!(
+            rise.std_dev > 0.05 && rise.std_dev < 2.0,
+            "{}",
+            rise.std_dev
+        );
+        assert!(rise.max - rise.min < 5.0);
+    }
+
+    #[test]
+    fn delta_t_only_test() {
+        // Arrange
+        let day = JD::from_date(Date::new(2024, 3, 20.0));
+        let ranges = Ranges {
+            delta_t: 30.0,
+            pressure: 0.0,
+            temperature: 0.0,
+            longitude: 0.0,
+            latitude: 0.0,
+            height: 0.0,
+        };
+        let monte_carlo = MonteCarlo::new(50).with_ranges(ranges);
+
+        // Act
+        let set = monte_carlo.moon_set(&munich(0), day).unwrap();
+
+        // Assert
+
+        // SS: the time in UTC shifts by the change of delta_t
+        assert!(set.max <= 0.5 + 1e-6 && set.min >= -0.5 - 1e-6);
+        assert!(set.max - set.min > 0.5);
+    }
+
+    #[test]
+    fn no_perturbation_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2024, 3, 20.5));
+        let ranges = Ranges {
+            delta_t: 0.0,
+            pressure: 0.0,
+            temperature: 0.0,
+            longitude: 0.0,
+            latitude: 0.0,
+            height: 0.0,
+        };
+        let monte_carlo = MonteCarlo::new(10).with_ranges(ranges);
+
+        // Act
+        let altitude = monte_carlo.moon_altitude(&munich(0), jd, 0.0);
+
+        // Assert
+        assert_approx_eq!(altitude.nominal, altitude.min, 1e-9);
+        assert_approx_eq!(altitude.nominal, altitude.max, 1e-9);
+        assert_approx_eq!(0.0, altitude.std_dev, 1e-9);
+    }
+
+    #[test]
+    fn moon_altitude_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2024, 3, 20.8));
+        let monte_carlo = MonteCarlo::new(50);
+
+        // Act
+        let altitude = monte_carlo.moon_altitude(&munich(0), jd, 500.0);
+
+        // Assert
+
+        // SS: 0.01 degrees of latitude and a second of delta_t change the
+        // altitude by at most 0.02 degrees, refraction matters close to the
+        // horizon only
+        assert!(altitude.max - altitude.min < 0.05, "{altitude:?}");
+        assert!(altitude.std_dev > 0.0);
+        assert!(altitude.min <= altitude.nominal && altitude.nominal <= altitude.max);
+    }
+
+    #[test]
+    fn never_rises_test() {
+        // Arrange
+
+        // SS: the Moon stays below the horizon all day in Utqiagvik
+        let observer = Observer::new(156.79, 71.29, -9, 1013.0, -20.0);
+        let day = JD::from_date(Date::new(2024, 3, 30.0));
+
+        // Act
+        let rise = MonteCarlo::new(10).moon_rise(&observer, day);
+
+        // Assert
+        assert!(rise.is_none());
+    }
+}
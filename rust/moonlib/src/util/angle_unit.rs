@@ -0,0 +1,11 @@
+//! Unit the caller wants angles in
+
+/// Unit of an angle handed to the caller, see Degrees::value and
+/// Radians::value
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AngleUnit {
+    Degrees,
+
+    /// To pass angles on to trigonometric functions without converting them
+    Radians,
+}
@@ -0,0 +1,42 @@
+//! Compass point names for azimuths
+
+use crate::util::degrees::Degrees;
+
+const COMPASS_POINTS: [&str; 16] = [
+    "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW",
+    "NNW",
+];
+
+/// Map an azimuth to the closest of the 16 compass points.
+/// In: Azimuth, measured from North, increasing to the East, in degrees [0, 360)
+/// Out: Compass point name, i.e. N, NNE, NE, ...
+pub(crate) fn compass_point(azimuth: Degrees) -> &'static str {
+    // SS: each compass point covers 22.5 deg, centered on the point
+    let sector_width = 360.0 / COMPASS_POINTS.len() as f64;
+    let azimuth = azimuth.map_to_0_to_360().0;
+    let idx = ((azimuth + sector_width / 2.0) / sector_width) as usize % COMPASS_POINTS.len();
+    COMPASS_POINTS[idx]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compass_point_test() {
+        // Arrange
+        let azimuths = [0.0, 11.0, 11.5, 45.0, 180.0, 200.0, 348.0, 359.0, -90.0];
+
+        // Act
+        let points: Vec<&str> = azimuths
+            .iter()
+            .map(|&a| compass_point(Degrees::new(a)))
+            .collect();
+
+        // Assert
+        assert_eq!(
+            vec!["N", "N", "NNE", "NE", "S", "SSW", "NNW", "N", "W"],
+            points
+        );
+    }
+}
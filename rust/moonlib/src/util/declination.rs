@@ -0,0 +1,93 @@
+//! Declination, a Degrees that is always in [-90, 90]
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::util::degrees::Degrees;
+use crate::util::radians::Radians;
+
+/// Declination, in degrees [-90, 90]. A separate type, so a declination
+/// cannot be passed where a right ascension or an hour angle is expected
+#[derive(Debug, Clone, Copy)]
+pub struct Declination {
+    degrees: Degrees,
+}
+
+impl Declination {
+    /// In: angle, in degrees. Angles beyond a pole are folded back, e.g.
+    /// 100 degrees to 80 degrees
+    pub fn new(degrees: Degrees) -> Self {
+        if (-90.0..=90.0).contains(&degrees.0) {
+            return Self { degrees };
+        }
+
+        // SS: map to [-180, 180), then reflect at the poles
+        let mut angle = (degrees.0 + 180.0).rem_euclid(360.0) - 180.0;
+        if angle > 90.0 {
+            angle = 180.0 - angle;
+        } else if angle < -90.0 {
+            angle = -180.0 - angle;
+        }
+        Self {
+            degrees: Degrees::new(angle),
+        }
+    }
+
+    pub fn from_dms(d: i16, m: u8, s: f64) -> Self {
+        Self::new(Degrees::from_dms(d, m, s))
+    }
+
+    pub fn degrees(self) -> Degrees {
+        self.degrees
+    }
+}
+
+/// Read access to the angle, e.g. decl.0 or decl.to_dms_str(1)
+impl Deref for Declination {
+    type Target = Degrees;
+
+    fn deref(&self) -> &Self::Target {
+        &self.degrees
+    }
+}
+
+impl From<Declination> for Degrees {
+    fn from(decl: Declination) -> Self {
+        decl.degrees
+    }
+}
+
+impl From<Declination> for Radians {
+    fn from(decl: Declination) -> Self {
+        Radians::from(decl.degrees)
+    }
+}
+
+impl fmt::Display for Declination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.degrees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn new_test() {
+        // Arrange
+        let angles = [45.0, 100.0, -100.0, 90.0, 370.0];
+
+        // Act
+        let decl = angles.map(|angle| Declination::new(Degrees::new(angle)).0);
+
+        // Assert
+        assert_approx_eq!(45.0, decl[0], 1e-12);
+        assert_approx_eq!(80.0, decl[1], 1e-12);
+        assert_approx_eq!(-80.0, decl[2], 1e-12);
+        assert_approx_eq!(90.0, decl[3], 1e-12);
+        assert_approx_eq!(10.0, decl[4], 1e-12);
+    }
+}
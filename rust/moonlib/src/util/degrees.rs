@@ -1,8 +1,10 @@
 //! Utility functions
 
+use std::fmt;
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 
 use crate::constants;
+use crate::util::angle_unit::AngleUnit;
 use crate::util::arcsec::ArcSec;
 use crate::util::radians::Radians;
 
@@ -14,6 +16,16 @@ impl Degrees {
         Self(degrees)
     }
 
+    /// The angle in the unit the caller asks for
+    /// In: unit
+    /// Out: angle, in degrees or radians
+    pub fn value(self, unit: AngleUnit) -> f64 {
+        match unit {
+            AngleUnit::Degrees => self.0,
+            AngleUnit::Radians => Radians::from(self).0,
+        }
+    }
+
     pub fn from_dms(d: i16, m: u8, s: f64) -> Self {
         let sign = if d < 0 { -1.0 } else { 1.0 };
         let value = sign * d as f64 + (m as f64 + s / 60.0) / 60.0;
@@ -101,6 +113,21 @@ impl Degrees {
             self
         }
     }
+
+    /// Smallest signed angle to turn other by to reach this angle, e.g. for
+    /// the hour angle from the siderial time and the right ascension
+    /// In: other angle, in degrees
+    /// Out: self - other, in degrees [-180, 180)
+    pub fn signed_difference(self, other: Degrees) -> Self {
+        let difference = (self.0 - other.0).rem_euclid(360.0);
+
+        // SS: rem_euclid rounds tiny negative differences up to 360
+        if difference >= 180.0 {
+            Self(difference - 360.0)
+        } else {
+            Self(difference)
+        }
+    }
 }
 
 impl Add for Degrees {
@@ -156,12 +183,50 @@ impl From<ArcSec> for Degrees {
     }
 }
 
+/// Degrees, arc minutes and arc seconds, e.g. -12° 03' 04.5"
+impl fmt::Display for Degrees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SS: round to tenths of an arc second first, so the seconds never
+        // show as 60.0
+        let tenths = (self.0.abs() * 36_000.0).round() as i64;
+        let sign = if self.0 < 0.0 && tenths > 0 { "-" } else { "" };
+        write!(
+            f,
+            "{sign}{}° {:02}' {:02}.{}\"",
+            tenths / 36_000,
+            tenths / 600 % 60,
+            tenths / 10 % 60,
+            tenths % 10
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_approx_eq::assert_approx_eq;
 
     use super::*;
 
+    #[test]
+    fn value_test() {
+        // Arrange
+        let angle = Degrees::new(-135.0);
+
+        // Act
+        let degrees = angle.value(AngleUnit::Degrees);
+        let radians = angle.value(AngleUnit::Radians);
+
+        // Assert
+        assert_eq!(-135.0, degrees);
+        assert_approx_eq!(-0.75 * std::f64::consts::PI, radians, 1e-15);
+        assert_eq!(radians, Radians::from(angle).value(AngleUnit::Radians));
+        assert_approx_eq!(
+            degrees,
+            Radians::from(angle).value(AngleUnit::Degrees),
+            1e-12
+        );
+    }
+
     #[test]
     fn map_neg180_to_180_test1() {
         // Arrange
@@ -198,6 +263,30 @@ mod tests {
         assert_approx_eq!(d.0, angle.0, 0.000_001)
     }
 
+    #[test]
+    fn signed_difference_test() {
+        // Arrange
+        let cases = [
+            (10.0, 350.0, 20.0),
+            (350.0, 10.0, -20.0),
+            (370.0, 0.0, 10.0),
+            (-370.0, 0.0, -10.0),
+            (180.0, 0.0, -180.0),
+            (0.0, 180.0, -180.0),
+            (-180.0, 0.0, -180.0),
+            (0.0, 1e-17, 0.0),
+            (725.0, -5.0, 10.0),
+        ];
+
+        for (a, b, expected) in cases {
+            // Act
+            let difference = Degrees::new(a).signed_difference(Degrees::new(b));
+
+            // Assert
+            assert_approx_eq!(expected, difference.0, 1e-12);
+        }
+    }
+
     #[test]
     fn arcsec_to_degrees_test_1() {
         // Arrange
@@ -382,4 +471,40 @@ mod tests {
         // Assert
         assert_approx_eq!(24.0 / 2.0, hours, 0.000_001)
     }
+
+    #[test]
+    fn display_test() {
+        // Arrange
+        let d = Degrees::new(13.769657226951539);
+
+        // Act
+        let s = d.to_string();
+
+        // Assert
+        assert_eq!("13° 46' 10.8\"", s);
+    }
+
+    #[test]
+    fn display_negative_test() {
+        // Arrange
+        let d = Degrees::new(-0.5);
+
+        // Act
+        let s = d.to_string();
+
+        // Assert
+        assert_eq!("-0° 30' 00.0\"", s);
+    }
+
+    #[test]
+    fn display_rounding_test() {
+        // Arrange
+        let d = Degrees::new(1.0 - 0.01 / 3600.0);
+
+        // Act
+        let s = d.to_string();
+
+        // Assert
+        assert_eq!("1° 00' 00.0\"", s);
+    }
 }
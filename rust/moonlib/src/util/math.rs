@@ -0,0 +1,131 @@
+//! Trigonometric functions used for the positions of the Sun and the Moon,
+//! the transformations between coordinate systems and the rise, set and
+//! transit times.
+//! The platform's libm may differ in the last bits between x86 and ARM, which
+//! can shift rise/set times by a minute in threshold cases. With the
+//! `deterministic` feature enabled, a software implementation of libm is
+//! used instead, so these calculations give bit-identical results on all
+//! platforms. The brightness models (illuminance, sky brightness) and the
+//! tidal coefficient still use the platform's libm.
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn sin(x: f64) -> f64 {
+    libm::sin(x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn sin(x: f64) -> f64 {
+    x.sin()
+}
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn cos(x: f64) -> f64 {
+    libm::cos(x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn cos(x: f64) -> f64 {
+    x.cos()
+}
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn tan(x: f64) -> f64 {
+    libm::tan(x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn tan(x: f64) -> f64 {
+    x.tan()
+}
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn asin(x: f64) -> f64 {
+    libm::asin(x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn asin(x: f64) -> f64 {
+    x.asin()
+}
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn acos(x: f64) -> f64 {
+    libm::acos(x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn acos(x: f64) -> f64 {
+    x.acos()
+}
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn atan(x: f64) -> f64 {
+    libm::atan(x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn atan(x: f64) -> f64 {
+    x.atan()
+}
+
+#[cfg(feature = "deterministic")]
+#[inline]
+pub(crate) fn atan2(y: f64, x: f64) -> f64 {
+    libm::atan2(y, x)
+}
+
+#[cfg(not(feature = "deterministic"))]
+#[inline]
+pub(crate) fn atan2(y: f64, x: f64) -> f64 {
+    y.atan2(x)
+}
+
+#[cfg(all(test, feature = "deterministic"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_identical_test() {
+        // Arrange
+        let x = 1234.5678;
+
+        // Act
+        let results = [
+            sin(x),
+            cos(x),
+            tan(x),
+            asin(0.3),
+            acos(0.3),
+            atan(0.3),
+            atan2(0.3, -0.7),
+        ];
+
+        // Assert
+
+        // SS: bit patterns of the software libm, the same on all platforms
+        let expected: [u64; 7] = [
+            0x3fb3_fa00_084e_e3dd,
+            0xbfef_e705_3dc3_1387,
+            0xbfb4_09a4_4879_4f2a,
+            0x3fd3_8015_9e14_f6ff,
+            0x3ff4_41f5_ecbe_ef59,
+            0x3fd2_a73a_661e_af06,
+            0x4005_e4c3_6ca0_118a,
+        ];
+        for (expected, result) in expected.iter().zip(results) {
+            assert_eq!(*expected, result.to_bits());
+        }
+    }
+}
@@ -1,4 +1,10 @@
+pub(crate) mod angle_unit;
 pub(crate) mod arcsec;
 pub(crate) mod binary_search;
+pub(crate) mod compass;
+pub(crate) mod declination;
 pub(crate) mod degrees;
+pub(crate) mod math;
 pub(crate) mod radians;
+pub(crate) mod random;
+pub(crate) mod right_ascension;
@@ -1,4 +1,5 @@
 use crate::constants;
+use crate::util::angle_unit::AngleUnit;
 use crate::util::arcsec::ArcSec;
 use crate::util::degrees::Degrees;
 use std::ops::{Add, Mul, Sub};
@@ -10,6 +11,16 @@ impl Radians {
     pub fn new(radians: f64) -> Self {
         Self(radians)
     }
+
+    /// The angle in the unit the caller asks for
+    /// In: unit
+    /// Out: angle, in degrees or radians
+    pub fn value(self, unit: AngleUnit) -> f64 {
+        match unit {
+            AngleUnit::Degrees => Degrees::from(self).0,
+            AngleUnit::Radians => self.0,
+        }
+    }
 }
 
 impl Add for Radians {
@@ -0,0 +1,82 @@
+//! Pseudo random numbers, for reproducible Monte Carlo runs
+
+/// SplitMix64 generator, Steele, Lea & Flood 2014. Not suitable for
+/// cryptography, but fast, and the same seed gives the same sequence on all
+/// platforms
+#[derive(Debug, Clone)]
+pub(crate) struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Out: uniformly distributed number in [0, 1)
+    pub(crate) fn uniform(&mut self) -> f64 {
+        // SS: the upper 53 bits fill the mantissa of a double
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// In: half_width
+    /// Out: uniformly distributed number in [-half_width, half_width)
+    pub(crate) fn symmetric(&mut self, half_width: f64) -> f64 {
+        (2.0 * self.uniform() - 1.0) * half_width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_test() {
+        // Arrange
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+
+        // Act
+        let a: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let b: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+
+        // Assert
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn reference_test() {
+        // Arrange
+        let mut random = Random::new(0);
+
+        // Act
+        let value = random.next_u64();
+
+        // Assert
+
+        // SS: first output of the reference implementation for seed 0
+        assert_eq!(0xe220_a839_7b1d_cdaf, value);
+    }
+
+    #[test]
+    fn symmetric_test() {
+        // Arrange
+        let mut random = Random::new(7);
+
+        // Act
+        let values: Vec<f64> = (0..10_000).map(|_| random.symmetric(2.0)).collect();
+
+        // Assert
+        assert!(values.iter().all(|value| (-2.0..2.0).contains(value)));
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        assert!(mean.abs() < 0.05, "{mean}");
+    }
+}
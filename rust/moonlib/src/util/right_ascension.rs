@@ -0,0 +1,103 @@
+//! Right ascension, a Degrees that is always in [0, 360)
+
+use std::fmt;
+use std::ops::{Deref, Sub};
+
+use crate::util::degrees::Degrees;
+use crate::util::radians::Radians;
+
+/// Right ascension, in degrees [0, 360). A separate type, so a right
+/// ascension cannot be passed where a declination or an hour angle is
+/// expected
+#[derive(Debug, Clone, Copy)]
+pub struct RightAscension {
+    degrees: Degrees,
+}
+
+impl RightAscension {
+    /// In: angle, in degrees, mapped to [0, 360)
+    pub fn new(degrees: Degrees) -> Self {
+        Self {
+            degrees: degrees.map_to_0_to_360(),
+        }
+    }
+
+    pub fn from_hms(h: u8, m: u8, s: f64) -> Self {
+        Self::new(Degrees::from_hms(h, m, s))
+    }
+
+    pub fn degrees(self) -> Degrees {
+        self.degrees
+    }
+}
+
+/// Read access to the angle, e.g. ra.0 or ra.to_hms_str(1)
+impl Deref for RightAscension {
+    type Target = Degrees;
+
+    fn deref(&self) -> &Self::Target {
+        &self.degrees
+    }
+}
+
+/// Difference in right ascension, in degrees. Not mapped to [0, 360), as
+/// the difference is not a right ascension
+impl Sub for RightAscension {
+    type Output = Degrees;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.degrees - rhs.degrees
+    }
+}
+
+impl From<RightAscension> for Degrees {
+    fn from(ra: RightAscension) -> Self {
+        ra.degrees
+    }
+}
+
+impl From<RightAscension> for Radians {
+    fn from(ra: RightAscension) -> Self {
+        Radians::from(ra.degrees)
+    }
+}
+
+/// In hours, minutes and seconds
+impl fmt::Display for RightAscension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.degrees.to_hms_str(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn new_test() {
+        // Arrange
+        let angles = [-15.0, 375.0, 360.0];
+
+        // Act
+        let ra = angles.map(|angle| RightAscension::new(Degrees::new(angle)).0);
+
+        // Assert
+        assert_approx_eq!(345.0, ra[0], 1e-12);
+        assert_approx_eq!(15.0, ra[1], 1e-12);
+        assert_approx_eq!(0.0, ra[2], 1e-12);
+    }
+
+    #[test]
+    fn display_test() {
+        // Arrange
+        let ra = RightAscension::from_hms(8, 58, 44.14);
+
+        // Act
+        let text = ra.to_string();
+
+        // Assert
+        assert_eq!("8h 58m 44.1s", text);
+    }
+}
@@ -0,0 +1,201 @@
+//! Validity of the models for dates far from the present.
+//!
+//! The library computes positions for any date, but the models it is built
+//! on were fitted to observations of the last few centuries. For dates before
+//! 1000 CE, e.g. for historical eclipses or lunar standstills, delta_t comes
+//! from polynomial expressions, the nutation series is used far from its
+//! epoch, and calendar dates may be in the proleptic Julian calendar. A
+//! ValidityReport tells which models are extrapolating and how far off the
+//! Moon's position may be.
+
+use std::fmt;
+
+use crate::date::jd::JD;
+use crate::time;
+use crate::util::degrees::Degrees;
+
+/// Dates before this year are historical, the report is meant to be checked
+const HISTORICAL_YEAR: i16 = 1000;
+
+/// The nutation series of Meeus, table 22.A, is truncated and its arguments
+/// are polynomials in T. Beyond this many centuries from J2000, the dropped
+/// terms and the polynomials are not reliable
+const NUTATION_CENTURIES: f64 = 10.0;
+
+/// The Julian calendar was used consistently from AD 8 on. Before, leap
+/// years were counted irregularly and dates are in the proleptic calendar
+const JULIAN_CALENDAR_YEAR: i16 = 8;
+
+/// Uncertainty of Meeus' truncated lunar theory, chapter 47, in degrees
+const LUNAR_THEORY_UNCERTAINTY: f64 = 10.0 / 3600.0;
+
+/// Earth's rotation rate relative to the stars, in degrees per second
+const EARTH_ROTATION_RATE: f64 = 360.0 / 86_164.090_5;
+
+/// Which models are extrapolating for a date, and the resulting uncertainty
+#[derive(Debug, Copy, Clone)]
+pub struct ValidityReport {
+    /// Whether the date is before 1000 CE
+    pub historical: bool,
+
+    /// Whether delta_t is extrapolated from polynomial expressions rather
+    /// than interpolated from observed values
+    pub delta_t_extrapolated: bool,
+
+    /// Whether the truncated nutation series is used more than 10 centuries
+    /// from J2000
+    pub nutation_truncated: bool,
+
+    /// Whether the calendar date is in the proleptic Julian calendar. Years
+    /// are numbered astronomically, i.e. year 0 is 1 BCE
+    pub proleptic_calendar: bool,
+
+    /// Estimated uncertainty of delta_t, in seconds
+    pub delta_t_uncertainty: f64,
+
+    /// Estimated uncertainty of the Moon's position in the observer's sky
+    pub position_uncertainty: Degrees,
+}
+
+impl ValidityReport {
+    /// Whether all models are used within their range of validity
+    pub fn is_reliable(&self) -> bool {
+        !(self.delta_t_extrapolated || self.nutation_truncated || self.proleptic_calendar)
+    }
+}
+
+/// One warning per line
+impl fmt::Display for ValidityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.delta_t_extrapolated {
+            writeln!(
+                f,
+                "delta_t extrapolated, ± {:.0} s",
+                self.delta_t_uncertainty
+            )?;
+        }
+        if self.nutation_truncated {
+            writeln!(f, "nutation series beyond its range of validity")?;
+        }
+        if self.proleptic_calendar {
+            writeln!(f, "date in the proleptic Julian calendar")?;
+        }
+        write!(f, "position uncertainty: {}", self.position_uncertainty)
+    }
+}
+
+/// Estimate the uncertainty of delta_t, from the parabola of
+/// Morrison & Stephenson 2004, see
+/// http://eclipse.gsfc.nasa.gov/SEhelp/uncertainty2004.html
+/// In: Julian Day in UTC
+/// Out: uncertainty of delta_t, in seconds. 0 where delta_t is tabulated
+fn delta_t_uncertainty(jd: JD) -> f64 {
+    if !time::is_extrapolating(jd) {
+        return 0.0;
+    }
+
+    let y = jd.to_calendar_date().fractional_year();
+    let t = (y - 1820.0) / 100.0;
+    0.8 * t * t
+}
+
+/// Report which models are extrapolating for a date and estimate the
+/// resulting uncertainty of the Moon's position.
+/// The uncertainty of delta_t translates into an uncertainty of Earth's
+/// rotation angle, which dominates the error of the Moon's position in the
+/// observer's sky for historical dates. Added to that is the uncertainty of
+/// Meeus' lunar theory, which is assumed not to grow with time.
+/// In: Julian day in dynamical time
+/// Out: Validity report
+pub fn validity_report(jd: JD) -> ValidityReport {
+    let date = jd.to_calendar_date();
+    let delta_t_uncertainty = delta_t_uncertainty(jd);
+
+    ValidityReport {
+        historical: date.year < HISTORICAL_YEAR,
+        delta_t_extrapolated: time::is_extrapolating(jd),
+        nutation_truncated: jd.centuries_from_epoch_j2000().abs() > NUTATION_CENTURIES,
+        proleptic_calendar: date.year < JULIAN_CALENDAR_YEAR,
+        delta_t_uncertainty,
+        position_uncertainty: Degrees::new(
+            LUNAR_THEORY_UNCERTAINTY + delta_t_uncertainty * EARTH_ROTATION_RATE,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_approx_eq::assert_approx_eq;
+
+    use super::*;
+    use crate::date::date::Date;
+
+    #[test]
+    fn present_test() {
+        // Arrange
+        let jd = JD::from_date(Date::new(2000, 1, 1.5));
+
+        // Act
+        let report = validity_report(jd);
+
+        // Assert
+        assert!(report.is_reliable());
+        assert!(!report.historical);
+        assert_eq!(0.0, report.delta_t_uncertainty);
+        assert_approx_eq!(
+            LUNAR_THEORY_UNCERTAINTY,
+            report.position_uncertainty.0,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn early_medieval_test() {
+        // Arrange
+
+        // SS: Julian calendar date, 12 centuries before J2000
+        let jd = JD::from_date(Date::new(800, 6, 1.0));
+
+        // Act
+        let report = validity_report(jd);
+
+        // Assert
+        assert!(!report.is_reliable());
+        assert!(report.historical);
+        assert!(report.delta_t_extrapolated);
+        assert!(report.nutation_truncated);
+        assert!(!report.proleptic_calendar);
+
+        // SS: 0.8 * 10.2^2 s, about 83 s, i.e. Earth rotates 0.35 deg
+        assert_approx_eq!(83.2, report.delta_t_uncertainty, 0.2);
+        assert_approx_eq!(0.35, report.position_uncertainty.0, 0.01);
+    }
+
+    #[test]
+    fn antiquity_test() {
+        // Arrange
+
+        // SS: 585 BCE, the eclipse predicted by Thales
+        let jd = JD::from_date(Date::new(-584, 5, 28.0));
+
+        // Act
+        let report = validity_report(jd);
+
+        // Assert
+        assert!(report.proleptic_calendar);
+        assert!(report.position_uncertainty.0 > 1.0);
+    }
+
+    #[test]
+    fn display_test() {
+        // Arrange
+        let report = validity_report(JD::from_date(Date::new(-584, 5, 28.0)));
+
+        // Act
+        let s = report.to_string();
+
+        // Assert
+        assert_eq!(4, s.lines().count());
+        assert!(s.starts_with("delta_t extrapolated"));
+    }
+}
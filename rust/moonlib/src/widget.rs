@@ -0,0 +1,315 @@
+//! Compact, fixed layout of the values a home screen widget shows. The app
+//! gets them in one byte array instead of an object with many fields, and the
+//! widget provider can persist and restore the bytes as they are.
+//!
+//! Layout, all values little-endian:
+//!
+//! | offset | size | type | value                                            |
+//! |--------|------|------|--------------------------------------------------|
+//! |      0 |    1 | u8   | version of the layout, WIDGET_DATA_VERSION       |
+//! |      1 |    1 | u8   | flags, see below                                 |
+//! |      2 |    2 |      | reserved, 0                                      |
+//! |      4 |    4 | f32  | phase angle, in degrees [0, 360)                 |
+//! |      8 |    4 | f32  | illuminated fraction [0, 1]                      |
+//! |     12 |    4 | f32  | phase age, in days                               |
+//! |     16 |    4 | f32  | apparent altitude, in degrees [-90, 90]          |
+//! |     20 |    4 | f32  | azimuth, in degrees [0, 360)                     |
+//! |     24 |    4 | f32  | distance from Earth, in kilometers               |
+//! |     28 |    8 | i64  | time the values are for, in Unix milliseconds    |
+//! |     36 |    8 | i64  | rise, in Unix milliseconds, 0 if invalid         |
+//! |     44 |    8 | i64  | set, in Unix milliseconds, 0 if invalid          |
+//! |     52 |    8 | i64  | transit, in Unix milliseconds, 0 if invalid      |
+//!
+//! Flags: bit 0 is set if the Moon is waxing, bits 1, 2 and 3 if rise, set and
+//! transit are valid. Bit 4 is set if the calculation failed, all values are
+//! then 0.
+
+use std::fmt;
+
+/// Version of the layout, increased for incompatible changes
+pub const WIDGET_DATA_VERSION: u8 = 1;
+
+/// Size of the layout, in bytes
+pub const WIDGET_DATA_SIZE: usize = 60;
+
+const FLAG_WAXING: u8 = 1;
+const FLAG_RISE: u8 = 1 << 1;
+const FLAG_SET: u8 = 1 << 2;
+const FLAG_TRANSIT: u8 = 1 << 3;
+const FLAG_ERROR: u8 = 1 << 4;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WidgetData {
+    /// false if the calculation produced values that are not finite numbers,
+    /// e.g. for invalid input
+    pub is_valid: bool,
+
+    /// in degrees [0, 360)
+    pub phase_angle: f32,
+
+    /// [0, 1]
+    pub illuminated_fraction: f32,
+
+    /// in days
+    pub phase_age: f32,
+
+    pub is_waxing: bool,
+
+    /// apparent altitude, in degrees [-90, 90]
+    pub altitude: f32,
+
+    /// in degrees [0, 360)
+    pub azimuth: f32,
+
+    /// in kilometers
+    pub distance: f32,
+
+    /// time the values are for, in Unix milliseconds
+    pub unix_millis: i64,
+
+    /// in Unix milliseconds, if the Moon rises on the day
+    pub rise: Option<i64>,
+
+    /// in Unix milliseconds, if the Moon sets on the day
+    pub set: Option<i64>,
+
+    /// in Unix milliseconds, if the Moon transits on the day
+    pub transit: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WidgetDataError {
+    /// The buffer does not have WIDGET_DATA_SIZE bytes
+    WrongSize(usize),
+
+    /// The buffer was written with a different layout
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for WidgetDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WidgetDataError::WrongSize(size) => {
+                write!(f, "expected {WIDGET_DATA_SIZE} bytes, got {size}")
+            }
+            WidgetDataError::UnsupportedVersion(version) => {
+                write!(f, "unsupported widget data version {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WidgetDataError {}
+
+impl WidgetData {
+    /// Whether all values are finite numbers
+    pub(crate) fn is_finite(&self) -> bool {
+        [
+            self.phase_angle,
+            self.illuminated_fraction,
+            self.phase_age,
+            self.altitude,
+            self.azimuth,
+            self.distance,
+        ]
+        .iter()
+        .all(|value| value.is_finite())
+    }
+
+    /// Encode into the fixed layout
+    pub fn encode(&self) -> [u8; WIDGET_DATA_SIZE] {
+        let mut bytes = [0; WIDGET_DATA_SIZE];
+
+        let mut flags = 0;
+        for (flag, is_set) in [
+            (FLAG_WAXING, self.is_waxing),
+            (FLAG_RISE, self.rise.is_some()),
+            (FLAG_SET, self.set.is_some()),
+            (FLAG_TRANSIT, self.transit.is_some()),
+            (FLAG_ERROR, !self.is_valid),
+        ] {
+            if is_set {
+                flags |= flag;
+            }
+        }
+
+        bytes[0] = WIDGET_DATA_VERSION;
+        bytes[1] = flags;
+
+        let values = [
+            self.phase_angle,
+            self.illuminated_fraction,
+            self.phase_age,
+            self.altitude,
+            self.azimuth,
+            self.distance,
+        ];
+        for (idx, value) in values.iter().enumerate() {
+            let offset = 4 + 4 * idx;
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        let times = [
+            self.unix_millis,
+            self.rise.unwrap_or(0),
+            self.set.unwrap_or(0),
+            self.transit.unwrap_or(0),
+        ];
+        for (idx, time) in times.iter().enumerate() {
+            let offset = 28 + 8 * idx;
+            bytes[offset..offset + 8].copy_from_slice(&time.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Decode from the fixed layout
+    /// In: bytes written by encode
+    /// Out: the widget data, or an error if the size or version do not match
+    pub fn decode(bytes: &[u8]) -> Result<Self, WidgetDataError> {
+        if bytes.len() != WIDGET_DATA_SIZE {
+            return Err(WidgetDataError::WrongSize(bytes.len()));
+        }
+        if bytes[0] != WIDGET_DATA_VERSION {
+            return Err(WidgetDataError::UnsupportedVersion(bytes[0]));
+        }
+
+        let flags = bytes[1];
+        let f32_at =
+            |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let i64_at =
+            |offset: usize| i64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let time_at = |offset: usize, flag: u8| (flags & flag != 0).then(|| i64_at(offset));
+
+        Ok(Self {
+            is_valid: flags & FLAG_ERROR == 0,
+            phase_angle: f32_at(4),
+            illuminated_fraction: f32_at(8),
+            phase_age: f32_at(12),
+            is_waxing: flags & FLAG_WAXING != 0,
+            altitude: f32_at(16),
+            azimuth: f32_at(20),
+            distance: f32_at(24),
+            unix_millis: i64_at(28),
+            rise: time_at(36, FLAG_RISE),
+            set: time_at(44, FLAG_SET),
+            transit: time_at(52, FLAG_TRANSIT),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widget_data() -> WidgetData {
+        WidgetData {
+            is_valid: true,
+            phase_angle: 123.25,
+            illuminated_fraction: 0.5,
+            phase_age: 9.75,
+            is_waxing: true,
+            altitude: -12.5,
+            azimuth: 271.0,
+            distance: 385_000.5,
+            unix_millis: 1_642_343_178_123,
+            rise: Some(1_642_300_000_000),
+            set: None,
+            transit: Some(-1),
+        }
+    }
+
+    #[test]
+    fn round_trip_test() {
+        // Arrange
+        let data = widget_data();
+
+        // Act
+        let decoded = WidgetData::decode(&data.encode()).unwrap();
+
+        // Assert
+        assert_eq!(data, decoded);
+    }
+
+    #[test]
+    fn layout_test() {
+        // Arrange
+        let data = widget_data();
+
+        // Act
+        let bytes = data.encode();
+
+        // Assert
+        assert_eq!(WIDGET_DATA_VERSION, bytes[0]);
+        assert_eq!(FLAG_WAXING | FLAG_RISE | FLAG_TRANSIT, bytes[1]);
+        assert_eq!([0, 0], bytes[2..4]);
+        assert_eq!(123.25f32.to_le_bytes(), bytes[4..8]);
+        assert_eq!(385_000.5f32.to_le_bytes(), bytes[24..28]);
+        assert_eq!(1_642_343_178_123i64.to_le_bytes(), bytes[28..36]);
+        assert_eq!([0; 8], bytes[44..52]);
+        assert_eq!([0xff; 8], bytes[52..60]);
+    }
+
+    #[test]
+    fn invalid_times_test() {
+        // Arrange
+        let data = WidgetData {
+            rise: None,
+            transit: None,
+            ..widget_data()
+        };
+
+        // Act
+        let decoded = WidgetData::decode(&data.encode()).unwrap();
+
+        // Assert
+        assert_eq!(None, decoded.rise);
+        assert_eq!(None, decoded.set);
+        assert_eq!(None, decoded.transit);
+    }
+
+    #[test]
+    fn wrong_size_test() {
+        // Arrange
+        let bytes = widget_data().encode();
+
+        // Act
+        let decoded = WidgetData::decode(&bytes[..WIDGET_DATA_SIZE - 1]);
+
+        // Assert
+        assert_eq!(
+            Err(WidgetDataError::WrongSize(WIDGET_DATA_SIZE - 1)),
+            decoded
+        );
+    }
+
+    #[test]
+    fn unsupported_version_test() {
+        // Arrange
+        let mut bytes = widget_data().encode();
+        bytes[0] = WIDGET_DATA_VERSION + 1;
+
+        // Act
+        let decoded = WidgetData::decode(&bytes);
+
+        // Assert
+        assert_eq!(
+            Err(WidgetDataError::UnsupportedVersion(WIDGET_DATA_VERSION + 1)),
+            decoded
+        );
+    }
+
+    #[test]
+    fn error_flag_test() {
+        // Arrange
+        let data = WidgetData::default();
+
+        // Act
+        let bytes = data.encode();
+        let decoded = WidgetData::decode(&bytes).unwrap();
+
+        // Assert
+        assert_eq!(FLAG_ERROR, bytes[1]);
+        assert!(!decoded.is_valid);
+    }
+}
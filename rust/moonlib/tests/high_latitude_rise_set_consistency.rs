@@ -0,0 +1,212 @@
+//! Consistency of moonrise and moonset between 54 and 78 degrees north and
+//! south, where the Moon stays above or below the horizon for days and
+//! crosses it at a shallow angle.
+//!
+//! The times in fixtures/high_latitude_rise_set_scan.csv are for Tromso,
+//! Utqiagvik, Ushuaia and McMurdo Station over the lunar month from 2024
+//! March 10. They are not published almanac times but were found by the
+//! library itself, with a brute-force scan of the upper limb's refracted
+//! topocentric altitude every minute, see
+//! moonlib::test_vectors::write_high_latitude_rise_set. So they test the
+//! rise/set algorithm, i.e. the iteration and the days without an event,
+//! against the library's own ephemeris, not the Moon's position. For
+//! published times, see high_latitude_rise_set_usno.rs. Regenerate with
+//! ```
+//! cargo run --package test_vectors --bin test-vectors -- --high-latitude --output moonlib/tests/fixtures/high_latitude_rise_set_scan.csv
+//! ```
+//! All Julian Days are in dynamical time.
+
+use moonlib::date::jd::JD;
+use moonlib::moon::rise_set_transit::OutputKind;
+use moonlib::session::{Observer, Session, TimeScale};
+
+const FIXTURE: &str = include_str!("fixtures/high_latitude_rise_set_scan.csv");
+
+/// Near the horizon, the Moon's altitude changes by as little as 0.1
+/// degrees in 5 minutes at these latitudes
+const TOLERANCE_MINUTES: f64 = 5.0;
+
+/// Days on which the Moon dips below the horizon, or rises above it, for a
+/// couple of hours only. Meeus, chapter 15, decides from the Moon's
+/// declination for the day whether it rises and sets at all, and misses
+/// both events.
+/// Remove a day once the algorithm finds its events.
+const KNOWN_MISSED: &[(&str, f64)] = &[("tromso", 2_460_383.5), ("utqiagvik", 2_460_397.5)];
+
+struct Day {
+    location: String,
+    observer: Observer,
+    day: JD,
+    rises: Vec<f64>,
+    sets: Vec<f64>,
+    all_day: String,
+}
+
+fn parse_events(field: &str) -> Vec<f64> {
+    field
+        .split_whitespace()
+        .map(|jd| jd.parse().unwrap())
+        .collect()
+}
+
+fn fixture() -> Vec<Day> {
+    FIXTURE
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            Day {
+                location: fields[0].to_string(),
+                observer: Observer::new(
+                    fields[1].parse().unwrap(),
+                    fields[2].parse().unwrap(),
+                    fields[3].parse().unwrap(),
+                    1013.25,
+                    10.0,
+                ),
+                day: JD::new(fields[4].parse().unwrap()),
+                rises: parse_events(fields[5]),
+                sets: parse_events(fields[6]),
+                all_day: fields[7].to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Difference to the closest reference event, in minutes
+fn difference(event: OutputKind, reference: &[f64]) -> Option<f64> {
+    match event {
+        OutputKind::Time(jd) => reference
+            .iter()
+            .map(|reference| (jd.jd - reference).abs() * 24.0 * 60.0)
+            .reduce(f64::min),
+        _ => None,
+    }
+}
+
+/// Calculate moonrise and moonset for each day of the fixture
+fn rise_set(days: &[Day]) -> Vec<(OutputKind, OutputKind)> {
+    let mut events = vec![];
+
+    // SS: one session for each location's consecutive days
+    for location in days.chunk_by(|a, b| a.location == b.location) {
+        let session = Session::new(
+            location[0].observer,
+            location[0].day,
+            location[location.len() - 1].day,
+        )
+        .with_time_scale(TimeScale::TT);
+        assert_eq!(location.len(), session.days().len());
+
+        events.extend(session.moon().rise().into_iter().zip(session.moon().set()));
+    }
+
+    events
+}
+
+#[test]
+fn fixture_test() {
+    // Arrange
+    let days = fixture();
+
+    // Assert
+    assert_eq!(4 * 30, days.len());
+    for location in ["tromso", "utqiagvik", "ushuaia", "mcmurdo"] {
+        assert!(days.iter().any(|day| day.location == location));
+    }
+
+    // SS: a lunar month takes the Moon from its northernmost to its
+    // southernmost declination, so it must stay up and down all day
+    // north and south of the polar circles
+    for location in ["tromso", "utqiagvik", "mcmurdo"] {
+        assert!(days
+            .iter()
+            .any(|day| day.location == location && day.all_day == "above"));
+        assert!(days
+            .iter()
+            .any(|day| day.location == location && day.all_day == "below"));
+    }
+}
+
+#[test]
+fn moon_stays_above_or_below_horizon_test() {
+    // Arrange
+    let days = fixture();
+
+    // Act
+    let events = rise_set(&days);
+
+    // Assert
+    for (day, &(rise, set)) in days.iter().zip(&events) {
+        let label = format!("{} {} {rise:?} {set:?}", day.location, day.day);
+        match day.all_day.as_str() {
+            "above" => assert!(
+                matches!((rise, set), (OutputKind::NeverSets, OutputKind::NeverSets)),
+                "{label}"
+            ),
+            "below" => assert!(
+                matches!(
+                    (rise, set),
+                    (OutputKind::NeverRises, OutputKind::NeverRises)
+                ),
+                "{label}"
+            ),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn moonrise_moonset_consistency_test() {
+    // Arrange
+    let days = fixture();
+
+    // Act
+    let events = rise_set(&days);
+
+    // Assert
+    let mut differences = vec![];
+    for (day, &(rise, set)) in days.iter().zip(&events) {
+        if KNOWN_MISSED.contains(&(day.location.as_str(), day.day.jd)) {
+            continue;
+        }
+
+        for (event, reference) in [(rise, &day.rises), (set, &day.sets)] {
+            let label = format!("{} {} {event:?} {reference:?}", day.location, day.day);
+
+            if reference.is_empty() {
+                assert!(!matches!(event, OutputKind::Time(_)), "{label}");
+                continue;
+            }
+
+            let difference = difference(event, reference).expect(&label);
+            assert!(difference < TOLERANCE_MINUTES, "{label} {difference}");
+            differences.push(difference);
+        }
+    }
+
+    // SS: away from grazing events, the times agree within a minute
+    let mean = differences.iter().sum::<f64>() / differences.len() as f64;
+    assert!(mean < 1.0, "{mean}");
+}
+
+#[test]
+fn known_missed_test() {
+    // Arrange
+    let days = fixture();
+
+    // Act
+    let events = rise_set(&days);
+
+    // Assert
+    for (day, &(rise, set)) in days.iter().zip(&events) {
+        if !KNOWN_MISSED.contains(&(day.location.as_str(), day.day.jd)) {
+            continue;
+        }
+
+        // SS: the reference has a rise and a set on these days
+        assert!(!day.rises.is_empty() && !day.sets.is_empty());
+        assert!(!matches!(rise, OutputKind::Time(_)));
+        assert!(!matches!(set, OutputKind::Time(_)));
+    }
+}
@@ -0,0 +1,130 @@
+//! Moonrise and moonset between 54 and 78 degrees north and south against
+//! the times published by the U.S. Naval Observatory, which test the
+//! Moon's position as well as the rise/set algorithm.
+//!
+//! The times come from USNO's Astronomical Applications API, "Sun and Moon
+//! Data for One Day",
+//! ```
+//! https://aa.usno.navy.mil/api/rstt/oneday?date=<YYYY-MM-DD>&coords=<latitude>,<longitude>&tz=<timezone_offset>&dst=false
+//! ```
+//! with the longitude positive east of Greenwich. USNO's times are for the
+//! upper limb with a standard refraction of 34', as in this library, in
+//! the local time of tz and rounded to the minute.
+//!
+//! The test reads a CSV file given by MOONLIB_USNO_RISE_SET, with the
+//! columns
+//! ```
+//! location,longitude,latitude,timezone_offset,date,rise,set
+//! ```
+//! longitude positive west of Greenwich, as in this library, date as
+//! YYYY-MM-DD and rise and set as HH:MM in local time, empty if USNO lists
+//! no event on the day.
+
+use moonlib::date::jd::JD;
+use moonlib::moon::rise_set_transit::OutputKind;
+use moonlib::session::{Observer, Session, TimeScale};
+use moonlib::time::{self, LeapSeconds};
+
+/// USNO rounds to the minute, and near the horizon, the Moon's altitude
+/// changes by as little as 0.1 degrees in 5 minutes at these latitudes
+const TOLERANCE_MINUTES: f64 = 5.0;
+
+struct Day {
+    label: String,
+    observer: Observer,
+    day: JD,
+    rise: Option<JD>,
+    set: Option<JD>,
+}
+
+/// Julian Day of 0h UTC of a date YYYY-MM-DD, see Howard Hinnant,
+/// "chrono-Compatible Low-Level Date Algorithms", days_from_civil
+fn julian_day(date: &str) -> JD {
+    let fields: Vec<i64> = date.split('-').map(|f| f.parse().unwrap()).collect();
+    let (year, month, day) = (fields[0], fields[1], fields[2]);
+
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days_since_unix_epoch = era * 146_097 + day_of_era - 719_468;
+
+    JD::new(2_440_587.5 + days_since_unix_epoch as f64)
+}
+
+/// Julian Day in UTC of a local time HH:MM on a day
+fn event(day: JD, timezone_offset: i8, time: &str) -> Option<JD> {
+    let (hours, minutes) = time.split_once(':')?;
+    let hours: f64 = hours.parse().unwrap();
+    let minutes: f64 = minutes.parse().unwrap();
+    Some(JD::new(
+        day.jd + (hours + minutes / 60.0 - timezone_offset as f64) / 24.0,
+    ))
+}
+
+fn fixture(path: &str) -> Vec<Day> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            let timezone_offset = fields[3].parse().unwrap();
+            let day = julian_day(fields[4]);
+            Day {
+                label: format!("{} {}", fields[0], fields[4]),
+                observer: Observer::new(
+                    fields[1].parse().unwrap(),
+                    fields[2].parse().unwrap(),
+                    timezone_offset,
+                    1013.25,
+                    10.0,
+                ),
+                day,
+                rise: event(day, timezone_offset, fields[5]),
+                set: event(day, timezone_offset, fields[6]),
+            }
+        })
+        .collect()
+}
+
+/// Difference to the reference event, in minutes
+fn difference(event: OutputKind, reference: JD) -> Option<f64> {
+    match event {
+        OutputKind::Time(tt) => {
+            let utc = time::tt_2_utc(tt, &LeapSeconds::iers());
+            Some((utc.jd - reference.jd).abs() * 24.0 * 60.0)
+        }
+        _ => None,
+    }
+}
+
+#[test]
+#[ignore = "needs USNO's times, set MOONLIB_USNO_RISE_SET to the CSV file"]
+fn usno_moonrise_moonset_test() {
+    // Arrange
+    let path = std::env::var("MOONLIB_USNO_RISE_SET").unwrap();
+    let days = fixture(&path);
+    assert!(!days.is_empty());
+
+    for day in days {
+        let session = Session::new(day.observer, day.day, day.day).with_time_scale(TimeScale::UTC);
+
+        // Act
+        let rise = session.moon().rise()[0];
+        let set = session.moon().set()[0];
+
+        // Assert
+        for (event, reference) in [(rise, day.rise), (set, day.set)] {
+            let label = format!("{} {event:?} {reference:?}", day.label);
+            match reference {
+                Some(reference) => {
+                    let difference = difference(event, reference).expect(&label);
+                    assert!(difference < TOLERANCE_MINUTES, "{label} {difference}");
+                }
+                None => assert!(!matches!(event, OutputKind::Time(_)), "{label}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Use the library the way callers outside the crate do, i.e. only through
+//! public paths.
+
+use moonlib::config::AngleUnit;
+use moonlib::date::jd::JD;
+use moonlib::moon::position::{equatorial_coordinates, equatorial_coordinates_fixed, Equinox};
+use moonlib::observation_log::{FixedEquatorial, Rounding};
+use moonlib::time::{delta_t_entries, LeapSeconds, ScaledJD, TimeScale};
+
+#[test]
+fn fixed_equatorial_test() {
+    // Arrange
+
+    // SS: Meeus, example 21.b, theta Persei
+    let ra = 41.054063;
+    let decl = 49.227750;
+
+    // Act
+    let fixed = FixedEquatorial::new(ra, decl, Rounding::HalfAwayFromZero);
+
+    // Assert
+    assert_eq!("02:44:12.98 +49:13:39.9", fixed.to_aavso());
+}
+
+#[test]
+fn moon_equatorial_coordinates_fixed_test() {
+    // Arrange
+    let jd = JD::new(2_448_724.5);
+
+    // Act
+    let fixed = equatorial_coordinates_fixed(jd, Equinox::J2000, Rounding::HalfEven);
+
+    // Assert
+    let (ra, _) = equatorial_coordinates(jd, Equinox::J2000);
+    let expected = (ra.to_hours() * 15.0 * 3_600_000.0).round() as i64;
+    assert_eq!(expected, fixed.ra);
+}
+
+#[test]
+fn angle_unit_test() {
+    // Arrange
+    let jd = JD::new(2_448_724.5);
+    let observer = moonlib::session::Observer::new(-11.6, 48.1, 0, 1013.0, 10.0);
+
+    // Act
+    let topocentric = moonlib::moon::position::topocentric_coordinates(jd, &observer, 0.0);
+
+    // Assert
+    let degrees = topocentric.azimuth.value(AngleUnit::Degrees);
+    let radians = topocentric.azimuth.value(AngleUnit::Radians);
+    assert!((degrees.to_radians() - radians).abs() < 1e-15);
+}
+
+#[test]
+fn calendar_date_utc_test() {
+    // Arrange
+    let table = LeapSeconds::iers();
+
+    // SS: 2024 March 10, 0:00:30 TT is before midnight in UTC
+    let tt = JD::new(2_460_379.5 + 30.0 / 86_400.0);
+
+    // Act
+    let date = tt.to_calendar_date_utc(&table);
+    let scaled = ScaledJD::new(tt, TimeScale::TT).to_utc(&table);
+
+    // Assert
+    assert_eq!(9, date.day.trunc() as i16);
+    assert_eq!(date.day, scaled.to_calendar_date().day);
+}
+
+#[test]
+fn data_tables_test() {
+    // Act
+    let delta_t = delta_t_entries();
+    let leap_seconds = LeapSeconds::iers();
+
+    // Assert
+
+    // SS: downstream crates can check the coverage window without knowing
+    // how the tables are stored
+    assert!(delta_t.len() > 1000);
+    let (_, last) = moonlib::time::delta_t_table_range();
+    assert!(last.year >= 2027);
+    let (first, _) = leap_seconds.range();
+    assert_eq!(1961, first.year);
+    let last = leap_seconds.entries().last().unwrap();
+    assert!(last.tai_minus_utc >= 37.0);
+}
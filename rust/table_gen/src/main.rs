@@ -0,0 +1,71 @@
+//! Generator for the delta_t table used in the calculation of TT from UTC.
+//! Open https://cddis.nasa.gov/archive/products/iers/finals2000A.all and
+//! https://cddis.nasa.gov/archive/products/iers/deltat.preds in a browser,
+//! select all and copy into text editor. Save the files as finals2000A.all and
+//! deltat.preds in the base folder with the top-level Cargo.toml file.
+//! Then execute
+//! ```
+//! cargo run --package table_gen --bin table-gen -- --finals ../finals2000A.all --preds ../deltat.preds
+//! ```
+//! This regenerates tabular/src/time/delta_t_data.rs. Predictions already covered
+//! by finals2000A.all are dropped and the merged data is checked to be strictly
+//! increasing before the table is written.
+mod parse;
+mod table;
+
+use clap::{App, Arg};
+use std::fs::File;
+use std::io::BufReader;
+
+fn main() -> Result<(), std::io::Error> {
+    let app = App::new("table-gen")
+        .about("Generates the delta t table to compute TT from UT from NASA data files")
+        .arg(
+            Arg::new("finals")
+                .long("finals")
+                .takes_value(true)
+                .required(true)
+                .help("finals2000A.all"),
+        )
+        .arg(
+            Arg::new("preds")
+                .long("preds")
+                .takes_value(true)
+                .required(true)
+                .help("deltat.preds"),
+        )
+        .arg(
+            Arg::new("table")
+                .long("table")
+                .takes_value(true)
+                .default_value("tabular/src/time/delta_t_data.rs")
+                .help("delta t table to update"),
+        )
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .takes_value(true)
+                .help("Output file, defaults to overwriting the table"),
+        )
+        .get_matches();
+
+    let finals_filename = app.value_of("finals").unwrap();
+    let finals = parse::parse_finals(BufReader::new(File::open(finals_filename)?))?;
+
+    let preds_filename = app.value_of("preds").unwrap();
+    let predictions = parse::parse_predictions(BufReader::new(File::open(preds_filename)?))?;
+
+    let table_filename = app.value_of("table").unwrap();
+    let table = std::fs::read_to_string(table_filename)?;
+
+    let n_finals = finals.len();
+    let n_predictions = predictions.len();
+    let output = table::generate(&table, finals, predictions)?;
+
+    let output_filename = app.value_of("output").unwrap_or(table_filename);
+    std::fs::write(output_filename, output)?;
+
+    println!("Processed {n_finals} finals and {n_predictions} prediction lines...");
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Parsers for the IERS delta t data files
+
+use moonlib::date::jd::JD;
+use std::io::{BufRead, Error, ErrorKind};
+
+pub(crate) struct DeltaTEntry {
+    pub(crate) jd: f64,
+    pub(crate) delta_t: f64,
+    pub(crate) comment: String,
+}
+
+/// Parse https://cddis.nasa.gov/archive/products/iers/finals2000A.all.
+/// delta t is calculated from UT1 - UTC and the cumulative leap seconds.
+/// Parsing stops at the first line that does not contain UT1 - UTC.
+pub(crate) fn parse_finals<R: BufRead>(reader: R) -> Result<Vec<DeltaTEntry>, Error> {
+    let mut entries = vec![];
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_end();
+        if line.len() < 68 {
+            break;
+        }
+
+        let mjd = parse_f64(&line[7..15])?;
+        let jd = JD::from_mjd(mjd);
+
+        let delta_ut = parse_f64(&line[58..68])?;
+
+        let cumulative_leap_secs = moonlib::time::cumulative_leap_seconds(jd);
+        let delta_t = -delta_ut + cumulative_leap_secs + 32.184;
+
+        let date = jd.to_calendar_date();
+        let comment = format!(
+            "{} {} {}, UT1-UTC={delta_ut:.7}, Cumulative leap seconds={cumulative_leap_secs}",
+            date.day,
+            month_text(date.month),
+            date.year
+        );
+
+        entries.push(DeltaTEntry {
+            jd: jd.jd,
+            delta_t,
+            comment,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parse https://cddis.nasa.gov/archive/products/iers/deltat.preds.
+/// The first line is a header, parsing stops at the first empty line.
+pub(crate) fn parse_predictions<R: BufRead>(reader: R) -> Result<Vec<DeltaTEntry>, Error> {
+    let mut entries = vec![];
+
+    // SS: skip header line
+    for line in reader.lines().skip(1) {
+        let line = line?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        if line.len() < 29 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Line too short: {line}"),
+            ));
+        }
+
+        let mjd = parse_f64(&line[3..12])?;
+        let jd = JD::from_mjd(mjd);
+
+        let delta_t = parse_f64(&line[24..29])?;
+
+        let date = jd.to_calendar_date();
+        let comment = format!("{} {} {}", date.day, month_text(date.month), date.year);
+
+        entries.push(DeltaTEntry {
+            jd: jd.jd,
+            delta_t,
+            comment,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn parse_f64(text: &str) -> Result<f64, Error> {
+    text.trim()
+        .parse::<f64>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{text}: {e}")))
+}
+
+fn month_text(m: u8) -> &'static str {
+    match m {
+        1 => "Jan",
+        2 => "Feb",
+        3 => "Mar",
+        4 => "Apr",
+        5 => "Mai",
+        6 => "Jun",
+        7 => "Jul",
+        8 => "Aug",
+        9 => "Sep",
+        10 => "Oct",
+        11 => "Nov",
+        12 => "Dec",
+        _ => "Invalid",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_finals_test() {
+        // Arrange
+        let data = "\
+73 1 2 41684.00 I  0.120733 0.009786  0.136966 0.015902  I 0.8084178 0.0002710  0.0000 0.1916  P    -0.766    0.199    -0.720    0.300  .143000   .137000   .8075000   -18.637    -3.667
+73 1 3 41685.00 I  0.118980 0.011039  0.135656 0.013616  I 0.8056163 0.0002710  3.5563 0.1916  P    -0.751    0.199    -0.701    0.300  .141000   .134000   .8044000   -18.636    -3.571
+2312 1 60309.00 P  0.147154 0.008298  0.290424 0.011942  P
+";
+
+        // Act
+        let entries = parse_finals(data.as_bytes()).unwrap();
+
+        // Assert
+        assert_eq!(2, entries.len());
+        assert_eq!(2_441_684.5, entries[0].jd);
+        assert!((43.3755822 - entries[0].delta_t).abs() < 0.000_000_1);
+        assert_eq!(
+            "2 Jan 1973, UT1-UTC=0.8084178, Cumulative leap seconds=12",
+            entries[0].comment
+        );
+    }
+
+    #[test]
+    fn parse_predictions_test() {
+        // Arrange
+        let data = "\
+  MJD        YEAR    TT-UT Pred  UT1-UTC Pred  ERROR
+   60036.00   2023.25   71.54        -0.0302     0.0006
+   60127.00   2023.50   71.67        -0.0186     0.0069
+
+";
+
+        // Act
+        let entries = parse_predictions(data.as_bytes()).unwrap();
+
+        // Assert
+        assert_eq!(2, entries.len());
+        assert_eq!(2_460_036.5, entries[0].jd);
+        assert_eq!(71.54, entries[0].delta_t);
+        assert_eq!("2 Jul 2023", entries[1].comment);
+    }
+}
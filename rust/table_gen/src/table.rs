@@ -0,0 +1,269 @@
+//! Merge the parsed delta t data into tabular/src/time/delta_t_data.rs
+
+use crate::parse::DeltaTEntry;
+use std::fmt::Write;
+use std::io::{Error, ErrorKind};
+
+/// Everything in the table after this marker is generated
+const FINALS_MARKER: &str =
+    "    // SS: Data taken from https://cddis.nasa.gov/archive/products/iers/finals2000A.all";
+
+const PREDICTIONS_MARKER: &str =
+    "    // delta_t predictions, from https://cddis.nasa.gov/archive/products/iers/deltat.preds";
+
+/// Return the part of the existing table that is not generated, i.e. the
+/// type definitions and the historical delta t data.
+fn historical_section(table: &str) -> Result<&str, Error> {
+    let idx = table.find(FINALS_MARKER).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Marker not found in table: {}", FINALS_MARKER.trim()),
+        )
+    })?;
+    Ok(&table[..idx])
+}
+
+/// Extract the Julian days of the historical delta t data.
+fn historical_jds(historical: &str) -> Result<Vec<f64>, Error> {
+    historical
+        .lines()
+        .map(|line| line.trim())
+        .filter_map(|line| line.strip_prefix("jd:"))
+        .map(|jd| {
+            jd.trim()
+                .trim_end_matches(',')
+                .parse::<f64>()
+                .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{jd}: {e}")))
+        })
+        .collect()
+}
+
+/// Drop all predictions that are already covered by finals2000A.all.
+pub(crate) fn deduplicate(
+    finals: &[DeltaTEntry],
+    predictions: Vec<DeltaTEntry>,
+) -> Vec<DeltaTEntry> {
+    let last_jd = finals.last().map_or(f64::MIN, |entry| entry.jd);
+    predictions
+        .into_iter()
+        .filter(|entry| entry.jd > last_jd)
+        .collect()
+}
+
+/// Make sure the Julian days are strictly increasing and all delta t values
+/// are finite, as the lookup in moonlib::time uses binary search.
+pub(crate) fn validate(jds: &[f64], entries: &[DeltaTEntry]) -> Result<(), Error> {
+    let mut prev = f64::MIN;
+    for jd in jds.iter().copied().chain(entries.iter().map(|e| e.jd)) {
+        if jd <= prev {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Julian days not strictly increasing: {prev:.2} followed by {jd:.2}"),
+            ));
+        }
+        prev = jd;
+    }
+
+    if let Some(entry) = entries.iter().find(|e| !e.delta_t.is_finite()) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid delta t for jd={:.2}", entry.jd),
+        ));
+    }
+
+    Ok(())
+}
+
+fn write_entries(output: &mut String, entries: &[DeltaTEntry]) {
+    for entry in entries {
+        writeln!(
+            output,
+            "    DeltaTValue {{\n        jd: {:.2},\n        delta_t: {:.7},\n    }}, // {}",
+            entry.jd, entry.delta_t, entry.comment
+        )
+        .unwrap();
+    }
+}
+
+/// Generate the new table from the existing one. The historical section is
+/// kept as is, the finals2000A.all data and the predictions are replaced.
+/// In:
+/// table: Content of tabular/src/time/delta_t_data.rs
+/// finals: Parsed finals2000A.all data
+/// predictions: Parsed deltat.preds data
+/// Out:
+/// New content of tabular/src/time/delta_t_data.rs
+pub(crate) fn generate(
+    table: &str,
+    finals: Vec<DeltaTEntry>,
+    predictions: Vec<DeltaTEntry>,
+) -> Result<String, Error> {
+    let historical = historical_section(table)?;
+    let historical_jds = historical_jds(historical)?;
+
+    let predictions = deduplicate(&finals, predictions);
+
+    let mut entries = finals;
+    let n_finals = entries.len();
+    entries.extend(predictions);
+    validate(&historical_jds, &entries)?;
+
+    let count = historical_jds.len() + entries.len();
+
+    // SS: update the array size
+    let mut output = String::new();
+    for line in historical.lines() {
+        if line.starts_with("pub const DELTA_T_DATA") {
+            writeln!(output, "pub const DELTA_T_DATA: [DeltaTValue; {count}] = [").unwrap();
+        } else {
+            writeln!(output, "{line}").unwrap();
+        }
+    }
+
+    writeln!(output, "{FINALS_MARKER}").unwrap();
+    writeln!(
+        output,
+        "    // Generate these DeltaTValue struct elements by running table-gen"
+    )
+    .unwrap();
+    write_entries(&mut output, &entries[..n_finals]);
+
+    writeln!(output, "{PREDICTIONS_MARKER}").unwrap();
+    writeln!(output, "    // To update, run table-gen").unwrap();
+    write_entries(&mut output, &entries[n_finals..]);
+
+    writeln!(output, "];").unwrap();
+    output.push_str(TESTS);
+
+    Ok(output)
+}
+
+/// Tests appended to the generated table
+const TESTS: &str = "
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_test() {
+        // Arrange
+        let pairs = DELTA_T_DATA.windows(2);
+
+        // Act
+        let sorted = pairs.into_iter().all(|w| w[0].jd < w[1].jd);
+
+        // Assert
+        assert!(sorted);
+    }
+
+    #[test]
+    fn finite_test() {
+        // Arrange
+        let values = DELTA_T_DATA.iter();
+
+        // Act
+        let finite = values.into_iter().all(|v| v.delta_t.is_finite());
+
+        // Assert
+        assert!(finite);
+    }
+}
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(jd: f64, delta_t: f64) -> DeltaTEntry {
+        DeltaTEntry {
+            jd,
+            delta_t,
+            comment: String::new(),
+        }
+    }
+
+    const TABLE: &str = "\
+pub const DELTA_T_DATA: [DeltaTValue; 3] = [
+    // SS: historical data
+    DeltaTValue {
+        jd: 2441317.50,
+        delta_t: 42.227,
+    }, // Year 1972.00
+    // SS: Data taken from https://cddis.nasa.gov/archive/products/iers/finals2000A.all
+    DeltaTValue {
+        jd: 2441684.50,
+        delta_t: 43.3755822,
+    }, // 2 Jan 1973
+];
+";
+
+    #[test]
+    fn deduplicate_test() {
+        // Arrange
+        let finals = vec![entry(2_460_035.5, 69.2), entry(2_460_036.5, 69.2)];
+        let predictions = vec![entry(2_460_036.5, 71.54), entry(2_460_127.5, 71.67)];
+
+        // Act
+        let predictions = deduplicate(&finals, predictions);
+
+        // Assert
+        assert_eq!(1, predictions.len());
+        assert_eq!(2_460_127.5, predictions[0].jd);
+    }
+
+    #[test]
+    fn validate_not_increasing_test() {
+        // Arrange
+        let entries = vec![entry(2_441_684.5, 43.37), entry(2_441_684.5, 43.37)];
+
+        // Act
+        let result = validate(&[2_441_317.5], &entries);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_overlaps_historical_test() {
+        // Arrange
+        let entries = vec![entry(2_441_317.5, 42.2)];
+
+        // Act
+        let result = validate(&[2_441_317.5], &entries);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn generate_test() {
+        // Arrange
+        let finals = vec![
+            entry(2_441_684.5, 43.3755822),
+            entry(2_441_685.5, 43.3783837),
+        ];
+        let predictions = vec![entry(2_441_685.5, 43.4), entry(2_460_036.5, 71.54)];
+
+        // Act
+        let table = generate(TABLE, finals, predictions).unwrap();
+
+        // Assert
+        assert!(table.starts_with("pub const DELTA_T_DATA: [DeltaTValue; 4] = ["));
+        assert!(table.contains("        jd: 2441317.50,\n        delta_t: 42.227,\n"));
+        assert!(table.contains("        jd: 2460036.50,\n        delta_t: 71.5400000,\n"));
+        assert_eq!(1, table.matches("jd: 2441685.50").count());
+        assert!(table.contains("mod tests"));
+    }
+
+    #[test]
+    fn generate_missing_marker_test() {
+        // Arrange
+        let table = "pub const DELTA_T_DATA: [DeltaTValue; 0] = [\n];\n";
+
+        // Act
+        let result = generate(table, vec![], vec![]);
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Generator for the blobs of feature blob, see src/blob.rs. Parses the
+//! values of the delta t table and the VSOP87 series from their Rust sources
+//! and writes them as little-endian f64s to OUT_DIR. Without feature blob,
+//! the tables are compiled from source and nothing is generated. Only the
+//! blobs of the datasets enabled by their features are generated.
+
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+const DELTA_T_SOURCE: &str = "src/time/delta_t_data.rs";
+const VSOP87_SOURCE: &str = "src/vsop87d_ear.rs";
+
+fn main() -> Result<(), Error> {
+    println!("cargo:rerun-if-changed=build.rs");
+    if std::env::var_os("CARGO_FEATURE_BLOB").is_none() {
+        return Ok(());
+    }
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+
+    if std::env::var_os("CARGO_FEATURE_DELTA_T").is_some() {
+        println!("cargo:rerun-if-changed={DELTA_T_SOURCE}");
+        let delta_t = delta_t_values(&fs::read_to_string(DELTA_T_SOURCE)?)?;
+        fs::write(out_dir.join("delta_t_data.bin"), to_bytes(&delta_t))?;
+    }
+
+    if std::env::var_os("CARGO_FEATURE_VSOP87_EARTH").is_some() {
+        println!("cargo:rerun-if-changed={VSOP87_SOURCE}");
+        for (name, values) in vsop87_series(&fs::read_to_string(VSOP87_SOURCE)?)? {
+            let filename = format!("{}.bin", name.to_lowercase());
+            fs::write(out_dir.join(filename), to_bytes(&values))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn parse(value: &str) -> Result<f64, Error> {
+    value
+        .trim()
+        .parse::<f64>()
+        .map_err(|e| invalid_data(format!("{value}: {e}")))
+}
+
+/// The jd and delta_t fields of the DeltaTValues, in order
+fn delta_t_values(source: &str) -> Result<Vec<f64>, Error> {
+    let mut values = vec![];
+    for line in source.lines().map(str::trim) {
+        if let Some(value) = line
+            .strip_prefix("jd:")
+            .or_else(|| line.strip_prefix("delta_t:"))
+        {
+            values.push(parse(value.trim_end_matches(','))?);
+        }
+    }
+
+    if values.is_empty() || values.len() % 2 != 0 {
+        return Err(invalid_data(format!(
+            "{DELTA_T_SOURCE}: {} values are not (jd, delta_t) pairs",
+            values.len()
+        )));
+    }
+    Ok(values)
+}
+
+/// The terms of each block of terms, e.g. VSOP87D_L0_EARTH, in order
+fn vsop87_series(source: &str) -> Result<Vec<(String, Vec<f64>)>, Error> {
+    let mut series = vec![];
+    let mut lines = source.lines();
+
+    while let Some(line) = lines.next() {
+        // SS: const VSOP87D_L0_EARTH: [[f64; 3]; 559] = [
+        let Some(declaration) = line.strip_prefix("const ") else {
+            continue;
+        };
+        let (name, rest) = declaration.split_once(':').unwrap();
+        let count = rest
+            .rsplit_once(';')
+            .and_then(|(_, count)| count.split(']').next())
+            .map(parse)
+            .ok_or_else(|| invalid_data(format!("{VSOP87_SOURCE}: {line}")))??;
+
+        let mut values = vec![];
+        for line in lines.by_ref().map(str::trim) {
+            if line == "];" {
+                break;
+            }
+            let term = line.trim_start_matches('[').trim_end_matches("],");
+            for value in term.split(',') {
+                values.push(parse(value)?);
+            }
+        }
+
+        if values.len() != 3 * count as usize {
+            return Err(invalid_data(format!(
+                "{VSOP87_SOURCE}: {name} has {} values instead of 3 * {count}",
+                values.len()
+            )));
+        }
+        series.push((name.to_string(), values));
+    }
+
+    Ok(series)
+}
+
+fn to_bytes(values: &[f64]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
@@ -0,0 +1,25 @@
+//! Tables stored as binary blobs instead of Rust source, for feature blob.
+//! build.rs parses the tables' Rust sources and writes their values as
+//! little-endian f64s to OUT_DIR. The blobs are included with include_bytes!
+//! and viewed in place with bytemuck, so the tables are neither compiled
+//! from source nor copied at runtime.
+
+#[cfg(target_endian = "big")]
+compile_error!("the table blobs are little-endian");
+
+/// Aligns an included blob for the f64s it holds
+#[repr(C, align(8))]
+pub(crate) struct Aligned<B: ?Sized>(pub(crate) B);
+
+/// Include a blob written by build.rs
+macro_rules! include_blob {
+    ($name:literal) => {
+        &$crate::blob::Aligned(*include_bytes!(concat!(env!("OUT_DIR"), "/", $name)))
+    };
+}
+pub(crate) use include_blob;
+
+/// View a blob as a table
+pub(crate) fn view<T: bytemuck::Pod>(blob: &'static Aligned<[u8]>) -> &'static [T] {
+    bytemuck::cast_slice(&blob.0)
+}
@@ -1,3 +1,14 @@
+#[cfg(all(feature = "blob", any(feature = "vsop87-earth", feature = "delta-t")))]
+mod blob;
+#[cfg(feature = "elp")]
 pub mod moon_position_data;
+#[cfg(any(feature = "delta-t", feature = "leap-seconds"))]
 pub mod time;
+
+// SS: with feature blob, the series are read from blobs written by build.rs,
+// see crate::blob
+#[cfg(all(feature = "vsop87-earth", not(feature = "blob")))]
+pub mod vsop87d_ear;
+#[cfg(all(feature = "vsop87-earth", feature = "blob"))]
+#[path = "vsop87d_ear_blob.rs"]
 pub mod vsop87d_ear;
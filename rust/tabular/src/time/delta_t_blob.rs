@@ -0,0 +1,32 @@
+//! The delta t table of delta_t_data.rs, as a blob written by build.rs
+
+use super::DeltaTValue;
+use crate::blob::{include_blob, view, Aligned};
+
+static DELTA_T_DATA: &Aligned<[u8]> = include_blob!("delta_t_data.bin");
+
+pub(super) fn delta_t_table() -> &'static [DeltaTValue] {
+    view(DELTA_T_DATA)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::delta_t_data;
+
+    #[test]
+    fn same_as_source_test() {
+        // Arrange
+        let source = &delta_t_data::DELTA_T_DATA;
+
+        // Act
+        let blob = delta_t_table();
+
+        // Assert
+        assert_eq!(source.len(), blob.len());
+        for (source, blob) in source.iter().zip(blob) {
+            assert_eq!(source.jd.to_bits(), blob.jd.to_bits());
+            assert_eq!(source.delta_t.to_bits(), blob.delta_t.to_bits());
+        }
+    }
+}
@@ -1,27 +1,4 @@
-use std::cmp::Ordering;
-
-pub struct DeltaTValue {
-    pub jd: f64,
-    pub delta_t: f64,
-}
-
-impl PartialEq<Self> for DeltaTValue {
-    fn eq(&self, other: &Self) -> bool {
-        self.jd == other.jd
-    }
-}
-
-impl PartialOrd<Self> for DeltaTValue {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.jd < other.jd {
-            Some(Ordering::Less)
-        } else if self.jd > other.jd {
-            Some(Ordering::Greater)
-        } else {
-            Some(Ordering::Equal)
-        }
-    }
-}
+pub use super::DeltaTValue;
 
 pub const DELTA_T_DATA: [DeltaTValue; 18947] = [
     // SS: historical data is based on https://cddis.nasa.gov/archive/products/iers/historic_deltat.data
@@ -2555,7 +2532,7 @@ pub const DELTA_T_DATA: [DeltaTValue; 18947] = [
         delta_t: 42.825,
     }, // Year 1972.50
     // SS: Data taken from https://cddis.nasa.gov/archive/products/iers/finals2000A.all
-    // Generate these DeltaTValue struct elements by running delta_t_converter
+    // Generate these DeltaTValue struct elements by running table-gen
     DeltaTValue {
         jd: 2441684.50,
         delta_t: 43.3755822,
@@ -75741,7 +75718,7 @@ pub const DELTA_T_DATA: [DeltaTValue; 18947] = [
         delta_t: 69.2139714,
     }, // 4 Feb 2023, UT1-UTC=-0.0299714, Cumulative leap seconds=37
     // delta_t predictions, from https://cddis.nasa.gov/archive/products/iers/deltat.preds
-    // To update, run delta_t_pred_converter
+    // To update, run table-gen
     DeltaTValue {
         jd: 2460036.50,
         delta_t: 71.5400000,
@@ -75819,3 +75796,32 @@ pub const DELTA_T_DATA: [DeltaTValue; 18947] = [
         delta_t: 73.6600000,
     }, // 2 Oct 2027
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_test() {
+        // Arrange
+        let pairs = DELTA_T_DATA.windows(2);
+
+        // Act
+        let sorted = pairs.into_iter().all(|w| w[0].jd < w[1].jd);
+
+        // Assert
+        assert!(sorted);
+    }
+
+    #[test]
+    fn finite_test() {
+        // Arrange
+        let values = DELTA_T_DATA.iter();
+
+        // Act
+        let finite = values.into_iter().all(|v| v.delta_t.is_finite());
+
+        // Assert
+        assert!(finite);
+    }
+}
@@ -1,2 +1,57 @@
+#[cfg(feature = "delta-t")]
+use std::cmp::Ordering;
+
+// SS: with feature blob, the table is read from a blob written by build.rs,
+// see crate::blob. The tests compare the blob to the source
+#[cfg(all(feature = "delta-t", feature = "blob"))]
+mod delta_t_blob;
+#[cfg(all(feature = "delta-t", any(not(feature = "blob"), test)))]
 pub mod delta_t_data;
+#[cfg(feature = "leap-seconds")]
 pub mod leap_second_data;
+
+#[cfg(feature = "delta-t")]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct DeltaTValue {
+    pub jd: f64,
+    pub delta_t: f64,
+}
+
+#[cfg(feature = "delta-t")]
+impl PartialEq<Self> for DeltaTValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.jd == other.jd
+    }
+}
+
+#[cfg(feature = "delta-t")]
+impl PartialOrd<Self> for DeltaTValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.jd < other.jd {
+            Some(Ordering::Less)
+        } else if self.jd > other.jd {
+            Some(Ordering::Greater)
+        } else {
+            Some(Ordering::Equal)
+        }
+    }
+}
+
+// SS: two f64s without padding, any bit pattern is valid
+#[cfg(all(feature = "delta-t", feature = "blob"))]
+unsafe impl bytemuck::Zeroable for DeltaTValue {}
+#[cfg(all(feature = "delta-t", feature = "blob"))]
+unsafe impl bytemuck::Pod for DeltaTValue {}
+
+/// The delta t table, sorted by Julian Day
+#[cfg(all(feature = "delta-t", not(feature = "blob")))]
+pub fn delta_t_table() -> &'static [DeltaTValue] {
+    &delta_t_data::DELTA_T_DATA
+}
+
+/// The delta t table, sorted by Julian Day
+#[cfg(all(feature = "delta-t", feature = "blob"))]
+pub fn delta_t_table() -> &'static [DeltaTValue] {
+    delta_t_blob::delta_t_table()
+}
@@ -0,0 +1,111 @@
+//! The VSOP87D series of vsop87d_ear.rs, as blobs written by build.rs
+
+use crate::blob::{include_blob, view, Aligned};
+
+macro_rules! blobs {
+    ($($name:ident: $file:literal),* $(,)?) => {
+        $(static $name: &Aligned<[u8]> = include_blob!($file);)*
+    };
+}
+
+blobs! {
+    VSOP87D_L0_EARTH: "vsop87d_l0_earth.bin",
+    VSOP87D_L1_EARTH: "vsop87d_l1_earth.bin",
+    VSOP87D_L2_EARTH: "vsop87d_l2_earth.bin",
+    VSOP87D_L3_EARTH: "vsop87d_l3_earth.bin",
+    VSOP87D_L4_EARTH: "vsop87d_l4_earth.bin",
+    VSOP87D_L5_EARTH: "vsop87d_l5_earth.bin",
+    VSOP87D_B0_EARTH: "vsop87d_b0_earth.bin",
+    VSOP87D_B1_EARTH: "vsop87d_b1_earth.bin",
+    VSOP87D_B2_EARTH: "vsop87d_b2_earth.bin",
+    VSOP87D_B3_EARTH: "vsop87d_b3_earth.bin",
+    VSOP87D_B4_EARTH: "vsop87d_b4_earth.bin",
+    VSOP87D_R0_EARTH: "vsop87d_r0_earth.bin",
+    VSOP87D_R1_EARTH: "vsop87d_r1_earth.bin",
+    VSOP87D_R2_EARTH: "vsop87d_r2_earth.bin",
+    VSOP87D_R3_EARTH: "vsop87d_r3_earth.bin",
+    VSOP87D_R4_EARTH: "vsop87d_r4_earth.bin",
+    VSOP87D_R5_EARTH: "vsop87d_r5_earth.bin",
+}
+
+/// Blocks of terms (A, B, C) for the heliocentric longitude, multiplied by
+/// tau^0, tau^1, ...
+pub fn vsop87d_l_earth() -> [&'static [[f64; 3]]; 6] {
+    [
+        view(VSOP87D_L0_EARTH),
+        view(VSOP87D_L1_EARTH),
+        view(VSOP87D_L2_EARTH),
+        view(VSOP87D_L3_EARTH),
+        view(VSOP87D_L4_EARTH),
+        view(VSOP87D_L5_EARTH),
+    ]
+}
+
+/// Blocks of terms (A, B, C) for the heliocentric latitude, multiplied by
+/// tau^0, tau^1, ...
+pub fn vsop87d_b_earth() -> [&'static [[f64; 3]]; 5] {
+    [
+        view(VSOP87D_B0_EARTH),
+        view(VSOP87D_B1_EARTH),
+        view(VSOP87D_B2_EARTH),
+        view(VSOP87D_B3_EARTH),
+        view(VSOP87D_B4_EARTH),
+    ]
+}
+
+/// Blocks of terms (A, B, C) for the heliocentric radius vector, multiplied
+/// by tau^0, tau^1, ...
+pub fn vsop87d_r_earth() -> [&'static [[f64; 3]]; 6] {
+    [
+        view(VSOP87D_R0_EARTH),
+        view(VSOP87D_R1_EARTH),
+        view(VSOP87D_R2_EARTH),
+        view(VSOP87D_R3_EARTH),
+        view(VSOP87D_R4_EARTH),
+        view(VSOP87D_R5_EARTH),
+    ]
+}
+
+// SS: the series compiled from source, to compare the blobs to
+#[cfg(test)]
+#[path = "vsop87d_ear.rs"]
+mod source;
+
+#[cfg(test)]
+mod tests {
+    use super::source;
+
+    fn same(source: &[&[[f64; 3]]], blob: &[&[[f64; 3]]]) -> bool {
+        let bits = |series: &[&[[f64; 3]]]| {
+            series
+                .iter()
+                .flat_map(|block| block.iter().flatten().map(|x| x.to_bits()))
+                .collect::<Vec<_>>()
+        };
+        source.len() == blob.len()
+            && source.iter().zip(blob).all(|(s, b)| s.len() == b.len())
+            && bits(source) == bits(blob)
+    }
+
+    #[test]
+    fn same_as_source_test() {
+        // Arrange
+        let source = [
+            source::vsop87d_l_earth().to_vec(),
+            source::vsop87d_b_earth().to_vec(),
+            source::vsop87d_r_earth().to_vec(),
+        ];
+
+        // Act
+        let blob = [
+            super::vsop87d_l_earth().to_vec(),
+            super::vsop87d_b_earth().to_vec(),
+            super::vsop87d_r_earth().to_vec(),
+        ];
+
+        // Assert
+        for (source, blob) in source.iter().zip(&blob) {
+            assert!(same(source, blob));
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Export accuracy test vectors, i.e. Moon and Sun positions, rise/set/transit
+//! and twilight times and phases for a standard grid of dates and locations.
+//! Execute
+//! ```
+//! cargo run --package test_vectors --bin test-vectors -- --output vectors.csv
+//! ```
+//! before and after a change and diff the two files. All Julian Days are in
+//! dynamical time, which makes the files independent of the delta t table.
+//! With `--high-latitude`, writes the moonrise and moonset times for high
+//! latitudes found by a brute-force scan instead, which are kept in
+//! moonlib/tests/fixtures/high_latitude_rise_set_scan.csv.
+use clap::{App, Arg};
+use moonlib::test_vectors;
+use std::fs::File;
+use std::io::BufWriter;
+
+fn main() -> Result<(), std::io::Error> {
+    let app = App::new("test-vectors")
+        .about("Exports the crate's outputs for a standard grid of dates and locations as CSV")
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .takes_value(true)
+                .required(true)
+                .help("CSV output file"),
+        )
+        .arg(
+            Arg::new("high-latitude")
+                .long("high-latitude")
+                .help("Export the scanned moonrise and moonset times for high latitudes"),
+        )
+        .get_matches();
+
+    let output_filename = app.value_of("output").unwrap();
+    let mut writer = BufWriter::new(File::create(output_filename)?);
+
+    if app.is_present("high-latitude") {
+        let days = test_vectors::lunar_month();
+        let locations = test_vectors::high_latitude_locations();
+        test_vectors::write_high_latitude_rise_set(&mut writer, &days, &locations)?;
+
+        println!(
+            "Wrote {} days of rise/set times to {output_filename}...",
+            days.len() * locations.len()
+        );
+
+        return Ok(());
+    }
+
+    let dates = test_vectors::standard_dates();
+    let locations = test_vectors::standard_locations();
+
+    test_vectors::write_csv(&mut writer, &dates, &locations)?;
+
+    println!(
+        "Wrote {} test vectors to {output_filename}...",
+        dates.len() * locations.len()
+    );
+
+    Ok(())
+}